@@ -42,13 +42,13 @@ pub mod builtin;
 pub mod data;
 pub mod execution_environment;
 pub mod new_node_position;
+pub mod shortcuts;
 #[warn(missing_docs)]
 pub mod view;
 
 mod layers;
 #[warn(missing_docs)]
 mod selection;
-mod shortcuts;
 
 use crate::application::command::FrpNetworkProvider;
 use crate::component::node;
@@ -2757,8 +2757,12 @@ impl application::View for GraphEditor {
     }
 
     fn global_shortcuts() -> Vec<application::shortcut::Shortcut> {
-        use crate::shortcuts::SHORTCUTS;
-        SHORTCUTS.iter().map(|(a, b, c, d)| Self::self_shortcut_when(*a, *c, *d, *b)).collect()
+        shortcuts::shortcuts()
+            .into_iter()
+            .map(|(action, condition, rule, command)| {
+                Self::self_shortcut_when(action, rule, command, condition)
+            })
+            .collect()
     }
 }
 
@@ -3721,6 +3725,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn shortcut_context_flags_are_graph_editor_outputs() {
+        use application::command::CommandApi;
+        let (_, graph_editor) = init();
+        let status_map = graph_editor.frp.status_api();
+        for flag in shortcuts::flag::ALL {
+            let name = flag.name();
+            assert!(
+                status_map.borrow().contains_key(name),
+                "No `{name}` output in the graph editor."
+            );
+        }
+    }
+
     fn init() -> (Application, GraphEditor) {
         let app = Application::new("root");
         app.set_screen_size_for_tests();
@@ -24,6 +24,10 @@ const FILE_SANS_MONO_PREFIX: &str = "SansMono";
 const SANS_MONO_FONT_FAMILY_FONTS: &[(&str, font::Weight)] =
     &[("-Bold", font::Weight::Bold), ("", font::Weight::Normal)];
 
+const FILE_SANS_PREFIX: &str = "Sans";
+
+const SANS_FONT_FAMILY_FONTS: &[(&str, font::Weight)] = &[("", font::Weight::Normal)];
+
 
 
 // ===================
@@ -77,10 +81,19 @@ pub async fn install_sans_mono_with_css(
 
 /// The DejaVu Sans Mono Font.
 pub fn font() -> NonVariableDefinition {
-    SANS_MONO_FONT_FAMILY_FONTS
+    definition(FILE_SANS_MONO_PREFIX, SANS_MONO_FONT_FAMILY_FONTS)
+}
+
+/// The regular face of the proportional DejaVu Sans Font.
+pub fn sans_font() -> NonVariableDefinition {
+    definition(FILE_SANS_PREFIX, SANS_FONT_FAMILY_FONTS)
+}
+
+fn definition(prefix: &str, fonts: &[(&str, font::Weight)]) -> NonVariableDefinition {
+    fonts
         .iter()
         .map(|(name, weight)| {
-            let file = format!("{FILE_PREFIX}{FILE_SANS_MONO_PREFIX}{name}.ttf");
+            let file = format!("{FILE_PREFIX}{prefix}{name}.ttf");
             let header = NonVariableFaceHeader {
                 weight: *weight,
                 width:  font::Width::Normal,
@@ -192,6 +192,13 @@ define_themes! { [light:0, dark:1]
             hide_delay_duration_ms = 0.0, 0.0;
         }
 
+        focus_ring {
+            color = Rgba(0.18, 0.45, 0.92, 0.9), Rgba(0.45, 0.65, 1.0, 0.9);
+            width = 2.0, 2.0;
+            padding = 3.0, 3.0;
+            corner_radius = 6.0, 6.0;
+        }
+
         top_bar {
             padding_left = 19.0, 19.0;
             padding_top = 9.0, 9.0;
@@ -745,6 +752,40 @@ define_themes! { [light:0, dark:1]
         }
     }
     component {
+        chart {
+            background      = Rgba(1.0,1.0,1.0,0.0), Rgba(0.0,0.0,0.0,0.0);
+            axis_color      = Lcha(0.0,0.0,0.0,0.5), Lcha(1.0,0.0,0.0,0.5);
+            grid_color      = Lcha(0.0,0.0,0.0,0.08), Lcha(1.0,0.0,0.0,0.08);
+            label_color     = Lcha(0.0,0.0,0.0,0.6), Lcha(1.0,0.0,0.0,0.6);
+            crosshair_color = Lcha(0.0,0.0,0.0,0.3), Lcha(1.0,0.0,0.0,0.3);
+            line_width      = 1.5, 1.5;
+            point_size      = 4.0, 4.0;
+        }
+        dock {
+            tab_bar      = Lcha(0.0,0.0,0.0,0.04), Lcha(1.0,0.0,0.0,0.04);
+            active_tab   = Rgba(1.0,1.0,1.0,1.0), Rgba(0.16,0.16,0.16,1.0);
+            tab_text     = Lcha(0.0,0.0,0.0,0.7), Lcha(1.0,0.0,0.0,0.7);
+            drop_preview = Rgba(0.18,0.45,0.92,0.2), Rgba(0.45,0.65,1.0,0.2);
+        }
+        floating_panel {
+            background = Rgba(1.0,1.0,1.0,0.95), Rgba(0.12,0.12,0.12,0.95);
+            title_bar  = Lcha(0.0,0.0,0.0,0.06), Lcha(1.0,0.0,0.0,0.06);
+            title      = Lcha(0.0,0.0,0.0,0.7), Lcha(1.0,0.0,0.0,0.7);
+        }
+        side_panel {
+            background         = Rgba(1.0,1.0,1.0,0.9), Rgba(0.1,0.1,0.1,0.9);
+            handle_color       = Lcha(0.0,0.0,0.0,0.08), Lcha(1.0,0.0,0.0,0.08);
+            handle_hover_color = Rgba(0.18,0.45,0.92,0.6), Rgba(0.45,0.65,1.0,0.6);
+        }
+        table {
+            background        = Rgba(1.0,1.0,1.0,0.0), Rgba(0.0,0.0,0.0,0.0);
+            header_background = Lcha(0.0,0.0,0.0,0.06), Lcha(1.0,0.0,0.0,0.06);
+            header_text       = Lcha(0.0,0.0,0.0,0.7), Lcha(1.0,0.0,0.0,0.7);
+            selection         = Lcha(0.0,0.0,0.0,0.1), Lcha(1.0,0.0,0.0,0.1);
+            hover             = Lcha(0.0,0.0,0.0,0.04), Lcha(1.0,0.0,0.0,0.04);
+            header_height     = 28.0, 28.0;
+            row_height        = 24.0, 24.0;
+        }
         label {
             background = graph_editor::node::background , graph_editor::node::background;
             text       = Lcha(0.0,0.0,0.0,0.7) , Lcha(1.0,0.0,0.0,0.7);
@@ -776,6 +817,22 @@ define_themes! { [light:0, dark:1]
                 scale = 1.0, 1.0;
             }
         }
+        text_area {
+            selection {
+                corner_radius  = 2.0, 2.0;
+                merge_adjacent = 0.0, 0.0;
+            }
+            caret {
+                width = 2.0, 2.0;
+            }
+            text {
+                line_height = 1.0, 1.0;
+            }
+            gutter {
+                breakpoint_color = Rgba(0.86,0.26,0.26,1.0), Rgba(0.92,0.36,0.36,1.0);
+                bookmark_color   = Rgba(0.27,0.55,0.92,1.0), Rgba(0.38,0.63,0.96,1.0);
+            }
+        }
         toggle_button {
             non_toggled = Lcha(0.0,0.0,0.0,0.3), Lcha(0.4,0.0,0.0,1.0);
             toggled = Lcha(0.0,0.0,0.0,0.7), Lcha(1.0,0.0,0.0,0.7);
@@ -94,6 +94,7 @@ use ensogl_core::display;
 use ensogl_core::display::object::ObjectOps;
 use ensogl_core::display::shape::system::Shape;
 use ensogl_core::display::shape::system::ShapeWithDefaultableData;
+use ensogl_core::gui::accessibility;
 use ensogl_core::gui::component::ShapeView;
 
 
@@ -205,15 +206,18 @@ pub mod shape {
 pub struct Model<S: Shape> {
     display_object: display::object::Instance,
     shape:          ShapeView<S>,
+    accessibility:  accessibility::Node,
 }
 
 impl<Shape: ButtonShape> Model<Shape> {
     /// Construct a button's model.
-    pub fn new(_app: &Application) -> Self {
+    pub fn new(app: &Application) -> Self {
         let display_object = display::object::Instance::new();
         let shape = ShapeView::new();
+        let scene = &app.display.default_scene;
+        let accessibility = accessibility::Node::new(scene, accessibility::Role::Button);
         display_object.add_child(&shape);
-        Self { display_object, shape }
+        Self { display_object, shape, accessibility }
     }
 
     /// Set the background (i.e. the circle) color.
@@ -238,6 +242,8 @@ ensogl_core::define_endpoints! {
         set_size (Vector2),
         mouse_nearby (bool),
         click (),
+        /// Set the label read by the assistive technology, like screen readers.
+        set_accessible_label (ImString),
     }
     Output {
         clicked (),
@@ -323,6 +329,8 @@ impl<Shape: ButtonShape> View<Shape> {
             was_clicked           <- tracking_for_release.previous();
             frp.source.clicked    <+ mouse_released_on_me.gate(&was_clicked);
             frp.source.clicked    <+ frp.click;
+            frp.source.clicked    <+ model.accessibility.activated;
+            model.accessibility.set_label <+ frp.set_accessible_label;
             state <- all_with3(&frp.is_hovered,&frp.mouse_nearby,&tracking_for_release,
                 |strict_hover,nearby_hover,clicked| {
                     match (strict_hover,nearby_hover,clicked)  {
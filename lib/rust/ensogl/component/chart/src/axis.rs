@@ -0,0 +1,275 @@
+//! Chart axes: mapping of data values to the chart space and generation of tick marks.
+
+use ensogl_core::prelude::*;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The smallest span of an axis. Zooming in further is not possible.
+const MIN_SPAN: f32 = 1e-6;
+/// The smallest value displayed on a logarithmic axis.
+const MIN_LOG_VALUE: f32 = 1e-12;
+
+
+
+// =============
+// === Scale ===
+// =============
+
+/// The way values are distributed along an axis.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Scale {
+    /// Equal distances represent equal differences of values.
+    #[default]
+    Linear,
+    /// Equal distances represent equal ratios of values. Only positive values can be displayed.
+    Log,
+}
+
+impl Scale {
+    /// Map the value to the space in which the axis is linear.
+    pub fn transform(self, value: f32) -> f32 {
+        match self {
+            Self::Linear => value,
+            Self::Log => value.max(MIN_LOG_VALUE).log10(),
+        }
+    }
+
+    /// The inverse of [`Self::transform`].
+    pub fn inverse(self, value: f32) -> f32 {
+        match self {
+            Self::Linear => value,
+            Self::Log => 10_f32.powf(value),
+        }
+    }
+}
+
+
+
+// ================
+// === Interval ===
+// ================
+
+/// A closed range of values displayed on an axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(missing_docs)]
+pub struct Interval {
+    pub start: f32,
+    pub end:   f32,
+}
+
+impl Interval {
+    /// Constructor.
+    pub fn new(start: f32, end: f32) -> Self {
+        Self { start, end }
+    }
+
+    /// The distance between the ends of the interval.
+    pub fn span(self) -> f32 {
+        self.end - self.start
+    }
+
+    /// Check whether the value lies inside the interval.
+    pub fn contains(self, value: f32) -> bool {
+        (self.start..=self.end).contains(&value)
+    }
+}
+
+impl Default for Interval {
+    fn default() -> Self {
+        Self::new(0.0, 1.0)
+    }
+}
+
+
+
+// ============
+// === Tick ===
+// ============
+
+/// A tick mark of an axis.
+#[derive(Clone, Debug, PartialEq)]
+#[allow(missing_docs)]
+pub struct Tick {
+    pub value: f32,
+    pub label: String,
+}
+
+
+
+// ============
+// === Axis ===
+// ============
+
+/// A chart axis, displaying an [`Interval`] of values with the given [`Scale`].
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[allow(missing_docs)]
+pub struct Axis {
+    pub scale:    Scale,
+    pub interval: Interval,
+}
+
+impl Axis {
+    /// Constructor.
+    pub fn new(scale: Scale, interval: Interval) -> Self {
+        Self { scale, interval }
+    }
+
+    fn transformed(&self) -> Interval {
+        let start = self.scale.transform(self.interval.start);
+        let end = self.scale.transform(self.interval.end);
+        Interval { start, end }
+    }
+
+    /// The position of the value along the axis, where `0.0` is the start and `1.0` is the end of
+    /// the displayed interval.
+    pub fn project(&self, value: f32) -> f32 {
+        let interval = self.transformed();
+        (self.scale.transform(value) - interval.start) / interval.span()
+    }
+
+    /// The inverse of [`Self::project`].
+    pub fn unproject(&self, position: f32) -> f32 {
+        let interval = self.transformed();
+        self.scale.inverse(interval.start + position * interval.span())
+    }
+
+    /// Zoom the axis by the factor around the given position (see [`Self::project`]). Factors
+    /// smaller than `1.0` zoom in.
+    pub fn zoom(&mut self, factor: f32, position: f32) {
+        let interval = self.transformed();
+        let focus = interval.start + position * interval.span();
+        let span = (interval.span() * factor).max(MIN_SPAN);
+        let start = focus - position * span;
+        self.set_transformed(Interval::new(start, start + span));
+    }
+
+    /// Move the displayed interval by the given fraction of its span.
+    pub fn pan(&mut self, delta: f32) {
+        let interval = self.transformed();
+        let offset = delta * interval.span();
+        self.set_transformed(Interval::new(interval.start + offset, interval.end + offset));
+    }
+
+    fn set_transformed(&mut self, interval: Interval) {
+        let start = self.scale.inverse(interval.start);
+        let end = self.scale.inverse(interval.end);
+        self.interval = Interval { start, end };
+    }
+
+    /// Generate tick marks for the displayed interval, at most about `max_count` of them. Linear
+    /// axes are marked with multiples of 1, 2, or 5 times a power of ten. Logarithmic axes are
+    /// marked with powers of ten, or linearly if the interval spans less than one order of
+    /// magnitude.
+    pub fn ticks(&self, max_count: usize) -> Vec<Tick> {
+        match self.scale {
+            Scale::Log => {
+                let interval = self.transformed();
+                let first = interval.start.ceil() as i32;
+                let last = interval.end.floor() as i32;
+                if last > first {
+                    let step = ((last - first) as usize / max_count.max(1) + 1) as i32;
+                    let exponents = (first..=last).step_by(step as usize);
+                    exponents.map(|exp| log_tick(10_f32.powi(exp), exp)).collect()
+                } else {
+                    linear_ticks(self.interval, max_count)
+                }
+            }
+            Scale::Linear => linear_ticks(self.interval, max_count),
+        }
+    }
+}
+
+fn log_tick(value: f32, exponent: i32) -> Tick {
+    let label = if (-3..=3).contains(&exponent) {
+        let decimals = (-exponent).max(0) as usize;
+        format!("{value:.decimals$}")
+    } else {
+        format!("1e{exponent}")
+    };
+    Tick { value, label }
+}
+
+fn linear_ticks(interval: Interval, max_count: usize) -> Vec<Tick> {
+    let span = interval.span();
+    if span <= 0.0 || !span.is_finite() {
+        return default();
+    }
+    let (step, exponent) = nice_step(span / max_count.max(1) as f32);
+    let decimals = (-exponent).max(0) as usize;
+    let first = (interval.start / step).ceil() as i64;
+    let last = (interval.end / step).floor() as i64;
+    (first..=last)
+        .map(|index| {
+            let value = index as f32 * step;
+            let label = format!("{value:.decimals$}");
+            Tick { value, label }
+        })
+        .collect()
+}
+
+/// The smallest number of the form 1, 2, or 5 times a power of ten, not smaller than the value.
+/// Returns the number together with the exponent of the power of ten.
+fn nice_step(value: f32) -> (f32, i32) {
+    let exponent = value.log10().floor() as i32;
+    let residual = value / 10_f32.powi(exponent);
+    let (nice, exponent) = if residual <= 1.0 {
+        (1.0, exponent)
+    } else if residual <= 2.0 {
+        (2.0, exponent)
+    } else if residual <= 5.0 {
+        (5.0, exponent)
+    } else {
+        (1.0, exponent + 1)
+    };
+    (nice * 10_f32.powi(exponent), exponent)
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(axis: Axis, max_count: usize) -> Vec<String> {
+        axis.ticks(max_count).into_iter().map(|tick| tick.label).collect()
+    }
+
+    #[test]
+    fn linear_ticks_are_nice_numbers() {
+        let axis = Axis::new(Scale::Linear, Interval::new(0.0, 100.0));
+        assert_eq!(labels(axis, 5), ["0", "20", "40", "60", "80", "100"]);
+        let axis = Axis::new(Scale::Linear, Interval::new(-0.13, 0.27));
+        assert_eq!(labels(axis, 5), ["-0.1", "0.0", "0.1", "0.2"]);
+    }
+
+    #[test]
+    fn log_ticks_are_powers_of_ten() {
+        let axis = Axis::new(Scale::Log, Interval::new(0.5, 2000.0));
+        assert_eq!(labels(axis, 10), ["1", "10", "100", "1000"]);
+        let axis = Axis::new(Scale::Log, Interval::new(2.0, 8.0));
+        assert_eq!(labels(axis, 3), ["2", "4", "6", "8"]);
+    }
+
+    #[test]
+    fn zooming_and_panning() {
+        let mut axis = Axis::new(Scale::Linear, Interval::new(0.0, 10.0));
+        assert_eq!(axis.project(2.5), 0.25);
+        axis.zoom(0.5, 0.2);
+        assert_eq!(axis.interval, Interval::new(1.0, 6.0));
+        axis.pan(0.2);
+        assert_eq!(axis.interval, Interval::new(2.0, 7.0));
+
+        let mut axis = Axis::new(Scale::Log, Interval::new(1.0, 1000.0));
+        assert!((axis.project(10.0) - 1.0 / 3.0).abs() < 1e-6);
+        axis.zoom(2.0, 0.0);
+        assert!((axis.interval.end - 1e6).abs() / 1e6 < 1e-4);
+    }
+}
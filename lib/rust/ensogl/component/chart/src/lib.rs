@@ -0,0 +1,568 @@
+//! Basic charting primitives for built-in visualizations. A [`Chart`] displays line and scatter
+//! [series](series::Series) against linear or logarithmic [axes](axis::Axis) with generated tick
+//! marks. Series data can be streamed in, optionally keeping a sliding window of the newest points.
+//!
+//! Hovering the chart displays a cross-hair with the values under the pointer. The mouse wheel
+//! pans the chart, and zooms it when the `ctrl` key is pressed. While the chart is hovered, the
+//! scene [`Navigator`] provided with [`Frp::set_navigator`] is disabled, so the wheel events do
+//! not move the camera at the same time.
+
+// === Standard Linter Configuration ===
+#![deny(non_ascii_idents)]
+#![warn(unsafe_code)]
+#![allow(clippy::bool_to_int_with_if)]
+#![allow(clippy::let_and_return)]
+// === Non-Standard Linter Configuration ===
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+use ensogl_core::display::shape::*;
+use ensogl_core::prelude::*;
+
+use crate::axis::Axis;
+use crate::axis::Interval;
+use crate::axis::Scale;
+use crate::series::Series;
+
+use enso_frp as frp;
+use ensogl_core::application::Application;
+use ensogl_core::control::io::mouse;
+use ensogl_core::data::color;
+use ensogl_core::display;
+use ensogl_core::display::navigation::navigator::Navigator;
+use ensogl_core::display::navigation::navigator::PanEvent;
+use ensogl_core::display::navigation::navigator::ZoomEvent;
+use ensogl_hardcoded_theme::component::chart as theme;
+use ensogl_text::component::label_batch;
+use ensogl_text::component::label_batch::LabelId;
+use ensogl_text::LabelBatch;
+
+
+// ==============
+// === Export ===
+// ==============
+
+pub mod axis;
+pub mod series;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The space on the left of the plot area, reserved for the vertical axis labels.
+const MARGIN_LEFT: f32 = 44.0;
+/// The space below the plot area, reserved for the horizontal axis labels.
+const MARGIN_BOTTOM: f32 = 20.0;
+/// The distance between an axis and its labels.
+const LABEL_OFFSET: f32 = 4.0;
+/// The minimal distance between consecutive ticks of the horizontal axis.
+const MIN_X_TICK_DISTANCE: f32 = 80.0;
+/// The minimal distance between consecutive ticks of the vertical axis.
+const MIN_Y_TICK_DISTANCE: f32 = 40.0;
+/// The height of the axis labels, used to center them vertically.
+const LABEL_HEIGHT: f32 = label_batch::DEFAULT_FONT_SIZE * 0.7;
+/// The zoom amount per wheel delta unit. Zooming by amount `a` scales the axes by `exp(a)`.
+const WHEEL_ZOOM_SPEED: f32 = 0.005;
+
+
+
+// ================
+// === SeriesId ===
+// ================
+
+/// An index of a series in the chart.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SeriesId(pub usize);
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl_core::define_endpoints_2! {
+    Input {
+        set_size (Vector2),
+        set_x_scale (Scale),
+        set_y_scale (Scale),
+        set_x_interval (Interval),
+        set_y_interval (Interval),
+        /// Set both intervals to the smallest ones containing all points of all series.
+        fit_to_data (),
+        /// Zoom both axes around the focus point, given in the chart coordinates. Positive amounts
+        /// zoom out.
+        zoom (ZoomEvent),
+        /// Move the displayed data by the given number of pixels.
+        pan (PanEvent),
+        /// The navigator which is disabled while the chart is hovered.
+        set_navigator (Option<Navigator>),
+    }
+    Output {
+        size (Vector2),
+        x_interval (Interval),
+        y_interval (Interval),
+        /// The data values under the pointer, if the plot area is hovered.
+        hovered (Option<Vector2>),
+    }
+}
+
+
+
+// =============
+// === Style ===
+// =============
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Style {
+    background:      color::Rgba,
+    axis_color:      color::Rgba,
+    grid_color:      color::Rgba,
+    label_color:     color::Rgba,
+    crosshair_color: color::Rgba,
+    line_width:      f32,
+    point_size:      f32,
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+#[derive(Debug, display::Object)]
+struct Model {
+    display_object: display::object::Instance,
+    background:     Rectangle,
+    plot:           display::object::Instance,
+    axes:           [Rectangle; 2],
+    grid:           RefCell<Vec<Rectangle>>,
+    labels:         LabelBatch,
+    crosshair:      [Rectangle; 2],
+    crosshair_info: LabelBatch,
+    series:         RefCell<Vec<Series>>,
+    size:           Cell<Vector2>,
+    x_axis:         Cell<Axis>,
+    y_axis:         Cell<Axis>,
+    style:          Cell<Style>,
+}
+
+impl Model {
+    fn new(app: &Application) -> Self {
+        let scene = &app.display.default_scene;
+        let display_object = display::object::Instance::new_named("Chart");
+        let background = Rectangle();
+        display_object.add_child(&background);
+        let plot = display::object::Instance::new_named("Plot");
+        plot.set_xy(Vector2(MARGIN_LEFT, MARGIN_BOTTOM));
+        display_object.add_child(&plot);
+        let new_line = || {
+            let line = Rectangle();
+            line.set_pointer_events(false);
+            line
+        };
+        let axes = [new_line(), new_line()];
+        axes.iter().for_each(|axis| display_object.add_child(axis));
+        let crosshair = [new_line(), new_line()];
+        let grid = default();
+        let labels = LabelBatch::new(scene);
+        display_object.add_child(&labels);
+        let crosshair_info = LabelBatch::new(scene);
+        let series = default();
+        let size = default();
+        let x_axis = default();
+        let y_axis = default();
+        let style = default();
+        Self {
+            display_object,
+            background,
+            plot,
+            axes,
+            grid,
+            labels,
+            crosshair,
+            crosshair_info,
+            series,
+            size,
+            x_axis,
+            y_axis,
+            style,
+        }
+    }
+
+    fn plot_size(&self) -> Vector2 {
+        let size = self.size.get() - Vector2(MARGIN_LEFT, MARGIN_BOTTOM);
+        Vector2(size.x.max(0.0), size.y.max(0.0))
+    }
+
+    /// The position relative to the plot area, normalized to the `0..1` range in both dimensions.
+    fn normalized(&self, position: Vector2) -> Vector2 {
+        let plot_size = self.plot_size();
+        let position = position - Vector2(MARGIN_LEFT, MARGIN_BOTTOM);
+        Vector2(position.x / plot_size.x.max(1.0), position.y / plot_size.y.max(1.0))
+    }
+
+    fn modify_axes(&self, f: impl FnOnce(&mut Axis, &mut Axis)) {
+        let mut x_axis = self.x_axis.get();
+        let mut y_axis = self.y_axis.get();
+        f(&mut x_axis, &mut y_axis);
+        self.x_axis.set(x_axis);
+        self.y_axis.set(y_axis);
+    }
+
+    fn fit_to_data(&self) {
+        let series = self.series.borrow();
+        let bounds = series.iter().filter_map(|series| series.data.bounds());
+        let bounds = bounds.reduce(|(x1, y1), (x2, y2)| (union(x1, x2), union(y1, y2)));
+        if let Some((x, y)) = bounds {
+            self.modify_axes(|x_axis, y_axis| {
+                x_axis.interval = non_empty(x);
+                y_axis.interval = non_empty(y);
+            });
+        }
+    }
+
+    fn zoom(&self, event: ZoomEvent) {
+        let focus = self.normalized(event.focus);
+        let factor = event.amount.exp();
+        self.modify_axes(|x_axis, y_axis| {
+            x_axis.zoom(factor, focus.x);
+            y_axis.zoom(factor, focus.y);
+        });
+    }
+
+    fn pan(&self, event: PanEvent) {
+        let plot_size = self.plot_size();
+        let delta = -event.movement;
+        self.modify_axes(|x_axis, y_axis| {
+            x_axis.pan(delta.x / plot_size.x.max(1.0));
+            y_axis.pan(delta.y / plot_size.y.max(1.0));
+        });
+    }
+
+    fn hovered(&self, position: Vector2) -> Option<Vector2> {
+        let normalized = self.normalized(position);
+        let inside = (0.0..=1.0).contains(&normalized.x) && (0.0..=1.0).contains(&normalized.y);
+        inside.then(|| {
+            let x = self.x_axis.get().unproject(normalized.x);
+            let y = self.y_axis.get().unproject(normalized.y);
+            Vector2(x, y)
+        })
+    }
+
+    fn redraw(&self) {
+        let size = self.size.get();
+        let plot_size = self.plot_size();
+        let style = self.style.get();
+        let x_axis = self.x_axis.get();
+        let y_axis = self.y_axis.get();
+        self.background.set_size(size);
+        self.background.set_color(style.background);
+
+        let [x_line, y_line] = &self.axes;
+        x_line.set_xy(Vector2(MARGIN_LEFT, MARGIN_BOTTOM));
+        x_line.set_size(Vector2(plot_size.x, 1.0));
+        x_line.set_color(style.axis_color);
+        y_line.set_xy(Vector2(MARGIN_LEFT, MARGIN_BOTTOM));
+        y_line.set_size(Vector2(1.0, plot_size.y));
+        y_line.set_color(style.axis_color);
+
+        let x_ticks = x_axis.ticks((plot_size.x / MIN_X_TICK_DISTANCE).max(2.0) as usize);
+        let y_ticks = y_axis.ticks((plot_size.y / MIN_Y_TICK_DISTANCE).max(2.0) as usize);
+        let x_ticks = x_ticks.into_iter().map(|tick| (x_axis.project(tick.value), tick.label));
+        let y_ticks = y_ticks.into_iter().map(|tick| (y_axis.project(tick.value), tick.label));
+        let is_visible = |(position, _): &(f32, String)| (0.0..=1.0).contains(position);
+        let x_ticks = x_ticks.filter(is_visible).collect_vec();
+        let y_ticks = y_ticks.filter(is_visible).collect_vec();
+        let labels = x_ticks.iter().chain(&y_ticks).map(|(_, label)| label.clone());
+        self.labels.set_labels(labels);
+        self.labels.set_color(style.label_color);
+
+        let mut grid = self.grid.borrow_mut();
+        let mut coordinates = Vec::with_capacity((x_ticks.len() + y_ticks.len()) * 2);
+        let mut grid_lines = Vec::with_capacity(x_ticks.len() + y_ticks.len());
+        for (index, (position, _)) in x_ticks.iter().enumerate() {
+            let x = MARGIN_LEFT + position * plot_size.x;
+            let width = self.labels.width(LabelId(index)).unwrap_or_default();
+            coordinates.extend([x - width / 2.0, MARGIN_BOTTOM - LABEL_OFFSET - LABEL_HEIGHT]);
+            grid_lines.push((Vector2(x, MARGIN_BOTTOM), Vector2(1.0, plot_size.y)));
+        }
+        for (index, (position, _)) in y_ticks.iter().enumerate() {
+            let y = MARGIN_BOTTOM + position * plot_size.y;
+            let id = LabelId(x_ticks.len() + index);
+            let width = self.labels.width(id).unwrap_or_default();
+            coordinates.extend([MARGIN_LEFT - LABEL_OFFSET - width, y - LABEL_HEIGHT / 2.0]);
+            grid_lines.push((Vector2(MARGIN_LEFT, y), Vector2(plot_size.x, 1.0)));
+        }
+        self.labels.set_positions(&coordinates);
+        grid.truncate(grid_lines.len());
+        while grid.len() < grid_lines.len() {
+            let line = Rectangle();
+            line.set_pointer_events(false);
+            self.plot.add_child(&line);
+            grid.push(line);
+        }
+        for (line, (position, size)) in grid.iter().zip(grid_lines) {
+            let position = position - Vector2(MARGIN_LEFT, MARGIN_BOTTOM);
+            line.set_xy(position);
+            line.set_size(size);
+            line.set_color(style.grid_color);
+        }
+
+        for series in self.series.borrow_mut().iter_mut() {
+            series.redraw(&x_axis, &y_axis, plot_size, style.line_width, style.point_size);
+        }
+    }
+
+    fn redraw_crosshair(&self, position: Vector2, hovered: Option<Vector2>) {
+        let style = self.style.get();
+        let plot_size = self.plot_size();
+        let [vertical, horizontal] = &self.crosshair;
+        match hovered {
+            Some(value) => {
+                self.display_object.add_child(vertical);
+                self.display_object.add_child(horizontal);
+                self.display_object.add_child(&self.crosshair_info);
+                vertical.set_xy(Vector2(position.x, MARGIN_BOTTOM));
+                vertical.set_size(Vector2(1.0, plot_size.y));
+                vertical.set_color(style.crosshair_color);
+                horizontal.set_xy(Vector2(MARGIN_LEFT, position.y));
+                horizontal.set_size(Vector2(plot_size.x, 1.0));
+                horizontal.set_color(style.crosshair_color);
+                let info = format!("{:.3}, {:.3}", value.x, value.y);
+                self.crosshair_info.set_labels([info]);
+                self.crosshair_info.set_color(style.label_color);
+                self.crosshair_info.set_positions(&[position.x + LABEL_OFFSET, position.y + 2.0]);
+            }
+            None => {
+                vertical.unset_parent();
+                horizontal.unset_parent();
+                self.crosshair_info.unset_parent();
+            }
+        }
+    }
+
+    fn add_series(&self, kind: series::Kind, color: color::Rgba) -> SeriesId {
+        let series = Series::new(kind, color);
+        self.plot.add_child(&series);
+        let mut all_series = self.series.borrow_mut();
+        all_series.push(series);
+        SeriesId(all_series.len() - 1)
+    }
+}
+
+fn union(a: Interval, b: Interval) -> Interval {
+    Interval::new(a.start.min(b.start), a.end.max(b.end))
+}
+
+/// Extend empty intervals, so all points of the data are not projected to the same position.
+fn non_empty(interval: Interval) -> Interval {
+    if interval.span() > 0.0 {
+        interval
+    } else {
+        Interval::new(interval.start - 1.0, interval.end + 1.0)
+    }
+}
+
+
+
+// =============
+// === Chart ===
+// =============
+
+/// A chart displaying data series. See the module docs to learn more.
+#[derive(Clone, CloneRef, Debug, Deref, display::Object)]
+pub struct Chart {
+    #[deref]
+    frp:   Frp,
+    #[display_object]
+    model: Rc<Model>,
+}
+
+impl Chart {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let frp = Frp::new();
+        let model = Rc::new(Model::new(app));
+        Self { frp, model }.init(app)
+    }
+
+    fn init(self, app: &Application) -> Self {
+        let network = self.frp.network();
+        let input = &self.frp.private.input;
+        let out = &self.frp.private.output;
+        let model = &self.model;
+        let scene = &app.display.default_scene;
+        let style = StyleWatchFrp::new(&scene.style_sheet);
+        let background = style.get_color(theme::background);
+        let axis_color = style.get_color(theme::axis_color);
+        let grid_color = style.get_color(theme::grid_color);
+        let label_color = style.get_color(theme::label_color);
+        let crosshair_color = style.get_color(theme::crosshair_color);
+        let line_width = style.get_number(theme::line_width);
+        let point_size = style.get_number(theme::point_size);
+        let pointer_over = model.background.on_event::<mouse::Over>();
+        let pointer_out = model.background.on_event::<mouse::Out>();
+        let pointer_move = model.background.on_event::<mouse::Move>();
+        let wheel = model.background.on_event::<mouse::Wheel>();
+        let pointer = scene.pointer_position_in_object_space(network, &**model);
+
+        frp::extend! { network
+
+            // === Style ===
+
+            init <- source_();
+            colors_changed <- any_(&init, &background, &axis_color, &grid_color, &label_color);
+            style_changed <- any_(&colors_changed, &crosshair_color, &line_width, &point_size);
+            eval_ style_changed (model.style.set(Style {
+                background: background.value(),
+                axis_color: axis_color.value(),
+                grid_color: grid_color.value(),
+                label_color: label_color.value(),
+                crosshair_color: crosshair_color.value(),
+                line_width: line_width.value(),
+                point_size: point_size.value(),
+            }));
+
+
+            // === Axes ===
+
+            eval input.set_size ((size) model.size.set(*size));
+            out.size <+ input.set_size;
+            eval input.set_x_scale ((scale) model.modify_axes(|x, _| x.scale = *scale));
+            eval input.set_y_scale ((scale) model.modify_axes(|_, y| y.scale = *scale));
+            eval input.set_x_interval ((i) model.modify_axes(|x, _| x.interval = *i));
+            eval input.set_y_interval ((i) model.modify_axes(|_, y| y.interval = *i));
+            eval_ input.fit_to_data (model.fit_to_data());
+            eval input.zoom ((event) model.zoom(*event));
+            eval input.pan ((event) model.pan(*event));
+            axes_set <- any_(
+                &input.set_x_scale,
+                &input.set_y_scale,
+                &input.set_x_interval,
+                &input.set_y_interval,
+                &input.fit_to_data,
+            );
+            axes_changed <- any_(&axes_set, &input.zoom, &input.pan);
+            out.x_interval <+ axes_changed.map(f_!(model.x_axis.get().interval));
+            out.y_interval <+ axes_changed.map(f_!(model.y_axis.get().interval));
+            redraw <- any_(&style_changed, &input.set_size, &axes_changed);
+            eval_ redraw (model.redraw());
+
+
+            // === Cross-hair ===
+
+            local_position <- pointer.sample(&pointer_move);
+            hovered <- local_position.map(f!((position) model.hovered(*position)));
+            out.hovered <+ hovered;
+            out.hovered <+ pointer_out.constant(None);
+            crosshair <- all(&local_position, &out.hovered);
+            eval crosshair (((position, hovered)) model.redraw_crosshair(*position, *hovered));
+
+
+            // === Navigation ===
+
+            navigator <- input.set_navigator.sampler();
+            over <- bool(&pointer_out, &pointer_over);
+            eval over ([navigator](over) if let Some(navigator) = navigator.value() {
+                navigator.frp.set_enabled(!over);
+            });
+            eval wheel ((event) {
+                event.prevent_default();
+                event.stop_propagation();
+            });
+            wheel <- wheel.map(f!([pointer](event) {
+                (event.ctrl_key(), Vector2(event.delta_x(), event.delta_y()), pointer.value())
+            }));
+            input.zoom <+ wheel.filter_map(|(zoom, delta, focus)| {
+                zoom.then(|| ZoomEvent { focus: *focus, amount: delta.y * WHEEL_ZOOM_SPEED })
+            });
+            input.pan <+ wheel.filter_map(|(zoom, delta, _)| {
+                (!zoom).then(|| PanEvent::new(Vector2(-delta.x, delta.y)))
+            });
+        }
+        init.emit(());
+        self
+    }
+
+    /// Add an empty series.
+    pub fn add_series(&self, kind: series::Kind, color: impl Into<color::Rgba>) -> SeriesId {
+        self.model.add_series(kind, color.into())
+    }
+
+    /// Replace all points of the series.
+    pub fn set_points(&self, id: SeriesId, points: impl IntoIterator<Item = Vector2>) {
+        self.modify_series(id, |series| series.data.set(points));
+    }
+
+    /// Append points at the end of the series. This is the way of streaming data to the chart.
+    pub fn append_points(&self, id: SeriesId, points: impl IntoIterator<Item = Vector2>) {
+        self.modify_series(id, |series| series.data.append(points));
+    }
+
+    /// Set the maximum number of points kept in the series. The oldest points are dropped first.
+    pub fn set_capacity(&self, id: SeriesId, capacity: Option<usize>) {
+        self.modify_series(id, |series| series.data.set_capacity(capacity));
+    }
+
+    /// Change the way the series is displayed.
+    pub fn set_kind(&self, id: SeriesId, kind: series::Kind) {
+        self.modify_series(id, |series| series.set_kind(kind));
+    }
+
+    /// The number of points in the series.
+    pub fn point_count(&self, id: SeriesId) -> usize {
+        let series = self.model.series.borrow();
+        series.get(id.0).map_or(0, |series| series.data.points().len())
+    }
+
+    fn modify_series(&self, id: SeriesId, f: impl FnOnce(&mut Series)) {
+        let modified = self.model.series.borrow_mut().get_mut(id.0).map(f).is_some();
+        if modified {
+            self.model.redraw();
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streaming_fitting_and_zooming() {
+        let app = Application::new("root");
+        let chart = Chart::new(&app);
+        chart.set_size(Vector2(244.0, 120.0));
+        let line = chart.add_series(series::Kind::Line, color::Rgba::black());
+        let points = chart.add_series(series::Kind::Scatter, color::Rgba::black());
+        chart.set_capacity(line, Some(10));
+        chart.append_points(line, (0..20).map(|i| Vector2(i as f32, (i % 5) as f32)));
+        chart.append_points(points, [Vector2(15.0, -1.0)]);
+        assert_eq!(chart.point_count(line), 10);
+
+        chart.fit_to_data();
+        assert_eq!(chart.x_interval.value(), Interval::new(10.0, 19.0));
+        assert_eq!(chart.y_interval.value(), Interval::new(-1.0, 4.0));
+
+        let center = Vector2(MARGIN_LEFT + 100.0, MARGIN_BOTTOM + 50.0);
+        chart.zoom(ZoomEvent { focus: center, amount: -(2_f32.ln()) });
+        let x = chart.x_interval.value();
+        assert!((x.start - 12.25).abs() < 1e-4 && (x.end - 16.75).abs() < 1e-4);
+
+        chart.pan(PanEvent::new(Vector2(-50.0, 0.0)));
+        let panned = chart.x_interval.value();
+        assert!((panned.start - x.start - x.span() / 4.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,206 @@
+//! Data series displayed in a chart, as connected lines or scattered points.
+
+use ensogl_core::display::shape::*;
+use ensogl_core::prelude::*;
+
+use crate::axis::Axis;
+use crate::axis::Interval;
+
+use ensogl_core::data::color;
+use ensogl_core::display;
+
+
+
+// ============
+// === Kind ===
+// ============
+
+/// The way a series is displayed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// Consecutive points connected with straight lines.
+    #[default]
+    Line,
+    /// Separate points.
+    Scatter,
+}
+
+
+
+// ============
+// === Data ===
+// ============
+
+/// The points of a series, sorted by the order of appending. If the capacity is set, the oldest
+/// points are dropped when it is exceeded, which allows displaying a sliding window of streamed
+/// data.
+#[derive(Clone, Debug, Default)]
+pub struct Data {
+    points:   VecDeque<Vector2>,
+    capacity: Option<usize>,
+}
+
+impl Data {
+    /// The points of the series.
+    pub fn points(&self) -> &VecDeque<Vector2> {
+        &self.points
+    }
+
+    /// Set the maximum number of kept points. [`None`] means no limit.
+    pub fn set_capacity(&mut self, capacity: Option<usize>) {
+        self.capacity = capacity;
+        self.drop_overflow();
+    }
+
+    /// Replace all points.
+    pub fn set(&mut self, points: impl IntoIterator<Item = Vector2>) {
+        self.points.clear();
+        self.append(points);
+    }
+
+    /// Append points at the end of the series.
+    pub fn append(&mut self, points: impl IntoIterator<Item = Vector2>) {
+        self.points.extend(points);
+        self.drop_overflow();
+    }
+
+    fn drop_overflow(&mut self) {
+        if let Some(capacity) = self.capacity {
+            let overflow = self.points.len().saturating_sub(capacity);
+            self.points.drain(..overflow);
+        }
+    }
+
+    /// The smallest intervals containing all points, or [`None`] if the series is empty.
+    pub fn bounds(&self) -> Option<(Interval, Interval)> {
+        let mut points = self.points.iter();
+        let first = points.next()?;
+        let init = (Interval::new(first.x, first.x), Interval::new(first.y, first.y));
+        Some(points.fold(init, |(x, y), p| {
+            let x = Interval::new(x.start.min(p.x), x.end.max(p.x));
+            let y = Interval::new(y.start.min(p.y), y.end.max(p.y));
+            (x, y)
+        }))
+    }
+
+    /// The point with the `x` coordinate closest to the given one.
+    pub fn nearest_by_x(&self, x: f32) -> Option<Vector2> {
+        let distance = |p: &&Vector2| (p.x - x).abs();
+        self.points.iter().min_by(|a, b| distance(a).total_cmp(&distance(b))).copied()
+    }
+}
+
+
+
+// ==============
+// === Series ===
+// ==============
+
+/// The visual representation of a data series. The shapes are reused between redraws.
+#[derive(Debug, display::Object)]
+pub struct Series {
+    display_object: display::object::Instance,
+    kind:           Kind,
+    /// The color of lines and points.
+    pub color:      color::Rgba,
+    /// The displayed points.
+    pub data:       Data,
+    shapes:         Vec<Rectangle>,
+}
+
+impl Series {
+    /// Constructor.
+    pub fn new(kind: Kind, color: color::Rgba) -> Self {
+        let display_object = display::object::Instance::new_named("Series");
+        let data = default();
+        let shapes = default();
+        Self { display_object, kind, color, data, shapes }
+    }
+
+    /// Update the shapes to display the points in the plot area of the given size. Points outside
+    /// of the axes intervals are not displayed, and neither are lines leading to them.
+    pub fn redraw(&mut self, x: &Axis, y: &Axis, size: Vector2, line_width: f32, point_size: f32) {
+        let project = |p: &Vector2| Vector2(x.project(p.x) * size.x, y.project(p.y) * size.y);
+        let is_visible = |p: &Vector2| x.interval.contains(p.x) && y.interval.contains(p.y);
+        let points = self.data.points.iter().copied().collect_vec();
+        let mut count = 0;
+        match self.kind {
+            Kind::Line =>
+                for (a, b) in points.iter().tuple_windows() {
+                    if is_visible(a) && is_visible(b) {
+                        let shape = self.shape(count, Rectangle);
+                        count += 1;
+                        let (a, b) = (project(a), project(b));
+                        let direction = b - a;
+                        let angle = direction.y.atan2(direction.x);
+                        let normal = Vector2(-angle.sin(), angle.cos());
+                        shape.set_size(Vector2(direction.norm(), line_width));
+                        shape.set_xy(a - normal * line_width / 2.0);
+                        shape.set_rotation_z(angle);
+                    }
+                },
+            Kind::Scatter =>
+                for point in points.iter().filter(|p| is_visible(p)) {
+                    let shape = self.shape(count, Circle);
+                    count += 1;
+                    let radius = point_size / 2.0;
+                    shape.set_size(Vector2(point_size, point_size));
+                    shape.set_xy(project(point) - Vector2(radius, radius));
+                },
+        }
+        self.shapes.truncate(count);
+        let color = self.color;
+        self.shapes.iter().for_each(|shape| {
+            shape.set_color(color);
+        });
+    }
+
+    fn shape(&mut self, index: usize, new: impl FnOnce() -> Rectangle) -> &Rectangle {
+        if index == self.shapes.len() {
+            let shape = new();
+            shape.set_pointer_events(false);
+            self.display_object.add_child(&shape);
+            self.shapes.push(shape);
+        }
+        &self.shapes[index]
+    }
+
+    /// The way the series is displayed.
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// Change the way the series is displayed. The change is visible after the next redraw.
+    pub fn set_kind(&mut self, kind: Kind) {
+        if self.kind != kind {
+            self.kind = kind;
+            self.shapes.clear();
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streamed_points_are_capped() {
+        let mut data = Data::default();
+        data.set_capacity(Some(3));
+        data.append((0..5).map(|i| Vector2(i as f32, (i * i) as f32)));
+        let xs = data.points().iter().map(|p| p.x).collect_vec();
+        assert_eq!(xs, [2.0, 3.0, 4.0]);
+        let (x, y) = data.bounds().unwrap();
+        assert_eq!(x, Interval::new(2.0, 4.0));
+        assert_eq!(y, Interval::new(4.0, 16.0));
+        assert_eq!(data.nearest_by_x(2.4), Some(Vector2(2.0, 4.0)));
+        data.set([]);
+        assert_eq!(data.bounds(), None);
+    }
+}
@@ -0,0 +1,261 @@
+//! The view of a tab group: a tab bar with the titles of the grouped panels, above the content of
+//! the active panel.
+
+use ensogl_core::display::shape::*;
+use ensogl_core::prelude::*;
+
+use enso_frp as frp;
+use ensogl_core::application::Application;
+use ensogl_core::control::io::mouse;
+use ensogl_core::data::color;
+use ensogl_core::display;
+use ensogl_core::display::scene::Layer;
+use ensogl_core::gui::accessibility;
+use ensogl_hardcoded_theme::component::dock as theme;
+use ensogl_text as text;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The height of the tab bar.
+pub const TAB_BAR_HEIGHT: f32 = 24.0;
+/// The width of a single tab.
+pub const TAB_WIDTH: f32 = 110.0;
+const TAB_PADDING: f32 = 8.0;
+const TAB_FONT_SIZE: f32 = 12.0;
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl_core::define_endpoints_2! {
+    Input {
+        /// Set the tabs, as pairs of the panel identifier and title.
+        set_tabs (Vec<(ImString, ImString)>),
+        set_active (usize),
+        /// Set the size of the group, including the tab bar.
+        set_size (Vector2),
+    }
+    Output {
+        /// The identifier of the panel whose tab was pressed with the primary mouse button.
+        tab_pressed (ImString),
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+#[derive(Debug, display::Object)]
+struct Model {
+    app:             Application,
+    display_object:  display::object::Instance,
+    tab_bar:         Rectangle,
+    active_tab:      Rectangle,
+    titles:          RefCell<Vec<text::Text>>,
+    title_color:     Cell<color::Rgba>,
+    text_layer:      RefCell<Option<Layer>>,
+    content:         display::object::Instance,
+    accessibility:   accessibility::Node,
+    accessible_tabs: RefCell<Vec<accessibility::Node>>,
+}
+
+impl Model {
+    fn new(app: &Application) -> Self {
+        let app = app.clone_ref();
+        let display_object = display::object::Instance::new_named("TabGroup");
+        let tab_bar = Rectangle();
+        let active_tab = Rectangle();
+        let content = display::object::Instance::new_named("content");
+        display_object.add_child(&tab_bar);
+        display_object.add_child(&active_tab);
+        display_object.add_child(&content);
+        active_tab.set_pointer_events(false);
+        tab_bar.set_y(-TAB_BAR_HEIGHT);
+        active_tab.set_size(Vector2(TAB_WIDTH, TAB_BAR_HEIGHT));
+        active_tab.set_y(-TAB_BAR_HEIGHT);
+        content.set_y(-TAB_BAR_HEIGHT);
+        let titles = default();
+        let title_color = default();
+        let text_layer = default();
+        let scene = &app.display.default_scene;
+        let accessibility = accessibility::Node::new(scene, accessibility::Role::TabList);
+        let accessible_tabs = default();
+        Self {
+            app,
+            display_object,
+            tab_bar,
+            active_tab,
+            titles,
+            title_color,
+            text_layer,
+            content,
+            accessibility,
+            accessible_tabs,
+        }
+    }
+
+    fn new_title(&self, index: usize) -> text::Text {
+        let title = self.app.new_view::<text::Text>();
+        title.set_property_default(text::Size(TAB_FONT_SIZE));
+        title.set_property_default(color::Lcha::from(self.title_color.get()));
+        title.set_view_width(Some(TAB_WIDTH - 2.0 * TAB_PADDING));
+        let y = -(TAB_BAR_HEIGHT - TAB_FONT_SIZE) / 2.0;
+        title.set_xy(Vector2(index as f32 * TAB_WIDTH + TAB_PADDING, y));
+        self.display_object.add_child(&title);
+        if let Some(layer) = &*self.text_layer.borrow() {
+            layer.add(&title);
+        }
+        title
+    }
+
+    fn set_tabs(&self, tabs: &[(ImString, ImString)]) {
+        let mut titles = self.titles.borrow_mut();
+        let kept = tabs.len().min(titles.len());
+        for removed in titles.drain(kept..) {
+            removed.unset_parent();
+        }
+        while titles.len() < tabs.len() {
+            let title = self.new_title(titles.len());
+            titles.push(title);
+        }
+        for (title, (_, content)) in titles.iter().zip(tabs) {
+            title.set_content(content.clone_ref());
+        }
+    }
+
+    /// Mirror the tabs into the accessibility tree. Activating the tab node emits its index.
+    fn update_accessible_tabs(
+        &self,
+        tabs: &[(ImString, ImString)],
+        active: usize,
+        activated: &frp::Source<usize>,
+    ) {
+        let mut nodes = self.accessible_tabs.borrow_mut();
+        nodes.truncate(tabs.len());
+        while nodes.len() < tabs.len() {
+            let node = self.accessibility.add_child(accessibility::Role::Tab);
+            let index = nodes.len();
+            let network = node.network();
+            frp::extend! { network
+                eval_ node.activated (activated.emit(index));
+            }
+            nodes.push(node);
+        }
+        for (index, (node, (_, title))) in nodes.iter().zip(tabs).enumerate() {
+            node.set_label(title.clone_ref());
+            node.set_selected(Some(index == active));
+        }
+    }
+
+    fn set_title_color(&self, color: color::Rgba) {
+        self.title_color.set(color);
+        for title in &*self.titles.borrow() {
+            title.set_property_default(color::Lcha::from(color));
+        }
+    }
+
+    fn set_text_layer(&self, layer: &Layer) {
+        for title in &*self.titles.borrow() {
+            layer.add(title);
+        }
+        *self.text_layer.borrow_mut() = Some(layer.clone_ref());
+    }
+
+    fn tab_at(&self, position: Vector2, tabs: &[(ImString, ImString)]) -> Option<ImString> {
+        let on_tab_bar = (-TAB_BAR_HEIGHT..=0.0).contains(&position.y) && position.x >= 0.0;
+        if !on_tab_bar {
+            return None;
+        }
+        let index = (position.x / TAB_WIDTH) as usize;
+        tabs.get(index).map(|(id, _)| id.clone_ref())
+    }
+}
+
+
+
+// =============
+// === Group ===
+// =============
+
+/// The view of a tab group. Its origin is the top-left corner. See the module docs to learn more.
+#[derive(Clone, CloneRef, Debug, Deref, display::Object)]
+pub struct Group {
+    #[deref]
+    frp:   Frp,
+    #[display_object]
+    model: Rc<Model>,
+}
+
+impl Group {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let frp = Frp::new();
+        let model = Rc::new(Model::new(app));
+        Self { frp, model }.init(app)
+    }
+
+    fn init(self, app: &Application) -> Self {
+        let network = self.frp.network();
+        let input = &self.frp.private.input;
+        let out = &self.frp.private.output;
+        let model = &self.model;
+        let scene = &app.display.default_scene;
+        let style = StyleWatchFrp::new(&scene.style_sheet);
+        let tab_bar_color = style.get_color(theme::tab_bar);
+        let active_tab_color = style.get_color(theme::active_tab);
+        let title_color = style.get_color(theme::tab_text);
+        let tab_bar_down = model.tab_bar.on_event::<mouse::Down>();
+        let pointer = scene.pointer_position_in_object_space(network, &model.display_object);
+
+        frp::extend! { network
+            init <- source_();
+            colors_changed <- any_(&init, &tab_bar_color, &active_tab_color, &title_color);
+            eval_ colors_changed ([model, tab_bar_color, active_tab_color, title_color] {
+                model.tab_bar.set_color(tab_bar_color.value());
+                model.active_tab.set_color(active_tab_color.value());
+                model.set_title_color(title_color.value());
+            });
+
+            eval input.set_tabs ((tabs) model.set_tabs(tabs));
+            eval input.set_active ((index) model.active_tab.set_x(*index as f32 * TAB_WIDTH));
+            eval input.set_size ((size) model.tab_bar.set_size(Vector2(size.x, TAB_BAR_HEIGHT)));
+
+            tab_pressed <- tab_bar_down.filter(|e| e.button() == mouse::PrimaryButton);
+            pressed_position <- pointer.sample(&tab_pressed);
+            pressed_tab <- pressed_position.map2(&input.set_tabs,
+                f!((position, tabs) model.tab_at(*position, tabs))
+            );
+            out.tab_pressed <+ pressed_tab.filter_map(|tab| tab.clone());
+
+            accessible_tab_activated <- source::<usize>();
+            accessible_tabs <- all(&input.set_tabs, &input.set_active);
+            eval accessible_tabs ([model, accessible_tab_activated]((tabs, active))
+                model.update_accessible_tabs(tabs, *active, &accessible_tab_activated));
+            activated_tab <- accessible_tab_activated.map2(&input.set_tabs,
+                |index, tabs| tabs.get(*index).map(|(id, _)| id.clone_ref())
+            );
+            out.tab_pressed <+ activated_tab.filter_map(|tab| tab.clone());
+        }
+        init.emit(());
+        self
+    }
+
+    /// The display object to which the active panel content should be added. Its origin is the
+    /// top-left corner of the area below the tab bar.
+    pub fn content(&self) -> &display::object::Instance {
+        &self.model.content
+    }
+
+    /// Display the tab titles in the given layer.
+    pub fn set_text_layer(&self, layer: &Layer) {
+        self.model.set_text_layer(layer);
+    }
+}
@@ -0,0 +1,367 @@
+//! The description of a dock layout: which panels are grouped together as tabs, and where the tab
+//! groups are placed. Layouts are serializable, so they can be stored in the application settings.
+//!
+//! All positions are expressed in the coordinate system of the screen, with the origin in the
+//! screen center and the y axis pointing up.
+
+use ensogl_core::prelude::*;
+
+use ensogl_side_panel::Side;
+use serde::Deserialize;
+use serde::Serialize;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The distance from the screen edge within which a dropped panel is docked to that edge.
+pub const DOCK_ZONE_SIZE: f32 = 48.0;
+/// The size of a group created by docking a panel to a screen side with no group yet.
+pub const DEFAULT_DOCKED_SIZE: f32 = 240.0;
+/// The size of a floating group created by dropping a panel outside of other groups.
+pub const DEFAULT_FLOATING_SIZE: (f32, f32) = (320.0, 240.0);
+
+
+
+// =================
+// === Placement ===
+// =================
+
+/// The placement of a tab group.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub enum Placement {
+    /// Docked to a side of the screen. The size is measured perpendicularly to the side.
+    Docked { side: Side, size: f32, collapsed: bool },
+    /// Floating over the scene. The position is the top-left corner of the group.
+    Floating { position: (f32, f32), size: (f32, f32) },
+}
+
+impl Placement {
+    /// A group docked to the side with the default size.
+    pub fn docked(side: Side) -> Self {
+        Self::Docked { side, size: DEFAULT_DOCKED_SIZE, collapsed: false }
+    }
+
+    /// A floating group with the top-left corner at the given position and the default size.
+    pub fn floating(position: Vector2) -> Self {
+        Self::Floating { position: (position.x, position.y), size: DEFAULT_FLOATING_SIZE }
+    }
+
+    /// The side of the screen the group is docked to.
+    pub fn side(&self) -> Option<Side> {
+        match self {
+            Self::Docked { side, .. } => Some(*side),
+            Self::Floating { .. } => None,
+        }
+    }
+
+    /// The area covered by the group on the screen of the given size, as its top-left corner and
+    /// size. Collapsed groups cover no area.
+    pub fn area(&self, screen_size: Vector2) -> (Vector2, Vector2) {
+        let left = -screen_size.x / 2.0;
+        let right = screen_size.x / 2.0;
+        let top = screen_size.y / 2.0;
+        let bottom = -screen_size.y / 2.0;
+        match *self {
+            Self::Docked { collapsed: true, side, .. } =>
+                Self::Docked { side, size: 0.0, collapsed: false }.area(screen_size),
+            Self::Docked { side: Side::Left, size, .. } =>
+                (Vector2(left, top), Vector2(size, screen_size.y)),
+            Self::Docked { side: Side::Right, size, .. } =>
+                (Vector2(right - size, top), Vector2(size, screen_size.y)),
+            Self::Docked { side: Side::Bottom, size, .. } =>
+                (Vector2(left, bottom + size), Vector2(screen_size.x, size)),
+            Self::Floating { position, size } =>
+                (Vector2(position.0, position.1), Vector2(size.0, size.1)),
+        }
+    }
+}
+
+
+
+// =============
+// === Group ===
+// =============
+
+/// A group of panels displayed as tabs, only the active one being visible.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Group {
+    /// Where the group is displayed.
+    pub placement: Placement,
+    /// The identifiers of the grouped panels, in the order of their tabs.
+    pub tabs:      Vec<ImString>,
+    /// The index of the active tab.
+    pub active:    usize,
+}
+
+impl Group {
+    /// Constructor. The first tab is active.
+    pub fn new(placement: Placement, tabs: impl IntoIterator<Item = impl Into<ImString>>) -> Self {
+        let tabs = tabs.into_iter().map(Into::into).collect();
+        Self { placement, tabs, active: 0 }
+    }
+
+    /// The identifier of the active panel.
+    pub fn active_tab(&self) -> Option<&ImString> {
+        self.tabs.get(self.active)
+    }
+}
+
+
+
+// ==============
+// === Target ===
+// ==============
+
+/// The place where a panel is moved to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Target {
+    /// The group docked to the side of the screen, created if there is none.
+    Side(Side),
+    /// The existing group with the given index.
+    Group(usize),
+    /// A new floating group with the top-left corner at the given position.
+    Floating(Vector2),
+}
+
+impl Default for Target {
+    fn default() -> Self {
+        Self::Side(default())
+    }
+}
+
+
+
+// ==============
+// === Layout ===
+// ==============
+
+/// The dock layout. See the module docs to learn more.
+///
+/// There is at most one group docked to each side of the screen. The floating groups are ordered
+/// from the bottom to the top one.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Layout {
+    /// The tab groups.
+    pub groups: Vec<Group>,
+}
+
+impl Layout {
+    /// Constructor.
+    pub fn new(groups: impl IntoIterator<Item = Group>) -> Self {
+        let mut layout = Self { groups: groups.into_iter().collect() };
+        layout.normalize();
+        layout
+    }
+
+    /// The index of the group containing the panel.
+    pub fn group_of(&self, panel: &str) -> Option<usize> {
+        self.groups.iter().position(|group| group.tabs.iter().any(|tab| tab.as_str() == panel))
+    }
+
+    /// The index of the group docked to the side.
+    pub fn docked(&self, side: Side) -> Option<usize> {
+        self.groups.iter().position(|group| group.placement.side() == Some(side))
+    }
+
+    /// Make the panel the active tab of its group. Returns `false` if the panel is not in the
+    /// layout.
+    pub fn activate(&mut self, panel: &str) -> bool {
+        let Some(index) = self.group_of(panel) else { return false };
+        let group = &mut self.groups[index];
+        group.active = group.tabs.iter().position(|tab| tab.as_str() == panel).unwrap_or_default();
+        true
+    }
+
+    /// Move the panel to the target, and make it the active tab there. A panel which is not in the
+    /// layout yet is added. Groups left empty are removed.
+    pub fn move_panel(&mut self, panel: &ImString, target: Target) {
+        let source = self.group_of(panel);
+        let target = match target {
+            Target::Group(index) if index < self.groups.len() => index,
+            Target::Group(_) => return,
+            Target::Side(side) =>
+                self.docked(side).unwrap_or_else(|| self.push_group(Placement::docked(side))),
+            Target::Floating(position) => self.push_group(Placement::floating(position)),
+        };
+        if source == Some(target) {
+            self.activate(panel);
+            return;
+        }
+        if let Some(source) = source {
+            self.groups[source].tabs.retain(|tab| tab != panel);
+        }
+        let group = &mut self.groups[target];
+        group.tabs.push(panel.clone_ref());
+        group.active = group.tabs.len() - 1;
+        self.normalize();
+    }
+
+    /// Remove the panel from the layout. Groups left empty are removed.
+    pub fn remove_panel(&mut self, panel: &str) {
+        for group in &mut self.groups {
+            group.tabs.retain(|tab| tab.as_str() != panel);
+        }
+        self.normalize();
+    }
+
+    fn push_group(&mut self, placement: Placement) -> usize {
+        self.groups.push(Group { placement, tabs: default(), active: 0 });
+        self.groups.len() - 1
+    }
+
+    /// Merge the groups docked to the same side, remove the empty groups, and fix the active tab
+    /// indices. Layouts read from the settings may not satisfy these invariants.
+    pub fn normalize(&mut self) {
+        let mut docked_groups = HashMap::<Side, usize>::new();
+        let mut normalized = Vec::<Group>::with_capacity(self.groups.len());
+        for group in mem::take(&mut self.groups) {
+            let docked_group = group.placement.side().and_then(|side| docked_groups.get(&side));
+            if let Some(&index) = docked_group {
+                normalized[index].tabs.extend(group.tabs);
+            } else if !group.tabs.is_empty() {
+                if let Some(side) = group.placement.side() {
+                    docked_groups.insert(side, normalized.len());
+                }
+                normalized.push(group);
+            }
+        }
+        for group in &mut normalized {
+            group.active = group.active.min(group.tabs.len().saturating_sub(1));
+        }
+        self.groups = normalized;
+    }
+
+    /// The target of a panel dropped at the given position. Dropping near a screen edge docks the
+    /// panel there; dropping over a group adds the panel to its tabs.
+    pub fn drop_target(&self, position: Vector2, screen_size: Vector2) -> Target {
+        let half_screen = screen_size / 2.0;
+        if position.x < -half_screen.x + DOCK_ZONE_SIZE {
+            return Target::Side(Side::Left);
+        }
+        if position.x > half_screen.x - DOCK_ZONE_SIZE {
+            return Target::Side(Side::Right);
+        }
+        if position.y < -half_screen.y + DOCK_ZONE_SIZE {
+            return Target::Side(Side::Bottom);
+        }
+        let contains = |group: &Group| {
+            let (top_left, size) = group.placement.area(screen_size);
+            let x_range = top_left.x..=top_left.x + size.x;
+            let y_range = top_left.y - size.y..=top_left.y;
+            x_range.contains(&position.x) && y_range.contains(&position.y)
+        };
+        let is_floating = |index: &usize| self.groups[*index].placement.side().is_none();
+        let is_hovered = |index: &usize| contains(&self.groups[*index]);
+        let indices = 0..self.groups.len();
+        let (floating, docked): (Vec<_>, Vec<_>) = indices.partition(is_floating);
+        let floating_hovered = floating.into_iter().rev().find(is_hovered);
+        match floating_hovered.or_else(|| docked.into_iter().find(is_hovered)) {
+            Some(index) => Target::Group(index),
+            None => {
+                let half_width = DEFAULT_FLOATING_SIZE.0 / 2.0;
+                Target::Floating(Vector2(position.x - half_width, position.y))
+            }
+        }
+    }
+
+    /// The area which would be covered by the group after dropping a panel on the target, as its
+    /// top-left corner and size.
+    pub fn target_area(&self, target: Target, screen_size: Vector2) -> (Vector2, Vector2) {
+        let placement = match target {
+            Target::Side(side) => match self.docked(side) {
+                Some(index) => self.groups[index].placement,
+                None => Placement::docked(side),
+            },
+            Target::Group(index) => match self.groups.get(index) {
+                Some(group) => group.placement,
+                None => return default(),
+            },
+            Target::Floating(position) => Placement::floating(position),
+        };
+        match placement {
+            Placement::Docked { side, size, .. } =>
+                Placement::Docked { side, size, collapsed: false }.area(screen_size),
+            floating => floating.area(screen_size),
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout() -> Layout {
+        Layout::new([
+            Group::new(Placement::docked(Side::Left), ["project", "components"]),
+            Group::new(Placement::floating(Vector2(-100.0, 100.0)), ["console"]),
+        ])
+    }
+
+    #[test]
+    fn moving_panels_between_groups() {
+        let mut layout = layout();
+        layout.move_panel(&"console".into(), Target::Side(Side::Left));
+        assert_eq!(layout.groups.len(), 1);
+        assert_eq!(layout.groups[0].tabs, ["project", "components", "console"]);
+        assert_eq!(layout.groups[0].active_tab().map(|tab| tab.as_str()), Some("console"));
+
+        layout.move_panel(&"project".into(), Target::Side(Side::Bottom));
+        assert_eq!(layout.docked(Side::Bottom), Some(1));
+        assert_eq!(layout.group_of("project"), Some(1));
+        assert_eq!(layout.groups[0].tabs, ["components", "console"]);
+
+        layout.move_panel(&"project".into(), Target::Floating(Vector2(0.0, 0.0)));
+        assert_eq!(layout.docked(Side::Bottom), None);
+        assert_eq!(layout.group_of("project"), Some(1));
+        assert!(layout.activate("components"));
+        assert_eq!(layout.groups[0].active, 0);
+    }
+
+    #[test]
+    fn layouts_are_normalized() {
+        let mut layout = Layout {
+            groups: vec![
+                Group::new(Placement::docked(Side::Right), ["a"]),
+                Group::new(Placement::docked(Side::Left), Vec::<ImString>::new()),
+                Group::new(Placement::docked(Side::Right), ["b"]),
+            ],
+        };
+        layout.groups[2].active = 5;
+        layout.normalize();
+        assert_eq!(layout.groups.len(), 1);
+        assert_eq!(layout.groups[0].tabs, ["a", "b"]);
+        assert_eq!(layout.groups[0].active, 0);
+    }
+
+    #[test]
+    fn drop_targets() {
+        let layout = layout();
+        let screen = Vector2(1000.0, 800.0);
+        assert_eq!(layout.drop_target(Vector2(-490.0, 0.0), screen), Target::Side(Side::Left));
+        assert_eq!(layout.drop_target(Vector2(490.0, 0.0), screen), Target::Side(Side::Right));
+        assert_eq!(layout.drop_target(Vector2(0.0, -390.0), screen), Target::Side(Side::Bottom));
+        assert_eq!(layout.drop_target(Vector2(-400.0, 0.0), screen), Target::Group(0));
+        assert_eq!(layout.drop_target(Vector2(0.0, 0.0), screen), Target::Group(1));
+        let floating = layout.drop_target(Vector2(300.0, 200.0), screen);
+        assert_eq!(floating, Target::Floating(Vector2(140.0, 200.0)));
+        let area = layout.target_area(Target::Side(Side::Right), screen);
+        assert_eq!(area, (Vector2(260.0, 400.0), Vector2(240.0, 800.0)));
+    }
+
+    #[test]
+    fn layout_serialization() {
+        let layout = layout();
+        let json = serde_json::to_string(&layout).unwrap();
+        assert_eq!(serde_json::from_str::<Layout>(&json).unwrap(), layout);
+    }
+}
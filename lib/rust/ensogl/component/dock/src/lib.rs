@@ -0,0 +1,647 @@
+//! A docking framework, arranging tool panels like debug consoles or documentation around the
+//! scene.
+//!
+//! Panels are registered in the [`Dock`] with [`Dock::add_panel`], and displayed according to the
+//! current [`Layout`]: grouped as tabs, either docked to a side of the screen in a
+//! [side panel](ensogl_side_panel::SidePanel), or floating in a
+//! [floating panel](ensogl_floating_panel::Panel). A panel can be moved to another group by
+//! dragging its tab: dropping it near a screen edge docks it there, dropping it over a group adds
+//! it to the group tabs, and dropping it elsewhere creates a new floating group.
+//!
+//! Layouts are named, like "editing" or "debugging", and switched with [`Frp::switch_layout`].
+//! When a [settings key](Frp::set_settings_key) is provided, every change of the current layout is
+//! stored as JSON in the application [settings store](ensogl_core::application::settings::Store),
+//! and the layout displayed last is restored when the key is set again.
+
+// === Standard Linter Configuration ===
+#![deny(non_ascii_idents)]
+#![warn(unsafe_code)]
+#![allow(clippy::bool_to_int_with_if)]
+#![allow(clippy::let_and_return)]
+// === Non-Standard Linter Configuration ===
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+use ensogl_core::display::shape::*;
+use ensogl_core::prelude::*;
+
+use enso_frp as frp;
+use ensogl_core::application::Application;
+use ensogl_core::control::io::mouse;
+use ensogl_core::display;
+use ensogl_core::display::scene::Layer;
+use ensogl_floating_panel as floating_panel;
+use ensogl_hardcoded_theme::component::dock as theme;
+use ensogl_side_panel::Side;
+use ensogl_side_panel::SidePanel;
+
+
+// ==============
+// === Export ===
+// ==============
+
+pub mod group;
+pub mod layout;
+
+pub use layout::Group;
+pub use layout::Layout;
+pub use layout::Placement;
+pub use layout::Target;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The distance the pointer has to move with a pressed tab to start dragging its panel.
+const DRAG_THRESHOLD: f32 = 4.0;
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl_core::define_endpoints_2! {
+    Input {
+        /// Replace the current layout.
+        set_layout (Layout),
+        /// Move the panel to the target, as if its tab was dragged there.
+        move_panel (ImString, Target),
+        /// Set the layout displayed after switching to the given name, if no layout was saved
+        /// under that name yet.
+        set_default_layout (ImString, Layout),
+        /// Switch to the named layout. The layout saved under this name is displayed if present,
+        /// otherwise the default one. If neither is available, the current layout is kept and
+        /// saved under the new name.
+        switch_layout (ImString),
+        /// Save the current layout under the given name, making it the current layout name.
+        save_layout_as (ImString),
+        /// Store the layouts in the application settings, under keys starting with the given
+        /// prefix. If the settings contain the name of the layout displayed last, it is restored.
+        set_settings_key (Option<ImString>),
+    }
+    Output {
+        layout (Layout),
+        layout_name (Option<ImString>),
+        /// The panel being dragged by its tab.
+        dragged_panel (Option<ImString>),
+    }
+}
+
+
+
+// ==================
+// === ViewEvents ===
+// ==================
+
+/// The events of the dynamically created group views, passed to the dock network.
+#[derive(Clone, CloneRef, Debug)]
+struct ViewEvents {
+    network:          frp::WeakNetwork,
+    tab_pressed:      frp::Any<ImString>,
+    /// The index of a floating group, with its new position and size.
+    floating_changed: frp::Any<(usize, Vector2, Vector2)>,
+}
+
+
+
+// ==============
+// === Layers ===
+// ==============
+
+/// The layers used by the dock, from the bottom to the top one.
+#[derive(Debug, Clone, CloneRef)]
+struct Layers {
+    docked:       Layer,
+    docked_text:  Layer,
+    floating:     Layer,
+    drop_preview: Layer,
+}
+
+impl Layers {
+    fn new(base_layer: &Layer) -> Self {
+        let docked = base_layer.create_sublayer("dock");
+        let docked_text = base_layer.create_sublayer("dock_text");
+        let floating = base_layer.create_sublayer("dock_floating");
+        let drop_preview = base_layer.create_sublayer("dock_drop_preview");
+        Self { docked, docked_text, floating, drop_preview }
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+/// A registered panel.
+#[derive(Clone, Debug)]
+struct Registered {
+    title:   ImString,
+    content: display::object::Instance,
+}
+
+/// A view of the group docked to a side of the screen.
+#[derive(Debug)]
+struct DockedView {
+    side:  Side,
+    panel: SidePanel,
+    group: group::Group,
+}
+
+/// A view of a floating group.
+#[derive(Debug)]
+struct FloatingView {
+    /// The index of the displayed group in the layout.
+    index: Rc<Cell<usize>>,
+    panel: floating_panel::Panel,
+    group: group::Group,
+}
+
+#[derive(Debug, display::Object)]
+struct Model {
+    app:             Application,
+    display_object:  display::object::Instance,
+    _layers:         Layers,
+    docked:          [DockedView; 3],
+    floating_panels: floating_panel::Manager,
+    floating:        RefCell<Vec<FloatingView>>,
+    drop_preview:    Rectangle,
+    events:          ViewEvents,
+    panels:          RefCell<HashMap<ImString, Registered>>,
+    layout:          RefCell<Layout>,
+    layout_name:     RefCell<Option<ImString>>,
+    default_layouts: RefCell<HashMap<ImString, Layout>>,
+    settings_key:    RefCell<Option<ImString>>,
+    screen_size:     Cell<Vector2>,
+    /// Set while the layout is applied to the views, to ignore the geometry changes they report.
+    applying:        Cell<bool>,
+}
+
+impl Model {
+    fn new(app: &Application, events: ViewEvents) -> Self {
+        let app = app.clone_ref();
+        let scene = &app.display.default_scene;
+        let display_object = display::object::Instance::new_named("Dock");
+        let layers = Layers::new(&scene.layers.panel);
+        let floating_panels = floating_panel::Manager::new(&app, &layers.floating);
+        let drop_preview = Rectangle();
+        layers.docked.add(&display_object);
+        layers.drop_preview.add(&drop_preview);
+        display_object.add_child(&floating_panels);
+        display_object.add_child(&drop_preview);
+        drop_preview.set_pointer_events(false);
+        let docked = [Side::Left, Side::Right, Side::Bottom].map(|side| {
+            let panel = SidePanel::new(&app);
+            let group = group::Group::new(&app);
+            panel.set_side(side);
+            panel.content().add_child(&group);
+            group.set_text_layer(&layers.docked_text);
+            DockedView { side, panel, group }
+        });
+        let floating = default();
+        let panels = default();
+        let layout = default();
+        let layout_name = default();
+        let default_layouts = default();
+        let settings_key = default();
+        let screen_size = default();
+        let applying = default();
+        Self {
+            app,
+            display_object,
+            _layers: layers,
+            docked,
+            floating_panels,
+            floating,
+            drop_preview,
+            events,
+            panels,
+            layout,
+            layout_name,
+            default_layouts,
+            settings_key,
+            screen_size,
+            applying,
+        }
+    }
+
+    fn set_screen_size(&self, size: Vector2) {
+        self.screen_size.set(size);
+        for docked in &self.docked {
+            docked.panel.set_xy(-size / 2.0);
+            docked.panel.set_container_size(size);
+        }
+    }
+
+
+    // === Views ===
+
+    /// Update the views to display the current layout. The views of floating groups are reused,
+    /// so a group view is not removed while handling its own events.
+    fn apply_layout(&self) {
+        let layout = self.layout.borrow().clone();
+        let panels = self.panels.borrow().clone();
+        self.applying.set(true);
+        for panel in panels.values() {
+            panel.content.unset_parent();
+        }
+        let show_group = |group: &Group, view: &group::Group| {
+            let title = |id: &ImString| panels.get(id).map_or(id, |panel| &panel.title).clone_ref();
+            let tabs = group.tabs.iter().map(|id| (id.clone_ref(), title(id))).collect_vec();
+            view.set_tabs(tabs);
+            view.set_active(group.active);
+            if let Some(panel) = group.active_tab().and_then(|id| panels.get(id)) {
+                view.content().add_child(&panel.content);
+            }
+            group.active_tab().map(title).unwrap_or_default()
+        };
+
+        for docked in &self.docked {
+            let group = layout.docked(docked.side).map(|index| &layout.groups[index]);
+            match group {
+                Some(group) => {
+                    if let Placement::Docked { size, collapsed, .. } = group.placement {
+                        docked.panel.set_size(size);
+                        docked.panel.set_collapsed(collapsed);
+                    }
+                    show_group(group, &docked.group);
+                    self.display_object.add_child(&docked.panel);
+                }
+                None => docked.panel.unset_parent(),
+            }
+        }
+
+        let floating_groups = layout.groups.iter().enumerate();
+        let floating_groups = floating_groups.filter(|(_, group)| group.placement.side().is_none());
+        let floating_groups = floating_groups.collect_vec();
+        let mut floating = self.floating.borrow_mut();
+        let kept = floating_groups.len().min(floating.len());
+        for removed in floating.drain(kept..) {
+            self.floating_panels.remove_panel(&removed.panel);
+        }
+        while floating.len() < floating_groups.len() {
+            floating.push(self.new_floating_view());
+        }
+        for (view, (index, group)) in floating.iter().zip(floating_groups) {
+            view.index.set(index);
+            if let Placement::Floating { position, size } = group.placement {
+                view.panel.set_position(Vector2(position.0, position.1));
+                view.panel.set_size(Vector2(size.0, size.1));
+            }
+            let title = show_group(group, &view.group);
+            view.panel.set_title(title);
+        }
+        self.applying.set(false);
+    }
+
+    fn new_floating_view(&self) -> FloatingView {
+        let index = Rc::new(Cell::new(0));
+        let panel = self.floating_panels.add_panel("", None);
+        let group = group::Group::new(&self.app);
+        panel.content().add_child(&group);
+        if let Some((_, text_layer)) = panel.layers() {
+            group.set_text_layer(&text_layer);
+        }
+        let content_size =
+            |size: &Vector2| Vector2(size.x, size.y - floating_panel::panel::TITLE_BAR_HEIGHT);
+        group.set_size(content_size(&panel.size.value()));
+        if let Some(network) = self.events.network.upgrade_or_warn() {
+            let events = &self.events;
+            let panel_network = panel.network();
+            let group_network = group.network();
+            frp::new_bridge_network! { [network, panel_network, group_network] dock_floating_view
+                group.set_size <+ panel.size.map(content_size);
+                events.tab_pressed <+ group.tab_pressed;
+                interaction_ended <- any(&panel.moving, &panel.resizing).on_false();
+                geometry <- all(&panel.position, &panel.size).sample(&interaction_ended);
+                events.floating_changed <+ geometry.map(f!([index]((position, size))
+                    (index.get(), *position, *size)
+                ));
+            }
+        }
+        FloatingView { index, panel, group }
+    }
+
+    fn show_drop_preview(&self, target: Target) {
+        let screen_size = self.screen_size.get();
+        let (top_left, size) = self.layout.borrow().target_area(target, screen_size);
+        self.drop_preview.set_xy(Vector2(top_left.x, top_left.y - size.y));
+        self.drop_preview.set_size(size);
+    }
+
+    fn hide_drop_preview(&self) {
+        self.drop_preview.set_size(Vector2(0.0, 0.0));
+    }
+
+
+    // === Layout Changes ===
+
+    fn set_layout(&self, mut layout: Layout) {
+        layout.normalize();
+        *self.layout.borrow_mut() = layout;
+        self.apply_layout();
+    }
+
+    fn activate(&self, panel: &str) {
+        let activated = self.layout.borrow_mut().activate(panel);
+        if activated {
+            self.apply_layout();
+        }
+    }
+
+    fn move_panel(&self, panel: &ImString, target: Target) {
+        self.layout.borrow_mut().move_panel(panel, target);
+        self.apply_layout();
+    }
+
+    fn drop_target(&self, position: Vector2) -> Target {
+        self.layout.borrow().drop_target(position, self.screen_size.get())
+    }
+
+    fn set_docked_geometry(&self, side: Side, size: f32, collapsed: bool) {
+        if !self.applying.get() {
+            let mut layout = self.layout.borrow_mut();
+            if let Some(index) = layout.docked(side) {
+                layout.groups[index].placement = Placement::Docked { side, size, collapsed };
+            }
+        }
+    }
+
+    fn set_floating_geometry(&self, index: usize, position: Vector2, size: Vector2) {
+        if !self.applying.get() {
+            let mut layout = self.layout.borrow_mut();
+            let group = layout.groups.get_mut(index);
+            if let Some(group) = group.filter(|group| group.placement.side().is_none()) {
+                let position = (position.x, position.y);
+                let size = (size.x, size.y);
+                group.placement = Placement::Floating { position, size };
+            }
+        }
+    }
+
+
+    // === Persistence ===
+
+    fn layout_key(&self, name: &str) -> Option<String> {
+        self.settings_key.borrow().as_ref().map(|key| format!("{key}.layouts.{name}"))
+    }
+
+    fn current_layout_key(&self) -> Option<String> {
+        self.settings_key.borrow().as_ref().map(|key| format!("{key}.current"))
+    }
+
+    fn set_settings_key(&self, key: Option<ImString>) {
+        *self.settings_key.borrow_mut() = key;
+        let current_key = self.current_layout_key();
+        let current = current_key.and_then(|key| self.app.settings.get::<ImString>(&key));
+        if let Some(name) = current {
+            self.switch_layout(&name);
+        }
+    }
+
+    fn set_default_layout(&self, name: &ImString, layout: &Layout) {
+        self.default_layouts.borrow_mut().insert(name.clone_ref(), layout.clone());
+    }
+
+    fn switch_layout(&self, name: &ImString) {
+        let saved = self.layout_key(name).and_then(|key| self.app.settings.get::<Layout>(&key));
+        let default_layout = || self.default_layouts.borrow().get(name).cloned();
+        *self.layout_name.borrow_mut() = Some(name.clone_ref());
+        if let Some(layout) = saved.or_else(default_layout) {
+            self.set_layout(layout);
+        }
+    }
+
+    fn save_layout_as(&self, name: &ImString) {
+        *self.layout_name.borrow_mut() = Some(name.clone_ref());
+    }
+
+    /// Store the current layout under its name, and remember the name as the one displayed last.
+    fn save(&self) {
+        let name = self.layout_name.borrow().clone();
+        let Some(name) = name else { return };
+        if let (Some(layout_key), Some(current_key)) =
+            (self.layout_key(&name), self.current_layout_key())
+        {
+            self.app.settings.set(&layout_key, &*self.layout.borrow());
+            self.app.settings.set(&current_key, &name);
+        }
+    }
+}
+
+
+
+// ============
+// === Dock ===
+// ============
+
+/// The docking framework. See the crate docs to learn more.
+#[derive(Clone, CloneRef, Debug, Deref, display::Object)]
+pub struct Dock {
+    #[deref]
+    frp:   Frp,
+    #[display_object]
+    model: Rc<Model>,
+}
+
+impl Dock {
+    /// Constructor. The dock is displayed in the `panel` layer of the scene.
+    pub fn new(app: &Application) -> Self {
+        let frp = Frp::new();
+        let network = frp.network();
+        frp::extend! { network
+            tab_pressed <- any_mut::<ImString>();
+            floating_changed <- any_mut::<(usize, Vector2, Vector2)>();
+        }
+        let network = network.downgrade();
+        let events = ViewEvents { network, tab_pressed, floating_changed };
+        let model = Rc::new(Model::new(app, events));
+        Self { frp, model }.init(app)
+    }
+
+    fn init(self, app: &Application) -> Self {
+        let network = self.frp.network();
+        let input = &self.frp.private.input;
+        let out = &self.frp.private.output;
+        let model = &self.model;
+        let events = &model.events;
+        let scene = &app.display.default_scene;
+        let style = StyleWatchFrp::new(&scene.style_sheet);
+        let preview_color = style.get_color(theme::drop_preview);
+        let mouse_position = &scene.mouse.frp_deprecated.position;
+        let pointer_up = scene.on_event::<mouse::Up>();
+
+        frp::extend! { network
+            init <- source_();
+            preview_color_changed <- any_(&init, &preview_color);
+            eval_ preview_color_changed ([model, preview_color]
+                model.drop_preview.set_color(preview_color.value())
+            );
+
+            screen_size <- scene.frp.shape.map(|shape| Vector2(shape.width, shape.height));
+            eval screen_size ((size) model.set_screen_size(*size));
+            layout_changed <- any_mut::<()>();
+
+
+            // === Layouts ===
+
+            eval input.set_default_layout (((name, layout)) model.set_default_layout(name, layout));
+            eval input.set_settings_key ((key) model.set_settings_key(key.clone()));
+            eval input.switch_layout ((name) model.switch_layout(name));
+            eval input.save_layout_as ((name) model.save_layout_as(name));
+            eval input.set_layout ((layout) model.set_layout(layout.clone()));
+            layout_changed <+_ input.set_settings_key;
+            layout_changed <+_ input.switch_layout;
+            layout_changed <+_ input.save_layout_as;
+            layout_changed <+_ input.set_layout;
+
+
+            // === Tabs ===
+
+            eval events.tab_pressed ((panel) model.activate(panel));
+            layout_changed <+_ events.tab_pressed;
+            eval events.floating_changed (((index, position, size))
+                model.set_floating_geometry(*index, *position, *size)
+            );
+            layout_changed <+_ events.floating_changed;
+
+
+            // === Dragging ===
+
+            press_start <- all(mouse_position, &events.tab_pressed).sample(&events.tab_pressed);
+            tab_held <- bool(&pointer_up, &events.tab_pressed);
+            drag_distance <- any(...);
+            drag_distance <+ events.tab_pressed.constant(0.0);
+            drag_distance <+ mouse_position.gate(&tab_held).map2(&press_start,
+                |position, (start, _)| (position - start).norm()
+            );
+            drag_started <- drag_distance.map(|d| *d > DRAG_THRESHOLD).on_change().on_true();
+            is_dragging <- out.dragged_panel.map(|panel| panel.is_some());
+            dropped_panel <- out.dragged_panel.sample(&pointer_up).filter_map(|p| p.clone());
+            drag_ended <- pointer_up.gate(&is_dragging);
+            dragged_panel <- any(...);
+            dragged_panel <+ press_start.sample(&drag_started).map(|(_, p)| Some(p.clone()));
+            dragged_panel <+ drag_ended.constant(None);
+            out.dragged_panel <+ dragged_panel.on_change();
+
+            preview_target <- mouse_position.gate(&is_dragging).map(f!((p) model.drop_target(*p)));
+            eval preview_target ((target) model.show_drop_preview(*target));
+            eval_ drag_ended (model.hide_drop_preview());
+            dropped <- dropped_panel.map2(mouse_position,
+                f!((panel, position) (panel.clone(), model.drop_target(*position)))
+            );
+            move_panel <- any(&input.move_panel, &dropped);
+            eval move_panel (((panel, target)) model.move_panel(panel, *target));
+            layout_changed <+_ move_panel;
+        }
+
+        for docked in &model.docked {
+            let side = docked.side;
+            let panel = &docked.panel;
+            let group = &docked.group;
+            frp::extend! { network
+                group_size <- all_with(&panel.size, &screen_size, move |size, screen| match side {
+                    Side::Bottom => Vector2(screen.x, *size),
+                    Side::Left | Side::Right => Vector2(*size, screen.y),
+                });
+                group.set_size <+ group_size;
+                eval group_size ((size) group.set_y(size.y));
+                resize_ended <- panel.resizing.on_false();
+                geometry_changed <- any_(&resize_ended, &panel.collapsed);
+                geometry <- all(&panel.size, &panel.collapsed).sample(&geometry_changed);
+                eval geometry (((size, collapsed))
+                    model.set_docked_geometry(side, *size, *collapsed)
+                );
+                layout_changed <+_ geometry;
+            }
+        }
+
+        frp::extend! { network
+            eval_ layout_changed (model.save());
+            layout <- layout_changed.map(f_!(model.layout.borrow().clone()));
+            layout_name <- layout_changed.map(f_!(model.layout_name.borrow().clone()));
+            out.layout <+ layout.on_change();
+            out.layout_name <+ layout_name.on_change();
+        }
+        let shape = scene.frp.shape.value();
+        model.set_screen_size(Vector2(shape.width, shape.height));
+        init.emit(());
+        self
+    }
+
+    /// Register a panel. It is displayed when its identifier is in the current layout. The content
+    /// origin is placed in the top-left corner of the area below the tab bar.
+    ///
+    /// A layout may contain identifiers of panels which are not registered; their tabs display the
+    /// identifier instead of a title.
+    pub fn add_panel(
+        &self,
+        id: impl Into<ImString>,
+        title: impl Into<ImString>,
+        content: &impl display::Object,
+    ) {
+        let content = content.display_object().clone();
+        let panel = Registered { title: title.into(), content };
+        self.model.panels.borrow_mut().insert(id.into(), panel);
+        self.model.apply_layout();
+    }
+
+    /// Unregister the panel, removing its content from the dock. The layout is not changed.
+    pub fn remove_panel(&self, id: impl Into<ImString>) {
+        let removed = self.model.panels.borrow_mut().remove(&id.into());
+        if let Some(panel) = removed {
+            panel.content.unset_parent();
+        }
+        self.model.apply_layout();
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layouts_are_switched_and_persisted() {
+        let app = Application::new("root");
+        let dock = Dock::new(&app);
+        dock.add_panel("project", "Project", &display::object::Instance::new());
+        dock.add_panel("console", "Console", &display::object::Instance::new());
+        let editing =
+            Layout::new([Group::new(Placement::docked(Side::Left), ["project", "console"])]);
+        let debugging = Layout::new([Group::new(Placement::docked(Side::Bottom), ["console"])]);
+        dock.set_settings_key(Some("test.dock".into()));
+        dock.set_default_layout("editing", editing.clone());
+        dock.set_default_layout("debugging", debugging.clone());
+
+        dock.switch_layout("editing");
+        assert_eq!(dock.layout.value(), editing);
+        dock.move_panel("console", Target::Side(Side::Right));
+        let edited = dock.layout.value();
+        assert_eq!(edited.docked(Side::Right), Some(1));
+        assert_eq!(edited.groups[1].tabs, ["console"]);
+
+        dock.switch_layout("debugging");
+        assert_eq!(dock.layout.value(), debugging);
+
+        let restored = Dock::new(&app);
+        restored.set_settings_key(Some("test.dock".into()));
+        assert_eq!(restored.layout_name.value(), Some("debugging".into()));
+        assert_eq!(restored.layout.value(), debugging);
+        restored.switch_layout("editing");
+        assert_eq!(restored.layout.value(), edited);
+    }
+}
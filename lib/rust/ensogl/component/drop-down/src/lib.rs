@@ -19,6 +19,7 @@ use ensogl_core::application::shortcut;
 use ensogl_core::application::Application;
 use ensogl_core::application::View;
 use ensogl_core::data::color::Lcha;
+use ensogl_core::display::scene::layer::WeakLayer;
 use ensogl_core::display::shape::StyleWatchFrp;
 use ensogl_core::frp;
 use ensogl_gui_component::component;
@@ -74,6 +75,10 @@ ensogl_core::define_endpoints_2! { <T: (DropdownValue)>
         set_color(Lcha),
         /// The dropdown initially is not open. This event can open or close the dropdown.
         set_open(bool),
+        /// Set the layer the dropdown is moved to while open, like an overlay layer above the
+        /// surrounding content. After closing, the dropdown returns to its previous layer. If
+        /// [`None`], the dropdown is not moved. The default value is [`None`].
+        set_open_layer(Option<WeakLayer>),
         /// Set maximum width and height of the open dropdown. If the content is larger, it will be
         /// scrollable and/or ellipsis will be applied to labels.
         set_max_open_size(Vector2),
@@ -171,6 +176,12 @@ impl<T: DropdownValue> Frp<T> {
             open_anim.target <+ input.set_open.map(|open| if *open { 1.0 } else { 0.0 });
             output.is_open <+ input.set_open;
 
+            // The dropdown stays on the open layer until the closing animation finishes.
+            is_shown <- open_anim.value.map(|progress| *progress > 0.0).on_change();
+            on_open_layer <- all_with(&input.set_open, &is_shown, |open, shown| *open || *shown);
+            layer_update <- all(on_open_layer, input.set_open_layer);
+            eval layer_update(((open, layer)) model.update_layer(*open, layer));
+
             grid_width <- model.grid.content_size.map(|s| s.x).on_change();
             max_height <- input.set_max_open_size.map(|s| s.y);
             max_width <- input.set_max_open_size.map(|s| s.x);
@@ -246,6 +257,18 @@ impl<T: DropdownValue> Frp<T> {
             ).on_change();
             output.user_select_action <+_ model.grid.entry_accepted;
 
+            // === Accessibility ===
+            accessible_option_activated <- source::<usize>();
+            options_changed <- any_(&ranges_to_update, &selection_changed, &input.set_open);
+            options_open <- input.set_open.sample(&options_changed);
+            eval options_open ([model, accessible_option_activated](open)
+                model.update_accessible_options(*open, &accessible_option_activated));
+            accessible_entry_activated <- accessible_option_activated.filter_map(
+                f!((position) model.accessible_entry_index(*position))
+            );
+            model.grid.select_entry <+ accessible_entry_activated.map(|index| Some((*index, 0)));
+            model.grid.accept_selected_entry <+_ accessible_entry_activated;
+
             // === Keyboard navigation ===
             model.grid.accept_selected_entry <+ input.toggle_focused_entry;
             model.grid.move_selection_up <+ input.focus_previous_entry;
@@ -278,6 +301,7 @@ impl<T: DropdownValue> component::Frp<Model<T>> for Frp<T> {
         frp.set_multiselect(false);
         frp.set_max_cached_entries(DEFAULT_MAX_ENTRIES);
         frp.set_open(false);
+        frp.set_open_layer(None);
         frp.allow_deselect_all(false);
     }
 
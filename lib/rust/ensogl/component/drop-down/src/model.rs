@@ -10,6 +10,8 @@ use ensogl_core::application::Application;
 use ensogl_core::data::color;
 use ensogl_core::data::color::Lcha;
 use ensogl_core::display;
+use ensogl_core::display::scene::layer;
+use ensogl_core::gui::accessibility;
 use ensogl_grid_view as grid_view;
 use ensogl_gui_component::component;
 
@@ -44,12 +46,18 @@ const OPEN_ANIMATION_OFFSET: f32 = OPEN_ANIMATION_SCALE - 1.001;
 #[derive(Derivative, CloneRef, Debug, display::Object)]
 #[derivative(Clone(bound = ""))]
 pub struct Model<T> {
-    display_object:   display::object::Instance,
-    background:       Rectangle,
-    pub grid:         Grid,
-    selected_entries: Rc<RefCell<HashSet<T>>>,
-    cache:            Rc<RefCell<EntryCache<T>>>,
-    expected_indices: Rc<RefCell<HashSet<usize>>>,
+    display_object:     display::object::Instance,
+    background:         Rectangle,
+    pub grid:           Grid,
+    selected_entries:   Rc<RefCell<HashSet<T>>>,
+    cache:              Rc<RefCell<EntryCache<T>>>,
+    expected_indices:   Rc<RefCell<HashSet<usize>>>,
+    accessibility:      accessibility::Node,
+    /// The accessibility nodes of the cached entries, in the order of their indices.
+    accessible_options: Rc<RefCell<Vec<accessibility::Node>>>,
+    /// The entry indices of the [`Self::accessible_options`].
+    accessible_indices: Rc<RefCell<Vec<usize>>>,
+    layer_promotion:    layer::Promotion,
 }
 
 impl<T> component::Model for Model<T> {
@@ -63,6 +71,8 @@ impl<T> component::Model for Model<T> {
 
         let background = default();
         let grid = Grid::new(app);
+        let scene = &app.display.default_scene;
+        let accessibility = accessibility::Node::new(scene, accessibility::Role::ListBox);
         display_object.add_child(&background);
         display_object.add_child(&grid);
 
@@ -72,6 +82,7 @@ impl<T> component::Model for Model<T> {
         grid.set_entries_params(entries_params);
         grid.scroll_frp().set_corner_radius(inner_corners_radius);
         grid.set_entries_size(Vector2(min_width, ENTRY_HEIGHT));
+        let layer_promotion = layer::Promotion::new(&display_object);
 
         Model {
             background,
@@ -80,6 +91,10 @@ impl<T> component::Model for Model<T> {
             selected_entries: default(),
             cache: default(),
             expected_indices: default(),
+            accessibility,
+            accessible_options: default(),
+            accessible_indices: default(),
+            layer_promotion,
         }
     }
 }
@@ -285,10 +300,54 @@ impl<T: DropdownValue> Model<T> {
         }
     }
 
+    /// Mirror the cached entries into the accessibility tree, reusing the existing nodes, so the
+    /// focused one keeps the focus. The entries are mirrored only while the dropdown is open.
+    /// Activating the option node emits its position, which can be converted to the entry index
+    /// with [`Self::accessible_entry_index`].
+    pub fn update_accessible_options(&self, open: bool, activated: &frp::Source<usize>) {
+        let cache = self.cache.borrow();
+        let selection = self.selected_entries.borrow();
+        let mut indices = if open { cache.indices() } else { default() };
+        indices.sort_unstable();
+        let mut options = self.accessible_options.borrow_mut();
+        options.truncate(indices.len());
+        while options.len() < indices.len() {
+            let option = self.accessibility.add_child(accessibility::Role::Option);
+            let position = options.len();
+            let network = option.network();
+            frp::extend! { network
+                eval_ option.activated (activated.emit(position));
+            }
+            options.push(option);
+        }
+        for (option, index) in options.iter().zip(&indices) {
+            if let Some(entry) = cache.get(*index) {
+                option.set_label(entry.label());
+                option.set_selected(Some(selection.contains(entry)));
+            }
+        }
+        *self.accessible_indices.borrow_mut() = indices;
+    }
+
+    /// The index of the entry mirrored by the accessibility option node at the given position.
+    pub fn accessible_entry_index(&self, position: usize) -> Option<usize> {
+        self.accessible_indices.borrow().get(position).copied()
+    }
+
     /// Set the background color of the dropdown.
     pub fn set_color(&self, color: Lcha) {
         self.background.color.set(color::Rgba::from(color).into());
     }
+
+    /// Move the dropdown to the open layer, or return it to its previous layer if the dropdown is
+    /// closed or the open layer is not set.
+    pub fn update_layer(&self, open: bool, open_layer: &Option<layer::WeakLayer>) {
+        let open_layer = open_layer.as_ref().and_then(|layer| layer.upgrade());
+        match open_layer.filter(|_| open) {
+            Some(layer) => self.layer_promotion.promote(&layer),
+            None => self.layer_promotion.demote(),
+        }
+    }
 }
 
 type Grid = grid_view::scrollable::SelectableGridView<Entry>;
@@ -348,4 +407,8 @@ impl<T> EntryCache<T> {
     fn contains_key(&self, position: usize) -> bool {
         self.position_to_entry.contains_key(&position)
     }
+
+    fn indices(&self) -> Vec<usize> {
+        self.position_to_entry.keys().copied().collect()
+    }
 }
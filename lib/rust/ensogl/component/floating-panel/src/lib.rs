@@ -0,0 +1,170 @@
+//! Window-like panels floating over the scene, for tools like the performance monitor, theme
+//! editor, or debug consoles.
+//!
+//! A [`Panel`] is moved by dragging its title bar, and resized by dragging its edges. While moved,
+//! it snaps to the screen edges. When a [settings key](panel::Frp::set_settings_key) is provided,
+//! the panel position and size are remembered in the application
+//! [settings store](ensogl_core::application::settings::Store).
+//!
+//! The [`Manager`] creates panels, places each of them in a separate layer, and brings a panel to
+//! front when it is pressed. It also keeps the panels informed about the screen size.
+
+// === Standard Linter Configuration ===
+#![deny(non_ascii_idents)]
+#![warn(unsafe_code)]
+#![allow(clippy::bool_to_int_with_if)]
+#![allow(clippy::let_and_return)]
+// === Non-Standard Linter Configuration ===
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+use ensogl_core::prelude::*;
+
+use enso_frp as frp;
+use ensogl_core::application::Application;
+use ensogl_core::display;
+use ensogl_core::display::scene::Layer;
+
+
+// ==============
+// === Export ===
+// ==============
+
+pub mod panel;
+
+pub use panel::Panel;
+
+
+
+// ===============
+// === Manager ===
+// ===============
+
+#[derive(Debug)]
+struct Model {
+    app:            Application,
+    display_object: display::object::Instance,
+    layer:          Layer,
+    /// The panels ordered from the bottom to the top one.
+    panels:         RefCell<Vec<Panel>>,
+}
+
+impl Model {
+    fn bring_to_front(&self, panel: &display::object::Instance) {
+        let mut panels = self.panels.borrow_mut();
+        let Some(index) = panels.iter().position(|p| p.display_object() == panel) else {
+            return;
+        };
+        let panel = panels.remove(index);
+        panels.push(panel);
+        let layers = panels.iter().filter_map(|panel| panel.layers()).collect_vec();
+        let layers =
+            layers.iter().flat_map(|(layer, text_layer)| [layer, text_layer]).collect_vec();
+        self.layer.set_sublayers(&layers);
+    }
+}
+
+/// The manager of floating panels. See the crate docs to learn more.
+#[derive(Clone, CloneRef, Debug, display::Object)]
+pub struct Manager {
+    network: frp::Network,
+    #[display_object]
+    model:   Rc<Model>,
+}
+
+impl Manager {
+    /// Constructor. The panels are displayed in a sublayer of the given layer, usually
+    /// `scene.layers.panel`, which does not move with the scene camera.
+    pub fn new(app: &Application, parent_layer: &Layer) -> Self {
+        let network = frp::Network::new("floating_panel::Manager");
+        let app = app.clone_ref();
+        let display_object = display::object::Instance::new_named("FloatingPanels");
+        let layer = parent_layer.create_sublayer("floating_panels");
+        layer.add(&display_object);
+        let panels = default();
+        let model = Rc::new(Model { app, display_object, layer, panels });
+        Self { network, model }
+    }
+
+    /// Create a new panel with the given title and bring it to front. If `settings_key` is
+    /// provided, the panel position and size are restored from and saved to the application
+    /// settings.
+    pub fn add_panel(&self, title: impl Into<ImString>, settings_key: Option<&str>) -> Panel {
+        let model = &self.model;
+        let panel = Panel::new(&model.app);
+        let layer = model.layer.create_sublayer("floating_panel");
+        let text_layer = model.layer.create_sublayer("floating_panel_text");
+        panel.set_layers(&layer, &text_layer);
+        model.display_object.add_child(&panel);
+        model.panels.borrow_mut().push(panel.clone_ref());
+
+        let network = &self.network;
+        let scene = &model.app.display.default_scene;
+        let weak_model = Rc::downgrade(model);
+        let weak_panel = panel.display_object().downgrade();
+        frp::extend! { network
+            panel.set_screen_size <+ scene.frp.shape.map(|shape| Vector2(shape.width, shape.height));
+            eval_ panel.activated ([weak_model, weak_panel]
+                if let (Some(model), Some(panel)) = (weak_model.upgrade(), weak_panel.upgrade()) {
+                    model.bring_to_front(&panel);
+                }
+            );
+        }
+        let shape = scene.frp.shape.value();
+        panel.set_screen_size(Vector2(shape.width, shape.height));
+        panel.set_title(title.into());
+        panel.set_settings_key(settings_key.map(ImString::from));
+        model.bring_to_front(panel.display_object());
+        panel
+    }
+
+    /// Remove the panel from the manager and the scene.
+    pub fn remove_panel(&self, panel: &Panel) {
+        self.model.panels.borrow_mut().retain(|p| p.display_object() != panel.display_object());
+        panel.unset_parent();
+        if let Some((layer, text_layer)) = panel.layers() {
+            self.model.layer.remove_sublayer(&layer);
+            self.model.layer.remove_sublayer(&text_layer);
+        }
+    }
+
+    /// Bring the panel to front.
+    pub fn bring_to_front(&self, panel: &Panel) {
+        self.model.bring_to_front(panel.display_object());
+    }
+
+    /// The managed panels, ordered from the bottom to the top one.
+    pub fn panels(&self) -> Vec<Panel> {
+        self.model.panels.borrow().clone()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pressed_panel_is_brought_to_front() {
+        let app = Application::new("root");
+        let manager = Manager::new(&app, &app.display.default_scene.layers.panel);
+        let first = manager.add_panel("First", None);
+        let second = manager.add_panel("Second", None);
+        let order = || manager.panels().iter().map(|p| p.display_object().clone()).collect_vec();
+        assert_eq!(order(), [first.display_object().clone(), second.display_object().clone()]);
+        manager.bring_to_front(&first);
+        assert_eq!(order(), [second.display_object().clone(), first.display_object().clone()]);
+        manager.remove_panel(&second);
+        assert_eq!(order(), [first.display_object().clone()]);
+    }
+}
@@ -0,0 +1,423 @@
+//! A single floating panel, moved by its title bar and resized by its edges.
+
+use ensogl_core::display::shape::*;
+use ensogl_core::prelude::*;
+
+use enso_frp as frp;
+use ensogl_core::application::Application;
+use ensogl_core::control::io::mouse;
+use ensogl_core::data::color;
+use ensogl_core::display;
+use ensogl_core::display::scene::Layer;
+use ensogl_hardcoded_theme::component::floating_panel as theme;
+use ensogl_text as text;
+use serde::Deserialize;
+use serde::Serialize;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The height of the title bar.
+pub const TITLE_BAR_HEIGHT: f32 = 24.0;
+/// The width of the edges which can be dragged to resize the panel.
+pub const EDGE_WIDTH: f32 = 6.0;
+/// The distance from a screen edge at which a moved panel snaps to it.
+pub const SNAP_DISTANCE: f32 = 12.0;
+/// The horizontal padding of the title.
+const TITLE_PADDING: f32 = 8.0;
+/// The font size of the title.
+const TITLE_FONT_SIZE: f32 = 12.0;
+/// The default size of a panel.
+const DEFAULT_SIZE: Vector2 = Vector2(320.0, 240.0);
+/// The default minimum size of a panel.
+const DEFAULT_MIN_SIZE: Vector2 = Vector2(120.0, TITLE_BAR_HEIGHT + 40.0);
+
+
+
+// ============
+// === Edge ===
+// ============
+
+/// An edge of the panel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum Edge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl Edge {
+    /// All edges.
+    pub const ALL: [Edge; 4] = [Edge::Left, Edge::Right, Edge::Top, Edge::Bottom];
+}
+
+/// Compute the panel position and size after dragging the edge by `delta`. The position is the
+/// top-left corner of the panel. The edge opposite to the dragged one stays in place, also when
+/// the size would be smaller than `min_size`.
+pub fn resized(
+    edge: Edge,
+    position: Vector2,
+    size: Vector2,
+    delta: Vector2,
+    min_size: Vector2,
+) -> (Vector2, Vector2) {
+    match edge {
+        Edge::Right => (position, Vector2((size.x + delta.x).max(min_size.x), size.y)),
+        Edge::Bottom => (position, Vector2(size.x, (size.y - delta.y).max(min_size.y))),
+        Edge::Left => {
+            let width = (size.x - delta.x).max(min_size.x);
+            (Vector2(position.x + size.x - width, position.y), Vector2(width, size.y))
+        }
+        Edge::Top => {
+            let height = (size.y + delta.y).max(min_size.y);
+            (Vector2(position.x, position.y - size.y + height), Vector2(size.x, height))
+        }
+    }
+}
+
+/// Snap the panel position to the edges of the screen of the given size, if the panel is closer
+/// to them than [`SNAP_DISTANCE`]. The screen origin is in its center, and the position is the
+/// top-left corner of the panel.
+pub fn snapped(position: Vector2, size: Vector2, screen_size: Vector2) -> Vector2 {
+    let half_screen = screen_size / 2.0;
+    let snap = |value: f32, target: f32| (value - target).abs() < SNAP_DISTANCE;
+    let mut position = position;
+    if snap(position.x, -half_screen.x) {
+        position.x = -half_screen.x;
+    } else if snap(position.x + size.x, half_screen.x) {
+        position.x = half_screen.x - size.x;
+    }
+    if snap(position.y, half_screen.y) {
+        position.y = half_screen.y;
+    } else if snap(position.y - size.y, -half_screen.y) {
+        position.y = size.y - half_screen.y;
+    }
+    position
+}
+
+
+
+// =================
+// === Persisted ===
+// =================
+
+/// The panel geometry stored in the settings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Persisted {
+    position: (f32, f32),
+    size:     (f32, f32),
+}
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl_core::define_endpoints_2! {
+    Input {
+        set_title (ImString),
+        /// Set the position of the top-left corner of the panel.
+        set_position (Vector2),
+        set_size (Vector2),
+        set_min_size (Vector2),
+        /// Set the size of the screen, to which edges the moved panel snaps. The screen origin
+        /// is in its center.
+        set_screen_size (Vector2),
+        /// Remember the panel position and size in the application settings under the given key.
+        /// If the settings contain the geometry stored under this key, it is restored.
+        set_settings_key (Option<ImString>),
+    }
+    Output {
+        position (Vector2),
+        size (Vector2),
+        /// Emitted when the panel is pressed. The [`Manager`](crate::Manager) brings such panel to
+        /// front.
+        activated (),
+        /// True while the user drags the title bar.
+        moving (bool),
+        /// True while the user drags one of the edges.
+        resizing (bool),
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+#[derive(Debug, display::Object)]
+struct Model {
+    display_object: display::object::Instance,
+    background:     Rectangle,
+    title_bar:      Rectangle,
+    title:          text::Text,
+    edges:          [Rectangle; 4],
+    content:        display::object::Instance,
+    layer:          RefCell<Option<(Layer, Layer)>>,
+}
+
+impl Model {
+    fn new(app: &Application) -> Self {
+        let display_object = display::object::Instance::new_named("FloatingPanel");
+        let background = Rectangle();
+        let title_bar = Rectangle();
+        let title = app.new_view::<text::Text>();
+        let edges = [Rectangle(), Rectangle(), Rectangle(), Rectangle()];
+        let content = display::object::Instance::new_named("content");
+        display_object.add_child(&background);
+        display_object.add_child(&title_bar);
+        display_object.add_child(&title);
+        display_object.add_child(&content);
+        for edge in &edges {
+            edge.set_color(INVISIBLE_HOVER_COLOR);
+            display_object.add_child(edge);
+        }
+        title.set_property_default(text::Size(TITLE_FONT_SIZE));
+        title.set_xy(Vector2(TITLE_PADDING, -(TITLE_BAR_HEIGHT - TITLE_FONT_SIZE) / 2.0));
+        let layer = default();
+        Self { display_object, background, title_bar, title, edges, content, layer }
+    }
+
+    fn edge(&self, edge: Edge) -> &Rectangle {
+        &self.edges[edge as usize]
+    }
+
+    fn update_layout(&self, position: Vector2, size: Vector2) {
+        self.display_object.set_xy(position);
+        self.background.set_size(size);
+        self.background.set_y(-size.y);
+        self.title_bar.set_size(Vector2(size.x, TITLE_BAR_HEIGHT));
+        self.title_bar.set_y(-TITLE_BAR_HEIGHT);
+        self.title.set_view_width(Some(size.x - 2.0 * TITLE_PADDING));
+        self.content.set_y(-TITLE_BAR_HEIGHT);
+        let half_edge = EDGE_WIDTH / 2.0;
+        for edge in Edge::ALL {
+            let shape = self.edge(edge);
+            let (edge_position, edge_size) = match edge {
+                Edge::Left => (Vector2(-half_edge, -size.y), Vector2(EDGE_WIDTH, size.y)),
+                Edge::Right => (Vector2(size.x - half_edge, -size.y), Vector2(EDGE_WIDTH, size.y)),
+                Edge::Top => (Vector2(0.0, -half_edge), Vector2(size.x, EDGE_WIDTH)),
+                Edge::Bottom => (Vector2(0.0, -size.y - half_edge), Vector2(size.x, EDGE_WIDTH)),
+            };
+            shape.set_xy(edge_position);
+            shape.set_size(edge_size);
+        }
+    }
+
+    fn set_layers(&self, layer: &Layer, text_layer: &Layer) {
+        layer.add(&self.display_object);
+        text_layer.add(&self.title);
+        *self.layer.borrow_mut() = Some((layer.clone_ref(), text_layer.clone_ref()));
+    }
+}
+
+
+
+// =============
+// === Panel ===
+// =============
+
+/// A floating panel with a title bar. The panels are usually created by the
+/// [`Manager`](crate::Manager), which sets up their layers. See the crate docs to learn more.
+#[derive(Clone, CloneRef, Debug, Deref, display::Object)]
+pub struct Panel {
+    #[deref]
+    frp:   Frp,
+    #[display_object]
+    model: Rc<Model>,
+}
+
+impl Panel {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let frp = Frp::new();
+        let model = Rc::new(Model::new(app));
+        Self { frp, model }.init(app).init_inputs()
+    }
+
+    fn init(self, app: &Application) -> Self {
+        let network = self.frp.network();
+        let input = &self.frp.private.input;
+        let out = &self.frp.private.output;
+        let model = &self.model;
+        let settings = &app.settings;
+        let scene = &app.display.default_scene;
+        let style = StyleWatchFrp::new(&scene.style_sheet);
+        let background = style.get_color(theme::background);
+        let title_bar = style.get_color(theme::title_bar);
+        let title_color = style.get_color(theme::title);
+        let mouse_position = &scene.mouse.frp_deprecated.position;
+        let pointer_down = model.display_object.on_event::<mouse::Down>();
+        let title_down = model.title_bar.on_event::<mouse::Down>();
+        let pointer_up = scene.on_event::<mouse::Up>();
+        let edge_down = Edge::ALL.map(|edge| model.edge(edge).on_event::<mouse::Down>());
+        let [left_down, right_down, top_down, bottom_down] = edge_down;
+
+        frp::extend! { network
+
+            // === Style ===
+
+            init <- source_();
+            colors_changed <- any_(&init, &background, &title_bar);
+            eval_ colors_changed ([model, background, title_bar] {
+                model.background.set_color(background.value());
+                model.title_bar.set_color(title_bar.value());
+            });
+            title_color_changed <- any_(&init, &title_color);
+            model.title.set_property_default <+ title_color_changed.map(
+                f_!([title_color] Some(color::Lcha::from(title_color.value()).into()))
+            );
+            model.title.set_content <+ input.set_title;
+
+
+            // === Persistence ===
+
+            settings_key <- input.set_settings_key.sampler();
+            persisted <- input.set_settings_key.filter_map(f!([settings](key) {
+                key.as_ref().and_then(|key| settings.get::<Persisted>(key))
+            }));
+            persisted_position <- persisted.map(|p| Vector2(p.position.0, p.position.1));
+            persisted_size <- persisted.map(|p| Vector2(p.size.0, p.size.1));
+
+
+            // === Activation ===
+
+            primary_down <- pointer_down.map(|e| e.button() == mouse::PrimaryButton).on_true();
+            out.activated <+ primary_down;
+
+
+            // === Moving ===
+
+            move_started <- title_down.map(|e| e.button() == mouse::PrimaryButton).on_true();
+            move_ended <- pointer_up.gate(&out.moving);
+            out.moving <+ bool(&move_ended, &move_started).on_change();
+            move_start <- all(mouse_position, &out.position).sample(&move_started);
+            moved_position <- mouse_position.gate(&out.moving).map3(&move_start, &out.size,
+                |pointer, (start_pointer, start_position), size| (start_position + pointer - start_pointer, *size)
+            );
+            snapped_position <- moved_position.map2(&input.set_screen_size,
+                |(position, size), screen| snapped(*position, *size, *screen)
+            );
+
+
+            // === Resizing ===
+
+            resized_edge <- any(...);
+            resized_edge <+ left_down.constant(Edge::Left);
+            resized_edge <+ right_down.constant(Edge::Right);
+            resized_edge <+ top_down.constant(Edge::Top);
+            resized_edge <+ bottom_down.constant(Edge::Bottom);
+            resize_ended <- pointer_up.gate(&out.resizing);
+            out.resizing <+ bool(&resize_ended, &resized_edge).on_change();
+            resize_start <- all4(&resized_edge, mouse_position, &out.position, &out.size);
+            resize_start <- resize_start.sample(&resized_edge);
+            resized_geometry <- mouse_position.gate(&out.resizing).map3(&resize_start, &input.set_min_size,
+                |pointer, (edge, start_pointer, position, size), min_size| {
+                    resized(*edge, *position, *size, pointer - start_pointer, *min_size)
+                }
+            );
+
+
+            // === Geometry ===
+
+            position <- any(&input.set_position, &snapped_position, &persisted_position);
+            position <+ resized_geometry._0();
+            size_set <- any(&input.set_size, &persisted_size);
+            size_set <+ resized_geometry._1();
+            size <- all_with(&size_set, &input.set_min_size, |size, min| {
+                Vector2(size.x.max(min.x), size.y.max(min.y))
+            });
+            out.position <+ position.on_change();
+            out.size <+ size.on_change();
+            geometry <- all(&out.position, &out.size);
+            eval geometry (((position, size)) model.update_layout(*position, *size));
+
+
+            // === Saving ===
+
+            geometry_changed <- any_(&move_ended, &resize_ended);
+            geometry_to_save <- geometry.sample(&geometry_changed).map2(&settings_key,
+                |geometry, key| key.clone().map(|key| (key, *geometry))
+            );
+            eval geometry_to_save ([settings](geometry) if let Some((key, (position, size))) = geometry {
+                let position = (position.x, position.y);
+                let size = (size.x, size.y);
+                settings.set(key, &Persisted { position, size });
+            });
+        }
+        init.emit(());
+        self
+    }
+
+    fn init_inputs(self) -> Self {
+        self.set_min_size(DEFAULT_MIN_SIZE);
+        self.set_size(DEFAULT_SIZE);
+        self.set_position(Vector2(-DEFAULT_SIZE.x / 2.0, DEFAULT_SIZE.y / 2.0));
+        self
+    }
+
+    /// The display object to which the panel content should be added. Its origin is the top-left
+    /// corner of the area below the title bar.
+    pub fn content(&self) -> &display::object::Instance {
+        &self.model.content
+    }
+
+    /// Place the panel in the given layers. The panel title is displayed in the `text_layer`,
+    /// which should be above the `layer`.
+    pub fn set_layers(&self, layer: &Layer, text_layer: &Layer) {
+        self.model.set_layers(layer, text_layer);
+    }
+
+    /// The layers set with [`Self::set_layers`].
+    pub fn layers(&self) -> Option<(Layer, Layer)> {
+        self.model.layer.borrow().clone()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resizing_keeps_the_opposite_edge() {
+        let position = Vector2(10.0, 100.0);
+        let size = Vector2(200.0, 100.0);
+        let min = Vector2(50.0, 50.0);
+        let delta = Vector2(30.0, -20.0);
+        assert_eq!(
+            resized(Edge::Right, position, size, delta, min),
+            (position, Vector2(230.0, 100.0))
+        );
+        assert_eq!(
+            resized(Edge::Bottom, position, size, delta, min),
+            (position, Vector2(200.0, 120.0))
+        );
+        let (position, size) = resized(Edge::Left, position, size, Vector2(180.0, 0.0), min);
+        assert_eq!((position, size), (Vector2(160.0, 100.0), Vector2(50.0, 100.0)));
+        let (position, size) = resized(Edge::Top, position, size, Vector2(0.0, 20.0), min);
+        assert_eq!((position, size), (Vector2(160.0, 120.0), Vector2(50.0, 120.0)));
+    }
+
+    #[test]
+    fn panels_snap_to_screen_edges() {
+        let screen = Vector2(1000.0, 800.0);
+        let size = Vector2(200.0, 100.0);
+        assert_eq!(snapped(Vector2(-495.0, 0.0), size, screen), Vector2(-500.0, 0.0));
+        assert_eq!(snapped(Vector2(295.0, 392.0), size, screen), Vector2(300.0, 400.0));
+        assert_eq!(snapped(Vector2(0.0, -292.0), size, screen), Vector2(0.0, -300.0));
+        assert_eq!(snapped(Vector2(0.0, 0.0), size, screen), Vector2(0.0, 0.0));
+    }
+}
@@ -0,0 +1,377 @@
+//! Spatial keyboard navigation between focusable scene components. Components registered in a
+//! [`FocusRing`] can be focused with the arrow keys: pressing an arrow moves the focus to the
+//! nearest registered component lying in that direction. It is an alternative to the tab order,
+//! which follows the registration order instead of the layout. The focused component is
+//! surrounded by a ring shape styled with the `application.focus_ring` theme values.
+
+// === Standard Linter Configuration ===
+#![deny(non_ascii_idents)]
+#![warn(unsafe_code)]
+#![allow(clippy::bool_to_int_with_if)]
+#![allow(clippy::let_and_return)]
+// === Non-Standard Linter Configuration ===
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+use ensogl_core::display::shape::*;
+use ensogl_core::prelude::*;
+
+use enso_frp as frp;
+use enso_frp::io::keyboard::ArrowDirection;
+use enso_frp::io::keyboard::Key;
+use ensogl_core::application::Application;
+use ensogl_core::control::io::keyboard::KeyDown;
+use ensogl_core::display;
+use ensogl_core::display::object::event::FocusIn;
+use ensogl_core::display::object::event::FocusOut;
+use ensogl_core::display::object::WeakInstance;
+use ensogl_hardcoded_theme::application::focus_ring as theme;
+
+
+
+// =================
+// === Direction ===
+// =================
+
+/// A direction in which the focus can be moved.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[allow(missing_docs)]
+pub enum Direction {
+    #[default]
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Direction {
+    /// The direction corresponding to the pressed key, if it is an arrow key.
+    pub fn from_key(key: &Key) -> Option<Self> {
+        match key {
+            Key::Arrow(ArrowDirection::Left) => Some(Self::Left),
+            Key::Arrow(ArrowDirection::Right) => Some(Self::Right),
+            Key::Arrow(ArrowDirection::Up) => Some(Self::Up),
+            Key::Arrow(ArrowDirection::Down) => Some(Self::Down),
+            _ => None,
+        }
+    }
+
+    /// A unit vector pointing in this direction in the scene coordinate space (y axis pointing
+    /// up).
+    pub fn vector(self) -> Vector2 {
+        match self {
+            Self::Left => Vector2(-1.0, 0.0),
+            Self::Right => Vector2(1.0, 0.0),
+            Self::Up => Vector2(0.0, 1.0),
+            Self::Down => Vector2(0.0, -1.0),
+        }
+    }
+}
+
+
+
+// ==============
+// === Bounds ===
+// ==============
+
+/// An axis-aligned rectangle occupied by a focusable component, in scene coordinates.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[allow(missing_docs)]
+pub struct Bounds {
+    pub position: Vector2,
+    pub size:     Vector2,
+}
+
+impl Bounds {
+    /// Constructor.
+    pub fn new(position: Vector2, size: Vector2) -> Self {
+        Self { position, size }
+    }
+
+    /// Bounds of the provided display object, based on its global position and computed size.
+    pub fn of(object: &impl display::Object) -> Self {
+        Self::new(object.global_position().xy(), object.computed_size())
+    }
+
+    /// The center point of the rectangle.
+    pub fn center(&self) -> Vector2 {
+        self.position + self.size / 2.0
+    }
+}
+
+/// Find the index of the candidate which the focus should move to when moving from `from` in the
+/// given direction. Only candidates whose centers lie strictly in the requested direction are
+/// considered. Among them, the one closest along the direction wins, with the offset along the
+/// perpendicular axis weighted twice as much, so that aligned components are preferred over
+/// diagonal ones.
+pub fn nearest_in_direction(
+    from: Bounds,
+    candidates: impl IntoIterator<Item = Bounds>,
+    direction: Direction,
+) -> Option<usize> {
+    const PERPENDICULAR_WEIGHT: f32 = 2.0;
+    let origin = from.center();
+    let axis = direction.vector();
+    let scores = candidates.into_iter().enumerate().filter_map(|(index, candidate)| {
+        let offset = candidate.center() - origin;
+        let primary = offset.dot(&axis);
+        let perpendicular = (offset - axis * primary).norm();
+        (primary > f32::EPSILON).then_some((index, primary + perpendicular * PERPENDICULAR_WEIGHT))
+    });
+    scores.min_by(|(_, a), (_, b)| a.total_cmp(b)).map(|(index, _)| index)
+}
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl_core::define_endpoints_2! {
+    Input {
+        /// Move the focus to the nearest registered component in the given direction.
+        move_focus (Direction),
+        /// Enable or disable handling of the arrow keys. Enabled by default.
+        set_arrow_navigation (bool),
+    }
+    Output {
+        /// Emitted after the focus was moved by this ring.
+        focus_moved (Direction),
+        /// Whether the ring is visible, which happens when one of the registered components is
+        /// focused.
+        visible (bool),
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+#[derive(Debug, display::Object)]
+struct Model {
+    display_object: display::object::Instance,
+    scene_root:     display::object::Instance,
+    ring:           Rectangle,
+    focusables:     RefCell<Vec<WeakInstance>>,
+    focused:        RefCell<Option<WeakInstance>>,
+    border_width:   Cell<f32>,
+}
+
+impl Model {
+    fn new(app: &Application) -> Self {
+        let scene = &app.display.default_scene;
+        let scene_root = scene.display_object().clone_ref();
+        let display_object = display::object::Instance::new_named("FocusRing");
+        let ring = Rectangle::new();
+        ring.set_color(color::Rgba::transparent()).set_pointer_events(false);
+        scene.layers.tooltip.add(&ring);
+        let focusables = default();
+        let focused = default();
+        let border_width = default();
+        Self { display_object, scene_root, ring, focusables, focused, border_width }
+    }
+
+    fn register(&self, object: &display::object::Instance) {
+        let mut focusables = self.focusables.borrow_mut();
+        focusables.retain(|weak| weak.upgrade().is_some());
+        if !focusables.iter().any(|weak| weak.upgrade().as_ref() == Some(object)) {
+            focusables.push(object.downgrade());
+        }
+    }
+
+    fn unregister(&self, object: &display::object::Instance) {
+        self.focusables.borrow_mut().retain(|weak| weak.upgrade().map_or(false, |t| &t != object));
+    }
+
+    fn focusables(&self) -> Vec<display::object::Instance> {
+        self.focusables.borrow().iter().filter_map(|weak| weak.upgrade()).collect()
+    }
+
+    /// The registered component which is focused, or which contains the focused object.
+    fn registered_focused(&self) -> Option<display::object::Instance> {
+        let focused = self.scene_root.focused_instance()?;
+        let focusables = self.focusables();
+        let mut current = Some(focused);
+        while let Some(object) = current {
+            if focusables.contains(&object) {
+                return Some(object);
+            }
+            current = object.parent();
+        }
+        None
+    }
+
+    /// Whether the arrow keys should be handled by the ring: either nothing is focused, or the
+    /// focus is inside one of the registered components.
+    fn can_navigate(&self) -> bool {
+        self.scene_root.focused_instance().is_none() || self.registered_focused().is_some()
+    }
+
+    fn move_focus(&self, direction: Direction) -> bool {
+        let focusables = self.focusables();
+        let target = match self.registered_focused() {
+            Some(current) => {
+                let others = focusables.iter().filter(|t| *t != &current);
+                let others = others.collect_vec();
+                let bounds = others.iter().map(|t| Bounds::of(*t));
+                let index = nearest_in_direction(Bounds::of(&current), bounds, direction);
+                index.map(|index| others[index].clone_ref())
+            }
+            None => focusables.first().cloned(),
+        };
+        if let Some(target) = &target {
+            target.focus();
+        }
+        target.is_some()
+    }
+
+    fn update_focused(&self) -> bool {
+        let focused = self.registered_focused();
+        let visible = focused.is_some();
+        *self.focused.borrow_mut() = focused.as_ref().map(|t| t.downgrade());
+        if visible {
+            self.display_object.add_child(&self.ring);
+        } else {
+            self.ring.unset_parent();
+        }
+        visible
+    }
+
+    fn update_ring_position(&self, padding: f32) {
+        let focused = self.focused.borrow().as_ref().and_then(|t| t.upgrade());
+        if let Some(focused) = focused {
+            let bounds = Bounds::of(&focused);
+            let origin = self.display_object.global_position().xy();
+            let border = self.border_width.get();
+            let margin = Vector2(padding + border, padding + border);
+            self.ring.set_xy(bounds.position - origin - margin);
+            self.ring.set_size(bounds.size + margin * 2.0);
+        }
+    }
+
+    fn set_style(&self, color: color::Rgba, width: f32, corner_radius: f32) {
+        self.border_width.set(width);
+        self.ring.set_border_and_inset(width);
+        self.ring.set_border_color(color);
+        self.ring.set_corner_radius(corner_radius);
+    }
+}
+
+
+
+// =================
+// === FocusRing ===
+// =================
+
+/// Spatial keyboard navigation between registered components, together with the ring shape drawn
+/// around the focused one. See the module docs to learn more.
+///
+/// The ring has to be added to the scene, preferably as a direct child of its root, for the focus
+/// ring shape to be displayed.
+#[derive(Clone, CloneRef, Debug, Deref, display::Object)]
+pub struct FocusRing {
+    #[deref]
+    frp:   Frp,
+    #[display_object]
+    model: Rc<Model>,
+}
+
+impl FocusRing {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let frp = Frp::new();
+        let model = Rc::new(Model::new(app));
+        Self { frp, model }.init(app)
+    }
+
+    fn init(self, app: &Application) -> Self {
+        let network = self.frp.network();
+        let input = &self.frp.private.input;
+        let out = &self.frp.private.output;
+        let model = &self.model;
+        let scene = &app.display.default_scene;
+        let style = StyleWatchFrp::new(&scene.style_sheet);
+        let color = style.get_color(theme::color);
+        let width = style.get_number(theme::width);
+        let padding = style.get_number(theme::padding);
+        let corner_radius = style.get_number(theme::corner_radius);
+        let key_down = scene.on_event::<KeyDown>();
+        let focus_in = scene.on_event::<FocusIn>();
+        let focus_out = scene.on_event::<FocusOut>();
+        let after_animations = ensogl_core::animation::on_after_animations();
+
+        frp::extend! { network
+            arrow_navigation <- input.set_arrow_navigation.sampler();
+            arrow <- key_down.filter_map(f!([model, arrow_navigation](event) {
+                let direction = Direction::from_key(event.key())?;
+                let no_modifiers = !event.ctrl() && !event.meta() && !event.alt();
+                let handle = arrow_navigation.value() && no_modifiers;
+                let handle = handle && !event.is_default_prevented() && model.can_navigate();
+                handle.then(|| {
+                    event.prevent_default();
+                    direction
+                })
+            }));
+            move_focus <- any(&input.move_focus, &arrow);
+            out.focus_moved <+ move_focus.filter(f!((d) model.move_focus(*d)));
+
+            focus_changed <- any_(&focus_in, &focus_out);
+            out.visible <+ focus_changed.map(f_!(model.update_focused()));
+
+            init <- source_();
+            style_changed <- any_(&init, &color, &width, &corner_radius);
+            eval_ style_changed (
+                model.set_style(color.value(), width.value(), corner_radius.value())
+            );
+            update_position <- after_animations.gate(&out.visible);
+            eval_ update_position (model.update_ring_position(padding.value()));
+        }
+        input.set_arrow_navigation.emit(true);
+        init.emit(());
+        self
+    }
+
+    /// Register a component as focusable with the arrow keys.
+    pub fn register(&self, object: &impl display::Object) {
+        self.model.register(object.display_object());
+    }
+
+    /// Remove the component from the navigation. Dropped components are removed automatically.
+    pub fn unregister(&self, object: &impl display::Object) {
+        self.model.unregister(object.display_object());
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f32, y: f32) -> Bounds {
+        Bounds::new(Vector2(x, y), Vector2(10.0, 10.0))
+    }
+
+    #[test]
+    fn nearest_component_in_direction() {
+        let from = square(0.0, 0.0);
+        let candidates =
+            [square(50.0, 0.0), square(30.0, 40.0), square(0.0, 60.0), square(-40.0, 5.0)];
+        let nearest = |dir| nearest_in_direction(from, candidates.iter().copied(), dir);
+        assert_eq!(nearest(Direction::Right), Some(0));
+        assert_eq!(nearest(Direction::Up), Some(2));
+        assert_eq!(nearest(Direction::Left), Some(3));
+        assert_eq!(nearest(Direction::Down), None);
+    }
+}
@@ -785,29 +785,8 @@ impl Gaps {
 }
 
 impl<T: display::Object + CloneRef + 'static> Model<T> {
-    // FIXME: refactor and generalize
     fn screen_to_object_space(&self, screen_pos: Vector2) -> Vector2 {
-        let scene = scene();
-        let camera = scene.camera();
-        let origin_world_space = Vector4(0.0, 0.0, 0.0, 1.0);
-        let origin_clip_space = camera.view_projection_matrix() * origin_world_space;
-        let inv_object_matrix = self.root.transformation_matrix().try_inverse().unwrap();
-
-        let shape = scene.frp.shape.value();
-        let clip_space_z = origin_clip_space.z;
-        let clip_space_x = if shape.width.is_finite() && shape.width != 0.0 {
-            origin_clip_space.w * 2.0 * screen_pos.x / shape.width
-        } else {
-            0.0
-        };
-        let clip_space_y = if shape.height.is_finite() && shape.height != 0.0 {
-            origin_clip_space.w * 2.0 * screen_pos.y / shape.height
-        } else {
-            0.0
-        };
-        let clip_space = Vector4(clip_space_x, clip_space_y, clip_space_z, origin_clip_space.w);
-        let world_space = camera.inversed_view_projection_matrix() * clip_space;
-        (inv_object_matrix * world_space).xy()
+        scene().screen_to_object_space(&self.root, screen_pos)
     }
 
     fn len(&self) -> usize {
@@ -0,0 +1,177 @@
+//! A log viewer panel, displaying the latest records of the
+//! [log ring buffer](enso_logging::buffer). It allows inspecting warnings and errors without
+//! opening the browser console.
+//!
+//! The records are displayed in a text area in the log view mode: new records are appended at the
+//! bottom, the oldest lines are removed when the buffer capacity is exceeded, and the view stays
+//! scrolled to the bottom unless the user scrolls up.
+
+#![recursion_limit = "512"]
+// === Standard Linter Configuration ===
+#![deny(non_ascii_idents)]
+#![warn(unsafe_code)]
+#![allow(clippy::bool_to_int_with_if)]
+#![allow(clippy::let_and_return)]
+// === Non-Standard Linter Configuration ===
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+use ensogl_core::display::shape::*;
+use ensogl_core::prelude::*;
+
+use enso_frp as frp;
+use enso_logging::buffer;
+use ensogl_core::animation;
+use ensogl_core::application;
+use ensogl_core::application::Application;
+use ensogl_core::data::color;
+use ensogl_core::display;
+use ensogl_text as text;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The default size of the panel.
+pub const DEFAULT_SIZE: (f32, f32) = (600.0, 240.0);
+/// The distance between the panel border and the text.
+pub const PADDING: f32 = 8.0;
+const BACKGROUND_COLOR: color::Rgba = color::Rgba::new(1.0, 1.0, 1.0, 0.9);
+const CORNER_RADIUS: f32 = 6.0;
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl_core::define_endpoints_2! {
+    Input {
+        /// Set the size of the panel. The top left corner of the panel is placed at the origin.
+        set_size(Vector2),
+        /// Remove all displayed records. The records are removed from the ring buffer as well.
+        clear(),
+    }
+    Output {
+        /// The number of records displayed since the panel was created or cleared.
+        record_count(usize),
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+#[derive(Debug, display::Object)]
+struct Model {
+    display_object: display::object::Instance,
+    background:     Rectangle,
+    text:           text::Text,
+    /// The index of the next record to display, see [`buffer::RingBuffer::pushed_count`].
+    next_record:    Cell<usize>,
+    record_count:   Cell<usize>,
+}
+
+impl Model {
+    fn new(app: &Application) -> Self {
+        let scene = &app.display.default_scene;
+        let display_object = display::object::Instance::new_named("LogViewer");
+        let background = Rectangle().build(|r| {
+            r.set_color(BACKGROUND_COLOR).set_corner_radius(CORNER_RADIUS);
+        });
+        let text = app.new_view::<text::Text>();
+        text.set_xy(Vector2(PADDING, -PADDING));
+        text.set_max_line_count(Some(buffer::DEFAULT_CAPACITY));
+        text.set_pinned_to_bottom(true);
+        display_object.add_child(&background);
+        display_object.add_child(&text);
+        scene.layers.panel.add(&background);
+        scene.layers.panel_text.add(&text);
+        let next_record = default();
+        let record_count = default();
+        Self { display_object, background, text, next_record, record_count }
+    }
+
+    fn set_size(&self, size: Vector2) {
+        self.background.set_size(size);
+        self.background.set_y(-size.y);
+        self.text.set_view_width(Some(size.x - 2.0 * PADDING));
+        self.text.set_view_height(Some(size.y - 2.0 * PADDING));
+    }
+
+    /// Append the records pushed to the buffer since the last update. Returns the new record count
+    /// if there were any new records.
+    fn update(&self) -> Option<usize> {
+        let pushed_count = buffer::pushed_count();
+        let next_record = self.next_record.replace(pushed_count);
+        (pushed_count != next_record).then(|| {
+            let records = buffer::records_since(next_record);
+            let is_first = self.record_count.get() == 0;
+            let lines = records.iter().map(|record| record.to_string()).join("\n");
+            let separator = if is_first { "" } else { "\n" };
+            self.text.append(ImString::from(format!("{separator}{lines}")));
+            self.record_count.set(self.record_count.get() + records.len());
+            self.record_count.get()
+        })
+    }
+
+    fn clear(&self) {
+        buffer::clear();
+        self.next_record.set(buffer::pushed_count());
+        self.record_count.set(0);
+        self.text.set_content("");
+    }
+}
+
+
+
+// =================
+// === LogViewer ===
+// =================
+
+/// The log viewer panel. See the crate docs to learn more.
+#[derive(Clone, CloneRef, Debug, Deref, display::Object)]
+pub struct LogViewer {
+    #[deref]
+    frp:   Frp,
+    #[display_object]
+    model: Rc<Model>,
+}
+
+impl LogViewer {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let frp = Frp::new();
+        let model = Rc::new(Model::new(app));
+        let network = frp.network();
+        let out = &frp.private.output;
+        let on_frame = animation::on_before_animations();
+        frp::extend! { network
+            eval frp.set_size ((size) model.set_size(*size));
+            cleared <- frp.clear.map(f_!(model.clear()));
+            out.record_count <+ cleared.constant(0);
+            out.record_count <+ on_frame.filter_map(f_!(model.update()));
+        }
+        frp.set_size(Vector2(DEFAULT_SIZE.0, DEFAULT_SIZE.1));
+        Self { frp, model }
+    }
+}
+
+impl application::View for LogViewer {
+    fn label() -> &'static str {
+        "LogViewer"
+    }
+
+    fn new(app: &Application) -> Self {
+        Self::new(app)
+    }
+}
@@ -0,0 +1,369 @@
+//! A panel docked to the left, right, or bottom side of a container, like the application window.
+//!
+//! The panel displays its [content](SidePanel::content) over a background. The edge of the panel
+//! facing the container center is a handle, which can be dragged to resize the panel within the
+//! bounds set with [`Frp::set_min_size`] and [`Frp::set_max_size`]. A collapsed panel slides out of
+//! the container, leaving only the handle visible; clicking the handle expands it back.
+//!
+//! When a [settings key](Frp::set_settings_key) is provided, the size and the collapsed state are
+//! persisted in the application [settings store](ensogl_core::application::settings::Store) and
+//! restored when the key is set again, for example after reloading the application.
+
+// === Standard Linter Configuration ===
+#![deny(non_ascii_idents)]
+#![warn(unsafe_code)]
+#![allow(clippy::bool_to_int_with_if)]
+#![allow(clippy::let_and_return)]
+// === Non-Standard Linter Configuration ===
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+use ensogl_core::display::shape::*;
+use ensogl_core::prelude::*;
+
+use enso_frp as frp;
+use ensogl_core::animation::Animation;
+use ensogl_core::application::Application;
+use ensogl_core::control::io::mouse;
+use ensogl_core::display;
+use ensogl_hardcoded_theme::component::side_panel as theme;
+use serde::Deserialize;
+use serde::Serialize;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The width of the resize handle.
+pub const HANDLE_WIDTH: f32 = 6.0;
+/// The default size of the panel, measured perpendicularly to the side it is docked to.
+const DEFAULT_SIZE: f32 = 240.0;
+/// The default minimum size of the panel.
+const DEFAULT_MIN_SIZE: f32 = 120.0;
+/// The default maximum size of the panel.
+const DEFAULT_MAX_SIZE: f32 = 600.0;
+
+
+
+// ============
+// === Side ===
+// ============
+
+/// The side of the container the panel is docked to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub enum Side {
+    #[default]
+    Left,
+    Right,
+    Bottom,
+}
+
+impl Side {
+    /// The direction in which dragging the handle enlarges the panel.
+    fn growth_direction(self) -> Vector2 {
+        match self {
+            Self::Left => Vector2(1.0, 0.0),
+            Self::Right => Vector2(-1.0, 0.0),
+            Self::Bottom => Vector2(0.0, 1.0),
+        }
+    }
+}
+
+
+
+// =================
+// === Persisted ===
+// =================
+
+/// The panel state stored in the settings.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Persisted {
+    size:      f32,
+    collapsed: bool,
+}
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl_core::define_endpoints_2! {
+    Input {
+        set_side (Side),
+        /// Set the size of the container the panel is docked in. The panel origin should be placed
+        /// in the bottom-left corner of the container.
+        set_container_size (Vector2),
+        /// Set the panel size, measured perpendicularly to the side it is docked to.
+        set_size (f32),
+        set_min_size (f32),
+        set_max_size (f32),
+        set_collapsed (bool),
+        toggle_collapsed (),
+        /// Persist the panel size and collapsed state in the application settings under the given
+        /// key. If the settings contain the state stored under this key, it is restored.
+        set_settings_key (Option<ImString>),
+    }
+    Output {
+        side (Side),
+        /// The panel size when expanded, clamped to the size bounds.
+        size (f32),
+        /// The part of the container currently covered by the panel, changing during the collapse
+        /// and expand animations. Application shells should lay out the rest of their content in
+        /// the remaining space.
+        visible_size (f32),
+        collapsed (bool),
+        /// True while the user drags the resize handle.
+        resizing (bool),
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+#[derive(Debug, display::Object)]
+struct Model {
+    display_object: display::object::Instance,
+    /// The part of the panel which slides out of the container when collapsing.
+    body:           display::object::Instance,
+    background:     Rectangle,
+    handle:         Rectangle,
+    content:        display::object::Instance,
+}
+
+impl Model {
+    fn new() -> Self {
+        let display_object = display::object::Instance::new_named("SidePanel");
+        let body = display::object::Instance::new_named("body");
+        let background = Rectangle();
+        let handle = Rectangle();
+        let content = display::object::Instance::new_named("content");
+        display_object.add_child(&body);
+        body.add_child(&background);
+        body.add_child(&content);
+        body.add_child(&handle);
+        Self { display_object, body, background, handle, content }
+    }
+
+    /// Update the shapes positions. The `progress` is `1.0` for expanded, and `0.0` for fully
+    /// collapsed panel.
+    fn update_layout(&self, side: Side, container: Vector2, size: f32, progress: f32) -> f32 {
+        let visible_size = size * progress;
+        let hidden_size = size - visible_size;
+        let half_handle = HANDLE_WIDTH / 2.0;
+        match side {
+            Side::Left => {
+                self.body.set_xy(Vector2(-hidden_size, 0.0));
+                self.background.set_size(Vector2(size, container.y));
+                self.handle.set_size(Vector2(HANDLE_WIDTH, container.y));
+                self.handle.set_xy(Vector2(size - half_handle, 0.0));
+            }
+            Side::Right => {
+                self.body.set_xy(Vector2(container.x - visible_size, 0.0));
+                self.background.set_size(Vector2(size, container.y));
+                self.handle.set_size(Vector2(HANDLE_WIDTH, container.y));
+                self.handle.set_xy(Vector2(-half_handle, 0.0));
+            }
+            Side::Bottom => {
+                self.body.set_xy(Vector2(0.0, -hidden_size));
+                self.background.set_size(Vector2(container.x, size));
+                self.handle.set_size(Vector2(container.x, HANDLE_WIDTH));
+                self.handle.set_xy(Vector2(0.0, size - half_handle));
+            }
+        }
+        visible_size
+    }
+}
+
+
+
+// =================
+// === SidePanel ===
+// =================
+
+/// A resizable and collapsible panel docked to a side of a container. See the module docs to learn
+/// more.
+#[derive(Clone, CloneRef, Debug, Deref, display::Object)]
+pub struct SidePanel {
+    #[deref]
+    frp:   Frp,
+    #[display_object]
+    model: Rc<Model>,
+}
+
+impl SidePanel {
+    /// Constructor.
+    pub fn new(app: &Application) -> Self {
+        let frp = Frp::new();
+        let model = Rc::new(Model::new());
+        Self { frp, model }.init(app).init_inputs()
+    }
+
+    fn init(self, app: &Application) -> Self {
+        let network = self.frp.network();
+        let input = &self.frp.private.input;
+        let out = &self.frp.private.output;
+        let model = &self.model;
+        let settings = &app.settings;
+        let scene = &app.display.default_scene;
+        let style = StyleWatchFrp::new(&scene.style_sheet);
+        let background = style.get_color(theme::background);
+        let handle_color = style.get_color(theme::handle_color);
+        let handle_hover_color = style.get_color(theme::handle_hover_color);
+        let handle_over = model.handle.on_event::<mouse::Over>();
+        let handle_out = model.handle.on_event::<mouse::Out>();
+        let handle_down = model.handle.on_event::<mouse::Down>();
+        let pointer_up = scene.on_event::<mouse::Up>();
+        let pointer = scene.pointer_position_in_object_space(network, &model.display_object);
+        let progress = Animation::<f32>::new_with_init(network, 1.0);
+
+        frp::extend! { network
+
+            // === Style ===
+
+            init <- source_();
+            background_changed <- any_(&init, &background);
+            eval_ background_changed ([model, background]
+                model.background.set_color(background.value());
+            );
+            handle_hovered <- bool(&handle_out, &handle_over);
+            handle_highlighted <- any(&handle_hovered, &out.resizing);
+            handle_style_changed <- any_(&init, &handle_color, &handle_hover_color, &handle_highlighted);
+            eval_ handle_style_changed ([model, handle_color, handle_hover_color, handle_highlighted] {
+                let highlighted = handle_highlighted.value();
+                let color = if highlighted { handle_hover_color.value() } else { handle_color.value() };
+                model.handle.set_color(color);
+            });
+
+
+            // === Persistence ===
+
+            settings_key <- input.set_settings_key.sampler();
+            persisted <- input.set_settings_key.filter_map(f!([settings](key) {
+                key.as_ref().and_then(|key| settings.get::<Persisted>(key))
+            }));
+
+
+            // === Resizing ===
+
+            out.side <+ input.set_side;
+            handle_pressed <- handle_down.map(|e| e.button() == mouse::PrimaryButton).on_true();
+            resize_started <- handle_pressed.gate_not(&out.collapsed);
+            drag_start <- all(&pointer, &out.size).sample(&resize_started);
+            resize_ended <- pointer_up.gate(&out.resizing);
+            out.resizing <+ bool(&resize_ended, &resize_started).on_change();
+            dragged_size <- pointer.gate(&out.resizing).map3(&drag_start, &out.side,
+                |position, (start_position, start_size), side| {
+                    start_size + (position - start_position).dot(&side.growth_direction())
+                }
+            );
+
+            size_bounds <- all(&input.set_min_size, &input.set_max_size);
+            requested_size <- any(&input.set_size, &dragged_size);
+            requested_size <+ persisted.map(|state| state.size);
+            size <- all_with(&requested_size, &size_bounds, |size, (min, max)| {
+                size.max(*min).min(max.max(*min))
+            });
+            out.size <+ size.on_change();
+
+
+            // === Collapsing ===
+
+            collapsed <- any(...);
+            collapsed <+ input.set_collapsed;
+            collapsed <+ input.toggle_collapsed.map2(&out.collapsed, |_, collapsed| !collapsed);
+            collapsed <+ handle_pressed.gate(&out.collapsed).constant(false);
+            collapsed <+ persisted.map(|state| state.collapsed);
+            out.collapsed <+ collapsed.on_change();
+            progress.target <+ out.collapsed.map(|collapsed| if *collapsed { 0.0 } else { 1.0 });
+            progress.skip <+_ persisted;
+
+
+            // === Layout ===
+
+            layout <- all4(&out.side, &input.set_container_size, &out.size, &progress.value);
+            visible_size <- layout.map(f!(((side, container, size, progress))
+                model.update_layout(*side, *container, *size, *progress)
+            ));
+            out.visible_size <+ visible_size.on_change();
+
+
+            // === Saving ===
+
+            state_changed <- any_(&resize_ended, &out.collapsed);
+            state_to_save <- state_changed.map2(&settings_key, |_, key| key.clone());
+            state_to_save <- state_to_save.filter_map(|key| key.clone());
+            size_to_save <- out.size.sampler();
+            collapsed_to_save <- out.collapsed.sampler();
+            eval state_to_save ([settings, size_to_save, collapsed_to_save](key) {
+                let size = size_to_save.value();
+                let collapsed = collapsed_to_save.value();
+                settings.set(key, &Persisted { size, collapsed });
+            });
+        }
+        init.emit(());
+        self
+    }
+
+    fn init_inputs(self) -> Self {
+        self.set_side(Side::Left);
+        self.set_min_size(DEFAULT_MIN_SIZE);
+        self.set_max_size(DEFAULT_MAX_SIZE);
+        self.set_size(DEFAULT_SIZE);
+        self.set_collapsed(false);
+        self
+    }
+
+    /// The display object to which the panel content should be added. Its origin is the
+    /// bottom-left corner of the panel.
+    pub fn content(&self) -> &display::object::Instance {
+        &self.model.content
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn size_is_clamped_and_persisted() {
+        let app = Application::new("root");
+        let panel = SidePanel::new(&app);
+        panel.set_container_size(Vector2(800.0, 600.0));
+        panel.set_min_size(100.0);
+        panel.set_max_size(300.0);
+        panel.set_size(500.0);
+        assert_eq!(panel.size.value(), 300.0);
+        panel.set_size(50.0);
+        assert_eq!(panel.size.value(), 100.0);
+
+        panel.set_size(200.0);
+        panel.set_settings_key(Some("test.side_panel".into()));
+        panel.toggle_collapsed();
+        assert!(panel.collapsed.value());
+        let stored = app.settings.get::<Persisted>("test.side_panel");
+        assert_eq!(stored, Some(Persisted { size: 200.0, collapsed: true }));
+
+        let restored = SidePanel::new(&app);
+        restored.set_settings_key(Some("test.side_panel".into()));
+        assert_eq!(restored.size.value(), 200.0);
+        assert!(restored.collapsed.value());
+    }
+}
@@ -35,6 +35,7 @@ use ensogl_core::application::shortcut;
 use ensogl_core::application::tooltip;
 use ensogl_core::application::Application;
 use ensogl_core::control::io::mouse;
+use ensogl_core::control::io::pointer_lock::PointerLock;
 use ensogl_core::data::color;
 use ensogl_core::display;
 use ensogl_core::Animation;
@@ -292,6 +293,9 @@ ensogl_core::define_endpoints_2! {
         cancel_value_editing(),
         /// Set the slider's thumb size as fraction of the slider's length.
         set_thumb_size(f32),
+        /// Set whether the pointer is locked while dragging, so the value can be adjusted without
+        /// the pointer hitting the screen edges. The lock is released on the Escape key press.
+        set_pointer_lock(bool),
     }
     Output {
         /// The component's width.
@@ -370,7 +374,7 @@ impl Slider {
         let output = &self.frp.private.output;
         let model = &self.model;
         let scene = &app.display.default_scene;
-        let mouse = &scene.mouse.frp_deprecated;
+        let pointer_lock = scene.extension::<PointerLock>();
         let keyboard = &scene.global_keyboard.frp;
 
         let ptr_down_any = model.background.on_event::<mouse::Down>();
@@ -380,7 +384,7 @@ impl Slider {
         frp::extend! { network
             ptr_down <- ptr_down_any.map(|e| e.button() == mouse::PrimaryButton).on_true();
             ptr_up <- ptr_up_any.map(|e| e.button() == mouse::PrimaryButton).on_true();
-            pos <- mouse.position.map(
+            pos <- pointer_lock.position.map(
                 f!([scene, model] (p) scene.screen_to_object_space(model.display_object(), *p))
             );
 
@@ -398,6 +402,9 @@ impl Slider {
             on_drag_start <- ptr_down.gate_not(&keyboard.is_control_down);
             on_drag_stop <- any3(&ptr_up, &on_disabled, &on_editing);
             output.dragged <+ bool(&on_drag_stop, &on_drag_start);
+            pointer_lock.lock <+ on_drag_start.gate(&frp.set_pointer_lock);
+            drag_finished <- output.dragged.on_change().on_false();
+            pointer_lock.unlock <+ drag_finished.gate(&frp.set_pointer_lock);
             drag_start <- pos.sample(&on_drag_start);
             drag_end <- pos.gate(&output.dragged).any2(&drag_start);
             drag_delta <- all2(&drag_end, &drag_start).map(|(end, start)| end - start);
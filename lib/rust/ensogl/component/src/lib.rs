@@ -13,19 +13,26 @@
 
 pub use ensogl_breadcrumbs as breadcrumbs;
 pub use ensogl_button as button;
+pub use ensogl_chart as chart;
+pub use ensogl_dock as dock;
 pub use ensogl_drop_down as drop_down;
 pub use ensogl_drop_down_menu as drop_down_menu;
 pub use ensogl_drop_manager as drop_manager;
 pub use ensogl_file_browser as file_browser;
+pub use ensogl_floating_panel as floating_panel;
+pub use ensogl_focus_ring as focus_ring;
 pub use ensogl_grid_view as grid_view;
 pub use ensogl_label as label;
 pub use ensogl_list_editor as list_editor;
 pub use ensogl_list_view as list_view;
+pub use ensogl_log_viewer as log_viewer;
 pub use ensogl_scroll_area as scroll_area;
 pub use ensogl_scrollbar as scrollbar;
 pub use ensogl_selector as selector;
 pub use ensogl_shadow as shadow;
+pub use ensogl_side_panel as side_panel;
 pub use ensogl_spinner as spinner;
+pub use ensogl_table as table;
 pub use ensogl_text as text;
 pub use ensogl_toggle_button as toggle_button;
 pub use ensogl_tooltip as tooltip;
@@ -0,0 +1,309 @@
+//! A virtualized table component, the foundation of data-frame visualizations.
+//!
+//! The [`Table`] displays a header row which stays visible when the rows are scrolled vertically,
+//! and follows them when they are scrolled horizontally. Clicking a column title cycles the
+//! column sorting, and dragging a column border in the header resizes the column. The table does
+//! not sort the rows by itself: it emits the requested [`Frp::sorting`] and drops all cached rows,
+//! so they are requested again in the new order.
+//!
+//! The rows are provided lazily, using the same range-request protocol as the Dropdown component:
+//! the table emits [`Frp::rows_in_range_needed`] for rows scrolled into view, and the rows should
+//! be provided with [`Frp::provide_rows_at_range`]. Each
+//! cell is rendered by a Grid View [`Entry`]. The [`TextTable`] renders cells as plain texts, and
+//! any custom entry type may be used for other kinds of cells.
+
+#![recursion_limit = "512"]
+// === Features ===
+#![feature(let_chains)]
+// === Standard Linter Configuration ===
+#![deny(non_ascii_idents)]
+#![warn(unsafe_code)]
+#![allow(clippy::bool_to_int_with_if)]
+#![allow(clippy::let_and_return)]
+
+use ensogl_core::display::shape::*;
+use ensogl_core::prelude::*;
+use model::*;
+
+use ensogl_core::application::Application;
+use ensogl_core::control::io::mouse;
+use ensogl_core::display::shape::StyleWatchFrp;
+use ensogl_core::frp;
+use ensogl_grid_view::simple;
+use ensogl_grid_view::Col;
+use ensogl_grid_view::Entry;
+use ensogl_grid_view::Row;
+use ensogl_gui_component::component;
+use ensogl_gui_component::component::ComponentView;
+use ensogl_hardcoded_theme::component::table as theme;
+
+
+// ==============
+// === Export ===
+// ==============
+
+pub mod model;
+
+pub use model::Column;
+pub use model::SortOrder;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// Default size of the table.
+const DEFAULT_SIZE: Vector2 = Vector2(480.0, 320.0);
+/// Default maximum number of rows that can be cached at once.
+const DEFAULT_MAX_CACHED_ROWS: usize = 256;
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl_core::define_endpoints_2! { <CellModel: (frp::node::Data), CellParams: (frp::node::Data)>
+    Input {
+        /// Set the size of the table, including the header row.
+        set_size(Vector2),
+        /// Replace the columns of the table. All cached rows are dropped.
+        set_columns(Vec<Column>),
+        /// Set the width of the specified column. The width is clamped to [`MIN_COLUMN_WIDTH`].
+        set_column_width((Col, f32)),
+        /// Set the parameters of the entries displaying the cells.
+        set_cell_params(CellParams),
+        /// Set the total number of rows. Rows provided past this limit are discarded, so it should
+        /// be set before any rows are provided. The default value is 0.
+        set_number_of_rows(usize),
+        /// Update the rows at the specified range. Each row is a list of cell models, one for each
+        /// column. Provided rows are not guaranteed to be kept in memory. If a row was unloaded
+        /// due to memory constraints, it will be requested again.
+        provide_rows_at_range(Range<usize>, Vec<Vec<CellModel>>),
+        /// Set the maximum number of rows that can be kept in memory at once.
+        set_max_cached_rows(usize),
+        /// Drop all cached rows and request the visible ones again, for example after the data
+        /// changed.
+        reset_rows(),
+        /// Set the column by which the rows are sorted. Behaves as if the column header was
+        /// clicked, but does not cycle the sorting.
+        set_sorting(Option<(Col, SortOrder)>),
+        /// Select the row, or clear the selection.
+        select_row(Option<Row>),
+        /// Select the row above the selected one, or the first row if none is selected.
+        select_previous_row(),
+        /// Select the row below the selected one, or the first row if none is selected.
+        select_next_row(),
+        /// Accept the selected row.
+        accept_selected_row(),
+    }
+    Output {
+        size(Vector2),
+        /// Emitted when the table needs the rows in the specified range. They should be provided
+        /// using `provide_rows_at_range`. Until then, the cells of these rows are not displayed.
+        rows_in_range_needed(Range<usize>),
+        /// The range of rows currently in view.
+        currently_visible_range(Range<usize>),
+        /// Emitted when the column width was changed, either by the user dragging the column border
+        /// in the header or with `set_column_width`.
+        column_resized(Col, f32),
+        /// The requested sorting of rows. When it changes, all cached rows are dropped, and the
+        /// visible rows are requested again, expected to be provided in the new order.
+        sorting(Option<(Col, SortOrder)>),
+        selected_row(Option<Row>),
+        /// Emitted when a row was clicked, or the selected row was accepted with
+        /// `accept_selected_row`.
+        row_accepted(Row),
+    }
+}
+
+impl<E: Entry> component::Frp<Model<E>> for Frp<E::Model, E::Params>
+where
+    E::Model: frp::node::Data,
+    E::Params: frp::node::Data,
+{
+    fn init_inputs(frp: &Self::Public) {
+        frp.set_size(DEFAULT_SIZE);
+        frp.set_max_cached_rows(DEFAULT_MAX_CACHED_ROWS);
+    }
+
+    #[profile(Debug)]
+    fn init(
+        network: &frp::Network,
+        api: &Self::Private,
+        app: &Application,
+        model: &Model<E>,
+        style: &StyleWatchFrp,
+    ) {
+        let input = &api.input;
+        let output = &api.output;
+        let scene = &app.display.default_scene;
+        let header = &model.header;
+        let body = &model.body;
+        let background = style.get_color(theme::background);
+        let header_background = style.get_color(theme::header_background);
+        let header_text = style.get_color(theme::header_text);
+        let selection = style.get_color(theme::selection);
+        let hover = style.get_color(theme::hover);
+        let header_height = style.get_number(theme::header_height);
+        let row_height = style.get_number(theme::row_height);
+        let pointer = scene.pointer_position_in_object_space(network, model);
+        let pointer_down = model.display_object().on_event::<mouse::Down>();
+        let pointer_leave = model.display_object().on_event::<mouse::Leave>();
+        let pointer_up = scene.on_event::<mouse::Up>();
+
+        frp::extend! { network
+
+            // === Style and layout ===
+
+            init <- source_();
+            colors_changed <- any_(&init, &background, &selection, &hover);
+            eval_ colors_changed ([model, background, selection, hover]
+                model.set_colors(background.value(), selection.value(), hover.value())
+            );
+            header_colors_changed <- any_(&init, &header_background, &header_text);
+            header.set_entries_params <+ header_colors_changed.map(
+                f_!([header_background, header_text] simple::EntryParams {
+                    bg_color: header_background.value().into(),
+                    text_color: header_text.value().into(),
+                    ..default()
+                })
+            );
+            body.set_entries_params <+ input.set_cell_params;
+            heights_changed <- any_(&init, &header_height, &row_height);
+            heights <- heights_changed.map(
+                f_!([header_height, row_height] (header_height.value(), row_height.value()))
+            );
+            dimensions <- all(input.set_size, heights);
+            eval dimensions (((size, (header_h, row_h))) model.set_dimensions(*size, *header_h, *row_h));
+            output.size <+ input.set_size;
+
+            // The header follows the rows when they are scrolled horizontally.
+            header.scroll_frp().jump_to_x <+ body.scroll_frp().scroll_position_x;
+            scroll_x <- body.scroll_frp().scroll_position_x.sampler();
+
+
+            // === Columns ===
+
+            column_count <- input.set_columns.map(f!((columns) model.set_columns(columns)));
+            header.reset_entries <+ column_count.map(|cols| (1, *cols));
+            body.reset_entries <+ column_count.map2(&input.set_number_of_rows, |c, r| (*r, *c));
+            body.resize_grid <+ input.set_number_of_rows.map2(&column_count, |r, c| (*r, *c));
+            initial_widths <= input.set_columns.map(f_!(model.column_widths()));
+
+            primary_down <- pointer_down.map(|e| e.button() == mouse::PrimaryButton).on_true();
+            header_hit <- pointer.sample(&primary_down).map2(&scroll_x,
+                f!((position, scroll_x) model.header_hit(*position, *scroll_x))
+            );
+            border_pressed <- header_hit.filter_map(|hit| match hit {
+                Some(HeaderHit::Border(col)) => Some(*col),
+                _ => None,
+            });
+            title_clicked <- header_hit.filter_map(|hit| match hit {
+                Some(HeaderHit::Title(col)) => Some(*col),
+                _ => None,
+            });
+            resize_started <- border_pressed.map2(&pointer,
+                f!((col, position) (*col, position.x, model.column_width(*col)))
+            );
+            resizing <- any(...);
+            resizing <+ resize_started.some();
+            resizing <+ pointer_up.constant(None);
+            is_resizing <- resizing.map(|resizing| resizing.is_some());
+            dragged_width <- pointer.gate(&is_resizing).map2(&resizing, |position, resizing| {
+                resizing.map(|(col, start_x, start_width)| (col, start_width + position.x - start_x))
+            }).filter_map(|width| *width);
+
+            width_set <- any(initial_widths, input.set_column_width, dragged_width);
+            width_changed <- width_set.filter_map(f!(((col, width)) model.set_column_width(*col, *width)));
+            header.set_column_width <+ width_changed;
+            body.set_column_width <+ width_changed;
+            eval_ width_changed (model.update_highlights());
+            output.column_resized <+ width_changed;
+
+
+            // === Sorting ===
+
+            clicked_sorting <- title_clicked.map2(&output.sorting, |col, current| {
+                next_sorting(*current, *col)
+            });
+            sorting <- any(clicked_sorting, input.set_sorting).on_change();
+            output.sorting <+ sorting;
+            header.request_model_for_visible_entries <+_ sorting;
+            header.model_for_entry <+ header.model_for_entry_needed.map2(&output.sorting,
+                f!(((row, col), sorting) (*row, *col, model.header_model(*col, *sorting)))
+            );
+
+
+            // === Rows ===
+
+            rows_reset <- any_(&sorting, &input.reset_rows);
+            eval_ rows_reset (model.clear_rows());
+            body.reset_entries <+ body.grid_size.sample(&rows_reset);
+
+            visible_rows <- body.viewport.map(f!((viewport) model.visible_rows(*viewport)));
+            output.currently_visible_range <+ visible_rows.on_change();
+
+            requested_cells <- body.model_for_entry_needed.batch();
+            ready_and_needed <- requested_cells.map(
+                f!((cells) model.ready_cells_and_needed_rows(cells))
+            );
+            ready_cells <- ready_and_needed._0();
+            output.rows_in_range_needed <+ ready_and_needed._1().iter();
+            received_cells <- input.provide_rows_at_range.map4(
+                &visible_rows, &input.set_max_cached_rows, &input.set_number_of_rows,
+                f!([model]((range, rows), visible, max_size, row_count) {
+                    let range = range.clone();
+                    let visible = visible.clone();
+                    model.insert_rows_in_range(range, rows, visible, *max_size, *row_count)
+                })
+            );
+            cells <- any(ready_cells, received_cells);
+            body.model_for_entry <+ cells.iter();
+
+
+            // === Selection ===
+
+            selected <- any(...);
+            selected <+ body.entry_selected.filter_map(|entry| entry.map(|(row, _)| row)).some();
+            selected <+ input.select_row;
+            selected <+ input.select_previous_row.map3(
+                &output.selected_row, &input.set_number_of_rows,
+                |_, row, count| (*count > 0).then(|| row.map_or(0, |row| row.saturating_sub(1)))
+            );
+            selected <+ input.select_next_row.map3(
+                &output.selected_row, &input.set_number_of_rows,
+                |_, row, count| (*count > 0).then(|| row.map_or(0, |row| (row + 1).min(count - 1)))
+            );
+            output.selected_row <+ selected.on_change();
+            eval output.selected_row ((row) model.set_selected_row(*row));
+            selected_to_reveal <- output.selected_row.filter_map(|row| *row);
+            reveal_position <- selected_to_reveal.map2(&body.viewport,
+                f!((row, viewport) model.scroll_position_revealing_row(*row, *viewport))
+            );
+            body.scroll_frp().scroll_to_y <+ reveal_position.filter_map(|position| *position);
+
+            output.row_accepted <+ body.entry_accepted._0();
+            output.row_accepted <+ output.selected_row.sample(&input.accept_selected_row).filter_map(|row| *row);
+
+            hovered <- any(...);
+            hovered <+ body.entry_hovered.map(|entry| entry.map(|(row, _)| row));
+            hovered <+ pointer_leave.constant(None);
+            eval hovered ((row) model.set_hovered_row(*row));
+        }
+        init.emit(());
+    }
+}
+
+
+
+// =================
+// === Component ===
+// =================
+
+/// A virtualized table. See the [crate documentation](crate) for details.
+pub type Table<E> = ComponentView<Model<E>, Frp<<E as Entry>::Model, <E as Entry>::Params>>;
+
+/// A [`Table`] displaying cells as plain texts.
+pub type TextTable = Table<simple::Entry>;
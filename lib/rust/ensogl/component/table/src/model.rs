@@ -0,0 +1,480 @@
+//! The model of the [`Table`](crate::Table) component.
+
+use ensogl_core::display::shape::*;
+use ensogl_core::prelude::*;
+
+use ensogl_core::application::Application;
+use ensogl_core::data::color;
+use ensogl_core::display;
+use ensogl_grid_view as grid_view;
+use ensogl_grid_view::simple;
+use ensogl_grid_view::Col;
+use ensogl_grid_view::Entry;
+use ensogl_grid_view::Row;
+use ensogl_gui_component::component;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The width of columns which were not given any width.
+pub const DEFAULT_COLUMN_WIDTH: f32 = 120.0;
+/// The minimum width a column can be resized to.
+pub const MIN_COLUMN_WIDTH: f32 = 24.0;
+/// The width of the area around a column border in the header which starts resizing the column
+/// when pressed.
+const RESIZE_HANDLE_WIDTH: f32 = 8.0;
+
+
+
+// ==============
+// === Column ===
+// ==============
+
+/// The description of a table column.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Column {
+    pub title: ImString,
+    pub width: f32,
+}
+
+impl Column {
+    /// Constructor.
+    pub fn new(title: impl Into<ImString>, width: f32) -> Self {
+        Self { title: title.into(), width }
+    }
+}
+
+impl Default for Column {
+    fn default() -> Self {
+        Self { title: default(), width: DEFAULT_COLUMN_WIDTH }
+    }
+}
+
+
+
+// =================
+// === SortOrder ===
+// =================
+
+/// The order in which the rows are sorted by a column.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    fn indicator(self) -> &'static str {
+        match self {
+            Self::Ascending => " ▲",
+            Self::Descending => " ▼",
+        }
+    }
+}
+
+/// The sorting applied after clicking the column header while the given sorting is applied. The
+/// clicked column is sorted in ascending order first, then in descending order, and finally the
+/// sorting is removed.
+pub fn next_sorting(current: Option<(Col, SortOrder)>, clicked: Col) -> Option<(Col, SortOrder)> {
+    match current {
+        Some((col, SortOrder::Ascending)) if col == clicked => Some((col, SortOrder::Descending)),
+        Some((col, SortOrder::Descending)) if col == clicked => None,
+        _ => Some((clicked, SortOrder::Ascending)),
+    }
+}
+
+
+
+// =================
+// === HeaderHit ===
+// =================
+
+/// The part of the header row under the pointer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeaderHit {
+    /// The right border of the column.
+    Border(Col),
+    /// The column title.
+    Title(Col),
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+/// The Grid View displaying the header row.
+pub type HeaderGrid = grid_view::scrollable::GridView<simple::Entry>;
+/// The Grid View displaying the table rows.
+pub type BodyGrid<E> = grid_view::scrollable::GridView<E>;
+
+#[allow(missing_docs)]
+#[derive(Derivative, CloneRef, Debug, display::Object)]
+#[derivative(Clone(bound = ""))]
+pub struct Model<E: Entry> {
+    display_object:  display::object::Instance,
+    background:      Rectangle,
+    pub header:      HeaderGrid,
+    pub body:        BodyGrid<E>,
+    selection:       Rectangle,
+    hover:           Rectangle,
+    columns:         Rc<RefCell<Vec<Column>>>,
+    header_height:   Rc<Cell<f32>>,
+    row_height:      Rc<Cell<f32>>,
+    cache:           Rc<RefCell<RowCache<E::Model>>>,
+    /// The cells requested by the [`BodyGrid`] before their rows have become available.
+    expected_cells:  Rc<RefCell<HashMap<Row, HashSet<Col>>>>,
+    /// The highlighted rows, as (selected, hovered).
+    highlighted_row: Rc<Cell<(Option<Row>, Option<Row>)>>,
+}
+
+impl<E: Entry> component::Model for Model<E> {
+    fn label() -> &'static str {
+        "Table"
+    }
+
+    #[profile(Debug)]
+    fn new(app: &Application) -> Self {
+        let display_object = display::object::Instance::new_named("Table");
+        let background = Rectangle();
+        let header = HeaderGrid::new(app);
+        let body = BodyGrid::<E>::new(app);
+        let selection = Rectangle();
+        let hover = Rectangle();
+        display_object.add_child(&background);
+        display_object.add_child(&body);
+        display_object.add_child(&header);
+        for highlight in [&selection, &hover] {
+            highlight.set_pointer_events(false);
+        }
+        Model {
+            display_object,
+            background,
+            header,
+            body,
+            selection,
+            hover,
+            columns: default(),
+            header_height: default(),
+            row_height: default(),
+            cache: default(),
+            expected_cells: default(),
+            highlighted_row: default(),
+        }
+    }
+}
+
+impl<E: Entry> Model<E> {
+    /// Set the dimensions of the table. The table origin is its top-left corner.
+    #[profile(Debug)]
+    pub fn set_dimensions(&self, size: Vector2, header_height: f32, row_height: f32) {
+        self.header_height.set(header_height);
+        self.row_height.set(row_height);
+        self.background.set_size(size);
+        self.background.set_y(-size.y);
+        self.header.set_entries_size(Vector2(DEFAULT_COLUMN_WIDTH, header_height));
+        self.header.resize(Vector2(size.x, header_height));
+        self.body.set_entries_size(Vector2(DEFAULT_COLUMN_WIDTH, row_height));
+        self.body.set_y(-header_height);
+        self.body.resize(Vector2(size.x, (size.y - header_height).max(0.0)));
+        self.update_highlights();
+    }
+
+    /// Set the colors of the table parts which are not rendered by the grid entries.
+    pub fn set_colors(&self, background: color::Rgba, selection: color::Rgba, hover: color::Rgba) {
+        self.background.set_color(background);
+        self.selection.set_color(selection);
+        self.hover.set_color(hover);
+    }
+
+    /// Replace the table columns. Returns the number of columns.
+    pub fn set_columns(&self, columns: &[Column]) -> usize {
+        *self.columns.borrow_mut() = columns.to_vec();
+        self.clear_rows();
+        columns.len()
+    }
+
+    /// The current widths of all columns.
+    pub fn column_widths(&self) -> Vec<(Col, f32)> {
+        self.columns.borrow().iter().map(|column| column.width).enumerate().collect()
+    }
+
+    /// Remember the width of the column, clamped to [`MIN_COLUMN_WIDTH`]. Returns the clamped
+    /// width, or [`None`] if there is no such column.
+    pub fn set_column_width(&self, col: Col, width: f32) -> Option<(Col, f32)> {
+        let mut columns = self.columns.borrow_mut();
+        let column = columns.get_mut(col)?;
+        column.width = width.max(MIN_COLUMN_WIDTH);
+        Some((col, column.width))
+    }
+
+    /// The width of the column, or `0.0` if there is no such column.
+    pub fn column_width(&self, col: Col) -> f32 {
+        self.columns.borrow().get(col).map_or(0.0, |column| column.width)
+    }
+
+    /// The model of the header entry of the column, with the sorting indicator if the table is
+    /// sorted by the column.
+    pub fn header_model(&self, col: Col, sorting: Option<(Col, SortOrder)>) -> simple::EntryModel {
+        let columns = self.columns.borrow();
+        let title = columns.get(col).map(|column| column.title.as_str()).unwrap_or_default();
+        let indicator = sorting.filter(|(sorted, _)| *sorted == col).map(|(_, order)| order);
+        let indicator = indicator.map_or("", SortOrder::indicator);
+        format!("{title}{indicator}").into()
+    }
+
+    /// Check which part of the header row is at the position given in the table space, with the
+    /// table scrolled horizontally by `scroll_x`.
+    pub fn header_hit(&self, position: Vector2, scroll_x: f32) -> Option<HeaderHit> {
+        let in_header = (-self.header_height.get()..=0.0).contains(&position.y);
+        let x = position.x + scroll_x;
+        let columns = self.columns.borrow();
+        let mut right = 0.0;
+        let mut hit = None;
+        for (col, column) in columns.iter().enumerate() {
+            let left = right;
+            right += column.width;
+            if (x - right).abs() <= RESIZE_HANDLE_WIDTH / 2.0 {
+                return in_header.then_some(HeaderHit::Border(col));
+            } else if hit.is_none() && (left..right).contains(&x) {
+                hit = Some(HeaderHit::Title(col));
+            }
+        }
+        hit.filter(|_| in_header)
+    }
+
+    /// The range of rows visible in the viewport of the [`BodyGrid`].
+    pub fn visible_rows(&self, viewport: grid_view::Viewport) -> Range<Row> {
+        let row_height = self.row_height.get();
+        if row_height > 0.0 {
+            let start = (-viewport.top / row_height).floor().max(0.0) as usize;
+            let end = (-viewport.bottom / row_height).ceil().max(0.0) as usize;
+            start..end
+        } else {
+            0..0
+        }
+    }
+
+    /// The vertical scroll position at which the row is fully visible, or [`None`] if it already
+    /// is.
+    pub fn scroll_position_revealing_row(
+        &self,
+        row: Row,
+        viewport: grid_view::Viewport,
+    ) -> Option<f32> {
+        let row_height = self.row_height.get();
+        let row_top = row as f32 * row_height;
+        let row_bottom = row_top + row_height;
+        let (view_top, view_bottom) = (-viewport.top, -viewport.bottom);
+        if row_top < view_top {
+            Some(row_top)
+        } else if row_bottom > view_bottom {
+            Some(row_bottom - (view_bottom - view_top))
+        } else {
+            None
+        }
+    }
+
+
+    // === Rows ===
+
+    /// Split the requested cells into the ones whose rows are already in cache, returned with their
+    /// models, and the ranges of rows which need to be requested.
+    #[profile(Debug)]
+    pub fn ready_cells_and_needed_rows(
+        &self,
+        requested: &[(Row, Col)],
+    ) -> (Vec<(Row, Col, E::Model)>, Vec<Range<Row>>) {
+        let cache = self.cache.borrow();
+        let mut expected = self.expected_cells.borrow_mut();
+        let mut ready = vec![];
+        let mut needed: Vec<Row> = vec![];
+        for &(row, col) in requested {
+            match cache.get(row) {
+                Some(cells) => ready.extend(cells.get(col).map(|cell| (row, col, cell.clone()))),
+                None => {
+                    expected.entry(row).or_default().insert(col);
+                    needed.push(row);
+                }
+            }
+        }
+        (ready, coalesce_rows(needed))
+    }
+
+    /// Update the cache with the rows provided for the range. Returns the models of cells which
+    /// were requested before and have now become available.
+    #[profile(Debug)]
+    pub fn insert_rows_in_range(
+        &self,
+        range: Range<Row>,
+        rows: &[Vec<E::Model>],
+        visible: Range<Row>,
+        max_cache_size: usize,
+        row_count: usize,
+    ) -> Vec<(Row, Col, E::Model)> {
+        let start = range.start.min(row_count);
+        let end = range.end.min(row_count).min(start + rows.len());
+        let mut cache = self.cache.borrow_mut();
+        cache.insert(start..end, &rows[0..end - start], visible, max_cache_size);
+        let mut expected = self.expected_cells.borrow_mut();
+        let mut ready = vec![];
+        for row in start..end {
+            if let Some(cols) = expected.remove(&row) {
+                let cells = cache.get(row).into_iter().flatten().enumerate();
+                let cells = cells.filter(|(col, _)| cols.contains(col));
+                ready.extend(cells.map(|(col, cell)| (row, col, cell.clone())));
+            }
+        }
+        ready
+    }
+
+    /// Forget all cached and expected rows.
+    pub fn clear_rows(&self) {
+        self.cache.borrow_mut().clear();
+        self.expected_cells.borrow_mut().clear();
+    }
+
+    /// The number of rows kept in cache.
+    pub fn cached_row_count(&self) -> usize {
+        self.cache.borrow().len()
+    }
+
+
+    // === Highlights ===
+
+    /// Set the selected row.
+    pub fn set_selected_row(&self, row: Option<Row>) {
+        self.highlighted_row.modify_(|(selected, _)| *selected = row);
+        self.update_highlights();
+    }
+
+    /// Set the row under the pointer.
+    pub fn set_hovered_row(&self, row: Option<Row>) {
+        self.highlighted_row.modify_(|(_, hovered)| *hovered = row);
+        self.update_highlights();
+    }
+
+    /// Update the highlights of the selected and hovered rows. They span the whole content width
+    /// and are displayed in the [`BodyGrid`] space, so they are scrolled along with the rows.
+    pub fn update_highlights(&self) {
+        let (selected, hovered) = self.highlighted_row.get();
+        let width = self.columns.borrow().iter().map(|column| column.width).sum::<f32>();
+        let row_height = self.row_height.get();
+        let grid: &grid_view::GridView<E> = &self.body;
+        for (highlight, row) in [(&self.selection, selected), (&self.hover, hovered)] {
+            match row {
+                Some(row) => {
+                    highlight.set_size(Vector2(width, row_height));
+                    highlight.set_xy(Vector2(0.0, -(row as f32 + 1.0) * row_height));
+                    grid.add_child(highlight);
+                }
+                None => highlight.unset_parent(),
+            }
+        }
+    }
+}
+
+
+
+/// Convert the row indices to the sorted list of ranges covering them.
+fn coalesce_rows(mut rows: Vec<Row>) -> Vec<Range<Row>> {
+    rows.sort_unstable();
+    rows.dedup();
+    let mut ranges: Vec<Range<Row>> = vec![];
+    for row in rows {
+        if let Some(range) = ranges.last_mut() && range.end == row {
+            range.end = row + 1;
+        } else {
+            ranges.push(row..row + 1);
+        }
+    }
+    ranges
+}
+
+
+
+// ================
+// === RowCache ===
+// ================
+
+/// A cache for the rows of the table, storing the cells of each row by its index.
+#[derive(Debug)]
+struct RowCache<T> {
+    rows: HashMap<Row, Vec<T>>,
+}
+
+impl<T> Default for RowCache<T> {
+    fn default() -> Self {
+        Self { rows: default() }
+    }
+}
+
+impl<T: Clone> RowCache<T> {
+    fn insert(
+        &mut self,
+        range: Range<Row>,
+        rows: &[Vec<T>],
+        visible: Range<Row>,
+        max_cache_size: usize,
+    ) {
+        let max_cache_size = max_cache_size.max(visible.len());
+        if self.rows.len() + range.len() > max_cache_size {
+            self.rows.retain(|row, _| visible.contains(row));
+        }
+        for (index, row) in range.zip(rows) {
+            self.rows.insert(index, row.clone());
+        }
+    }
+
+    fn get(&self, row: Row) -> Option<&Vec<T>> {
+        self.rows.get(&row)
+    }
+
+    fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    fn len(&self) -> usize {
+        self.rows.len()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_clicks_cycle_sorting() {
+        use SortOrder::*;
+        assert_eq!(next_sorting(None, 1), Some((1, Ascending)));
+        assert_eq!(next_sorting(Some((1, Ascending)), 1), Some((1, Descending)));
+        assert_eq!(next_sorting(Some((1, Descending)), 1), None);
+        assert_eq!(next_sorting(Some((1, Descending)), 0), Some((0, Ascending)));
+    }
+
+    #[test]
+    fn requested_rows_are_coalesced_and_cached() {
+        assert_eq!(coalesce_rows(vec![7, 3, 4, 3]), [3..5, 7..8]);
+        let mut cache = RowCache::default();
+        cache.insert(0..4, &[vec![0], vec![1], vec![2], vec![3]], 0..2, 4);
+        assert_eq!(cache.len(), 4);
+        cache.insert(10..12, &[vec![10], vec![11]], 2..4, 4);
+        assert_eq!(cache.len(), 4);
+        assert_eq!(cache.get(0), None);
+        assert_eq!(cache.get(3), Some(&vec![3]));
+        assert_eq!(cache.get(11), Some(&vec![11]));
+    }
+}
@@ -12,17 +12,27 @@ use enso_font::NonVariableFaceHeader;
 use enso_frp as frp;
 use enso_text::text;
 use enso_text::text::BoundsError;
+use ensogl_core::animation::idle;
+use ensogl_core::system::web;
 
 
 // ==============
 // === Export ===
 // ==============
 
+pub mod ansi;
+pub mod bracket;
 pub mod formatting;
+pub mod html;
+pub mod indent;
 pub mod index;
+pub mod line_transform;
 pub mod movement;
 pub mod rope;
+pub mod search;
 pub mod selection;
+pub mod styled_text;
+pub mod substitution;
 
 
 
@@ -31,9 +41,18 @@ pub mod traits {
     pub use enso_text::traits::*;
 }
 
+pub use bracket::BracketPair;
 pub use formatting::*;
+pub use indent::IndentUnit;
+pub use line_transform::LineTransform;
 pub use movement::*;
+pub use rope::word::WordMode;
+pub use rope::word::WordPolicy;
+pub use search::Query;
+pub use search::SearchOptions;
 pub use selection::Selection;
+pub use styled_text::StyledText;
+pub use substitution::Substitutions;
 
 pub use enso_text::index::*;
 pub use enso_text::unit::*;
@@ -56,10 +75,56 @@ pub struct History {
 /// Internal representation of `History`.
 #[derive(Debug, Clone, Default)]
 pub struct HistoryData {
-    undo_stack: Vec<(Rope, Formatting, selection::Group)>,
-    #[allow(dead_code)]
-    /// Not yet implemented.
-    redo_stack: Vec<(Rope, Formatting, selection::Group)>,
+    undo_stack:           Vec<Snapshot>,
+    redo_stack:           Vec<Snapshot>,
+    /// The number of open transactions. See [`BufferModel::begin_transaction`].
+    transaction_depth:    usize,
+    /// Whether the modifications of the open transaction were already recorded.
+    transaction_recorded: bool,
+    /// The last typed character, which the next typed character can be coalesced with. See
+    /// [`BufferModel::insert_typed`].
+    last_typed:           Option<TypedInsertion>,
+}
+
+impl History {
+    fn begin_transaction(&self) {
+        let mut data = self.data.borrow_mut();
+        if data.transaction_depth == 0 {
+            data.transaction_recorded = false;
+        }
+        data.transaction_depth += 1;
+    }
+
+    fn end_transaction(&self) {
+        let mut data = self.data.borrow_mut();
+        data.transaction_depth = data.transaction_depth.saturating_sub(1);
+    }
+
+    /// Begin a transaction extending the last recorded modification.
+    fn continue_last_modification(&self) {
+        self.begin_transaction();
+        self.data.borrow_mut().transaction_recorded = true;
+    }
+}
+
+/// A character typed by the user, recorded to coalesce the consecutive typed characters into a
+/// single undoable modification.
+#[derive(Debug, Clone)]
+struct TypedInsertion {
+    view_id:    ViewId,
+    /// The time of typing, in milliseconds.
+    time:       f64,
+    /// The selections after typing. Moving the cursors ends the coalescing.
+    selections: Vec<Selection>,
+}
+
+/// The text, formatting, and selections of a buffer at some point in time. Thanks to the
+/// persistent rope, snapshots are cheap to take and keep.
+#[derive(Debug, Clone, Default)]
+pub struct Snapshot {
+    text:      Rope,
+    style:     Formatting,
+    selection: selection::Group,
 }
 
 
@@ -118,6 +183,67 @@ impl<Metric: Default, Str: Default> Default for Change<Metric, Str> {
     }
 }
 
+impl Change {
+    /// Transform the byte offset in the text before this change to the byte offset in the text
+    /// after this change. Offsets before the changed range are not affected, offsets after it are
+    /// shifted, and offsets inside it are kept, but clamped to the end of the inserted text.
+    pub fn transform_byte(&self, byte: Byte) -> Byte {
+        let range = self.change.range;
+        let inserted_end = Byte(range.start.value + self.change.text.last_byte_index().value);
+        if byte <= range.start {
+            byte
+        } else if byte >= range.end {
+            Byte(inserted_end.value + (byte.value - range.end.value))
+        } else {
+            std::cmp::min(byte, inserted_end)
+        }
+    }
+}
+
+
+
+// ===============
+// === ViewHub ===
+// ===============
+
+/// Identifier of a buffer view, unique among all views of the same buffer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ViewId(usize);
+
+/// Connection between all views of the same buffer. Every view announces its text modification
+/// before it is applied and broadcasts the changes afterwards, so the other views can keep their
+/// selections in place and redraw the modified text.
+#[derive(Clone, CloneRef, Debug)]
+pub struct ViewHub {
+    network:      frp::Network,
+    next_view_id: Rc<Cell<usize>>,
+    will_change:  frp::Source<ViewId>,
+    changed:      frp::Source<(ViewId, Rc<Vec<Change>>)>,
+}
+
+impl ViewHub {
+    /// Constructor. The view id 0 is reserved for the view the hub is created with.
+    pub fn new() -> Self {
+        let network = frp::Network::new("buffer::ViewHub");
+        frp::extend! { network
+            will_change <- source();
+            changed <- source();
+        }
+        let next_view_id = Rc::new(Cell::new(1));
+        Self { network, next_view_id, will_change, changed }
+    }
+
+    fn new_view_id(&self) -> ViewId {
+        ViewId(self.next_view_id.replace(self.next_view_id.get() + 1))
+    }
+}
+
+impl Default for ViewHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 
 
 // ===========
@@ -131,15 +257,68 @@ ensogl_core::define_endpoints_2! {
         set_cursor                 (Location),
         add_cursor                 (Location),
         set_single_selection       (selection::Shape),
+        /// Replace all selections, for example to restore them after a reload. Locations out of
+        /// the text are snapped to it.
+        set_selections             (selection::Group),
         set_newest_selection_end   (Location),
         set_oldest_selection_end   (Location),
         insert                     (ImString),
+        /// Insert the text typed by the user, applying the substitution rules. Every substitution
+        /// is undoable separately from the typed text.
+        insert_typed               (ImString),
+        set_substitutions          (Option<Substitutions>),
+        set_word_policy            (WordPolicy),
+        /// Coalesce the single characters typed in the given time window, in milliseconds, into a
+        /// single undoable modification. Set [`None`] (the default) to record every character
+        /// separately.
+        set_undo_coalescing_window (Option<f32>),
+        /// Begin a transaction. All modifications until the matching [`end_transaction`] are
+        /// recorded as a single undoable modification. Transactions can be nested.
+        begin_transaction          (),
+        end_transaction            (),
         paste                      (Rc<Vec<String>>),
+        apply_changes              (Rc<Vec<text::Change>>),
+        /// Apply the changes like [`apply_changes`], recording them in the undo history as a single
+        /// undoable modification. The range of every change is expressed in the text modified by
+        /// the previous changes.
+        apply_recorded_changes     (Rc<Vec<text::Change>>),
         remove_all_cursors         (),
         delete_left                (),
         delete_right               (),
         delete_word_left           (),
         delete_word_right          (),
+        /// Transform the lines covered by the selections. The transformation is a single undoable
+        /// modification.
+        transform_lines            (LineTransform),
+        /// Insert the indentation unit at the beginning of the lines covered by the selections. The
+        /// empty lines are not indented, unless the selection covers a single line. The selections
+        /// keep covering the same text. The indentation is a single undoable modification.
+        indent_selection           (),
+        /// Remove a single level of indentation from the beginning of the lines covered by the
+        /// selections. See [`IndentUnit::outdent_len`].
+        outdent_selection          (),
+        set_indent_unit            (IndentUnit),
+        /// Swap the lines covered by the selections with the line above them. The selections keep
+        /// covering the same text. Nothing is moved if the first line is covered. The move is a
+        /// single undoable modification.
+        move_lines_up              (),
+        /// Swap the lines covered by the selections with the line below them. See
+        /// [`move_lines_up`].
+        move_lines_down            (),
+        /// Insert a copy of the selected text after every selection, and select the copy. For a
+        /// cursor, the whole line is copied below it, and the cursor is moved to the copy. The
+        /// duplication is a single undoable modification.
+        duplicate_selection        (),
+        /// Add the comment prefix to the lines covered by the selections, or remove it if all of
+        /// them start with the prefix. The blank lines are skipped. The prefix is added after the
+        /// smallest indentation of the lines, and found after the indentation of every line. The
+        /// trailing whitespace of the prefix is optional when removing it. The toggle is a single
+        /// undoable modification.
+        toggle_line_comment        (ImString),
+        /// Move the text in the range to the location, or copy it if the flag is set, and select
+        /// the text at its new place. Nothing happens if the text is dropped inside of the range.
+        /// The drop is a single undoable modification.
+        drop_text                  (Range<Location>, Location, bool),
         clear_selection            (),
         keep_first_selection_only  (),
         keep_last_selection_only   (),
@@ -151,18 +330,31 @@ ensogl_core::define_endpoints_2! {
         keep_newest_cursor_only    (),
         undo                       (),
         redo                       (),
+        /// Restore the text, formatting, and selections from the snapshot. The change is not
+        /// recorded in the undo history.
+        restore_snapshot           (Snapshot),
         set_property               (Rc<Vec<Range<Byte>>>, Option<Property>),
+        /// Set the properties of many ranges in a single formatting update.
+        set_properties             (Rc<Vec<(Range<Byte>, Property)>>),
         mod_property               (Rc<Vec<Range<Byte>>>, Option<PropertyDiff>),
         set_property_default       (Option<ResolvedProperty>),
+        set_default_format         (Format),
         set_first_view_line        (Line),
         mod_first_view_line        (LineDiff),
+        set_view_line_count        (Option<usize>),
     }
 
     Output {
         selection_edit_mode     (Modification),
         selection_non_edit_mode (selection::Group),
         text_change             (Rc<Vec<Change>>),
+        /// Text changes made by other views of this buffer. Selections of this view are already
+        /// moved to keep them in place when this event is emitted.
+        remote_text_change      (Rc<Vec<Change>>),
         first_view_line         (Line),
+        /// The bracket pair next to the end of the newest selection, if any. Emitted after every
+        /// selection change.
+        matching_brackets       (Option<BracketPair>),
     }
 }
 
@@ -195,6 +387,7 @@ impl Buffer {
 
         frp::extend! { network
             mod_on_insert <- input.insert.map(f!((s) m.insert(s)));
+            mod_on_insert_typed <- input.insert_typed.map(f!((s) m.insert_typed(s)));
             mod_on_paste <- input.paste.map(f!((s) m.paste(s)));
             mod_on_delete_left <- input.delete_left.map(f_!(m.delete_left()));
             mod_on_delete_right <- input.delete_right.map(f_!(m.delete_right()));
@@ -202,9 +395,26 @@ impl Buffer {
             mod_on_delete_word_right <- input.delete_word_right.map(f_!(m.delete_word_right()));
             mod_on_delete <- any(mod_on_delete_left, mod_on_delete_right, mod_on_delete_word_left,
                 mod_on_delete_word_right);
-            any_mod <- any(mod_on_insert, mod_on_paste, mod_on_delete);
+            mod_on_apply <- input.apply_changes.map(f!((changes) m.apply_changes(changes)));
+            mod_on_transform <- input.transform_lines.map(f!((t) m.transform_lines(*t)));
+            mod_on_apply_recorded <- input.apply_recorded_changes.map(
+                f!((changes) m.apply_recorded_changes(changes))
+            );
+            mod_on_indent <- input.indent_selection.map(f_!(m.indent_lines(false)));
+            mod_on_outdent <- input.outdent_selection.map(f_!(m.indent_lines(true)));
+            mod_on_move_up <- input.move_lines_up.map(f_!(m.move_lines(true)));
+            mod_on_move_down <- input.move_lines_down.map(f_!(m.move_lines(false)));
+            mod_on_duplicate <- input.duplicate_selection.map(f_!(m.duplicate_selections()));
+            mod_on_comment <- input.toggle_line_comment.map(f!((p) m.toggle_line_comment(p)));
+            mod_on_drop <- input.drop_text.map(f!(((r, t, c)) m.drop_text(*r, *t, *c)));
+            any_mod <- any(mod_on_insert, mod_on_insert_typed, mod_on_paste, mod_on_delete,
+                mod_on_apply, mod_on_transform);
+            any_mod <- any(any_mod, mod_on_apply_recorded, mod_on_indent, mod_on_outdent);
+            any_mod <- any(any_mod, mod_on_move_up, mod_on_move_down, mod_on_duplicate);
+            any_mod <- any(any_mod, mod_on_comment, mod_on_drop);
             changed <- any_mod.map(|m| !m.changes.is_empty());
             output.text_change <+ any_mod.gate(&changed).map(|m| Rc::new(m.changes.clone()));
+            eval output.text_change ((changes) m.views.changed.emit((m.view_id, changes.clone())));
 
             sel_on_move <- input.cursors_move.map(f!((t) m.moved_selection(*t,false)));
             sel_on_mod <- input.cursors_select.map(f!((t) m.moved_selection(*t,true)));
@@ -229,15 +439,28 @@ impl Buffer {
             sel_on_set_oldest_end <- input.set_oldest_selection_end.map
                 (f!((t) m.set_oldest_selection_end(*t)));
 
+            sel_on_set_selections <- input.set_selections.map(f!((t) m.restored_selections(t)));
             sel_on_remove_all <- input.remove_all_cursors.map(|_| default());
             sel_on_undo <= input.undo.map(f_!(m.undo()));
+            sel_on_redo <= input.redo.map(f_!(m.redo()));
+            sel_on_restore <- input.restore_snapshot.map(f!((s) m.restore_snapshot(s.clone())));
 
             eval input.set_property (((range,value)) m.set_property(range,*value));
+            eval input.set_properties ((spans) m.set_properties(spans));
             eval input.mod_property (((range,value)) m.mod_property(range,*value));
             eval input.set_property_default ((prop) m.set_property_default(*prop));
+            eval input.set_default_format ((format) m.set_default_format(*format));
+            eval input.set_substitutions ((rules) *m.substitutions.borrow_mut() = rules.clone());
+            eval input.set_word_policy ((policy) *m.word_policy.borrow_mut() = policy.clone());
+            eval input.set_undo_coalescing_window ((t) m.undo_coalescing_window.set(*t));
+            eval input.set_indent_unit ((unit) m.indent_unit.set(*unit));
+            eval_ input.begin_transaction (m.begin_transaction());
+            eval_ input.end_transaction (m.end_transaction());
 
             output.selection_edit_mode <+ any_mod;
             output.selection_non_edit_mode <+ sel_on_undo;
+            output.selection_non_edit_mode <+ sel_on_redo;
+            output.selection_non_edit_mode <+ sel_on_restore;
             output.selection_non_edit_mode <+ sel_on_move;
             output.selection_non_edit_mode <+ sel_on_mod;
             output.selection_non_edit_mode <+ sel_on_clear;
@@ -254,10 +477,20 @@ impl Buffer {
             output.selection_non_edit_mode <+ sel_on_set_single_selection;
             output.selection_non_edit_mode <+ sel_on_set_newest_end;
             output.selection_non_edit_mode <+ sel_on_set_oldest_end;
+            output.selection_non_edit_mode <+ sel_on_set_selections;
             output.selection_non_edit_mode <+ sel_on_remove_all;
 
             eval output.selection_edit_mode ((t) m.set_selection(&t.selection_group));
             eval output.selection_non_edit_mode ((t) m.set_selection(t));
+            selection_changed <- any_(&output.selection_edit_mode, &output.selection_non_edit_mode);
+            output.matching_brackets <+ selection_changed.map(f_!(m.newest_matching_brackets()));
+
+            // === Changes Made By Other Views ===
+
+            eval m.views.will_change ((view) m.on_view_will_change(*view));
+            remote_change <= m.views.changed.map(f!(((view, t)) m.on_view_changed(*view, t)));
+            output.remote_text_change <+ remote_change;
+            output.selection_non_edit_mode <+ remote_change.map(f_!(m.selections()));
 
             // === Buffer Area Management ===
 
@@ -267,6 +500,8 @@ impl Buffer {
             new_first_view_line <- input.mod_first_view_line.map
                 (f!((diff) m.mod_first_view_line(*diff)));
             output.first_view_line <+ new_first_view_line;
+
+            eval input.set_view_line_count ((count) m.view_line_count.set(*count));
         }
         Self { model, frp }
     }
@@ -291,13 +526,28 @@ pub struct BufferModel {
 #[derive(Debug, Deref, Default)]
 pub struct BufferModelData {
     #[deref]
-    pub rope:          FormattedRope,
-    pub selection:     RefCell<selection::Group>,
-    next_selection_id: Cell<selection::Id>,
-    pub history:       History,
+    pub rope:               FormattedRope,
+    pub selection:          RefCell<selection::Group>,
+    next_selection_id:      Cell<selection::Id>,
+    pub history:            History,
     /// The line that corresponds to `ViewLine(0)`.
-    first_view_line:   Cell<Line>,
-    view_line_count:   Cell<Option<usize>>,
+    first_view_line:        Cell<Line>,
+    view_line_count:        Cell<Option<usize>>,
+    /// Connection with other views of the same text. See [`BufferModel::new_view`].
+    views:                  ViewHub,
+    view_id:                ViewId,
+    /// Selections expressed in bytes, remembered before another view modifies the text.
+    saved_selections:       RefCell<Option<Vec<Selection<Byte>>>>,
+    /// Whether the formatting compaction was scheduled in the idle time.
+    compaction_queued:      Cell<bool>,
+    /// Substitution rules applied to the typed text. See [`BufferModel::insert_typed`].
+    substitutions:          RefCell<Option<Substitutions>>,
+    /// The characters forming words, used by the word movement, selection, and deletion.
+    word_policy:            RefCell<WordPolicy>,
+    /// See [`Input::set_undo_coalescing_window`].
+    undo_coalescing_window: Cell<Option<f32>>,
+    /// See [`Input::set_indent_unit`].
+    indent_unit:            Cell<IndentUnit>,
 }
 
 impl BufferModel {
@@ -308,6 +558,71 @@ impl BufferModel {
 }
 
 
+// === Views ===
+
+impl BufferModel {
+    /// Create a new view of this buffer. The view shares the text, formatting, and history with
+    /// this buffer, but has its own selections and visible area. Text changes made in any view are
+    /// propagated to all other views of the same buffer. The editing settings, like the
+    /// substitution rules or the word policy, are not shared and start with their defaults.
+    pub fn new_view(&self) -> BufferModel {
+        let rope = self.rope.clone_ref();
+        let history = self.history.clone_ref();
+        let views = self.views.clone_ref();
+        let view_id = views.new_view_id();
+        let data = BufferModelData {
+            rope,
+            selection: default(),
+            next_selection_id: default(),
+            history,
+            first_view_line: default(),
+            view_line_count: default(),
+            views,
+            view_id,
+            saved_selections: default(),
+            compaction_queued: default(),
+            substitutions: default(),
+            word_policy: default(),
+            undo_coalescing_window: default(),
+            indent_unit: default(),
+        };
+        Self { data: Rc::new(data) }
+    }
+
+    /// Identifier of this view, unique among all views of the same buffer.
+    pub fn view_id(&self) -> ViewId {
+        self.view_id
+    }
+
+    /// Announce to other views that the text is about to be modified by this view.
+    fn announce_modification(&self) {
+        self.views.will_change.emit(self.view_id);
+    }
+
+    fn on_view_will_change(&self, view: ViewId) {
+        if view != self.view_id {
+            *self.saved_selections.borrow_mut() = Some(self.byte_selections());
+        }
+    }
+
+    /// Move selections to keep them in place after another view modified the text. Returns the
+    /// changes if they were made by another view.
+    fn on_view_changed(&self, view: ViewId, changes: &Rc<Vec<Change>>) -> Option<Rc<Vec<Change>>> {
+        (view != self.view_id).then(|| {
+            let saved_selections = self.saved_selections.take();
+            let byte_selections = saved_selections.unwrap_or_else(|| self.byte_selections());
+            let selections = byte_selections.into_iter().map(|selection| {
+                let transform = |byte| changes.iter().fold(byte, |t, c| c.transform_byte(t));
+                let selection = selection.map(transform);
+                Selection::<Location>::from_in_context_snapped(self, selection)
+            });
+            self.set_selection(&selections.collect());
+            changes.clone()
+        })
+    }
+}
+
+
 // === Location ===
 
 impl BufferModel {
@@ -318,7 +633,8 @@ impl BufferModel {
         Range { start, end }
     }
 
-    /// Get the previous column of the provided location.
+    /// Get the previous column of the provided location. Columns are counted in grapheme clusters,
+    /// so a character with combining marks is always skipped as a whole.
     pub fn prev_column(&self, location: Location) -> Location {
         // Column can be bigger than last line column if the cursor moved from longer line to a
         // shorter one. We keep the bigger column in the cursor, so if it moves back to the longer
@@ -335,7 +651,7 @@ impl BufferModel {
         }
     }
 
-    /// Get the next column of the provided location.
+    /// Get the next column of the provided location. See [`Self::prev_column`].
     pub fn next_column(&self, location: Location) -> Location {
         let desired_column = location.offset + Column(1);
         if desired_column <= self.line_last_column(location.line) {
@@ -494,6 +810,24 @@ impl BufferModel {
         group
     }
 
+    /// The selections snapped to the text. The next selection id is bumped above the ids of the
+    /// selections, so new selections do not collide with them.
+    fn restored_selections(&self, group: &selection::Group) -> selection::Group {
+        let snap = |location: Location| self.snap_location(location);
+        let selections = group.iter().map(|s| Selection(snap(s.start), snap(s.end), s.id));
+        let group: selection::Group = selections.collect();
+        let max_id = group.iter().map(|s| s.id.value + 1).max().unwrap_or_default();
+        let next_id = self.next_selection_id.get().value.max(max_id);
+        self.next_selection_id.set(selection::Id { value: next_id });
+        group
+    }
+
+    /// The location snapped to the text.
+    fn snap_location(&self, location: Location) -> Location {
+        let offset = Byte::from_in_context_snapped(self, location);
+        Location::from_in_context_snapped(self, offset)
+    }
+
     /// Current selections expressed in bytes.
     pub fn byte_selections(&self) -> Vec<Selection<Byte>> {
         let selections = self.selection.borrow().clone();
@@ -518,6 +852,15 @@ impl BufferModel {
         }
         result
     }
+
+    /// Return all selections as a single HTML fragment preserving the formatting of the text. See
+    /// [`html::chunks_to_html`] to learn more.
+    pub fn selections_html(&self) -> String {
+        let selections = self.byte_selections();
+        let contents = self.selections_contents();
+        let styles = selections.iter().map(|selection| self.rope.sub_style(selection.range()));
+        html::chunks_to_html(&contents.into_iter().zip(styles).collect_vec())
+    }
 }
 
 
@@ -544,6 +887,84 @@ impl BufferModel {
         self.modify_selections(iter::repeat(text.into()), None)
     }
 
+    /// Insert the text typed by the user in the place of current selections / cursors, and apply
+    /// the substitution rules at every cursor. The substitutions are recorded in the undo history
+    /// after the typed text, so undoing them brings the typed text back.
+    ///
+    /// A single character typed at the cursors shortly after the previous one is recorded in the
+    /// undo history together with it, see [`Input::set_undo_coalescing_window`].
+    fn insert_typed(&self, text: &str) -> Modification {
+        let snapshot_before = self.snapshot();
+        let coalesced = self.can_coalesce_typing(text);
+        if coalesced {
+            self.history.continue_last_modification();
+        }
+        let mut modification = self.insert(text);
+        if coalesced {
+            self.history.end_transaction();
+        }
+        let substitutions = self.substitutions.borrow().clone();
+        let Some(substitutions) = substitutions else {
+            self.record_typing(text, &modification);
+            return modification;
+        };
+        self.set_selection(&modification.selection_group);
+        let changes = self.byte_selections().into_iter().filter_map(|selection| {
+            let cursor = selection.end;
+            let line_start = self.line_offset_snapped(self.line_snapped(cursor));
+            let before_cursor = self.rope.text.sub(Range::new(line_start, cursor)).to_string();
+            let substitution = substitutions.find(&before_cursor, text)?;
+            let start = Byte(cursor.value - substitution.len);
+            let range = Range::new(start, cursor);
+            Some(text::Change { range, text: (&substitution.replacement).into() })
+        });
+        let changes = changes.collect_vec();
+        if changes.is_empty() {
+            self.record_typing(text, &modification);
+        } else {
+            // The character triggering the substitution is undoable separately from the
+            // characters it was coalesced with, just like the substitution itself.
+            if coalesced {
+                self.history.data.borrow_mut().undo_stack.push(snapshot_before);
+            }
+            let substituted = self.apply_recorded_changes_from_end(changes);
+            modification.changes.extend(substituted.changes);
+            modification.selection_group = substituted.selection_group;
+            modification.byte_offset += substituted.byte_offset;
+        }
+        modification
+    }
+
+    /// Check whether the typed text can be coalesced with the previously typed character: it is a
+    /// single character typed in the same view within the coalescing window, and the cursors did
+    /// not move since then.
+    fn can_coalesce_typing(&self, text: &str) -> bool {
+        let Some(window) = self.undo_coalescing_window.get() else { return false };
+        let history = self.history.data.borrow();
+        let Some(last_typed) = &history.last_typed else { return false };
+        let is_single_char = text.chars().count() == 1;
+        let selections = self.selection.borrow().iter().copied().collect_vec();
+        let elapsed = web::time_from_start() - last_typed.time;
+        is_single_char
+            && history.transaction_depth == 0
+            && last_typed.view_id == self.view_id
+            && last_typed.selections == selections
+            && elapsed <= window as f64
+    }
+
+    /// Remember the typed text, so the next typed character can be coalesced with it.
+    fn record_typing(&self, text: &str, modification: &Modification) {
+        let selections = modification.selection_group.iter().copied().collect_vec();
+        let is_single_char = text.chars().count() == 1;
+        let is_typed_at_cursors = selections.iter().all(|s| s.is_cursor());
+        let last_typed = (is_single_char && is_typed_at_cursors).then(|| TypedInsertion {
+            view_id: self.view_id,
+            time: web::time_from_start(),
+            selections,
+        });
+        self.history.data.borrow_mut().last_typed = last_typed;
+    }
+
     /// Paste new text in the place of current selections / cursors. In case of pasting multiple
     /// chunks (e.g. after copying multiple selections), the chunks will be pasted into subsequent
     /// selections. In case there are more chunks than selections, end chunks will be dropped. In
@@ -583,6 +1004,7 @@ impl BufferModel {
     fn modify_selections<I>(&self, mut iter: I, transform: Option<Transform>) -> Modification
     where I: Iterator<Item = Rope> {
         self.commit_history();
+        self.announce_modification();
         let mut modification = Modification::default();
         for rel_byte_selection in self.byte_selections() {
             let text = iter.next().unwrap_or_default();
@@ -593,6 +1015,363 @@ impl BufferModel {
         modification
     }
 
+    /// Apply the text changes, for example, the ones made in an external text model. Every change
+    /// range is expressed in the text after applying the previous changes. Selections are moved to
+    /// keep them in place, the same way as when the text is modified by another view of this
+    /// buffer. Please note that the changes are not recorded in the undo history.
+    fn apply_changes(&self, changes: &[text::Change]) -> Modification {
+        self.announce_modification();
+        let mut byte_selections = self.byte_selections();
+        let mut modification = Modification::default();
+        for change in changes {
+            let range = self.crop_byte_range(change.range);
+            let byte_selection = Selection::new(range.start, range.end, default());
+            let line_selection =
+                Selection::<ViewLocation>::from_in_context_snapped(self, byte_selection);
+            let line_selection = line_selection.map_shape(|s| s.normalized());
+            let loc_selection =
+                Selection::<Location>::from_in_context_snapped(self, byte_selection);
+            self.rope.replace(range, &change.text);
+
+            let redraw_start_line = loc_selection.start.line;
+            let redraw_end_line = loc_selection.end.line;
+            let line_diff = redraw_start_line + change.text.last_line_index() - redraw_end_line;
+            let text = change.text.clone();
+            let change = text::Change { range, text };
+            let change_range = redraw_start_line..=redraw_end_line;
+            let change = Change { change, change_range, line_diff, selection: line_selection };
+            for selection in &mut byte_selections {
+                *selection = selection.map(|byte| change.transform_byte(byte));
+            }
+            modification.byte_offset += change.text.last_byte_index().to_diff() - range.size();
+            modification.changes.push(change);
+        }
+        modification.selection_group = byte_selections
+            .into_iter()
+            .map(|selection| Selection::<Location>::from_in_context_snapped(self, selection))
+            .collect();
+        modification
+    }
+
+    /// Apply the changes, recording them in the undo history as a single modification.
+    fn apply_recorded_changes(&self, changes: &[text::Change]) -> Modification {
+        self.commit_history();
+        self.apply_changes(changes)
+    }
+
+    /// Apply the changes, recording them in the undo history as a single modification, unless
+    /// there are no changes. Unlike in [`Self::apply_recorded_changes`], the ranges of all changes
+    /// are expressed in the current text, and they must not overlap. See
+    /// [`Self::sort_changes_from_end`].
+    fn apply_recorded_changes_from_end(&self, mut changes: Vec<text::Change>) -> Modification {
+        Self::sort_changes_from_end(&mut changes);
+        if !changes.is_empty() {
+            self.commit_history();
+        }
+        self.apply_changes(&changes)
+    }
+
+    /// Sort the changes expressed in the current text, so they are applied from the end of the
+    /// text. Applying a change moves the text after it, so it does not affect the byte ranges of
+    /// the changes not applied yet. The changes starting at the same offset keep their order.
+    fn sort_changes_from_end(changes: &mut [text::Change]) {
+        changes.sort_by(|a, b| b.range.start.cmp(&a.range.start));
+    }
+
+    /// The inclusive ranges of the lines covered by the selections, sorted. A selection ending at
+    /// the beginning of a line does not cover that line. Overlapping line ranges of many
+    /// selections are merged. If `extend_single_line` is set, the ranges of a single line include
+    /// the following line as well.
+    fn selected_line_ranges(&self, extend_single_line: bool) -> Vec<(usize, usize)> {
+        let last_line = self.last_line_index().value;
+        let mut line_ranges = self
+            .byte_selections()
+            .into_iter()
+            .map(|selection| {
+                let start = std::cmp::min(selection.start, selection.end);
+                let end = std::cmp::max(selection.start, selection.end);
+                let start_line = self.line_snapped(start).value;
+                let mut end_line = self.line_snapped(end).value;
+                if end > start
+                    && end_line > start_line
+                    && end == self.line_offset_snapped(Line(end_line))
+                {
+                    end_line -= 1;
+                }
+                if start_line == end_line && extend_single_line {
+                    end_line = std::cmp::min(end_line + 1, last_line);
+                }
+                (start_line, end_line)
+            })
+            .collect_vec();
+        line_ranges.sort_unstable();
+        let mut merged_line_ranges: Vec<(usize, usize)> = default();
+        for (start, end) in line_ranges {
+            match merged_line_ranges.last_mut() {
+                Some(last) if start <= last.1 => last.1 = std::cmp::max(last.1, end),
+                _ => merged_line_ranges.push((start, end)),
+            }
+        }
+        merged_line_ranges
+    }
+
+    /// Transform the lines covered by the selections. See [`Self::selected_line_ranges`].
+    fn transform_lines(&self, transform: LineTransform) -> Modification {
+        let merged_line_ranges = self.selected_line_ranges(transform.extends_single_line());
+        let changes = merged_line_ranges.into_iter().filter_map(|(start, end)| {
+            let start = self.line_offset_snapped(Line(start));
+            let end = self.line_end_offset_snapped(Line(end));
+            let range = Range::new(start, end);
+            let old_text = self.rope.text.sub(range).to_string();
+            let new_text = transform.apply_to_text(&old_text);
+            (new_text != old_text).then(|| text::Change { range, text: new_text.into() })
+        });
+        self.apply_recorded_changes_from_end(changes.collect_vec())
+    }
+
+    /// Indent or outdent the lines covered by the selections. See [`Input::indent_selection`]
+    /// and [`Input::outdent_selection`].
+    fn indent_lines(&self, outdent: bool) -> Modification {
+        let unit = self.indent_unit.get();
+        let mut changes = vec![];
+        for (start, end) in self.selected_line_ranges(false) {
+            for line in start..=end {
+                let line_start = self.line_offset_snapped(Line(line));
+                let line_end = self.line_end_offset_snapped(Line(line));
+                let content = self.rope.text.sub(Range::new(line_start, line_end)).to_string();
+                if outdent {
+                    let len = unit.outdent_len(&content);
+                    let range = Range::new(line_start, Byte(line_start.value + len));
+                    if len > 0 {
+                        changes.push(text::Change { range, text: default() });
+                    }
+                } else if !content.is_empty() || start == end {
+                    let range = Range::new(line_start, line_start);
+                    changes.push(text::Change { range, text: unit.text().into() });
+                }
+            }
+        }
+        self.apply_line_prefix_changes(changes)
+    }
+
+    /// Apply the changes inserting or removing text at the beginning of lines, recording them as
+    /// a single undoable modification. See [`Self::apply_recorded_changes_from_end`]. Unlike in
+    /// [`Change::transform_byte`], the selection offsets at an insertion are moved after the
+    /// inserted text, so the selections keep covering the same text.
+    fn apply_line_prefix_changes(&self, mut changes: Vec<text::Change>) -> Modification {
+        Self::sort_changes_from_end(&mut changes);
+        let transform = |byte: Byte| {
+            changes.iter().fold(byte, |byte, change| {
+                let range = change.range;
+                let inserted = change.text.last_byte_index().value;
+                if byte < range.start {
+                    byte
+                } else if byte < range.end {
+                    range.start
+                } else {
+                    Byte(byte.value - range.end.value + range.start.value + inserted)
+                }
+            })
+        };
+        let selections = self.byte_selections().into_iter().map(|s| s.map(transform));
+        let selections = selections.collect_vec();
+        let mut modification = self.apply_recorded_changes_from_end(changes);
+        modification.selection_group = selections
+            .into_iter()
+            .map(|selection| Selection::<Location>::from_in_context_snapped(self, selection))
+            .collect();
+        modification
+    }
+
+    /// Add the comment prefix to the lines covered by the selections or, if all of them are
+    /// commented, remove it. See [`Input::toggle_line_comment`].
+    fn toggle_line_comment(&self, prefix: &str) -> Modification {
+        let trimmed_prefix = prefix.trim_end();
+        if trimmed_prefix.is_empty() {
+            return self.apply_changes(&[]);
+        }
+        // The non-blank lines with their offsets and the widths of their indentation.
+        let mut lines = vec![];
+        for (start, end) in self.selected_line_ranges(false) {
+            for line in start..=end {
+                let line_start = self.line_offset_snapped(Line(line));
+                let line_end = self.line_end_offset_snapped(Line(line));
+                let content = self.rope.text.sub(Range::new(line_start, line_end)).to_string();
+                let indent = content.len() - content.trim_start().len();
+                if indent < content.len() {
+                    lines.push((line_start, indent, content));
+                }
+            }
+        }
+        let is_commented = |(_, indent, content): &(Byte, usize, String)| {
+            content[*indent..].starts_with(trimmed_prefix)
+        };
+        let uncomment = !lines.is_empty() && lines.iter().all(is_commented);
+        let min_indent = lines.iter().map(|(_, indent, _)| *indent).min().unwrap_or_default();
+        let changes = lines.iter().map(|(line_start, indent, content)| {
+            if uncomment {
+                let commented = &content[*indent..];
+                let removed = if commented.starts_with(prefix) { prefix } else { trimmed_prefix };
+                let start = Byte(line_start.value + indent);
+                let range = Range::new(start, Byte(start.value + removed.len()));
+                text::Change { range, text: default() }
+            } else {
+                let start = Byte(line_start.value + min_indent);
+                text::Change { range: Range::new(start, start), text: prefix.into() }
+            }
+        });
+        self.apply_line_prefix_changes(changes.collect_vec())
+    }
+
+    /// Move the lines covered by the selections up or down by one line. See
+    /// [`Input::move_lines_up`] and [`Input::move_lines_down`].
+    fn move_lines(&self, up: bool) -> Modification {
+        let last_line = self.last_line_index().value;
+        // The adjacent line ranges are merged, so the ranges of the changes do not overlap.
+        let mut line_ranges: Vec<(usize, usize)> = default();
+        for (start, end) in self.selected_line_ranges(false) {
+            match line_ranges.last_mut() {
+                Some(last) if start <= last.1 + 1 => last.1 = std::cmp::max(last.1, end),
+                _ => line_ranges.push((start, end)),
+            }
+        }
+        let blocked = if up {
+            line_ranges.first().map_or(true, |(start, _)| *start == 0)
+        } else {
+            line_ranges.last().map_or(true, |(_, end)| *end == last_line)
+        };
+        if blocked {
+            return self.apply_changes(&[]);
+        }
+        let line_text = |line: usize| {
+            let start = self.line_offset_snapped(Line(line));
+            let end = self.line_end_offset_snapped(Line(line));
+            self.rope.text.sub(Range::new(start, end)).to_string()
+        };
+        let line_ending = |line: usize| {
+            let start = self.line_end_offset_snapped(Line(line));
+            let end = self.line_offset_snapped(Line(line + 1));
+            self.rope.text.sub(Range::new(start, end)).to_string()
+        };
+        let changes = line_ranges.iter().map(|&(start, end)| {
+            let (first, last) = if up { (start - 1, end) } else { (start, end + 1) };
+            let range_start = self.line_offset_snapped(Line(first));
+            let range_end = self.line_end_offset_snapped(Line(last));
+            let block_start = self.line_offset_snapped(Line(start));
+            let block_end = self.line_end_offset_snapped(Line(end));
+            let block = self.rope.text.sub(Range::new(block_start, block_end)).to_string();
+            let text = if up {
+                format!("{block}{}{}", line_ending(first), line_text(first))
+            } else {
+                format!("{}{}{block}", line_text(last), line_ending(end))
+            };
+            text::Change { range: Range::new(range_start, range_end), text: text.into() }
+        });
+        let changes = changes.collect_vec();
+        // The selection ending at the beginning of the line after the block does not cover that
+        // line, but it is moved with the block.
+        let move_location = |location: Location| {
+            let line = location.line.value;
+            let moved = line_ranges.iter().any(|&(start, end)| {
+                (start..=end).contains(&line) || (line == end + 1 && location.offset == Column(0))
+            });
+            match moved {
+                true if up => location.with_line(Line(line - 1)),
+                true => location.with_line(Line(line + 1)),
+                false => location,
+            }
+        };
+        let selections = self.selections().iter().map(|s| s.map(move_location)).collect_vec();
+        let mut modification = self.apply_recorded_changes_from_end(changes);
+        modification.selection_group =
+            selections.into_iter().map(|s| s.map(|t| self.snap_location(t))).collect();
+        modification
+    }
+
+    /// Duplicate the selected text or, for cursors, the lines containing them. See
+    /// [`Input::duplicate_selection`].
+    fn duplicate_selections(&self) -> Modification {
+        let last_line = self.last_line_index();
+        let text_of =
+            |start: Byte, end: Byte| self.rope.text.sub(Range::new(start, end)).to_string();
+        // The copied line is preceded by the line ending of the line, or the line above it if it
+        // is the last line.
+        let line_ending = |line: Line| {
+            let line = if line < last_line { line } else { Line(line.value.saturating_sub(1)) };
+            let start = self.line_end_offset_snapped(line);
+            let end = self.line_offset_snapped(line + Line(1));
+            Some(text_of(start, end)).filter(|t| !t.is_empty()).unwrap_or_else(|| "\n".into())
+        };
+        let selections = self.byte_selections();
+        // The insertions are shared by the cursors placed in the same line.
+        let mut insertions: Vec<(Byte, String)> = default();
+        let mut owners = vec![];
+        for selection in &selections {
+            let start = std::cmp::min(selection.start, selection.end);
+            let end = std::cmp::max(selection.start, selection.end);
+            let insertion = if start == end {
+                let line = self.line_snapped(start);
+                let line_start = self.line_offset_snapped(line);
+                let line_end = self.line_end_offset_snapped(line);
+                (line_end, format!("{}{}", line_ending(line), text_of(line_start, line_end)))
+            } else {
+                (end, text_of(start, end))
+            };
+            let index = insertions.iter().position(|t| *t == insertion).unwrap_or_else(|| {
+                insertions.push(insertion);
+                insertions.len() - 1
+            });
+            owners.push(index);
+        }
+        // Every selection is moved by its own insertion, and by all insertions before it.
+        let move_offset = |offset: Byte, owner: usize| {
+            let moved_by = insertions.iter().enumerate();
+            let moved_by = moved_by.filter(|(index, (at, _))| *index == owner || *at < offset);
+            let shift: usize = moved_by.map(|(_, (_, text))| text.len()).sum();
+            Byte(offset.value + shift)
+        };
+        let selections = selections
+            .into_iter()
+            .zip(owners)
+            .map(|(selection, owner)| selection.map(|offset| move_offset(offset, owner)));
+        let selections = selections.collect_vec();
+        let changes = insertions
+            .into_iter()
+            .map(|(at, text)| text::Change { range: Range::new(at, at), text: text.into() });
+        let mut modification = self.apply_recorded_changes_from_end(changes.collect_vec());
+        modification.selection_group = selections
+            .into_iter()
+            .map(|selection| Selection::<Location>::from_in_context_snapped(self, selection))
+            .collect();
+        modification
+    }
+
+    /// Move or copy the text in the range to the target location, selecting it at the new place.
+    /// See [`Input::drop_text`].
+    fn drop_text(&self, range: Range<Location>, target: Location, copy: bool) -> Modification {
+        let start = Byte::from_in_context_snapped(self, range.start);
+        let end = Byte::from_in_context_snapped(self, range.end);
+        let (start, end) = (start.min(end), start.max(end));
+        let target = Byte::from_in_context_snapped(self, target);
+        let is_inside =
+            if copy { start < target && target < end } else { start <= target && target <= end };
+        if start == end || is_inside {
+            return self.apply_changes(&[]);
+        }
+        let text: Rope = self.rope.text.sub(Range::new(start, end));
+        let len = end.value - start.value;
+        let insertion = text::Change { range: Range::new(target, target), text };
+        let deletion = text::Change { range: Range::new(start, end), text: default() };
+        let changes = if copy { vec![insertion] } else { vec![insertion, deletion] };
+        let is_after_deletion = !copy && target > start;
+        let new_start = if is_after_deletion { Byte(target.value - len) } else { target };
+        let mut modification = self.apply_recorded_changes_from_end(changes);
+        let selection = Selection::new(new_start, Byte(new_start.value + len), default());
+        let selection = Selection::<Location>::from_in_context_snapped(self, selection);
+        modification.selection_group = iter::once(selection).collect();
+        modification
+    }
+
     /// Generic selection modify utility. It replaces selection range with given text.
     ///
     /// If `transform` is provided and selection is a simple cursor, it will modify it before
@@ -651,15 +1430,38 @@ impl BufferModel {
                 let range = self.crop_byte_range(range);
                 self.formatting.set_property(range, property)
             }
+            self.schedule_formatting_compaction();
         }
     }
 
+    fn set_properties(&self, spans: &[(Range<Byte>, Property)]) {
+        for (range, property) in spans {
+            let range = self.crop_byte_range(range);
+            self.formatting.set_property(range, *property)
+        }
+        self.schedule_formatting_compaction();
+    }
+
     fn mod_property(&self, ranges: &Vec<Range<Byte>>, property: Option<PropertyDiff>) {
         if let Some(property) = property {
             for range in ranges {
                 let range = self.crop_byte_range(range);
                 self.formatting.mod_property(range, property)
             }
+            self.schedule_formatting_compaction();
+        }
+    }
+
+    /// Merge the formatting spans fragmented by the property changes in the idle time.
+    fn schedule_formatting_compaction(&self) {
+        if !self.compaction_queued.replace(true) {
+            let weak_data = Rc::downgrade(&self.data);
+            idle::schedule_idle_with_priority(idle::Priority::Low, move || {
+                if let Some(data) = weak_data.upgrade() {
+                    data.compaction_queued.set(false);
+                    data.formatting.compact();
+                }
+            });
         }
     }
 
@@ -669,6 +1471,10 @@ impl BufferModel {
         }
     }
 
+    fn set_default_format(&self, format: Format) {
+        self.formatting.set_default_format(format)
+    }
+
     /// Resolve the provided property by applying a default value if needed.
     pub fn resolve_property(&self, property: Property) -> ResolvedProperty {
         self.formatting.resolve_property(property)
@@ -707,6 +1513,12 @@ impl BufferModel {
             .unwrap_or_else(|| self.last_line_index().value + 1 - self.first_view_line.get().value)
     }
 
+    /// The explicitly limited number of visible lines, if any. If not limited, all lines starting
+    /// with the first view line are visible.
+    pub fn view_line_limit(&self) -> Option<usize> {
+        self.view_line_count.get()
+    }
+
     /// Last index of visible lines.
     pub fn last_view_line_index(&self) -> ViewLine {
         ViewLine(self.view_line_count() - 1)
@@ -724,24 +1536,132 @@ impl BufferModel {
 }
 
 
+// === Search ===
+
+impl BufferModel {
+    /// All non-overlapping matches of the query in the text, in the order of occurrence.
+    pub fn find_all(&self, query: &Query) -> Vec<Range<Byte>> {
+        query.find_all(&self.rope.text().to_string())
+    }
+
+    /// The first match of the query starting at or after the given offset. The search wraps around
+    /// to the beginning of the text if there are no matches after the offset.
+    pub fn find(&self, query: &Query, from: Byte) -> Option<Range<Byte>> {
+        query.find(&self.rope.text().to_string(), from)
+    }
+
+    /// The last match of the query starting before the given offset. The search wraps around to
+    /// the end of the text if there are no matches before the offset.
+    pub fn find_before(&self, query: &Query, before: Byte) -> Option<Range<Byte>> {
+        query.find_before(&self.rope.text().to_string(), before)
+    }
+}
+
+
+// === Brackets ===
+
+impl BufferModel {
+    /// The bracket pair next to the location. See [`BracketPair::find`].
+    pub fn matching_brackets(&self, location: Location) -> Option<BracketPair> {
+        let offset = Byte::from_in_context_snapped(self, location);
+        BracketPair::find(&self.rope.text().to_string(), offset)
+    }
+
+    /// The bracket pair next to the end of the newest selection.
+    pub fn newest_matching_brackets(&self) -> Option<BracketPair> {
+        let newest = self.selection.borrow().newest().copied()?;
+        self.matching_brackets(newest.end)
+    }
+
+    /// The location the newest cursor jumps to when jumping to the matching bracket. See
+    /// [`BracketPair::jump_target`].
+    pub fn matching_bracket_jump_target(&self) -> Option<Location> {
+        let newest = self.selection.borrow().newest().copied()?;
+        let offset = Byte::from_in_context_snapped(self, newest.end);
+        let pair = self.matching_brackets(newest.end)?;
+        Some(Location::from_in_context_snapped(self, pair.jump_target(offset)))
+    }
+}
+
+
 // === Undo / Redo ===
 
 impl BufferModel {
-    fn commit_history(&self) {
+    /// Take a snapshot of the text, formatting, and selections.
+    pub fn snapshot(&self) -> Snapshot {
         let text = self.rope.text();
         let style = self.rope.style();
         let selection = self.selection.borrow().clone();
-        self.history.data.borrow_mut().undo_stack.push((text, style, selection));
+        Snapshot { text, style, selection }
+    }
+
+    fn commit_history(&self) {
+        let item = self.snapshot();
+        let mut history = self.history.data.borrow_mut();
+        history.last_typed = None;
+        if history.transaction_depth > 0 {
+            if history.transaction_recorded {
+                return;
+            }
+            history.transaction_recorded = true;
+        }
+        history.undo_stack.push(item);
+        history.redo_stack.clear();
+    }
+
+    /// Begin a transaction. All modifications until the matching [`Self::end_transaction`] are
+    /// recorded as a single undoable modification. Transactions can be nested, in which case the
+    /// outermost one defines the recorded modification.
+    pub fn begin_transaction(&self) {
+        self.history.begin_transaction();
+    }
+
+    /// End the transaction started with [`Self::begin_transaction`].
+    pub fn end_transaction(&self) {
+        self.history.end_transaction();
+    }
+
+    /// The number of modifications which can be undone.
+    pub fn history_len(&self) -> usize {
+        self.history.data.borrow().undo_stack.len()
     }
 
     fn undo(&self) -> Option<selection::Group> {
         let item = self.history.data.borrow_mut().undo_stack.pop();
-        item.map(|(text, style, selection)| {
-            self.rope.set_text(text);
-            self.rope.set_style(style);
-            selection
+        item.map(|item| {
+            let current = self.snapshot();
+            self.history.data.borrow_mut().redo_stack.push(current);
+            self.restore_snapshot(item)
+        })
+    }
+
+    fn redo(&self) -> Option<selection::Group> {
+        let item = self.history.data.borrow_mut().redo_stack.pop();
+        item.map(|item| {
+            let current = self.snapshot();
+            self.history.data.borrow_mut().undo_stack.push(current);
+            self.restore_snapshot(item)
         })
     }
+
+    /// Restore the text and formatting from the snapshot, returning its selections. The change is
+    /// not recorded in the undo history.
+    fn restore_snapshot(&self, snapshot: Snapshot) -> selection::Group {
+        let Snapshot { text, style, selection } = snapshot;
+        self.history.data.borrow_mut().last_typed = None;
+        let old_range = self.full_range();
+        let old_last_line = self.last_line_index();
+        self.announce_modification();
+        self.rope.set_text(text);
+        self.rope.set_style(style);
+        // Other views see the restored snapshot as a replacement of the whole text.
+        let line_diff = self.last_line_index() - old_last_line;
+        let change = text::Change { range: old_range, text: self.rope.text() };
+        let change_range = Line(0)..=old_last_line;
+        let change = Change { change, change_range, line_diff, selection: default() };
+        self.views.changed.emit((self.view_id, Rc::new(vec![change])));
+        selection
+    }
 }
 
 
@@ -1189,3 +2109,204 @@ where
         Selection::new(start, end, id)
     }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::seq::SliceRandom;
+    use rand::Rng;
+    use rand::SeedableRng;
+
+    /// Text fragments used by the random modifications. They contain multi-byte code points and
+    /// multi-code-point grapheme clusters, as the selection math has many edge cases around them.
+    const FRAGMENTS: &[&str] = &["a", "xyz", " ", "\n", "\n\n", "🧑🏾", "ட்", "e\u{301}", "ab\ncd"];
+
+    /// A simple string mirror of the buffer content, updated with the emitted changes.
+    struct Mirror {
+        text:      String,
+        snapshots: Vec<String>,
+    }
+
+    impl Mirror {
+        /// Apply the changes, checking their line diffs against the mirrored text.
+        fn apply(&mut self, changes: &[Change]) {
+            for change in changes {
+                let range = change.range.start.value..change.range.end.value;
+                let removed_lines = self.text[range.clone()].matches('\n').count() as i32;
+                let inserted = change.text.to_string();
+                let inserted_lines = inserted.matches('\n').count() as i32;
+                assert_eq!(change.line_diff, LineDiff(inserted_lines - removed_lines));
+                self.text.replace_range(range, &inserted);
+            }
+        }
+    }
+
+    fn grapheme_boundaries(rope: &Rope) -> Vec<Byte> {
+        let mut offsets = vec![Byte(0)];
+        while let Some(next) = rope.next_grapheme_offset(*offsets.last().unwrap()) {
+            offsets.push(next);
+        }
+        offsets
+    }
+
+    fn random_location(rng: &mut impl Rng, rope: &Rope) -> Location {
+        let offset = *grapheme_boundaries(rope).choose(rng).unwrap();
+        enso_text::FromInContextSnapped::from_in_context_snapped(rope, offset)
+    }
+
+    fn random_change(rng: &mut impl Rng, rope: &Rope) -> text::Change {
+        let boundaries = grapheme_boundaries(rope);
+        let mut range = [*boundaries.choose(rng).unwrap(), *boundaries.choose(rng).unwrap()];
+        range.sort();
+        let text = FRAGMENTS.choose(rng).unwrap().to_string().into();
+        text::Change { range: (range[0]..range[1]).into(), text }
+    }
+
+    fn check_invariants(model: &BufferModel, mirror: &Mirror) {
+        let rope = model.rope.text();
+        assert_eq!(rope.to_string(), mirror.text);
+        let style = model.rope.style();
+        assert_eq!(style.font_size.spans.len(), rope.last_byte_index());
+        assert_eq!(style.color.spans.len(), rope.last_byte_index());
+        let boundaries = grapheme_boundaries(&rope);
+        for selection in model.selections() {
+            for location in [selection.start, selection.end] {
+                assert!(location.line <= rope.last_line_index());
+                assert!(location.offset <= rope.line_last_column(location.line).unwrap());
+            }
+            let byte_selection = Selection::<Byte>::from_in_context_snapped(model, selection);
+            assert!(boundaries.contains(&byte_selection.start));
+            assert!(boundaries.contains(&byte_selection.end));
+        }
+    }
+
+    /// Apply random sequences of multi-cursor modifications, checking the buffer invariants after
+    /// every step.
+    #[test]
+    fn random_modifications_keep_invariants() {
+        let mut rng = rand_chacha::ChaCha8Rng::seed_from_u64(0);
+        for _ in 0..20 {
+            let model = BufferModel::new();
+            let mut mirror = Mirror { text: default(), snapshots: default() };
+            model.set_selection(&model.set_cursor(default()));
+            for _ in 0..100 {
+                let rope = model.rope.text();
+                let edit = |mirror: &mut Mirror, modification: Modification| {
+                    mirror.apply(&modification.changes);
+                    model.set_selection(&modification.selection_group);
+                };
+                match rng.gen_range(0..10) {
+                    0 => model.set_selection(&model.set_cursor(random_location(&mut rng, &rope))),
+                    1 => model.set_selection(&model.add_cursor(random_location(&mut rng, &rope))),
+                    2 => {
+                        let start = random_location(&mut rng, &rope);
+                        let end = random_location(&mut rng, &rope);
+                        let shape = selection::Shape::new(start, end);
+                        model.set_selection(&model.set_single_selection(shape));
+                    }
+                    3 =>
+                        if let Some(selection) = model.undo() {
+                            mirror.text = mirror.snapshots.pop().unwrap();
+                            model.set_selection(&selection);
+                        },
+                    4 => {
+                        let changes = [random_change(&mut rng, &rope)];
+                        edit(&mut mirror, model.apply_changes(&changes));
+                    }
+                    op => {
+                        mirror.snapshots.push(mirror.text.clone());
+                        let modification = match op {
+                            5 => model.delete_left(),
+                            6 => model.delete_right(),
+                            7 => model.delete_word_left(),
+                            _ => model.insert(*FRAGMENTS.choose(&mut rng).unwrap()),
+                        };
+                        edit(&mut mirror, modification);
+                    }
+                }
+                check_invariants(&model, &mirror);
+            }
+            while let Some(selection) = model.undo() {
+                mirror.text = mirror.snapshots.pop().unwrap();
+                model.set_selection(&selection);
+                check_invariants(&model, &mirror);
+            }
+            assert!(mirror.snapshots.is_empty());
+        }
+    }
+
+    /// Grapheme clusters with many combining marks are traversed, selected, and deleted atomically.
+    #[test]
+    fn clusters_with_combining_marks_are_atomic() {
+        // The zalgo string from the text area example and a pathological cluster.
+        let extreme = format!("a{}b", "\u{301}".repeat(1000));
+        for text in ["Z̮̞̠͙͔ͅḀ̗̞͈̻̗Ḷ͙͎̯̹̞͓G̻O̭̗̮", &extreme] {
+            let model = BufferModel::new();
+            model.set_selection(&model.set_cursor(default()));
+            model.set_selection(&model.insert(text).selection_group);
+            let boundaries = grapheme_boundaries(&model.rope.text());
+            model.set_selection(&model.set_cursor(default()));
+            for &boundary in &boundaries[1..] {
+                model.set_selection(&model.moved_selection(Transform::Right, false));
+                let cursor = *model.selections().newest().unwrap();
+                assert_eq!(Byte::from_in_context_snapped(&model, cursor.end), boundary);
+            }
+            let end = *boundaries.last().unwrap();
+            for &boundary in boundaries.iter().rev().skip(1) {
+                model.set_selection(&model.moved_selection(Transform::Left, true));
+                let selection = *model.selections().newest().unwrap();
+                let selection = Selection::<Byte>::from_in_context_snapped(&model, selection);
+                assert_eq!((selection.start, selection.end), (end, boundary));
+            }
+            model.set_selection(&model.moved_selection(Transform::EndOfDocument, false));
+            for &boundary in boundaries.iter().rev().skip(1) {
+                model.set_selection(&model.delete_left().selection_group);
+                assert_eq!(model.rope.text().last_byte_index(), boundary);
+            }
+            model.set_selection(&model.insert(text).selection_group);
+            model.set_selection(&model.set_cursor(default()));
+            for &boundary in &boundaries[1..] {
+                model.set_selection(&model.delete_right().selection_group);
+                assert_eq!(model.rope.text().last_byte_index(), end - boundary);
+            }
+        }
+    }
+
+    /// Views of the same buffer share the text and the undo history, so a modification made in one
+    /// view can be undone in another one.
+    #[test]
+    fn views_share_text_and_history() {
+        let buffer1 = Buffer::new(BufferModel::new());
+        let buffer2 = Buffer::new(buffer1.new_view());
+        assert_ne!(buffer1.view_id(), buffer2.view_id());
+        let text = |buffer: &Buffer| buffer.rope.text().to_string();
+        buffer1.frp.set_cursor(Location::default());
+        buffer1.frp.insert("hello");
+        assert_eq!(text(&buffer2), "hello");
+        buffer2.frp.set_cursor(Location::default());
+        buffer2.frp.cursors_move(Transform::EndOfDocument);
+        buffer2.frp.insert(" world");
+        assert_eq!(text(&buffer1), "hello world");
+        assert_eq!(buffer1.history_len(), 2);
+        buffer1.frp.undo();
+        assert_eq!(text(&buffer1), "hello");
+        assert_eq!(text(&buffer2), "hello");
+        buffer2.frp.undo();
+        assert_eq!(text(&buffer1), "");
+        assert_eq!(text(&buffer2), "");
+        assert_eq!(buffer2.history_len(), 0);
+        buffer1.frp.redo();
+        buffer2.frp.redo();
+        assert_eq!(text(&buffer1), "hello world");
+        assert_eq!(text(&buffer2), "hello world");
+        // The selections of the other view are kept within the restored text.
+        check_invariants(&buffer1, &Mirror { text: text(&buffer2), snapshots: default() });
+        check_invariants(&buffer2, &Mirror { text: text(&buffer1), snapshots: default() });
+    }
+}
@@ -0,0 +1,247 @@
+//! Parser of strings containing ANSI escape sequences, like the colored output of compilers and
+//! processes. The text is converted to [`StyledText`], so terminal-style output can be displayed
+//! directly in the text area.
+//!
+//! The SGR (Select Graphic Rendition) sequences are supported: bold, faint, italic, underline,
+//! strikethrough, and foreground colors (the 16 basic colors, the 256-color palette, and 24-bit
+//! colors). All other escape sequences are removed from the text.
+
+use crate::prelude::*;
+
+use crate::buffer::formatting;
+use crate::buffer::formatting::color;
+use crate::buffer::StyledText;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+const ESCAPE: char = '\u{1b}';
+
+/// The basic 16 colors, using the VGA palette values.
+const BASIC_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (170, 0, 0),
+    (0, 170, 0),
+    (170, 85, 0),
+    (0, 0, 170),
+    (170, 0, 170),
+    (0, 170, 170),
+    (170, 170, 170),
+    (85, 85, 85),
+    (255, 85, 85),
+    (85, 255, 85),
+    (255, 255, 85),
+    (85, 85, 255),
+    (255, 85, 255),
+    (85, 255, 255),
+    (255, 255, 255),
+];
+
+
+
+// =============
+// === Style ===
+// =============
+
+/// The graphic rendition state set by the SGR sequences.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[allow(missing_docs)]
+pub struct Style {
+    pub color:         Option<color::Rgba>,
+    pub bold:          bool,
+    pub faint:         bool,
+    pub italic:        bool,
+    pub underline:     bool,
+    pub strikethrough: bool,
+}
+
+impl Style {
+    /// Update the style with the parameters of a single SGR sequence.
+    pub fn apply_sgr(&mut self, params: &[u32]) {
+        let mut params = params.iter().copied();
+        while let Some(param) = params.next() {
+            match param {
+                0 => *self = default(),
+                1 => self.bold = true,
+                2 => self.faint = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                9 => self.strikethrough = true,
+                22 => {
+                    self.bold = false;
+                    self.faint = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                29 => self.strikethrough = false,
+                30..=37 => self.color = Some(basic_color(param - 30)),
+                90..=97 => self.color = Some(basic_color(param - 90 + 8)),
+                39 => self.color = None,
+                38 => self.color = extended_color(&mut params).or(self.color),
+                // The background color parameters are consumed, as they are not supported.
+                48 => {
+                    extended_color(&mut params);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn apply_to(&self, mut text: StyledText) -> StyledText {
+        if let Some(color) = self.color {
+            text = text.color(color);
+        }
+        if self.bold {
+            text = text.bold();
+        } else if self.faint {
+            text = text.weight(formatting::Weight::Light);
+        }
+        if self.italic {
+            text = text.italic();
+        }
+        if self.underline {
+            text = text.underline();
+        }
+        if self.strikethrough {
+            text = text.strikethrough();
+        }
+        text
+    }
+}
+
+fn rgb(r: u8, g: u8, b: u8) -> color::Rgba {
+    color::Rgba::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0)
+}
+
+fn basic_color(index: u32) -> color::Rgba {
+    let (r, g, b) = BASIC_COLORS[index as usize % BASIC_COLORS.len()];
+    rgb(r, g, b)
+}
+
+/// The color of the 256-color palette: the 16 basic colors, the 6×6×6 color cube, and 24 shades
+/// of gray.
+fn palette_color(index: u8) -> color::Rgba {
+    match index {
+        0..=15 => basic_color(index as u32),
+        16..=231 => {
+            let index = index - 16;
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            rgb(level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            rgb(level, level, level)
+        }
+    }
+}
+
+/// Parse the parameters following the 38 or 48 SGR parameter: `5;n` for the 256-color palette, or
+/// `2;r;g;b` for 24-bit colors.
+fn extended_color(params: &mut impl Iterator<Item = u32>) -> Option<color::Rgba> {
+    let mut next = || params.next().map(|t| t.min(255) as u8);
+    match next()? {
+        5 => next().map(palette_color),
+        2 => Some(rgb(next()?, next()?, next()?)),
+        _ => None,
+    }
+}
+
+
+
+// ==============
+// === Parser ===
+// ==============
+
+/// Convert the string containing ANSI escape sequences to styled text.
+pub fn parse(input: &str) -> StyledText {
+    let mut text = StyledText::new();
+    let mut style = Style::default();
+    let mut segment = String::new();
+    let mut chars = input.chars().peekable();
+    while let Some(char) = chars.next() {
+        if char != ESCAPE {
+            segment.push(char);
+            continue;
+        }
+        if chars.next_if_eq(&'[').is_none() {
+            // A two-character escape sequence, which does not affect the style.
+            chars.next();
+            continue;
+        }
+        let mut params = String::new();
+        let mut final_char = None;
+        for char in chars.by_ref() {
+            if ('@'..='~').contains(&char) {
+                final_char = Some(char);
+                break;
+            }
+            params.push(char);
+        }
+        if final_char == Some('m') {
+            if !segment.is_empty() {
+                text = style.apply_to(text.text(mem::take(&mut segment)));
+            }
+            let params =
+                params.split(';').map(|t| t.parse::<u32>().unwrap_or_default()).collect_vec();
+            style.apply_sgr(&params);
+        }
+    }
+    if !segment.is_empty() {
+        text = style.apply_to(text.text(segment));
+    }
+    text
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use enso_text::unit::*;
+
+    use crate::buffer::formatting::PropertyTag;
+    use crate::buffer::Range;
+
+    #[test]
+    fn escape_sequences_are_converted_to_formatting() {
+        let input =
+            "\u{1b}[1;31merror\u{1b}[0m: \u{1b}[2Kfile \u{1b}[38;5;21m\u{1b}[4mmain\u{1b}[m";
+        let text = parse(input);
+        assert_eq!(text.content(), "error: file main");
+        let spans = text.spans().iter().map(|(range, property)| (*range, property.tag()));
+        let error = Range::new(Byte(0), Byte(5));
+        let main = Range::new(Byte(12), Byte(16));
+        assert_eq!(spans.collect_vec(), vec![
+            (error, PropertyTag::Color),
+            (error, PropertyTag::Weight),
+            (main, PropertyTag::Color),
+            (main, PropertyTag::Underline),
+        ]);
+    }
+
+    #[test]
+    fn sgr_parameters_update_style() {
+        let mut style = Style::default();
+        style.apply_sgr(&[38, 2, 255, 0, 0, 1, 4]);
+        assert_eq!(style.color, Some(rgb(255, 0, 0)));
+        assert!(style.bold && style.underline);
+        style.apply_sgr(&[48, 5, 100, 22, 92]);
+        assert_eq!(style.color, Some(basic_color(10)));
+        assert!(!style.bold && style.underline);
+        style.apply_sgr(&[9, 24]);
+        assert!(style.strikethrough && !style.underline);
+        style.apply_sgr(&[38, 5, 232]);
+        assert_eq!(style.color, Some(rgb(8, 8, 8)));
+        style.apply_sgr(&[38, 5, 16 + 36 * 5]);
+        assert_eq!(style.color, Some(rgb(255, 0, 0)));
+        style.apply_sgr(&[0]);
+        assert_eq!(style, Style::default());
+    }
+}
@@ -0,0 +1,145 @@
+//! Matching of the bracket pairs. The round, square, and curly brackets are matched. Only the
+//! brackets of the same kind are counted when searching for the matching bracket, so the brackets
+//! of other kinds do not need to be balanced.
+
+use crate::prelude::*;
+use enso_text::unit::*;
+
+use crate::buffer::Range;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The matched pairs of the opening and closing brackets.
+pub const BRACKETS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+
+
+// ===================
+// === BracketPair ===
+// ===================
+
+/// The ranges of the matching opening and closing brackets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BracketPair {
+    /// The range of the opening bracket.
+    pub open:  Range<Byte>,
+    /// The range of the closing bracket.
+    pub close: Range<Byte>,
+}
+
+impl BracketPair {
+    /// The pair of brackets next to the offset. The bracket after the offset is preferred over the
+    /// bracket before it. Returns [`None`] if there is no bracket next to the offset, or if the
+    /// bracket has no match.
+    pub fn find(text: &str, offset: Byte) -> Option<Self> {
+        let offset = offset.value.min(text.len());
+        let after = text.get(offset..).and_then(|t| t.chars().next());
+        let before = text.get(..offset).and_then(|t| t.chars().next_back());
+        let pair_at = |start: usize, char: char| Self::find_matching(text, start, char);
+        let pair_after = after.and_then(|t| pair_at(offset, t));
+        pair_after.or_else(|| before.and_then(|t| pair_at(offset - t.len_utf8(), t)))
+    }
+
+    /// The pair of the bracket starting at the offset.
+    fn find_matching(text: &str, start: usize, bracket: char) -> Option<Self> {
+        let range =
+            |start: usize, char: char| Range::new(Byte(start), Byte(start + char.len_utf8()));
+        let bracket_range = range(start, bracket);
+        if let Some((open, close)) = BRACKETS.iter().find(|(open, _)| *open == bracket) {
+            let after = &text[bracket_range.end.value..];
+            let chars = after.char_indices().map(|(i, t)| (bracket_range.end.value + i, t));
+            let close_start = Self::find_unbalanced(chars, *open, *close)?;
+            Some(Self { open: bracket_range, close: range(close_start, *close) })
+        } else if let Some((open, close)) = BRACKETS.iter().find(|(_, close)| *close == bracket) {
+            let chars = text[..start].char_indices().rev();
+            let open_start = Self::find_unbalanced(chars, *close, *open)?;
+            Some(Self { open: range(open_start, *open), close: bracket_range })
+        } else {
+            None
+        }
+    }
+
+    /// The offset of the first `target` bracket not balanced by the `nested` brackets.
+    fn find_unbalanced(
+        chars: impl Iterator<Item = (usize, char)>,
+        nested: char,
+        target: char,
+    ) -> Option<usize> {
+        let mut depth = 0_usize;
+        for (offset, char) in chars {
+            if char == nested {
+                depth += 1;
+            } else if char == target {
+                if depth == 0 {
+                    return Some(offset);
+                }
+                depth -= 1;
+            }
+        }
+        None
+    }
+
+    /// Both bracket ranges, the opening one first.
+    pub fn ranges(&self) -> [Range<Byte>; 2] {
+        [self.open, self.close]
+    }
+
+    /// The offset the cursor placed at the given offset jumps to. The cursor next to the opening
+    /// bracket jumps before the closing one, and vice versa, so jumping twice returns the cursor
+    /// before the bracket it started at.
+    pub fn jump_target(&self, offset: Byte) -> Byte {
+        if offset <= self.open.end {
+            self.close.start
+        } else {
+            self.open.start
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(text: &str, offset: usize) -> Option<(usize, usize)> {
+        BracketPair::find(text, Byte(offset)).map(|t| (t.open.start.value, t.close.start.value))
+    }
+
+    #[test]
+    fn brackets_are_matched() {
+        let text = "f(a[0], {b}) (";
+        assert_eq!(pair(text, 1), Some((1, 11)));
+        assert_eq!(pair(text, 2), Some((1, 11)));
+        assert_eq!(pair(text, 12), Some((1, 11)));
+        assert_eq!(pair(text, 3), Some((3, 5)));
+        assert_eq!(pair(text, 9), Some((8, 10)));
+        assert_eq!(pair(text, 0), None);
+        assert_eq!(pair(text, 14), None);
+        // The bracket after the cursor is preferred.
+        assert_eq!(pair(text, 11), Some((1, 11)));
+        assert_eq!(pair(text, 10), Some((8, 10)));
+        // Nested brackets of the same kind are balanced.
+        assert_eq!(pair("((a) ())", 0), Some((0, 7)));
+        assert_eq!(pair("((a) ())", 8), Some((0, 7)));
+        // Multi-byte characters are skipped correctly.
+        assert_eq!(pair("[ąę]", 0), Some((0, 5)));
+    }
+
+    #[test]
+    fn cursor_jumps_between_brackets() {
+        let pair = BracketPair::find("a(bc)d", Byte(1)).unwrap();
+        assert_eq!(pair.jump_target(Byte(1)), Byte(4));
+        assert_eq!(pair.jump_target(Byte(2)), Byte(4));
+        assert_eq!(pair.jump_target(Byte(4)), Byte(1));
+        assert_eq!(pair.jump_target(Byte(5)), Byte(1));
+    }
+}
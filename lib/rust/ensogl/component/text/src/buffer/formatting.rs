@@ -60,6 +60,215 @@ macro_rules! def_unit {
 
 def_unit!(Size(f32) = 12.0);
 def_unit!(SdfWeight(f32) = 0.0);
+def_unit!(Underline(bool) = false);
+def_unit!(Strikethrough(bool) = false);
+
+
+// === FontFamily ===
+
+thread_local! {
+    static FONT_FAMILY_NAMES: RefCell<Vec<font::Name>> = default();
+}
+
+/// The font family used to render a text range. The default value means the font of the text area
+/// (see [`crate::component::text::Frp::set_font`]). Other values refer to fonts registered in
+/// [`font::Registry`] by name. The names are interned, so the property is cheap to copy and
+/// compare, as required by the formatting spans.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct FontFamily {
+    id: Option<usize>,
+}
+
+impl FontFamily {
+    /// Constructor.
+    pub fn new(name: impl Into<font::Name>) -> Self {
+        let name = name.into();
+        let id = FONT_FAMILY_NAMES.with(|names| {
+            let mut names = names.borrow_mut();
+            names.iter().position(|t| t == &name).unwrap_or_else(|| {
+                names.push(name);
+                names.len() - 1
+            })
+        });
+        Self { id: Some(id) }
+    }
+
+    /// The name of the font family. Returns [`None`] for the default family.
+    pub fn name(self) -> Option<font::Name> {
+        let id = self.id?;
+        FONT_FAMILY_NAMES.with(|names| names.borrow().get(id).cloned())
+    }
+}
+
+/// Smart constructor.
+#[allow(non_snake_case)]
+pub fn FontFamily(name: impl Into<font::Name>) -> FontFamily {
+    FontFamily::new(name)
+}
+
+
+// === Outline ===
+
+/// The text outline (halo), keeping the text legible over busy backgrounds, like labels drawn over
+/// graph edges. The width is relative to the font size, the same way as the [`SdfWeight`]. The
+/// outline is rendered from the glyph distance field, so it can not be wider than the distance
+/// field range of the font atlas. The default outline has zero width and is not rendered.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[allow(missing_docs)]
+pub struct Outline {
+    pub color: color::Lcha,
+    pub width: f32,
+}
+
+impl Outline {
+    /// Constructor.
+    pub fn new(color: impl Into<color::Lcha>, width: f32) -> Self {
+        let color = color.into();
+        Self { color, width }
+    }
+}
+
+
+// === Shadow ===
+
+/// The text shadow, making labels legible over arbitrary scene content without background panels.
+/// The shadow is the glyph shape moved by the offset, with the edge softened by the blur. Both are
+/// relative to the font size. Just like the [`Outline`], the shadow is rendered from the glyph
+/// distance field, so it can not be moved or blurred further than the distance field range of the
+/// font atlas. The default shadow has zero offset and blur, and is not rendered.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[allow(missing_docs)]
+pub struct Shadow {
+    pub color:  color::Lcha,
+    pub offset: Vector2,
+    pub blur:   f32,
+}
+
+impl Shadow {
+    /// Constructor.
+    pub fn new(color: impl Into<color::Lcha>, offset: Vector2, blur: f32) -> Self {
+        let color = color.into();
+        Self { color, offset, blur }
+    }
+
+    /// Check whether the shadow is rendered.
+    pub fn is_visible(&self) -> bool {
+        self.offset != Vector2::zero() || self.blur > 0.0
+    }
+}
+
+
+// === Emphasis ===
+
+/// Transient emphasis of the text, like flashing search matches or recently changed text. The
+/// color and the SDF weight of the text pulse towards the emphasis `color` and `sdf_weight`. The
+/// animation is computed in the glyph shader, so it does not require redrawing the text every
+/// frame. The default emphasis has zero period and is not rendered.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Emphasis {
+    /// The color of the text at the peak of every pulse.
+    pub color:       color::Lcha,
+    /// The SDF weight added to the text at the peak of every pulse.
+    pub sdf_weight:  f32,
+    /// The duration of a single pulse in milliseconds.
+    pub period:      f32,
+    /// The number of pulses. If zero, the text pulses until the emphasis is removed.
+    pub pulse_count: u32,
+    /// The animation start time, in milliseconds since the animation loop started. It is set by
+    /// the `emphasize` input of the text area.
+    pub start_time:  f32,
+}
+
+impl Emphasis {
+    /// Constructor.
+    pub fn new(color: impl Into<color::Lcha>, sdf_weight: f32, period: f32) -> Self {
+        let color = color.into();
+        Self { color, sdf_weight, period, pulse_count: 1, start_time: 0.0 }
+    }
+
+    /// Set the number of pulses. See [`Self::pulse_count`].
+    pub fn with_pulse_count(self, pulse_count: u32) -> Self {
+        Self { pulse_count, ..self }
+    }
+
+    /// Set the animation start time. See [`Self::start_time`].
+    pub fn started_at(self, start_time: f32) -> Self {
+        Self { start_time, ..self }
+    }
+}
+
+
+// === Underline ===
+
+/// The color of the underline. The default value means the color of the underlined text, so the
+/// underline follows the text color changes.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[allow(missing_docs)]
+pub struct UnderlineColor {
+    pub color: Option<color::Lcha>,
+}
+
+impl UnderlineColor {
+    /// Constructor.
+    pub fn new(color: impl Into<color::Lcha>) -> Self {
+        let color = Some(color.into());
+        Self { color }
+    }
+}
+
+/// The line style of the underline. The wavy underline (squiggle) is used to mark diagnostics,
+/// like errors and warnings, without hiding the text color.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum UnderlineStyle {
+    #[default]
+    Solid,
+    Dashed,
+    Wavy,
+}
+
+
+// === Script ===
+
+/// The font size scale of superscript and subscript text.
+const SCRIPT_SIZE_SCALE: f32 = 0.65;
+
+/// The baseline offset of superscript text, relative to the font size.
+const SUPERSCRIPT_BASELINE_OFFSET: f32 = 0.35;
+
+/// The baseline offset of subscript text, relative to the font size.
+const SUBSCRIPT_BASELINE_OFFSET: f32 = -0.15;
+
+/// The vertical position of the text relative to the baseline. Superscript and subscript text is
+/// rendered with a smaller font size and an offset baseline, for example, for units (`m²`) or
+/// mathematical annotations (`xᵢ`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum Script {
+    #[default]
+    Normal,
+    Super,
+    Sub,
+}
+
+impl Script {
+    /// The scale of the font size.
+    pub fn size_scale(self) -> f32 {
+        match self {
+            Script::Normal => 1.0,
+            Script::Super | Script::Sub => SCRIPT_SIZE_SCALE,
+        }
+    }
+
+    /// The baseline offset, relative to the (not scaled) font size.
+    pub fn baseline_offset(self) -> f32 {
+        match self {
+            Script::Normal => 0.0,
+            Script::Super => SUPERSCRIPT_BASELINE_OFFSET,
+            Script::Sub => SUBSCRIPT_BASELINE_OFFSET,
+        }
+    }
+}
 
 
 
@@ -72,12 +281,21 @@ def_unit!(SdfWeight(f32) = 0.0);
 macro_rules! with_formatting_properties {
     ($macro_name:ident) => {
         $macro_name! {
-            font_size  : Size,
-            color      : color::Lcha,
-            weight     : Weight,
-            width      : Width,
-            style      : Style,
-            sdf_weight : SdfWeight,
+            font_size      : Size,
+            color          : color::Lcha,
+            weight         : Weight,
+            width          : Width,
+            style          : Style,
+            sdf_weight     : SdfWeight,
+            font_family    : FontFamily,
+            script         : Script,
+            outline        : Outline,
+            shadow         : Shadow,
+            emphasis       : Emphasis,
+            underline      : Underline,
+            underline_color: UnderlineColor,
+            underline_style: UnderlineStyle,
+            strikethrough  : Strikethrough,
         }
     };
 }
@@ -152,6 +370,16 @@ macro_rules! define_property {
             $([<$field:camel>]),*
         }
 
+        impl PropertyTag {
+            /// The property of this tag with the default value placeholder. Setting it resets the
+            /// property to the default value.
+            pub fn default_property(self) -> Property {
+                match self {
+                    $(Self::[<$field:camel>] => Property::[<$field:camel>](None)),*
+                }
+            }
+        }
+
         impl From<Property> for PropertyTag {
             fn from(property: Property) -> Self {
                 match property {
@@ -172,6 +400,17 @@ macro_rules! define_property {
 
 with_formatting_properties! { define_property }
 
+impl PropertyTag {
+    /// Check whether the property is displayed by the line decorations, like the underline or the
+    /// strikethrough, instead of the glyphs.
+    pub fn is_decoration(self) -> bool {
+        matches!(
+            self,
+            Self::Underline | Self::UnderlineColor | Self::UnderlineStyle | Self::Strikethrough
+        )
+    }
+}
+
 impl From<color::Rgba> for Property {
     fn from(t: color::Rgba) -> Self {
         Property::Color(Some(t.into()))
@@ -234,6 +473,12 @@ macro_rules! define_formatting {
                 $(self.$field.replace_resize(range,len,None);)*
             }
 
+            /// Merge adjacent spans holding equal values of all properties. See
+            /// [`enso_text::Spans::compact`] to learn more.
+            pub fn compact(&mut self) {
+                $(self.$field.spans.compact();)*
+            }
+
             /// Return all span ranges of default values for the given property.
             pub fn span_ranges_of_default_values(&self, tag:PropertyTag) -> Vec<Range<Byte>> {
                 match tag {
@@ -275,6 +520,65 @@ macro_rules! define_formatting {
 
 with_formatting_properties! { define_formatting }
 
+
+
+// ==============
+// === Format ===
+// ==============
+
+/// Defines a bundle of default values of all formatting properties. See the usage below to learn
+/// more.
+macro_rules! define_format {
+    ($($field:ident : $field_type:ty),* $(,)?) => {paste! {
+        /// A bundle of formatting property values, used to set many defaults at once. Properties
+        /// set to [`None`] are left unchanged.
+        #[derive(Clone, Copy, Debug, Default)]
+        #[allow(missing_docs)]
+        pub struct Format {
+            $(pub $field : Option<$field_type>),*
+        }
+
+        impl Format {
+            /// Constructor.
+            pub fn new() -> Self {
+                Self::default()
+            }
+
+            $(
+                /// Property setter.
+                pub fn $field(mut self, value: impl Into<$field_type>) -> Self {
+                    self.$field = Some(value.into());
+                    self
+                }
+            )*
+
+            /// All properties set in this bundle.
+            pub fn properties(&self) -> Vec<ResolvedProperty> {
+                let mut properties = vec![];
+                $(
+                    if let Some(value) = self.$field {
+                        properties.push(ResolvedProperty::[<$field:camel>](value));
+                    }
+                )*
+                properties
+            }
+        }
+
+        impl Formatting {
+            /// Sets new default values for all properties set in the provided format.
+            pub fn set_default_format(&mut self, format: Format) {
+                $(
+                    if let Some(value) = format.$field {
+                        self.$field.default = value;
+                    }
+                )*
+            }
+        }
+    }};
+}
+
+with_formatting_properties! { define_format }
+
 impl Formatting {
     /// Returns list of spans for triples of (width, weight, style). The triple is used to identify
     /// a non-variable font family.
@@ -292,40 +596,60 @@ impl Formatting {
         &self,
         rope: &'a Rope,
     ) -> impl Iterator<Item = (std::ops::Range<Byte>, NonVariableFaceHeader)> + 'a {
-        let seq_font_header = self.non_variable_font_spans();
-        let iter = gen_iter!(move {
-            let mut start_byte = Byte(0);
-            let mut end_byte = Byte(0);
-            let mut header_iter = seq_font_header.into_iter();
-            let mut opt_header = header_iter.next();
-            while let Some(header) = opt_header
-               && let Some(new_end_byte) = rope.next_grapheme_offset(end_byte) {
-                end_byte = new_end_byte;
-                if end_byte >= header.range.end {
-                    yield (start_byte..end_byte, header.value);
-                    start_byte = end_byte;
-                    opt_header = header_iter.next();
-                }
-            }
-            if start_byte != end_byte {
-                error!("Misaligned bytes found when shaping text. {:?} != {:?}", start_byte, end_byte);
-                yield (start_byte..end_byte, default());
-            }
-        });
-        // We are merging subsequent ranges if they have the same header. The underlying rope
-        // implementation can return chunks with the same value. For example, after setting a glyph
-        // to a bold face, and unsetting it, there will be separate chunks emitted.
-        iter.coalesce(|mut a, b| {
-            if a.1 == b.1 {
-                a.0.end = b.0.end;
-                Ok(a)
-            } else {
-                Err((a, b))
-            }
-        })
+        grapheme_aligned_chunks(self.non_variable_font_spans(), rope)
+    }
+
+    /// Return list of spans for different [`FontFamily`] values. The result is aligned with
+    /// grapheme cluster boundaries the same way as in [`Self::chunks_per_font_face`].
+    pub fn chunks_per_font_family<'a>(
+        &self,
+        rope: &'a Rope,
+    ) -> impl Iterator<Item = (std::ops::Range<Byte>, FontFamily)> + 'a {
+        grapheme_aligned_chunks(self.font_family.to_vector(), rope)
     }
 }
 
+/// Align the spans with grapheme cluster boundaries. If the value changes inside a grapheme
+/// cluster, the cluster will be associated with the value it starts with.
+fn grapheme_aligned_chunks<'a, T>(
+    spans: Vec<RangedValue<Byte, T>>,
+    rope: &'a Rope,
+) -> impl Iterator<Item = (std::ops::Range<Byte>, T)> + 'a
+where
+    T: Copy + Debug + Default + PartialEq + 'a,
+{
+    let iter = gen_iter!(move {
+        let mut start_byte = Byte(0);
+        let mut end_byte = Byte(0);
+        let mut span_iter = spans.into_iter();
+        let mut opt_span = span_iter.next();
+        while let Some(span) = opt_span
+           && let Some(new_end_byte) = rope.next_grapheme_offset(end_byte) {
+            end_byte = new_end_byte;
+            if end_byte >= span.range.end {
+                yield (start_byte..end_byte, span.value);
+                start_byte = end_byte;
+                opt_span = span_iter.next();
+            }
+        }
+        if start_byte != end_byte {
+            error!("Misaligned bytes found when shaping text. {:?} != {:?}", start_byte, end_byte);
+            yield (start_byte..end_byte, default());
+        }
+    });
+    // We are merging subsequent ranges if they have the same value. The underlying rope
+    // implementation can return chunks with the same value. For example, after setting a glyph
+    // to a bold face, and unsetting it, there will be separate chunks emitted.
+    iter.coalesce(|mut a, b| {
+        if a.1 == b.1 {
+            a.0.end = b.0.end;
+            Ok(a)
+        } else {
+            Err((a, b))
+        }
+    })
+}
+
 
 
 // =================
@@ -603,10 +927,20 @@ impl FormattingCell {
         self.cell.borrow_mut().set_property_default(property)
     }
 
+    /// Set the default values of all properties set in the provided format.
+    pub fn set_default_format(&self, format: Format) {
+        self.cell.borrow_mut().set_default_format(format)
+    }
+
     /// Resolve property by applying default values if needed.
     pub fn resolve_property(&self, property: Property) -> ResolvedProperty {
         self.cell.borrow().resolve_property(property)
     }
+
+    /// Merge adjacent spans holding equal values.
+    pub fn compact(&self) {
+        self.cell.borrow_mut().compact()
+    }
 }
 
 macro_rules! define_formatting_cell_getters {
@@ -623,3 +957,34 @@ macro_rules! define_formatting_cell_getters {
 }
 
 with_formatting_properties! { define_formatting_cell_getters }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn font_family_chunks_are_aligned_with_grapheme_clusters() {
+        let rope = Rope::from("ab🧑🏾cd");
+        let mut formatting = Formatting::new();
+        formatting.set_resize_with_default(default(), rope.last_byte_index());
+        let code = FontFamily("DejaVu Sans Mono");
+        assert_eq!(code, FontFamily::new("dejavu sans mono"));
+        assert_eq!(code.name().unwrap().normalized, "dejavu sans mono");
+        // The range ends inside of the emoji grapheme cluster.
+        formatting.set_property(Range { start: Byte(1), end: Byte(4) }, code.into());
+        let chunks = formatting.chunks_per_font_family(&rope).collect_vec();
+        let emoji_end = Byte(2 + "🧑🏾".len());
+        let expected = vec![
+            (Byte(0)..Byte(1), FontFamily::default()),
+            (Byte(1)..emoji_end, code),
+            (emoji_end..rope.last_byte_index(), FontFamily::default()),
+        ];
+        assert_eq!(chunks, expected);
+    }
+}
@@ -0,0 +1,178 @@
+//! Serializer of the formatted text to HTML. The HTML is written to the clipboard alongside the
+//! plain text, so the color, size, weight, and style of the copied text survive pasting into other
+//! applications, like text processors or e-mail clients.
+
+use crate::prelude::*;
+use enso_text::unit::*;
+
+use crate::buffer::formatting;
+use crate::buffer::formatting::color;
+use crate::buffer::formatting::Formatting;
+use crate::buffer::formatting::FormattingForByte;
+
+
+
+// ===============
+// === To HTML ===
+// ===============
+
+/// Serialize the text and its formatting to an HTML fragment. The `formatting` has to cover the
+/// whole text, like the formatting of a buffer range narrowed with [`Formatting::sub`]. Every run
+/// of characters with the same style is placed in a `span` element with an inline style. The
+/// whitespace is preserved, and the line breaks are converted to `br` elements.
+pub fn to_html(text: &str, formatting: &Formatting) -> String {
+    with_preserved_whitespace(&spans(text, formatting))
+}
+
+/// Serialize many formatted text chunks, like the contents of many selections, to a single HTML
+/// fragment. The chunks are separated with `br` elements, just like they are separated with
+/// newlines in the plain text. See [`to_html`] to learn more.
+pub fn chunks_to_html(chunks: &[(String, Formatting)]) -> String {
+    let spans = chunks.iter().map(|(text, formatting)| spans(text, formatting));
+    with_preserved_whitespace(&spans.join("<br>"))
+}
+
+/// The `span` elements of every run of characters with the same style.
+fn spans(text: &str, formatting: &Formatting) -> String {
+    let mut runs: Vec<(String, String)> = default();
+    let mut bytes = formatting.iter_bytes();
+    for char in text.chars() {
+        let css = bytes.next().map(css).unwrap_or_default();
+        bytes.skip_bytes(Byte(char.len_utf8() - 1));
+        match runs.last_mut() {
+            Some((run_css, run)) if *run_css == css => run.push(char),
+            _ => runs.push((css, char.into())),
+        }
+    }
+    let mut html = String::new();
+    for (css, run) in runs {
+        html.push_str(&format!("<span style=\"{css}\">{}</span>", escape(&run)));
+    }
+    html
+}
+
+/// Wrap the HTML in a block element preserving the whitespace.
+fn with_preserved_whitespace(html: &str) -> String {
+    format!("<div style=\"white-space: pre-wrap\">{html}</div>")
+}
+
+/// The inline CSS style of the text with the given formatting. The properties without CSS
+/// equivalents, like the outline or the emphasis, are skipped.
+fn css(format: FormattingForByte) -> String {
+    let color = color::Rgba::from(format.color).to_javascript_string();
+    let size = format.font_size.value * format.script.size_scale();
+    let weight = format.weight.to_number();
+    let style = match format.style {
+        formatting::Style::Normal => "normal",
+        formatting::Style::Italic => "italic",
+        formatting::Style::Oblique => "oblique",
+    };
+    let mut css = format!("color: {color}; font-size: {size}px; font-weight: {weight}; ");
+    css.push_str(&format!("font-style: {style}"));
+    if let Some(family) = format.font_family.name() {
+        let family = family.replace(['\'', '"'], "");
+        css.push_str(&format!("; font-family: '{family}'"));
+    }
+    match format.script {
+        formatting::Script::Normal => {}
+        formatting::Script::Super => css.push_str("; vertical-align: super"),
+        formatting::Script::Sub => css.push_str("; vertical-align: sub"),
+    }
+    let mut lines = vec![];
+    if format.underline.value {
+        lines.push("underline");
+    }
+    if format.strikethrough.value {
+        lines.push("line-through");
+    }
+    if !lines.is_empty() {
+        css.push_str(&format!("; text-decoration-line: {}", lines.join(" ")));
+    }
+    if format.underline.value {
+        let style = match format.underline_style {
+            formatting::UnderlineStyle::Solid => "solid",
+            formatting::UnderlineStyle::Dashed => "dashed",
+            formatting::UnderlineStyle::Wavy => "wavy",
+        };
+        css.push_str(&format!("; text-decoration-style: {style}"));
+        if let Some(color) = format.underline_color.color {
+            let color = color::Rgba::from(color).to_javascript_string();
+            css.push_str(&format!("; text-decoration-color: {color}"));
+        }
+    }
+    css
+}
+
+/// Escape the HTML special characters and convert the line breaks to `br` elements.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\n' => escaped.push_str("<br>"),
+            '\r' => {}
+            _ => escaped.push(char),
+        }
+    }
+    escaped
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::buffer::Range;
+
+    #[test]
+    fn formatted_runs_are_serialized() {
+        let text = "a<b\nbold";
+        let mut formatting = Formatting::new();
+        formatting.set_resize_with_default(default(), Byte(text.len()));
+        let bold_range = Range { start: Byte(4), end: Byte(8) };
+        formatting.set_property(bold_range, formatting::Weight::Bold.into());
+        let html = to_html(text, &formatting);
+        let spans = html.split("<span").skip(1).collect_vec();
+        assert_eq!(spans.len(), 2);
+        assert!(spans[0].contains("font-weight: 400"));
+        assert!(spans[0].ends_with(">a&lt;b<br></span>"));
+        assert!(spans[1].contains("font-weight: 700; font-style: normal\">bold</span>"));
+        assert!(html.starts_with("<div style=\"white-space: pre-wrap\">"));
+    }
+
+    #[test]
+    fn decorations_are_serialized() {
+        let text = "typo";
+        let mut formatting = Formatting::new();
+        formatting.set_resize_with_default(default(), Byte(text.len()));
+        let range = Range { start: Byte(0), end: Byte(4) };
+        formatting.set_property(range, formatting::Underline(true).into());
+        formatting.set_property(range, formatting::UnderlineStyle::Wavy.into());
+        let html = to_html(text, &formatting);
+        assert!(html.contains("; text-decoration-line: underline; text-decoration-style: wavy\""));
+        formatting.set_property(range, formatting::Strikethrough(true).into());
+        let html = to_html(text, &formatting);
+        assert!(html.contains("; text-decoration-line: underline line-through;"));
+    }
+
+    #[test]
+    fn chunks_are_separated() {
+        let chunk = |text: &str| {
+            let mut formatting = Formatting::new();
+            formatting.set_resize_with_default(default(), Byte(text.len()));
+            (text.to_string(), formatting)
+        };
+        let html = chunks_to_html(&[chunk("foo"), chunk("bar")]);
+        assert_eq!(html.matches("<div").count(), 1);
+        assert!(html.contains(">foo</span><br><span"));
+        assert!(html.ends_with(">bar</span></div>"));
+    }
+}
@@ -0,0 +1,84 @@
+//! The indentation unit, inserted at the beginning of the lines when indenting them and removed
+//! when outdenting them. See the `indent_selection` and `outdent_selection` buffer inputs.
+
+use crate::prelude::*;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The number of spaces of the default indentation unit.
+pub const DEFAULT_INDENT_WIDTH: usize = 4;
+
+
+
+// ==================
+// === IndentUnit ===
+// ==================
+
+/// A single level of indentation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IndentUnit {
+    /// A single tab character.
+    Tab,
+    /// The given number of spaces.
+    Spaces(usize),
+}
+
+impl Default for IndentUnit {
+    fn default() -> Self {
+        Self::Spaces(DEFAULT_INDENT_WIDTH)
+    }
+}
+
+impl IndentUnit {
+    /// The text inserted when indenting a line.
+    pub fn text(self) -> String {
+        match self {
+            Self::Tab => "\t".into(),
+            Self::Spaces(count) => " ".repeat(count),
+        }
+    }
+
+    /// The number of bytes removed from the beginning of the line when outdenting it. A leading
+    /// tab is a single level of indentation regardless of the unit. Otherwise, the leading spaces
+    /// are removed, up to the width of the unit. The width of [`Self::Tab`] is the
+    /// [`DEFAULT_INDENT_WIDTH`].
+    pub fn outdent_len(self, line: &str) -> usize {
+        if line.starts_with('\t') {
+            return 1;
+        }
+        let width = match self {
+            Self::Tab => DEFAULT_INDENT_WIDTH,
+            Self::Spaces(count) => count,
+        };
+        line.bytes().take(width).take_while(|byte| *byte == b' ').count()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_are_outdented_by_single_unit() {
+        let two_spaces = IndentUnit::Spaces(2);
+        assert_eq!(two_spaces.text(), "  ");
+        assert_eq!(IndentUnit::Tab.text(), "\t");
+        assert_eq!(two_spaces.outdent_len("     foo"), 2);
+        assert_eq!(two_spaces.outdent_len(" foo"), 1);
+        assert_eq!(two_spaces.outdent_len("\t\tfoo"), 1);
+        assert_eq!(two_spaces.outdent_len("foo"), 0);
+        assert_eq!(IndentUnit::Tab.outdent_len("\tfoo"), 1);
+        assert_eq!(IndentUnit::Tab.outdent_len("      foo"), 4);
+        assert_eq!(IndentUnit::Tab.outdent_len(""), 0);
+    }
+}
@@ -0,0 +1,105 @@
+//! Line-oriented transformations of the text, like sorting lines or removing duplicated lines. They
+//! are applied to the lines covered by selections with the `transform_lines` buffer input.
+
+use crate::prelude::*;
+
+
+
+// =====================
+// === LineTransform ===
+// =====================
+
+/// A transformation of a block of lines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineTransform {
+    /// Sort the lines lexicographically.
+    #[default]
+    Sort,
+    /// Sort the lines lexicographically, in descending order.
+    SortDescending,
+    /// Sort the lines lexicographically, ignoring the letter case.
+    SortCaseInsensitive,
+    /// Remove lines equal to one of the previous lines.
+    RemoveDuplicates,
+    /// Remove whitespace at the end of every line.
+    TrimTrailingWhitespace,
+    /// Join the lines into a single line. The leading whitespace of all lines but the first one is
+    /// replaced with a single space.
+    Join,
+}
+
+impl LineTransform {
+    /// Whether the transformation of a single line should include the following line as well. It
+    /// is the case for joining lines, which would not change a single line.
+    pub fn extends_single_line(self) -> bool {
+        self == Self::Join
+    }
+
+    /// Transform the lines. The lines should not contain the line endings.
+    pub fn apply(self, mut lines: Vec<&str>) -> Vec<String> {
+        match self {
+            Self::Sort => lines.sort(),
+            Self::SortDescending => lines.sort_by(|a, b| b.cmp(a)),
+            Self::SortCaseInsensitive => lines.sort_by_cached_key(|line| line.to_lowercase()),
+            Self::RemoveDuplicates => {
+                let mut seen = HashSet::new();
+                lines.retain(|line| seen.insert(*line));
+            }
+            Self::TrimTrailingWhitespace =>
+                lines.iter_mut().for_each(|line| *line = (*line).trim_end()),
+            Self::Join => {
+                let mut joined = String::new();
+                for (index, line) in lines.iter().enumerate() {
+                    if index == 0 {
+                        joined.push_str(line);
+                    } else {
+                        let line = line.trim_start();
+                        if !line.is_empty() {
+                            if !joined.is_empty() && !joined.ends_with(char::is_whitespace) {
+                                joined.push(' ');
+                            }
+                            joined.push_str(line);
+                        }
+                    }
+                }
+                return vec![joined];
+            }
+        }
+        lines.into_iter().map(|line| line.to_owned()).collect()
+    }
+
+    /// Transform the text consisting of many lines. The line endings of the result are `\r\n` if
+    /// the text contains them, or `\n` otherwise.
+    pub fn apply_to_text(self, text: &str) -> String {
+        let line_ending = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        let lines = text.split('\n').map(|line| line.strip_suffix('\r').unwrap_or(line));
+        self.apply(lines.collect()).join(line_ending)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_are_transformed() {
+        let text = "b\nA\na  \nb\n";
+        let test = |transform: LineTransform, expected: &str| {
+            assert_eq!(transform.apply_to_text(text), expected, "{transform:?}");
+        };
+        test(LineTransform::Sort, "\nA\na  \nb\nb");
+        test(LineTransform::SortDescending, "b\nb\na  \nA\n");
+        test(LineTransform::SortCaseInsensitive, "\nA\na  \nb\nb");
+        test(LineTransform::RemoveDuplicates, "b\nA\na  \n");
+        test(LineTransform::TrimTrailingWhitespace, "b\nA\na\nb\n");
+        test(LineTransform::Join, "b A a  b");
+        assert_eq!(LineTransform::Join.apply_to_text("foo\r\n    bar"), "foo bar");
+        assert_eq!(LineTransform::Sort.apply_to_text("b\r\na"), "a\r\nb");
+    }
+}
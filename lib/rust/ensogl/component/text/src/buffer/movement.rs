@@ -121,6 +121,7 @@ impl BufferModel {
         modify: bool,
     ) -> Selection {
         let text = &self.text();
+        let word_policy = self.word_policy.borrow();
         let shape = selection::Shape;
         let shape: selection::Shape = match transform {
             Transform::All => shape(default(), self.last_line_last_location()),
@@ -174,7 +175,7 @@ impl BufferModel {
 
             Transform::LeftWord => {
                 let end_offset = Byte::from_in_context_snapped(self, selection.end);
-                let mut word_cursor = WordCursor::new(text, end_offset);
+                let mut word_cursor = WordCursor::new(text, end_offset, &word_policy);
                 let offset = word_cursor.prev_boundary().unwrap_or_else(|| 0.byte());
                 let end = Location::from_in_context_snapped(self, offset);
                 shape(selection.start, end)
@@ -182,7 +183,7 @@ impl BufferModel {
 
             Transform::RightWord => {
                 let end_offset = Byte::from_in_context_snapped(self, selection.end);
-                let mut word_cursor = WordCursor::new(text, end_offset);
+                let mut word_cursor = WordCursor::new(text, end_offset, &word_policy);
                 let offset = word_cursor.next_boundary().unwrap_or_else(|| text.last_byte_index());
                 let end = Location::from_in_context_snapped(self, offset);
                 shape(selection.start, end)
@@ -190,7 +191,7 @@ impl BufferModel {
 
             Transform::Word => {
                 let end_offset = Byte::from_in_context_snapped(self, selection.end);
-                let mut word_cursor = WordCursor::new(text, end_offset);
+                let mut word_cursor = WordCursor::new(text, end_offset, &word_policy);
                 let offsets = word_cursor.select_word();
                 let start = Location::from_in_context_snapped(self, offsets.0);
                 let end = Location::from_in_context_snapped(self, offsets.1);
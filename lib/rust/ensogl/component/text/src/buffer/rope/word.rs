@@ -7,6 +7,65 @@ use enso_text::rope;
 
 
 
+// ================
+// === WordMode ===
+// ================
+
+/// The classification of characters used to find word boundaries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WordMode {
+    /// Words are separated by whitespace and ASCII punctuation other than `_`. Suitable for prose.
+    #[default]
+    Text,
+    /// Words consist of alphanumeric characters and `_` only, like identifiers in most
+    /// programming languages. All other characters, including non-ASCII symbols, are punctuation.
+    Identifier,
+}
+
+
+
+// ==================
+// === WordPolicy ===
+// ==================
+
+/// The policy defining which characters form words. It is respected by word movement, word
+/// selection, and word deletion.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WordPolicy {
+    /// The classification of characters not listed in [`Self::word_chars`].
+    pub mode:       WordMode,
+    /// Characters treated as word characters regardless of the mode, like `-` for kebab-case
+    /// names. Whitespace and line breaks listed here are ignored.
+    pub word_chars: Vec<char>,
+}
+
+impl WordPolicy {
+    /// The policy for editing code, see [`WordMode::Identifier`].
+    pub fn identifier() -> Self {
+        Self { mode: WordMode::Identifier, ..default() }
+    }
+
+    /// Treat the characters as word characters. See [`Self::word_chars`].
+    pub fn with_word_chars(mut self, chars: &str) -> Self {
+        self.word_chars.extend(chars.chars());
+        self
+    }
+
+    fn char_class(&self, codepoint: char) -> CharClass {
+        let is_space = codepoint <= ' ';
+        if !is_space && self.word_chars.contains(&codepoint) {
+            CharClass::Other
+        } else {
+            match self.mode {
+                WordMode::Text => char_class(codepoint),
+                WordMode::Identifier => identifier_char_class(codepoint),
+            }
+        }
+    }
+}
+
+
+
 // ==================
 // === WordCursor ===
 // ==================
@@ -14,13 +73,14 @@ use enso_text::rope;
 /// Cursor allowing word-based traversal.
 pub struct WordCursor<'a> {
     cursor: rope::Cursor<'a, rope::Info>,
+    policy: &'a WordPolicy,
 }
 
 impl<'a> WordCursor<'a> {
     /// Constructor.
-    pub fn new(text: &'a rope::XiRope, pos: Byte) -> WordCursor<'a> {
+    pub fn new(text: &'a rope::XiRope, pos: Byte, policy: &'a WordPolicy) -> WordCursor<'a> {
         let cursor = rope::Cursor::new(text, pos.value);
-        WordCursor { cursor }
+        WordCursor { cursor, policy }
     }
 
     /// Get previous boundary, and set the cursor at the boundary found.
@@ -118,11 +178,11 @@ impl<'a> WordCursor<'a> {
     }
 
     fn next_codepoint_class(&mut self) -> Option<CharClass> {
-        self.cursor.next_codepoint().map(char_class)
+        self.cursor.next_codepoint().map(|codepoint| self.policy.char_class(codepoint))
     }
 
     fn prev_codepoint_class(&mut self) -> Option<CharClass> {
-        self.cursor.prev_codepoint().map(char_class)
+        self.cursor.prev_codepoint().map(|codepoint| self.policy.char_class(codepoint))
     }
 }
 
@@ -227,3 +287,15 @@ fn char_class(codepoint: char) -> CharClass {
     }
     CharClass::Other
 }
+
+fn identifier_char_class(codepoint: char) -> CharClass {
+    if codepoint <= ' ' {
+        char_class(codepoint)
+    } else if codepoint.is_alphanumeric() || codepoint == '_' {
+        CharClass::Other
+    } else if codepoint.is_whitespace() {
+        CharClass::Space
+    } else {
+        CharClass::Punctuation
+    }
+}
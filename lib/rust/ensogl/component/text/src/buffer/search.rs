@@ -0,0 +1,131 @@
+//! Text search queries. A query matches either the exact text, or a regular expression. The
+//! case-insensitive text queries are converted to regular expressions.
+
+use crate::prelude::*;
+use enso_text::unit::*;
+
+use crate::buffer::Range;
+
+use regex::Regex;
+use regex::RegexBuilder;
+
+
+
+// =====================
+// === SearchOptions ===
+// =====================
+
+/// The way the query pattern is matched.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Whether the letter case is ignored.
+    pub case_insensitive: bool,
+    /// Whether the pattern is a regular expression.
+    pub regex:            bool,
+}
+
+
+
+// =============
+// === Query ===
+// =============
+
+/// A text search query.
+#[derive(Clone, Debug)]
+pub enum Query {
+    /// The exact text, matched case-sensitively.
+    Text(ImString),
+    /// A regular expression.
+    Regex(Regex),
+}
+
+impl Default for Query {
+    fn default() -> Self {
+        Self::Text(default())
+    }
+}
+
+impl Query {
+    /// A query matching the exact text.
+    pub fn text(text: impl Into<ImString>) -> Self {
+        Self::Text(text.into())
+    }
+
+    /// Constructor. Fails if the pattern is not a valid regular expression.
+    pub fn new(pattern: &str, options: SearchOptions) -> Result<Self, regex::Error> {
+        if !options.case_insensitive && !options.regex {
+            return Ok(Self::text(pattern));
+        }
+        let pattern = if options.regex { pattern.to_owned() } else { regex::escape(pattern) };
+        let regex = RegexBuilder::new(&pattern).case_insensitive(options.case_insensitive).build();
+        regex.map(Self::Regex)
+    }
+
+    /// All non-overlapping matches in the text, in the order of occurrence. Empty matches are
+    /// skipped, so an empty query matches nothing.
+    pub fn find_all(&self, text: &str) -> Vec<Range<Byte>> {
+        let range = |start: usize, end: usize| Range::new(Byte(start), Byte(end));
+        match self {
+            Self::Text(query) if query.is_empty() => default(),
+            Self::Text(query) => text
+                .match_indices(&**query)
+                .map(|(start, t)| range(start, start + t.len()))
+                .collect(),
+            Self::Regex(regex) => regex
+                .find_iter(text)
+                .filter(|t| !t.as_str().is_empty())
+                .map(|t| range(t.start(), t.end()))
+                .collect(),
+        }
+    }
+
+    /// The first match starting at or after the given offset. The search wraps around to the
+    /// beginning of the text if there are no matches after the offset.
+    pub fn find(&self, text: &str, from: Byte) -> Option<Range<Byte>> {
+        let matches = self.find_all(text);
+        let after = matches.iter().find(|range| range.start >= from);
+        after.or_else(|| matches.first()).copied()
+    }
+
+    /// The last match starting before the given offset. The search wraps around to the end of the
+    /// text if there are no matches before the offset.
+    pub fn find_before(&self, text: &str, before: Byte) -> Option<Range<Byte>> {
+        let matches = self.find_all(text);
+        let prior = matches.iter().rev().find(|range| range.start < before);
+        prior.or_else(|| matches.last()).copied()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_are_matched() {
+        let text = "Foo foo f.o";
+        let ranges = |query: Query| {
+            query.find_all(text).into_iter().map(|t| (t.start.value, t.end.value)).collect_vec()
+        };
+        assert_eq!(ranges(Query::text("foo")), vec![(4, 7)]);
+        assert_eq!(ranges(Query::text("")), vec![]);
+        let case_insensitive = SearchOptions { case_insensitive: true, regex: false };
+        assert_eq!(ranges(Query::new("FOO", case_insensitive).unwrap()), vec![(0, 3), (4, 7)]);
+        assert_eq!(ranges(Query::new("f.o", case_insensitive).unwrap()), vec![(8, 11)]);
+        let regex = SearchOptions { case_insensitive: false, regex: true };
+        assert_eq!(ranges(Query::new("f.o", regex).unwrap()), vec![(4, 7), (8, 11)]);
+        assert_eq!(ranges(Query::new("x*", regex).unwrap()), vec![]);
+        assert!(Query::new("(", regex).is_err());
+
+        let query = Query::text("o");
+        assert_eq!(query.find(text, Byte(3)), Some(Range::new(Byte(5), Byte(6))));
+        assert_eq!(query.find(text, Byte(11)), Some(Range::new(Byte(1), Byte(2))));
+        assert_eq!(query.find_before(text, Byte(5)), Some(Range::new(Byte(2), Byte(3))));
+        assert_eq!(query.find_before(text, Byte(1)), Some(Range::new(Byte(10), Byte(11))));
+    }
+}
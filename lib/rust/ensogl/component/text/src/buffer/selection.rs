@@ -4,6 +4,10 @@ use crate::prelude::*;
 use enso_text::unit::*;
 
 use enso_text::Range;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
 
 
 
@@ -23,6 +27,7 @@ pub trait Boundary = Copy + Ord + Eq;
 /// Selection ID.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, Debug, Display, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Serialize, Deserialize)]
 pub struct Id {
     pub value: usize,
 }
@@ -37,7 +42,7 @@ pub struct Id {
 /// interpreted as a cursor. Please note that the start of the selection is not always smaller than
 /// its end. If the selection was dragged from right to left, the start byte offset will be bigger
 /// than the end. Use the `min` and `max` methods to discover the edges.
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct Shape<T = Location> {
     pub start: T,
@@ -150,7 +155,7 @@ impl<T: Boundary> Shape<T> {
 
 /// Text selection. It is a text selection [`Shape`] bundled with an [`Id`] information, which is
 /// used by graphical interface to track and animate the movement of the selections.
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Deref, DerefMut)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Deref, DerefMut, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct Selection<T = Location> {
     #[deref]
@@ -368,6 +373,23 @@ impl From<Option<Selection>> for Group {
 }
 
 
+// === Serialization ===
+
+/// The group is serialized as a sequence of selections. The sorting and merging invariant is
+/// restored after deserialization.
+impl Serialize for Group {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for Group {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Vec::<Selection>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+
 // === Iterators ===
 
 impl<'t> IntoIterator for &'t Group {
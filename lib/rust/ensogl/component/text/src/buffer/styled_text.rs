@@ -0,0 +1,146 @@
+//! A builder of formatted text, allowing to construct the content and its formatting without
+//! manual byte range arithmetic.
+
+use crate::prelude::*;
+use enso_text::unit::*;
+
+use crate::buffer::formatting;
+use crate::buffer::formatting::Formatting;
+use crate::buffer::formatting::Property;
+use crate::buffer::Range;
+
+use enso_text::Rope;
+
+
+
+// ==================
+// === StyledText ===
+// ==================
+
+/// A builder of formatted text. The text is appended in segments, and the formatting methods apply
+/// to the most recently appended segment:
+///
+/// ```text
+/// StyledText::new().text("err: ").bold().color(red).text(msg)
+/// ```
+///
+/// The result can be installed into a text area with the `set_styled_content` input of
+/// [`crate::Text`], or converted to a [`Rope`] and [`Formatting`] pair with [`StyledText::build`].
+#[derive(Clone, Debug, Default)]
+pub struct StyledText {
+    content:      String,
+    spans:        Vec<(Range<Byte>, Property)>,
+    last_segment: Option<Range<Byte>>,
+}
+
+impl StyledText {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Append a new text segment. The following formatting methods apply to it.
+    pub fn text(mut self, text: impl AsRef<str>) -> Self {
+        let start = Byte(self.content.len());
+        self.content.push_str(text.as_ref());
+        let end = Byte(self.content.len());
+        self.last_segment = Some(Range::new(start, end));
+        self
+    }
+
+    /// Set the property of the last text segment. Does nothing if no text was appended yet.
+    pub fn property(mut self, property: impl Into<Property>) -> Self {
+        if let Some(range) = self.last_segment {
+            self.spans.push((range, property.into()));
+        }
+        self
+    }
+
+    /// Set the color of the last text segment.
+    pub fn color(self, color: impl Into<formatting::color::Lcha>) -> Self {
+        self.property(color.into())
+    }
+
+    /// Set the font size of the last text segment.
+    pub fn size(self, size: f32) -> Self {
+        self.property(formatting::Size(size))
+    }
+
+    /// Set the font weight of the last text segment.
+    pub fn weight(self, weight: formatting::Weight) -> Self {
+        self.property(weight)
+    }
+
+    /// Make the last text segment bold.
+    pub fn bold(self) -> Self {
+        self.weight(formatting::Weight::Bold)
+    }
+
+    /// Make the last text segment italic.
+    pub fn italic(self) -> Self {
+        self.property(formatting::Style::Italic)
+    }
+
+    /// Underline the last text segment.
+    pub fn underline(self) -> Self {
+        self.property(formatting::Underline(true))
+    }
+
+    /// Strike through the last text segment.
+    pub fn strikethrough(self) -> Self {
+        self.property(formatting::Strikethrough(true))
+    }
+
+    /// Set the SDF weight of the last text segment.
+    pub fn sdf_weight(self, weight: f32) -> Self {
+        self.property(formatting::SdfWeight(weight))
+    }
+
+    /// The text content.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// The formatting properties with the byte ranges they apply to, in the order of definition.
+    pub fn spans(&self) -> &[(Range<Byte>, Property)] {
+        &self.spans
+    }
+
+    /// Build the text and its formatting. Properties not set explicitly have default values.
+    pub fn build(&self) -> (Rope, Formatting) {
+        let rope = Rope::from(self.content.as_str());
+        let mut formatting = Formatting::new();
+        formatting.set_resize_with_default(default(), rope.last_byte_index());
+        for (range, property) in &self.spans {
+            formatting.set_property(*range, *property);
+        }
+        (rope, formatting)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn properties_apply_to_the_last_segment() {
+        let styled = StyledText::new().bold().text("err: ").bold().size(14.0).text("msg").italic();
+        assert_eq!(styled.content(), "err: msg");
+        let ranges = styled.spans().iter().map(|(range, property)| (*range, property.tag()));
+        let err = Range::new(Byte(0), Byte(5));
+        let msg = Range::new(Byte(5), Byte(8));
+        assert_eq!(ranges.collect_vec(), vec![
+            (err, formatting::PropertyTag::Weight),
+            (err, formatting::PropertyTag::FontSize),
+            (msg, formatting::PropertyTag::Style),
+        ]);
+        let (rope, _) = styled.build();
+        assert_eq!(rope.to_string(), "err: msg");
+    }
+}
@@ -0,0 +1,158 @@
+//! Substitutions of typed text, like replacing `--` with an em dash or straight quotes with curly
+//! ones. They are applied to the text typed by the user with the `insert_typed` buffer input, and
+//! are meant for prose, so they are disabled by default.
+
+use crate::prelude::*;
+
+
+
+// ============
+// === Rule ===
+// ============
+
+/// A substitution rule. When the text before the cursor ends with the `pattern` after typing, the
+/// pattern is replaced with the `replacement`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub struct Rule {
+    pub pattern:     ImString,
+    pub replacement: ImString,
+}
+
+impl Rule {
+    /// Constructor.
+    pub fn new(pattern: impl Into<ImString>, replacement: impl Into<ImString>) -> Self {
+        let pattern = pattern.into();
+        let replacement = replacement.into();
+        Self { pattern, replacement }
+    }
+}
+
+
+
+// ====================
+// === Substitution ===
+// ====================
+
+/// A substitution to be made after typing: the given number of bytes before the cursor should be
+/// replaced with the `replacement`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub struct Substitution {
+    pub len:         usize,
+    pub replacement: ImString,
+}
+
+
+
+// =====================
+// === Substitutions ===
+// =====================
+
+/// A set of substitution rules. The rules are checked in the order of definition, and the first
+/// matching one is applied. Smart quotes are checked after all the rules.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Substitutions {
+    rules:        Vec<Rule>,
+    smart_quotes: bool,
+}
+
+impl Substitutions {
+    /// Constructor of an empty set of rules.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The rules suitable for prose: smart quotes, dashes, and ellipsis.
+    pub fn prose() -> Self {
+        Self::new().rule("--", "—").rule("...", "…").smart_quotes(true)
+    }
+
+    /// Add a rule replacing the `pattern` with the `replacement`.
+    pub fn rule(mut self, pattern: impl Into<ImString>, replacement: impl Into<ImString>) -> Self {
+        self.rules.push(Rule::new(pattern, replacement));
+        self
+    }
+
+    /// Enable or disable replacing the straight quotes with the curly ones. The opening quote is
+    /// used at the beginning of the line, after whitespace, and after opening brackets.
+    pub fn smart_quotes(mut self, enabled: bool) -> Self {
+        self.smart_quotes = enabled;
+        self
+    }
+
+    /// All the rules, in the order of definition.
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Find the substitution to be made after typing the `typed` text. The `before_cursor` is the
+    /// text of the line before the cursor, including the typed text. Only patterns ending with the
+    /// typed text are taken into account, so the text typed earlier is not substituted again.
+    pub fn find(&self, before_cursor: &str, typed: &str) -> Option<Substitution> {
+        if typed.is_empty() || !before_cursor.ends_with(typed) {
+            return None;
+        }
+        let rule = self.rules.iter().find(|rule| {
+            !rule.pattern.is_empty()
+                && before_cursor.ends_with(&*rule.pattern)
+                && (rule.pattern.ends_with(typed) || typed.ends_with(&*rule.pattern))
+        });
+        let from_rule = rule.map(|rule| Substitution {
+            len:         rule.pattern.len(),
+            replacement: rule.replacement.clone_ref(),
+        });
+        from_rule.or_else(|| self.smart_quotes.then(|| smart_quote(before_cursor)).flatten())
+    }
+}
+
+/// The curly quote replacing the straight quote at the end of the text, if any.
+fn smart_quote(before_cursor: &str) -> Option<Substitution> {
+    let mut chars = before_cursor.chars().rev();
+    let quote = chars.next()?;
+    let opening = match chars.next() {
+        None => true,
+        Some(char) => char.is_whitespace() || "([{<—–-".contains(char),
+    };
+    let replacement = match (quote, opening) {
+        ('"', true) => "“",
+        ('"', false) => "”",
+        ('\'', true) => "‘",
+        ('\'', false) => "’",
+        _ => return None,
+    };
+    Some(Substitution { len: quote.len_utf8(), replacement: replacement.into() })
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_text_is_substituted() {
+        let substitutions = Substitutions::prose().rule("(c)", "©");
+        let test = |before_cursor: &str, typed: &str, expected: Option<(usize, &str)>| {
+            let found = substitutions.find(before_cursor, typed);
+            let found = found.as_ref().map(|t| (t.len, &*t.replacement));
+            assert_eq!(found, expected, "{before_cursor:?}");
+        };
+        test("a -", "-", None);
+        test("a --", "-", Some((2, "—")));
+        test("wait...", ".", Some((3, "…")));
+        test("(c)", ")", Some((3, "©")));
+        test("\"", "\"", Some((1, "“")));
+        test("say \"", "\"", Some((1, "“")));
+        test("(\"", "\"", Some((1, "“")));
+        test("“quoted\"", "\"", Some((1, "”")));
+        test("don'", "'", Some((1, "’")));
+        test("'", "'", Some((1, "‘")));
+        test("--a", "a", None);
+        assert_eq!(Substitutions::new().find("a --", "-"), None);
+    }
+}
@@ -5,13 +5,29 @@
 // === Export ===
 // ==============
 
+pub mod annotation;
+pub mod binding;
+pub mod clipboard;
+pub mod dom_mirror;
+pub mod editing_state;
+pub mod font_features;
+pub mod gutter;
+pub mod highlight;
+pub mod hyphenation;
+pub mod label_batch;
 pub mod line;
+pub mod pagination;
+pub mod shape_cache;
 pub mod text;
+pub mod token_style;
+pub mod whitespace;
+pub mod wrap;
 
 
 
 #[warn(missing_docs)]
 mod selection;
 
+pub use label_batch::LabelBatch;
 use selection::Selection;
 pub use text::Text;
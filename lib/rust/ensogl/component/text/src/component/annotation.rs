@@ -0,0 +1,261 @@
+//! Annotations of text ranges provided by external tools, like linter warnings or review comments.
+//! The annotations are exchanged as a serializable [`Document`]. After being imported into the text
+//! area, they follow the text through edits, so the tool can export them back with the ranges
+//! adjusted to the current text.
+
+use crate::prelude::*;
+use enso_text::unit::*;
+
+use crate::buffer;
+
+use ensogl_core::data::color;
+use ensogl_core::display;
+use ensogl_core::display::shape::Rectangle;
+use serde::Deserialize;
+use serde::Serialize;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The version of the annotation document format. Documents of other versions are rejected.
+pub const FORMAT_VERSION: u32 = 1;
+
+
+
+// =============
+// === Style ===
+// =============
+
+/// The style of the annotated text range.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Style {
+    /// The background color of the range, as RGBA components in the range from 0 to 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background: Option<[f32; 4]>,
+}
+
+impl Style {
+    /// The background color, if any.
+    pub fn background_color(&self) -> Option<color::Rgba> {
+        self.background.map(|[r, g, b, a]| color::Rgba::new(r, g, b, a))
+    }
+}
+
+
+
+// ==================
+// === Annotation ===
+// ==================
+
+/// An annotation of the text range. The range is expressed in byte offsets.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct Annotation {
+    pub start: usize,
+    pub end:   usize,
+    /// The type of the annotation, like `warning` or `comment`, defined by the tool providing the
+    /// annotations.
+    pub kind:  String,
+    /// Arbitrary metadata of the annotation, like the message, attached by the tool.
+    #[serde(default)]
+    pub data:  serde_json::Value,
+    #[serde(default)]
+    pub style: Style,
+}
+
+impl Annotation {
+    /// Constructor.
+    pub fn new(range: buffer::Range<Byte>, kind: impl Into<String>) -> Self {
+        let mut annotation = Self { kind: kind.into(), ..default() };
+        annotation.set_range(range);
+        annotation
+    }
+
+    /// The annotated range.
+    pub fn range(&self) -> buffer::Range<Byte> {
+        buffer::Range::new(Byte(self.start), Byte(self.end))
+    }
+
+    /// Set the annotated range.
+    pub fn set_range(&mut self, range: buffer::Range<Byte>) {
+        self.start = range.start.value;
+        self.end = range.end.value;
+    }
+
+    /// Move the range to keep it in place after the text change. Text inserted at the range
+    /// boundaries is not included in the range.
+    pub fn apply_change(&mut self, change: &buffer::Change) {
+        self.set_range(follow_change(self.range(), change));
+    }
+}
+
+/// Move the range to keep it in place after the text change. Text inserted at the range boundaries
+/// is not included in the range.
+pub fn follow_change(range: buffer::Range<Byte>, change: &buffer::Change) -> buffer::Range<Byte> {
+    let is_insertion = change.range.start == change.range.end;
+    let start = if is_insertion && change.range.start == range.start {
+        Byte(range.start.value + change.text.last_byte_index().value)
+    } else {
+        change.transform_byte(range.start)
+    };
+    let end = std::cmp::max(start, change.transform_byte(range.end));
+    buffer::Range::new(start, end)
+}
+
+
+
+// ================
+// === Document ===
+// ================
+
+/// A serializable set of annotations.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct Document {
+    /// The format version, see [`FORMAT_VERSION`].
+    pub version:     u32,
+    pub annotations: Vec<Annotation>,
+}
+
+impl Default for Document {
+    fn default() -> Self {
+        Self::new(default())
+    }
+}
+
+impl Document {
+    /// Constructor.
+    pub fn new(annotations: Vec<Annotation>) -> Self {
+        Self { version: FORMAT_VERSION, annotations }
+    }
+
+    /// Serialize the document to JSON.
+    pub fn to_json(&self) -> String {
+        // Serializing plain structs with string keys can not fail.
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Deserialize the document from JSON. Fails if the document has unsupported version.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let document: Self = serde_json::from_str(json)?;
+        if document.version != FORMAT_VERSION {
+            let error = format!("Unsupported annotation format version {}.", document.version);
+            return Err(serde::de::Error::custom(error));
+        }
+        Ok(document)
+    }
+
+    /// Move the annotation ranges to keep them in place after the text changes.
+    pub fn apply_changes<'a>(&mut self, changes: impl IntoIterator<Item = &'a buffer::Change>) {
+        for change in changes {
+            for annotation in &mut self.annotations {
+                annotation.apply_change(change);
+            }
+        }
+    }
+}
+
+
+
+// ============
+// === View ===
+// ============
+
+/// The view displaying the backgrounds of annotated text ranges.
+#[derive(Clone, CloneRef, Debug, display::Object)]
+pub struct View {
+    display_object: display::object::Instance,
+    views:          Rc<RefCell<Vec<Rectangle>>>,
+    /// The annotations imported into the text area.
+    pub document:   Rc<RefCell<Document>>,
+}
+
+impl View {
+    /// Constructor.
+    pub fn new() -> Self {
+        let display_object = display::object::Instance::new_named("Annotations");
+        let views = default();
+        let document = default();
+        Self { display_object, views, document }
+    }
+
+    /// Redraw the backgrounds. The `range_rects` function should return the bottom-left corners
+    /// and the sizes of the visible parts of the given range, one per line.
+    pub fn redraw(&self, range_rects: impl Fn(buffer::Range<Byte>) -> Vec<(Vector2, Vector2)>) {
+        let mut views = self.views.borrow_mut();
+        for view in views.drain(..) {
+            view.unset_parent();
+        }
+        for annotation in &self.document.borrow().annotations {
+            if let Some(color) = annotation.style.background_color() {
+                for (position, size) in range_rects(annotation.range()) {
+                    let view = Rectangle().build(|r| {
+                        r.set_pointer_events(false);
+                        r.set_color(color);
+                    });
+                    view.set_xy(position);
+                    view.set_size(size);
+                    self.display_object.add_child(&view);
+                    views.push(view);
+                }
+            }
+        }
+    }
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insertion(offset: usize, text: &str) -> buffer::Change {
+        let range = buffer::Range::new(Byte(offset), Byte(offset));
+        let change = enso_text::Change { range, text: text.into() };
+        buffer::Change { change, ..default() }
+    }
+
+    #[test]
+    fn document_is_serialized() {
+        let mut annotation = Annotation::new(buffer::Range::new(Byte(2), Byte(5)), "warning");
+        annotation.data = serde_json::json!({ "message": "Unused variable." });
+        annotation.style.background = Some([1.0, 0.0, 0.0, 0.5]);
+        let document = Document::new(vec![annotation]);
+        let json = document.to_json();
+        assert_eq!(Document::from_json(&json).unwrap(), document);
+        let minimal = r#"{"version":1,"annotations":[{"start":0,"end":1,"kind":"comment"}]}"#;
+        let minimal = Document::from_json(minimal).unwrap();
+        assert_eq!(minimal.annotations[0].data, serde_json::Value::Null);
+        assert!(Document::from_json(r#"{"version":2,"annotations":[]}"#).is_err());
+    }
+
+    #[test]
+    fn ranges_follow_text_changes() {
+        let range = |start, end| buffer::Range::new(Byte(start), Byte(end));
+        let mut document = Document::new(vec![
+            Annotation::new(range(2, 5), "a"),
+            Annotation::new(range(6, 8), "b"),
+        ]);
+        document.apply_changes(&[insertion(2, "xx"), insertion(7, "y")]);
+        let ranges = document.annotations.iter().map(|t| t.range()).collect_vec();
+        assert_eq!(ranges, vec![range(4, 7), range(9, 11)]);
+
+        let removal = enso_text::Change { range: range(3, 10), text: "".into() };
+        document.apply_changes(&[buffer::Change { change: removal, ..default() }]);
+        let ranges = document.annotations.iter().map(|t| t.range()).collect_vec();
+        assert_eq!(ranges, vec![range(3, 3), range(3, 4)]);
+    }
+}
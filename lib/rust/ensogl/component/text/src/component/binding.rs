@@ -0,0 +1,85 @@
+//! A bidirectional binding between the text area and an external text model, like a file edited
+//! by the language server or a node expression kept by the engine. The binding applies the changes
+//! made in the external model to the text area (moving its selections to keep them in place), and
+//! emits the changes made in the text area, so they can be applied to the external model.
+//!
+//! The changes applied from the external model are not emitted back (echo suppression), so the
+//! binding can be connected to the external model in both directions without creating feedback
+//! loops.
+
+use crate::prelude::*;
+
+use crate::component::Text;
+
+use enso_frp as frp;
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+ensogl_core::define_endpoints_2! {
+    Input {
+        /// Apply the changes made in the external text model to the text area. Every change range
+        /// is expressed in the text after applying the previous changes.
+        apply_remote_changes (Rc<Vec<enso_text::Change>>),
+    }
+    Output {
+        /// The changes made in the text area, which should be applied to the external text model.
+        /// The changes applied by [`Input::apply_remote_changes`] are not emitted.
+        local_changes (Rc<Vec<enso_text::Change>>),
+    }
+}
+
+
+
+// ===============
+// === Binding ===
+// ===============
+
+/// A bidirectional binding between the text area and an external text model. See the module docs
+/// to learn more.
+#[derive(Clone, CloneRef, Debug, Deref)]
+#[allow(missing_docs)]
+pub struct Binding {
+    #[deref]
+    pub frp:         Frp,
+    text:            Text,
+    applying_remote: Rc<Cell<bool>>,
+}
+
+impl Binding {
+    /// Constructor. The binding is active as long as it is not dropped.
+    pub fn new(text: &Text) -> Self {
+        let frp = Frp::new();
+        let text = text.clone_ref();
+        let applying_remote = default();
+        Self { frp, text, applying_remote }.init()
+    }
+
+    fn init(self) -> Self {
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+        let text = &self.text;
+        let applying_remote = &self.applying_remote;
+        frp::extend! { network
+            eval input.apply_remote_changes ([text, applying_remote](changes) {
+                applying_remote.set(true);
+                text.apply_changes(changes);
+                applying_remote.set(false);
+            });
+            local_changes <- text.changed.filter(f_!(!applying_remote.get()));
+            out.local_changes <+ local_changes.map(|changes| {
+                Rc::new(changes.iter().map(|change| change.change.clone()).collect())
+            });
+        }
+        self
+    }
+
+    /// The bound text area.
+    pub fn text(&self) -> &Text {
+        &self.text
+    }
+}
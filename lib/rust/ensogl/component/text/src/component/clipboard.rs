@@ -0,0 +1,117 @@
+//! Copying and pasting the text of the selections using the system clipboard.
+//!
+//! # Clipboard Content Format
+//!
+//! The text of every selection is a separate chunk, so the text copied from many selections can be
+//! pasted into the same number of selections. A single chunk is written as plain text. Many chunks
+//! are written as a JSON-encoded list marked with our custom [`MIME_TYPE`], and as plain text with
+//! the chunks joined with newlines, so they can be pasted into other applications. The formatted
+//! chunks are also written as HTML, so the text style is preserved when pasting into applications
+//! supporting rich text, see [`crate::buffer::html`].
+//!
+//! If the browser cannot read the custom format back (see [`clipboard::is_custom_supported`]), or
+//! writing all the flavours fails, the plain text is the only way to pass the chunks between
+//! selections, so they are joined with the [`RECORD_SEPARATOR`] instead of newlines. In the oldest
+//! browsers, only the plain text is written, using the obsolete `execCommand`.
+//!
+//! When reading, the custom format has a priority over the plain text. The plain text is split at
+//! the [`RECORD_SEPARATOR`]s.
+
+use crate::prelude::*;
+
+use ensogl_core::system::web::clipboard;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The MIME type of the plain text flavour.
+pub const PLAIN_TEXT_MIME_TYPE: &str = "text/plain";
+
+/// The MIME type of the rich text flavour.
+pub const HTML_MIME_TYPE: &str = "text/html";
+
+/// The MIME type of the many chunks. We use the `web` prefix to be able to use a custom MIME type.
+/// See [Clipboard pickling](https://github.com/w3c/editing/blob/gh-pages/docs/clipboard-pickling/explainer.md).
+pub const MIME_TYPE: &str = "web application/enso-text";
+
+/// Record separator ASCII code. Used for separating of copied strings in the plain text. It is
+/// defined as the `\RS` escape code (`x1E`) (https://en.wikipedia.org/wiki/ASCII).
+pub const RECORD_SEPARATOR: &str = "\x1E";
+
+
+
+// ===================
+// === Write, Read ===
+// ===================
+
+/// Write the text chunks to the clipboard, one chunk per selection. The `html` contains the
+/// formatted chunks, written as the rich text flavour, see [`crate::buffer::html::chunks_to_html`].
+pub fn write(chunks: &[String], html: &str) {
+    let is_custom_supported = clipboard::is_custom_supported();
+    let separator = if is_custom_supported { "\n" } else { RECORD_SEPARATOR };
+    let text = chunks.join(separator);
+    // Written instead of all the flavours if writing them fails at runtime.
+    let fallback_text = is_custom_supported.then(|| chunks.join(RECORD_SEPARATOR));
+    let mut flavours = vec![
+        (PLAIN_TEXT_MIME_TYPE.to_string(), text.as_bytes()),
+        (HTML_MIME_TYPE.to_string(), html.as_bytes()),
+    ];
+    let encoded = match chunks {
+        [_, _, ..] => serde_json::to_string(chunks)
+            .map_err(|error| warn!("Failed to encode the copied text: {error}"))
+            .ok(),
+        _ => None,
+    };
+    if let Some(encoded) = &encoded {
+        flavours.push((MIME_TYPE.to_string(), encoded.as_bytes()));
+    }
+    clipboard::write_flavours(&flavours, fallback_text);
+}
+
+/// Read the text chunks from the clipboard. The callback is called asynchronously, see
+/// [`clipboard::read`].
+pub fn read(callback: impl Fn(Vec<String>) + 'static) {
+    let callback = Rc::new(callback);
+    let plain_text_callback = callback.clone_ref();
+    clipboard::read(
+        MIME_TYPE.to_string(),
+        move |data| callback(decode(&data)),
+        move |text| plain_text_callback(decode_plain_text(&text)),
+    );
+}
+
+/// Decode the chunks written in the custom format. The malformed data is read as plain text.
+pub fn decode(data: &[u8]) -> Vec<String> {
+    serde_json::from_slice(data).unwrap_or_else(|error| {
+        warn!("The copied text has an incorrect format: {error}");
+        decode_plain_text(&String::from_utf8_lossy(data))
+    })
+}
+
+/// Decode the chunks written as plain text.
+pub fn decode_plain_text(text: &str) -> Vec<String> {
+    text.split(RECORD_SEPARATOR).map(|chunk| chunk.into()).collect()
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_are_decoded() {
+        let chunks = vec!["foo".to_string(), "bar\nbaz".to_string()];
+        let encoded = serde_json::to_string(&chunks).unwrap();
+        assert_eq!(decode(encoded.as_bytes()), chunks);
+        assert_eq!(decode_plain_text("foo\x1Ebar\nbaz"), chunks);
+        assert_eq!(decode(b"foo"), vec!["foo".to_string()]);
+    }
+}
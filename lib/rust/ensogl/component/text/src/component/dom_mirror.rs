@@ -0,0 +1,287 @@
+//! A mirror of the text content in real DOM elements. The text area is drawn on a canvas, so its
+//! text can not be selected with the browser, found with the find-in-page, or indexed by search
+//! engines. The mirror keeps a DOM element per line in sync with the text, updating only the lines
+//! touched by the changes, so embedding text areas in documentation pages makes their content
+//! available to the browser.
+
+use crate::prelude::*;
+use enso_text::unit::*;
+use ensogl_core::system::web::traits::*;
+
+use enso_text::Rope;
+use ensogl_core::display;
+use ensogl_core::display::object::ObjectOps;
+use ensogl_core::display::DomSymbol;
+use ensogl_core::system::web;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The CSS class of the element containing the mirrored lines. Pages embedding the text areas can
+/// use it to style the mirrored text.
+pub const CLASS_NAME: &str = "ensogl-text-mirror";
+
+
+
+// ============
+// === Mode ===
+// ============
+
+/// Whether and how the text is mirrored to the DOM.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// The text is not mirrored.
+    #[default]
+    Disabled,
+    /// The mirrored text is visually hidden. It can be found with the find-in-page, indexed, and
+    /// read by screen readers, but not selected.
+    Hidden,
+    /// The mirrored text is transparent and placed over the drawn text, so it can be selected and
+    /// found with the find-in-page in place. The mirror captures the mouse events, so this mode is
+    /// meant for text areas which are not edited with the mouse, like labels.
+    Overlay,
+}
+
+
+
+// ==============
+// === Layout ===
+// ==============
+
+/// The placement of the mirrored text, matching the drawn text.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[allow(missing_docs)]
+pub struct Layout {
+    /// The size of the visible part of the text area.
+    pub size:            Vector2<f32>,
+    pub font_size:       f32,
+    pub line_height:     f32,
+    pub first_view_line: Line,
+}
+
+
+
+// ======================
+// === Replaced Lines ===
+// ======================
+
+/// The lines replaced by the changes: the range of lines of the text before the changes, and the
+/// range of lines replacing them in the text after the changes. Every change is described by the
+/// range of the replaced lines and the difference of the number of lines it introduced, with the
+/// range expressed in the text after applying the previous changes, just like in
+/// [`enso_text::LineWidthIndex::apply_changes`].
+///
+/// The lines before the first change and after the last one are never touched, so the returned
+/// ranges cover all lines between them. Returns [`None`] if the changes do not match the number
+/// of lines.
+pub fn replaced_lines(
+    line_count: usize,
+    changes: impl IntoIterator<Item = (RangeInclusive<Line>, LineDiff)>,
+) -> Option<(Range<usize>, Range<usize>)> {
+    let mut count = line_count;
+    let mut start = None::<usize>;
+    // The number of lines at the end of the text not touched by any change so far.
+    let mut untouched_end = line_count;
+    for (lines, line_diff) in changes {
+        let change_start = lines.start().value;
+        let change_end = lines.end().value;
+        let diff = line_diff.value as i64;
+        let replacing_line_count = (change_end as i64 - change_start as i64 + 1) + diff;
+        if change_start > change_end || change_end >= count || replacing_line_count < 1 {
+            return None;
+        }
+        count = (count as i64 + diff) as usize;
+        let replacing_end = change_start + replacing_line_count as usize;
+        start = Some(start.map_or(change_start, |start| start.min(change_start)));
+        untouched_end = untouched_end.min(count - replacing_end);
+    }
+    let start = start.unwrap_or_default();
+    let end = |count: usize| (count - untouched_end).max(start);
+    Some((start..end(line_count), start..end(count)))
+}
+
+
+
+// ==============
+// === Mirror ===
+// ==============
+
+/// The DOM elements mirroring the text. See the module docs to learn more.
+#[derive(Debug)]
+pub struct Mirror {
+    symbol:  DomSymbol,
+    content: web::HtmlDivElement,
+    lines:   RefCell<Vec<web::HtmlDivElement>>,
+    mode:    Cell<Mode>,
+    layout:  Cell<Layout>,
+}
+
+impl Mirror {
+    /// Constructor. The mirror should be added to the DOM layer of the scene.
+    pub fn new() -> Self {
+        let content = web::document.create_div_or_panic();
+        content.set_class_name(CLASS_NAME);
+        content.set_style_or_warn("position", "absolute");
+        content.set_style_or_warn("left", "0px");
+        content.set_style_or_warn("white-space", "pre");
+        let symbol = DomSymbol::new(&content);
+        symbol.set_style_or_warn("overflow", "hidden");
+        let lines = default();
+        let mode = default();
+        let layout = default();
+        Self { symbol, content, lines, mode, layout }
+    }
+
+    /// The DOM symbol containing the mirrored text.
+    pub fn symbol(&self) -> &DomSymbol {
+        &self.symbol
+    }
+
+    /// The number of mirrored lines.
+    pub fn line_count(&self) -> usize {
+        self.lines.borrow().len()
+    }
+
+    /// Mirror the whole text again.
+    pub fn set_text(&self, text: &Rope) {
+        let mut lines = self.lines.borrow_mut();
+        for line in lines.drain(..) {
+            line.remove_from_parent_or_warn();
+        }
+        for line in 0..=text.last_line_index().value {
+            let element = Self::new_line(text, Line(line));
+            self.content.append_or_warn(&element);
+            lines.push(element);
+        }
+    }
+
+    /// Update the lines touched by the changes, see [`replaced_lines`] to learn how the changes are
+    /// described. The `text` is the text after applying all the changes. If the changes do not
+    /// match the mirrored lines, the whole text is mirrored again.
+    pub fn apply_changes(
+        &self,
+        text: &Rope,
+        changes: impl IntoIterator<Item = (RangeInclusive<Line>, LineDiff)>,
+    ) {
+        let replaced = replaced_lines(self.line_count(), changes);
+        let line_count = text.last_line_index().value + 1;
+        let replaced =
+            replaced.filter(|(old, new)| self.line_count() - old.len() + new.len() == line_count);
+        let Some((old, new)) = replaced else {
+            self.set_text(text);
+            return;
+        };
+        let mut lines = self.lines.borrow_mut();
+        for line in lines.drain(old.clone()) {
+            line.remove_from_parent_or_warn();
+        }
+        let next_line = lines.get(old.start).cloned();
+        let new_lines = new.map(|line| Self::new_line(text, Line(line))).collect_vec();
+        for element in &new_lines {
+            match &next_line {
+                Some(next_line) => self.content.insert_before_or_warn(element, next_line),
+                None => self.content.append_or_warn(element),
+            }
+        }
+        lines.splice(old.start..old.start, new_lines);
+    }
+
+    /// Set the mirroring mode. The [`Mode::Disabled`] mode is treated as [`Mode::Hidden`], the
+    /// mirror should be dropped instead.
+    pub fn set_mode(&self, mode: Mode) {
+        self.mode.set(mode);
+        self.update_style();
+    }
+
+    /// Place the mirrored text over the drawn text.
+    pub fn set_layout(&self, layout: Layout) {
+        self.layout.set(layout);
+        self.update_style();
+    }
+
+    fn update_style(&self) {
+        let layout = self.layout.get();
+        let is_overlay = self.mode.get() == Mode::Overlay;
+        let size = if is_overlay { layout.size } else { Vector2(1.0, 1.0) };
+        let first_view_line_y = layout.first_view_line.value as f32 * layout.line_height;
+        let top = if is_overlay { -first_view_line_y } else { 0.0 };
+        self.symbol.set_dom_size(size);
+        self.symbol.set_xy(Vector2(size.x / 2.0, -size.y / 2.0));
+        self.symbol.set_style_or_warn("clip-path", if is_overlay { "none" } else { "inset(50%)" });
+        self.content.set_style_or_warn("top", format!("{top}px"));
+        self.content.set_style_or_warn("font-size", format!("{}px", layout.font_size));
+        self.content.set_style_or_warn("line-height", format!("{}px", layout.line_height));
+        self.content.set_style_or_warn("color", if is_overlay { "transparent" } else { "" });
+    }
+
+    fn new_line(text: &Rope, line: Line) -> web::HtmlDivElement {
+        let element = web::document.create_div_or_panic();
+        let content = text.line_range(line).map(|range| text.sub(range).to_string());
+        let content = content.unwrap_or_default();
+        // An empty element would collapse, so empty lines contain a line break.
+        element.set_inner_text(if content.is_empty() { "\n" } else { &content });
+        element
+    }
+}
+
+impl Default for Mirror {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl display::Object for Mirror {
+    fn display_object(&self) -> &display::object::Instance {
+        self.symbol.display_object()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replace the byte range, returning the change description expected by [`replaced_lines`].
+    fn replace(
+        rope: &mut Rope,
+        range: Range<usize>,
+        text: &str,
+    ) -> (RangeInclusive<Line>, LineDiff) {
+        let start = rope.line_snapped(Byte(range.start));
+        let end = rope.line_snapped(Byte(range.end));
+        rope.replace(Byte(range.start)..Byte(range.end), text);
+        let inserted_lines = Rope::from(text).last_line_index().value as i32;
+        let line_diff = LineDiff(start.value as i32 + inserted_lines - end.value as i32);
+        (start..=end, line_diff)
+    }
+
+    fn lines(rope: &Rope) -> Vec<String> {
+        rope.to_string().split('\n').map(|line| line.to_owned()).collect()
+    }
+
+    #[test]
+    fn replaced_lines_cover_all_changes() {
+        let mut rope = Rope::from("1\n2\n3\n4\n5\n6");
+        let mut mirrored = lines(&rope);
+        let first = replace(&mut rope, 2..3, "two\nlines");
+        let second = replace(&mut rope, 14..15, "");
+        let (old, new) = replaced_lines(mirrored.len(), [first, second]).unwrap();
+        assert_eq!((old.clone(), new.clone()), (1..4, 1..5));
+        let new_lines = lines(&rope)[new].to_vec();
+        mirrored.splice(old, new_lines);
+        assert_eq!(mirrored, lines(&rope));
+
+        let change = replace(&mut rope, 0..0, "0\n");
+        assert_eq!(replaced_lines(mirrored.len(), [change]), Some((0..1, 0..2)));
+        assert_eq!(replaced_lines(6, []), Some((0..0, 0..0)));
+        assert_eq!(replaced_lines(6, [(Line(6)..=Line(6), LineDiff(0))]), None);
+    }
+}
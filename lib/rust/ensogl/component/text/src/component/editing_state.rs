@@ -0,0 +1,95 @@
+//! The serializable editing state of a text area: its selections, scroll position, and view
+//! configuration. An embedder can persist it together with the text content and restore the exact
+//! editing state after a reload, see [`crate::Text::editing_state`] and
+//! [`crate::component::text::Input::restore_editing_state`].
+
+use crate::prelude::*;
+
+use crate::buffer::selection;
+use crate::component::text::WheelScrollMode;
+use crate::component::wrap::WrapMode;
+
+use enso_text::unit::Line;
+use serde::Deserialize;
+use serde::Serialize;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The version of the editing state format. States of other versions are rejected.
+pub const FORMAT_VERSION: u32 = 1;
+
+
+
+// ==================
+// === ViewConfig ===
+// ==================
+
+/// The configuration of the text area view, restored together with the selections.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(missing_docs)]
+pub struct ViewConfig {
+    pub view_width:                Option<f32>,
+    pub view_height:               Option<f32>,
+    pub long_text_truncation_mode: bool,
+    pub single_line_mode:          bool,
+    pub editable:                  bool,
+    pub wheel_scroll_mode:         WheelScrollMode,
+    /// Missing in the states saved before the soft wrapping was introduced.
+    #[serde(default)]
+    pub wrap_mode:                 WrapMode,
+}
+
+
+
+// ====================
+// === EditingState ===
+// ====================
+
+/// The editing state of a text area. The text content is not included, it should be persisted
+/// separately.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EditingState {
+    /// The format version, see [`FORMAT_VERSION`].
+    pub version:         u32,
+    /// The selections, including cursors. Locations out of the restored text are snapped to it.
+    pub selections:      selection::Group,
+    /// The first visible line, describing the vertical scroll position.
+    pub first_view_line: Line,
+    #[allow(missing_docs)]
+    pub view:            ViewConfig,
+}
+
+impl Default for EditingState {
+    fn default() -> Self {
+        Self::new(default(), default(), default())
+    }
+}
+
+impl EditingState {
+    /// Constructor.
+    pub fn new(selections: selection::Group, first_view_line: Line, view: ViewConfig) -> Self {
+        Self { version: FORMAT_VERSION, selections, first_view_line, view }
+    }
+
+    /// Serialize the state to JSON.
+    pub fn to_json(&self) -> String {
+        // Serializing plain structs with string keys can not fail.
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Deserialize the state from JSON. Fails if the state has unsupported version.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let state: Self = serde_json::from_str(json)?;
+        if state.version != FORMAT_VERSION {
+            let error = format!("Unsupported editing state format version {}.", state.version);
+            return Err(serde::de::Error::custom(error));
+        }
+        Ok(state)
+    }
+}
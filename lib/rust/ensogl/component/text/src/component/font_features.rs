@@ -0,0 +1,113 @@
+//! OpenType shaping features set per text area, like the ligatures of programming fonts or the
+//! tabular numbers. The features are written in the syntax accepted by [`rustybuzz`], the same as
+//! used for the default features of the embedded fonts, e.g. `liga`, `-calt`, or `tnum=1`. They
+//! are applied after the default features of the font, so they can override them.
+
+use crate::prelude::*;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The standard ligatures, like `fi` in proportional fonts or `->` in programming fonts.
+pub const LIGATURES: &str = "liga";
+/// The contextual alternates, used by programming fonts for the longer ligatures, like `==>`.
+pub const CONTEXTUAL_ALTERNATES: &str = "calt";
+/// The digits of equal widths, keeping the numbers in columns aligned.
+pub const TABULAR_NUMBERS: &str = "tnum";
+
+
+
+// ====================
+// === FontFeatures ===
+// ====================
+
+/// The OpenType features enabled or disabled in a text area. The features are compared by their
+/// settings, so the text areas with equal features can share the shaping results.
+#[derive(Clone, Debug, Default)]
+pub struct FontFeatures {
+    settings: Rc<[ImString]>,
+    features: Rc<[rustybuzz::Feature]>,
+}
+
+impl FontFeatures {
+    /// Constructor. The settings which can not be parsed are reported and skipped.
+    pub fn new(settings: impl IntoIterator<Item = impl Into<ImString>>) -> Self {
+        let mut valid = vec![];
+        let mut features = vec![];
+        for setting in settings {
+            let setting = setting.into();
+            match setting.parse::<rustybuzz::Feature>() {
+                Ok(feature) => {
+                    valid.push(setting);
+                    features.push(feature);
+                }
+                Err(error) => warn!("Invalid font feature setting {setting:?}: {error}"),
+            }
+        }
+        Self { settings: valid.into(), features: features.into() }
+    }
+
+    /// The features enabling the ligatures of programming fonts.
+    pub fn ligatures() -> Self {
+        Self::new([LIGATURES, CONTEXTUAL_ALTERNATES])
+    }
+
+    /// The valid settings the features were created from.
+    pub fn settings(&self) -> &[ImString] {
+        &self.settings
+    }
+
+    /// Check whether no features are set.
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    /// The features passed to the shaper: the default features of the font followed by these.
+    pub fn with_font_defaults(&self, defaults: &[rustybuzz::Feature]) -> Vec<rustybuzz::Feature> {
+        defaults.iter().chain(self.features.iter()).copied().collect()
+    }
+}
+
+impl PartialEq for FontFeatures {
+    fn eq(&self, other: &Self) -> bool {
+        self.settings == other.settings
+    }
+}
+
+impl Eq for FontFeatures {}
+
+impl Hash for FontFeatures {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.settings.hash(state);
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_settings_are_skipped() {
+        let features = FontFeatures::new(["liga", "not a feature", "-calt", "tnum=1"]);
+        let settings = features.settings().iter().map(|t| t.as_str()).collect_vec();
+        assert_eq!(settings, vec!["liga", "-calt", "tnum=1"]);
+        let defaults: Vec<rustybuzz::Feature> = vec!["-liga".parse().unwrap()];
+        let all = features.with_font_defaults(&defaults);
+        assert_eq!(all.len(), 4);
+        // The features of the text area override the defaults of the font.
+        assert_eq!(all[0].value, 0);
+        assert_eq!(all[1].value, 1);
+        assert_eq!(all[2].value, 0);
+        assert!(FontFeatures::default().is_empty());
+        assert_eq!(FontFeatures::ligatures(), FontFeatures::new(["liga", "calt"]));
+    }
+}
@@ -0,0 +1,475 @@
+//! The gutter of the text area. It is an interactive margin on the left side of the text, which
+//! reports clicks and hovers per line and displays per-line glyph markers, like breakpoint dots or
+//! bookmark flags. The markers are anchored to lines and follow them through buffer edits. The
+//! gutter can also display the line numbers, left of the markers.
+
+use crate::prelude::*;
+use enso_text::unit::*;
+
+use crate::buffer;
+use crate::component::label_batch::LabelBatch;
+use crate::component::label_batch::LabelId;
+
+use ensogl_core::data::color;
+use ensogl_core::display;
+use ensogl_core::display::shape::Circle;
+use ensogl_core::display::shape::Rectangle;
+use ensogl_core::display::shape::INVISIBLE_HOVER_COLOR;
+use ensogl_core::display::Scene;
+use std::collections::BTreeSet;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The size of the breakpoint dot.
+const BREAKPOINT_SIZE: f32 = 8.0;
+/// The width of the bookmark flag.
+const BOOKMARK_WIDTH: f32 = 6.0;
+/// The height of the bookmark flag.
+const BOOKMARK_HEIGHT: f32 = 10.0;
+/// The corner radius of the bookmark flag.
+const BOOKMARK_CORNER_RADIUS: f32 = 1.0;
+/// The horizontal spacing between markers displayed in the same line.
+const MARKER_SPACING: f32 = 2.0;
+/// The width of the gutter part reserved for the markers, on the right side of the line numbers.
+pub const MARKER_AREA_WIDTH: f32 = BREAKPOINT_SIZE + BOOKMARK_WIDTH + 3.0 * MARKER_SPACING;
+/// The spacing on the left side of the line numbers.
+const LINE_NUMBER_PADDING: f32 = 6.0;
+/// The default color of the line numbers.
+pub const LINE_NUMBER_COLOR: color::Rgba = color::Rgba::new(0.5, 0.5, 0.5, 1.0);
+
+
+
+// ==================
+// === MarkerKind ===
+// ==================
+
+/// A kind of a glyph marker displayed in the gutter. A line can have at most one marker of every
+/// kind.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[allow(missing_docs)]
+pub enum MarkerKind {
+    #[default]
+    Breakpoint,
+    Bookmark,
+}
+
+impl MarkerKind {
+    /// All marker kinds, in the order they are displayed in the gutter (from the right).
+    pub const ALL: [MarkerKind; 2] = [MarkerKind::Breakpoint, MarkerKind::Bookmark];
+
+    /// The size of the marker glyph.
+    pub fn size(self) -> Vector2 {
+        match self {
+            Self::Breakpoint => Vector2(BREAKPOINT_SIZE, BREAKPOINT_SIZE),
+            Self::Bookmark => Vector2(BOOKMARK_WIDTH, BOOKMARK_HEIGHT),
+        }
+    }
+
+    /// The default color of the marker glyph.
+    pub fn color(self) -> color::Rgba {
+        match self {
+            Self::Breakpoint => color::Rgba::new(0.86, 0.26, 0.26, 1.0),
+            Self::Bookmark => color::Rgba::new(0.27, 0.55, 0.92, 1.0),
+        }
+    }
+
+    fn new_view(self, color: color::Rgba) -> Rectangle {
+        let view = match self {
+            Self::Breakpoint => Circle(),
+            Self::Bookmark => Rectangle().build(|r| {
+                r.set_corner_radius(BOOKMARK_CORNER_RADIUS);
+            }),
+        };
+        view.set_color(color);
+        view.set_pointer_events(false);
+        view.set_size(self.size());
+        view
+    }
+}
+
+
+
+// ===============
+// === Markers ===
+// ===============
+
+/// Per-line state of gutter markers.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Markers {
+    map: BTreeMap<Line, BTreeSet<MarkerKind>>,
+}
+
+impl Markers {
+    /// Check whether the line has a marker of the given kind.
+    pub fn has(&self, line: Line, kind: MarkerKind) -> bool {
+        self.map.get(&line).map_or(false, |kinds| kinds.contains(&kind))
+    }
+
+    /// All markers of the given line.
+    pub fn of_line(&self, line: Line) -> Vec<MarkerKind> {
+        self.map.get(&line).map(|kinds| kinds.iter().copied().collect()).unwrap_or_default()
+    }
+
+    /// Sorted lines having a marker of the given kind.
+    pub fn lines_with(&self, kind: MarkerKind) -> Vec<Line> {
+        self.map.iter().filter(|(_, kinds)| kinds.contains(&kind)).map(|(line, _)| *line).collect()
+    }
+
+    /// The first line after the given one having a marker of the given kind. Wraps around to the
+    /// first marked line if there are no marked lines after the given one.
+    pub fn next_line_with(&self, kind: MarkerKind, line: Line) -> Option<Line> {
+        let lines = self.lines_with(kind);
+        lines.iter().find(|l| **l > line).or_else(|| lines.first()).copied()
+    }
+
+    /// The last line before the given one having a marker of the given kind. Wraps around to the
+    /// last marked line if there are no marked lines before the given one.
+    pub fn prev_line_with(&self, kind: MarkerKind, line: Line) -> Option<Line> {
+        let lines = self.lines_with(kind);
+        lines.iter().rev().find(|l| **l < line).or_else(|| lines.last()).copied()
+    }
+
+    /// Iterate over all lines having any marker, together with their markers.
+    pub fn iter(&self) -> impl Iterator<Item = (Line, &BTreeSet<MarkerKind>)> {
+        self.map.iter().map(|(line, kinds)| (*line, kinds))
+    }
+
+    /// Check whether there are no markers.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Set or unset the marker of the given kind in the line. Returns [`true`] if the state has
+    /// changed.
+    pub fn set(&mut self, line: Line, kind: MarkerKind, enabled: bool) -> bool {
+        if enabled {
+            self.map.entry(line).or_default().insert(kind)
+        } else {
+            let kinds = self.map.get_mut(&line);
+            let changed = kinds.map_or(false, |kinds| kinds.remove(&kind));
+            if self.map.get(&line).map_or(false, |kinds| kinds.is_empty()) {
+                self.map.remove(&line);
+            }
+            changed
+        }
+    }
+
+    /// Toggle the marker of the given kind in the line. Returns the new marker state.
+    pub fn toggle(&mut self, line: Line, kind: MarkerKind) -> bool {
+        let enabled = !self.has(line, kind);
+        self.set(line, kind, enabled);
+        enabled
+    }
+
+    /// Remove all markers of the given kind. Returns [`true`] if any marker was removed.
+    pub fn clear(&mut self, kind: MarkerKind) -> bool {
+        let mut changed = false;
+        self.map.retain(|_, kinds| {
+            changed |= kinds.remove(&kind);
+            !kinds.is_empty()
+        });
+        changed
+    }
+
+    /// Move the markers so that they stay anchored to their lines after the buffer change. Markers
+    /// of lines below the change are shifted by the number of inserted or removed lines. Markers of
+    /// removed lines are merged into the last line that remains from the changed line range.
+    /// Returns [`true`] if any marker was moved.
+    pub fn apply_change(&mut self, change: &buffer::Change) -> bool {
+        let line_diff = change.line_diff;
+        if line_diff == LineDiff(0) {
+            return false;
+        }
+        let change_start = *change.change_range.start();
+        let change_end = *change.change_range.end();
+        let last_kept_line = std::cmp::max(change_start, change_end + line_diff);
+        let to_move = self.map.drain_filter(|line, _| *line > change_start).collect_vec();
+        let mut changed = false;
+        for (line, kinds) in to_move {
+            let new_line = if line > change_end {
+                line + line_diff
+            } else {
+                std::cmp::min(line, last_kept_line)
+            };
+            changed |= new_line != line;
+            self.map.entry(new_line).or_default().extend(kinds);
+        }
+        changed
+    }
+}
+
+
+
+// ==============
+// === Gutter ===
+// ==============
+
+/// The gutter view. It is placed on the left side of the text area origin. Its hit area covers the
+/// whole text height, while the markers and the line numbers are positioned by the text area,
+/// which knows the line placement.
+#[derive(Clone, CloneRef, Debug, display::Object)]
+pub struct Gutter {
+    display_object:       display::object::Instance,
+    hit_area:             Rectangle,
+    /// The displayed marker glyphs of every kind, reused between redraws.
+    marker_views:         Rc<RefCell<HashMap<MarkerKind, Vec<Rectangle>>>>,
+    marker_colors:        Rc<RefCell<HashMap<MarkerKind, color::Rgba>>>,
+    line_numbers:         LabelBatch,
+    line_numbers_visible: Rc<Cell<bool>>,
+    /// The width needed to display the line numbers of the visible lines.
+    line_numbers_width:   Rc<Cell<f32>>,
+    /// The width set with [`Self::set_size`]. The gutter can be wider to fit the line numbers.
+    min_width:            Rc<Cell<f32>>,
+    height:               Rc<Cell<f32>>,
+    /// The per-line marker state.
+    pub markers:          Rc<RefCell<Markers>>,
+}
+
+impl Gutter {
+    /// Constructor.
+    pub fn new(scene: &Scene) -> Self {
+        let display_object = display::object::Instance::new_named("Gutter");
+        let hit_area = Rectangle().build(|r| {
+            r.set_color(INVISIBLE_HOVER_COLOR);
+        });
+        display_object.add_child(&hit_area);
+        let marker_views = default();
+        let marker_colors = default();
+        let line_numbers = LabelBatch::new(scene);
+        line_numbers.set_color(LINE_NUMBER_COLOR);
+        display_object.add_child(&line_numbers);
+        let line_numbers_visible = default();
+        let line_numbers_width = default();
+        let min_width = default();
+        let height = default();
+        let markers = default();
+        Self {
+            display_object,
+            hit_area,
+            marker_views,
+            marker_colors,
+            line_numbers,
+            line_numbers_visible,
+            line_numbers_width,
+            min_width,
+            height,
+            markers,
+        }
+    }
+
+    /// The shape receiving mouse events of the gutter.
+    pub fn hit_area(&self) -> &Rectangle {
+        &self.hit_area
+    }
+
+    /// The gutter width. It is the width set with [`Self::set_size`], or the width needed to
+    /// display the line numbers, if larger. If it is zero, the gutter is not displayed.
+    pub fn width(&self) -> f32 {
+        let min_width = self.min_width.get();
+        if self.line_numbers_visible.get() {
+            min_width.max(self.line_numbers_width.get() + MARKER_AREA_WIDTH)
+        } else {
+            min_width
+        }
+    }
+
+    /// Check whether the line numbers are displayed.
+    pub fn line_numbers_visible(&self) -> bool {
+        self.line_numbers_visible.get()
+    }
+
+    /// Display or hide the line numbers. They are drawn by [`Self::redraw_line_numbers`].
+    pub fn set_line_numbers_visible(&self, visible: bool) {
+        self.line_numbers_visible.set(visible);
+    }
+
+    /// Set the color of the line numbers.
+    pub fn set_line_number_color(&self, color: color::Rgba) {
+        self.line_numbers.set_color(color);
+    }
+
+    /// Set the font size of the line numbers. It should match the default font size of the text.
+    pub fn set_line_number_font_size(&self, size: f32) {
+        self.line_numbers.set_font_size(size);
+    }
+
+    /// Check whether the x-axis position, relative to the text area origin, is over the line
+    /// numbers rather than over the markers.
+    pub fn is_over_line_numbers(&self, x: f32) -> bool {
+        self.line_numbers_visible.get() && x < -MARKER_AREA_WIDTH
+    }
+
+    /// The color of the marker glyphs of the given kind.
+    pub fn marker_color(&self, kind: MarkerKind) -> color::Rgba {
+        self.marker_colors.borrow().get(&kind).copied().unwrap_or_else(|| kind.color())
+    }
+
+    /// Set the color of the marker glyphs of the given kind. It is applied when the markers are
+    /// redrawn.
+    pub fn set_marker_color(&self, kind: MarkerKind, color: color::Rgba) {
+        self.marker_colors.borrow_mut().insert(kind, color);
+    }
+
+    /// Set the gutter width and height. The gutter is placed on the left side of the text area
+    /// origin, growing downwards. It can be wider than the given width, see [`Self::width`].
+    pub fn set_size(&self, width: f32, height: f32) {
+        self.min_width.set(width);
+        self.height.set(height);
+        self.update_size();
+    }
+
+    fn update_size(&self) {
+        let width = self.width();
+        let height = self.height.get();
+        self.display_object.set_x(-width);
+        self.hit_area.set_xy((0.0, -height));
+        self.hit_area.set_size((width, height));
+        self.hit_area.set_pointer_events(width > 0.0);
+    }
+
+    /// Redraw the line numbers, right-aligned to the marker area. The `baselines` are the
+    /// baselines of the visible lines. The gutter is resized if the numbers do not fit it. Returns
+    /// [`true`] if the gutter width was changed.
+    pub fn redraw_line_numbers(&self, baselines: impl IntoIterator<Item = (Line, f32)>) -> bool {
+        let old_width = self.width();
+        if self.line_numbers_visible.get() {
+            let (lines, baselines): (Vec<_>, Vec<_>) = baselines.into_iter().unzip();
+            self.line_numbers.set_labels(lines.iter().map(|line| (line.value + 1).to_string()));
+            let widths = (0..lines.len()).map(|index| self.line_numbers.width(LabelId(index)));
+            let widths = widths.map(|width| width.unwrap_or_default()).collect_vec();
+            let max_width = widths.iter().copied().fold(0.0, f32::max);
+            self.line_numbers_width.set(max_width + LINE_NUMBER_PADDING);
+            let right = self.width() - MARKER_AREA_WIDTH;
+            let positions = widths.iter().zip(&baselines).flat_map(|(w, y)| [right - w, *y]);
+            self.line_numbers.set_positions(&positions.collect_vec());
+        } else {
+            self.line_numbers.clear();
+            self.line_numbers_width.set(0.0);
+        }
+        let width_changed = self.width() != old_width;
+        if width_changed {
+            self.update_size();
+        }
+        width_changed
+    }
+
+    /// Redraw the marker glyphs. The `line_center_y` function should return the y-axis center of
+    /// the given line if the line is visible. The views of the previously displayed markers are
+    /// reused, only the missing ones are created and the excess ones are removed.
+    pub fn redraw_markers(&self, line_center_y: impl Fn(Line) -> Option<f32>) {
+        let width = self.width();
+        let mut positions = HashMap::<MarkerKind, Vec<Vector2>>::new();
+        if width > 0.0 {
+            for (line, kinds) in self.markers.borrow().iter() {
+                if let Some(center_y) = line_center_y(line) {
+                    let mut right = width - MARKER_SPACING;
+                    for kind in kinds.iter().rev() {
+                        let size = kind.size();
+                        let position = Vector2(right - size.x, center_y - size.y / 2.0);
+                        positions.entry(*kind).or_default().push(position);
+                        right -= size.x + MARKER_SPACING;
+                    }
+                }
+            }
+        }
+        let mut marker_views = self.marker_views.borrow_mut();
+        for kind in MarkerKind::ALL {
+            let positions = positions.remove(&kind).unwrap_or_default();
+            let views = marker_views.entry(kind).or_default();
+            if views.len() > positions.len() {
+                for view in views.drain(positions.len()..) {
+                    view.unset_parent();
+                }
+            }
+            while views.len() < positions.len() {
+                let view = kind.new_view(self.marker_color(kind));
+                self.display_object.add_child(&view);
+                views.push(view);
+            }
+            let color = self.marker_color(kind);
+            for (view, position) in views.iter().zip(positions) {
+                view.set_color(color);
+                view.set_xy(position);
+            }
+        }
+    }
+}
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(change_range: RangeInclusive<Line>, line_diff: i32) -> buffer::Change {
+        let line_diff = LineDiff(line_diff);
+        buffer::Change { change_range, line_diff, ..default() }
+    }
+
+    #[test]
+    fn set_and_toggle_markers() {
+        let mut markers = Markers::default();
+        assert!(markers.set(Line(2), MarkerKind::Breakpoint, true));
+        assert!(!markers.set(Line(2), MarkerKind::Breakpoint, true));
+        assert!(markers.toggle(Line(2), MarkerKind::Bookmark));
+        assert_eq!(markers.of_line(Line(2)), vec![MarkerKind::Breakpoint, MarkerKind::Bookmark]);
+        assert!(!markers.toggle(Line(2), MarkerKind::Bookmark));
+        assert!(markers.set(Line(2), MarkerKind::Breakpoint, false));
+        assert!(markers.is_empty());
+        markers.set(Line(1), MarkerKind::Bookmark, true);
+        markers.set(Line(3), MarkerKind::Bookmark, true);
+        markers.set(Line(3), MarkerKind::Breakpoint, true);
+        assert_eq!(markers.lines_with(MarkerKind::Bookmark), vec![Line(1), Line(3)]);
+        assert!(markers.clear(MarkerKind::Bookmark));
+        assert_eq!(markers.lines_with(MarkerKind::Bookmark), vec![]);
+        assert_eq!(markers.lines_with(MarkerKind::Breakpoint), vec![Line(3)]);
+    }
+
+    #[test]
+    fn navigating_between_markers() {
+        let mut markers = Markers::default();
+        assert_eq!(markers.next_line_with(MarkerKind::Bookmark, Line(0)), None);
+        markers.set(Line(2), MarkerKind::Bookmark, true);
+        markers.set(Line(5), MarkerKind::Bookmark, true);
+        markers.set(Line(7), MarkerKind::Breakpoint, true);
+        let next = |line| markers.next_line_with(MarkerKind::Bookmark, Line(line));
+        let prev = |line| markers.prev_line_with(MarkerKind::Bookmark, Line(line));
+        assert_eq!(next(0), Some(Line(2)));
+        assert_eq!(next(2), Some(Line(5)));
+        assert_eq!(next(5), Some(Line(2)));
+        assert_eq!(prev(5), Some(Line(2)));
+        assert_eq!(prev(3), Some(Line(2)));
+        assert_eq!(prev(2), Some(Line(5)));
+    }
+
+    #[test]
+    fn markers_follow_inserted_lines() {
+        let mut markers = Markers::default();
+        markers.set(Line(1), MarkerKind::Breakpoint, true);
+        markers.set(Line(4), MarkerKind::Bookmark, true);
+        // Inserting two new lines in line 2.
+        assert!(markers.apply_change(&change(Line(2)..=Line(2), 2)));
+        assert_eq!(markers.lines_with(MarkerKind::Breakpoint), vec![Line(1)]);
+        assert_eq!(markers.lines_with(MarkerKind::Bookmark), vec![Line(6)]);
+        // Editing a single line does not move markers.
+        assert!(!markers.apply_change(&change(Line(0)..=Line(0), 0)));
+    }
+
+    #[test]
+    fn markers_of_removed_lines_are_merged() {
+        let mut markers = Markers::default();
+        markers.set(Line(1), MarkerKind::Breakpoint, true);
+        markers.set(Line(3), MarkerKind::Bookmark, true);
+        markers.set(Line(5), MarkerKind::Breakpoint, true);
+        // Removing lines 2 and 3 by joining lines 1..=3 into one.
+        markers.apply_change(&change(Line(1)..=Line(3), -2));
+        assert_eq!(markers.of_line(Line(1)), vec![MarkerKind::Breakpoint, MarkerKind::Bookmark]);
+        assert_eq!(markers.lines_with(MarkerKind::Breakpoint), vec![Line(1), Line(3)]);
+    }
+}
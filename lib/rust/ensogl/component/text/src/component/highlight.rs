@@ -0,0 +1,194 @@
+//! Named highlight sets, styling text ranges by their semantic class, like search matches,
+//! diagnostics, or occurrences of the symbol under the cursor. Every set is managed independently
+//! of the user selections, the formatting, and the other sets, so several highlighting layers can
+//! coexist. Setting a set with an existing name replaces it. The ranges follow the text through
+//! edits.
+
+use crate::prelude::*;
+use enso_text::unit::*;
+
+use crate::buffer;
+use crate::component::annotation;
+
+use ensogl_core::data::color;
+use ensogl_core::display;
+use ensogl_core::display::shape::Rectangle;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The thickness of the line drawn below the ranges of the underlined sets.
+pub const UNDERLINE_WIDTH: f32 = 1.5;
+
+
+
+// =============
+// === Style ===
+// =============
+
+/// The style of the ranges of a highlight set.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Style {
+    /// The background color of the ranges. For the underlined sets, it is the color of the line.
+    pub background: color::Rgba,
+    /// The order of drawing the sets. Sets with a higher order are drawn above the sets with a
+    /// lower one. Sets of equal order are drawn in the order of their names.
+    pub z_order:    i32,
+    /// Whether the ranges are underlined instead of having their background filled, like the
+    /// in-progress input method composition.
+    pub underline:  bool,
+}
+
+impl Style {
+    /// Constructor.
+    pub fn new(background: impl Into<color::Rgba>, z_order: i32) -> Self {
+        let background = background.into();
+        Self { background, z_order, underline: false }
+    }
+
+    /// Constructor of the style underlining the ranges with a line of the given color.
+    pub fn underline(color: impl Into<color::Rgba>, z_order: i32) -> Self {
+        Self { underline: true, ..Self::new(color, z_order) }
+    }
+}
+
+
+
+// ===========
+// === Set ===
+// ===========
+
+/// A named set of highlighted text ranges.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[allow(missing_docs)]
+pub struct Set {
+    pub name:   ImString,
+    pub ranges: Vec<buffer::Range<Byte>>,
+    pub style:  Style,
+}
+
+impl Set {
+    /// Constructor.
+    pub fn new(
+        name: impl Into<ImString>,
+        ranges: impl Into<Vec<buffer::Range<Byte>>>,
+        style: Style,
+    ) -> Self {
+        let name = name.into();
+        let ranges = ranges.into();
+        Self { name, ranges, style }
+    }
+
+    /// Move the ranges to keep them in place after the text change.
+    pub fn apply_change(&mut self, change: &buffer::Change) {
+        for range in &mut self.ranges {
+            *range = annotation::follow_change(*range, change);
+        }
+    }
+}
+
+
+
+// ============
+// === View ===
+// ============
+
+/// The view displaying the backgrounds of the highlight sets.
+#[derive(Clone, CloneRef, Debug, display::Object)]
+pub struct View {
+    display_object: display::object::Instance,
+    views:          Rc<RefCell<Vec<Rectangle>>>,
+    sets:           Rc<RefCell<BTreeMap<ImString, Set>>>,
+}
+
+impl View {
+    /// Constructor.
+    pub fn new() -> Self {
+        let display_object = display::object::Instance::new_named("HighlightSets");
+        let views = default();
+        let sets = default();
+        Self { display_object, views, sets }
+    }
+
+    /// Add the set, replacing the set of the same name.
+    pub fn set(&self, set: Set) {
+        self.sets.borrow_mut().insert(set.name.clone(), set);
+    }
+
+    /// The set of the given name, if any.
+    pub fn get(&self, name: &str) -> Option<Set> {
+        self.sets.borrow().get(name).cloned()
+    }
+
+    /// Remove the set of the given name.
+    pub fn clear(&self, name: &str) {
+        self.sets.borrow_mut().remove(name);
+    }
+
+    /// Remove all sets.
+    pub fn clear_all(&self) {
+        self.sets.borrow_mut().clear();
+    }
+
+    /// All sets, in the drawing order.
+    pub fn sets(&self) -> Vec<Set> {
+        let mut sets = self.sets.borrow().values().cloned().collect_vec();
+        sets.sort_by_key(|set| set.style.z_order);
+        sets
+    }
+
+    /// Move the ranges of all sets to keep them in place after the text changes.
+    pub fn apply_changes<'a>(&self, changes: impl IntoIterator<Item = &'a buffer::Change>) {
+        let mut sets = self.sets.borrow_mut();
+        for change in changes {
+            for set in sets.values_mut() {
+                set.apply_change(change);
+            }
+        }
+    }
+
+    /// Update all ranges with the provided function, for example to crop them to the text.
+    pub fn map_ranges(&self, f: impl Fn(buffer::Range<Byte>) -> buffer::Range<Byte>) {
+        for set in self.sets.borrow_mut().values_mut() {
+            for range in &mut set.ranges {
+                *range = f(*range);
+            }
+        }
+    }
+
+    /// Redraw the backgrounds and the underlines. The `range_rects` function should return the
+    /// bottom-left corners and the sizes of the visible parts of the given range, one per line.
+    pub fn redraw(&self, range_rects: impl Fn(buffer::Range<Byte>) -> Vec<(Vector2, Vector2)>) {
+        let mut views = self.views.borrow_mut();
+        for view in views.drain(..) {
+            view.unset_parent();
+        }
+        // The rectangles created later are drawn above the earlier ones.
+        for set in self.sets() {
+            for range in &set.ranges {
+                for (position, mut size) in range_rects(*range) {
+                    if set.style.underline {
+                        size.y = UNDERLINE_WIDTH;
+                    }
+                    let view = Rectangle().build(|r| {
+                        r.set_pointer_events(false);
+                        r.set_color(set.style.background);
+                    });
+                    view.set_xy(position);
+                    view.set_size(size);
+                    self.display_object.add_child(&view);
+                    views.push(view);
+                }
+            }
+        }
+    }
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self::new()
+    }
+}
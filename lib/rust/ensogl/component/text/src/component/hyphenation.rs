@@ -0,0 +1,185 @@
+//! Dictionary-driven hyphenation. Words too long to fit the remaining width of a soft-wrapped row
+//! can be broken at syllable boundaries listed in the dictionary of the selected language, with a
+//! hyphen rendered at the end of the broken row. See [`crate::component::wrap::Rows::hyphenated`]
+//! and the `set_hyphenation_language` input of the text area.
+
+use crate::prelude::*;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The text rendered at the end of a line ending with a broken word.
+pub const HYPHEN: &str = "-";
+/// The default minimal number of characters before the first break point of a word.
+const DEFAULT_LEFT_MIN: usize = 2;
+/// The default minimal number of characters after the last break point of a word.
+const DEFAULT_RIGHT_MIN: usize = 3;
+
+
+
+// ==================
+// === Dictionary ===
+// ==================
+
+/// A hyphenation dictionary of a single language. It maps words to the byte offsets of their
+/// syllable boundaries. The words are looked up case-insensitively.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Dictionary {
+    language:  ImString,
+    words:     HashMap<String, Vec<usize>>,
+    left_min:  usize,
+    right_min: usize,
+}
+
+impl Dictionary {
+    /// Constructor of an empty dictionary of the language with the given tag, like `en-US`.
+    pub fn new(language: impl Into<ImString>) -> Self {
+        let language = language.into();
+        let words = default();
+        let left_min = DEFAULT_LEFT_MIN;
+        let right_min = DEFAULT_RIGHT_MIN;
+        Self { language, words, left_min, right_min }
+    }
+
+    /// Add a word with its syllables separated with hyphens, like `hy-phen-a-tion`.
+    pub fn word(mut self, hyphenated: &str) -> Self {
+        let mut word = String::with_capacity(hyphenated.len());
+        let mut break_points = vec![];
+        for syllable in hyphenated.split('-').filter(|t| !t.is_empty()) {
+            if !word.is_empty() {
+                break_points.push(word.len());
+            }
+            word.push_str(&syllable.to_lowercase());
+        }
+        self.words.insert(word, break_points);
+        self
+    }
+
+    /// Add many words. See [`Self::word`].
+    pub fn words<'a>(self, hyphenated: impl IntoIterator<Item = &'a str>) -> Self {
+        hyphenated.into_iter().fold(self, |dictionary, word| dictionary.word(word))
+    }
+
+    /// Set the minimal number of characters before the first and after the last break point of a
+    /// word. Break points closer to the word ends are not used.
+    pub fn min_fragment_lengths(mut self, left_min: usize, right_min: usize) -> Self {
+        self.left_min = left_min;
+        self.right_min = right_min;
+        self
+    }
+
+    /// The language tag of this dictionary.
+    pub fn language(&self) -> &ImString {
+        &self.language
+    }
+
+    /// The byte offsets of the word at which it can be broken, in ascending order. Empty if the
+    /// word is not in the dictionary.
+    pub fn break_points(&self, word: &str) -> Vec<usize> {
+        let Some(break_points) = self.words.get(&word.to_lowercase()) else { return default() };
+        let char_count = word.chars().count();
+        // The lowercase form may have a different byte length, so the offsets are mapped by chars.
+        let lowercase_char_offsets =
+            word.to_lowercase().char_indices().map(|(offset, _)| offset).collect_vec();
+        let char_offsets = word.char_indices().map(|(offset, _)| offset).collect_vec();
+        break_points
+            .iter()
+            .filter_map(|offset| lowercase_char_offsets.binary_search(offset).ok())
+            .filter(|chars| *chars >= self.left_min && char_count - chars >= self.right_min)
+            .filter_map(|chars| char_offsets.get(chars).copied())
+            .collect()
+    }
+
+    /// Find the last break point such that the part of the word before it, followed by the
+    /// [`HYPHEN`], fits the line. The `fits` function checks whether the given text fits.
+    pub fn break_word(&self, word: &str, fits: impl Fn(&str) -> bool) -> Option<usize> {
+        self.break_points(word)
+            .into_iter()
+            .rev()
+            .find(|offset| fits(&format!("{}{HYPHEN}", &word[..*offset])))
+    }
+}
+
+
+
+// ===================
+// === Hyphenation ===
+// ===================
+
+/// A set of hyphenation dictionaries with the selected language. Hyphenation is disabled if no
+/// language is selected, or if there is no dictionary of the selected language.
+#[derive(Clone, Debug, Default)]
+pub struct Hyphenation {
+    dictionaries: HashMap<ImString, Rc<Dictionary>>,
+    language:     Option<ImString>,
+}
+
+impl Hyphenation {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Add a dictionary, replacing the previous dictionary of the same language.
+    pub fn add_dictionary(&mut self, dictionary: Dictionary) {
+        self.dictionaries.insert(dictionary.language.clone_ref(), Rc::new(dictionary));
+    }
+
+    /// Select the language, or disable hyphenation with [`None`].
+    pub fn set_language(&mut self, language: Option<ImString>) {
+        self.language = language;
+    }
+
+    /// The selected language.
+    pub fn language(&self) -> Option<&ImString> {
+        self.language.as_ref()
+    }
+
+    /// The dictionary of the selected language, if hyphenation is enabled.
+    pub fn dictionary(&self) -> Option<&Rc<Dictionary>> {
+        self.dictionaries.get(self.language.as_ref()?)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_are_broken_at_syllable_boundaries() {
+        let dictionary = Dictionary::new("en-US").words(["hy-phen-a-tion", "a-gain", "ré-su-mé"]);
+        assert_eq!(dictionary.break_points("hyphenation"), vec![2, 6, 7]);
+        assert_eq!(dictionary.break_points("Hyphenation"), vec![2, 6, 7]);
+        // The break point after the first character is not used.
+        assert_eq!(dictionary.break_points("again"), vec![]);
+        assert_eq!(dictionary.break_points("Résumé"), vec![3]);
+        assert_eq!(dictionary.break_points("unknown"), vec![]);
+        let fits = |text: &str| text.len() <= 7;
+        assert_eq!(dictionary.break_word("hyphenation", fits), Some(6));
+        assert_eq!(dictionary.break_word("hyphenation", |_| false), None);
+    }
+
+    #[test]
+    fn dictionary_of_selected_language_is_used() {
+        let mut hyphenation = Hyphenation::new();
+        hyphenation.add_dictionary(Dictionary::new("en-US").word("hy-phen"));
+        hyphenation.add_dictionary(Dictionary::new("de-DE").word("sil-be"));
+        assert!(hyphenation.dictionary().is_none());
+        hyphenation.set_language(Some("de-DE".into()));
+        assert_eq!(
+            hyphenation.dictionary().map(|t| t.language().to_string()),
+            Some("de-DE".into())
+        );
+        hyphenation.set_language(Some("fr-FR".into()));
+        assert!(hyphenation.dictionary().is_none());
+    }
+}
@@ -0,0 +1,373 @@
+//! A batch of short, uniformly styled labels, like axis ticks or node port types. It trades the
+//! rich formatting and editing capabilities of the [`Text`](crate::Text) area for throughput: all
+//! glyphs of all labels are instances of a single glyph system, so tens of thousands of labels are
+//! rendered in a few draw calls. The labels are shaped through the [`ShapeCache`], so repeated
+//! contents are shaped once, and their positions can be updated in bulk from a flat array of
+//! coordinates, for example a copy of a `Float32Array`.
+
+use crate::prelude::*;
+
+use crate::buffer::formatting;
+use crate::buffer::formatting::FontFamily;
+use crate::component::shape_cache;
+use crate::component::shape_cache::ShapeCache;
+use crate::component::text::ShapedGlyph;
+use crate::component::text::ShapedGlyphSet;
+use crate::font;
+use crate::font::glyph;
+use crate::font::glyph::Glyph;
+use crate::font::Font;
+use crate::font::GlyphId;
+
+use enso_font::NonVariableFaceHeader;
+use ensogl_core::data::color;
+use ensogl_core::display;
+use ensogl_core::display::Scene;
+use owned_ttf_parser::AsFaceRef;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The default font size of the labels.
+pub const DEFAULT_FONT_SIZE: f32 = 12.0;
+
+
+
+// ==========
+// === Id ===
+// ==========
+
+/// An index of a label in the batch.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LabelId(pub usize);
+
+
+
+// =============
+// === Label ===
+// =============
+
+#[derive(Debug)]
+struct Label {
+    display_object: display::object::Instance,
+    content:        ImString,
+    shaped:         Option<ShapedGlyphSet>,
+    glyphs:         Vec<Glyph>,
+    /// The color overriding the batch color.
+    color:          Option<color::Lcha>,
+    width:          f32,
+}
+
+impl Label {
+    fn new(parent: &display::object::Instance) -> Self {
+        let display_object = display::object::Instance::new_no_debug();
+        parent.add_child(&display_object);
+        let content = default();
+        let shaped = default();
+        let glyphs = default();
+        let color = default();
+        let width = default();
+        Self { display_object, content, shaped, glyphs, color, width }
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+#[derive(Debug, display::Object)]
+struct Model {
+    display_object: display::object::Instance,
+    shape_cache:    ShapeCache,
+    glyph_system:   RefCell<glyph::System>,
+    font_size:      Cell<f32>,
+    color:          Cell<color::Lcha>,
+    labels:         RefCell<Vec<Label>>,
+}
+
+impl Model {
+    fn new(scene: &Scene) -> Self {
+        let display_object = display::object::Instance::new_named("LabelBatch");
+        let shape_cache = scene.extension::<ShapeCache>();
+        let glyph_system = RefCell::new(glyph::System::new(scene, font::DEFAULT_FONT));
+        let font_size = Cell::new(DEFAULT_FONT_SIZE);
+        let color = Cell::new(color::Lcha::black());
+        let labels = default();
+        Self { display_object, shape_cache, glyph_system, font_size, color, labels }
+    }
+
+    fn shape(&self, content: &str) -> Option<ShapedGlyphSet> {
+        let font = self.glyph_system.borrow().font.font.clone_ref();
+        let variations = font.closest_non_variable_variations_or_panic(default()).variations;
+        if content.is_empty() {
+            None
+        } else if content.len() > shape_cache::MAX_CONTENT_BYTE_SIZE {
+            shape(&font, variations, content)
+        } else {
+            let name = font.name().clone();
+            let family = FontFamily::default();
+            let key = shape_cache::Key::new(name, variations, family, default(), content);
+            let shape_content = || shape(&font, variations, content).into_iter().collect();
+            self.shape_cache.get_or_shape(key, content, shape_content).into_iter().next()
+        }
+    }
+
+    fn set_content(&self, label: &mut Label, content: ImString) {
+        label.shaped = self.shape(&content);
+        label.content = content;
+        self.layout(label);
+    }
+
+    /// Update the glyphs of the label to match its shaped content, reusing the existing ones.
+    fn layout(&self, label: &mut Label) {
+        let glyph_count = label.shaped.as_ref().map_or(0, |set| set.glyphs.len());
+        let glyph_system = self.glyph_system.borrow();
+        label.glyphs.truncate(glyph_count);
+        while label.glyphs.len() < glyph_count {
+            let glyph = glyph_system.new_glyph();
+            label.display_object.add_child(&glyph);
+            label.glyphs.push(glyph);
+        }
+        let font_size = self.font_size.get();
+        let color = label.color.unwrap_or_else(|| self.color.get());
+        let mut offset_x = 0.0;
+        if let Some(set) = &label.shaped {
+            let units_per_em = set.units_per_em as f32;
+            // See the comment about the magic scale in the text area glyph layout.
+            let magic_scale = 2048.0 / units_per_em;
+            for (glyph, shaped_glyph) in label.glyphs.iter().zip(&set.glyphs) {
+                let x_advance = shaped_glyph.position.x_advance as f32 * font_size / units_per_em;
+                let render_offset = shaped_glyph.render_info.offset.scale(font_size);
+                glyph.set_color(color);
+                glyph.set_font_size(formatting::Size(font_size * magic_scale));
+                glyph.set_properties(set.non_variable_variations);
+                glyph.set_glyph_id(shaped_glyph.id());
+                glyph.x_advance.set(x_advance);
+                glyph.view.set_xy(render_offset * magic_scale);
+                glyph.set_xy(Vector2(offset_x, 0.0));
+                offset_x += x_advance;
+            }
+        }
+        label.width = offset_x;
+    }
+
+    fn set_color(&self, label: &Label) {
+        let color = label.color.unwrap_or_else(|| self.color.get());
+        label.glyphs.iter().for_each(|glyph| glyph.set_color(color));
+    }
+}
+
+/// Shape the content with a single font face.
+fn shape(font: &Font, variations: NonVariableFaceHeader, content: &str) -> Option<ShapedGlyphSet> {
+    font.with_borrowed_face(variations, |face| {
+        let ttf_face = face.ttf.as_face_ref();
+        // This is safe. Unwrap should be removed after rustybuzz is fixed:
+        // https://github.com/RazrFalcon/rustybuzz/issues/52
+        let buzz_face = rustybuzz::Face::from_face(ttf_face.clone()).unwrap();
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(content);
+        let shaped = rustybuzz::shape(&buzz_face, font.feature_settings(), buffer);
+        let variable_variations = default();
+        let glyphs = shaped
+            .glyph_positions()
+            .iter()
+            .zip(shaped.glyph_infos())
+            .map(|(&position, &info)| {
+                let glyph_id = GlyphId(info.glyph_id as u16);
+                let render_info =
+                    font.glyph_info_of_known_face(variations, &variable_variations, glyph_id, face);
+                ShapedGlyph { position, info, render_info }
+            })
+            .collect();
+        let space_glyph = ttf_face.glyph_index(' ');
+        let space_advance = space_glyph.and_then(|t| ttf_face.glyph_hor_advance(t));
+        ShapedGlyphSet {
+            units_per_em: ttf_face.units_per_em(),
+            ascender: ttf_face.ascender(),
+            descender: ttf_face.descender(),
+            line_gap: ttf_face.line_gap(),
+            non_variable_variations: variations,
+            font_family: default(),
+            space_advance: space_advance.unwrap_or_default(),
+            hyphen: None,
+            glyphs,
+        }
+    })
+}
+
+
+
+// ==================
+// === LabelBatch ===
+// ==================
+
+/// A batch of short, uniformly styled labels. See the module docs to learn more.
+///
+/// The labels are positioned by their baseline start. The batch color applies to all labels which
+/// do not have their own color set with [`Self::set_label_color`].
+#[derive(Clone, CloneRef, Debug, display::Object)]
+pub struct LabelBatch {
+    model: Rc<Model>,
+}
+
+impl LabelBatch {
+    /// Constructor. The labels are displayed with the default font.
+    pub fn new(scene: impl AsRef<Scene>) -> Self {
+        let model = Rc::new(Model::new(scene.as_ref()));
+        Self { model }
+    }
+
+    /// The number of labels in the batch.
+    pub fn len(&self) -> usize {
+        self.model.labels.borrow().len()
+    }
+
+    /// Check whether the batch has no labels.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Add a new label at the origin of the batch.
+    pub fn push(&self, content: impl Into<ImString>) -> LabelId {
+        let mut label = Label::new(&self.model.display_object);
+        self.model.set_content(&mut label, content.into());
+        let mut labels = self.model.labels.borrow_mut();
+        labels.push(label);
+        LabelId(labels.len() - 1)
+    }
+
+    /// Replace all labels of the batch. The glyphs of the existing labels are reused.
+    pub fn set_labels<T: Into<ImString>>(&self, contents: impl IntoIterator<Item = T>) {
+        let mut labels = self.model.labels.borrow_mut();
+        let mut count = 0;
+        for (index, content) in contents.into_iter().enumerate() {
+            if index == labels.len() {
+                labels.push(Label::new(&self.model.display_object));
+            }
+            self.model.set_content(&mut labels[index], content.into());
+            count = index + 1;
+        }
+        labels.truncate(count);
+    }
+
+    /// Remove all labels.
+    pub fn clear(&self) {
+        self.model.labels.borrow_mut().clear();
+    }
+
+    /// The content of the label.
+    pub fn content(&self, id: LabelId) -> Option<ImString> {
+        self.model.labels.borrow().get(id.0).map(|label| label.content.clone())
+    }
+
+    /// Change the content of the label.
+    pub fn set_content(&self, id: LabelId, content: impl Into<ImString>) {
+        if let Some(label) = self.model.labels.borrow_mut().get_mut(id.0) {
+            self.model.set_content(label, content.into());
+        }
+    }
+
+    /// The width of the label.
+    pub fn width(&self, id: LabelId) -> Option<f32> {
+        self.model.labels.borrow().get(id.0).map(|label| label.width)
+    }
+
+    /// The position of the label.
+    pub fn position(&self, id: LabelId) -> Option<Vector2> {
+        self.model.labels.borrow().get(id.0).map(|label| label.display_object.position().xy())
+    }
+
+    /// Move the label.
+    pub fn set_position(&self, id: LabelId, position: Vector2) {
+        if let Some(label) = self.model.labels.borrow().get(id.0) {
+            label.display_object.set_xy(position);
+        }
+    }
+
+    /// Move all labels at once. The coordinates are consecutive `x` and `y` pairs, one per label,
+    /// in the order of label ids. Superfluous coordinates are ignored, and labels without
+    /// coordinates are not moved.
+    pub fn set_positions(&self, coordinates: &[f32]) {
+        let labels = self.model.labels.borrow();
+        for (label, xy) in labels.iter().zip(coordinates.chunks_exact(2)) {
+            label.display_object.set_xy(Vector2(xy[0], xy[1]));
+        }
+    }
+
+    /// Set the color of all labels without their own color.
+    pub fn set_color(&self, color: impl Into<color::Lcha>) {
+        self.model.color.set(color.into());
+        self.model.labels.borrow().iter().for_each(|label| self.model.set_color(label));
+    }
+
+    /// Set the color of a single label, or reset it to the batch color if [`None`] is provided.
+    pub fn set_label_color(&self, id: LabelId, color: Option<color::Lcha>) {
+        if let Some(label) = self.model.labels.borrow_mut().get_mut(id.0) {
+            label.color = color;
+            self.model.set_color(label);
+        }
+    }
+
+    /// Set the font size of all labels.
+    pub fn set_font_size(&self, size: f32) {
+        self.model.font_size.set(size);
+        for label in self.model.labels.borrow_mut().iter_mut() {
+            self.model.layout(label);
+        }
+    }
+
+    /// Set the font of all labels. The name will be looked up in [`font::Registry`].
+    pub fn set_font(&self, scene: impl AsRef<Scene>, font_name: impl Into<font::Name>) {
+        let glyph_system = glyph::System::new(scene, font_name);
+        *self.model.glyph_system.borrow_mut() = glyph_system;
+        for label in self.model.labels.borrow_mut().iter_mut() {
+            label.glyphs.clear();
+            let content = label.content.clone();
+            self.model.set_content(label, content);
+        }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ensogl_core::application::Application;
+
+    #[test]
+    fn labels_are_shaped_once_and_moved_in_bulk() {
+        let app = Application::new("root");
+        let scene = &app.display.default_scene;
+        let batch = LabelBatch::new(scene);
+        let cache = scene.extension::<ShapeCache>();
+        let misses = cache.miss_count();
+        batch.set_labels(["Number", "Text", "Number"]);
+        assert_eq!(batch.len(), 3);
+        assert_eq!(cache.miss_count(), misses + 2);
+        assert_eq!(batch.width(LabelId(0)), batch.width(LabelId(2)));
+        assert!(batch.width(LabelId(0)) > Some(0.0));
+
+        batch.set_positions(&[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(batch.position(LabelId(0)), Some(Vector2(1.0, 2.0)));
+        assert_eq!(batch.position(LabelId(1)), Some(Vector2(3.0, 4.0)));
+        assert_eq!(batch.position(LabelId(2)), Some(Vector2(0.0, 0.0)));
+
+        let width = batch.width(LabelId(1)).unwrap();
+        batch.set_font_size(DEFAULT_FONT_SIZE * 2.0);
+        assert_eq!(batch.width(LabelId(1)), Some(width * 2.0));
+
+        batch.set_labels(["Text"]);
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch.content(LabelId(0)).as_deref(), Some("Text"));
+    }
+}
@@ -5,6 +5,7 @@ use enso_text::unit::*;
 use ensogl_core::display::shape::*;
 
 use crate::buffer::formatting;
+use crate::component::wrap;
 use crate::font::glyph::Glyph;
 
 use ensogl_core::data::color;
@@ -171,6 +172,119 @@ impl From<formatting::Size> for TruncationSize {
 }
 
 
+// ===================
+// === Decorations ===
+// ===================
+
+/// The thickness of the underline and the strikethrough.
+const DECORATION_THICKNESS: f32 = 1.0;
+/// The length of the dashes of the dashed underline, and the gaps between them.
+const DASH_LENGTH: f32 = 3.0;
+/// The length of a single wave of the wavy underline.
+const WAVE_PERIOD: f32 = 4.0;
+/// The height of the wavy underline, from the bottom of its troughs to the top of its crests.
+const WAVE_HEIGHT: f32 = 3.0;
+/// The height of the repeated dash and wave tiles. It only needs to exceed the decoration height.
+const DECORATION_TILE_HEIGHT: f32 = 16.0;
+/// The underline position below the baseline, relative to the line descender.
+const UNDERLINE_POSITION: f32 = 0.5;
+/// The strikethrough position above the baseline, relative to the line ascender.
+const STRIKETHROUGH_POSITION: f32 = 0.3;
+/// The maximum distance between glyphs drawn with a single decoration.
+const DECORATION_GAP_TOLERANCE: f32 = 0.01;
+
+/// A horizontal line along a run of glyphs, like an underline or a strikethrough. The line style
+/// is chosen by the `dashed` and `wavy` parameters, which are either 0.0 or 1.0. If both are 0.0,
+/// the line is solid.
+mod decoration {
+    use super::*;
+    ensogl_core::shape! {
+        pointer_events = false;
+        alignment = center;
+        (style: Style, rgba: Vector4<f32>, dashed: f32, wavy: f32) {
+            let width = Var::<f32>::from("input_size.x");
+            let height = Var::<f32>::from("input_size.y");
+            let bounds = Rect((1.px() * width, 1.px() * height));
+
+            let dash = Rect((DASH_LENGTH.px(), DECORATION_TILE_HEIGHT.px()));
+            let dashes = dash.repeat(((DASH_LENGTH * 2.0).px(), DECORATION_TILE_HEIGHT.px()));
+            let dashes = dashes.intersection(&bounds);
+
+            let half_period = WAVE_PERIOD / 2.0;
+            let rise = WAVE_HEIGHT - DECORATION_THICKNESS;
+            let slope_length = half_period.hypot(rise);
+            let slope_angle = rise.atan2(half_period);
+            let slope = Rect((slope_length.px(), DECORATION_THICKNESS.px()));
+            let rising = slope.rotate(slope_angle.radians());
+            let rising = rising.translate_x((-half_period / 2.0).px());
+            let falling = slope.rotate((-slope_angle).radians());
+            let falling = falling.translate_x((half_period / 2.0).px());
+            let waves = (rising + falling).repeat((WAVE_PERIOD.px(), DECORATION_TILE_HEIGHT.px()));
+            let waves = waves.intersection(&bounds);
+
+            let solid_weight = Var::<f32>::from(1.0) - dashed.clone() - wavy.clone();
+            let solid_alpha = rgba.w() * solid_weight;
+            let dashed_alpha = rgba.w() * dashed;
+            let wavy_alpha = rgba.w() * wavy;
+            let rgb = rgba.xyz();
+            let color = |alpha: Var<f32>| {
+                format!("srgba({}.x,{}.y,{}.z,{})", rgb, rgb, rgb, alpha.glsl())
+            };
+            let solid = bounds.fill(color(solid_alpha));
+            let dashes = dashes.fill(color(dashed_alpha));
+            let waves = waves.fill(color(wavy_alpha));
+            let shape = solid + dashes + waves;
+            shape.into()
+        }
+    }
+}
+
+/// A decoration drawn along a run of glyphs with equal decoration properties.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Decoration {
+    /// The position of the decoration start, relative to the line baseline.
+    pub start: Vector2,
+    /// The x-axis position of the decoration end.
+    pub end_x: f32,
+    /// The decoration color.
+    pub color: color::Lcha,
+    /// The line style. The strikethrough is always solid.
+    pub style: formatting::UnderlineStyle,
+}
+
+impl Decoration {
+    /// The decoration width.
+    pub fn width(&self) -> f32 {
+        self.end_x - self.start.x
+    }
+
+    /// The decoration height, including the waves of the wavy underline.
+    pub fn height(&self) -> f32 {
+        match self.style {
+            formatting::UnderlineStyle::Wavy => WAVE_HEIGHT,
+            _ => DECORATION_THICKNESS,
+        }
+    }
+
+    /// Check whether the decoration can be extended by the next one, so the dashes and waves do
+    /// not restart at every glyph.
+    fn is_continued_by(&self, next: &Decoration) -> bool {
+        let adjacent = (next.start.x - self.end_x).abs() < DECORATION_GAP_TOLERANCE;
+        let same_row = self.start.y == next.start.y;
+        adjacent && same_row && self.color == next.color && self.style == next.style
+    }
+}
+
+/// Push the decoration to the runs, extending the last run if possible.
+fn push_decoration(runs: &mut Vec<Decoration>, decoration: Decoration) {
+    match runs.last_mut() {
+        Some(last) if last.is_continued_by(&decoration) => last.end_x = decoration.end_x,
+        _ => runs.push(decoration),
+    }
+}
+
+
+
 // ===============
 // === Metrics ===
 // ===============
@@ -207,6 +321,8 @@ ensogl_core::define_endpoints_2! {
         set_metrics(Metrics),
         /// Set the baseline y-axis position.
         set_baseline(f32),
+        /// Set the height of the wrapped rows below the first row of the line.
+        set_wrapped_height(f32),
         skip_baseline_animation(),
 
         // === Internal API ===
@@ -248,6 +364,15 @@ pub struct View {
     /// Centers between division points. Used for glyph selection with mouse cursor.
     pub centers:        Vec<f32>,
     pub truncation:     Truncation,
+    /// The visual rows of the line. There is more than one row if the line is soft-wrapped.
+    pub rows:           wrap::Rows,
+    /// The distance between the baselines of consecutive rows.
+    pub row_height:     f32,
+    /// The hyphens rendered at the ends of the rows ending with a broken word, see
+    /// [`crate::component::hyphenation`].
+    hyphens:            Vec<Glyph>,
+    /// The views of the underlines and strikethroughs, see [`Self::update_decorations`].
+    decoration_views:   RefCell<Vec<decoration::View>>,
     baseline_anim:      Animation<f32>,
 }
 
@@ -262,6 +387,10 @@ impl View {
         let divs = default();
         let centers = default();
         let truncation: Truncation = default();
+        let rows = default();
+        let row_height = default();
+        let hyphens = default();
+        let decoration_views = default();
         let frame_time = frame_time.clone_ref();
         baseline_anim.simulator.update_spring(|s| s * crate::DEBUG_ANIMATION_SPRING_FACTOR);
 
@@ -275,8 +404,8 @@ impl View {
 
             new_baseline <- baseline_anim.value.on_change();
             frp.private.output.baseline <+ new_baseline;
-            new_descent <- all_with(&new_baseline, &frp.set_metrics,
-                |baseline,metrics| baseline + metrics.descender
+            new_descent <- all_with3(&new_baseline, &frp.set_metrics, &frp.set_wrapped_height,
+                |baseline,metrics,wrapped_height| baseline + metrics.descender - wrapped_height
             );
             frp.private.output.descent <+ new_descent.on_change();
             frp.private.output.metrics <+ frp.set_metrics.on_change();
@@ -287,7 +416,19 @@ impl View {
             eval start_time ((t) truncation.set_animation_start_time(*t));
         }
 
-        Self { frp, display_object, glyphs, divs, centers, truncation, baseline_anim }
+        Self {
+            frp,
+            display_object,
+            glyphs,
+            divs,
+            centers,
+            truncation,
+            rows,
+            row_height,
+            hyphens,
+            decoration_views,
+            baseline_anim,
+        }
     }
 
     /// Get glyph for the provided column or create a new one if it does not exist.
@@ -299,6 +440,56 @@ impl View {
         &self.glyphs[column]
     }
 
+    /// Get glyph for the provided column or create a new one if it does not exist or if it was
+    /// created for a different font family. Glyphs of different families are created by different
+    /// glyph systems, so they can not be reused.
+    pub fn get_or_create_of_family(
+        &mut self,
+        column: Column,
+        font_family: formatting::FontFamily,
+        cons: impl Fn() -> Glyph,
+    ) -> &Glyph {
+        if self.get_or_create(column, &cons).font_family() != font_family {
+            let glyph = cons();
+            self.add_child(&glyph);
+            let old_glyph = mem::replace(&mut self.glyphs[column], glyph);
+            old_glyph.unset_parent();
+        }
+        &self.glyphs[column]
+    }
+
+    /// Get the hyphen glyph with the provided index or create a new one if it does not exist or if
+    /// it was created for a different font family. See [`Self::get_or_create_of_family`].
+    pub fn get_or_create_hyphen(
+        &mut self,
+        index: usize,
+        font_family: formatting::FontFamily,
+        cons: impl Fn() -> Glyph,
+    ) -> &Glyph {
+        while self.hyphens.len() <= index {
+            let glyph = cons();
+            self.add_child(&glyph);
+            self.hyphens.push(glyph);
+        }
+        if self.hyphens[index].font_family() != font_family {
+            let glyph = cons();
+            self.add_child(&glyph);
+            let old_glyph = mem::replace(&mut self.hyphens[index], glyph);
+            old_glyph.unset_parent();
+        }
+        &self.hyphens[index]
+    }
+
+    /// The hyphens rendered at the ends of the rows, in the order of the rows.
+    pub fn hyphens(&self) -> &[Glyph] {
+        &self.hyphens
+    }
+
+    /// Remove the hyphen glyphs above the provided count.
+    pub fn truncate_hyphens(&mut self, count: usize) {
+        self.hyphens.truncate(count);
+    }
+
     /// Set the truncation of the line to the specified size.
     pub fn set_truncated(&mut self, size: Option<formatting::Size>) {
         if let Some(size) = size {
@@ -364,6 +555,130 @@ impl View {
         }
     }
 
+    /// Place the glyphs in the visual rows. The glyphs are expected to be placed in the unwrapped
+    /// line, one after another. The rows below the first one are placed `row_height` apart.
+    pub fn set_rows(&mut self, rows: wrap::Rows, row_height: f32) {
+        if rows.is_wrapped() {
+            for glyph in &self.glyphs {
+                let row = rows.row_of_x(glyph.x());
+                let y = glyph.y() - row as f32 * row_height;
+                glyph.set_xy(Vector2(glyph.x() - rows.x_offset(row), y));
+            }
+        }
+        self.rows = rows;
+        self.row_height = row_height;
+        self.set_wrapped_height(self.wrapped_height());
+    }
+
+    /// The height of the wrapped rows below the first row of the line.
+    pub fn wrapped_height(&self) -> f32 {
+        (self.rows.count() - 1) as f32 * self.row_height
+    }
+
+    /// The position of the division point before the column, relative to the line baseline. The
+    /// row of the column is taken into account if the line is wrapped.
+    pub fn position_of_column(&self, column: Column) -> Vector2 {
+        let row = self.rows.row_of_column(column);
+        let x = self.div_by_column(column) - self.rows.x_offset(row);
+        Vector2(x, -(row as f32) * self.row_height)
+    }
+
+    /// The x-axis position of the right edge of the row, relative to the line start.
+    pub fn row_end_x(&self, row: usize) -> f32 {
+        self.div_by_column(self.rows.end(row)) - self.rows.x_offset(row)
+    }
+
+    /// Finds the column close to the position relative to the line baseline. The row is chosen
+    /// first, and then the column within the row, see [`Self::column_in_row`].
+    pub fn column_close_to(&self, position: Vector2) -> Column {
+        let metrics = self.metrics();
+        let below_first_row = -position.y + metrics.descender - metrics.gap / 2.0;
+        let row =
+            if self.row_height > 0.0 { (below_first_row / self.row_height).ceil() } else { 0.0 };
+        self.column_in_row(row.max(0.0) as usize, position.x)
+    }
+
+    /// Finds the column of the row close to the x-axis position relative to the line start.
+    /// Positions after the end of a row which is followed by another row resolve to the last
+    /// column of the row, as the column starting the next row is displayed in that row.
+    pub fn column_in_row(&self, row: usize, x: f32) -> Column {
+        let row = row.min(self.rows.count() - 1);
+        let start = self.rows.start(row);
+        let is_last_row = row + 1 == self.rows.count();
+        let end =
+            if is_last_row { Column(self.divs.len() - 1) } else { self.rows.last_column(row) };
+        let column = Column(self.div_index_close_to(x + self.rows.x_offset(row)));
+        column.max(start).min(end.max(start))
+    }
+
+    /// The decorations of the glyphs, merged into runs of adjacent glyphs in the same row. The
+    /// underlines are placed below the baseline of the row, regardless of the baseline offset of
+    /// superscript or subscript glyphs.
+    pub fn decorations(&self) -> Vec<Decoration> {
+        let metrics = self.metrics();
+        let underline_y = metrics.descender * UNDERLINE_POSITION;
+        let strikethrough_y = metrics.ascender * STRIKETHROUGH_POSITION;
+        let mut underlines = vec![];
+        let mut strikethroughs = vec![];
+        for glyph in &self.glyphs {
+            if glyph.attached_to_cursor.get() {
+                continue;
+            }
+            let start_x = glyph.x();
+            let end_x = start_x + glyph.x_advance.get();
+            let row_y = self.row_baseline_y(glyph.y());
+            if glyph.underline().value {
+                let color = glyph.underline_color().color.unwrap_or_else(|| glyph.color());
+                let start = Vector2(start_x, row_y + underline_y);
+                let style = glyph.underline_style();
+                push_decoration(&mut underlines, Decoration { start, end_x, color, style });
+            }
+            if glyph.strikethrough().value {
+                let color = glyph.color();
+                let start = Vector2(start_x, row_y + strikethrough_y);
+                let style = default();
+                push_decoration(&mut strikethroughs, Decoration { start, end_x, color, style });
+            }
+        }
+        underlines.extend(strikethroughs);
+        underlines
+    }
+
+    /// The baseline of the row containing a glyph placed at the given y-axis position. The
+    /// baseline offsets of superscript and subscript glyphs are smaller than half of the row
+    /// height, so the closest row baseline is the baseline of the glyph row.
+    fn row_baseline_y(&self, glyph_y: f32) -> f32 {
+        if self.row_height > 0.0 {
+            (glyph_y / self.row_height).round() * self.row_height
+        } else {
+            0.0
+        }
+    }
+
+    /// Redraw the underlines and strikethroughs of the glyphs. It needs to be called after the
+    /// glyphs are placed in rows, or after changing their color or decoration properties.
+    pub fn update_decorations(&self) {
+        let decorations = self.decorations();
+        let mut views = self.decoration_views.borrow_mut();
+        views.truncate(decorations.len());
+        while views.len() < decorations.len() {
+            let view = decoration::View::new();
+            self.add_child(&view);
+            views.push(view);
+        }
+        for (view, decoration) in views.iter().zip(&decorations) {
+            let width = decoration.width();
+            let position = decoration.start + Vector2(width / 2.0, 0.0);
+            let dashed = decoration.style == formatting::UnderlineStyle::Dashed;
+            let wavy = decoration.style == formatting::UnderlineStyle::Wavy;
+            view.set_size(Vector2(width, decoration.height()));
+            view.set_xy(position);
+            view.rgba.set(color::Rgba::from(decoration.color).into());
+            view.dashed.set(if dashed { 1.0 } else { 0.0 });
+            view.wavy.set(if wavy { 1.0 } else { 0.0 });
+        }
+    }
+
     /// Resize glyph vector and use the provided constructor to create missing glyphs if any.
     pub fn resize_with(&mut self, size: usize, cons: impl Fn() -> Glyph) {
         let display_object = self.display_object().clone_ref();
@@ -0,0 +1,196 @@
+//! Splitting the text into pages for printing and exporting, for example to PDF. The text is split
+//! between lines, using the heights of the shaped lines, so every page fits in the page content
+//! area. The pages can be exported as print-friendly HTML, or displayed one by one in a text area
+//! rendered to an offscreen target, see [`crate::component::text::Input::show_page`].
+
+use crate::prelude::*;
+use enso_text::unit::*;
+
+use enso_text::Rope;
+
+
+
+// =================
+// === PageSetup ===
+// =================
+
+/// The page size and margins, in pixels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(missing_docs)]
+pub struct PageSetup {
+    pub width:         f32,
+    pub height:        f32,
+    pub margin_top:    f32,
+    pub margin_right:  f32,
+    pub margin_bottom: f32,
+    pub margin_left:   f32,
+}
+
+impl PageSetup {
+    /// Constructor of a page without margins.
+    pub fn new(width: f32, height: f32) -> Self {
+        let margins = 0.0;
+        Self {
+            width,
+            height,
+            margin_top: margins,
+            margin_right: margins,
+            margin_bottom: margins,
+            margin_left: margins,
+        }
+    }
+
+    /// The A4 page in the portrait orientation at 96 pixels per inch, with 2 cm margins.
+    pub fn a4() -> Self {
+        Self::new(794.0, 1123.0).with_margins(76.0, 76.0, 76.0, 76.0)
+    }
+
+    /// Set the margins, in the CSS order: top, right, bottom, and left.
+    pub fn with_margins(mut self, top: f32, right: f32, bottom: f32, left: f32) -> Self {
+        self.margin_top = top;
+        self.margin_right = right;
+        self.margin_bottom = bottom;
+        self.margin_left = left;
+        self
+    }
+
+    /// The size of the page area available for the text.
+    pub fn content_size(&self) -> Vector2<f32> {
+        let width = self.width - self.margin_left - self.margin_right;
+        let height = self.height - self.margin_top - self.margin_bottom;
+        Vector2(width.max(0.0), height.max(0.0))
+    }
+}
+
+impl Default for PageSetup {
+    fn default() -> Self {
+        Self::a4()
+    }
+}
+
+
+
+// ============
+// === Page ===
+// ============
+
+/// A page of the text.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Page {
+    /// The lines of the page, never empty.
+    pub lines:  Range<Line>,
+    /// The height of the lines of the page. It may exceed the page content height if the page
+    /// contains a single line higher than the page.
+    pub height: f32,
+}
+
+
+
+// ================
+// === Paginate ===
+// ================
+
+/// Split the lines of the given heights into pages. Every line height should include the spacing
+/// between it and the previous line. The lines are placed on the page as long as they fit in the
+/// page content area, and a line higher than the content area is placed on a separate page.
+/// ```
+/// # use ensogl_text::component::pagination::*;
+/// # use enso_text::unit::Line;
+/// let setup = PageSetup::new(100.0, 50.0).with_margins(5.0, 5.0, 5.0, 5.0);
+/// let pages = paginate([20.0, 20.0, 20.0, 60.0], &setup);
+/// let lines = pages.iter().map(|page| page.lines.clone()).collect::<Vec<_>>();
+/// assert_eq!(lines, vec![Line(0)..Line(2), Line(2)..Line(3), Line(3)..Line(4)]);
+/// ```
+pub fn paginate(line_heights: impl IntoIterator<Item = f32>, setup: &PageSetup) -> Vec<Page> {
+    let content_height = setup.content_size().y;
+    let mut pages = vec![];
+    let mut page = Page::default();
+    for (line, height) in line_heights.into_iter().enumerate() {
+        let is_page_empty = page.lines.is_empty();
+        if !is_page_empty && page.height + height > content_height {
+            let next_page = Page { lines: Line(line)..Line(line), height: 0.0 };
+            pages.push(mem::replace(&mut page, next_page));
+        }
+        page.lines.end = Line(line + 1);
+        page.height += height;
+    }
+    if !page.lines.is_empty() {
+        pages.push(page);
+    }
+    pages
+}
+
+
+
+// ============
+// === HTML ===
+// ============
+
+/// Style of the text exported to HTML.
+#[derive(Clone, Debug, Default)]
+#[allow(missing_docs)]
+pub struct HtmlStyle {
+    pub font_family: ImString,
+    pub font_size:   f32,
+    pub color:       ImString,
+}
+
+/// Export the pages as an HTML document. Every page is a separate element sized as the printed
+/// page, followed by a page break. The lines are given the provided heights, so the text is split
+/// into pages exactly as in the pagination.
+pub fn to_html(
+    text: &Rope,
+    pages: &[Page],
+    line_heights: &[f32],
+    setup: &PageSetup,
+    style: &HtmlStyle,
+) -> String {
+    let PageSetup { width, height, margin_top, margin_right, margin_bottom, margin_left } = *setup;
+    let content = setup.content_size();
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<style>\n");
+    html.push_str(&format!("@page {{ size: {width}px {height}px; margin: 0; }}\n"));
+    html.push_str("body { margin: 0; }\n");
+    html.push_str(&format!(
+        ".page {{ box-sizing: content-box; width: {}px; height: {}px; \
+         padding: {margin_top}px {margin_right}px {margin_bottom}px {margin_left}px; \
+         overflow: hidden; break-after: page; font-family: {}; font-size: {}px; color: {}; }}\n",
+        content.x, content.y, style.font_family, style.font_size, style.color,
+    ));
+    html.push_str(".page:last-child { break-after: auto; }\n");
+    html.push_str(".line { white-space: pre; overflow: hidden; }\n");
+    html.push_str("</style>\n</head>\n<body>\n");
+    for page in pages {
+        html.push_str("<div class=\"page\">\n");
+        for line in page.lines.start.value..page.lines.end.value {
+            let height = line_heights.get(line).copied().unwrap_or_default();
+            let content = text.line_range(Line(line)).map(|range| text.sub(range).to_string());
+            let content = escape_html(&content.unwrap_or_default());
+            let style = format!("height: {height}px; line-height: {height}px");
+            html.push_str(&format!("<div class=\"line\" style=\"{style}\">{content}</div>\n"));
+        }
+        html.push_str("</div>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Escape the characters with a special meaning in HTML.
+/// ```
+/// # use ensogl_text::component::pagination::escape_html;
+/// assert_eq!(escape_html("a < b && \"c\""), "a &lt; b &amp;&amp; &quot;c&quot;");
+/// ```
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(char),
+        }
+    }
+    escaped
+}
@@ -26,11 +26,13 @@ pub use crate::buffer::selection::Id;
 // =============
 
 const CURSOR_PADDING: f32 = 4.0;
-const CURSOR_WIDTH: f32 = 2.0;
+/// The default width of the cursor (caret).
+pub const CURSOR_WIDTH: f32 = 2.0;
 const CURSOR_ALPHA: f32 = 0.8;
 const CURSORS_SPACING: f32 = 1.0;
 const SELECTION_ALPHA: f32 = 0.3;
-const SELECTION_CORNER_RADIUS: f32 = 2.0;
+/// The default corner radius of the selection shape.
+pub const SELECTION_CORNER_RADIUS: f32 = 2.0;
 const BLINK_SLOPE_IN_DURATION: f32 = 200.0;
 const BLINK_SLOPE_OUT_DURATION: f32 = 200.0;
 const BLINK_ON_DURATION: f32 = 300.0;
@@ -68,7 +70,15 @@ pub mod shape {
         above = [ensogl_core::display::shape::compound::rectangle];
         pointer_events = false;
         alignment = center;
-        (style:Style, selection:f32, start_time:f32, not_blinking:f32, color_rgb:Vector3<f32>) {
+        (
+            style: Style,
+            selection: f32,
+            start_time: f32,
+            not_blinking: f32,
+            color_rgb: Vector3<f32>,
+            caret_color_rgb: Vector3<f32>,
+            corner_radius: f32
+        ) {
             let width_abs = Var::<f32>::from("abs(input_size.x)");
             let height = Var::<f32>::from("input_size.y");
             let rect_width = width_abs - 2.0 * CURSOR_PADDING;
@@ -84,8 +94,8 @@ pub mod shape {
             let blinking_alpha = (one - slope_out + slope_in) * CURSOR_ALPHA;
             let alpha = not_blinking.mix(blinking_alpha, SELECTION_ALPHA);
             let shape = Rect((1.px() * rect_width,1.px() * rect_height));
-            let shape = shape.corners_radius(SELECTION_CORNER_RADIUS.px());
-            let rgb = color_rgb;
+            let shape = shape.corners_radius(1.px() * corner_radius);
+            let rgb = format!("mix({caret_color_rgb},{color_rgb},{not_blinking})");
             let color = format!("srgba({}.x,{}.y,{}.z,{})", rgb, rgb, rgb,alpha.glsl());
             let shape = shape.fill(color);
             shape.into()
@@ -102,6 +112,11 @@ pub mod shape {
 ensogl_core::define_endpoints_2! {
     Input {
         set_color (color::Lch),
+        /// Set the color of the cursor (caret). If [`None`], the [`set_color`] is used.
+        set_caret_color (Option<color::Lch>),
+        /// Set the width of the cursor (caret). [`CURSOR_WIDTH`] by default.
+        set_caret_width (f32),
+        set_corner_radius (f32),
         set_ascender (f32),
         set_descender (f32),
         set_attached_glyphs (Rc<Vec<WeakGlyph>>),
@@ -213,11 +228,12 @@ impl Selection {
 
             // === Updating Display Object ===
 
-            _eval <- all_with(&position.value, &width.value,
-                f!([model](p,width){
+            caret_width_changed <- frp.set_caret_width.map(f!((w) model.caret_width.set(*w)));
+            _eval <- all_with3(&position.value, &width.value, &caret_width_changed,
+                f!([model](p,width,_){
                     let side       = width.signum();
                     let abs_width  = width.abs();
-                    let width      = max(CURSOR_WIDTH, abs_width - CURSORS_SPACING);
+                    let width      = max(model.caret_width.get(), abs_width - CURSORS_SPACING);
                     let view_width = CURSOR_PADDING * 2.0 + width;
                     let view_x     = (abs_width/2.0) * side;
                     model.display_object.set_xy(*p);
@@ -227,6 +243,11 @@ impl Selection {
                 })
             );
             eval frp.set_color((color) model.view.color_rgb.set(color.into()));
+            caret_color <- all_with(&frp.set_color, &frp.set_caret_color,
+                |color, caret_color| caret_color.unwrap_or(*color)
+            );
+            eval caret_color((color) model.view.caret_color_rgb.set(color.into()));
+            eval frp.set_corner_radius((radius) model.view.corner_radius.set(*radius));
 
 
             // === Right side of last glyph computation ===
@@ -291,6 +312,7 @@ pub struct SelectionModel {
     display_object: display::object::Instance,
     right_side:     display::object::Instance,
     edit_mode:      Rc<Cell<bool>>,
+    caret_width:    Rc<Cell<f32>>,
 }
 
 impl SelectionModel {
@@ -299,11 +321,13 @@ impl SelectionModel {
         let display_object = display::object::Instance::new();
         let right_side = display::object::Instance::new();
         let edit_mode = Rc::new(Cell::new(edit_mode));
+        let caret_width = Rc::new(Cell::new(CURSOR_WIDTH));
 
         display_object.add_child(&view);
         display_object.add_child(&right_side);
+        view.corner_radius.set(SELECTION_CORNER_RADIUS);
 
-        Self { view, display_object, right_side, edit_mode }
+        Self { view, display_object, right_side, edit_mode, caret_width }
     }
 }
 
@@ -0,0 +1,150 @@
+//! A cache of shaping results shared by all text areas of a scene. Scenes often display thousands
+//! of identical short labels (e.g. port types), and shaping each of them separately dominates the
+//! load time. Short lines rendered with a single font face are shaped once and the resulting glyph
+//! sets, including the render info used to set up the glyph instances, are reused.
+//!
+//! The font size is not a part of the key, as shaped glyph positions are expressed in font units
+//! and scaled to the font size only when the glyphs are laid out.
+
+use crate::prelude::*;
+
+use crate::buffer::formatting::FontFamily;
+use crate::component::font_features::FontFeatures;
+use crate::component::text::ShapedGlyphSet;
+use crate::font;
+
+use enso_font::NonVariableFaceHeader;
+use ensogl_core::display::scene;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The maximum byte size of a cached content. Longer lines are unlikely to repeat.
+pub const MAX_CONTENT_BYTE_SIZE: usize = 64;
+/// The maximum number of cached entries. The whole cache is dropped when it is exceeded.
+pub const MAX_ENTRIES: usize = 4096;
+
+
+
+// ===========
+// === Key ===
+// ===========
+
+/// The identity of a shaped content.
+#[allow(missing_docs)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub font:         font::Name,
+    pub variations:   NonVariableFaceHeader,
+    pub font_family:  FontFamily,
+    /// The OpenType features of the text area, as the text areas of a scene may differ in them.
+    pub features:     FontFeatures,
+    pub content_hash: u64,
+}
+
+impl Key {
+    /// Constructor.
+    pub fn new(
+        font: font::Name,
+        variations: NonVariableFaceHeader,
+        font_family: FontFamily,
+        features: FontFeatures,
+        content: &str,
+    ) -> Self {
+        let mut hasher = DefaultHasher::new();
+        std::hash::Hash::hash(content, &mut hasher);
+        let content_hash = hasher.finish();
+        Self { font, variations, font_family, features, content_hash }
+    }
+}
+
+
+
+// ==================
+// === ShapeCache ===
+// ==================
+
+#[derive(Debug)]
+struct Entry {
+    /// The content is kept to detect hash collisions.
+    content:    ImString,
+    glyph_sets: Vec<ShapedGlyphSet>,
+}
+
+#[derive(Debug, Default)]
+struct Model {
+    entries: RefCell<HashMap<Key, Entry>>,
+    hits:    Cell<usize>,
+    misses:  Cell<usize>,
+}
+
+/// The shaping results shared by all text areas of a scene. It is a
+/// [scene extension](scene::Extension), available with [`scene::Scene::extension`].
+#[derive(Clone, CloneRef, Debug, Default)]
+pub struct ShapeCache {
+    model: Rc<Model>,
+}
+
+impl ShapeCache {
+    /// Return the glyph sets of the content. If they are not cached yet, they are shaped with the
+    /// provided function first.
+    pub fn get_or_shape(
+        &self,
+        key: Key,
+        content: &str,
+        shape: impl FnOnce() -> Vec<ShapedGlyphSet>,
+    ) -> Vec<ShapedGlyphSet> {
+        let entries = self.model.entries.borrow();
+        let entry = entries.get(&key).filter(|entry| entry.content == content);
+        let cached = entry.map(|entry| entry.glyph_sets.clone());
+        drop(entries);
+        if let Some(glyph_sets) = cached {
+            self.model.hits.modify_(|t| *t += 1);
+            glyph_sets
+        } else {
+            self.model.misses.modify_(|t| *t += 1);
+            let glyph_sets = shape();
+            let mut entries = self.model.entries.borrow_mut();
+            if entries.len() >= MAX_ENTRIES {
+                entries.clear();
+            }
+            let content = content.into();
+            entries.insert(key, Entry { content, glyph_sets: glyph_sets.clone() });
+            glyph_sets
+        }
+    }
+
+    /// Drop all cached entries.
+    pub fn clear(&self) {
+        self.model.entries.borrow_mut().clear();
+    }
+
+    /// The number of cached entries.
+    pub fn len(&self) -> usize {
+        self.model.entries.borrow().len()
+    }
+
+    /// Check whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of lookups served from the cache.
+    pub fn hit_count(&self) -> usize {
+        self.model.hits.get()
+    }
+
+    /// The number of lookups which required shaping.
+    pub fn miss_count(&self) -> usize {
+        self.model.misses.get()
+    }
+}
+
+impl scene::Extension for ShapeCache {
+    fn init(_scene: &scene::Scene) -> Self {
+        default()
+    }
+}
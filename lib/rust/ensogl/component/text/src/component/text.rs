@@ -11,15 +11,30 @@ use crate::buffer;
 use crate::buffer::formatting;
 use crate::buffer::formatting::Formatting;
 use crate::buffer::FromInContextSnapped;
+use crate::buffer::LineTransform;
 use crate::buffer::Transform;
 use crate::buffer::TryFromInContext;
+use crate::component::annotation;
+use crate::component::clipboard;
+use crate::component::dom_mirror;
+use crate::component::editing_state;
+use crate::component::editing_state::EditingState;
+use crate::component::font_features;
+use crate::component::gutter;
+use crate::component::highlight;
+use crate::component::hyphenation;
 use crate::component::line;
+use crate::component::pagination;
 use crate::component::selection;
+use crate::component::shape_cache;
+use crate::component::shape_cache::ShapeCache;
+use crate::component::token_style;
+use crate::component::whitespace;
+use crate::component::wrap;
 use crate::component::Selection;
 use crate::font;
 use crate::font::glyph;
 use crate::font::glyph::Glyph;
-use crate::font::Font;
 use crate::font::GlyphId;
 use crate::font::GlyphRenderInfo;
 
@@ -27,15 +42,32 @@ use enso_font::NonVariableFaceHeader;
 use enso_frp as frp;
 use enso_frp::io::keyboard::Key;
 use enso_frp::stream::ValueProvider;
+use enso_text::LineWidthIndex;
 use enso_text::Rope;
+use ensogl_core::animation::idle;
+use ensogl_core::animation::quality;
 use ensogl_core::application;
 use ensogl_core::application::command::FrpNetworkProvider;
 use ensogl_core::application::shortcut;
 use ensogl_core::application::Application;
+use ensogl_core::control::callback;
+use ensogl_core::control::io::ime::Ime;
+use ensogl_core::control::io::mouse;
+use ensogl_core::control::io::virtual_keyboard::VirtualKeyboard;
 use ensogl_core::data::color;
+use ensogl_core::debug::input_latency;
+use ensogl_core::debug::time_travel::TimeTravel;
 use ensogl_core::display;
+use ensogl_core::display::scene::layer;
+use ensogl_core::display::scene::layer::WeakLayer;
+use ensogl_core::display::shape::Rectangle;
+use ensogl_core::display::shape::StyleWatchFrp;
+use ensogl_core::display::shape::INVISIBLE_HOVER_COLOR;
+use ensogl_core::display::style::data::DataMatch;
+use ensogl_core::gui::accessibility;
 use ensogl_core::gui::cursor;
-use ensogl_core::system::web::clipboard;
+use ensogl_core::system::web;
+use ensogl_core::Animation;
 use owned_ttf_parser::AsFaceRef;
 
 
@@ -52,13 +84,191 @@ pub use crate::buffer::RangeLike;
 // === Constants ===
 // =================
 
-/// Record separator ASCII code. Used for separating of copied strings. It is defined as the `\RS`
-/// escape code (`x1E`) (https://en.wikipedia.org/wiki/ASCII).
-pub const CLIPBOARD_RECORD_SEPARATOR: &str = "\x1E";
-
 /// The default ratio of ascender / descender. Used when creating a new line without glyphs.
 pub const DEFAULT_ASCENDER_TO_DESCENDER_RATIO: f32 = 0.1;
 
+/// Record separator ASCII code, separating the chunks of the copied text in the plain text.
+#[deprecated(note = "Use `component::clipboard::RECORD_SEPARATOR` instead.")]
+pub const CLIPBOARD_RECORD_SEPARATOR: &str = clipboard::RECORD_SEPARATOR;
+
+/// The number of lines below the visible area which are shaped in the idle time, so they are ready
+/// when the view is moved.
+pub const SHAPING_PREFETCH_LINE_COUNT: usize = 16;
+
+/// The maximum number of code points of a grapheme cluster passed to the shaper. Texts with heavy
+/// combining marks (like the "zalgo" texts) can contain clusters of thousands of code points. Only
+/// the first glyph of every cluster is displayed (see [`TextModel::shape_range`]), so the excess
+/// code points are dropped before shaping, keeping its cost bounded.
+pub const MAX_SHAPED_GRAPHEME_CLUSTER_LEN: usize = 32;
+
+/// The default time window, in milliseconds, in which consecutive typed characters are coalesced
+/// into a single undoable modification. See [`Input::set_undo_coalescing_window`].
+pub const UNDO_COALESCING_WINDOW_MS: f32 = 1000.0;
+
+/// The color of the line highlight displayed after going to a line. Its alpha fades out to zero.
+const GO_TO_LINE_HIGHLIGHT_COLOR: color::Rgba = color::Rgba::new(1.0, 0.85, 0.3, 0.35);
+
+/// The minimal width of the line highlight displayed after going to a line.
+const GO_TO_LINE_HIGHLIGHT_MIN_WIDTH: f32 = 100.0;
+
+/// The name of the highlight set displaying the search matches. See [`Input::find_all`].
+pub const SEARCH_HIGHLIGHT_SET: &str = "search";
+
+/// The default background color of the search matches.
+const SEARCH_MATCH_COLOR: color::Rgba = color::Rgba::new(0.95, 0.6, 0.1, 0.3);
+
+/// The name of the highlight set displaying the brackets matching the bracket next to the newest
+/// cursor. See [`Output::matching_brackets`].
+pub const BRACKET_HIGHLIGHT_SET: &str = "matching_brackets";
+
+/// The default background color of the matching brackets.
+const MATCHING_BRACKET_COLOR: color::Rgba = color::Rgba::new(0.5, 0.5, 0.5, 0.25);
+
+/// The name of the highlight set underlining the in-progress IME composition, see the
+/// [`ensogl_core::control::io::ime`] module docs.
+pub const IME_COMPOSITION_HIGHLIGHT_SET: &str = "ime_composition";
+
+/// The color of the IME composition underline, used if the caret color is not set.
+const IME_COMPOSITION_COLOR: color::Rgba = color::Rgba::new(0.0, 0.0, 0.0, 0.6);
+
+/// The default number of columns between the tab stops. See [`Input::set_tab_size`].
+pub const DEFAULT_TAB_SIZE: usize = 4;
+
+/// The number of lines scrolled by a single wheel event in the [`WheelScrollMode::Lines`] mode.
+pub const WHEEL_SCROLL_LINES: usize = 3;
+
+/// The relative change of the font size per unit of the wheel delta when zooming the text with
+/// the Ctrl key pressed.
+pub const WHEEL_ZOOM_SENSITIVITY: f32 = 0.002;
+
+/// The minimal font size reachable by zooming with the mouse wheel.
+pub const WHEEL_ZOOM_MIN_FONT_SIZE: f32 = 4.0;
+
+/// The maximal font size reachable by zooming with the mouse wheel.
+pub const WHEEL_ZOOM_MAX_FONT_SIZE: f32 = 128.0;
+
+/// The maximal time, in milliseconds, between clicks counted as a double or triple click.
+pub const MULTI_CLICK_INTERVAL_MS: f64 = 500.0;
+
+/// The maximal distance, in pixels, between clicks counted as a double or triple click.
+pub const MULTI_CLICK_MAX_DISTANCE: f32 = 4.0;
+
+/// The distance, in pixels, the mouse pressed over a selection has to move to start dragging the
+/// selection. Releasing the mouse earlier is a click.
+pub const SELECTION_DRAG_THRESHOLD: f32 = 4.0;
+
+
+
+// =============
+// === Theme ===
+// =============
+
+/// Theme paths of the text area style. The values set explicitly with the FRP inputs take
+/// precedence over the values from the theme. All keys are optional, the built-in defaults are used
+/// for the missing ones.
+#[allow(missing_docs)]
+pub mod theme {
+    pub const SELECTION_COLOR: &str = "component.text_area.selection.color";
+    pub const INACTIVE_SELECTION_COLOR: &str = "component.text_area.selection.inactive_color";
+    pub const SELECTION_CORNER_RADIUS: &str = "component.text_area.selection.corner_radius";
+    /// Non-zero value enables the [`super::Input::set_merge_adjacent_selections`] mode.
+    pub const MERGE_ADJACENT_SELECTIONS: &str = "component.text_area.selection.merge_adjacent";
+    /// The cursor color. If missing, the cursors are displayed with the selection color.
+    pub const CARET_COLOR: &str = "component.text_area.caret.color";
+    pub const CARET_WIDTH: &str = "component.text_area.caret.width";
+    /// The default text color, used by the text ranges without the color set explicitly.
+    pub const TEXT_COLOR: &str = "component.text_area.text.color";
+    /// The line height multiplier, see [`super::Input::set_line_height`].
+    pub const LINE_HEIGHT: &str = "component.text_area.text.line_height";
+    /// The color of the line highlight displayed after going to a line.
+    pub const LINE_HIGHLIGHT_COLOR: &str = "component.text_area.line_highlight.color";
+    pub const GUTTER_BREAKPOINT_COLOR: &str = "component.text_area.gutter.breakpoint_color";
+    pub const GUTTER_BOOKMARK_COLOR: &str = "component.text_area.gutter.bookmark_color";
+    pub const GUTTER_LINE_NUMBER_COLOR: &str = "component.text_area.gutter.line_number_color";
+}
+
+
+
+// =======================
+// === WheelScrollMode ===
+// =======================
+
+/// The way the text area is scrolled with the mouse wheel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum WheelScrollMode {
+    /// Every wheel event scrolls the text by [`WHEEL_SCROLL_LINES`] lines, regardless of its
+    /// delta. Suitable for mouse wheels with discrete steps.
+    #[default]
+    Lines,
+    /// The wheel deltas are accumulated, and the text is scrolled by one line every time the
+    /// accumulated delta exceeds the line height. Suitable for touchpads emitting many small
+    /// deltas.
+    Pixels,
+}
+
+
+
+// ==================
+// === MultiClick ===
+// ==================
+
+/// The state of the mouse selection by words or lines. A double click selects the word, and a
+/// triple click selects the line at the mouse position. Dragging the mouse after such a click
+/// extends the selection by whole words or lines.
+#[derive(Clone, Copy, Debug, Default)]
+struct MultiClick {
+    last_time:     f64,
+    last_position: Vector2,
+    count:         usize,
+    /// The selection unit ([`Transform::Word`] or [`Transform::Line`]) and the range selected by
+    /// the last double or triple click, if the last click was not a single one.
+    anchor:        Option<(Transform, Location, Location)>,
+}
+
+impl MultiClick {
+    /// Register the click and return the number of consecutive clicks at the same place. The
+    /// fourth click starts counting again.
+    fn register(&mut self, time: f64, position: Vector2) -> usize {
+        let is_in_time = time - self.last_time <= MULTI_CLICK_INTERVAL_MS;
+        let is_in_place = (position - self.last_position).norm() <= MULTI_CLICK_MAX_DISTANCE;
+        let is_continued = self.count > 0 && is_in_time && is_in_place;
+        self.count = if is_continued { self.count % 3 + 1 } else { 1 };
+        self.last_time = time;
+        self.last_position = position;
+        self.count
+    }
+}
+
+
+
+// =========================
+// === VerticalAlignment ===
+// =========================
+
+/// The vertical alignment of the text block within the view height set with the
+/// `set_view_height` input.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VerticalAlignment {
+    /// The top of the first line is placed at the top of the view.
+    #[default]
+    Top,
+    /// The text block is centered in the view.
+    Middle,
+    /// The bottom of the last line is placed at the bottom of the view.
+    Bottom,
+    /// The first baseline is placed at the default font size below the top of the view,
+    /// regardless of the fonts used in the first line. Text areas with the same default font
+    /// size placed next to each other share the first baseline.
+    Baseline,
+}
+
+impl VerticalAlignment {
+    /// Whether the position of the text depends on the height of the whole text block.
+    pub fn depends_on_content_height(self) -> bool {
+        matches!(self, Self::Middle | Self::Bottom)
+    }
+}
+
 
 
 // ====================
@@ -73,6 +283,25 @@ pub struct SelectionMap {
     location_map: HashMap<ViewLine, HashMap<Column, selection::Id>>,
 }
 
+/// Merge non-empty selections touching each other in the same line, keeping the id of the first
+/// one. The input selections have to be sorted, as in [`buffer::selection::Group`].
+fn merge_adjacent_selections(selections: &[buffer::Selection]) -> Vec<buffer::Selection> {
+    let mut merged: Vec<buffer::Selection> = Vec::with_capacity(selections.len());
+    for &selection in selections {
+        if let Some(last) = merged.last_mut() {
+            let touching = last.max() == selection.min();
+            let single_line = last.min().line == selection.max().line;
+            let non_empty = !last.is_cursor() && !selection.is_cursor();
+            if touching && single_line && non_empty {
+                *last = last.merge_with(selection);
+                continue;
+            }
+        }
+        merged.push(selection);
+    }
+    merged
+}
+
 
 
 // =============
@@ -119,7 +348,9 @@ impl Lines {
     }
 
     /// Get the coordinates of the provided locations. Please note that this function works properly
-    /// only for single-line locations. Multi-line location computation is not implemented yet.
+    /// only for single-line locations. Multi-line location computation is not implemented yet. If
+    /// the locations are in different rows of a wrapped line, the end is placed at the end of the
+    /// start row.
     pub fn coordinates(
         &self,
         start_location: ViewLocation,
@@ -130,20 +361,26 @@ impl Lines {
                 "Trying to compute coordinates for multi-line location. This is not supported yet."
             );
         }
-        let get_pos_x = |location: ViewLocation| {
-            let lines = self.borrow();
+        let lines = self.borrow();
+        let get_pos = |location: ViewLocation| {
             if location.line > self.last_line_index() {
-                *lines.last().divs.last()
+                let line = lines.last();
+                line.position_of_column(Column(line.divs.len() - 1))
             } else {
-                lines[location.line].div_by_column(location.offset)
+                lines[location.line].position_of_column(location.offset)
             }
         };
 
-        let start_x = get_pos_x(start_location);
-        let end_x = get_pos_x(end_location);
-        let y = self.borrow()[start_location.line].baseline();
-        let start_pos = Vector2(start_x, y);
-        let end_pos = Vector2(end_x, y);
+        let start_line = &lines[start_location.line];
+        let start_pos = get_pos(start_location);
+        let mut end_pos = get_pos(end_location);
+        if start_location.line == end_location.line && start_pos.y != end_pos.y {
+            let start_row = start_line.rows.row_of_column(start_location.offset);
+            end_pos = Vector2(start_line.row_end_x(start_row), start_pos.y);
+        }
+        let y = start_line.baseline();
+        let start_pos = Vector2(start_pos.x, start_pos.y + y);
+        let end_pos = Vector2(end_pos.x, start_pos.y);
         (start_pos, end_pos)
     }
 }
@@ -176,9 +413,21 @@ impl Text {
     /// Constructor.
     #[profile(Debug)]
     pub fn new(app: &Application) -> Self {
+        Self::new_with_buffer(app, buffer::BufferModel::new())
+    }
+
+    /// Constructor of a new view of the buffer of another text area. Both views edit the same
+    /// text, with shared formatting and undo history, but they have independent cursors and scroll
+    /// positions. Text changes made in any view are propagated to all other views. See
+    /// [`buffer::BufferModel::new_view`] to learn more.
+    pub fn new_with_shared_buffer(app: &Application, buffer: &buffer::BufferModel) -> Self {
+        Self::new_with_buffer(app, buffer.new_view())
+    }
+
+    fn new_with_buffer(app: &Application, buffer: buffer::BufferModel) -> Self {
         let frp = Frp::new();
         let scene = app.display.default_scene.clone_ref();
-        let data = TextModel::new(scene, &frp);
+        let data = TextModel::new(scene, &frp, buffer);
         Self { data, frp }.init()
     }
 }
@@ -193,10 +442,34 @@ ensogl_core::define_endpoints_2! {
     Input {
         /// Insert character of the last pressed key at every cursor.
         insert_char_of_last_pressed_key(),
-        /// Increase the indentation of all lines containing cursors.
-        increase_indentation(),
-        /// Decrease the indentation of all lines containing cursors.
-        decrease_indentation(),
+        /// Indent the lines covered by the selections. See [`buffer::Input::indent_selection`].
+        indent_selection(),
+        /// Outdent the lines covered by the selections. See
+        /// [`buffer::Input::outdent_selection`].
+        outdent_selection(),
+        /// Set the text inserted when indenting the lines. Four spaces by default.
+        set_indent_unit(buffer::IndentUnit),
+        /// Swap the lines covered by the selections with the line above them. See
+        /// [`buffer::Input::move_lines_up`].
+        move_lines_up(),
+        /// Swap the lines covered by the selections with the line below them.
+        move_lines_down(),
+        /// Duplicate the selected text, or the lines containing the cursors. See
+        /// [`buffer::Input::duplicate_selection`].
+        duplicate_selection(),
+        /// Comment or uncomment the lines covered by the selections with the line comment prefix,
+        /// like `"# "`. See [`buffer::Input::toggle_line_comment`].
+        toggle_line_comment(ImString),
+        /// Sort the lines covered by the selections. See [`buffer::LineTransform`] to learn how
+        /// the lines are transformed.
+        sort_lines(),
+        sort_lines_descending(),
+        sort_lines_case_insensitive(),
+        remove_duplicate_lines(),
+        trim_trailing_whitespace(),
+        /// Join the lines covered by the selections. If a selection covers a single line, it is
+        /// joined with the following line.
+        join_lines(),
         /// Removes the character on the left of every cursor.
         delete_left(),
         /// Removes the character on the right of every cursor.
@@ -223,6 +496,18 @@ ensogl_core::define_endpoints_2! {
         start_newest_selection_end_follow_mouse(),
         /// Stop changing the shape of the newest selection with the mouse position.
         stop_newest_selection_end_follow_mouse(),
+        /// Start dragging the selection under the mouse cursor. The drag starts after the mouse
+        /// moves further than the [`SELECTION_DRAG_THRESHOLD`], showing the drop caret at the mouse
+        /// position. Releasing the mouse earlier sets the cursor at the mouse position, like a
+        /// click. Bound to pressing the mouse when the `pointer_over_selection` output is set.
+        start_selection_drag(),
+        /// Drop the dragged selection at the mouse position, moving its text there. The move is a
+        /// single undoable modification.
+        drop_selection(),
+        /// Drop the dragged selection at the mouse position, copying its text there.
+        drop_selection_copy(),
+        /// Stop dragging the selection without modifying the text.
+        cancel_selection_drag(),
         /// Move the cursor to the text start.
         cursor_move_to_text_start(),
         /// Move the cursor to the text_end.
@@ -291,12 +576,16 @@ ensogl_core::define_endpoints_2! {
         undo(),
         /// Redo the last operation.
         redo(),
-        /// Copy the selected text to the clipboard.
+        /// Copy the selected text to the clipboard. The text of every selection is copied as a
+        /// separate chunk, see [`clipboard`].
         copy(),
         /// Copy the selected text to the clipboard and remove it from the text area.
         cut(),
         /// Paste the selected text from the clipboard.
         paste(),
+        /// Paste the text chunks into subsequent selections, like the chunks copied from many
+        /// selections. See [`TextModel::paste_chunks`].
+        paste_chunks(Rc<Vec<String>>),
 
         hover(),
         unhover(),
@@ -304,37 +593,286 @@ ensogl_core::define_endpoints_2! {
         blur(),
         set_single_line_mode(bool),
         set_hover(bool),
+        /// Render the glyphs in the scene picking pass, so the glyph under the pointer is resolved
+        /// by the GPU instead of the line geometry, which is robust at any zoom level. The glyph
+        /// location is emitted in the [`Output::glyph_under_pointer`]. Disabled by default.
+        set_glyph_picking(bool),
+        /// Enable or disable the text area. The content of a disabled text area is dimmed, the
+        /// carets are hidden, and it can't be focused nor edited by the user. The changes made with
+        /// the FRP API are still applied. Text areas are enabled by default.
+        set_enabled(bool),
+        /// Enable or disable editing the text by the user. The text of a read-only text area can
+        /// still be selected and copied, and it can be changed with the FRP API, like
+        /// [`set_content`] or [`apply_changes`]. Text areas are editable by default.
+        set_editable(bool),
+        /// Mirror the text area in the accessibility tree as a text box with the given label, so
+        /// it can be found and focused by the assistive technology, like screen readers. The text
+        /// areas without the label, like the ones used as plain labels, are not mirrored.
+        set_accessible_label(ImString),
+        /// Mirror the text into DOM elements kept in sync with the text changes, so it can be
+        /// found with the browser find-in-page, selected, and indexed by search engines. See the
+        /// [`dom_mirror`] module docs to learn more. The text is not mirrored by default.
+        set_dom_mirror(dom_mirror::Mode),
+        /// Record the modifications in the application-wide undo history instead of handling the
+        /// `cmd z` shortcut locally. See [`application::undo::Manager`] to learn more.
+        set_undo_manager(Option<application::undo::Manager>),
+        /// Coalesce the single characters typed within the time window, in milliseconds, into a
+        /// single undoable modification, so undoing a typed word does not take an undo per
+        /// character. Moving the cursors or any other modification ends the coalescing. Set
+        /// [`None`] to record every typed character separately. Defaults to
+        /// [`UNDO_COALESCING_WINDOW_MS`].
+        set_undo_coalescing_window(Option<f32>),
+        /// Begin an undo transaction. All modifications until the matching
+        /// [`end_undo_transaction`] are undone and redone together. Transactions can be nested.
+        begin_undo_transaction(),
+        end_undo_transaction(),
 
         set_cursor (LocationLike),
         add_cursor (LocationLike),
         select     (LocationLike, LocationLike),
         paste_string (ImString),
         insert (ImString),
+        /// Insert the text as if it was typed by the user: the substitution rules set with
+        /// [`set_substitutions`] are applied, and every substitution can be undone separately.
+        insert_typed (ImString),
+        /// Set the substitution rules applied to the typed text, for example
+        /// [`buffer::Substitutions::prose`] in prose contexts. Set [`None`] to disable them, e.g.
+        /// when editing code. Disabled by default.
+        set_substitutions (Option<buffer::Substitutions>),
+        /// Set the characters forming words, respected by the word movement, the word selection
+        /// (also with a double click), and the word deletion. For example,
+        /// [`buffer::WordPolicy::identifier`] is suitable for editing code.
+        set_word_policy (buffer::WordPolicy),
+        /// Apply the text changes, moving the selections to keep them in place. Used to synchronize
+        /// the text with external text models, see [`crate::component::binding`].
+        apply_changes (Rc<Vec<enso_text::Change>>),
         set_property (RangeLike, Option<formatting::Property>),
         set_property_default (Option<formatting::ResolvedProperty>),
+        /// Set the default values of all properties of the provided format at once. Unlike
+        /// calling [`set_property_default`] for each property, the text is redrawn only once.
+        set_default_format (formatting::Format),
         mod_property (RangeLike, Option<formatting::PropertyDiff>),
+        /// Start the emphasis animation of the range, e.g. to flash search matches. The start time
+        /// of the emphasis is set to the current frame time. The emphasis pulsing until removed can
+        /// be stopped by setting the [`formatting::Property::Emphasis`] property to [`None`].
+        emphasize (RangeLike, formatting::Emphasis),
+        /// Find all occurrences of the query. The matches are emitted in the
+        /// [`Output::search_matches`] and highlighted with the [`SEARCH_HIGHLIGHT_SET`]. If the
+        /// property is provided, it is set on all matches, replacing their previous value of this
+        /// property.
+        find_all (buffer::Query, Option<formatting::Property>),
+        /// Select the first match of the last [`find_all`] query after the newest selection, and
+        /// scroll the view to it. The search wraps around at the end of the text.
+        find_next (),
+        /// Select the last match of the last [`find_all`] query before the newest selection, and
+        /// scroll the view to it. The search wraps around at the start of the text.
+        find_prev (),
+        /// Remove the search matches and their highlight.
+        clear_search (),
+        /// Set the style of the search matches highlight.
+        set_search_highlight_style (highlight::Style),
+        /// Replace the newest selection with the text if it is a match of the last [`find_all`]
+        /// query, and select the next match. If the selection is not a match, only the next match
+        /// is selected, so the match can be reviewed before being replaced.
+        replace_next (ImString),
+        /// Replace all matches of the last [`find_all`] query with the text. The replacement is a
+        /// single undoable modification.
+        replace_all (ImString),
+        /// Move the newest cursor to the bracket matching the bracket next to it. See
+        /// [`buffer::BracketPair::jump_target`].
+        jump_to_matching_bracket (),
+        /// Set the style of the matching brackets highlight.
+        set_bracket_highlight_style (highlight::Style),
 
         /// Set color of selections (the cursor or characters selection).
         set_selection_color (color::Lch),
+        /// Set color of selections displayed when the text area is not focused. If not set, the
+        /// [`set_selection_color`] is used regardless of the focus.
+        set_inactive_selection_color (color::Lch),
+        /// Set the corner radius of the selection shapes.
+        set_selection_corner_radius (f32),
+        /// If set, non-empty selections touching each other in the same line are displayed as a
+        /// single shape. The selections in the buffer are not affected.
+        set_merge_adjacent_selections (bool),
+        /// Set color of the cursors. If not set, the cursors are displayed with the selection
+        /// color.
+        set_caret_color (color::Lch),
+        /// Set the width of the cursors.
+        set_caret_width (f32),
+        /// Set the line height multiplier. The value of 1.0 (the default) keeps the line height
+        /// resulting from the font metrics, bigger values add space between the lines.
+        set_line_height (f32),
+        /// Set the color of the line highlight displayed after going to a line.
+        set_line_highlight_color (color::Rgba),
+        /// Set the color of the gutter markers of the given kind.
+        set_gutter_marker_color (gutter::MarkerKind, color::Rgba),
+        /// Set the color of the line numbers displayed in the gutter.
+        set_line_number_color (color::Rgba),
 
         /// Set font in the text area. The name will be looked up in [`font::Registry`].
         ///
         /// Note, that this is a relatively heavy operation - it requires not only redrawing all
         /// lines, but also re-load internal structures for rendering (like WebGL buffers,
-        /// MSDF texture, etc.).
+        /// MSDF texture, etc.). The content, formatting and selections are preserved. See
+        /// [`Output::font_changed`].
         set_font (ImString),
+        /// Set the OpenType features enabled or disabled in this text area, for example
+        /// [`font_features::FontFeatures::ligatures`] to display the ligatures of programming
+        /// fonts. The features are applied after the default features of the font. All lines are
+        /// reshaped.
+        set_font_features (font_features::FontFeatures),
         set_content (ImString),
+        /// Set the content together with its formatting, built with [`buffer::StyledText`].
+        set_styled_content (buffer::StyledText),
+
+        /// Enable or disable the built-in mouse wheel handling. The wheel scrolls the text
+        /// vertically, Shift + wheel scrolls it horizontally if the view width is set, or emits
+        /// the [`Output::horizontal_wheel_scroll`] event otherwise, and Ctrl + wheel changes the
+        /// default font size. The handled wheel events do not propagate to the parents of the text
+        /// area, so enable it only if the text area is not embedded in another scroll area.
+        /// Disabled by default.
+        set_wheel_handling(bool),
+        set_wheel_scroll_mode(WheelScrollMode),
+        /// Scroll the view so the text at the given distance in pixels from the top of the content
+        /// is at the top of the view. The view is scrolled by whole lines of the default font size
+        /// and cannot be scrolled past the last line. Requires the view height to be set.
+        scroll_to (f32),
+        /// Scroll the view by the given distance in pixels. Positive values scroll towards the end
+        /// of the text. The distances shorter than a line are accumulated, like the wheel deltas
+        /// in the [`WheelScrollMode::Pixels`] mode. Requires the view height to be set.
+        scroll_by (f32),
+        /// Scroll the view horizontally, so the text at the given distance in pixels from the left
+        /// edge of the text is at the left edge of the view. Limited to the width of the longest
+        /// visible line. Requires the view width to be set, and has no effect if the lines are
+        /// wrapped or truncated.
+        horizontal_scroll_to (f32),
+        /// Scroll the view horizontally by the given distance in pixels. Positive values scroll
+        /// towards the line ends. See [`Input::horizontal_scroll_to`].
+        horizontal_scroll_by (f32),
+        /// Scroll the view after every selection change, so the newest cursor is visible, both
+        /// vertically and horizontally. Disabled by default.
+        set_cursor_follow (bool),
+
+        /// Set the default font size. The text is reshaped, and if the view height is set, the
+        /// number of visible lines is recomputed. The line of the primary cursor keeps its position
+        /// in the view, if visible.
+        set_font_size(f32),
+        /// Set the height of the text view in pixels. If set, only the lines fitting in the view
+        /// are displayed. If set to [`None`] (the default), the number of lines is unlimited.
+        set_view_height(Option<f32>),
+        /// Set the vertical alignment of the text block within the view height. The
+        /// [`VerticalAlignment::Middle`] and [`VerticalAlignment::Bottom`] alignments have no
+        /// effect if the view height is not set.
+        set_vertical_alignment(VerticalAlignment),
+        /// Snap the line baselines to the multiples of the given step, measured from the top of
+        /// the text area. Text areas placed next to each other with the same grid step share
+        /// baselines. Disabled if set to [`None`] (the default).
+        set_baseline_grid(Option<f32>),
+
+        /// Append the text at the end of the content. The selections are kept in place, and only
+        /// the last line is reshaped. Designed for log views, see [`set_max_line_count`] and
+        /// [`set_pinned_to_bottom`].
+        append (ImString),
+        /// Limit the number of lines. If exceeded after [`append`], the lines are removed from the
+        /// top. Removing them is a regular text change, so the remaining visible lines are moved
+        /// up and redrawn. If set to [`None`] (the default), the number of lines is unlimited.
+        set_max_line_count (Option<usize>),
+        /// Keep the view scrolled to the last line when text is appended. Requires the view height
+        /// to be set. Disengaged when the view is scrolled up, and engaged again when it is
+        /// scrolled back to the bottom.
+        set_pinned_to_bottom (bool),
 
         /// Set the width of the text view. If set to [`None`], the text view will be unlimited.
-        /// If set to a smaller value, either a horizontal scrollbar will appear or text will be
-        /// truncated (see the [`set_long_text_truncation_mode`]) if any of the lines is longer.
-        /// Please note that the support for scrollbars is not implemented yet.
+        /// If set to a smaller value, the lines longer than the view width are clipped and can be
+        /// scrolled horizontally, or truncated (see the [`set_long_text_truncation_mode`]). The
+        /// glyphs crossing the view edges are displayed entirely.
         set_view_width(Option<f32>),
 
         /// Truncate text not fitting the view. Truncated text will be displayed as three dots.
         /// Please note that you have to set the view width as well.
         set_long_text_truncation_mode(bool),
 
+        /// Set the soft wrapping mode. Wrapped lines are displayed in several visual rows, and the
+        /// cursor movement up, down, and to the line ends follows the rows. The truncation mode
+        /// has no effect on the wrapped lines. The lines are not wrapped by default.
+        set_wrap_mode(wrap::WrapMode),
+        /// Set the number of columns between the tab stops, measured in the widths of the space.
+        /// The tab characters are displayed up to the next tab stop. The default is
+        /// [`DEFAULT_TAB_SIZE`].
+        set_tab_size(usize),
+        /// Select the language of the hyphenation of the wrapped lines, or disable it with
+        /// [`None`]. The words not fitting a row are broken at the syllable boundaries listed in
+        /// the dictionary of the language, see [`add_hyphenation_dictionary`]. The hyphenation is
+        /// disabled by default.
+        set_hyphenation_language(Option<ImString>),
+        /// Add a hyphenation dictionary, replacing the previous dictionary of the same language.
+        add_hyphenation_dictionary(hyphenation::Dictionary),
+        /// If set, [`insert_tab`] inserts spaces up to the next tab stop instead of the tab
+        /// character.
+        set_insert_spaces_for_tab(bool),
+        /// Insert a tab at every cursor, replacing the selected text. If a selection covers several
+        /// lines, the lines are indented instead, see [`indent_selection`].
+        insert_tab(),
+
+        /// Highlight the trailing whitespace and the indentation mixing tabs and spaces. The issues
+        /// are listed in the [`Output::whitespace_issues`]. Disabled by default.
+        set_whitespace_diagnostics(bool),
+        /// Replace the annotations with the ones from the document, e.g. provided by a linter. The
+        /// annotation ranges follow the text through edits. The current annotations are available
+        /// in the [`Output::annotations`] and with the [`Text::export_annotations`] method.
+        import_annotations(Rc<annotation::Document>),
+        /// Set the named highlight set, replacing the set of the same name. The sets are managed
+        /// independently of the selections and the formatting, see the [`highlight`] module docs.
+        set_highlight_set(highlight::Set),
+        /// Remove the highlight set of the given name.
+        clear_highlight_set(ImString),
+        /// Remove all highlight sets.
+        clear_highlight_sets(),
+        /// Set the syntax highlighting tokens, replacing the previous ones. The tokens are
+        /// formatted according to their kinds with the [`Output::token_palette`], and their ranges
+        /// follow the text through edits.
+        set_tokens(Rc<Vec<token_style::Token>>),
+        /// Set the formats of the token kinds, overriding the formats defined by the theme. See
+        /// the [`token_style`] module docs to learn more.
+        set_token_palette(token_style::Palette),
+
+        /// Set the width of the gutter, the clickable margin on the left side of the text which
+        /// displays line markers. If set to zero (the default), the gutter is hidden.
+        set_gutter_width(f32),
+        /// Set or unset the gutter marker of the given kind in the line.
+        set_line_marker(Line, gutter::MarkerKind, bool),
+        /// Toggle the gutter marker of the given kind in the line.
+        toggle_line_marker(Line, gutter::MarkerKind),
+        /// Display the line numbers in the gutter, left of the markers. The gutter grows to fit
+        /// the numbers of the visible lines, see [`Output::gutter_width`]. Clicking a line number
+        /// selects the whole line.
+        set_line_numbers_visible(bool),
+        /// Remove all gutter markers of the given kind.
+        clear_line_markers(gutter::MarkerKind),
+        /// Toggle the bookmark marker in the line.
+        toggle_bookmark(Line),
+        /// Go to the next bookmarked line, wrapping around at the end of the text.
+        next_bookmark(),
+        /// Go to the previous bookmarked line, wrapping around at the start of the text.
+        prev_bookmark(),
+        /// Place the cursor at the location and scroll the view so the line is visible. The line
+        /// is briefly highlighted.
+        go_to_line(Line, Column),
+        /// If set, [`go_to_line`] places the line in the middle of the view. Please note that it
+        /// has an effect only if the number of visible lines is limited.
+        set_go_to_line_centered(bool),
+        /// Restore the selections, the scroll position, and the view configuration saved with
+        /// [`Text::editing_state`]. The text content should be restored first.
+        restore_editing_state(Rc<EditingState>),
+        /// Move the text area to the layer, like an overlay layer above the surrounding content
+        /// while it is edited. Set [`None`] to return it to its previous layer. The text area keeps
+        /// its content, selections, and the place in the display object hierarchy.
+        set_overlay_layer(Option<WeakLayer>),
+        /// Display the page, computed with [`Text::paginate`], by setting the view height and the
+        /// first view line. Displaying the pages one by one in a text area rendered to an offscreen
+        /// target allows printing the text as images.
+        show_page(pagination::Page),
+
         // === NOT FINISHED YET ===
         // The following endpoints control the view area of the text area. They are not finished
         // yet and using them will probably cause panics and rendering issues.
@@ -349,11 +887,128 @@ ensogl_core::define_endpoints_2! {
         selections      (buffer::selection::Group),
         content         (Rope),
         hovered         (bool),
+        glyph_picking   (bool),
+        /// The location of the glyph under the pointer, if the glyph picking is enabled. See
+        /// [`Input::set_glyph_picking`].
+        glyph_under_pointer (Option<Location>),
+        /// Whether the mouse cursor hovers over a selection of an editable text area, so pressing
+        /// the mouse starts dragging the selection instead of placing the cursor. Available in
+        /// shortcut conditions as `pointer_over_selection`.
+        pointer_over_selection (bool),
+        /// Set to `true` when the selection drag starts, and to `false` when the selection is
+        /// dropped or the drag is canceled. See [`Input::start_selection_drag`].
+        dragging_selection (bool),
         selection_color (color::Lch),
+        inactive_selection_color (Option<color::Lch>),
+        selection_corner_radius (f32),
+        merge_adjacent_selections (bool),
+        caret_color (Option<color::Lch>),
+        caret_width (f32),
+        line_height (f32),
         single_line_mode(bool),
+        /// Whether the text area is enabled. Available in shortcut conditions as `enabled`.
+        enabled         (bool),
+        /// Whether the text can be edited by the user. Available in shortcut conditions as
+        /// `editable`.
+        editable        (bool),
+        /// Whether the modifications are recorded in an application-wide undo history. Available
+        /// in shortcut conditions as `uses_undo_manager`.
+        uses_undo_manager (bool),
         view_width(Option<f32>),
         long_text_truncation_mode(bool),
+        wrap_mode(wrap::WrapMode),
+        tab_size(usize),
+        hyphenation_language(Option<ImString>),
+        insert_spaces_for_tab(bool),
+        font_size       (f32),
+        view_height     (Option<f32>),
+        vertical_alignment (VerticalAlignment),
+        baseline_grid   (Option<f32>),
+        whitespace_diagnostics (bool),
+        /// The whitespace issues found in the text, if the whitespace diagnostics are enabled.
+        /// Emitted after every change of the issues. Can be used to offer fixing all of them.
+        whitespace_issues (Rc<Vec<whitespace::Issue>>),
+        /// The annotations with ranges adjusted to the current text. Emitted after importing the
+        /// annotations and after every text change.
+        annotations (Rc<annotation::Document>),
+        /// The highlight sets with ranges adjusted to the current text, in the drawing order.
+        /// Emitted after every change of the sets and after every text change.
+        highlight_sets (Rc<Vec<highlight::Set>>),
+        /// The formats of the token kinds: the formats defined by the theme, overridden by the ones
+        /// set with [`Input::set_token_palette`]. Emitted after every theme change.
+        token_palette (token_style::Palette),
+        /// The ranges of the matches found with the [`Input::find_all`].
+        search_matches (Rc<Vec<buffer::Range<Byte>>>),
+        /// The match selected with [`Input::find_next`] or [`Input::find_prev`], or [`None`] if
+        /// there are no matches.
+        current_match (Option<buffer::Range<Byte>>),
+        search_highlight_style (highlight::Style),
+        /// The number of matches replaced with [`Input::replace_next`] or [`Input::replace_all`].
+        /// Emitted after every replacement request, also if nothing was replaced.
+        replacements_made (usize),
+        /// The bracket pair next to the newest cursor, highlighted with the
+        /// [`BRACKET_HIGHLIGHT_SET`]. Emitted after every selection change.
+        matching_brackets (Option<buffer::BracketPair>),
+        bracket_highlight_style (highlight::Style),
+        max_line_count  (Option<usize>),
+        pinned_to_bottom (bool),
+        wheel_handling  (bool),
+        wheel_scroll_mode (WheelScrollMode),
+        /// The horizontal mouse wheel delta, including the vertical delta with Shift pressed.
+        /// Emitted only if the view width is not set, as the text area cannot scroll horizontally
+        /// then, so it is up to the embedder to handle it.
+        horizontal_wheel_scroll (f32),
+        /// The distance in pixels between the top of the content and the top of the view.
+        scroll_position (f32),
+        /// The height of the whole content in pixels, including the lines out of the view.
+        /// Together with the [`Output::scroll_position`] and the view height, it describes the
+        /// vertical scroll extent.
+        content_height (f32),
+        /// The distance in pixels between the left edge of the text and the left edge of the view.
+        horizontal_scroll_position (f32),
+        cursor_follow (bool),
         glyph_system    (Option<glyph::System>),
+        /// Emitted after the font was changed with [`Input::set_font`], after the text was
+        /// reshaped. Contains the name of the loaded font, which is the default font name if the
+        /// requested font was not found.
+        font_changed    (ImString),
+        font_features   (font_features::FontFeatures),
+        /// The gutter width. It is the width set with [`Input::set_gutter_width`], or the width
+        /// needed to display the line numbers, if larger.
+        gutter_width    (f32),
+        line_numbers_visible (bool),
+        /// Emitted when the gutter was clicked next to the line.
+        gutter_line_clicked (Line),
+        /// The line next to which the mouse hovers over the gutter, if any.
+        gutter_line_hovered (Option<Line>),
+        /// The per-line gutter markers. Emitted when markers are changed, including when they are
+        /// moved by text edits.
+        line_markers    (gutter::Markers),
+        go_to_line_centered (bool),
+
+        // === Statistics ===
+
+        /// The number of lines of the text.
+        line_count      (usize),
+        /// The size of the text in bytes.
+        byte_size       (Byte),
+        /// The number of grapheme clusters of the text. Please note that counting them requires
+        /// iterating over the whole text after every change.
+        grapheme_count  (usize),
+        /// The number of characters (code points) of the text. Unlike [`grapheme_count`], it is
+        /// maintained incrementally, counting only the changed lines.
+        char_count      (usize),
+        /// The number of words (sequences of non-whitespace characters) of the text. Just like
+        /// [`char_count`], it is maintained incrementally.
+        word_count      (usize),
+        /// The width of the widest line of the whole text, in characters. Unlike [`width`], it
+        /// includes the lines out of the view, so it describes the horizontal scroll extent. It
+        /// is maintained incrementally, measuring only the changed lines.
+        max_line_width  (usize),
+        /// The number of selections, including cursors.
+        selection_count (usize),
+        /// The location of the end of the newest selection (the primary cursor), if any.
+        cursor_location (Option<Location>),
 
         // === Internal API ===
 
@@ -361,34 +1016,73 @@ ensogl_core::define_endpoints_2! {
         refresh_width(),
         /// The height value of text area will be refreshed.
         refresh_height(),
+        /// Emitted after the undo or the redo replaced the text, once the ranges attached to the
+        /// text were cropped to the restored text.
+        history_restored(),
     }
 }
 
 impl Text {
     fn init(self) -> Self {
         self.init_hover();
+        self.init_enabled();
         self.init_focus();
+        self.init_accessibility();
+        self.init_virtual_keyboard();
+        self.init_ime();
         self.init_single_line_mode();
         self.init_cursors();
         self.init_selections();
         self.init_copy_cut_paste();
+        self.init_selection_drag();
         self.init_edits();
         self.init_styles();
+        self.init_selection_style();
+        self.init_theme_style();
         self.init_view_management();
         self.init_undo_redo();
+        self.init_gutter();
+        self.init_navigation();
+        self.init_wheel();
+        self.init_scrolling();
+        self.init_log_view();
+        self.init_whitespace_diagnostics();
+        self.init_annotations();
+        self.init_highlight_sets();
+        self.init_search();
+        self.init_bracket_matching();
+        self.init_tabs();
+        self.init_token_styles();
+        self.init_editing_state();
+        self.init_dom_mirror();
+        self.init_pagination();
         self
     }
 
     fn init_hover(&self) {
+        let m = &self.data;
         let network = self.frp.network();
         let input = &self.frp.input;
         let out = &self.frp.private.output;
+        let pointer_move = m.display_object.on_event::<mouse::Move>();
+        let pointer_leave = m.display_object.on_event::<mouse::Leave>();
 
         frp::extend! { network
             hovered <- bool(&input.unhover,&input.hover);
             hovered <- any(&input.set_hover,&hovered);
             out.hovered <+ hovered;
             out.pointer_style <+ out.hovered.map(|h| h.then_or_default(cursor::Style::cursor));
+
+
+            // === Glyph Picking ===
+
+            out.glyph_picking <+ input.set_glyph_picking.on_change();
+            eval out.glyph_picking ((t) m.set_glyph_picking(*t));
+            picked_glyph <- pointer_move.map(f!((e) e.target().and_then(|t| m.glyph_location(&t))));
+            picked_glyph <- picked_glyph.gate(&out.glyph_picking);
+            no_picked_glyph <- any_(&pointer_leave, &out.glyph_picking.on_false()).constant(None);
+            glyph_under_pointer <- any(picked_glyph, no_picked_glyph);
+            out.glyph_under_pointer <+ glyph_under_pointer.on_change();
         }
     }
 
@@ -399,13 +1093,105 @@ impl Text {
         let out = &self.frp.private.output;
 
         frp::extend! { network
-            eval_ input.focus (m.focus());
+            focus <- input.focus.gate(&out.enabled);
+            eval_ focus (m.focus());
             eval_ input.blur (m.blur());
-            out.focused <+ input.focus.constant(true);
+            out.focused <+ focus.constant(true);
             out.focused <+ input.blur.constant(false);
         }
     }
 
+    /// Forward the focus between the text area and its accessibility node. It is initialized before
+    /// the virtual keyboard, so on touch screens the DOM focus ends up in the keyboard input.
+    fn init_accessibility(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+
+        frp::extend! { network
+            eval input.set_accessible_label ((label) m.set_accessible_label(label));
+            focused <- out.focused.on_true();
+            eval_ focused ([m] if let Some(node) = m.accessibility_node() { node.focus() });
+        }
+    }
+
+    /// Keep the caret of the focused text area above the on-screen keyboard, and insert the text
+    /// typed without key presses or with the symbol bar.
+    fn init_virtual_keyboard(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+        let keyboard = m.scene.extension::<VirtualKeyboard>();
+
+        frp::extend! { network
+            focused <- out.focused.on_true();
+            blurred <- out.focused.on_false();
+            caret_moved <- out.cursor_location.gate(&out.focused);
+            caret_changed <- any_(&focused, &caret_moved);
+            caret <- caret_changed.map(f_!(m.caret()));
+            keyboard.set_caret <+ caret;
+            last_caret <- caret.filter_map(|caret| caret.clone());
+            keyboard.unset_caret <+ last_caret.sample(&blurred);
+            input.insert_typed <+ keyboard.text_input.gate(&out.focused);
+        }
+    }
+
+    /// Display the in-progress IME composition at the caret of the focused text area, and insert
+    /// the committed composition string.
+    fn init_ime(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+        let ime = m.scene.extension::<Ime>();
+        let composition_set_name = ImString::new(IME_COMPOSITION_HIGHLIGHT_SET);
+
+        frp::extend! { network
+            focused <- out.focused.on_true();
+            blurred <- out.focused.on_false();
+            caret_moved <- out.cursor_location.gate(&out.focused);
+            caret_changed <- any_(&focused, &caret_moved);
+            caret <- caret_changed.map(f_!(m.caret()));
+            ime.set_caret <+ caret;
+            last_caret <- caret.filter_map(|caret| caret.clone());
+            ime.unset_caret <+ last_caret.sample(&blurred);
+
+            composing <- all_with(&out.focused, &out.editable, |f, e| *f && *e);
+            composition <- ime.composition.gate(&composing);
+            composition <- any(&composition, &composing.on_false().constant(None));
+            composition_range <- composition.map(f!((t) m.set_composition(t.as_deref())));
+            composition_set <- composition_range.map2(&out.caret_color, |range, color| {
+                let color = color.map(color::Rgba::from).unwrap_or(IME_COMPOSITION_COLOR);
+                let style = highlight::Style::underline(color, 1);
+                range.map(|t| highlight::Set::new(IME_COMPOSITION_HIGHLIGHT_SET, vec![t], style))
+            });
+            input.set_highlight_set <+ composition_set.filter_map(|t| t.clone());
+            no_composition <- composition_range.filter(|t| t.is_none());
+            input.clear_highlight_set <+ no_composition.constant(composition_set_name);
+            input.insert <+ ime.committed.gate(&composing);
+        }
+    }
+
+    fn init_enabled(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+
+        frp::extend! { network
+            init <- source_();
+            enabled <- any(&input.set_enabled, &init.constant(true));
+            out.enabled <+ enabled.on_change();
+            eval out.enabled ((t) m.set_enabled(*t));
+            input.blur <+ out.enabled.on_false();
+            editable <- any(&input.set_editable, &init.constant(true));
+            out.editable <+ editable.on_change();
+        }
+        init.emit(());
+    }
+
     fn init_single_line_mode(&self) {
         let _network = self.frp.network();
         let input = &self.frp.input;
@@ -472,37 +1258,95 @@ impl Text {
 
             buf.cursors_move <+ input.cursor_move_left.constant(Transform::Left);
             buf.cursors_move <+ input.cursor_move_right.constant(Transform::Right);
-            buf.cursors_move <+ input.cursor_move_up.constant(Transform::Up);
-            buf.cursors_move <+ input.cursor_move_down.constant(Transform::Down);
 
             buf.cursors_move <+ input.cursor_move_left_word.constant(Transform::LeftWord);
             buf.cursors_move <+ input.cursor_move_right_word.constant(Transform::RightWord);
 
-            buf.cursors_move <+ input.cursor_move_left_of_line.constant(Transform::LeftOfLine);
-            buf.cursors_move <+ input.cursor_move_right_of_line.constant(Transform::RightOfLine);
-
             buf.cursors_move <+ input.cursor_move_to_text_start.constant(Transform::StartOfDocument);
             buf.cursors_move <+ input.cursor_move_to_text_end.constant(Transform::EndOfDocument);
 
             buf.cursors_select <+ input.cursor_select_left.constant(Transform::Left);
             buf.cursors_select <+ input.cursor_select_right.constant(Transform::Right);
-            buf.cursors_select <+ input.cursor_select_up.constant(Transform::Up);
-            buf.cursors_select <+ input.cursor_select_down.constant(Transform::Down);
 
             buf.cursors_select <+ input.cursor_select_left_word.constant(Transform::LeftWord);
             buf.cursors_select <+ input.cursor_select_right_word.constant(Transform::RightWord);
 
-            buf.cursors_select <+ input.cursor_select_left_of_line.constant(Transform::LeftOfLine);
-            buf.cursors_select <+ input.cursor_select_right_of_line.constant(Transform::RightOfLine);
-
             buf.cursors_select <+ input.cursor_select_to_text_start.constant(Transform::StartOfDocument);
             buf.cursors_select <+ input.cursor_select_to_text_end.constant(Transform::EndOfDocument);
 
             buf.cursors_select <+ input.select_all.constant(Transform::All);
             buf.cursors_select <+ input.select_word_at_cursor.constant(Transform::Word);
+
+
+            // === Cursor Transformations Following Wrapped Rows ===
+
+            row_move <- any_mut::<Transform>();
+            row_move <+ input.cursor_move_up.constant(Transform::Up);
+            row_move <+ input.cursor_move_down.constant(Transform::Down);
+            row_move <+ input.cursor_move_left_of_line.constant(Transform::LeftOfLine);
+            row_move <+ input.cursor_move_right_of_line.constant(Transform::RightOfLine);
+            row_select <- any_mut::<Transform>();
+            row_select <+ input.cursor_select_up.constant(Transform::Up);
+            row_select <+ input.cursor_select_down.constant(Transform::Down);
+            row_select <+ input.cursor_select_left_of_line.constant(Transform::LeftOfLine);
+            row_select <+ input.cursor_select_right_of_line.constant(Transform::RightOfLine);
+
+            wrapping <- self.frp.output.wrap_mode.map(|mode| *mode != wrap::WrapMode::None);
+            buf.cursors_move <+ row_move.gate_not(&wrapping);
+            buf.cursors_select <+ row_select.gate_not(&wrapping);
+            buf.set_selections <+ row_move.gate(&wrapping).map(
+                f!((t) m.moved_selection_by_rows(*t, false))
+            );
+            buf.set_selections <+ row_select.gate(&wrapping).map(
+                f!((t) m.moved_selection_by_rows(*t, true))
+            );
         }
     }
 
+    /// The annotations with ranges adjusted to the current text. See [`Input::import_annotations`].
+    pub fn export_annotations(&self) -> annotation::Document {
+        self.data.annotations.document.borrow().clone()
+    }
+
+    /// The selections, the scroll position, and the view configuration, which can be serialized
+    /// and restored after a reload with [`Input::restore_editing_state`].
+    pub fn editing_state(&self) -> EditingState {
+        let out = &self.frp.output;
+        let view = editing_state::ViewConfig {
+            view_width:                out.view_width.value(),
+            view_height:               out.view_height.value(),
+            long_text_truncation_mode: out.long_text_truncation_mode.value(),
+            single_line_mode:          out.single_line_mode.value(),
+            editable:                  out.editable.value(),
+            wheel_scroll_mode:         out.wheel_scroll_mode.value(),
+            wrap_mode:                 out.wrap_mode.value(),
+        };
+        let buffer = &self.data.buffer;
+        EditingState::new(buffer.selections(), buffer.first_view_line(), view)
+    }
+
+    /// Split the text into pages, using the heights of the shaped lines. The pages can be exported
+    /// with [`Self::export_pages_html`] or displayed with [`Input::show_page`].
+    pub fn paginate(&self, setup: &pagination::PageSetup) -> Vec<pagination::Page> {
+        pagination::paginate(self.data.measured_line_heights(), setup)
+    }
+
+    /// Split the text into pages and export them as a print-friendly HTML document, for example to
+    /// export the text to PDF with the browser print dialog.
+    pub fn export_pages_html(&self, setup: &pagination::PageSetup) -> String {
+        let m = &self.data;
+        let line_heights = m.measured_line_heights();
+        let pages = pagination::paginate(line_heights.iter().copied(), setup);
+        let font_name = m.glyph_system.borrow().font.name().to_string();
+        let color = color::Rgba::from(m.buffer.formatting.color().default);
+        let style = pagination::HtmlStyle {
+            font_family: format!("\"{font_name}\", monospace").into(),
+            font_size:   m.buffer.formatting.font_size().default.value,
+            color:       color.to_javascript_string().into(),
+        };
+        pagination::to_html(&m.buffer.text(), &pages, &line_heights, setup, &style)
+    }
+
     /// Get current text location under the mouse cursor within this text area.
     pub fn location_at_mouse_position(&self) -> Location {
         let m = &self.data;
@@ -535,7 +1379,57 @@ impl Text {
             sel_end_2 <- mouse.position.sample(&input.set_newest_selection_end_to_mouse_position);
             set_newest_selection_end <- any(&sel_end_1, &sel_end_2);
             sel_end_pos <- set_newest_selection_end.map(f!((pos) m.screen_to_text_location(*pos)));
-            m.buffer.frp.set_newest_selection_end <+ sel_end_pos;
+            by_units <- sel_end_pos.map(f_!(m.multi_click.borrow().anchor.is_some()));
+            m.buffer.frp.set_newest_selection_end <+ sel_end_pos.gate_not(&by_units);
+            dragged_units <= sel_end_pos.map(f!((loc) m.multi_click_selection_to(*loc)));
+
+
+            // === Multi-Click ===
+
+            click_pos <- mouse.position.sample(&input.set_cursor_at_mouse_position);
+            clicked_units <= click_pos.map(f!((pos) m.register_click(*pos)));
+            unit_selection <- any(dragged_units, clicked_units);
+            input.select <+ unit_selection.map(|(start, end)| ((*start).into(), (*end).into()));
+            eval_ input.stop_newest_selection_end_follow_mouse (m.finish_multi_click());
+        }
+    }
+
+    fn init_selection_drag(&self) {
+        let m = &self.data;
+        let mouse = &m.scene.mouse.frp_deprecated;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+
+        frp::extend! { network
+            pointer_update <- any_(&mouse.position, &out.selections, &out.editable);
+            hovered_update <- pointer_update.gate(&out.hovered);
+            over_selection <- hovered_update.map(f_!(m.pointer_over_selection()));
+            not_hovered <- out.hovered.on_false().constant(false);
+            over_selection <- any(over_selection, not_hovered);
+            out.pointer_over_selection <+ over_selection.on_change();
+
+            press_pos <- mouse.position.sample(&input.start_selection_drag);
+            eval press_pos ((pos) m.press_selection(*pos));
+            drag_started <- mouse.position.map(f!((pos) m.drag_selection_to(*pos))).on_true();
+
+            drop_move <- input.drop_selection.constant(false);
+            drop_copy <- input.drop_selection_copy.constant(true);
+            drop <- any(drop_move, drop_copy);
+            release <= drop.map(f!((copy) m.release_selection(*copy)));
+            click <- release.filter(|r| matches!(r, SelectionRelease::Click)).constant(());
+            input.set_cursor_at_mouse_position <+ click;
+            input.stop_newest_selection_end_follow_mouse <+ click;
+            dropped <= release.map(|r| match r {
+                SelectionRelease::Drop { range, target, copy } => Some((*range, *target, *copy)),
+                SelectionRelease::Click => None,
+            });
+            m.buffer.frp.drop_text <+ dropped.gate(&out.editable);
+
+            canceled <- input.cancel_selection_drag.map(f_!(m.cancel_selection_drag())).on_true();
+            drag_ended <- any_(&dropped, &canceled);
+            out.dragging_selection <+ drag_started.constant(true);
+            out.dragging_selection <+ drag_ended.constant(false);
         }
     }
 
@@ -543,6 +1437,7 @@ impl Text {
         let m = &self.data;
         let network = self.frp.network();
         let input = &self.frp.input;
+        let out = &self.frp.private.output;
 
         frp::extend! { network
 
@@ -556,11 +1451,12 @@ impl Text {
             eval_ copy_whole_lines (m.buffer.frp.cursors_select(Transform::Line));
             sels_on_copy_whole_lines <- copy_whole_lines.map(f_!(m.buffer.selections_contents()));
             text_chunks_to_copy <- any(&sels_on_copy_whole_lines, &copy_regions_only);
-            eval text_chunks_to_copy ((s) m.copy(s));
+            eval text_chunks_to_copy ((s) clipboard::write(s, &m.buffer.selections_html()));
 
             // === Cut ===
 
-            sels_on_cut <- input.cut.map(f_!(m.buffer.selections_contents()));
+            cut <- input.cut.gate(&out.editable);
+            sels_on_cut <- cut.map(f_!(m.buffer.selections_contents()));
             all_empty_sels_on_cut <- sels_on_cut.map(|s|s.iter().all(|t|t.is_empty()));
             cut_whole_lines <- sels_on_cut.gate(&all_empty_sels_on_cut);
             cut_regions_only <- sels_on_cut.gate_not(&all_empty_sels_on_cut);
@@ -568,14 +1464,18 @@ impl Text {
             eval_ cut_whole_lines (m.buffer.frp.cursors_select(Transform::Line));
             sels_on_cut_whole_lines <- cut_whole_lines.map(f_!(m.buffer.selections_contents()));
             sels_to_cut <- any(&sels_on_cut_whole_lines,&cut_regions_only);
-            eval sels_to_cut ((s) m.copy(s));
+            eval sels_to_cut ((s) clipboard::write(s, &m.buffer.selections_html()));
             eval_ sels_to_cut (m.buffer.frp.delete_left());
 
             // === Paste ===
 
-            let paste_string = input.paste_string.clone_ref();
-            eval_ input.paste ([] clipboard::read_text(f!((t) paste_string.emit(t))));
-            eval input.paste_string((s) m.paste_string(s));
+            let paste_chunks = input.paste_chunks.clone_ref();
+            paste <- input.paste.gate(&out.editable);
+            eval_ paste ([] clipboard::read(f!((t) paste_chunks.emit(Rc::new(t)))));
+            paste_chunks <- input.paste_chunks.gate(&out.editable);
+            eval paste_chunks ((chunks) m.paste_chunks(chunks.to_vec()));
+            paste_string <- input.paste_string.gate(&out.editable);
+            eval paste_string ((s) m.paste_chunks(clipboard::decode_plain_text(s)));
         }
     }
 
@@ -592,32 +1492,107 @@ impl Text {
 
             // === User Driven Changes ===
 
-            eval_ input.delete_left (m.buffer.frp.delete_left());
-            eval_ input.delete_right (m.buffer.frp.delete_right());
-            eval_ input.delete_word_left (m.buffer.frp.delete_word_left());
-            eval_ input.delete_word_right (m.buffer.frp.delete_word_right());
-
+            // The endpoints below are ignored when the text area is not editable.
+            delete_left <- input.delete_left.gate(&out.editable);
+            delete_right <- input.delete_right.gate(&out.editable);
+            delete_word_left <- input.delete_word_left.gate(&out.editable);
+            delete_word_right <- input.delete_word_right.gate(&out.editable);
+            eval_ delete_left (m.buffer.frp.delete_left());
+            eval_ delete_right (m.buffer.frp.delete_right());
+            eval_ delete_word_left (m.buffer.frp.delete_word_left());
+            eval_ delete_word_right (m.buffer.frp.delete_word_right());
+            transform_lines <- any_mut::<LineTransform>();
+            transform_lines <+ input.sort_lines.constant(LineTransform::Sort);
+            transform_lines <+ input.sort_lines_descending.constant(LineTransform::SortDescending);
+            transform_lines <+
+                input.sort_lines_case_insensitive.constant(LineTransform::SortCaseInsensitive);
+            transform_lines <+
+                input.remove_duplicate_lines.constant(LineTransform::RemoveDuplicates);
+            transform_lines <+
+                input.trim_trailing_whitespace.constant(LineTransform::TrimTrailingWhitespace);
+            transform_lines <+ input.join_lines.constant(LineTransform::Join);
+            m.buffer.frp.transform_lines <+ transform_lines.gate(&out.editable);
+            m.buffer.frp.indent_selection <+ input.indent_selection.gate(&out.editable);
+            m.buffer.frp.outdent_selection <+ input.outdent_selection.gate(&out.editable);
+            m.buffer.frp.set_indent_unit <+ input.set_indent_unit;
+            m.buffer.frp.move_lines_up <+ input.move_lines_up.gate(&out.editable);
+            m.buffer.frp.move_lines_down <+ input.move_lines_down.gate(&out.editable);
+            m.buffer.frp.duplicate_selection <+ input.duplicate_selection.gate(&out.editable);
+            m.buffer.frp.toggle_line_comment <+ input.toggle_line_comment.gate(&out.editable);
+
+            key_down <- key_down.gate(&out.enabled).gate(&out.editable);
             key_to_insert <= key_down.map2(&out.single_line_mode, TextModel::process_key_event);
-            str_to_insert <- any(&input.insert, &key_to_insert);
-            eval str_to_insert ((s) m.buffer.frp.insert(s));
+            insert <- input.insert.gate(&out.editable);
+            eval insert ((s) m.buffer.frp.insert(s));
+            str_to_insert_typed <- any(&input.insert_typed, &key_to_insert);
+            str_to_insert_typed <- str_to_insert_typed.gate(&out.editable);
+            eval str_to_insert_typed ((s) m.buffer.frp.insert_typed(s));
+            eval input.set_substitutions ((rules) m.buffer.frp.set_substitutions(rules.clone()));
+            eval input.set_word_policy ((policy) m.buffer.frp.set_word_policy(policy.clone()));
+            eval input.set_overlay_layer ((layer) m.set_overlay_layer(layer));
+            eval input.apply_changes ((changes) m.buffer.frp.apply_changes(changes));
             eval input.set_content ((s) {
                 input.set_cursor(&default());
                 input.select_all();
-                input.insert(s);
+                // Inserting to the buffer directly, as the content can be set even if the text
+                // area is not editable.
+                m.buffer.frp.insert(s);
                 input.remove_all_cursors();
             });
+            eval input.set_styled_content ((styled) {
+                input.set_content(ImString::new(styled.content()));
+                m.set_properties(styled.spans());
+            });
 
 
             // === Reacting To Changes ===
 
+            // Typed characters will be visible after rendering the next frame, which finishes the
+            // input latency measurement.
+            eval_ m.buffer.frp.text_change (input_latency::mark_visual_change());
+            // Changes made by other views of the same buffer are not known line by line, so the
+            // whole text is redrawn.
+            eval_ m.buffer.frp.remote_text_change (m.clear_cache_and_redraw());
+            any_text_change <- any(&m.buffer.frp.text_change, &m.buffer.frp.remote_text_change);
+
             // The `content` event should be fired first, as any listener for `changed` may want to
             // read the new content, so it should be up-to-date.
-            out.content <+ m.buffer.frp.text_change.map(f_!(m.buffer.text()));
-            out.changed <+ m.buffer.frp.text_change;
+            out.content <+ any_text_change.map(f_!(m.buffer.text()));
+            out.changed <+ any_text_change;
             out.selections <+ m.buffer.frp.selection_non_edit_mode;
             out.selections <+ m.buffer.frp.selection_edit_mode.map(|m| m.selection_group.clone());
 
 
+            // === Statistics ===
+
+            init <- source_();
+            content_changed <- any(&init, &any_text_change.constant(()));
+            line_count <- content_changed.map(f_!(m.buffer.last_line_index().value + 1));
+            byte_size <- content_changed.map(f_!(m.buffer.last_byte_index()));
+            grapheme_count <- content_changed.map(f_!(m.buffer.grapheme_count()));
+            out.line_count <+ line_count.on_change();
+            out.byte_size <+ byte_size.on_change();
+            out.grapheme_count <+ grapheme_count.on_change();
+            // The undo, the redo, and the changes made by other views replace the whole text, so
+            // all lines are measured again.
+            history_restored <- any_(&m.buffer.frp.input.undo, &m.buffer.frp.input.redo);
+            snapshot_restored <- any_(&m.buffer.frp.input.restore_snapshot, &history_restored);
+            line_widths_reset <- any_(&snapshot_restored, &m.buffer.frp.remote_text_change);
+            // The line statistics are updated incrementally, measuring only the changed lines.
+            line_stats_changed <- line_widths_reset.map(f_!(m.reset_line_widths()));
+            line_stats_changed <+ m.buffer.frp.text_change.map(f!((c) m.update_line_widths(c)));
+            max_line_width <- line_stats_changed.map(f_!(m.line_widths.borrow().max_line_width()));
+            char_count <- line_stats_changed.map(f_!(m.line_widths.borrow().char_count()));
+            word_count <- line_stats_changed.map(f_!(m.line_widths.borrow().word_count()));
+            out.max_line_width <+ max_line_width.on_change();
+            out.char_count <+ char_count.on_change();
+            out.word_count <+ word_count.on_change();
+            selection_count <- out.selections.map(|sels| sels.len());
+            cursor_location <- out.selections.map(|sels| sels.newest().map(|sel| sel.end));
+            out.selection_count <+ selection_count.on_change();
+            out.cursor_location <+ cursor_location.on_change();
+
+
             // === Text Width And Height Updates ===
 
             // We are computing new width and height after all animations are run. This is because
@@ -629,6 +1604,7 @@ impl Text {
             eval_ out.refresh_width(m.width_dirty.set(true));
             eval_ out.refresh_height(m.height_dirty.set(true));
         }
+        init.emit(());
     }
 
     fn init_styles(&self) {
@@ -644,14 +1620,19 @@ impl Text {
 
             new_glyph_system <- input.set_font.map(f!([m](t) Some(m.set_font(t))));
             out.glyph_system <+ new_glyph_system;
+            out.font_changed <+ new_glyph_system.filter_map(|system| {
+                system.as_ref().map(|system| system.font.name().to_string().into())
+            });
+            out.font_features <+ input.set_font_features.on_change();
+            eval_ out.font_features (m.clear_cache_and_redraw());
 
 
             // === Colors ===
 
             m.buffer.frp.set_property_default <+ input.set_property_default;
             eval input.set_property_default((t) m.set_property_default(*t));
-            eval self.frp.set_selection_color((t) m.set_selection_color(*t));
-            out.selection_color <+ self.frp.set_selection_color;
+            eval input.set_default_format((t) m.set_default_format(*t));
+            out.font_size <+ input.set_default_format.filter_map(|t| t.font_size.map(|s| s.value));
 
 
             // === Style ===
@@ -663,7 +1644,170 @@ impl Text {
             mod_prop <- input.mod_property.map(f!([m]((r, p)) (Rc::new(r.expand(&m.buffer)),*p)));
             m.buffer.frp.mod_property <+ mod_prop;
             eval mod_prop ([m](t) t.1.map(|p| m.mod_property(&t.0, p)));
+
+
+            // === Emphasis ===
+
+            input.set_property <+ input.emphasize.map2(&m.scene.frp.frame_time,
+                |(range, emphasis), time| {
+                    let emphasis = emphasis.started_at(*time);
+                    (range.clone(), Some(formatting::Property::from(emphasis)))
+                }
+            );
+        }
+    }
+
+    fn init_selection_style(&self) {
+        let network = self.frp.network();
+        let m = &self.data;
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+        let style = &m.style_watch;
+        let color_theme = style.get(theme::SELECTION_COLOR);
+        let inactive_color_theme = style.get(theme::INACTIVE_SELECTION_COLOR);
+        let corner_radius_theme =
+            style.get_number_or(theme::SELECTION_CORNER_RADIUS, selection::SELECTION_CORNER_RADIUS);
+        let merge_theme = style.get_number_or(theme::MERGE_ADJACENT_SELECTIONS, 0.0);
+        let theme_color = |data: &Option<ensogl_core::display::style::Data>| {
+            data.color().map(|c| color::Lch::from(color::Lcha::from(c)))
+        };
+
+        frp::extend! { network
+            init <- source_();
+
+            // === Theme ===
+            // The theme values are ignored once the value was set explicitly.
+
+            color_set <- input.set_selection_color.constant(true).sampler();
+            color_from_theme <- all(&color_theme, &init)._0().gate_not(&color_set);
+            out.selection_color <+ color_from_theme.filter_map(theme_color);
+            out.selection_color <+ input.set_selection_color;
+
+            inactive_color_set <- input.set_inactive_selection_color.constant(true).sampler();
+            inactive_color_from_theme <- all(&inactive_color_theme, &init)._0();
+            inactive_color_from_theme <- inactive_color_from_theme.gate_not(&inactive_color_set);
+            out.inactive_selection_color <+ inactive_color_from_theme.map(theme_color);
+            out.inactive_selection_color <+ input.set_inactive_selection_color.some();
+
+            corner_radius_set <- input.set_selection_corner_radius.constant(true).sampler();
+            corner_radius_from_theme <- all(&corner_radius_theme, &init)._0();
+            out.selection_corner_radius <+ corner_radius_from_theme.gate_not(&corner_radius_set);
+            out.selection_corner_radius <+ input.set_selection_corner_radius;
+
+            merge_set <- input.set_merge_adjacent_selections.constant(true).sampler();
+            merge_from_theme <- all(&merge_theme, &init)._0().map(|v| *v > 0.0);
+            out.merge_adjacent_selections <+ merge_from_theme.gate_not(&merge_set);
+            out.merge_adjacent_selections <+ input.set_merge_adjacent_selections;
+
+
+            // === Applying Style ===
+
+            displayed_color <- all_with3(
+                &out.selection_color,
+                &out.inactive_selection_color,
+                &out.focused,
+                |color, inactive_color, focused| {
+                    if *focused { *color } else { inactive_color.unwrap_or(*color) }
+                }
+            );
+            eval displayed_color ((t) m.set_selection_color(*t));
+            eval out.selection_corner_radius ((t) m.set_selection_corner_radius(*t));
+            merge_changed <- out.merge_adjacent_selections.on_change();
+            eval_ merge_changed (m.update_selections());
+        }
+        init.emit(());
+    }
+
+    fn init_theme_style(&self) {
+        let network = self.frp.network();
+        let m = &self.data;
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+        let style = &m.style_watch;
+        let caret_color_theme = style.get(theme::CARET_COLOR);
+        let caret_width_theme = style.get_number_or(theme::CARET_WIDTH, selection::CURSOR_WIDTH);
+        let text_color_theme = style.get(theme::TEXT_COLOR);
+        let line_height_theme = style.get_number_or(theme::LINE_HEIGHT, 1.0);
+        let line_highlight_theme = style.get(theme::LINE_HIGHLIGHT_COLOR);
+        let line_number_color_theme = style.get(theme::GUTTER_LINE_NUMBER_COLOR);
+        let theme_color = |data: &Option<ensogl_core::display::style::Data>| data.color();
+        let is_color = |p: &Option<formatting::ResolvedProperty>| {
+            matches!(p, Some(formatting::ResolvedProperty::Color(_)))
+        };
+
+        frp::extend! { network
+            init <- source_();
+            // The theme values are ignored once the value was set explicitly.
+
+            // === Caret ===
+
+            caret_color_set <- input.set_caret_color.constant(true).sampler();
+            caret_color_from_theme <- all(&caret_color_theme, &init)._0();
+            caret_color_from_theme <- caret_color_from_theme.gate_not(&caret_color_set);
+            out.caret_color <+ caret_color_from_theme
+                .map(move |t| theme_color(t).map(|c| color::Lch::from(color::Lcha::from(c))));
+            out.caret_color <+ input.set_caret_color.some();
+            eval out.caret_color ((t) m.set_caret_color(*t));
+
+            caret_width_set <- input.set_caret_width.constant(true).sampler();
+            caret_width_from_theme <- all(&caret_width_theme, &init)._0();
+            out.caret_width <+ caret_width_from_theme.gate_not(&caret_width_set);
+            out.caret_width <+ input.set_caret_width;
+            eval out.caret_width ((t) m.set_caret_width(*t));
+
+
+            // === Text ===
+
+            color_default_set <- input.set_property_default.filter(is_color);
+            format_color_set <- input.set_default_format.filter(|format| format.color.is_some());
+            text_color_set <- any_(&color_default_set, &format_color_set).constant(true).sampler();
+            text_color_from_theme <- all(&text_color_theme, &init)._0().gate_not(&text_color_set);
+            text_color_from_theme <- text_color_from_theme.filter_map(theme_color);
+            eval text_color_from_theme ((t) m.set_default_text_color((*t).into()));
+
+            line_height_set <- input.set_line_height.constant(true).sampler();
+            line_height_from_theme <- all(&line_height_theme, &init)._0();
+            out.line_height <+ line_height_from_theme.gate_not(&line_height_set);
+            out.line_height <+ input.set_line_height;
+            eval out.line_height ((t) m.set_line_height(*t));
+
+
+            // === Highlights ===
+
+            highlight_color_set <- input.set_line_highlight_color.constant(true).sampler();
+            highlight_color_from_theme <- all(&line_highlight_theme, &init)._0();
+            highlight_color_from_theme <- highlight_color_from_theme.gate_not(&highlight_color_set);
+            highlight_color <- highlight_color_from_theme.filter_map(theme_color);
+            highlight_color <- any(&highlight_color, &input.set_line_highlight_color);
+            eval highlight_color ((t) m.line_highlight_color.set(*t));
+
+
+            // === Gutter ===
+
+            eval input.set_gutter_marker_color (((k, c)) m.set_gutter_marker_color(*k, *c));
+
+            number_color_set <- input.set_line_number_color.constant(true).sampler();
+            number_color_from_theme <- all(&line_number_color_theme, &init)._0();
+            number_color_from_theme <- number_color_from_theme.gate_not(&number_color_set);
+            number_color <- number_color_from_theme.filter_map(theme_color);
+            number_color <- any(&number_color, &input.set_line_number_color);
+            eval number_color ((color) m.gutter.set_line_number_color(*color));
+        }
+        let marker_theme_paths = [
+            (gutter::MarkerKind::Breakpoint, theme::GUTTER_BREAKPOINT_COLOR),
+            (gutter::MarkerKind::Bookmark, theme::GUTTER_BOOKMARK_COLOR),
+        ];
+        for (kind, path) in marker_theme_paths {
+            let color_theme = style.get(path);
+            frp::extend! { network
+                color_set <- input.set_gutter_marker_color.filter(move |(k, _)| *k == kind);
+                color_set <- color_set.constant(true).sampler();
+                color_from_theme <- all(&color_theme, &init)._0().gate_not(&color_set);
+                color_from_theme <- color_from_theme.filter_map(theme_color);
+                eval color_from_theme ((color) m.set_gutter_marker_color(kind, *color));
+            }
         }
+        init.emit(());
     }
 
     fn init_view_management(&self) {
@@ -681,291 +1825,1082 @@ impl Text {
 
             out.long_text_truncation_mode <+ self.frp.set_long_text_truncation_mode;
             eval_ self.frp.set_long_text_truncation_mode (m.redraw());
+
+            out.wrap_mode <+ self.frp.set_wrap_mode;
+            eval_ self.frp.set_wrap_mode (m.redraw());
+
+            out.hyphenation_language <+ self.frp.set_hyphenation_language;
+            eval self.frp.set_hyphenation_language ((t) m.set_hyphenation_language(t.clone()));
+            eval self.frp.add_hyphenation_dictionary ((t) m.add_hyphenation_dictionary(t.clone()));
+
+            out.view_height <+ self.frp.set_view_height;
+            eval self.frp.set_view_height ((height) m.set_view_height(*height));
+            eval self.frp.set_font_size ((size) m.set_font_size(*size));
+            out.font_size <+ self.frp.set_font_size;
+
+            out.vertical_alignment <+ self.frp.set_vertical_alignment;
+            out.baseline_grid <+ self.frp.set_baseline_grid;
+            eval_ out.vertical_alignment (m.redraw());
+            eval_ out.baseline_grid (m.redraw());
         }
     }
 
     fn init_undo_redo(&self) {
         let m = &self.data;
         let input = &self.frp.input;
+        let out = &self.frp.private.output;
         let network = self.frp.network();
 
         frp::extend! { network
-            eval_ input.undo (m.buffer.frp.undo());
-            eval_ input.undo (m.redraw());
-            eval_ input.redo (m.buffer.frp.redo());
+            init <- source_();
+            undo <- input.undo.gate(&out.editable);
+            redo <- input.redo.gate(&out.editable);
+            eval_ undo (m.buffer.frp.undo());
+            eval_ undo (m.clear_cache_and_redraw());
+            eval_ redo (m.buffer.frp.redo());
+            eval_ redo (m.clear_cache_and_redraw());
+            eval_ m.buffer.frp.input.restore_snapshot (m.clear_cache_and_redraw());
+            history_restored <- any_(&m.buffer.frp.input.undo, &m.buffer.frp.input.redo);
+            eval_ history_restored (m.crop_to_restored_text());
+            out.history_restored <+ history_restored;
+            default_coalescing_window <- init.constant(Some(UNDO_COALESCING_WINDOW_MS));
+            coalescing_window <- any(&input.set_undo_coalescing_window, &default_coalescing_window);
+            m.buffer.frp.set_undo_coalescing_window <+ coalescing_window;
+            m.buffer.frp.begin_transaction <+ input.begin_undo_transaction;
+            m.buffer.frp.end_transaction <+ input.end_undo_transaction;
+
+
+            // === Undo Manager ===
+
+            // Every modification recorded in the buffer history since the last text change is
+            // recorded as a single operation in the undo manager.
+            undo_manager <- input.set_undo_manager.sampler();
+            out.uses_undo_manager <+ undo_manager.map(|manager| manager.is_some());
+            history_changed <- any_(&m.buffer.frp.text_change, &undo, &redo, &undo_manager);
+            history_len <- history_changed.map(f_!(m.buffer.history_len()));
+            prev_history_len <- history_len.previous();
+            new_entries <- history_len.map2(&prev_history_len, |t, prev| t.saturating_sub(*prev));
+            edit_entries <- new_entries.sample(&m.buffer.frp.text_change);
+            eval edit_entries ([m, undo_manager](count)
+                m.record_undo_operation(&undo_manager.value(), *count)
+            );
         }
+        init.emit(());
     }
-}
 
+    fn init_gutter(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+        let mouse = &m.scene.mouse.frp_deprecated;
+        let hit_area = m.gutter.hit_area();
+        let gutter_over = hit_area.on_event::<mouse::Over>();
+        let gutter_out = hit_area.on_event::<mouse::Out>();
+        let gutter_down = hit_area.on_event::<mouse::Down>();
 
+        frp::extend! { network
 
-// =================
-// === TextModel ===
-// =================
+            // === Size ===
 
-/// Internal representation of `Text`.
-#[derive(Clone, CloneRef, Debug, Deref, display::Object)]
-pub struct TextModel {
-    rc: Rc<TextModelData>,
-}
+            gutter_size <- all(&input.set_gutter_width, &out.height);
+            new_gutter_width <- gutter_size.map(f!(((w, h)) m.set_gutter_size(*w, *h)));
+            out.gutter_width <+ new_gutter_width;
 
-/// Internal representation of `Text`.
-#[derive(Debug, display::Object)]
-pub struct TextModelData {
-    buffer:         buffer::Buffer,
-    scene:          display::Scene,
-    frp:            WeakFrp,
-    display_object: display::object::Instance,
-    glyph_system:   RefCell<glyph::System>,
-    lines:          Lines,
-    selection_map:  RefCell<SelectionMap>,
-    width_dirty:    Cell<bool>,
-    height_dirty:   Cell<bool>,
-    /// Cache of shaped lines.
-    shaped_lines:   RefCell<BTreeMap<Line, ShapedLine>>,
-}
 
-impl TextModel {
-    /// Constructor.
-    fn new(scene: display::Scene, frp: &Frp) -> Self {
-        let selection_map = default();
-        let display_object = display::object::Instance::new_named("Text");
-        let glyph_system = font::glyph::System::new(&scene, font::DEFAULT_CODE_FONT);
-        frp.private.output.glyph_system.emit(Some(glyph_system.clone()));
-        let glyph_system = RefCell::new(glyph_system);
-        let buffer = buffer::Buffer::new(buffer::BufferModel::new());
+            // === Line Numbers ===
 
-        let default_size = buffer.formatting.font_size().default.value;
-        let first_line =
-            Self::new_line_helper(&scene.frp.frame_time, &display_object, default_size);
-        first_line.set_baseline((-default_size).round());
-        first_line.skip_baseline_animation();
+            out.line_numbers_visible <+ input.set_line_numbers_visible;
+            eval input.set_line_numbers_visible ((visible) m.set_line_numbers_visible(*visible));
+            eval out.font_size ((size) m.set_line_number_font_size(*size));
 
-        let lines = Lines::new(first_line);
-        let width_dirty = default();
-        let height_dirty = default();
-        let shaped_lines = default();
 
-        let frp = frp.downgrade();
-        let data = TextModelData {
-            scene,
-            frp,
-            buffer,
-            display_object,
-            glyph_system,
-            lines,
-            selection_map,
-            width_dirty,
-            height_dirty,
-            shaped_lines,
-        };
-        Self { rc: Rc::new(data) }.init()
-    }
+            // === Mouse ===
 
-    #[profile(Debug)]
-    fn init(self) -> Self {
-        self.init_line(self.lines.borrow().first());
-        self
-    }
+            gutter_hovered <- bool(&gutter_out, &gutter_over);
+            hover_pos <- mouse.position.gate(&gutter_hovered);
+            hovered_line <- hover_pos.map(f!((p) Some(m.screen_to_text_location(*p).line)));
+            unhovered_line <- gutter_out.constant(None);
+            hovered_line <- any(hovered_line, unhovered_line);
+            out.gutter_line_hovered <+ hovered_line.on_change();
+            click <- gutter_down.map(|e| e.button() == mouse::PrimaryButton).on_true();
+            click_pos <- mouse.position.sample(&click);
+            out.gutter_line_clicked <+ click_pos.map(f!((p) m.screen_to_text_location(*p).line));
+            line_number_clicked <= click_pos.map(f!((p) m.line_number_at(*p)));
+            input.select <+ line_number_clicked.map(f!((line) m.line_selection(*line)));
 
-    fn init_line(&self, line: &line::View) {
-        let network = line.frp.network();
-        frp::extend! { network
-            self.frp.private.output.refresh_height <+_ line.descent;
+
+            // === Markers ===
+
+            marker_set <- input.set_line_marker.map(
+                f!(((line, kind, enabled)) m.gutter.markers.borrow_mut().set(*line, *kind, *enabled))
+            );
+            marker_toggled <- input.toggle_line_marker.map(
+                f!(((line, kind)) { m.gutter.markers.borrow_mut().toggle(*line, *kind); true })
+            );
+            markers_cleared <- input.clear_line_markers.map(
+                f!((kind) m.gutter.markers.borrow_mut().clear(*kind))
+            );
+            markers_moved <- out.changed.map(
+                f!((changes) m.move_line_markers_after_changes(changes))
+            );
+            markers_changed <- any(marker_set, marker_toggled, markers_cleared, markers_moved);
+            markers_changed <- markers_changed.on_true();
+            eval_ markers_changed (m.redraw_gutter_markers());
+            out.line_markers <+ markers_changed.map(f_!(m.gutter.markers.borrow().clone()));
         }
     }
 
-    /// Helper for newline creation. It is used to create a new line during model initialization,
-    /// and thus, it can't be a method.
-    fn new_line_helper(
-        frame_time: &enso_frp::Stream<f32>,
-        display_object: &display::object::Instance,
-        default_size: f32,
-    ) -> line::View {
-        let line = line::View::new(frame_time);
-        let ascender = default_size;
-        let descender = ascender * DEFAULT_ASCENDER_TO_DESCENDER_RATIO;
-        let gap = 0.0;
-        let metrics = line::Metrics { ascender, descender, gap };
-        line.set_metrics(metrics);
-        display_object.add_child(&line);
-        line
+    fn init_wheel(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+        let wheel = m.display_object.on_event::<mouse::Wheel>();
+
+        frp::extend! { network
+            init <- source_();
+            out.wheel_handling <+ any(&input.set_wheel_handling, &init.constant(false));
+            out.wheel_scroll_mode <+ input.set_wheel_scroll_mode;
+            hit_area_size <- all_with4(&out.width, &out.height, &out.view_width, &out.view_height,
+                |width, height, view_width, view_height| {
+                    Vector2(view_width.unwrap_or(*width), view_height.unwrap_or(*height))
+                }
+            );
+            eval hit_area_size ((size) m.set_hit_area_size(*size));
+
+            wheel <- wheel.gate(&out.wheel_handling);
+            eval wheel ((event) {
+                event.prevent_default();
+                event.stop_propagation();
+            });
+            zoom <- wheel.filter(|event| event.ctrl_key());
+            scroll <- wheel.filter(|event| !event.ctrl_key());
+
+
+            // === Zoom ===
+
+            input.set_font_size <+ zoom.map(f!((event) m.wheel_zoomed_font_size(event.delta_y())));
+
+
+            // === Scroll ===
+
+            delta <- scroll.map(|event| {
+                if event.shift_key() {
+                    Vector2(event.delta_x() + event.delta_y(), 0.0)
+                } else {
+                    Vector2(event.delta_x(), event.delta_y())
+                }
+            });
+            horizontal_delta <- delta.map(|d| d.x).filter(|x| x.abs() > 0.0);
+            has_view_width <- out.view_width.map(|width| width.is_some());
+            input.horizontal_scroll_by <+ horizontal_delta.gate(&has_view_width);
+            out.horizontal_wheel_scroll <+ horizontal_delta.gate_not(&has_view_width);
+            vertical_delta <- delta.map(|d| d.y).filter(|y| y.abs() > 0.0);
+            line_diff <= vertical_delta.map2(&out.wheel_scroll_mode,
+                f!((delta, mode) m.wheel_line_diff(*delta, *mode)));
+            input.set_first_view_line <+ line_diff.filter_map(f!((d) m.scrolled_first_view_line(*d)));
+        }
+        init.emit(());
     }
 
-    fn new_line(&self) -> line::View {
-        let line = Self::new_line_helper(
-            &self.scene.frp.frame_time,
-            &self.display_object,
-            self.buffer.formatting.font_size().default.value,
-        );
-        self.init_line(&line);
-        line
+    fn init_scrolling(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+
+        frp::extend! { network
+            init <- source_();
+            line_height_changed <- any_(&init, &out.font_size, &out.line_height);
+
+            scrolled_to <- input.scroll_to.filter_map(f!((y) m.first_view_line_at(*y)));
+            input.set_first_view_line <+ scrolled_to;
+            line_diff <= input.scroll_by.map(f!((delta) m.scroll_line_diff(*delta)));
+            scrolled_by <- line_diff.filter_map(f!((d) m.scrolled_first_view_line(*d)));
+            input.set_first_view_line <+ scrolled_by;
+
+            scroll_changed <- any_(&line_height_changed, &m.buffer.frp.first_view_line);
+            out.scroll_position <+ scroll_changed.map(f_!(m.scroll_position())).on_change();
+            content_changed <- any_(&line_height_changed, &m.buffer.frp.text_change);
+            out.content_height <+ content_changed.map(f_!(m.content_height())).on_change();
+
+
+            // === Cursor Follow ===
+
+            out.cursor_follow <+ input.set_cursor_follow;
+            selections_changed <- out.selections.gate(&out.cursor_follow);
+            new_first_view_line <= selections_changed.map(
+                f_!(m.first_view_line_showing(m.cursor_line(), false))
+            );
+            input.set_first_view_line <+ new_first_view_line;
+
+
+            // === Horizontal Scrolling ===
+
+            scrolled_by_x <- input.horizontal_scroll_by.map(f!((d) m.horizontal_scroll.get() + d));
+            new_scroll_x <- any(&input.horizontal_scroll_to, &scrolled_by_x);
+            cursor_scroll_x <= selections_changed.map(f_!(m.horizontal_scroll_showing_cursor()));
+            new_scroll_x <+ cursor_scroll_x;
+            // The scroll position is limited again after the lines or the view were changed.
+            viewport_changed <- any_(&out.width, &out.view_width, &out.wrap_mode);
+            viewport_changed <- any_(&viewport_changed, &out.long_text_truncation_mode);
+            new_scroll_x <+ viewport_changed.map(f_!(m.horizontal_scroll.get()));
+            scroll_x <- new_scroll_x.map(f!((x) m.set_horizontal_scroll(*x)));
+            out.horizontal_scroll_position <+ scroll_x.on_change();
+        }
+        init.emit(());
     }
 
-    fn take_lines(&self) -> Lines {
-        let lines_vec = LinesVec::singleton(self.new_line());
-        let old_lines_vec = mem::replace(&mut *self.lines.borrow_mut(), lines_vec);
-        old_lines_vec.into()
+    fn init_log_view(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+
+        frp::extend! { network
+            out.max_line_count <+ input.set_max_line_count;
+            eval_ input.set_max_line_count (m.trim_lines());
+            eval input.append ((text) m.append(text));
+
+            out.pinned_to_bottom <+ input.set_pinned_to_bottom;
+            eval input.set_pinned_to_bottom ([m](pinned) if *pinned { m.scroll_to_bottom() });
+            out.pinned_to_bottom <+ m.buffer.frp.first_view_line.map(f_!(m.is_scrolled_to_bottom()));
+        }
     }
-}
 
+    fn init_whitespace_diagnostics(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
 
+        frp::extend! { network
+            out.whitespace_diagnostics <+ input.set_whitespace_diagnostics;
+            enabled <- out.whitespace_diagnostics.on_change();
+            // The undo and the redo replace the whole text without reporting the changes, so all
+            // lines are inspected again.
+            history_restored <- out.history_restored.gate(&out.whitespace_diagnostics);
+            reset <- any(&enabled, &history_restored.constant(true));
+            issues_reset <- reset.map(f!((enabled) m.reset_whitespace_issues(*enabled)));
+            changes <- out.changed.gate(&out.whitespace_diagnostics);
+            issues_updated <- changes.map(f!((changes) m.update_whitespace_issues(changes)));
+            issues_changed <- any(issues_reset, issues_updated).on_true();
+            eval_ issues_changed (m.redraw_whitespace_issues());
+            out.whitespace_issues <+ issues_changed.map(f_!(m.whitespace_issues()));
+        }
+    }
 
-// =============================================
-// === Screen - Text Coordinates Conversions ===
-// =============================================
+    fn init_annotations(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
 
-impl TextModel {
-    /// Transforms screen position to the object (display object) coordinate system.
-    fn screen_to_object_space(&self, screen_pos: Vector2) -> Vector2 {
-        let Some(display_layer) = self.display_layer() else { return Vector2::zero() };
-        let camera = display_layer.camera();
-        let origin_world_space = Vector4(0.0, 0.0, 0.0, 1.0);
-        let origin_clip_space = camera.view_projection_matrix() * origin_world_space;
-        let inv_object_matrix = self.transformation_matrix().try_inverse();
-        let Some(inv_object_matrix) = inv_object_matrix else { return Vector2::zero() };
-
-        let shape = self.scene.frp.shape.value();
-        let clip_space_z = origin_clip_space.z;
-        let clip_space_x = origin_clip_space.w * 2.0 * screen_pos.x / shape.width;
-        let clip_space_y = origin_clip_space.w * 2.0 * screen_pos.y / shape.height;
-        let clip_space = Vector4(clip_space_x, clip_space_y, clip_space_z, origin_clip_space.w);
-        let world_space = camera.inversed_view_projection_matrix() * clip_space;
-        (inv_object_matrix * world_space).xy()
+        frp::extend! { network
+            eval input.import_annotations ((document) m.import_annotations(document));
+            eval out.changed ((changes) m.update_annotations(changes));
+            annotations_changed <- any_(&input.import_annotations, &out.changed);
+            annotations_changed <- any_(&annotations_changed, &out.history_restored);
+            out.annotations <+ annotations_changed.map(f_!(m.annotations()));
+        }
     }
 
-    /// Transform screen position to in-text location.
-    fn screen_to_text_location(&self, screen_pos: Vector2) -> Location {
-        let object_space = self.screen_to_object_space(screen_pos);
-        let mut view_line = ViewLine(0);
-        let lines = self.lines.borrow();
-        for line in &*lines {
-            // We are adding half of the gap here, so if someone clicks between the lines, the line
-            // closer to the mouse pointer will be selected.
-            let height = line.baseline() + line.metrics().descender + line.metrics().gap / 2.0;
-            if height < object_space.y {
-                break;
-            }
-            view_line += ViewLine(1);
+    fn init_highlight_sets(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+
+        frp::extend! { network
+            eval input.set_highlight_set ((set) m.highlights.set(set.clone()));
+            eval input.clear_highlight_set ((name) m.highlights.clear(name));
+            eval_ input.clear_highlight_sets (m.highlights.clear_all());
+            eval out.changed ((changes) m.highlights.apply_changes(changes.iter()));
+            sets_changed <- any_(&input.set_highlight_set, &input.clear_highlight_set);
+            sets_changed <- any_(&sets_changed, &input.clear_highlight_sets);
+            sets_changed <- any_(&sets_changed, &out.changed, &out.history_restored);
+            eval_ sets_changed (m.redraw_highlight_sets());
+            out.highlight_sets <+ sets_changed.map(f_!(Rc::new(m.highlights.sets())));
         }
-        let view_line = std::cmp::min(view_line, self.lines.last_line_index());
-        let div_index = self.lines.borrow()[view_line].div_index_close_to(object_space.x);
-        let line = Line::from_in_context_snapped(self, view_line);
-        let column = Column(div_index);
-        let out = Location(line, column);
-        out
     }
-}
 
+    fn init_search(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+        let default_style = highlight::Style::new(SEARCH_MATCH_COLOR, 0);
+        let no_matches: Rc<Vec<buffer::Range<Byte>>> = default();
+        let no_match: Option<buffer::Range<Byte>> = None;
+        let search_set_name = ImString::new(SEARCH_HIGHLIGHT_SET);
+
+        frp::extend! { network
+            init <- source_();
+            out.search_highlight_style <+ init.constant(default_style);
+            out.search_highlight_style <+ input.set_search_highlight_style;
 
+            search_matches <- input.find_all.map(f!([m]((q, _)) Rc::new(m.buffer.find_all(q))));
+            out.search_matches <+ search_matches;
+            out.search_matches <+ input.clear_search.constant(no_matches);
+            highlight <- search_matches.map2(&input.find_all, |t, (_, p)| (t.clone(), *p));
+            highlight <- highlight.filter(|(_, p)| p.is_some());
+            m.buffer.frp.set_property <+ highlight;
+            eval highlight ([m](t) t.1.map(|p| m.set_property(&t.0, p)));
 
-// =====================
-// === Lines Shaping ===
-// =====================
 
-/// A shaped line of glyphs.
-#[allow(missing_docs)]
-#[derive(Debug)]
-pub enum ShapedLine {
-    NonEmpty {
-        glyph_sets: NonEmptyVec<ShapedGlyphSet>,
-    },
-    Empty {
-        /// Shaped newline character ending the previous line if any. It is used to calculate the
-        /// line style (e.g. its height) for empty lines.
-        prev_glyph_info: Option<(Byte, ShapedGlyphSet)>,
-    },
-}
+            // === Matches Highlight ===
 
-impl ShapedLine {
-    /// Number of glyphs in line.
-    pub fn glyph_count(&self) -> usize {
-        match self {
-            Self::NonEmpty { glyph_sets } => glyph_sets.iter().map(|set| set.glyphs.len()).sum(),
-            Self::Empty { .. } => 0,
+            matches_set <- search_matches.map2(&out.search_highlight_style, |ranges, style| {
+                highlight::Set::new(SEARCH_HIGHLIGHT_SET, ranges.to_vec(), *style)
+            });
+            restyled_set <- out.search_highlight_style.map2(&out.search_matches, |style, ranges| {
+                highlight::Set::new(SEARCH_HIGHLIGHT_SET, ranges.to_vec(), *style)
+            });
+            restyled_set <- restyled_set.filter(|set| !set.ranges.is_empty());
+            input.set_highlight_set <+ any(matches_set, restyled_set);
+            input.clear_highlight_set <+ input.clear_search.constant(search_set_name);
+
+
+            // === Jumping Between Matches ===
+
+            last_search <- input.find_all.sampler();
+            query <- input.find_all._0().sampler();
+            next_match <- query.sample(&input.find_next).map(f!((q) m.next_search_match(q)));
+            prev_match <- query.sample(&input.find_prev).map(f!((q) m.prev_search_match(q)));
+            current_match <- any(next_match, prev_match);
+            out.current_match <+ current_match;
+            out.current_match <+ input.clear_search.constant(no_match);
+            found_match <- current_match.filter_map(|t| *t);
+            input.select <+ found_match.map(|range| {
+                (LocationLike::from(range.start), LocationLike::from(range.end))
+            });
+            new_first_view_line <= found_match.map(
+                f!((range) m.first_view_line_showing(m.buffer.line_snapped(range.start), false))
+            );
+            m.buffer.frp.set_first_view_line <+ new_first_view_line;
+
+
+            // === Replacing Matches ===
+
+            replace_next <- input.replace_next.gate(&out.editable);
+            replace_all <- input.replace_all.gate(&out.editable);
+            replaced_next <- replace_next.map2(&query,
+                f!((text, query) m.replace_selected_match(query, text))
+            );
+            replaced_all <- replace_all.map2(&query,
+                f!((text, query) m.replace_all_matches(query, text))
+            );
+            out.replacements_made <+ any(replaced_next, replaced_all);
+            // The matches are searched again, as the replacement text can contain new matches.
+            any_replaced <- out.replacements_made.filter(|count| *count > 0);
+            input.find_all <+ last_search.sample(&any_replaced);
+            input.find_next <+ replaced_next.constant(());
         }
+        init.emit(());
     }
-}
 
-/// A shaped set of glyphs.
-#[allow(missing_docs)]
-#[derive(Debug)]
-pub struct ShapedGlyphSet {
-    pub units_per_em:            u16,
-    pub ascender:                i16,
-    pub descender:               i16,
-    pub line_gap:                i16,
-    pub non_variable_variations: NonVariableFaceHeader,
-    /// Please note that shaped glyphs in this set have cumulative offsets. This means that even if
-    /// they were produced by separate calls to `rustybuzz::shape`, their `info.cluster` is summed
-    /// between the calls. For example, if there are two regular glyphs and two bold glyphs, the
-    /// `rustybuzz::shape` function will be called twice, and thus, the third buffer's
-    /// `info.cluster` will be zero. This is fixed here and the third buffer's `info.cluster` will
-    /// be the byte size of first two glyphs.
-    pub glyphs:                  Vec<ShapedGlyph>,
-}
+    fn init_bracket_matching(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+        let default_style = highlight::Style::new(MATCHING_BRACKET_COLOR, 0);
+        let brackets_set_name = ImString::new(BRACKET_HIGHLIGHT_SET);
 
-/// A shaped glyph description. See the [`rustybuzz`] library to learn more about data stored in
-/// this struct.
-#[allow(missing_docs)]
-#[derive(Debug, Clone, Copy)]
-pub struct ShapedGlyph {
-    pub position:    rustybuzz::GlyphPosition,
-    pub info:        rustybuzz::GlyphInfo,
-    pub render_info: GlyphRenderInfo,
-}
+        frp::extend! { network
+            init <- source_();
+            out.bracket_highlight_style <+ init.constant(default_style);
+            out.bracket_highlight_style <+ input.set_bracket_highlight_style;
+            out.matching_brackets <+ m.buffer.frp.matching_brackets.on_change();
+
+            brackets_set <- all_with(&out.matching_brackets, &out.bracket_highlight_style,
+                |pair, style| pair.map(|t| {
+                    highlight::Set::new(BRACKET_HIGHLIGHT_SET, t.ranges().to_vec(), *style)
+                })
+            );
+            input.set_highlight_set <+ brackets_set.filter_map(|t| t.clone());
+            no_brackets <- out.matching_brackets.filter(|t| t.is_none());
+            input.clear_highlight_set <+ no_brackets.constant(brackets_set_name);
 
-impl ShapedGlyph {
-    /// Returns the byte start of this glyph.
-    pub fn start_byte(&self) -> Byte {
-        Byte(self.info.cluster as usize)
+            jump_target <= input.jump_to_matching_bracket.map(
+                f_!(m.buffer.matching_bracket_jump_target())
+            );
+            m.buffer.frp.set_cursor <+ jump_target;
+        }
+        init.emit(());
     }
 
-    /// The glyph id, index of glyph in the font.
-    pub fn id(&self) -> GlyphId {
-        GlyphId(self.info.glyph_id as u16)
-    }
-}
+    fn init_tabs(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
 
-impl TextModel {
-    /// Clear the cache of all shaped lines. Use with caution, this will cause all required lines
-    /// to be reshaped.
-    pub fn clear_shaped_lines_cache(&self) {
-        mem::take(&mut *self.shaped_lines.borrow_mut());
+        frp::extend! { network
+            init <- source_();
+            out.tab_size <+ init.constant(DEFAULT_TAB_SIZE);
+            out.tab_size <+ input.set_tab_size.map(|size| (*size).max(1));
+            eval_ out.tab_size (m.redraw());
+            out.insert_spaces_for_tab <+ input.set_insert_spaces_for_tab;
+
+            insert_tab <- input.insert_tab.gate(&out.editable);
+            selections_span_lines <- insert_tab.map(f_!(m.selections_span_lines()));
+            m.buffer.frp.indent_selection <+ selections_span_lines.filter(|t| *t).constant(());
+            insert_tab_text <- selections_span_lines.filter(|t| !*t);
+            tab_insertions <- insert_tab_text.map2(&out.insert_spaces_for_tab,
+                f!((_, spaces) m.tab_insertions(*spaces))
+            );
+            m.buffer.frp.paste <+ tab_insertions;
+        }
+        init.emit(());
     }
 
-    /// Clear the shaped lines cache for the provided line index.
-    pub fn clear_shaped_lines_cache_for_line(&self, line: Line) {
-        self.shaped_lines.borrow_mut().remove(&line);
+    fn init_token_styles(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+        let style = &m.style_watch;
+        let is_enabled = |data: &Option<ensogl_core::display::style::Data>| {
+            data.number().map_or(false, |number| number != 0.0)
+        };
+
+        frp::extend! { network
+            init <- source_();
+            theme_palette_changed <- any_mut::<()>();
+        }
+        for kind in token_style::kind::ALL {
+            let color_theme = style.get(token_style::theme_path(kind, "color"));
+            let bold_theme = style.get(token_style::theme_path(kind, "bold"));
+            let italic_theme = style.get(token_style::theme_path(kind, "italic"));
+            frp::extend! { network
+                format <- all_with4(&color_theme, &bold_theme, &italic_theme, &init,
+                    move |color, bold, italic, _| {
+                        token_style::format_from_theme(color.color(), is_enabled(bold),
+                            is_enabled(italic))
+                    }
+                );
+                eval format ((format) m.set_theme_token_format(kind, *format));
+                theme_palette_changed <+ format.constant(());
+            }
+        }
+        frp::extend! { network
+            out.token_palette <+ all_with(&theme_palette_changed, &input.set_token_palette,
+                f!((_, palette) m.theme_token_palette.borrow().overridden_by(palette)));
+
+            eval input.set_tokens ((tokens) m.tokens.borrow_mut().set_tokens((**tokens).clone()));
+            eval out.changed ((changes) m.move_tokens(changes));
+            restyle <- any_(&input.set_tokens, &out.token_palette);
+            token_properties <= restyle.map2(&out.token_palette, f!((_, p) m.restyle_tokens(p)));
+            m.buffer.frp.set_property <+ token_properties;
+            eval token_properties (((ranges, p)) p.map(|p| m.set_property(ranges, p)));
+        }
+        init.emit(());
     }
 
-    /// Run the closure with the shaped line. If the line was not in the shaped lines cache, it will
-    /// be first re-shaped.
-    pub fn with_shaped_line<T>(&self, line: Line, mut f: impl FnMut(&ShapedLine) -> T) -> T {
-        let mut shaped_lines = self.shaped_lines.borrow_mut();
-        if let Some(shaped_line) = shaped_lines.get(&line) {
-            f(shaped_line)
-        } else {
-            let shaped_line = self.shape_line(line);
-            let out = f(&shaped_line);
-            shaped_lines.insert(line, shaped_line);
-            out
+    fn init_editing_state(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+
+        frp::extend! { network
+            // The view configuration is restored first, as the view height limits the scrolling.
+            state <- input.restore_editing_state;
+            input.set_view_width <+ state.map(|s| s.view.view_width);
+            input.set_view_height <+ state.map(|s| s.view.view_height);
+            input.set_long_text_truncation_mode <+ state.map(|s| s.view.long_text_truncation_mode);
+            input.set_single_line_mode <+ state.map(|s| s.view.single_line_mode);
+            input.set_editable <+ state.map(|s| s.view.editable);
+            input.set_wheel_scroll_mode <+ state.map(|s| s.view.wheel_scroll_mode);
+            input.set_wrap_mode <+ state.map(|s| s.view.wrap_mode);
+            m.buffer.frp.set_selections <+ state.map(|s| s.selections.clone());
+            first_view_line <- state.map(f!((s) s.first_view_line.min(m.buffer.last_line_index())));
+            input.set_first_view_line <+ first_view_line;
         }
     }
 
-    /// Recompute the shape of the provided byte range.
-    fn shape_range(&self, range: Range<Byte>) -> Vec<ShapedGlyphSet> {
-        let line_style = self.buffer.sub_style(range.clone());
-        let rope = self.buffer.rope.sub(range);
-        let content = rope.to_string();
-        let glyph_system = self.glyph_system.borrow();
-        let font = &glyph_system.font;
-        let mut glyph_sets = vec![];
-        let mut prev_chunk_cluster_byte_offset = 0;
+    fn init_dom_mirror(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+
+        frp::extend! { network
+            eval input.set_dom_mirror ((mode) m.set_dom_mirror_mode(*mode));
+            eval m.buffer.frp.text_change ((changes) m.update_dom_mirror(changes));
+            // The undo, the redo, and the changes made by other views replace the whole text, so
+            // it is mirrored again.
+            history_restored <- any_(&m.buffer.frp.input.undo, &m.buffer.frp.input.redo);
+            snapshot_restored <- any_(&m.buffer.frp.input.restore_snapshot, &history_restored);
+            mirror_reset <- any_(&snapshot_restored, &m.buffer.frp.remote_text_change);
+            eval_ mirror_reset (m.reset_dom_mirror());
+
+            size <- all_with4(&out.width, &out.height, &out.view_width, &out.view_height,
+                |w, h, view_w, view_h| Vector2(view_w.unwrap_or(*w), view_h.unwrap_or(*h))
+            );
+            view_changed <- any_(&input.set_dom_mirror, &out.font_size, &out.line_height);
+            view_changed <- any_(&view_changed, &m.buffer.frp.first_view_line);
+            layout_changed <- any_(&view_changed, &size);
+            layout <- layout_changed.map2(&size, f!((_, size) m.dom_mirror_layout(*size)));
+            eval layout ((layout) m.set_dom_mirror_layout(*layout));
+        }
+    }
+
+    fn init_pagination(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+
+        frp::extend! { network
+            page <- input.show_page;
+            input.set_view_height <+ page.map(|page| Some(page.height));
+            // The view height is measured in the default line heights, so the number of lines is
+            // set explicitly to match the measured heights of the lines of the page.
+            line_count <- page.map(|page| page.lines.end.value - page.lines.start.value);
+            m.buffer.frp.set_view_line_count <+ line_count.map(|count| Some(*count));
+            input.set_first_view_line <+ page.map(|page| page.lines.start);
+            eval_ page (m.redraw());
+        }
+    }
+
+    fn init_navigation(&self) {
+        let m = &self.data;
+        let network = self.frp.network();
+        let input = &self.frp.input;
+        let out = &self.frp.private.output;
+        let highlight = Animation::<f32>::new(network);
+        let bookmark = gutter::MarkerKind::Bookmark;
+
+        frp::extend! { network
+
+            // === Bookmarks ===
+
+            input.toggle_line_marker <+ input.toggle_bookmark.map(move |line| (*line, bookmark));
+            next_bookmark <= input.next_bookmark.map(
+                f_!(m.gutter.markers.borrow().next_line_with(bookmark, m.cursor_line()))
+            );
+            prev_bookmark <= input.prev_bookmark.map(
+                f_!(m.gutter.markers.borrow().prev_line_with(bookmark, m.cursor_line()))
+            );
+            bookmark_line <- any(next_bookmark, prev_bookmark);
+            input.go_to_line <+ bookmark_line.map(|line| (*line, Column(0)));
+
+
+            // === Go To Line ===
+
+            out.go_to_line_centered <+ input.set_go_to_line_centered;
+            go_to_location <- input.go_to_line.map(
+                f!([m]((line, column)) Location(std::cmp::min(*line, m.buffer.last_line_index()), *column))
+            );
+            m.buffer.frp.set_cursor <+ go_to_location;
+            new_first_view_line <= go_to_location.map2(&out.go_to_line_centered,
+                f!((location, centered) m.first_view_line_showing(location.line, *centered))
+            );
+            m.buffer.frp.set_first_view_line <+ new_first_view_line;
+
+
+            // === Highlight Pulse ===
+
+            eval go_to_location ((location) m.place_go_to_line_highlight(location.line));
+            highlight.set_value <+ go_to_location.constant(1.0);
+            highlight.target <+ go_to_location.constant(0.0);
+            eval highlight.value ((alpha) m.set_go_to_line_highlight_alpha(*alpha));
+        }
+    }
+}
+
+
+
+// =================
+// === TextModel ===
+// =================
+
+/// Internal representation of `Text`.
+#[derive(Clone, CloneRef, Debug, Deref, display::Object)]
+pub struct TextModel {
+    rc: Rc<TextModelData>,
+}
+
+/// Internal representation of `Text`.
+#[derive(Debug, display::Object)]
+pub struct TextModelData {
+    buffer:                  buffer::Buffer,
+    scene:                   display::Scene,
+    frp:                     WeakFrp,
+    display_object:          display::object::Instance,
+    /// The parent of the lines, the selections, and the decorations scrolled horizontally with
+    /// the text. The gutter is not scrolled.
+    content:                 display::object::Instance,
+    glyph_system:            RefCell<glyph::System>,
+    /// Glyph systems of font families other than the default one, used by the text ranges with
+    /// the [`formatting::FontFamily`] property set.
+    family_glyph_systems:    RefCell<HashMap<font::Name, glyph::System>>,
+    lines:                   Lines,
+    gutter:                  gutter::Gutter,
+    line_highlight:          Rectangle,
+    /// The transparent shape covering the text area, receiving the mouse wheel events. See
+    /// [`Input::set_wheel_handling`].
+    hit_area:                Rectangle,
+    line_highlight_color:    Cell<color::Rgba>,
+    /// The line height multiplier, see [`Input::set_line_height`].
+    line_height:             Cell<f32>,
+    whitespace:              whitespace::View,
+    annotations:             annotation::View,
+    highlights:              highlight::View,
+    selection_map:           RefCell<SelectionMap>,
+    disabled:                Cell<bool>,
+    /// The vertical scroll distance not consumed by scrolling by whole lines, see
+    /// [`WheelScrollMode::Pixels`] and [`Input::scroll_by`].
+    wheel_delta_remainder:   Cell<f32>,
+    /// The horizontal scroll position, see [`Output::horizontal_scroll_position`].
+    horizontal_scroll:       Cell<f32>,
+    multi_click:             RefCell<MultiClick>,
+    /// The selection pressed with the mouse, see [`Input::start_selection_drag`].
+    selection_drag:          RefCell<Option<SelectionDrag>>,
+    /// The caret showing where the dragged selection will be dropped, created on the first drag.
+    drop_caret:              RefCell<Option<Selection>>,
+    style_watch:             StyleWatchFrp,
+    width_dirty:             Cell<bool>,
+    height_dirty:            Cell<bool>,
+    /// Cache of shaped lines.
+    shaped_lines:            RefCell<BTreeMap<Line, ShapedLine>>,
+    /// Whether the shaping of lines below the visible area is queued, see
+    /// [`TextModel::schedule_shaping_prefetch`].
+    shaping_prefetch_queued: Cell<bool>,
+    /// The hyphenation of the wrapped lines, see [`Input::set_hyphenation_language`].
+    hyphenation:             RefCell<hyphenation::Hyphenation>,
+    /// Shaping results shared with other text areas of the scene.
+    shape_cache:             ShapeCache,
+    time_travel_handle:      callback::Handle,
+    /// The accessibility node, created when the text area is given an accessible label.
+    accessibility:           RefCell<Option<accessibility::Node>>,
+    /// The syntax highlighting tokens, see [`Input::set_tokens`].
+    tokens:                  RefCell<token_style::Styled>,
+    /// The formats of the well-known token kinds defined by the theme.
+    theme_token_palette:     RefCell<token_style::Palette>,
+    /// The widths and the other statistics of all lines, see [`Output::max_line_width`] and
+    /// [`Output::char_count`].
+    line_widths:             RefCell<LineWidthIndex>,
+    /// The DOM elements mirroring the text, see [`Input::set_dom_mirror`].
+    dom_mirror:              RefCell<Option<dom_mirror::Mirror>>,
+    layer_promotion:         layer::Promotion,
+}
+
+impl TextModel {
+    /// Constructor.
+    fn new(scene: display::Scene, frp: &Frp, buffer: buffer::BufferModel) -> Self {
+        let selection_map = default();
+        let display_object = display::object::Instance::new_named("Text");
+        let content = display::object::Instance::new_named("Content");
+        display_object.add_child(&content);
+        let glyph_system = font::glyph::System::new(&scene, font::DEFAULT_CODE_FONT);
+        frp.private.output.glyph_system.emit(Some(glyph_system.clone()));
+        let glyph_system = RefCell::new(glyph_system);
+        let family_glyph_systems = default();
+        let buffer = buffer::Buffer::new(buffer);
+
+        let default_size = buffer.formatting.font_size().default.value;
+        let first_line = Self::new_line_helper(&scene.frp.frame_time, &content, default_size);
+        first_line.set_baseline((-default_size).round());
+        first_line.skip_baseline_animation();
+
+        let lines = Lines::new(first_line);
+        let gutter = gutter::Gutter::new(&scene);
+        gutter.set_line_number_font_size(default_size);
+        display_object.add_child(&gutter);
+        let line_highlight = Rectangle().build(|r| {
+            r.set_pointer_events(false);
+            r.set_color(color::Rgba::transparent());
+        });
+        content.add_child(&line_highlight);
+        let hit_area = Rectangle().build(|r| {
+            r.set_color(INVISIBLE_HOVER_COLOR);
+        });
+        display_object.add_child(&hit_area);
+        let line_highlight_color = Cell::new(GO_TO_LINE_HIGHLIGHT_COLOR);
+        let line_height = Cell::new(1.0);
+        let whitespace = whitespace::View::new();
+        content.add_child(&whitespace);
+        let annotations = annotation::View::new();
+        content.add_child(&annotations);
+        let highlights = highlight::View::new();
+        content.add_child(&highlights);
+        let disabled = default();
+        let wheel_delta_remainder = default();
+        let horizontal_scroll = default();
+        let multi_click = default();
+        let selection_drag = default();
+        let drop_caret = default();
+        let style_watch = StyleWatchFrp::new(&scene.style_sheet);
+        let width_dirty = default();
+        let height_dirty = default();
+        let shaped_lines = default();
+        let shaping_prefetch_queued = default();
+        let hyphenation = default();
+        let shape_cache = scene.extension::<ShapeCache>();
+        let time_travel_handle = Self::init_time_travel(&scene, &buffer);
+        let accessibility = default();
+        let tokens = default();
+        let theme_token_palette = default();
+        let line_widths = default();
+        let dom_mirror = default();
+        let layer_promotion = layer::Promotion::new(&display_object);
+
+        let frp = frp.downgrade();
+        let data = TextModelData {
+            scene,
+            frp,
+            buffer,
+            display_object,
+            content,
+            glyph_system,
+            family_glyph_systems,
+            lines,
+            gutter,
+            line_highlight,
+            hit_area,
+            line_highlight_color,
+            line_height,
+            whitespace,
+            annotations,
+            highlights,
+            selection_map,
+            disabled,
+            wheel_delta_remainder,
+            horizontal_scroll,
+            multi_click,
+            selection_drag,
+            drop_caret,
+            style_watch,
+            width_dirty,
+            height_dirty,
+            shaped_lines,
+            shaping_prefetch_queued,
+            hyphenation,
+            shape_cache,
+            time_travel_handle,
+            accessibility,
+            tokens,
+            theme_token_palette,
+            line_widths,
+            dom_mirror,
+            layer_promotion,
+        };
+        Self { rc: Rc::new(data) }.init()
+    }
+
+    /// Capture the buffer state in the time-travel debugging snapshots.
+    fn init_time_travel(scene: &display::Scene, buffer: &buffer::Buffer) -> callback::Handle {
+        let time_travel = scene.extension::<TimeTravel>();
+        let buffer = buffer.clone_ref();
+        time_travel.on_capture(move |snapshot| {
+            let buffer_snapshot = buffer.snapshot();
+            let frp = buffer.frp.downgrade();
+            snapshot.add(move || {
+                if let Some(frp) = frp.upgrade() {
+                    frp.restore_snapshot(buffer_snapshot.clone());
+                }
+            });
+        })
+    }
+
+    #[profile(Debug)]
+    fn init(self) -> Self {
+        self.init_line(self.lines.borrow().first());
+        self
+    }
+
+    /// The text buffer displayed by this text area.
+    pub fn buffer(&self) -> &buffer::Buffer {
+        &self.buffer
+    }
+
+    fn init_line(&self, line: &line::View) {
+        let network = line.frp.network();
+        frp::extend! { network
+            self.frp.private.output.refresh_height <+_ line.descent;
+        }
+    }
+
+    /// Helper for newline creation. It is used to create a new line during model initialization,
+    /// and thus, it can't be a method.
+    fn new_line_helper(
+        frame_time: &enso_frp::Stream<f32>,
+        display_object: &display::object::Instance,
+        default_size: f32,
+    ) -> line::View {
+        let line = line::View::new(frame_time);
+        let ascender = default_size;
+        let descender = ascender * DEFAULT_ASCENDER_TO_DESCENDER_RATIO;
+        let gap = 0.0;
+        let metrics = line::Metrics { ascender, descender, gap };
+        line.set_metrics(metrics);
+        display_object.add_child(&line);
+        line
+    }
+
+    fn new_line(&self) -> line::View {
+        let line = Self::new_line_helper(
+            &self.scene.frp.frame_time,
+            &self.content,
+            self.buffer.formatting.font_size().default.value,
+        );
+        self.init_line(&line);
+        line
+    }
+
+    fn take_lines(&self) -> Lines {
+        let lines_vec = LinesVec::singleton(self.new_line());
+        let old_lines_vec = mem::replace(&mut *self.lines.borrow_mut(), lines_vec);
+        old_lines_vec.into()
+    }
+}
+
+
+
+// =============================================
+// === Screen - Text Coordinates Conversions ===
+// =============================================
+
+impl TextModel {
+    /// Transforms screen position to the object (display object) coordinate system.
+    fn screen_to_object_space(&self, screen_pos: Vector2) -> Vector2 {
+        if self.display_layer().is_none() {
+            return Vector2::zero();
+        }
+        self.scene.screen_to_object_space(self, screen_pos)
+    }
+
+    /// Transform screen position to in-text location.
+    fn screen_to_text_location(&self, screen_pos: Vector2) -> Location {
+        let object_space = self.screen_to_object_space(screen_pos);
+        let object_space = object_space + Vector2(self.horizontal_scroll.get(), 0.0);
+        let mut view_line = ViewLine(0);
+        let lines = self.lines.borrow();
+        for line in &*lines {
+            // We are adding half of the gap here, so if someone clicks between the lines, the line
+            // closer to the mouse pointer will be selected.
+            let descender = line.metrics().descender - line.wrapped_height();
+            let height = line.baseline() + descender + line.metrics().gap / 2.0;
+            if height < object_space.y {
+                break;
+            }
+            view_line += ViewLine(1);
+        }
+        let view_line = std::cmp::min(view_line, self.lines.last_line_index());
+        let line_view = &lines[view_line];
+        let column = line_view.column_close_to(object_space - Vector2(0.0, line_view.baseline()));
+        let line = Line::from_in_context_snapped(self, view_line);
+        let out = Location(line, column);
+        out
+    }
+
+    /// The bottom-left corner and the size of the in-line byte range, if the line is visible.
+    fn line_range_rect(
+        &self,
+        lines: &LinesVec,
+        line: Line,
+        range: std::ops::Range<Byte>,
+    ) -> Option<(Vector2, Vector2)> {
+        let view_line = ViewLine::try_from_in_context(&self.buffer, line).ok()?;
+        let line_view = lines.get(view_line)?;
+        let column = |offset: Byte| {
+            let location = Location(line, offset);
+            Location::<Column, Line>::from_in_context_snapped(&self.buffer, location).offset
+        };
+        let start = line_view.position_of_column(column(range.start));
+        let end = line_view.position_of_column(column(range.end));
+        let start_x = start.x;
+        let end_x = if end.y == start.y {
+            end.x
+        } else {
+            line_view.row_end_x(line_view.rows.row_of_column(column(range.start)))
+        };
+        let metrics = line_view.metrics();
+        let position = Vector2(start_x, line_view.baseline() + start.y + metrics.descender);
+        let size = Vector2(end_x - start_x, metrics.ascender - metrics.descender);
+        Some((position, size))
+    }
+
+    /// The rectangles covering the byte range in the visible lines, one per line.
+    fn range_rects(&self, range: buffer::Range<Byte>) -> Vec<(Vector2, Vector2)> {
+        let lines = self.lines.borrow();
+        let start_line = self.buffer.line_snapped(range.start).max(self.buffer.first_view_line());
+        let end_line = self.buffer.line_snapped(range.end).min(self.buffer.last_view_line());
+        let line_rects = (start_line.value..=end_line.value).map(Line).filter_map(|line| {
+            let line_start = self.buffer.line_offset_snapped(line);
+            let line_end = self.buffer.line_end_offset_snapped(line);
+            let start = std::cmp::max(range.start, line_start);
+            let end = std::cmp::min(range.end, line_end);
+            let in_line_range =
+                Byte(start.value - line_start.value)..Byte(end.value - line_start.value);
+            (end > start).then(|| self.line_range_rect(&lines, line, in_line_range)).flatten()
+        });
+        line_rects.collect()
+    }
+}
+
+
+
+// =====================
+// === Lines Shaping ===
+// =====================
+
+/// A shaped line of glyphs.
+#[allow(missing_docs)]
+#[derive(Debug)]
+pub enum ShapedLine {
+    NonEmpty {
+        glyph_sets: NonEmptyVec<ShapedGlyphSet>,
+    },
+    Empty {
+        /// Shaped newline character ending the previous line if any. It is used to calculate the
+        /// line style (e.g. its height) for empty lines.
+        prev_glyph_info: Option<(Byte, ShapedGlyphSet)>,
+    },
+}
+
+impl ShapedLine {
+    /// Number of glyphs in line.
+    pub fn glyph_count(&self) -> usize {
+        match self {
+            Self::NonEmpty { glyph_sets } => glyph_sets.iter().map(|set| set.glyphs.len()).sum(),
+            Self::Empty { .. } => 0,
+        }
+    }
+}
+
+/// A shaped set of glyphs.
+#[allow(missing_docs)]
+#[derive(Clone, Debug)]
+pub struct ShapedGlyphSet {
+    pub units_per_em:            u16,
+    pub ascender:                i16,
+    pub descender:               i16,
+    pub line_gap:                i16,
+    pub non_variable_variations: NonVariableFaceHeader,
+    pub font_family:             formatting::FontFamily,
+    /// The advance of the space glyph, in font units. The tab stops are multiples of it.
+    pub space_advance:           u16,
+    /// The hyphen glyph of the font, if it has one. See [`hyphenation::HYPHEN`].
+    pub hyphen:                  Option<ShapedHyphen>,
+    /// Please note that shaped glyphs in this set have cumulative offsets. This means that even if
+    /// they were produced by separate calls to `rustybuzz::shape`, their `info.cluster` is summed
+    /// between the calls. For example, if there are two regular glyphs and two bold glyphs, the
+    /// `rustybuzz::shape` function will be called twice, and thus, the third buffer's
+    /// `info.cluster` will be zero. This is fixed here and the third buffer's `info.cluster` will
+    /// be the byte size of first two glyphs.
+    pub glyphs:                  Vec<ShapedGlyph>,
+}
+
+/// A shaped glyph description. See the [`rustybuzz`] library to learn more about data stored in
+/// this struct.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy)]
+pub struct ShapedGlyph {
+    pub position:    rustybuzz::GlyphPosition,
+    pub info:        rustybuzz::GlyphInfo,
+    pub render_info: GlyphRenderInfo,
+}
+
+/// The hyphen glyph of a font, rendered at the end of a row ending with a word broken by the
+/// hyphenation.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy)]
+pub struct ShapedHyphen {
+    pub id:          GlyphId,
+    /// The advance of the hyphen glyph, in font units.
+    pub x_advance:   u16,
+    pub render_info: GlyphRenderInfo,
+}
+
+impl ShapedGlyph {
+    /// Returns the byte start of this glyph.
+    pub fn start_byte(&self) -> Byte {
+        Byte(self.info.cluster as usize)
+    }
+
+    /// The glyph id, index of glyph in the font.
+    pub fn id(&self) -> GlyphId {
+        GlyphId(self.info.glyph_id as u16)
+    }
+}
+
+impl TextModel {
+    /// Clear the cache of all shaped lines. Use with caution, this will cause all required lines
+    /// to be reshaped.
+    pub fn clear_shaped_lines_cache(&self) {
+        mem::take(&mut *self.shaped_lines.borrow_mut());
+    }
+
+    /// Clear the shaped lines cache for the provided line index.
+    pub fn clear_shaped_lines_cache_for_line(&self, line: Line) {
+        self.shaped_lines.borrow_mut().remove(&line);
+    }
+
+    /// Run the closure with the shaped line. If the line was not in the shaped lines cache, it will
+    /// be first re-shaped.
+    pub fn with_shaped_line<T>(&self, line: Line, mut f: impl FnMut(&ShapedLine) -> T) -> T {
+        let mut shaped_lines = self.shaped_lines.borrow_mut();
+        if let Some(shaped_line) = shaped_lines.get(&line) {
+            f(shaped_line)
+        } else {
+            let shaped_line = self.shape_line(line);
+            let out = f(&shaped_line);
+            shaped_lines.insert(line, shaped_line);
+            out
+        }
+    }
+
+    /// Shape the lines just below the visible area in the idle time, one line per idle task. Lines
+    /// which are already in the shaped lines cache are skipped. At most one task is queued at a
+    /// time, so redrawing the text repeatedly, for example when scrolling, does not grow the idle
+    /// queue. The prefetch is disabled under the reduced quality, and it is deferred until the text
+    /// area is shown, so text areas which are hidden or scrolled out of the viewport do not shape
+    /// lines nobody sees.
+    fn schedule_shaping_prefetch(&self) {
+        if quality::is_reduced() || self.shaping_prefetch_queued.replace(true) {
+            return;
+        }
+        let weak_model = Rc::downgrade(&self.rc);
+        self.display_object.init_when_shown(move || {
+            idle::schedule_idle_with_priority(idle::Priority::Low, move || {
+                if let Some(rc) = weak_model.upgrade() {
+                    let model = TextModel { rc };
+                    model.shaping_prefetch_queued.set(false);
+                    if model.prefetch_next_shaped_line() {
+                        model.schedule_shaping_prefetch();
+                    }
+                }
+            });
+        });
+    }
+
+    /// Shape the first of the lines below the visible area which is not in the shaped lines cache.
+    /// Returns [`false`] if all of them were already shaped.
+    fn prefetch_next_shaped_line(&self) -> bool {
+        let first_line = self.buffer.last_view_line().value + 1;
+        let last_line = self.buffer.last_line_index();
+        let mut lines = (first_line..).take(SHAPING_PREFETCH_LINE_COUNT).map(Line);
+        let is_missing = |line: &Line| !self.shaped_lines.borrow().contains_key(line);
+        let missing = lines.find(|line| *line <= last_line && is_missing(line));
+        missing.map(|line| self.with_shaped_line(line, |_| {})).is_some()
+    }
+
+    /// Recompute the shape of the provided byte range.
+    fn shape_range(&self, range: Range<Byte>) -> Vec<ShapedGlyphSet> {
+        let font_features = self.frp.output.font_features.value();
+        let line_style = self.buffer.sub_style(range.clone());
+        let rope = self.buffer.rope.sub(range);
+        let content = rope.to_string();
+        let mut glyph_sets = vec![];
+        let mut prev_chunk_cluster_byte_offset = 0;
         let mut grapheme_byte_offset = Byte(0);
-        for (range, requested_non_variable_variations) in
-            Self::chunks_per_font_face(font, &line_style, &rope)
-        {
+        for chunk in self.chunks_per_font(&line_style, &rope) {
+            let requested_non_variable_variations = chunk.non_variable_variations;
+            let FontChunk { range, font_family, glyph_system, .. } = chunk;
+            let font = &glyph_system.font;
             let non_variable_variations_match =
                 font.closest_non_variable_variations_or_panic(requested_non_variable_variations);
             let non_variable_variations = non_variable_variations_match.variations;
@@ -975,1090 +2910,4131 @@ impl TextModel {
                     requested_non_variable_variations, non_variable_variations
                 );
             }
-            font.with_borrowed_face(non_variable_variations, |face| {
-                let ttf_face = face.ttf.as_face_ref();
-                let units_per_em = ttf_face.units_per_em();
-                let ascender = ttf_face.ascender();
-                let descender = ttf_face.descender();
-                let line_gap = ttf_face.line_gap();
-                // This is safe. Unwrap should be removed after rustybuzz is fixed:
-                // https://github.com/RazrFalcon/rustybuzz/issues/52
-                let buzz_face = rustybuzz::Face::from_face(ttf_face.clone()).unwrap();
-                let mut buffer = rustybuzz::UnicodeBuffer::new();
-                buffer.push_str(&content[range.start.value..range.end.value]);
-                let features = font.feature_settings();
-                let shaped = rustybuzz::shape(&buzz_face, features, buffer);
-                let variable_variations = default();
-                let glyphs = shaped
-                    .glyph_positions()
-                    .iter()
-                    .zip(shaped.glyph_infos())
-                    .filter_map(|(&position, &info)| {
-                        let mut info = info;
-                        // TODO: Add support for variable fonts here.
-                        // let variable_variations = glyph.variations.borrow();
-                        let glyph_id = GlyphId(info.glyph_id as u16);
-                        let render_info = font.glyph_info_of_known_face(
-                            non_variable_variations,
-                            &variable_variations,
-                            glyph_id,
-                            face,
-                        );
-                        info.cluster += prev_chunk_cluster_byte_offset;
-                        if Byte(info.cluster as usize) < grapheme_byte_offset {
-                            // This glyph is part of the previous grapheme cluster. This is caused
-                            // by font not supporting displaying this grapheme cluster. We will not
-                            // display it.
-                            None
-                        } else {
-                            match rope.next_grapheme_offset(grapheme_byte_offset) {
-                                None => error!("Misaligned grapheme cluster boundary."),
-                                Some(next_grapheme_byte_offset) => {
-                                    grapheme_byte_offset = next_grapheme_byte_offset;
-                                }
-                            }
-                            Some(ShapedGlyph { position, info, render_info })
-                        }
-                    })
-                    .collect();
-                let shaped_glyph_set = ShapedGlyphSet {
-                    units_per_em,
-                    ascender,
-                    descender,
-                    line_gap,
-                    non_variable_variations,
-                    glyphs,
-                };
-                glyph_sets.push(shaped_glyph_set);
+            let variable_variations = font.variable_variations(non_variable_variations);
+            font.with_borrowed_face(non_variable_variations, |face| {
+                let ttf_face = face.ttf.as_face_ref();
+                let units_per_em = ttf_face.units_per_em();
+                let ascender = ttf_face.ascender();
+                let descender = ttf_face.descender();
+                let line_gap = ttf_face.line_gap();
+                let space_glyph = ttf_face.glyph_index(' ');
+                let space_advance = space_glyph.and_then(|t| ttf_face.glyph_hor_advance(t));
+                let space_advance = space_advance.unwrap_or_default();
+                let hyphen_char = hyphenation::HYPHEN.chars().next();
+                let hyphen = hyphen_char.and_then(|t| ttf_face.glyph_index(t)).map(|id| {
+                    let x_advance = ttf_face.glyph_hor_advance(id).unwrap_or_default();
+                    let render_info = font.glyph_info_of_known_face(
+                        non_variable_variations,
+                        &variable_variations,
+                        id,
+                        face,
+                    );
+                    ShapedHyphen { id, x_advance, render_info }
+                });
+                // This is safe. Unwrap should be removed after rustybuzz is fixed:
+                // https://github.com/RazrFalcon/rustybuzz/issues/52
+                let mut buzz_face = rustybuzz::Face::from_face(ttf_face.clone()).unwrap();
+                buzz_face.set_variations(&variable_variations.to_rustybuzz());
+                let mut buffer = rustybuzz::UnicodeBuffer::new();
+                let clamped = ClampedText::new(&rope, &content, range.clone());
+                let chunk = &content[range.start.value..range.end.value];
+                buffer.push_str(clamped.as_ref().map_or(chunk, |clamped| &clamped.text));
+                let features = font_features.with_font_defaults(font.feature_settings());
+                let shaped = rustybuzz::shape(&buzz_face, &features, buffer);
+                let glyphs = shaped
+                    .glyph_positions()
+                    .iter()
+                    .zip(shaped.glyph_infos())
+                    .filter_map(|(&position, &info)| {
+                        let mut info = info;
+                        let glyph_id = GlyphId(info.glyph_id as u16);
+                        let render_info = font.glyph_info_of_known_face(
+                            non_variable_variations,
+                            &variable_variations,
+                            glyph_id,
+                            face,
+                        );
+                        if let Some(clamped) = &clamped {
+                            info.cluster = clamped.original_offset(info.cluster);
+                        }
+                        info.cluster += prev_chunk_cluster_byte_offset;
+                        if Byte(info.cluster as usize) < grapheme_byte_offset {
+                            // This glyph is part of the previous grapheme cluster. This is caused
+                            // by font not supporting displaying this grapheme cluster. We will not
+                            // display it.
+                            None
+                        } else {
+                            match rope.next_grapheme_offset(grapheme_byte_offset) {
+                                None => error!("Misaligned grapheme cluster boundary."),
+                                Some(next_grapheme_byte_offset) => {
+                                    grapheme_byte_offset = next_grapheme_byte_offset;
+                                }
+                            }
+                            Some(ShapedGlyph { position, info, render_info })
+                        }
+                    })
+                    .collect();
+                let shaped_glyph_set = ShapedGlyphSet {
+                    units_per_em,
+                    ascender,
+                    descender,
+                    line_gap,
+                    non_variable_variations,
+                    font_family,
+                    space_advance,
+                    hyphen,
+                    glyphs,
+                };
+                glyph_sets.push(shaped_glyph_set);
+            });
+            prev_chunk_cluster_byte_offset = range.end.value as u32;
+        }
+        glyph_sets
+    }
+
+    /// Shape the provided byte range, reusing the result from the [`ShapeCache`] if the range is
+    /// short and rendered with a single font face.
+    fn shape_range_cached(&self, range: Range<Byte>) -> Vec<ShapedGlyphSet> {
+        let byte_size = range.end.value.saturating_sub(range.start.value);
+        if byte_size == 0 || byte_size > shape_cache::MAX_CONTENT_BYTE_SIZE {
+            return self.shape_range(range);
+        }
+        let line_style = self.buffer.sub_style(range.clone());
+        let rope = self.buffer.rope.sub(range.clone());
+        let mut chunks = self.chunks_per_font(&line_style, &rope).into_iter();
+        match (chunks.next(), chunks.next()) {
+            (Some(chunk), None) => {
+                let font = &chunk.glyph_system.font;
+                let variations = font
+                    .closest_non_variable_variations_or_panic(chunk.non_variable_variations)
+                    .variations;
+                let content = rope.to_string();
+                let font_name = font.name().clone();
+                let features = self.frp.output.font_features.value();
+                let family = chunk.font_family;
+                let key = shape_cache::Key::new(font_name, variations, family, features, &content);
+                self.shape_cache.get_or_shape(key, &content, || self.shape_range(range))
+            }
+            _ => self.shape_range(range),
+        }
+    }
+
+    /// Recompute the shape of the provided line index.
+    #[profile(Debug)]
+    pub fn shape_line(&self, line: Line) -> ShapedLine {
+        let line_range = self.buffer.line_range_snapped(line);
+        let glyph_sets = self.shape_range_cached(line_range.clone());
+        match NonEmptyVec::try_from(glyph_sets) {
+            Ok(glyph_sets) => ShapedLine::NonEmpty { glyph_sets },
+            Err(_) => {
+                if let Some(prev_grapheme_off) =
+                    self.buffer.rope.prev_grapheme_offset(line_range.start)
+                {
+                    let prev_char_range = prev_grapheme_off..line_range.start;
+                    let prev_glyph_sets = self.shape_range(prev_char_range);
+                    let last_glyph_set = prev_glyph_sets.into_iter().last();
+                    let prev_glyph_info = last_glyph_set.map(|t| (prev_grapheme_off, t));
+                    ShapedLine::Empty { prev_glyph_info }
+                } else {
+                    ShapedLine::Empty { prev_glyph_info: None }
+                }
+            }
+        }
+    }
+
+    /// Return list of spans for different font families and [`NonVariableFaceHeader`]s, together
+    /// with glyph systems rendering them. See [`Formatting::chunks_per_font_face`] to learn more.
+    /// In case of variable fonts, the face header is rendered with the variation axes, see
+    /// [`font::Font::variable_variations`].
+    fn chunks_per_font(&self, line_style: &Formatting, rope: &Rope) -> Vec<FontChunk> {
+        let mut chunks = vec![];
+        let face_chunks = line_style.chunks_per_font_face(rope).collect_vec();
+        for (family_range, font_family) in line_style.chunks_per_font_family(rope) {
+            let glyph_system = self.glyph_system_of_family(font_family);
+            for (range, non_variable_variations) in face_chunks.iter().cloned() {
+                let start = range.start.max(family_range.start);
+                let end = range.end.min(family_range.end);
+                if start < end {
+                    let range = start..end;
+                    let glyph_system = glyph_system.clone_ref();
+                    chunks.push(FontChunk {
+                        range,
+                        non_variable_variations,
+                        font_family,
+                        glyph_system,
+                    });
+                }
+            }
+        }
+        chunks
+    }
+
+    /// The glyph system rendering the font family. The default family is rendered with the font
+    /// set by [`Frp::set_font`]. The glyph systems of other families are created on demand.
+    fn glyph_system_of_family(&self, font_family: formatting::FontFamily) -> glyph::System {
+        match font_family.name() {
+            None => self.glyph_system.borrow().clone_ref(),
+            Some(name) => {
+                let mut systems = self.family_glyph_systems.borrow_mut();
+                let system = systems
+                    .entry(name)
+                    .or_insert_with_key(|name| glyph::System::new(&self.scene, name));
+                system.clone_ref()
+            }
+        }
+    }
+}
+
+
+
+// =================
+// === FontChunk ===
+// =================
+
+/// A range of a line rendered with a single font face.
+#[derive(Debug)]
+struct FontChunk {
+    range:                   Range<Byte>,
+    non_variable_variations: NonVariableFaceHeader,
+    font_family:             formatting::FontFamily,
+    glyph_system:            glyph::System,
+}
+
+
+
+// ==================
+// === Glyph Divs ===
+// ==================
+
+/// Push the division points of a glyph covering the `range` of the line. A glyph covering several
+/// grapheme clusters (like the "fi" ligature of proportional fonts) has its advance split evenly
+/// between the clusters, so every cluster has its own division point, and the cursor can be placed
+/// inside the ligature. Returns the number of pushed division points.
+fn push_glyph_divs(
+    divs: &mut NonEmptyVec<f32>,
+    line: &Rope,
+    range: Range<Byte>,
+    x_advance: f32,
+) -> usize {
+    let mut cluster_count = 1;
+    let mut offset = range.start;
+    while let Some(next) = line.next_grapheme_offset(offset) && next < range.end {
+        cluster_count += 1;
+        offset = next;
+    }
+    let start_x = *divs.last();
+    let cluster_advance = x_advance / cluster_count as f32;
+    for index in 1..=cluster_count {
+        divs.push(start_x + cluster_advance * index as f32);
+    }
+    cluster_count
+}
+
+/// Whether the grapheme clusters of the line are whitespace, one entry per column.
+fn whitespace_columns(line: &Rope) -> Vec<bool> {
+    let text = line.to_string();
+    let offsets = column_byte_offsets(line);
+    let columns = offsets.array_windows();
+    columns
+        .map(|[start, end]| text[start.value..end.value].chars().all(char::is_whitespace))
+        .collect()
+}
+
+/// The byte offsets of the grapheme clusters of the line, one entry per column, followed by the
+/// line end.
+fn column_byte_offsets(line: &Rope) -> Vec<Byte> {
+    let end = line.last_byte_index();
+    let mut offsets = vec![Byte(0)];
+    let mut offset = Byte(0);
+    while offset < end {
+        offset = line.next_grapheme_offset(offset).unwrap_or(end);
+        offsets.push(offset);
+    }
+    offsets
+}
+
+/// Break the word of the line at the last break point of the dictionary for which the row fits,
+/// see [`wrap::Rows::hyphenated`]. The `offsets` are the [`column_byte_offsets`] of the line. The
+/// break points not placed at the grapheme cluster boundaries are not used.
+fn break_word_at_column(
+    dictionary: &hyphenation::Dictionary,
+    line: &str,
+    offsets: &[Byte],
+    word: Range<Column>,
+    fits: &dyn Fn(Column) -> bool,
+) -> Option<Column> {
+    let start = *offsets.get(word.start.value)?;
+    let end = *offsets.get(word.end.value)?;
+    let text = line.get(start.value..end.value)?;
+    let column_of = |word_offset: usize| {
+        let offset = Byte(start.value + word_offset);
+        offsets.binary_search(&offset).ok().map(Column)
+    };
+    let break_offset = dictionary.break_word(text, |prefix| {
+        let word_offset = prefix.len() - hyphenation::HYPHEN.len();
+        column_of(word_offset).map_or(false, fits)
+    })?;
+    column_of(break_offset)
+}
+
+/// The hyphen rendered after a column if the row is broken after it, see
+/// [`TextModel::update_hyphens`].
+#[derive(Clone, Copy, Debug)]
+struct ColumnHyphen {
+    shape:      ShapedHyphen,
+    /// The advance of the hyphen, scaled to the font size of the column.
+    width:      f32,
+    variations: NonVariableFaceHeader,
+}
+
+
+
+// =================
+// === Tab Stops ===
+// =================
+
+/// The advance of a tab character placed at the `x` position, reaching the next tab stop. The tab
+/// stops are placed every `tab_width`. A tab placed exactly at a tab stop reaches the next one.
+/// Returns [`None`] if the tab width is not positive, for example, if the font has no space glyph.
+fn tab_advance(x: f32, tab_width: f32) -> Option<f32> {
+    (tab_width > 0.0).then(|| ((x / tab_width).floor() + 1.0) * tab_width - x)
+}
+
+/// The number of spaces reaching the next tab stop from the end of the line prefix. The tab
+/// characters of the prefix reach the next tab stop as well.
+fn spaces_to_tab_stop(prefix: &str, tab_size: usize) -> usize {
+    let tab_size = tab_size.max(1);
+    let column = prefix.chars().fold(0, |column, char| {
+        if char == '\t' {
+            (column / tab_size + 1) * tab_size
+        } else {
+            column + 1
+        }
+    });
+    tab_size - column % tab_size
+}
+
+
+
+// ===================
+// === ClampedText ===
+// ===================
+
+/// A text chunk prepared for shaping, with grapheme clusters longer than
+/// [`MAX_SHAPED_GRAPHEME_CLUSTER_LEN`] code points truncated.
+#[derive(Debug)]
+struct ClampedText {
+    text:   String,
+    /// Byte offsets (in the clamped text) of clusters following the truncated ones, paired with
+    /// the total number of bytes removed before them.
+    shifts: Vec<(u32, u32)>,
+}
+
+impl ClampedText {
+    /// Clamp the grapheme clusters of the `range` of the text. The `content` is the string of the
+    /// `rope`. Returns [`None`] if no cluster needed to be truncated.
+    fn new(rope: &Rope, content: &str, range: Range<Byte>) -> Option<Self> {
+        let clusters = || {
+            let mut offset = range.start;
+            iter::from_fn(move || {
+                (offset < range.end).then(|| {
+                    let next = rope.next_grapheme_offset(offset).unwrap_or(range.end);
+                    let next = next.min(range.end);
+                    let cluster = &content[offset.value..next.value];
+                    offset = next;
+                    cluster
+                })
+            })
+        };
+        // The byte length is a cheap upper bound of the code point count.
+        let is_long = |c: &str| {
+            c.len() > MAX_SHAPED_GRAPHEME_CLUSTER_LEN
+                && c.chars().count() > MAX_SHAPED_GRAPHEME_CLUSTER_LEN
+        };
+        let has_long_clusters = clusters().any(is_long);
+        if !has_long_clusters {
+            return None;
+        }
+        let mut text = String::new();
+        let mut shifts = vec![];
+        let mut removed = 0;
+        for cluster in clusters() {
+            let kept_chars = cluster.char_indices().nth(MAX_SHAPED_GRAPHEME_CLUSTER_LEN);
+            let kept_len = kept_chars.map_or(cluster.len(), |(len, _)| len);
+            text.push_str(&cluster[..kept_len]);
+            if kept_len < cluster.len() {
+                removed += cluster.len() - kept_len;
+                shifts.push((text.len() as u32, removed as u32));
+            }
+        }
+        (!shifts.is_empty()).then_some(Self { text, shifts })
+    }
+
+    /// Convert the byte offset of a cluster start in the clamped text to the offset in the
+    /// original text.
+    fn original_offset(&self, offset: u32) -> u32 {
+        let index = self.shifts.partition_point(|(start, _)| *start <= offset);
+        match index.checked_sub(1) {
+            Some(index) => offset + self.shifts[index].1,
+            None => offset,
+        }
+    }
+}
+
+
+
+// =============================
+// === Redrawing And Updates ===
+// =============================
+
+impl TextModel {
+    /// Apply the changes to the text buffer and update the lines.
+    #[profile(Debug)]
+    fn on_modified_selection(
+        &self,
+        buffer_selections: &buffer::selection::Group,
+        changes: Option<&[buffer::Change]>,
+    ) {
+        let do_edit = changes.is_some();
+        self.update_lines_after_change(changes);
+        self.replace_selections(do_edit, buffer_selections);
+        if do_edit {
+            self.attach_glyphs_to_cursors();
+        }
+    }
+
+    /// Implementation of lazy line redrawing. After a change, only the needed lines are redrawn.
+    /// If a change produced more lines than the current number of lines, the new lines are inserted
+    /// in appropriate positions. If a change produces fewer lines than the current number of lines,
+    /// appropriate lines are removed. Then, a minimal line redraw range is computed and performed.
+    ///
+    /// # Smooth animations
+    /// This function also attaches unchanged lines below a cursor to the cursor for smooth vertical
+    /// animation. Please note that when cursors are moved in a non-edit mode (e.g. after pressing
+    /// an arrow key), the animations are skipped. This is a performance optimization. If we would
+    /// like to continue the animations, we would need to either attach animation system to every
+    /// glyph, or create multiple, possibly hierarchical "virtual cursors". The animations are so
+    /// fast that this is barely noticeable.
+    ///
+    /// # Possible future optimizations.
+    /// This section describes possible future optimizations that are not implemented now because
+    /// of their complexity and/or lack of use cases.
+    ///
+    /// ## Limiting the number of glyphs per line.
+    /// Currently, nothing limits the number of glyphs in line. They are computed and displayed even
+    /// if they are not visible on the screen.
+    ///
+    /// ## Redrawing parts of lines only.
+    /// Currently, the whole line is redrawn after any change. This is not optimal, especially for
+    /// lines containing a lot of visible characters. However, redrawing only parts of a changed
+    /// line is way more complex than it seems. Let's consider the input `அட0`. If we insert `்`
+    /// after `ட`, then we should get `ட்` instead of `ட`, but we do not need to redraw neither
+    /// `அ` nor `0`. Inserting a new code point can affect any number of code points to the left
+    /// and to the right of the insertion point. Unfortunately, the current Rustybuzz
+    /// implementation does not support such use cases:
+    /// https://github.com/RazrFalcon/rustybuzz/issues/54
+    #[profile(Debug)]
+    fn update_lines_after_change(&self, changes: Option<&[buffer::Change]>) {
+        debug_span!("update_lines_after_change").in_scope(|| {
+            self.detach_glyphs_from_cursors();
+            if let Some(changes) = changes {
+                let view_line_range = self.buffer.view_line_range();
+                let lines_to_redraw = changes
+                    .iter()
+                    .filter_map(|change_with_selection| {
+                        // Updating shaped lines cache.
+                        let line_diff = change_with_selection.line_diff;
+                        let redraw_start_line = *change_with_selection.change_range.start();
+                        let redraw_end_line = *change_with_selection.change_range.end();
+                        if line_diff != LineDiff(0) {
+                            let mut shaped_lines = self.shaped_lines.borrow_mut();
+                            let to_update = shaped_lines.drain_filter(|l, _| *l > redraw_end_line);
+                            let updated = to_update.map(|(l, s)| (l + line_diff, s)).collect_vec();
+                            shaped_lines.extend(updated);
+                        }
+                        let redraw_range_end = (redraw_end_line + line_diff).value;
+                        let redraw_range = redraw_start_line.value..=redraw_range_end;
+                        for line in redraw_range {
+                            let line = Line(line);
+                            self.shaped_lines.borrow_mut().remove(&line);
+                        }
+
+                        // Updating lines.
+                        let change_start = change_with_selection.selection.start.line;
+                        let change_end = change_with_selection.selection.end.line;
+                        let view_change_range = change_start..=change_end;
+                        let first_line_index = *view_change_range.start();
+                        let second_line_index = first_line_index.inc();
+                        let mut lines = self.lines.borrow_mut();
+                        let first_line_baseline = lines[first_line_index].baseline();
+
+                        #[allow(clippy::comparison_chain)]
+                        if line_diff > LineDiff(0) {
+                            // Add missing lines. They will be redrawn later. This is needed for
+                            // proper partial redraw (redrawing only the lines that changed).
+                            let new_lines = iter::from_fn(|| {
+                                let new_line = self.new_line();
+                                new_line.set_baseline(first_line_baseline);
+                                new_line.skip_baseline_animation();
+                                Some(new_line)
+                            });
+                            let new_lines = new_lines.take(line_diff.value as usize);
+                            if second_line_index < ViewLine(lines.len()) {
+                                lines.extend_at(second_line_index, new_lines);
+                            } else {
+                                lines.extend(new_lines);
+                            }
+                        } else if line_diff < LineDiff(0) {
+                            // Remove lines that are no longer needed. This is needed for proper
+                            // partial redraw (redrawing only the lines that changed).
+                            let line_diff = -line_diff.value as usize;
+                            let line_diff = ViewLine(line_diff);
+                            lines.drain(second_line_index..second_line_index + line_diff);
+                        }
+
+                        let range_end = view_change_range.end() + line_diff;
+                        let range = (*view_change_range.start())..=range_end;
+                        range.intersect(&view_line_range)
+                    })
+                    .collect_vec();
+
+                let lines_to_redraw = std_ext::range::merge_overlapping_ranges(lines_to_redraw);
+                self.redraw_sorted_line_ranges(lines_to_redraw);
+            }
+        })
+    }
+
+    /// Update selection positions. This is needed e.g. if the selected glyph size changed.
+    fn update_selections(&self) {
+        let buffer_selections = self.buffer.selections();
+        self.replace_selections(false, &buffer_selections);
+    }
+
+    /// Replace selections with new ones.
+    #[profile(Debug)]
+    fn replace_selections(&self, do_edit: bool, buffer_selections: &buffer::selection::Group) {
+        let mut new_selection_map = SelectionMap::default();
+        let merge = !do_edit && self.frp.output.merge_adjacent_selections.value();
+        let buffer_selections = buffer_selections
+            .iter()
+            .map(|selection| self.limit_selection_to_known_values(*selection))
+            .collect_vec();
+        let buffer_selections =
+            if merge { merge_adjacent_selections(&buffer_selections) } else { buffer_selections };
+        for buffer_selection in buffer_selections {
+            let id = buffer_selection.id;
+            let selection_start_line =
+                ViewLine::from_in_context_snapped(self, buffer_selection.start.line);
+            let selection_end_line =
+                ViewLine::from_in_context_snapped(self, buffer_selection.end.line);
+            let start_location = Location(selection_start_line, buffer_selection.start.offset);
+            let end_location = Location(selection_end_line, buffer_selection.end.offset);
+            let (start_pos, end_pos) = self.lines.coordinates(start_location, end_location);
+            let width = end_pos.x - start_pos.x;
+            // FIXME[WD]: This does not work nicely for multi-line selection.
+            //     See: https://www.pivotaltracker.com/story/show/183691214.
+            let metrics = self.lines.borrow()[selection_start_line].metrics();
+            let prev_selection = self.selection_map.borrow_mut().id_map.remove(&id);
+            let reused_selection = prev_selection.is_some();
+            let selection = if let Some(selection) = prev_selection {
+                selection.set_width_and_flip_sides_if_needed(width, start_pos.x);
+                selection
+            } else {
+                let frame_time = &self.scene.frp.frame_time;
+                let selection = Selection::new(frame_time, do_edit);
+                let network = selection.network();
+                let out = &self.frp.private.output;
+                frp::extend! { network
+                    out.refresh_height <+_ selection.position;
+                    out.refresh_width <+_ selection.right_side_of_last_attached_glyph;
+                }
+                selection.set_color(self.displayed_selection_color());
+                selection.set_caret_color(self.frp.output.caret_color.value());
+                selection.set_caret_width(self.frp.output.caret_width.value());
+                selection.set_corner_radius(self.frp.output.selection_corner_radius.value());
+                selection.set_width_target(width);
+                selection
+            };
+            selection.set_position_target(start_pos);
+            selection.set_ascender(metrics.ascender);
+            selection.set_descender(metrics.descender);
+            selection.edit_mode().set(do_edit);
+            let hide_caret = self.disabled.get() && buffer_selection.is_cursor();
+            if hide_caret {
+                selection.unset_parent();
+            } else if !selection.has_parent() {
+                self.content.add_child(&selection);
+            }
+            if !reused_selection {
+                selection.skip_position_animation();
+            }
+            new_selection_map.id_map.insert(id, selection);
+            let loc_map = new_selection_map.location_map.entry(selection_start_line).or_default();
+            loc_map.insert(buffer_selection.start.offset, id);
+        }
+        *self.selection_map.borrow_mut() = new_selection_map;
+    }
+
+    /// Constrain the selection to values fitting inside the current text buffer. This can be needed
+    /// when using the API and providing invalid values.
+    fn limit_selection_to_known_values(&self, selection: buffer::Selection) -> buffer::Selection {
+        let start_location = Location::from_in_context_snapped(&self.buffer, selection.start);
+        let end_location = Location::from_in_context_snapped(&self.buffer, selection.end);
+        let start = self.buffer.snap_location(start_location);
+        let end = self.buffer.snap_location(end_location);
+        let start = Location::from_in_context_snapped(&self.buffer, start);
+        let end = Location::from_in_context_snapped(&self.buffer, end);
+        selection.with_start(start).with_end(end)
+    }
+
+    /// Record the given number of the most recent buffer history entries as a single operation in
+    /// the undo manager.
+    fn record_undo_operation(&self, manager: &Option<application::undo::Manager>, count: usize) {
+        if let Some(manager) = manager && count > 0 {
+            let undo_frp = self.frp.clone_ref();
+            let redo_frp = self.frp.clone_ref();
+            let undo = move || {
+                if let Some(frp) = undo_frp.upgrade() {
+                    (0..count).for_each(|_| frp.undo());
+                }
+            };
+            let redo = move || {
+                if let Some(frp) = redo_frp.upgrade() {
+                    (0..count).for_each(|_| frp.redo());
+                }
+            };
+            manager.record("Text edit", undo, redo);
+        }
+    }
+
+    /// Resize lines vector to contain the required lines count.
+    fn resize_lines(&self) {
+        let line_count = self.buffer.view_line_count();
+        self.lines.resize_with(line_count, |_| self.new_line());
+    }
+
+    /// Clean all the glyph shape caches and redraw all the text. This function should be used only
+    /// when the shapes of the lines are no longer valid, like after changing the font, as it is
+    /// very costly.
+    pub fn clear_cache_and_redraw(&self) {
+        self.clear_shaped_lines_cache();
+        self.redraw();
+    }
+
+    /// Redraw all the text. The shaped lines cache is kept, so this should be used only when the
+    /// shapes of the lines are still valid, like after scrolling or changing the view width. See
+    /// [`Self::clear_cache_and_redraw`].
+    #[profile(Debug)]
+    pub fn redraw(&self) {
+        let end = ViewLine::try_from_in_context(&self.buffer, self.buffer.last_view_line());
+        // FIXME: Unwrap used here. To be fixed when view area will be implemented properly.
+        let end = end.unwrap();
+        self.detach_glyphs_from_cursors();
+        self.redraw_sorted_line_ranges(std::iter::once(ViewLine(0)..=end));
+        self.update_selections();
+        self.schedule_shaping_prefetch();
+    }
+
+    /// Redraw the given line ranges.
+    #[profile(Debug)]
+    fn redraw_sorted_line_ranges(
+        &self,
+        sorted_line_ranges: impl Iterator<Item = RangeInclusive<ViewLine>>,
+    ) {
+        self.resize_lines();
+        self.width_dirty.set(true);
+        let sorted_line_ranges = sorted_line_ranges.inspect(|range| {
+            for line in range.clone() {
+                self.redraw_line(line);
+            }
+        });
+        self.position_sorted_line_ranges(sorted_line_ranges);
+        self.set_horizontal_scroll(self.horizontal_scroll.get());
+        self.redraw_gutter();
+        self.redraw_whitespace_issues();
+        self.redraw_annotations();
+        self.redraw_highlight_sets();
+    }
+
+    /// Redraw the line. This will re-position all line glyphs.
+    #[profile(Debug)]
+    fn redraw_line(&self, view_line: ViewLine) {
+        let line = &mut self.lines.borrow_mut()[view_line];
+        let default_divs = || NonEmptyVec::singleton(0.0);
+        let mut divs = default_divs();
+        let mut column_hyphens = vec![];
+        let mut column = Column(0);
+        let mut to_be_truncated = 0;
+        let mut divs_to_be_truncated = 0;
+        let mut truncated = false;
+        let default_size = self.buffer.formatting.font_size().default;
+        let line_index = Line::from_in_context_snapped(self, view_line);
+        let view_width = self.frp.output.view_width.value();
+        let wrap_width = self.frp.output.wrap_mode.value().width(view_width);
+        let tab_size = self.frp.output.tab_size.value();
+        self.with_shaped_line(line_index, |shaped_line| {
+            match shaped_line {
+                ShapedLine::NonEmpty { glyph_sets } => {
+                    let long_text_truncation_mode =
+                        self.frp.output.long_text_truncation_mode.value() && wrap_width.is_none();
+                    let line_range = self.buffer.byte_range_of_view_line_index_snapped(view_line);
+                    let line_style = self.buffer.sub_style(line_range.start..line_range.end);
+                    let mut line_style_iter = line_style.iter_bytes();
+                    let line_rope = self.buffer.rope.sub(line_range.start..line_range.end);
+                    let line_content = line_rope.to_string();
+                    // The division points of a glyph are pushed after the start of the next glyph
+                    // is known, as the glyph can cover several grapheme clusters.
+                    let mut pending_divs: Option<(Byte, f32, bool, Option<ColumnHyphen>)> = None;
+                    let mut glyph_offset_x = 0.0;
+                    let mut prev_cluster_byte_off = Byte(0);
+                    let truncation_size = line::TruncationSize::from(default_size);
+                    let ellipsis_width = truncation_size.width_with_text_offset();
+                    let mut line_metrics = None;
+                    for shaped_glyph_set in glyph_sets {
+                        if truncated {
+                            break;
+                        }
+                        // FIXME[WD]: This is a workaround for a bug in the MSDFgen binding. It
+                        //     should be fixed after updating the MSDFgen library.
+                        //     See: https://www.pivotaltracker.com/n/projects/2539304/stories/183747513
+                        let magic_scale = 2048.0 / shaped_glyph_set.units_per_em as f32;
+                        let font_family = shaped_glyph_set.font_family;
+                        let glyph_system = self.glyph_system_of_family(font_family);
+                        let new_glyph = || {
+                            let glyph = glyph_system.new_glyph();
+                            glyph.set_font_family(font_family);
+                            glyph
+                        };
+                        for shaped_glyph in &shaped_glyph_set.glyphs {
+                            let glyph_byte_start = shaped_glyph.start_byte();
+                            if let Some((start, x_advance, truncate, hyphen)) = pending_divs.take()
+                            {
+                                let range = start..glyph_byte_start;
+                                let count =
+                                    push_glyph_divs(&mut divs, &line_rope, range, x_advance);
+                                column_hyphens.extend(std::iter::repeat(hyphen).take(count));
+                                if truncate {
+                                    divs_to_be_truncated += count;
+                                }
+                            }
+                            // Drop styles assigned to skipped bytes. One byte will be skipped
+                            // during the call to `line_style_iter.next()`.
+                            let cluster_diff =
+                                glyph_byte_start - prev_cluster_byte_off - ByteDiff(1);
+                            let cluster_diff = Byte::try_from(cluster_diff).unwrap_or_default();
+                            line_style_iter.skip_bytes(cluster_diff);
+                            let style = line_style_iter.next().unwrap_or_default();
+                            prev_cluster_byte_off = glyph_byte_start;
+
+                            // The line metrics are computed for the not scaled font size, so the
+                            // line height does not change with superscript or subscript text.
+                            let units_per_em = shaped_glyph_set.units_per_em as f32;
+                            let scale = units_per_em / style.font_size.value;
+                            let ascender = shaped_glyph_set.ascender as f32 / scale;
+                            let descender = shaped_glyph_set.descender as f32 / scale;
+                            let gap = shaped_glyph_set.line_gap as f32 / scale;
+                            let font_size = style.font_size.value * style.script.size_scale();
+                            let baseline_offset =
+                                style.font_size.value * style.script.baseline_offset();
+                            let mut x_advance =
+                                shaped_glyph.position.x_advance as f32 * font_size / units_per_em;
+                            let glyph_content = line_content.get(glyph_byte_start.value..);
+                            if glyph_content.map_or(false, |t| t.starts_with('\t')) {
+                                let space_advance = shaped_glyph_set.space_advance as f32;
+                                let space_width = space_advance * font_size / units_per_em;
+                                let tab_width = space_width * tab_size as f32;
+                                let tab_advance = tab_advance(glyph_offset_x, tab_width);
+                                x_advance = tab_advance.unwrap_or(x_advance);
+                            }
+                            let glyph_rhs = glyph_offset_x + x_advance;
+
+                            let mut truncate_glyph = false;
+                            if long_text_truncation_mode {
+                                if let Some(view_width) = view_width {
+                                    if glyph_rhs > view_width {
+                                        truncated = true;
+                                        break;
+                                    } else if glyph_rhs > view_width - ellipsis_width {
+                                        to_be_truncated += 1;
+                                        truncate_glyph = true;
+                                    }
+                                };
+                            }
+
+                            let glyph =
+                                &line.get_or_create_of_family(column, font_family, new_glyph);
+                            glyph.line_byte_offset.set(glyph_byte_start);
+
+                            let glyph_line_metrics = line::Metrics { ascender, descender, gap };
+                            line_metrics = line_metrics.concat(Some(glyph_line_metrics));
+
+                            let render_info = &shaped_glyph.render_info;
+                            let glyph_render_offset = render_info.offset.scale(font_size);
+                            glyph.set_color(style.color);
+                            glyph.set_sdf_weight(style.sdf_weight.value);
+                            glyph.set_outline(style.outline);
+                            glyph.set_shadow(style.shadow);
+                            glyph.set_emphasis(style.emphasis);
+                            glyph.set_underline(style.underline);
+                            glyph.set_underline_color(style.underline_color);
+                            glyph.set_underline_style(style.underline_style);
+                            glyph.set_strikethrough(style.strikethrough);
+                            glyph.set_dim(self.glyph_dim());
+                            glyph.set_pickable(self.frp.output.glyph_picking.value());
+                            glyph.set_script(style.script);
+                            glyph.set_font_size(formatting::Size(font_size * magic_scale));
+                            glyph.set_properties(shaped_glyph_set.non_variable_variations);
+                            glyph.set_glyph_id(shaped_glyph.id());
+                            glyph.x_advance.set(x_advance);
+                            glyph.view.set_xy(glyph_render_offset * magic_scale);
+                            glyph.set_xy(Vector2(glyph_offset_x, baseline_offset));
+
+                            let hyphen = shaped_glyph_set.hyphen.map(|shape| {
+                                let width = shape.x_advance as f32 * font_size / units_per_em;
+                                let variations = shaped_glyph_set.non_variable_variations;
+                                ColumnHyphen { shape, width, variations }
+                            });
+                            glyph_offset_x += x_advance;
+                            pending_divs =
+                                Some((glyph_byte_start, x_advance, truncate_glyph, hyphen));
+                            column += Column(1);
+                        }
+                    }
+                    if let Some((start, x_advance, truncate, hyphen)) = pending_divs {
+                        let range = start..line_rope.last_byte_index();
+                        let count = push_glyph_divs(&mut divs, &line_rope, range, x_advance);
+                        column_hyphens.extend(std::iter::repeat(hyphen).take(count));
+                        if truncate {
+                            divs_to_be_truncated += count;
+                        }
+                    }
+                    if let Some(line_metrics) = line_metrics {
+                        line.set_metrics(line_metrics);
+                    } else {
+                        warn!("Internal error. Line metrics was not computed.")
+                    }
+                }
+                ShapedLine::Empty { prev_glyph_info } => {
+                    if let Some((offset, shaped_glyph_set)) = prev_glyph_info {
+                        let line_style = self.buffer.sub_style(*offset..);
+                        let mut line_style_iter = line_style.iter_bytes();
+                        let style = line_style_iter.next().unwrap_or_default();
+                        let scale = shaped_glyph_set.units_per_em as f32 / style.font_size.value;
+                        let ascender = shaped_glyph_set.ascender as f32 / scale;
+                        let descender = shaped_glyph_set.descender as f32 / scale;
+                        let gap = shaped_glyph_set.line_gap as f32 / scale;
+                        let metrics = line::Metrics { ascender, descender, gap };
+                        line.set_metrics(metrics);
+                    }
+                }
+            }
+        });
+
+        if truncated {
+            let divs = (divs[0..divs.len() - divs_to_be_truncated]).to_vec();
+            let divs = NonEmptyVec::try_from(divs).unwrap_or_else(|_| default_divs());
+            line.set_divs(divs);
+            line.glyphs.truncate(column.value - to_be_truncated);
+            line.set_truncated(Some(default_size));
+            line.update_truncation_color();
+        } else {
+            line.set_divs(divs);
+            line.glyphs.truncate(column.value);
+            line.set_truncated(None);
+        }
+
+        let rows = match wrap_width {
+            Some(width) => {
+                let line_range = self.buffer.byte_range_of_view_line_index_snapped(view_line);
+                let line_rope = self.buffer.rope.sub(line_range.start..line_range.end);
+                let whitespace = whitespace_columns(&line_rope);
+                let is_whitespace = |column: Column| whitespace.get(column.value) == Some(&true);
+                let divs = line.divs.as_slice();
+                match self.hyphenation.borrow().dictionary() {
+                    Some(dictionary) => {
+                        let line_content = line_rope.to_string();
+                        let offsets = column_byte_offsets(&line_rope);
+                        let hyphen = |column: Column| column_hyphens.get(column.value).copied();
+                        let hyphen_width = |break_column: Column| {
+                            let column = Column(break_column.value.saturating_sub(1));
+                            hyphen(column).flatten().map_or(0.0, |hyphen| hyphen.width)
+                        };
+                        let break_word = |word: Range<Column>, fits: &dyn Fn(Column) -> bool| {
+                            break_word_at_column(dictionary, &line_content, &offsets, word, fits)
+                        };
+                        wrap::Rows::hyphenated(divs, width, is_whitespace, hyphen_width, break_word)
+                    }
+                    None => wrap::Rows::new(divs, width, is_whitespace),
+                }
+            }
+            None => wrap::Rows::unwrapped(line.divs.as_slice()),
+        };
+        let metrics = line.metrics();
+        let row_height = metrics.ascender - metrics.descender + metrics.gap;
+        let row_height = row_height + self.extra_line_spacing(&metrics);
+        line.set_rows(rows, row_height);
+        self.update_hyphens(line, &column_hyphens);
+        line.update_decorations();
+    }
+
+    /// Render the hyphens at the ends of the rows ending with a word broken by the hyphenation.
+    /// The hyphen is styled like the last glyph of the row.
+    fn update_hyphens(&self, line: &mut line::View, column_hyphens: &[Option<ColumnHyphen>]) {
+        let mut count = 0;
+        for row in line.rows.hyphenated_rows().to_vec() {
+            let column = line.rows.last_column(row);
+            let hyphen = column_hyphens.get(column.value).copied().flatten();
+            let Some(hyphen) = hyphen else { continue };
+            let Some(source) = line.glyphs.get(column).map(|t| t.clone_ref()) else { continue };
+            let font_family = source.font_family();
+            let glyph_system = self.glyph_system_of_family(font_family);
+            let new_glyph = || {
+                let glyph = glyph_system.new_glyph();
+                glyph.set_font_family(font_family);
+                glyph
+            };
+            let x = line.row_end_x(row);
+            let glyph = line.get_or_create_hyphen(count, font_family, new_glyph);
+            let font_size = source.font_size();
+            glyph.set_color(source.color());
+            glyph.set_sdf_weight(source.sdf_weight());
+            glyph.set_outline(source.outline());
+            glyph.set_shadow(source.shadow());
+            glyph.set_emphasis(source.emphasis());
+            glyph.set_dim(source.dim());
+            glyph.set_pickable(source.is_pickable());
+            glyph.set_script(source.script());
+            glyph.set_font_size(font_size);
+            glyph.set_properties(hyphen.variations);
+            glyph.set_glyph_id(hyphen.shape.id);
+            glyph.x_advance.set(hyphen.width);
+            glyph.view.set_xy(hyphen.shape.render_info.offset.scale(font_size.value));
+            glyph.set_xy(Vector2(x, source.y()));
+            count += 1;
+        }
+        line.truncate_hyphens(count);
+    }
+
+    /// Clear shaped lines cache and redraw lines in the provided range. Clearing the cache is
+    /// required when the line needs to be re-shaped, for example, after setting a glyph to a bold
+    /// style or changing glyph size.
+    pub fn clear_cache_and_redraw_sorted_line_ranges(
+        &self,
+        ranges: impl IntoIterator<Item = buffer::Range<Byte>>,
+    ) {
+        let view_line_ranges = ranges.into_iter().map(|range| {
+            let range = buffer::Range::<Location>::from_in_context_snapped(self, range);
+            let line_range = range.start.line..=range.end.line;
+            for line_index in line_range {
+                self.clear_shaped_lines_cache_for_line(line_index);
+            }
+            let view_line_start = ViewLine::from_in_context_snapped(self, range.start.line);
+            let view_line_end = ViewLine::from_in_context_snapped(self, range.end.line);
+            view_line_start..=view_line_end
+        });
+        self.redraw_sorted_line_ranges(view_line_ranges);
+        self.update_selections();
+    }
+
+    /// Attach glyphs to cursors if cursors are in edit mode.
+    #[profile(Debug)]
+    pub fn attach_glyphs_to_cursors(&self) {
+        for line in ViewLine(0)..=self.buffer.last_view_line_index() {
+            self.attach_glyphs_to_cursors_for_line(line)
+        }
+    }
+
+    /// Attach glyphs to cursors if cursors are in edit mode.
+    fn attach_glyphs_to_cursors_for_line(&self, view_line: ViewLine) {
+        let cursor_map = self.selection_map.borrow().location_map.get(&view_line).cloned();
+        let cursor_map = cursor_map.unwrap_or_default();
+        let line = &self.lines.borrow()[view_line];
+
+        let mut attached_glyphs = vec![];
+        let mut last_cursor: Option<Selection> = None;
+        let mut last_cursor_target_x = default();
+
+        let mut column = Column(0);
+        for glyph in line {
+            cursor_map.get(&column).for_each(|id| {
+                if let Some(cursor) = self.selection_map.borrow().id_map.get(id) {
+                    if cursor.edit_mode().get() {
+                        if let Some(last_cursor) = &last_cursor {
+                            let attached_glyphs = Rc::new(mem::take(&mut attached_glyphs));
+                            last_cursor.set_attached_glyphs(attached_glyphs);
+                        }
+                        last_cursor = Some(cursor.clone_ref());
+                        last_cursor_target_x = glyph.x();
+                    }
+                }
+            });
+
+            if let Some(cursor) = &last_cursor {
+                cursor.right_side().add_child(glyph);
+                glyph.attached_to_cursor.set(true);
+                glyph.update_x(|p| p - last_cursor_target_x);
+                attached_glyphs.push(glyph.downgrade());
+            }
+            column += Column(1);
+        }
+        if let Some(last_cursor) = &last_cursor {
+            last_cursor.set_attached_glyphs(Rc::new(mem::take(&mut attached_glyphs)));
+        } else if !attached_glyphs.is_empty() {
+            error!("Internal error. Cannot attach glyphs to cursors.");
+        }
+    }
+
+    /// Detach all glyphs from cursors and place them back in lines.
+    #[profile(Debug)]
+    pub fn detach_glyphs_from_cursors(&self) {
+        let selection_map = self.selection_map.borrow();
+        for (&line, cursor_map) in &selection_map.location_map {
+            for cursor_id in cursor_map.values() {
+                let selection = selection_map.id_map.get(cursor_id).unwrap();
+                for glyph in &*selection.set_attached_glyphs.value() {
+                    if let Some(glyph) = glyph.upgrade() {
+                        self.lines.borrow_mut()[line].add_child(&glyph);
+                        let pos_x = selection.position_target.value().x;
+                        glyph.update_xy(|pos| Vector2(pos.x + pos_x, 0.0));
+                        glyph.attached_to_cursor.set(false);
+                    }
+                }
+                selection.set_attached_glyphs(Rc::new(vec![]));
+            }
+        }
+    }
+}
+
+
+
+// ===========================
+// === Property Management ===
+// ===========================
+
+impl TextModel {
+    /// Check whether the property change will invalidate the cache, and thus, will require line
+    /// re-shaping and re-drawing.
+    fn property_change_invalidates_cache(property: impl Into<formatting::PropertyTag>) -> bool {
+        let tag = property.into();
+        match tag {
+            formatting::PropertyTag::FontSize => true,
+            formatting::PropertyTag::Color => false,
+            formatting::PropertyTag::Weight => true,
+            formatting::PropertyTag::Width => true,
+            formatting::PropertyTag::Style => true,
+            formatting::PropertyTag::SdfWeight => false,
+            formatting::PropertyTag::FontFamily => true,
+            formatting::PropertyTag::Script => true,
+            formatting::PropertyTag::Outline => false,
+            formatting::PropertyTag::Shadow => false,
+            formatting::PropertyTag::Emphasis => false,
+            formatting::PropertyTag::Underline => false,
+            formatting::PropertyTag::UnderlineColor => false,
+            formatting::PropertyTag::UnderlineStyle => false,
+            formatting::PropertyTag::Strikethrough => false,
+        }
+    }
+
+    /// Set the property to selected glyphs. Redraw lines if needed.
+    fn set_property(&self, ranges: &Vec<buffer::Range<Byte>>, property: formatting::Property) {
+        if Self::property_change_invalidates_cache(property) {
+            self.clear_cache_and_redraw_sorted_line_ranges(ranges.iter().copied())
+        } else {
+            self.set_glyphs_property_without_line_redraw(ranges, property)
+        }
+    }
+
+    /// Set the properties of many ranges in a single formatting update. The lines covered by the
+    /// properties changing the glyph shapes are redrawn once, the other glyphs are updated in
+    /// place.
+    fn set_properties(&self, spans: &[(buffer::Range<Byte>, formatting::Property)]) {
+        if spans.is_empty() {
+            return;
+        }
+        self.buffer.frp.set_properties(Rc::new(spans.to_vec()));
+        let (reshaping, restyling): (Vec<_>, Vec<_>) = spans
+            .iter()
+            .partition(|(_, property)| Self::property_change_invalidates_cache(*property));
+        let reshaped_range = reshaping.iter().map(|(range, _)| *range).reduce(|a, b| a.union(&b));
+        if let Some(range) = reshaped_range {
+            self.clear_cache_and_redraw_sorted_line_ranges(std::iter::once(range));
+        }
+        for (range, property) in restyling {
+            self.set_glyphs_property_without_line_redraw(&vec![*range], *property);
+        }
+    }
+
+    /// Modify the property of selected glyphs. Redraw lines if needed.
+    fn mod_property(&self, ranges: &Vec<buffer::Range<Byte>>, property: formatting::PropertyDiff) {
+        if Self::property_change_invalidates_cache(property) {
+            self.clear_cache_and_redraw_sorted_line_ranges(ranges.iter().copied())
+        } else {
+            self.mod_glyphs_property_without_line_redraw(ranges, property)
+        }
+    }
+
+    /// Set the property to selected glyphs. No redraw will be performed.
+    fn set_glyphs_property_without_line_redraw(
+        &self,
+        ranges: &Vec<buffer::Range<Byte>>,
+        property: formatting::Property,
+    ) {
+        let property = self.buffer.resolve_property(property);
+        let tag = property.tag();
+        let line_style_change = tag == formatting::PropertyTag::Color || tag.is_decoration();
+        self.modify_glyphs_in_ranges_without_line_redraw(ranges, line_style_change, |g| {
+            g.set_property(property)
+        });
+    }
+
+    /// Modify the property of selected glyphs. No redraw will be performed.
+    fn mod_glyphs_property_without_line_redraw(
+        &self,
+        ranges: &Vec<buffer::Range<Byte>>,
+        property: formatting::PropertyDiff,
+    ) {
+        let line_style_change = property.tag() == formatting::PropertyTag::Color;
+        self.modify_glyphs_in_ranges_without_line_redraw(ranges, line_style_change, |g| {
+            g.mod_property(property)
+        });
+    }
+
+    /// Modify the selected glyphs. No redraw will be performed.
+    fn modify_glyphs_in_ranges_without_line_redraw(
+        &self,
+        ranges: &Vec<buffer::Range<Byte>>,
+        line_style_change: bool,
+        f: impl Fn(&Glyph),
+    ) {
+        for &range in ranges {
+            self.modify_glyphs_in_range_without_line_redraw(range, line_style_change, &f);
+        }
+    }
+
+    /// Modify the selected glyphs. No redraw will be performed. If the `line_style_change` is set,
+    /// the parts of the lines styled after their glyphs, like the truncation ellipsis color or the
+    /// underlines, are updated.
+    fn modify_glyphs_in_range_without_line_redraw(
+        &self,
+        range: buffer::Range<Byte>,
+        line_style_change: bool,
+        f: impl Fn(&Glyph),
+    ) {
+        let range = buffer::Range::<ViewLocation<Byte>>::from_in_context_snapped(self, range);
+        let lines = self.lines.borrow();
+        if range.start.line == range.end.line {
+            let line = &lines[range.start.line];
+            for glyph in line {
+                if glyph.line_byte_offset.get() >= range.end.offset {
+                    break;
+                }
+                if glyph.line_byte_offset.get() >= range.start.offset {
+                    f(glyph)
+                }
+            }
+            if line_style_change {
+                line.update_truncation_color();
+                line.update_decorations();
+            }
+        } else {
+            let first_line = range.start.line;
+            let second_line = first_line + ViewLine(1);
+            let last_line = range.end.line;
+            for glyph in &lines[first_line] {
+                if glyph.line_byte_offset.get() >= range.start.offset {
+                    f(glyph)
+                }
+            }
+            for line in &lines[second_line..last_line] {
+                for glyph in line {
+                    f(glyph)
+                }
+            }
+            for glyph in &lines[last_line] {
+                if glyph.line_byte_offset.get() < range.end.offset {
+                    f(glyph)
+                }
+            }
+            if line_style_change {
+                for line in &lines[first_line..last_line + ViewLine(1)] {
+                    line.update_truncation_color();
+                    line.update_decorations();
+                }
+            }
+        }
+    }
+
+    fn set_selection_color(&self, color: color::Lch) {
+        for selection in self.selection_map.borrow().id_map.values() {
+            selection.set_color(color);
+        }
+    }
+
+    fn set_caret_color(&self, color: Option<color::Lch>) {
+        for selection in self.selection_map.borrow().id_map.values() {
+            selection.set_caret_color(color);
+        }
+    }
+
+    fn set_caret_width(&self, width: f32) {
+        for selection in self.selection_map.borrow().id_map.values() {
+            selection.set_caret_width(width);
+        }
+    }
+
+    /// Set the default text color without marking it as set explicitly, so the theme can still
+    /// change it.
+    fn set_default_text_color(&self, color: color::Lcha) {
+        let property = Some(formatting::ResolvedProperty::Color(color));
+        self.buffer.frp.set_property_default(property);
+        self.set_property_default(property);
+    }
+
+    fn set_line_height(&self, line_height: f32) {
+        if self.line_height.replace(line_height) != line_height {
+            self.height_dirty.set(true);
+            self.redraw();
+        }
+    }
+
+    /// The space added between the lines by the line height multiplier.
+    fn extra_line_spacing(&self, metrics: &line::Metrics) -> f32 {
+        (self.line_height.get() - 1.0) * (metrics.ascender - metrics.descender)
+    }
+
+    fn set_gutter_marker_color(&self, kind: gutter::MarkerKind, color: color::Rgba) {
+        self.gutter.set_marker_color(kind, color);
+        self.redraw_gutter_markers();
+    }
+
+    fn set_enabled(&self, enabled: bool) {
+        self.disabled.set(!enabled);
+        let dim = self.glyph_dim();
+        for line in &*self.lines.borrow() {
+            for glyph in &line.glyphs {
+                glyph.set_dim(dim);
+            }
+        }
+        self.update_selections();
+        if let Some(node) = &*self.accessibility.borrow() {
+            node.set_disabled(!enabled);
+        }
+    }
+
+    fn set_accessible_label(&self, label: &ImString) {
+        let mut node = self.accessibility.borrow_mut();
+        let node = node.get_or_insert_with(|| {
+            let node = accessibility::Node::new(&self.scene, accessibility::Role::TextBox);
+            let text_frp = self.frp.clone_ref();
+            let network = node.network();
+            frp::extend! { network
+                focused <- node.focused.on_true();
+                eval_ focused ([text_frp] if let Some(frp) = text_frp.upgrade() { frp.focus() });
+            }
+            node.set_disabled(self.disabled.get());
+            node
+        });
+        node.set_label(label.clone_ref());
+    }
+
+    fn accessibility_node(&self) -> Option<accessibility::Node> {
+        self.accessibility.borrow().clone()
+    }
+
+    fn set_glyph_picking(&self, enabled: bool) {
+        for line in &*self.lines.borrow() {
+            for glyph in &line.glyphs {
+                glyph.set_pickable(enabled);
+            }
+        }
+    }
+
+    /// The location of the glyph whose view is the given pointer event target, if any.
+    fn glyph_location(&self, target: &display::object::Instance) -> Option<Location> {
+        let lines = self.lines.borrow();
+        lines.iter().enumerate().find_map(|(view_line, line)| {
+            let glyph = line.glyphs.iter().find(|g| g.view.display_object() == target)?;
+            let line = Line::from_in_context_snapped(self, ViewLine(view_line));
+            let offset = self.buffer.line_offset_snapped(line) + glyph.line_byte_offset.get();
+            Some(Location::from_in_context_snapped(&self.buffer, offset))
+        })
+    }
+
+    /// Cover the area of the text with the [`TextModelData::hit_area`]. The text is placed below
+    /// its origin.
+    fn set_hit_area_size(&self, size: Vector2) {
+        self.hit_area.set_xy((0.0, -size.y));
+        self.hit_area.set_size(size);
+    }
+
+    /// The default font size changed by zooming with the mouse wheel.
+    fn wheel_zoomed_font_size(&self, delta_y: f32) -> f32 {
+        let size = self.buffer.formatting.font_size().default.value;
+        let zoomed = size * (1.0 - delta_y * WHEEL_ZOOM_SENSITIVITY);
+        zoomed.clamp(WHEEL_ZOOM_MIN_FONT_SIZE, WHEEL_ZOOM_MAX_FONT_SIZE)
+    }
+
+    /// The height of a line of the default font size. Used to convert distances in pixels to lines.
+    fn default_line_height(&self) -> f32 {
+        let size = self.buffer.formatting.font_size().default.value;
+        size * (1.0 + DEFAULT_ASCENDER_TO_DESCENDER_RATIO) * self.line_height.get()
+    }
+
+    /// The number of lines to scroll by after the wheel event, if any.
+    fn wheel_line_diff(&self, delta_y: f32, mode: WheelScrollMode) -> Option<LineDiff> {
+        let lines = match mode {
+            WheelScrollMode::Lines => delta_y.signum() as i32 * WHEEL_SCROLL_LINES as i32,
+            WheelScrollMode::Pixels => return self.scroll_line_diff(delta_y),
+        };
+        (lines != 0).then_some(LineDiff(lines))
+    }
+
+    /// The number of lines to scroll by after scrolling by the given distance in pixels, if any.
+    /// The distance not consumed by scrolling by whole lines is kept for the next scroll.
+    fn scroll_line_diff(&self, delta_y: f32) -> Option<LineDiff> {
+        let line_height = self.default_line_height();
+        let delta = self.wheel_delta_remainder.get() + delta_y;
+        let lines = (delta / line_height).trunc();
+        self.wheel_delta_remainder.set(delta - lines * line_height);
+        let lines = lines as i32;
+        (lines != 0).then_some(LineDiff(lines))
+    }
+
+    /// The first view line after scrolling to the given distance in pixels from the top of the
+    /// content. Returns [`None`] if the number of visible lines is not limited, or if the view
+    /// would not move.
+    fn first_view_line_at(&self, y: f32) -> Option<Line> {
+        self.wheel_delta_remainder.set(0.0);
+        let line = (y.max(0.0) / self.default_line_height()).floor() as i32;
+        let first_line = self.buffer.first_view_line();
+        self.scrolled_first_view_line(LineDiff(line - first_line.value as i32))
+    }
+
+    /// The distance in pixels between the top of the content and the top of the view. See
+    /// [`Output::scroll_position`].
+    fn scroll_position(&self) -> f32 {
+        self.buffer.first_view_line().value as f32 * self.default_line_height()
+    }
+
+    /// The height of the whole content in pixels. See [`Output::content_height`].
+    fn content_height(&self) -> f32 {
+        let line_count = self.buffer.last_line_index().value + 1;
+        line_count as f32 * self.default_line_height()
+    }
+
+    /// The first view line after scrolling by the given number of lines. Returns [`None`] if the
+    /// number of visible lines is not limited, or if the view would not move.
+    fn scrolled_first_view_line(&self, diff: LineDiff) -> Option<Line> {
+        let view_line_limit = self.buffer.view_line_limit()?;
+        let line_count = self.buffer.last_line_index().value + 1;
+        let max_first_line = Line(line_count.saturating_sub(view_line_limit));
+        let first_line = self.buffer.first_view_line();
+        let new_first_line = first_line.saturating_add_diff(diff).min(max_first_line);
+        (new_first_line != first_line).then_some(new_first_line)
+    }
+
+    /// Whether the last line is visible.
+    fn is_scrolled_to_bottom(&self) -> bool {
+        self.scrolled_to_bottom_first_view_line()
+            .map_or(true, |line| self.buffer.first_view_line() >= line)
+    }
+
+    /// The first view line showing the last line at the bottom of the view. Returns [`None`] if
+    /// the number of visible lines is not limited.
+    fn scrolled_to_bottom_first_view_line(&self) -> Option<Line> {
+        let view_line_limit = self.buffer.view_line_limit()?;
+        let line_count = self.buffer.last_line_index().value + 1;
+        Some(Line(line_count.saturating_sub(view_line_limit)))
+    }
+
+    fn scroll_to_bottom(&self) {
+        if let Some(line) = self.scrolled_to_bottom_first_view_line() {
+            if line != self.buffer.first_view_line() {
+                self.buffer.frp.set_first_view_line(line);
+            }
+        }
+    }
+
+    /// The view width if the text can be scrolled horizontally, that is, if the lines are neither
+    /// wrapped nor truncated.
+    fn horizontal_scroll_view_width(&self) -> Option<f32> {
+        let out = &self.frp.output;
+        let wrapped = out.wrap_mode.value().width(out.view_width.value()).is_some();
+        let truncated = out.long_text_truncation_mode.value();
+        out.view_width.value().filter(|_| !wrapped && !truncated)
+    }
+
+    /// Scroll the view horizontally, limiting the position to the width of the longest visible
+    /// line, and clip the glyphs to the view. Returns the new scroll position.
+    fn set_horizontal_scroll(&self, x: f32) -> f32 {
+        let view_width = self.horizontal_scroll_view_width();
+        let x = match view_width {
+            Some(width) => {
+                let lines = self.lines.borrow();
+                let max_line_width = lines.iter().map(|line| *line.divs.last()).fold(0.0, f32::max);
+                x.min(max_line_width - width).max(0.0)
+            }
+            None => 0.0,
+        };
+        self.horizontal_scroll.set(x);
+        self.content.set_x(-x);
+        for line in &*self.lines.borrow() {
+            for glyph in line.glyphs.iter() {
+                let is_outside = |width: f32| {
+                    let start = glyph.x();
+                    let end = start + glyph.x_advance.get();
+                    end < x || start > x + width
+                };
+                let clipped =
+                    !glyph.attached_to_cursor.get() && view_width.map_or(false, is_outside);
+                glyph.set_clipped(clipped);
+            }
+        }
+        x
+    }
+
+    /// The horizontal scroll position showing the newest cursor. Returns [`None`] if the cursor is
+    /// already visible, or if its line is not visible.
+    fn horizontal_scroll_showing_cursor(&self) -> Option<f32> {
+        let view_width = self.horizontal_scroll_view_width()?;
+        let location = self.buffer.selections().newest()?.end;
+        let view_line = ViewLine::try_from_in_context(&self.buffer, location.line).ok()?;
+        let x = self.lines.borrow().get(view_line)?.position_of_column(location.offset).x;
+        let scroll_x = self.horizontal_scroll.get();
+        if x < scroll_x {
+            Some(x)
+        } else if x > scroll_x + view_width {
+            Some(x - view_width)
+        } else {
+            None
+        }
+    }
+
+    fn set_hyphenation_language(&self, language: Option<ImString>) {
+        self.hyphenation.borrow_mut().set_language(language);
+        self.redraw();
+    }
+
+    fn add_hyphenation_dictionary(&self, dictionary: hyphenation::Dictionary) {
+        self.hyphenation.borrow_mut().add_dictionary(dictionary);
+        self.redraw();
+    }
+
+    fn set_view_height(&self, view_height: Option<f32>) {
+        self.update_view_line_count(view_height);
+        self.redraw();
+    }
+
+    /// Set the number of visible lines fitting in the view height. No redraw will be performed.
+    fn update_view_line_count(&self, view_height: Option<f32>) {
+        let line_height = self.default_line_height();
+        let count = view_height.map(|height| ((height / line_height).floor() as usize).max(1));
+        self.buffer.frp.set_view_line_count(count);
+    }
+
+    /// Change the default font size, keeping the cursor line in place. The text is redrawn once.
+    fn set_font_size(&self, size: f32) {
+        let first_view_line = self.buffer.first_view_line();
+        let last_view_line = self.buffer.last_view_line();
+        let cursor_line = self.cursor_line();
+        let visible_cursor_line =
+            Some(cursor_line).filter(|line| (first_view_line..=last_view_line).contains(line));
+        let cursor_offset_px = visible_cursor_line
+            .map(|line| (line.value - first_view_line.value) as f32 * self.default_line_height());
+
+        let property = formatting::ResolvedProperty::Size(formatting::Size(size));
+        self.buffer.frp.set_property_default(Some(property));
+        self.clear_shaped_lines_cache_of_default_values(property.tag());
+        self.height_dirty.set(true);
+        let view_height = self.frp.output.view_height.value();
+        if view_height.is_some() {
+            self.update_view_line_count(view_height);
+        }
+
+        let new_first_view_line = visible_cursor_line.zip(cursor_offset_px).map(|(line, px)| {
+            let rows_above = (px / self.default_line_height()).floor() as usize;
+            let view_line_count = self.buffer.view_line_count();
+            let rows_above = rows_above.min(view_line_count.saturating_sub(1));
+            Line(line.value.saturating_sub(rows_above))
+        });
+        match new_first_view_line {
+            // Changing the first view line redraws the text.
+            Some(line) if line != first_view_line => self.buffer.frp.set_first_view_line(line),
+            _ => self.redraw(),
+        }
+    }
+
+    fn glyph_dim(&self) -> f32 {
+        if self.disabled.get() {
+            1.0
+        } else {
+            0.0
+        }
+    }
+
+    fn set_selection_corner_radius(&self, radius: f32) {
+        for selection in self.selection_map.borrow().id_map.values() {
+            selection.set_corner_radius(radius);
+        }
+    }
+
+    /// The color of selections, depending on whether the text area is focused.
+    fn displayed_selection_color(&self) -> color::Lch {
+        let color = self.frp.output.selection_color.value();
+        let inactive_color = self.frp.output.inactive_selection_color.value();
+        let focused = self.frp.output.focused.value();
+        if focused {
+            color
+        } else {
+            inactive_color.unwrap_or(color)
+        }
+    }
+}
+
+
+
+// ================
+// === Log View ===
+// ================
+
+impl TextModel {
+    fn append(&self, text: &str) {
+        // Trimming the lines moves the view, which would disengage the pinning.
+        let pinned_to_bottom = self.frp.output.pinned_to_bottom.value();
+        let end = self.buffer.last_byte_index();
+        let change = enso_text::Change::inserted(end, Rope::from(text));
+        self.buffer.frp.apply_changes(Rc::new(vec![change]));
+        self.trim_lines();
+        if pinned_to_bottom {
+            self.scroll_to_bottom();
+        }
+    }
+
+    /// Remove the lines from the top exceeding the maximum line count. The view is moved up by the
+    /// number of removed lines, so the same content stays visible.
+    fn trim_lines(&self) {
+        let Some(max_line_count) = self.frp.output.max_line_count.value() else { return };
+        let line_count = self.buffer.last_line_index().value + 1;
+        let removed_line_count = line_count.saturating_sub(max_line_count.max(1));
+        if removed_line_count > 0 {
+            let first_view_line = self.buffer.first_view_line();
+            let end = self.buffer.line_offset_snapped(Line(removed_line_count));
+            let change =
+                enso_text::Change { range: buffer::Range::new(Byte(0), end), text: default() };
+            self.buffer.frp.apply_changes(Rc::new(vec![change]));
+            let new_first_view_line =
+                Line(first_view_line.value.saturating_sub(removed_line_count));
+            if new_first_view_line != first_view_line {
+                self.buffer.frp.set_first_view_line(new_first_view_line);
+            }
+        }
+    }
+}
+
+
+
+// ===================================
+// === Default Property Management ===
+// ===================================
+
+impl TextModel {
+    /// Change a default value of a property.
+    fn set_property_default(&self, property: Option<formatting::ResolvedProperty>) {
+        if let Some(property) = property {
+            if Self::property_change_invalidates_cache(property) {
+                self.set_property_default_with_line_redraw(property)
+            } else {
+                self.set_property_default_without_line_redraw(property)
+            }
+        }
+    }
+
+    /// Change the default values of all properties set in the provided format. If any of them
+    /// requires line redraw, all lines are redrawn once. Otherwise, the existing glyphs are
+    /// updated.
+    fn set_default_format(&self, format: formatting::Format) {
+        self.buffer.frp.set_default_format(format);
+        let properties = format.properties();
+        if properties.iter().any(|t| Self::property_change_invalidates_cache(*t)) {
+            self.clear_shaped_lines_cache();
+            self.height_dirty.set(true);
+            if format.font_size.is_some() && self.frp.output.view_height.value().is_some() {
+                self.set_view_height(self.frp.output.view_height.value());
+            } else {
+                self.redraw();
+            }
+        } else {
+            for property in properties {
+                self.set_property_default_without_line_redraw(property);
+            }
+        }
+    }
+
+    /// Change a default value of a property that requires line redraw, like changing the default
+    /// glyph weight or size.
+    fn set_property_default_with_line_redraw(&self, property: formatting::ResolvedProperty) {
+        let range = self.buffer.full_range();
+        let formatting = self.buffer.sub_style(range);
+        let span_ranges = formatting.span_ranges_of_default_values(property.tag());
+        self.clear_cache_and_redraw_sorted_line_ranges(span_ranges);
+    }
+
+    /// Clear the shaped lines cache of the lines containing the default values of the property. No
+    /// redraw will be performed.
+    fn clear_shaped_lines_cache_of_default_values(&self, tag: formatting::PropertyTag) {
+        let formatting = self.buffer.sub_style(self.buffer.full_range());
+        for range in formatting.span_ranges_of_default_values(tag) {
+            let range = buffer::Range::<Location>::from_in_context_snapped(self, range);
+            for line in range.start.line..=range.end.line {
+                self.clear_shaped_lines_cache_for_line(line);
+            }
+        }
+    }
+
+    /// Change a default value of a property  that does not require line redraw, like changing the
+    /// default glyph color. The glyphs are matched by their byte offsets rather than by columns, as
+    /// a glyph can cover several columns (for example, a ligature of a proportional font).
+    fn set_property_default_without_line_redraw(&self, property: formatting::ResolvedProperty) {
+        let range = self.buffer.full_range();
+        let formatting = self.buffer.sub_style(range);
+        let span_ranges = formatting.span_ranges_of_default_values(property.tag());
+        let tag = property.tag();
+        let line_style_change = tag == formatting::PropertyTag::Color || tag.is_decoration();
+        let lines = self.lines.borrow();
+        for span_range in span_ranges {
+            let range = buffer::Range::<Location>::from_in_context_snapped(self, span_range);
+            for line_index in range.start.line.value..=range.end.line.value {
+                let line_index = Line(line_index);
+                let Ok(view_line) = ViewLine::try_from_in_context(&self.buffer, line_index) else {
+                    continue;
+                };
+                let Some(line) = lines.get(view_line) else { continue };
+                let line_offset = self.buffer.line_offset_snapped(line_index);
+                for glyph in &line.glyphs {
+                    let offset = line_offset + glyph.line_byte_offset.get();
+                    if span_range.start <= offset && offset < span_range.end {
+                        glyph.set_property(property);
+                    }
+                }
+                if line_style_change {
+                    line.update_decorations();
+                }
+            }
+        }
+    }
+
+    #[profile(Debug)]
+    fn set_font(&self, font_name: &str) -> glyph::System {
+        let glyph_system = font::glyph::System::new(&self.scene, font_name);
+        self.glyph_system.replace(glyph_system.clone());
+        // Remove old Glyph structures, as they still refer to the old Glyph System.
+        self.take_lines();
+        // The new font has different metrics, so all lines are reshaped and re-measured. The
+        // selections are kept, as they are expressed in text locations.
+        self.height_dirty.set(true);
+        self.clear_cache_and_redraw();
+        glyph_system
+    }
+}
+
+
+
+// ========================
+// === Line Positioning ===
+// ========================
+
+impl TextModel {
+    // Update the lines y-axis position starting with the provided line index. Results the first
+    // well positioned line or the next line after the last visible line.
+    fn position_lines_starting_with(&self, mut line_index: ViewLine) -> ViewLine {
+        let alignment = self.frp.output.vertical_alignment.value();
+        if alignment.depends_on_content_height() {
+            // The position of the first line depends on all lines.
+            line_index = ViewLine(0);
+        }
+        let baseline_grid = self.frp.output.baseline_grid.value().filter(|step| *step > 0.0);
+        let last_line_index = self.lines.last_line_index();
+        let lines = self.lines.borrow();
+        while line_index <= last_line_index {
+            let line = &lines[line_index];
+            let current_pos_y = line.baseline();
+            let ascender = -line.metrics().ascender;
+            let new_baseline = if line_index == ViewLine(0) {
+                ascender - self.first_line_offset(alignment, &lines)
+            } else {
+                let prev_line_index = ViewLine(line_index.value - 1);
+                let prev_line = &lines[prev_line_index];
+                let metrics = line.metrics();
+                let spacing = metrics.gap + self.extra_line_spacing(&metrics);
+                let prev_descender = prev_line.metrics().descender - prev_line.wrapped_height();
+                let offset = prev_descender + ascender - spacing;
+                prev_line.baseline() + offset
+            };
+            let new_baseline = match baseline_grid {
+                Some(step) => (new_baseline / step).floor() * step,
+                None => new_baseline.round(),
+            };
+            if current_pos_y == new_baseline {
+                break;
+            }
+            line.set_baseline(new_baseline);
+            line_index += ViewLine(1);
+        }
+        line_index
+    }
+
+    /// The distance between the top of the view and the top of the first line, resulting from the
+    /// vertical alignment.
+    fn first_line_offset(&self, alignment: VerticalAlignment, lines: &LinesVec) -> f32 {
+        let free_space = || {
+            let view_height = self.frp.output.view_height.value()?;
+            let mut content_height = 0.0;
+            let mut prev_descender = None;
+            for line in lines {
+                let metrics = line.metrics();
+                let gap = metrics.gap + self.extra_line_spacing(&metrics);
+                let spacing = prev_descender.map_or(0.0, |descender| gap - descender);
+                content_height += spacing + metrics.ascender;
+                prev_descender = Some(metrics.descender - line.wrapped_height());
+            }
+            content_height -= prev_descender.unwrap_or_default();
+            Some((view_height - content_height).max(0.0))
+        };
+        match alignment {
+            VerticalAlignment::Top => 0.0,
+            VerticalAlignment::Middle => free_space().unwrap_or_default() / 2.0,
+            VerticalAlignment::Bottom => free_space().unwrap_or_default(),
+            VerticalAlignment::Baseline => {
+                let default_size = self.buffer.formatting.font_size().default.value;
+                let ascender = lines.first().metrics().ascender;
+                default_size - ascender
+            }
+        }
+    }
+
+    /// Position all lines in the provided line range. The range has to be sorted.
+    #[profile(Debug)]
+    fn position_sorted_line_ranges(
+        &self,
+        sorted_line_ranges: impl Iterator<Item = RangeInclusive<ViewLine>>,
+    ) {
+        let mut first_ok_line_index = None;
+        let mut line_index_to_position;
+        for range in sorted_line_ranges {
+            line_index_to_position = match first_ok_line_index {
+                None => *range.start(),
+                Some(p) => std::cmp::max(p + ViewLine(1), *range.start()),
+            };
+            // We are positioning one more line, because if a line is removed, the last redraw line
+            // index can be placed in the previous line, that was already well positioned. The next
+            // line has to be updated.
+            let range_end = *range.end() + ViewLine(1);
+            if line_index_to_position <= range_end {
+                loop {
+                    let ok_line_index = self.position_lines_starting_with(line_index_to_position);
+                    first_ok_line_index = Some(ok_line_index);
+                    if ok_line_index >= range_end {
+                        break;
+                    }
+                    line_index_to_position = ok_line_index + ViewLine(1);
+                }
+            }
+        }
+    }
+}
+
+
+
+// =============================
+// === Multi-Click Selection ===
+// =============================
+
+impl TextModel {
+    /// Register the click at the screen position. Returns the selection of the word or the line
+    /// at the position if it is a double or triple click, respectively.
+    fn register_click(&self, screen_pos: Vector2) -> Option<(Location, Location)> {
+        let count = self.multi_click.borrow_mut().register(web::time_from_start(), screen_pos);
+        self.start_multi_click(count, self.screen_to_text_location(screen_pos))
+    }
+
+    /// Select the unit at the location, depending on the click count. See [`MultiClick`].
+    fn start_multi_click(&self, count: usize, location: Location) -> Option<(Location, Location)> {
+        let unit = match count {
+            2 => Some(Transform::Word),
+            3 => Some(Transform::Line),
+            _ => None,
+        };
+        let anchor = unit.map(|unit| {
+            let (start, end) = self.unit_range_at(unit, location);
+            (unit, start, end)
+        });
+        self.multi_click.borrow_mut().anchor = anchor;
+        anchor.map(|(_, start, end)| (start, end))
+    }
+
+    /// Stop extending the selection by units after the mouse button was released.
+    fn finish_multi_click(&self) {
+        self.multi_click.borrow_mut().anchor = None;
+    }
+
+    /// The selection extended from the unit selected by the last double or triple click to the
+    /// unit at the location. Returns [`None`] if the selection is not extended by units.
+    fn multi_click_selection_to(&self, location: Location) -> Option<(Location, Location)> {
+        let (unit, anchor_start, anchor_end) = self.multi_click.borrow().anchor?;
+        let (start, end) = self.unit_range_at(unit, location);
+        if start < anchor_start {
+            Some((anchor_end, start))
+        } else {
+            Some((anchor_start, end.max(anchor_end)))
+        }
+    }
+
+    /// The range of the word or the line at the location, found with the buffer transformation.
+    fn unit_range_at(&self, unit: Transform, location: Location) -> (Location, Location) {
+        let cursor = buffer::selection::Selection::new_cursor(location, default());
+        let range = self.buffer.moved_selection_region(unit, cursor, true);
+        (range.min(), range.max())
+    }
+}
+
+
+
+// ===============================
+// === Selection Drag and Drop ===
+// ===============================
+
+/// The selection pressed with the mouse. It is dragged after the mouse moves far enough from the
+/// place it was pressed at.
+#[derive(Clone, Copy, Debug)]
+struct SelectionDrag {
+    press_position: Vector2,
+    range:          buffer::Range<Location>,
+    started:        bool,
+}
+
+/// The result of releasing the mouse pressed over a selection.
+#[derive(Clone, Copy, Debug)]
+enum SelectionRelease {
+    /// The mouse did not move far enough to start the drag.
+    Click,
+    /// The selected text was dropped at the target location.
+    Drop { range: buffer::Range<Location>, target: Location, copy: bool },
+}
+
+impl TextModel {
+    /// The range of the non-empty selection at the screen position, if any.
+    fn selection_range_at(&self, screen_pos: Vector2) -> Option<buffer::Range<Location>> {
+        let location = self.screen_to_text_location(screen_pos);
+        self.buffer.selections().iter().find_map(|selection| {
+            let start = selection.start.min(selection.end);
+            let end = selection.start.max(selection.end);
+            let is_over = start != end && start <= location && location <= end;
+            is_over.then(|| buffer::Range::new(start, end))
+        })
+    }
+
+    /// Whether the mouse cursor hovers over a selection which can be dragged.
+    fn pointer_over_selection(&self) -> bool {
+        let position = self.scene.mouse.frp_deprecated.position.value();
+        self.frp.output.editable.value() && self.selection_range_at(position).is_some()
+    }
+
+    /// Remember the selection pressed at the screen position.
+    fn press_selection(&self, screen_pos: Vector2) {
+        let drag = self.selection_range_at(screen_pos).map(|range| SelectionDrag {
+            press_position: screen_pos,
+            range,
+            started: false,
+        });
+        *self.selection_drag.borrow_mut() = drag;
+    }
+
+    /// Update the drag of the pressed selection with the mouse position, showing the drop caret.
+    /// Returns `true` if the drag has just started.
+    fn drag_selection_to(&self, screen_pos: Vector2) -> bool {
+        let mut just_started = false;
+        if let Some(drag) = self.selection_drag.borrow_mut().as_mut() {
+            let distance = (screen_pos - drag.press_position).norm();
+            just_started = !drag.started && distance > SELECTION_DRAG_THRESHOLD;
+            drag.started |= just_started;
+        }
+        let started = self.selection_drag.borrow().map_or(false, |drag| drag.started);
+        if started {
+            self.show_drop_caret(self.screen_to_text_location(screen_pos));
+        }
+        just_started
+    }
+
+    /// Finish the drag of the pressed selection. Returns [`None`] if no selection was pressed.
+    fn release_selection(&self, copy: bool) -> Option<SelectionRelease> {
+        let drag = self.selection_drag.take()?;
+        self.hide_drop_caret();
+        Some(if drag.started {
+            let position = self.scene.mouse.frp_deprecated.position.value();
+            let target = self.screen_to_text_location(position);
+            SelectionRelease::Drop { range: drag.range, target, copy }
+        } else {
+            SelectionRelease::Click
+        })
+    }
+
+    /// Cancel the drag of the pressed selection. Returns `true` if the drag was started.
+    fn cancel_selection_drag(&self) -> bool {
+        let drag = self.selection_drag.take();
+        self.hide_drop_caret();
+        drag.map_or(false, |drag| drag.started)
+    }
+
+    /// Show the drop caret at the location.
+    fn show_drop_caret(&self, location: Location) {
+        let line = ViewLine::from_in_context_snapped(self, location.line);
+        let view_location = Location(line, location.offset);
+        let (position, _) = self.lines.coordinates(view_location, view_location);
+        let metrics = self.lines.borrow()[line].metrics();
+        let mut drop_caret = self.drop_caret.borrow_mut();
+        let caret = drop_caret.get_or_insert_with(|| {
+            let caret = Selection::new(&self.scene.frp.frame_time, false);
+            caret.set_caret_color(self.frp.output.caret_color.value());
+            caret.set_caret_width(self.frp.output.caret_width.value());
+            caret
+        });
+        caret.set_position_target(position);
+        caret.set_ascender(metrics.ascender);
+        caret.set_descender(metrics.descender);
+        if !caret.has_parent() {
+            self.content.add_child(caret);
+            caret.skip_position_animation();
+        }
+    }
+
+    /// Hide the drop caret, if shown.
+    fn hide_drop_caret(&self) {
+        if let Some(caret) = self.drop_caret.borrow().as_ref() {
+            caret.unset_parent();
+        }
+    }
+}
+
+
+
+// =======================
+// === IME Composition ===
+// =======================
+
+impl TextModel {
+    /// Display the in-progress IME composition after the newest cursor, replacing the previous
+    /// composition, or remove it if [`None`] is given. The composition is not recorded in the undo
+    /// history, as it is removed before the final string is inserted. Returns the range of the
+    /// displayed composition.
+    fn set_composition(&self, text: Option<&str>) -> Option<buffer::Range<Byte>> {
+        let previous = self.highlights.get(IME_COMPOSITION_HIGHLIGHT_SET);
+        let previous = previous.and_then(|set| set.ranges.first().copied());
+        let range = match previous {
+            Some(range) => range,
+            None if text.is_some() => {
+                let cursor = self.buffer.selections().newest()?.end;
+                let offset = Byte::from_in_context_snapped(&self.buffer, cursor);
+                buffer::Range::new(offset, offset)
+            }
+            None => return None,
+        };
+        let text = text.unwrap_or_default();
+        let change = enso_text::Change { range, text: Rope::from(text) };
+        self.buffer.frp.apply_changes(Rc::new(vec![change]));
+        let end = Byte(range.start.value + text.len());
+        (!text.is_empty()).then(|| buffer::Range::new(range.start, end))
+    }
+}
+
+
+
+// =====================
+// === Soft Wrapping ===
+// =====================
+
+impl TextModel {
+    /// Apply the cursor transformation to all selections, following the visual rows of the
+    /// wrapped lines. The up and down movements go to the neighboring row, keeping the x-axis
+    /// position, and the movements to the line ends go to the ends of the current row. The other
+    /// transformations, and the movements not resolvable in the visible lines, are handled by the
+    /// buffer.
+    fn moved_selection_by_rows(
+        &self,
+        transform: Transform,
+        modify: bool,
+    ) -> buffer::selection::Group {
+        let mut result = buffer::selection::Group::new();
+        for &selection in self.buffer.selections().iter() {
+            let origin = match transform {
+                Transform::Up if !modify => selection.min(),
+                Transform::Down if !modify => selection.max(),
+                _ => selection.end,
+            };
+            let new_selection = match self.location_after_row_motion(transform, origin) {
+                Some(end) => {
+                    let start = if modify { selection.start } else { end };
+                    buffer::selection::Selection(start, end, selection.id)
+                }
+                None => self.buffer.moved_selection_region(transform, selection, modify),
+            };
+            result.merge(new_selection);
+        }
+        result
+    }
+
+    /// The location after moving from the provided one by the visual rows. Returns [`None`] if the
+    /// transformation does not depend on the rows, or if the lines involved are not visible.
+    fn location_after_row_motion(
+        &self,
+        transform: Transform,
+        location: Location,
+    ) -> Option<Location> {
+        let lines = self.lines.borrow();
+        let view_line = ViewLine::try_from_in_context(&self.buffer, location.line).ok()?;
+        let line_view = lines.get(view_line)?;
+        let rows = &line_view.rows;
+        let row = rows.row_of_column(location.offset);
+        let x = line_view.position_of_column(location.offset).x;
+        let is_last_row = row + 1 == rows.count();
+        let line = location.line;
+        match transform {
+            Transform::LeftOfLine => Some(Location(line, rows.start(row))),
+            Transform::RightOfLine if !is_last_row => Some(Location(line, rows.last_column(row))),
+            Transform::Up if row > 0 => Some(Location(line, line_view.column_in_row(row - 1, x))),
+            Transform::Up => {
+                let prev_line_view = lines.get(ViewLine(view_line.value.checked_sub(1)?))?;
+                let last_row = prev_line_view.rows.count() - 1;
+                let column = prev_line_view.column_in_row(last_row, x);
+                Some(Location(Line(line.value - 1), column))
+            }
+            Transform::Down if !is_last_row =>
+                Some(Location(line, line_view.column_in_row(row + 1, x))),
+            Transform::Down => {
+                let next_line_view = lines.get(view_line + ViewLine(1))?;
+                Some(Location(line + Line(1), next_line_view.column_in_row(0, x)))
+            }
+            _ => None,
+        }
+    }
+}
+
+
+
+// ===================
+// === Line Widths ===
+// ===================
+
+impl TextModel {
+    /// Update the line widths and the other line statistics after the text changes made in this
+    /// view.
+    fn update_line_widths(&self, changes: &[buffer::Change]) {
+        let text = self.buffer.text();
+        let changes = changes.iter().map(|change| (change.change_range.clone(), change.line_diff));
+        self.line_widths.borrow_mut().apply_changes(&text, changes);
+    }
+
+    /// Measure all lines again.
+    fn reset_line_widths(&self) {
+        *self.line_widths.borrow_mut() = LineWidthIndex::new(&self.buffer.text());
+    }
+}
+
+
+
+// =======================
+// === Size Management ===
+// =======================
+
+impl TextModel {
+    fn compute_width_if_dirty(&self) -> Option<f32> {
+        self.width_dirty.get().then(|| {
+            self.width_dirty.set(false);
+            let mut max_width = 0.0;
+            for line in &*self.lines.borrow() {
+                if let Some(truncation) = &*line.truncation.borrow() {
+                    let width = truncation.max_x();
+                    if width > max_width {
+                        max_width = width;
+                    }
+                } else {
+                    let last_glyph = line.glyphs.iter().rev().find(|g| !g.attached_to_cursor.get());
+                    let width = last_glyph.map(|g| g.x() + g.x_advance.get()).unwrap_or_default();
+                    if width > max_width {
+                        max_width = width;
+                    }
+                }
+            }
+            let selection_map = self.selection_map.borrow();
+            for selection in selection_map.id_map.values() {
+                let width = selection.right_side_of_last_attached_glyph.value();
+                if width > max_width {
+                    max_width = width;
+                }
+            }
+            max_width
+        })
+    }
+
+    fn compute_height_if_dirty(&self) -> Option<f32> {
+        self.height_dirty.get().then(|| {
+            self.height_dirty.set(false);
+            let mut max_height = -self.lines.borrow().last().descent.value();
+            let selection_map = self.selection_map.borrow();
+
+            for (view_line, map) in &selection_map.location_map {
+                for selection_id in map.values() {
+                    let selection = selection_map.id_map.get(selection_id).unwrap();
+                    let baseline = selection.position.value().y;
+                    let descender = self.lines.borrow()[*view_line].metrics.value().descender;
+                    let height = -baseline - descender;
+                    if height > max_height {
+                        max_height = height;
+                    }
+                }
+            }
+            max_height
+        })
+    }
+}
+
+
+
+// ==============
+// === Gutter ===
+// ==============
+
+impl TextModel {
+    /// Set the gutter size and redraw it. Returns the gutter width, which can be larger than the
+    /// given one if the line numbers are displayed.
+    fn set_gutter_size(&self, width: f32, height: f32) -> f32 {
+        self.gutter.set_size(width, height);
+        self.redraw_gutter();
+        self.gutter.width()
+    }
+
+    fn set_line_numbers_visible(&self, visible: bool) {
+        self.gutter.set_line_numbers_visible(visible);
+        self.redraw_gutter();
+    }
+
+    fn set_line_number_font_size(&self, size: f32) {
+        self.gutter.set_line_number_font_size(size);
+        self.redraw_gutter();
+    }
+
+    /// Redraw the line numbers and the markers of the visible lines. The gutter can be resized to
+    /// fit the line numbers, in which case the [`Output::gutter_width`] is emitted.
+    fn redraw_gutter(&self) {
+        let width_changed = {
+            let lines = self.lines.borrow();
+            let baselines = lines.iter().enumerate().map(|(index, line)| {
+                (Line::from_in_context_snapped(self, ViewLine(index)), line.baseline())
             });
-            prev_chunk_cluster_byte_offset = range.end.value as u32;
+            self.gutter.redraw_line_numbers(baselines)
+        };
+        if width_changed {
+            self.frp.private.output.gutter_width.emit(self.gutter.width());
+        }
+        self.redraw_gutter_markers();
+    }
+
+    /// The line whose number is displayed in the gutter at the screen position, if any.
+    fn line_number_at(&self, screen_pos: Vector2) -> Option<Line> {
+        let object_space = self.screen_to_object_space(screen_pos);
+        let is_over_numbers = self.gutter.is_over_line_numbers(object_space.x);
+        is_over_numbers.then(|| self.screen_to_text_location(screen_pos).line)
+    }
+
+    /// The selection covering the whole line, including its line break.
+    fn line_selection(&self, line: Line) -> (LocationLike, LocationLike) {
+        let start = self.buffer.line_offset_snapped(line);
+        let end = if line < self.buffer.last_line_index() {
+            self.buffer.line_offset_snapped(Line(line.value + 1))
+        } else {
+            self.buffer.line_end_offset_snapped(line)
+        };
+        (start.into(), end.into())
+    }
+
+    /// Keep the gutter markers anchored to their lines after the buffer changes. Returns [`true`]
+    /// if any marker was moved.
+    fn move_line_markers_after_changes(&self, changes: &[buffer::Change]) -> bool {
+        let mut markers = self.gutter.markers.borrow_mut();
+        changes.iter().fold(false, |moved, change| markers.apply_change(change) || moved)
+    }
+
+    /// Redraw the gutter markers of the visible lines.
+    fn redraw_gutter_markers(&self) {
+        let lines = self.lines.borrow();
+        self.gutter.redraw_markers(|line| {
+            let view_line = ViewLine::try_from_in_context(&self.buffer, line).ok()?;
+            let line = lines.get(view_line)?;
+            let metrics = line.metrics();
+            Some(line.baseline() + (metrics.ascender + metrics.descender) / 2.0)
+        });
+    }
+}
+
+
+
+// ==============================
+// === Whitespace Diagnostics ===
+// ==============================
+
+impl TextModel {
+    /// The text of the line without the line ending, if the line exists.
+    fn line_text(&self, line: Line) -> Option<String> {
+        self.buffer.line_range(line).ok().map(|range| self.buffer.sub(range).to_string())
+    }
+
+    /// Inspect all lines again if the diagnostics are enabled, or remove all issues otherwise.
+    /// Returns [`true`] if the issues have changed.
+    fn reset_whitespace_issues(&self, enabled: bool) -> bool {
+        let mut issues = self.whitespace.issues.borrow_mut();
+        let old_issues = mem::take(&mut *issues);
+        if enabled {
+            for line in (0..=self.buffer.last_line_index().value).map(Line) {
+                issues.update_line(line, self.line_text(line).as_deref());
+            }
+        }
+        *issues != old_issues
+    }
+
+    /// Inspect the changed lines again. Returns [`true`] if the issues have changed.
+    fn update_whitespace_issues(&self, changes: &[buffer::Change]) -> bool {
+        let mut issues = self.whitespace.issues.borrow_mut();
+        let old_issues = issues.clone();
+        issues.apply_changes(changes, |line| self.line_text(line));
+        *issues != old_issues
+    }
+
+    /// All whitespace issues with ranges expressed in the text byte offsets.
+    fn whitespace_issues(&self) -> Rc<Vec<whitespace::Issue>> {
+        let issues = self.whitespace.issues.borrow();
+        let issues = issues.iter().flat_map(|(line, issues)| {
+            let line_offset = self.buffer.line_offset_snapped(line);
+            issues.iter().map(move |(kind, range)| {
+                let range = buffer::Range::new(line_offset + range.start, line_offset + range.end);
+                whitespace::Issue { kind: *kind, range }
+            })
+        });
+        Rc::new(issues.collect())
+    }
+
+    /// Redraw the whitespace issues of the visible lines.
+    fn redraw_whitespace_issues(&self) {
+        let lines = self.lines.borrow();
+        self.whitespace.redraw(|line, range| self.line_range_rect(&lines, line, range));
+    }
+}
+
+
+
+// ===================
+// === Annotations ===
+// ===================
+
+impl TextModel {
+    fn import_annotations(&self, document: &annotation::Document) {
+        *self.annotations.document.borrow_mut() = document.clone();
+        self.crop_annotations();
+    }
+
+    /// Move the annotations to keep them in place after the text changes.
+    fn update_annotations(&self, changes: &[buffer::Change]) {
+        self.annotations.document.borrow_mut().apply_changes(changes);
+        self.redraw_annotations();
+    }
+
+    /// Crop the annotation ranges to the text.
+    /// Crop the ranges attached to the text to the text restored by the undo or the redo. The
+    /// history replaces the whole text without reporting the changes, so the ranges can not follow
+    /// the edits, and are only cropped to the new text.
+    fn crop_to_restored_text(&self) {
+        self.crop_annotations();
+        self.crop_highlight_sets();
+        self.crop_tokens();
+    }
+
+    fn crop_annotations(&self) {
+        for annotation in &mut self.annotations.document.borrow_mut().annotations {
+            annotation.set_range(self.buffer.crop_byte_range(annotation.range()));
         }
-        glyph_sets
+        self.redraw_annotations();
     }
 
-    /// Recompute the shape of the provided line index.
-    #[profile(Debug)]
-    pub fn shape_line(&self, line: Line) -> ShapedLine {
-        let line_range = self.buffer.line_range_snapped(line);
-        let glyph_sets = self.shape_range(line_range.clone());
-        match NonEmptyVec::try_from(glyph_sets) {
-            Ok(glyph_sets) => ShapedLine::NonEmpty { glyph_sets },
-            Err(_) => {
-                if let Some(prev_grapheme_off) =
-                    self.buffer.rope.prev_grapheme_offset(line_range.start)
-                {
-                    let prev_char_range = prev_grapheme_off..line_range.start;
-                    let prev_glyph_sets = self.shape_range(prev_char_range);
-                    let last_glyph_set = prev_glyph_sets.into_iter().last();
-                    let prev_glyph_info = last_glyph_set.map(|t| (prev_grapheme_off, t));
-                    ShapedLine::Empty { prev_glyph_info }
-                } else {
-                    ShapedLine::Empty { prev_glyph_info: None }
-                }
+    fn annotations(&self) -> Rc<annotation::Document> {
+        Rc::new(self.annotations.document.borrow().clone())
+    }
+
+    /// Redraw the backgrounds of the annotations in the visible lines.
+    fn redraw_annotations(&self) {
+        self.annotations.redraw(|range| self.range_rects(range));
+    }
+
+    /// Crop the ranges of the highlight sets to the text.
+    fn crop_highlight_sets(&self) {
+        self.highlights.map_ranges(|range| self.buffer.crop_byte_range(range));
+    }
+
+    /// Redraw the backgrounds of the highlight sets in the visible lines.
+    fn redraw_highlight_sets(&self) {
+        self.highlights.redraw(|range| self.range_rects(range));
+    }
+}
+
+
+
+// ====================
+// === Token Styles ===
+// ====================
+
+impl TextModel {
+    fn set_theme_token_format(&self, kind: &str, format: formatting::Format) {
+        let mut palette = self.theme_token_palette.borrow_mut();
+        *palette = palette.with(kind, format);
+    }
+
+    /// Move the tokens to keep them in place after the text changes.
+    fn move_tokens(&self, changes: &[buffer::Change]) {
+        let mut tokens = self.tokens.borrow_mut();
+        for change in changes {
+            tokens.apply_change(change);
+        }
+    }
+
+    fn crop_tokens(&self) {
+        self.tokens.borrow_mut().map_ranges(|range| self.buffer.crop_byte_range(range));
+    }
+
+    /// The properties to set to format the tokens with the palette.
+    fn restyle_tokens(&self, palette: &token_style::Palette) -> Vec<token_style::RangesProperty> {
+        self.tokens.borrow_mut().restyle(palette)
+    }
+}
+
+
+
+// ==================
+// === Pagination ===
+// ==================
+
+impl TextModel {
+    /// The heights of all lines of the text, including the spacing above them, measured by
+    /// shaping the lines.
+    fn measured_line_heights(&self) -> Vec<f32> {
+        let line_count = self.buffer.last_line_index().value + 1;
+        (0..line_count).map(|line| self.measured_line_height(Line(line))).collect()
+    }
+
+    fn measured_line_height(&self, line: Line) -> f32 {
+        match self.shaped_line_metrics(line) {
+            Some(metrics) => {
+                let spacing = metrics.gap + self.extra_line_spacing(&metrics);
+                metrics.ascender - metrics.descender + spacing
             }
+            None => self.default_line_height(),
         }
     }
 
-    /// Return list of spans for different [`NonVariableFaceHeader`]. The result will be aligned
-    /// with grapheme cluster boundaries. If the face header changes inside a grapheme cluster, the
-    /// cluster will be associated with the header it starts with.
-    pub fn chunks_per_font_face<'a>(
-        font: &'a Font,
-        line_style: &'a Formatting,
-        rope: &'a Rope,
-    ) -> impl Iterator<Item = (Range<Byte>, NonVariableFaceHeader)> + 'a {
-        gen_iter!(move {
-            match font {
-                Font::NonVariable(_) =>
-                    for chunk in line_style.chunks_per_font_face(rope) {
-                        yield chunk;
-                    }
-                Font::Variable(_) => {
-                    let range = Byte(0)..rope.last_byte_index();
-                    // For variable fonts, we do not care about non-variable variations.
-                    let non_variable_variations = NonVariableFaceHeader::default();
-                    yield (range, non_variable_variations);
-                }
+    /// The metrics of the shaped line, computed the same way as when the line is displayed.
+    fn shaped_line_metrics(&self, line: Line) -> Option<line::Metrics> {
+        let font_size_at = |offset: Byte| {
+            let font_sizes = self.buffer.sub_style(offset..offset + Byte(1)).font_size;
+            font_sizes.to_vector().first().map_or(font_sizes.default.value, |span| span.value.value)
+        };
+        let metrics_of = |glyph_set: &ShapedGlyphSet, font_size: f32| {
+            let scale = glyph_set.units_per_em as f32 / font_size;
+            let ascender = glyph_set.ascender as f32 / scale;
+            let descender = glyph_set.descender as f32 / scale;
+            let gap = glyph_set.line_gap as f32 / scale;
+            line::Metrics { ascender, descender, gap }
+        };
+        let line_offset = self.buffer.line_offset_snapped(line);
+        match self.shape_line(line) {
+            ShapedLine::NonEmpty { glyph_sets } => {
+                let glyphs =
+                    glyph_sets.iter().flat_map(|set| set.glyphs.iter().map(move |g| (set, g)));
+                glyphs.fold(None, |metrics, (set, glyph)| {
+                    let font_size = font_size_at(line_offset + glyph.start_byte());
+                    metrics.concat(Some(metrics_of(set, font_size)))
+                })
             }
-        })
+            ShapedLine::Empty { prev_glyph_info } => prev_glyph_info
+                .map(|(offset, glyph_set)| metrics_of(&glyph_set, font_size_at(offset))),
+        }
     }
 }
 
 
 
-// =============================
-// === Redrawing And Updates ===
-// =============================
+// ==================
+// === DOM Mirror ===
+// ==================
 
 impl TextModel {
-    /// Apply the changes to the text buffer and update the lines.
-    #[profile(Debug)]
-    fn on_modified_selection(
-        &self,
-        buffer_selections: &buffer::selection::Group,
-        changes: Option<&[buffer::Change]>,
-    ) {
-        let do_edit = changes.is_some();
-        self.update_lines_after_change(changes);
-        self.replace_selections(do_edit, buffer_selections);
-        if do_edit {
-            self.attach_glyphs_to_cursors();
+    fn set_dom_mirror_mode(&self, mode: dom_mirror::Mode) {
+        if mode == dom_mirror::Mode::Disabled {
+            self.dom_mirror.take();
+            return;
         }
+        let mut mirror = self.dom_mirror.borrow_mut();
+        let mirror = mirror.get_or_insert_with(|| {
+            let mirror = dom_mirror::Mirror::new();
+            self.scene.dom.layers.front.manage(mirror.symbol());
+            self.display_object.add_child(&mirror);
+            mirror.set_text(&self.buffer.text());
+            mirror
+        });
+        mirror.set_mode(mode);
     }
 
-    /// Implementation of lazy line redrawing. After a change, only the needed lines are redrawn.
-    /// If a change produced more lines than the current number of lines, the new lines are inserted
-    /// in appropriate positions. If a change produces fewer lines than the current number of lines,
-    /// appropriate lines are removed. Then, a minimal line redraw range is computed and performed.
-    ///
-    /// # Smooth animations
-    /// This function also attaches unchanged lines below a cursor to the cursor for smooth vertical
-    /// animation. Please note that when cursors are moved in a non-edit mode (e.g. after pressing
-    /// an arrow key), the animations are skipped. This is a performance optimization. If we would
-    /// like to continue the animations, we would need to either attach animation system to every
-    /// glyph, or create multiple, possibly hierarchical "virtual cursors". The animations are so
-    /// fast that this is barely noticeable.
-    ///
-    /// # Possible future optimizations.
-    /// This section describes possible future optimizations that are not implemented now because
-    /// of their complexity and/or lack of use cases.
-    ///
-    /// ## Limiting the number of glyphs per line.
-    /// Currently, nothing limits the number of glyphs in line. They are computed and displayed even
-    /// if they are not visible on the screen.
-    ///
-    /// ## Redrawing parts of lines only.
-    /// Currently, the whole line is redrawn after any change. This is not optimal, especially for
-    /// lines containing a lot of visible characters. However, redrawing only parts of a changed
-    /// line is way more complex than it seems. Let's consider the input `அட0`. If we insert `்`
-    /// after `ட`, then we should get `ட்` instead of `ட`, but we do not need to redraw neither
-    /// `அ` nor `0`. Inserting a new code point can affect any number of code points to the left
-    /// and to the right of the insertion point. Unfortunately, the current Rustybuzz
-    /// implementation does not support such use cases:
-    /// https://github.com/RazrFalcon/rustybuzz/issues/54
-    #[profile(Debug)]
-    fn update_lines_after_change(&self, changes: Option<&[buffer::Change]>) {
-        debug_span!("update_lines_after_change").in_scope(|| {
-            self.detach_glyphs_from_cursors();
-            if let Some(changes) = changes {
-                let view_line_range = self.buffer.view_line_range();
-                let lines_to_redraw = changes
-                    .iter()
-                    .filter_map(|change_with_selection| {
-                        // Updating shaped lines cache.
-                        let line_diff = change_with_selection.line_diff;
-                        let redraw_start_line = *change_with_selection.change_range.start();
-                        let redraw_end_line = *change_with_selection.change_range.end();
-                        if line_diff != LineDiff(0) {
-                            let mut shaped_lines = self.shaped_lines.borrow_mut();
-                            let to_update = shaped_lines.drain_filter(|l, _| *l > redraw_end_line);
-                            let updated = to_update.map(|(l, s)| (l + line_diff, s)).collect_vec();
-                            shaped_lines.extend(updated);
-                        }
-                        let redraw_range_end = (redraw_end_line + line_diff).value;
-                        let redraw_range = redraw_start_line.value..=redraw_range_end;
-                        for line in redraw_range {
-                            let line = Line(line);
-                            self.shaped_lines.borrow_mut().remove(&line);
-                        }
+    /// Update the mirrored lines after the text changes made in this view.
+    fn update_dom_mirror(&self, changes: &[buffer::Change]) {
+        if let Some(mirror) = &*self.dom_mirror.borrow() {
+            let changes =
+                changes.iter().map(|change| (change.change_range.clone(), change.line_diff));
+            mirror.apply_changes(&self.buffer.text(), changes);
+        }
+    }
 
-                        // Updating lines.
-                        let change_start = change_with_selection.selection.start.line;
-                        let change_end = change_with_selection.selection.end.line;
-                        let view_change_range = change_start..=change_end;
-                        let first_line_index = *view_change_range.start();
-                        let second_line_index = first_line_index.inc();
-                        let mut lines = self.lines.borrow_mut();
-                        let first_line_baseline = lines[first_line_index].baseline();
+    fn reset_dom_mirror(&self) {
+        if let Some(mirror) = &*self.dom_mirror.borrow() {
+            mirror.set_text(&self.buffer.text());
+        }
+    }
 
-                        #[allow(clippy::comparison_chain)]
-                        if line_diff > LineDiff(0) {
-                            // Add missing lines. They will be redrawn later. This is needed for
-                            // proper partial redraw (redrawing only the lines that changed).
-                            let new_lines = iter::from_fn(|| {
-                                let new_line = self.new_line();
-                                new_line.set_baseline(first_line_baseline);
-                                new_line.skip_baseline_animation();
-                                Some(new_line)
-                            });
-                            let new_lines = new_lines.take(line_diff.value as usize);
-                            if second_line_index < ViewLine(lines.len()) {
-                                lines.extend_at(second_line_index, new_lines);
-                            } else {
-                                lines.extend(new_lines);
-                            }
-                        } else if line_diff < LineDiff(0) {
-                            // Remove lines that are no longer needed. This is needed for proper
-                            // partial redraw (redrawing only the lines that changed).
-                            let line_diff = -line_diff.value as usize;
-                            let line_diff = ViewLine(line_diff);
-                            lines.drain(second_line_index..second_line_index + line_diff);
-                        }
+    fn dom_mirror_layout(&self, size: Vector2) -> dom_mirror::Layout {
+        let font_size = self.buffer.formatting.font_size().default.value;
+        let line_height = self.default_line_height();
+        let first_view_line = self.buffer.first_view_line();
+        dom_mirror::Layout { size, font_size, line_height, first_view_line }
+    }
 
-                        let range_end = view_change_range.end() + line_diff;
-                        let range = (*view_change_range.start())..=range_end;
-                        range.intersect(&view_line_range)
-                    })
-                    .collect_vec();
+    fn set_dom_mirror_layout(&self, layout: dom_mirror::Layout) {
+        if let Some(mirror) = &*self.dom_mirror.borrow() {
+            mirror.set_layout(layout);
+        }
+    }
+}
 
-                let lines_to_redraw = std_ext::range::merge_overlapping_ranges(lines_to_redraw);
-                self.redraw_sorted_line_ranges(lines_to_redraw);
+
+
+// ==============
+// === Layers ===
+// ==============
+
+impl TextModel {
+    fn set_overlay_layer(&self, layer: &Option<WeakLayer>) {
+        match layer.as_ref().and_then(|layer| layer.upgrade()) {
+            Some(layer) => self.layer_promotion.promote(&layer),
+            None => self.layer_promotion.demote(),
+        }
+    }
+}
+
+
+
+// ==================
+// === Navigation ===
+// ==================
+
+impl TextModel {
+    /// The display object of the newest cursor.
+    fn caret(&self) -> Option<display::object::Instance> {
+        let newest = self.buffer.selections().newest().map(|s| s.id)?;
+        let selection_map = self.selection_map.borrow();
+        selection_map.id_map.get(&newest).map(|s| s.display_object().clone())
+    }
+
+    /// The line of the newest cursor.
+    fn cursor_line(&self) -> Line {
+        self.buffer.selections().newest().map(|s| s.end.line).unwrap_or_default()
+    }
+
+    /// The first view line needed to make the provided line visible. Returns [`None`] if the line
+    /// is already visible and should not be centered.
+    fn first_view_line_showing(&self, line: Line, centered: bool) -> Option<Line> {
+        let first_view_line = self.buffer.first_view_line();
+        let new_first_view_line = match self.buffer.view_line_limit() {
+            None => std::cmp::min(first_view_line, line),
+            Some(count) if centered => Line(line.value.saturating_sub(count / 2)),
+            Some(count) if line.value >= first_view_line.value + count =>
+                Line(line.value + 1 - count),
+            Some(_) => std::cmp::min(first_view_line, line),
+        };
+        (new_first_view_line != first_view_line).then_some(new_first_view_line)
+    }
+
+    /// The first match of the query after the newest selection. See [`Input::find_next`].
+    fn next_search_match(&self, query: &buffer::Query) -> Option<buffer::Range<Byte>> {
+        let from = self.buffer.selections().newest().map(|s| s.max()).unwrap_or_default();
+        let from = Byte::from_in_context_snapped(&self.buffer, from);
+        self.buffer.find(query, from)
+    }
+
+    /// The last match of the query before the newest selection. See [`Input::find_prev`].
+    fn prev_search_match(&self, query: &buffer::Query) -> Option<buffer::Range<Byte>> {
+        let before = self.buffer.selections().newest().map(|s| s.min()).unwrap_or_default();
+        let before = Byte::from_in_context_snapped(&self.buffer, before);
+        self.buffer.find_before(query, before)
+    }
+
+    /// Replace the newest selection with the text, if the selection is a match of the query.
+    /// Returns the number of replaced matches. See [`Input::replace_next`].
+    fn replace_selected_match(&self, query: &buffer::Query, text: &str) -> usize {
+        let Some(selection) = self.buffer.selections().newest().copied() else { return 0 };
+        let start = Byte::from_in_context_snapped(&self.buffer, selection.min());
+        let end = Byte::from_in_context_snapped(&self.buffer, selection.max());
+        let range = buffer::Range::new(start, end);
+        let is_match = self.buffer.find_all(query).contains(&range);
+        if is_match {
+            self.replace_ranges(&[range], text)
+        } else {
+            0
+        }
+    }
+
+    /// Replace all matches of the query with the text. Returns the number of replaced matches.
+    /// See [`Input::replace_all`].
+    fn replace_all_matches(&self, query: &buffer::Query, text: &str) -> usize {
+        self.replace_ranges(&self.buffer.find_all(query), text)
+    }
+
+    /// Replace the sorted, non-overlapping ranges with the text as a single undoable modification.
+    /// Returns the number of replaced ranges.
+    fn replace_ranges(&self, ranges: &[buffer::Range<Byte>], text: &str) -> usize {
+        if !ranges.is_empty() {
+            // The ranges are replaced starting with the last one, so the ranges before it are not
+            // moved by the replacement.
+            let text = Rope::from(text);
+            let changes = ranges.iter().rev().map(|range| {
+                let range = *range;
+                enso_text::Change { range, text: text.clone() }
+            });
+            self.buffer.frp.apply_recorded_changes(Rc::new(changes.collect()));
+        }
+        ranges.len()
+    }
+
+    fn place_go_to_line_highlight(&self, line: Line) {
+        let lines = self.lines.borrow();
+        let view_line = ViewLine::try_from_in_context(&self.buffer, line).ok();
+        if let Some(line) = view_line.and_then(|view_line| lines.get(view_line)) {
+            let metrics = line.metrics();
+            let width = self.frp.output.width.value().max(GO_TO_LINE_HIGHLIGHT_MIN_WIDTH);
+            let height = metrics.ascender - metrics.descender;
+            self.line_highlight.set_xy((0.0, line.baseline() + metrics.descender));
+            self.line_highlight.set_size((width, height));
+        }
+    }
+
+    fn set_go_to_line_highlight_alpha(&self, alpha: f32) {
+        self.line_highlight.set_color(self.line_highlight_color.get().multiply_alpha(alpha));
+    }
+}
+
+
+
+// ==================
+// === Operations ===
+// ==================
+
+impl TextModel {
+    /// Paste new text in the place of current selections / cursors. In case of pasting multiple
+    /// chunks (e.g. after copying multiple selections), the chunks will be pasted into subsequent
+    /// selections. In case there are more chunks than selections, end chunks will be dropped. In
+    /// case there is more selections than chunks, end selections will be replaced with empty
+    /// strings. I `self.single_line` is set to true then each chunk will be truncated to its first
+    /// line.
+    fn paste_chunks(&self, mut chunks: Vec<String>) {
+        if self.frp.output.single_line_mode.value() {
+            for f in &mut chunks {
+                Self::drop_all_but_first_line(f);
             }
-        })
+        }
+        self.buffer.frp.paste(chunks);
     }
 
-    /// Update selection positions. This is needed e.g. if the selected glyph size changed.
-    fn update_selections(&self) {
-        let buffer_selections = self.buffer.selections();
-        self.replace_selections(false, &buffer_selections);
+    fn drop_all_but_first_line(s: &mut String) {
+        *s = s.lines().next().unwrap_or("").to_string();
     }
 
-    /// Replace selections with new ones.
-    #[profile(Debug)]
-    fn replace_selections(&self, do_edit: bool, buffer_selections: &buffer::selection::Group) {
-        let mut new_selection_map = SelectionMap::default();
-        for buffer_selection in buffer_selections {
-            let buffer_selection = self.limit_selection_to_known_values(*buffer_selection);
-            let id = buffer_selection.id;
-            let selection_start_line =
-                ViewLine::from_in_context_snapped(self, buffer_selection.start.line);
-            let selection_end_line =
-                ViewLine::from_in_context_snapped(self, buffer_selection.end.line);
-            let start_location = Location(selection_start_line, buffer_selection.start.offset);
-            let end_location = Location(selection_end_line, buffer_selection.end.offset);
-            let (start_pos, end_pos) = self.lines.coordinates(start_location, end_location);
-            let width = end_pos.x - start_pos.x;
-            // FIXME[WD]: This does not work nicely for multi-line selection.
-            //     See: https://www.pivotaltracker.com/story/show/183691214.
-            let metrics = self.lines.borrow()[selection_start_line].metrics();
-            let prev_selection = self.selection_map.borrow_mut().id_map.remove(&id);
-            let reused_selection = prev_selection.is_some();
-            let selection = if let Some(selection) = prev_selection {
-                selection.set_width_and_flip_sides_if_needed(width, start_pos.x);
-                selection
+    /// Whether any selection covers more than a single line.
+    fn selections_span_lines(&self) -> bool {
+        self.buffer.selections().iter().any(|selection| selection.start.line != selection.end.line)
+    }
+
+    /// The text inserted at every selection by [`Input::insert_tab`], in the selections order.
+    fn tab_insertions(&self, insert_spaces: bool) -> Rc<Vec<String>> {
+        let tab_size = self.frp.output.tab_size.value();
+        let insertions = self.buffer.byte_selections().into_iter().map(|selection| {
+            if insert_spaces {
+                let start = std::cmp::min(selection.start, selection.end);
+                let line_start = self.buffer.line_offset_snapped(self.buffer.line_snapped(start));
+                let prefix = self.buffer.rope.sub(line_start..start).to_string();
+                " ".repeat(spaces_to_tab_stop(&prefix, tab_size))
             } else {
-                let frame_time = &self.scene.frp.frame_time;
-                let selection = Selection::new(frame_time, do_edit);
-                let network = selection.network();
-                let out = &self.frp.private.output;
-                frp::extend! { network
-                    out.refresh_height <+_ selection.position;
-                    out.refresh_width <+_ selection.right_side_of_last_attached_glyph;
-                }
-                self.add_child(&selection);
-                selection.set_color(self.frp.output.selection_color.value());
-                selection.set_width_target(width);
-                selection
-            };
-            selection.set_position_target(start_pos);
-            selection.set_ascender(metrics.ascender);
-            selection.set_descender(metrics.descender);
-            selection.edit_mode().set(do_edit);
-            if !reused_selection {
-                selection.skip_position_animation();
+                "\t".to_owned()
             }
-            new_selection_map.id_map.insert(id, selection);
-            let loc_map = new_selection_map.location_map.entry(selection_start_line).or_default();
-            loc_map.insert(buffer_selection.start.offset, id);
+        });
+        Rc::new(insertions.collect())
+    }
+
+    fn process_key_event(
+        event: &ensogl_core::event::Event<KeyDown>,
+        single_line_mode: &bool,
+    ) -> Option<ImString> {
+        let check_modifiers = (!event.ctrl() && !event.meta()).then_some(());
+        let text =
+            check_modifiers.and_then(|_| Self::key_to_string(event.key(), *single_line_mode));
+        if text.is_some() {
+            event.stop_propagation();
         }
-        *self.selection_map.borrow_mut() = new_selection_map;
+        text
     }
 
-    /// Constrain the selection to values fitting inside the current text buffer. This can be needed
-    /// when using the API and providing invalid values.
-    fn limit_selection_to_known_values(&self, selection: buffer::Selection) -> buffer::Selection {
-        let start_location = Location::from_in_context_snapped(&self.buffer, selection.start);
-        let end_location = Location::from_in_context_snapped(&self.buffer, selection.end);
-        let start = self.buffer.snap_location(start_location);
-        let end = self.buffer.snap_location(end_location);
-        let start = Location::from_in_context_snapped(&self.buffer, start);
-        let end = Location::from_in_context_snapped(&self.buffer, end);
-        selection.with_start(start).with_end(end)
+    fn key_to_string(key: &Key, single_line_mode: bool) -> Option<ImString> {
+        match key {
+            Key::Character(s) => Some(s.into()),
+            Key::Enter if !single_line_mode => Some("\n".into()),
+            Key::Space => Some(" ".into()),
+            _ => None,
+        }
     }
+}
 
-    /// Resize lines vector to contain the required lines count.
-    fn resize_lines(&self) {
-        let line_count = self.buffer.view_line_count();
-        self.lines.resize_with(line_count, |_| self.new_line());
+
+
+// ==============
+// === Traits ===
+// ==============
+
+impl<S, T> FromInContextSnapped<&TextModel, S> for T
+where T: for<'t> FromInContextSnapped<&'t buffer::Buffer, S>
+{
+    fn from_in_context_snapped(context: &TextModel, arg: S) -> Self {
+        T::from_in_context_snapped(&context.buffer, arg)
     }
+}
 
-    /// Clean all the glyph shape caches and redraw all the text. This function should be used only
-    /// when necessary as it is very costly.
-    #[profile(Debug)]
-    pub fn redraw(&self) {
-        self.clear_shaped_lines_cache();
-        let end = ViewLine::try_from_in_context(&self.buffer, self.buffer.last_view_line());
-        // FIXME: Unwrap used here. To be fixed when view area will be implemented properly.
-        let end = end.unwrap();
-        self.detach_glyphs_from_cursors();
-        self.redraw_sorted_line_ranges(std::iter::once(ViewLine(0)..=end));
-        self.update_selections();
+impl<S, T> FromInContextSnapped<&Text, S> for T
+where T: for<'t> FromInContextSnapped<&'t TextModel, S>
+{
+    fn from_in_context_snapped(context: &Text, arg: S) -> Self {
+        T::from_in_context_snapped(&context.data, arg)
     }
+}
 
-    /// Redraw the given line ranges.
-    #[profile(Debug)]
-    fn redraw_sorted_line_ranges(
-        &self,
-        sorted_line_ranges: impl Iterator<Item = RangeInclusive<ViewLine>>,
-    ) {
-        self.resize_lines();
-        self.width_dirty.set(true);
-        let sorted_line_ranges = sorted_line_ranges.inspect(|range| {
-            for line in range.clone() {
-                self.redraw_line(line);
-            }
-        });
-        self.position_sorted_line_ranges(sorted_line_ranges);
+impl FrpNetworkProvider for Text {
+    fn network(&self) -> &frp::Network {
+        self.frp.network()
     }
+}
 
-    /// Redraw the line. This will re-position all line glyphs.
-    #[profile(Debug)]
-    fn redraw_line(&self, view_line: ViewLine) {
-        let line = &mut self.lines.borrow_mut()[view_line];
-        let default_divs = || NonEmptyVec::singleton(0.0);
-        let mut divs = default_divs();
-        let mut column = Column(0);
-        let mut to_be_truncated = 0;
-        let mut truncated = false;
-        let default_size = self.buffer.formatting.font_size().default;
-        let line_index = Line::from_in_context_snapped(self, view_line);
-        self.with_shaped_line(line_index, |shaped_line| {
-            match shaped_line {
-                ShapedLine::NonEmpty { glyph_sets } => {
-                    let glyph_system = self.glyph_system.borrow();
-                    let view_width = self.frp.output.view_width.value();
-                    let long_text_truncation_mode =
-                        self.frp.output.long_text_truncation_mode.value();
-                    let line_range = self.buffer.byte_range_of_view_line_index_snapped(view_line);
-                    let line_style = self.buffer.sub_style(line_range.start..line_range.end);
-                    let mut line_style_iter = line_style.iter_bytes();
-                    let mut glyph_offset_x = 0.0;
-                    let mut prev_cluster_byte_off = Byte(0);
-                    let truncation_size = line::TruncationSize::from(default_size);
-                    let ellipsis_width = truncation_size.width_with_text_offset();
-                    let mut line_metrics = None;
-                    for shaped_glyph_set in glyph_sets {
-                        if truncated {
-                            break;
-                        }
-                        // FIXME[WD]: This is a workaround for a bug in the MSDFgen binding. It
-                        //     should be fixed after updating the MSDFgen library.
-                        //     See: https://www.pivotaltracker.com/n/projects/2539304/stories/183747513
-                        let magic_scale = 2048.0 / shaped_glyph_set.units_per_em as f32;
-                        for shaped_glyph in &shaped_glyph_set.glyphs {
-                            let glyph_byte_start = shaped_glyph.start_byte();
-                            // Drop styles assigned to skipped bytes. One byte will be skipped
-                            // during the call to `line_style_iter.next()`.
-                            let cluster_diff =
-                                glyph_byte_start - prev_cluster_byte_off - ByteDiff(1);
-                            let cluster_diff = Byte::try_from(cluster_diff).unwrap_or_default();
-                            line_style_iter.skip_bytes(cluster_diff);
-                            let style = line_style_iter.next().unwrap_or_default();
-                            prev_cluster_byte_off = glyph_byte_start;
 
-                            let scale =
-                                shaped_glyph_set.units_per_em as f32 / style.font_size.value;
-                            let ascender = shaped_glyph_set.ascender as f32 / scale;
-                            let descender = shaped_glyph_set.descender as f32 / scale;
-                            let gap = shaped_glyph_set.line_gap as f32 / scale;
-                            let x_advance = shaped_glyph.position.x_advance as f32 / scale;
-                            let glyph_rhs = glyph_offset_x + x_advance;
 
-                            if long_text_truncation_mode {
-                                if let Some(view_width) = view_width {
-                                    if glyph_rhs > view_width {
-                                        truncated = true;
-                                        break;
-                                    } else if glyph_rhs > view_width - ellipsis_width {
-                                        to_be_truncated += 1;
-                                    }
-                                };
-                            }
+// ================
+// === App View ===
+// ================
+
+impl application::View for Text {
+    fn label() -> &'static str {
+        "TextArea"
+    }
+
+    fn new(app: &Application) -> Self {
+        Text::new(app)
+    }
+
+    fn global_shortcuts() -> Vec<shortcut::Shortcut> {
+        use shortcut::ActionType::*;
+        // These shortcuts emit `focus` event when triggered.
+        let focus_capturing_shortcuts = [
+            (PressAndRepeat, "left", "cursor_move_left", ""),
+            (PressAndRepeat, "right", "cursor_move_right", ""),
+            (PressAndRepeat, "up", "cursor_move_up", "!single_line_mode"),
+            (PressAndRepeat, "down", "cursor_move_down", "!single_line_mode"),
+            (PressAndRepeat, "cmd left", "cursor_move_left_word", ""),
+            (PressAndRepeat, "cmd right", "cursor_move_right_word", ""),
+            (Press, "alt left", "cursor_move_left_of_line", ""),
+            (Press, "alt right", "cursor_move_right_of_line", ""),
+            (Press, "home", "cursor_move_left_of_line", ""),
+            (Press, "end", "cursor_move_right_of_line", ""),
+            (Press, "alt shift left", "cursor_select_left_of_line", ""),
+            (Press, "alt shift right", "cursor_select_right_of_line", ""),
+            (Press, "shift home", "cursor_select_left_of_line", ""),
+            (Press, "shift end", "cursor_select_right_of_line", ""),
+            (Press, "cmd up", "cursor_move_to_text_start", ""),
+            (Press, "cmd down", "cursor_move_to_text_end", ""),
+            (Press, "ctrl home", "cursor_move_to_text_start", ""),
+            (Press, "ctrl end", "cursor_move_to_text_end", ""),
+            (Press, "cmd shift up", "cursor_select_to_text_start", ""),
+            (Press, "cmd shift down", "cursor_select_to_text_end", ""),
+            (Press, "ctrl shift home", "cursor_select_to_text_start", ""),
+            (Press, "ctrl shift end", "cursor_select_to_text_end", ""),
+            (PressAndRepeat, "shift left", "cursor_select_left", ""),
+            (PressAndRepeat, "shift right", "cursor_select_right", ""),
+            (PressAndRepeat, "cmd shift left", "cursor_select_left_word", ""),
+            (PressAndRepeat, "cmd shift right", "cursor_select_right_word", ""),
+            (PressAndRepeat, "shift up", "cursor_select_up", "!single_line_mode"),
+            (PressAndRepeat, "shift down", "cursor_select_down", "!single_line_mode"),
+            (Press, "shift left-mouse-button", "set_newest_selection_end_to_mouse_position", ""),
+            (Press, "left-mouse-button", "set_cursor_at_mouse_position", "!pointer_over_selection"),
+            (
+                Press,
+                "left-mouse-button",
+                "start_newest_selection_end_follow_mouse",
+                "!pointer_over_selection",
+            ),
+            (Press, "left-mouse-button", "start_selection_drag", "pointer_over_selection"),
+            (Press, "alt left-mouse-button", "start_selection_drag", "pointer_over_selection"),
+            (Press, "cmd left-mouse-button", "add_cursor_at_mouse_position", ""),
+            (Press, "cmd left-mouse-button", "start_newest_selection_end_follow_mouse", ""),
+            (Press, "cmd a", "select_all", ""),
+        ];
+        let non_focus_capturing_shortcuts = [
+            (Press, "cmd c", "copy", ""),
+            (Press, "cmd x", "cut", "editable"),
+            (Press, "cmd v", "paste", "editable"),
+            (Press, "cmd z", "undo", "editable & !uses_undo_manager"),
+            (Press, "escape", "keep_oldest_cursor_only", ""),
+            (Release, "left-mouse-button", "stop_newest_selection_end_follow_mouse", ""),
+            (Release, "cmd left-mouse-button", "stop_newest_selection_end_follow_mouse", ""),
+            (Release, "left-mouse-button", "drop_selection", ""),
+            (Release, "alt left-mouse-button", "drop_selection_copy", ""),
+            (Press, "escape", "cancel_selection_drag", ""),
+            (PressAndRepeat, "backspace", "delete_left", "editable"),
+            (PressAndRepeat, "delete", "delete_right", "editable"),
+            (PressAndRepeat, "cmd backspace", "delete_word_left", "editable"),
+            (PressAndRepeat, "cmd delete", "delete_word_right", "editable"),
+            (PressAndRepeat, "tab", "insert_tab", "editable & !single_line_mode"),
+            (PressAndRepeat, "shift tab", "outdent_selection", "editable & !single_line_mode"),
+            (PressAndRepeat, "alt up", "move_lines_up", "editable & !single_line_mode"),
+            (PressAndRepeat, "alt down", "move_lines_down", "editable & !single_line_mode"),
+            (PressAndRepeat, "alt shift down", "duplicate_selection", "editable"),
+        ];
+        non_focus_capturing_shortcuts
+            .iter()
+            .copied()
+            .chain(focus_capturing_shortcuts.iter().copied())
+            .chain(focus_capturing_shortcuts.iter().map(|(a, r, _, c)| (*a, *r, "focus", *c)))
+            .map(|(action, rule, command, condition)| {
+                let only_hovered = action != Release && rule.contains("left-mouse-button");
+                let base_condition =
+                    if only_hovered { "enabled & focused & hovered" } else { "enabled & focused" };
+                let condition = if condition.is_empty() {
+                    Cow::from(base_condition)
+                } else {
+                    Cow::from(format!("{base_condition} & {condition}"))
+                };
+                Self::self_shortcut_when(action, rule, command, condition.as_ref())
+            })
+            .collect()
+    }
+}
+
 
-                            let glyph = &line.get_or_create(column, || glyph_system.new_glyph());
-                            glyph.line_byte_offset.set(glyph_byte_start);
 
-                            let glyph_line_metrics = line::Metrics { ascender, descender, gap };
-                            line_metrics = line_metrics.concat(Some(glyph_line_metrics));
+// =============
+// === Tests ===
+// =============
 
-                            let render_info = &shaped_glyph.render_info;
-                            let glyph_render_offset =
-                                render_info.offset.scale(style.font_size.value);
-                            glyph.set_color(style.color);
-                            glyph.set_sdf_weight(style.sdf_weight.value);
-                            glyph.set_font_size(formatting::Size(
-                                style.font_size.value * magic_scale,
-                            ));
-                            glyph.set_properties(shaped_glyph_set.non_variable_variations);
-                            glyph.set_glyph_id(shaped_glyph.id());
-                            glyph.x_advance.set(x_advance);
-                            glyph.view.set_xy(glyph_render_offset * magic_scale);
-                            glyph.set_xy(Vector2(glyph_offset_x, 0.0));
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-                            glyph_offset_x += x_advance;
-                            divs.push(glyph_offset_x);
-                            column += Column(1);
-                        }
-                    }
-                    if let Some(line_metrics) = line_metrics {
-                        line.set_metrics(line_metrics);
-                    } else {
-                        warn!("Internal error. Line metrics was not computed.")
-                    }
-                }
-                ShapedLine::Empty { prev_glyph_info } => {
-                    if let Some((offset, shaped_glyph_set)) = prev_glyph_info {
-                        let line_style = self.buffer.sub_style(*offset..);
-                        let mut line_style_iter = line_style.iter_bytes();
-                        let style = line_style_iter.next().unwrap_or_default();
-                        let scale = shaped_glyph_set.units_per_em as f32 / style.font_size.value;
-                        let ascender = shaped_glyph_set.ascender as f32 / scale;
-                        let descender = shaped_glyph_set.descender as f32 / scale;
-                        let gap = shaped_glyph_set.line_gap as f32 / scale;
-                        let metrics = line::Metrics { ascender, descender, gap };
-                        line.set_metrics(metrics);
-                    }
-                }
-            }
-        });
+    /// Assert that there is no inherent memory leak in the [text::Text].
+    #[test]
+    fn assert_no_leak() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        let text_frp = text.frp.downgrade();
+        let text_data = Rc::downgrade(&text.data);
+        drop(text);
+        assert_eq!(text_frp.strong_count(), 0, "There are FRP references left.");
+        assert_eq!(text_data.strong_count(), 0, "There are  data references left.");
+    }
 
-        if truncated {
-            let divs = (divs[0..divs.len() - to_be_truncated]).to_vec();
-            let divs = NonEmptyVec::try_from(divs).unwrap_or_else(|_| default_divs());
-            line.set_divs(divs);
-            line.glyphs.truncate(column.value - to_be_truncated);
-            line.set_truncated(Some(default_size));
-            line.update_truncation_color();
-        } else {
-            line.set_divs(divs);
-            line.glyphs.truncate(column.value);
-            line.set_truncated(None);
-        }
+    #[test]
+    fn disabled_text_area_cannot_be_focused() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        assert!(text.enabled.value());
+        text.set_enabled(false);
+        text.focus();
+        assert!(!text.focused.value());
+        text.set_enabled(true);
+        text.focus();
+        assert!(text.focused.value());
     }
 
-    /// Clear shaped lines cache and redraw lines in the provided range. Clearing the cache is
-    /// required when the line needs to be re-shaped, for example, after setting a glyph to a bold
-    /// style or changing glyph size.
-    pub fn clear_cache_and_redraw_sorted_line_ranges(
-        &self,
-        ranges: impl IntoIterator<Item = buffer::Range<Byte>>,
-    ) {
-        let view_line_ranges = ranges.into_iter().map(|range| {
-            let range = buffer::Range::<Location>::from_in_context_snapped(self, range);
-            let line_range = range.start.line..=range.end.line;
-            for line_index in line_range {
-                self.clear_shaped_lines_cache_for_line(line_index);
-            }
-            let view_line_start = ViewLine::from_in_context_snapped(self, range.start.line);
-            let view_line_end = ViewLine::from_in_context_snapped(self, range.end.line);
-            view_line_start..=view_line_end
-        });
-        self.redraw_sorted_line_ranges(view_line_ranges);
-        self.update_selections();
+    #[test]
+    fn read_only_text_area_is_selectable_but_not_editable() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_editable(false);
+        text.set_content("abc");
+        assert_eq!(text.content.value().to_string(), "abc");
+        text.set_cursor(Location(Line(0), Column(3)));
+        text.insert("d");
+        text.delete_left();
+        text.undo();
+        assert_eq!(text.content.value().to_string(), "abc");
+        text.select_all();
+        assert_eq!(text.data.buffer.selections_contents(), vec!["abc".to_string()]);
+        text.set_editable(true);
+        text.insert("d");
+        assert_eq!(text.content.value().to_string(), "d");
     }
 
-    /// Attach glyphs to cursors if cursors are in edit mode.
-    #[profile(Debug)]
-    pub fn attach_glyphs_to_cursors(&self) {
-        for line in ViewLine(0)..=self.buffer.last_view_line_index() {
-            self.attach_glyphs_to_cursors_for_line(line)
-        }
+    #[test]
+    fn modifications_are_undone_by_the_undo_manager() {
+        let app = Application::new("root");
+        let text1 = app.new_view::<Text>();
+        let text2 = app.new_view::<Text>();
+        text1.set_undo_manager(Some(app.undo.clone_ref()));
+        text2.set_undo_manager(Some(app.undo.clone_ref()));
+        assert!(text1.uses_undo_manager.value());
+        text1.insert("a");
+        text2.insert("b");
+        text1.insert("c");
+        let contents = || (text1.content.value().to_string(), text2.content.value().to_string());
+        assert_eq!(contents(), ("ac".into(), "b".into()));
+        app.undo.undo();
+        assert_eq!(contents(), ("a".into(), "b".into()));
+        app.undo.undo();
+        assert_eq!(contents(), ("a".into(), "".into()));
+        app.undo.redo();
+        assert_eq!(contents(), ("a".into(), "b".into()));
+        text2.insert("d");
+        assert!(!app.undo.can_redo.value());
+        assert_eq!(contents(), ("a".into(), "bd".into()));
+        app.undo.undo();
+        app.undo.undo();
+        assert_eq!(contents(), ("a".into(), "".into()));
     }
 
-    /// Attach glyphs to cursors if cursors are in edit mode.
-    fn attach_glyphs_to_cursors_for_line(&self, view_line: ViewLine) {
-        let cursor_map = self.selection_map.borrow().location_map.get(&view_line).cloned();
-        let cursor_map = cursor_map.unwrap_or_default();
-        let line = &self.lines.borrow()[view_line];
+    #[test]
+    fn wheel_deltas_are_accumulated_in_pixel_mode() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        let line_height = text.data.default_line_height();
+        let pixels = WheelScrollMode::Pixels;
+        assert_eq!(text.data.wheel_line_diff(line_height * 0.6, pixels), None);
+        assert_eq!(text.data.wheel_line_diff(line_height * 0.6, pixels), Some(LineDiff(1)));
+        assert_eq!(text.data.wheel_line_diff(line_height * -2.5, pixels), Some(LineDiff(-2)));
+        let lines = WheelScrollMode::Lines;
+        let step = WHEEL_SCROLL_LINES as i32;
+        assert_eq!(text.data.wheel_line_diff(0.1, lines), Some(LineDiff(step)));
+        assert_eq!(text.data.wheel_line_diff(-100.0, lines), Some(LineDiff(-step)));
+    }
 
-        let mut attached_glyphs = vec![];
-        let mut last_cursor: Option<Selection> = None;
-        let mut last_cursor_target_x = default();
+    #[test]
+    fn wheel_events_scroll_the_view_if_enabled() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content((0..20).map(|line| line.to_string()).join("\n"));
+        text.set_view_height(Some(text.data.default_line_height() * 5.0));
+        let wheel = || {
+            let data = mouse::WheelEventData { delta: Vector2(0.0, 1.0), ..default() };
+            text.data.hit_area.dispatch_event(mouse::Wheel::simulated(data, default()))
+        };
+        // The wheel events are not handled by default, so they reach the parents of the text area.
+        assert!(wheel());
+        assert_eq!(text.data.buffer.first_view_line(), Line(0));
 
-        let mut column = Column(0);
-        for glyph in line {
-            cursor_map.get(&column).for_each(|id| {
-                if let Some(cursor) = self.selection_map.borrow().id_map.get(id) {
-                    if cursor.edit_mode().get() {
-                        if let Some(last_cursor) = &last_cursor {
-                            let attached_glyphs = Rc::new(mem::take(&mut attached_glyphs));
-                            last_cursor.set_attached_glyphs(attached_glyphs);
-                        }
-                        last_cursor = Some(cursor.clone_ref());
-                        last_cursor_target_x = glyph.x();
-                    }
-                }
-            });
+        text.set_wheel_handling(true);
+        assert!(!wheel());
+        assert_eq!(text.data.buffer.first_view_line(), Line(WHEEL_SCROLL_LINES));
+    }
 
-            if let Some(cursor) = &last_cursor {
-                cursor.right_side().add_child(glyph);
-                glyph.attached_to_cursor.set(true);
-                glyph.update_x(|p| p - last_cursor_target_x);
-                attached_glyphs.push(glyph.downgrade());
-            }
-            column += Column(1);
-        }
-        if let Some(last_cursor) = &last_cursor {
-            last_cursor.set_attached_glyphs(Rc::new(mem::take(&mut attached_glyphs)));
-        } else if !attached_glyphs.is_empty() {
-            error!("Internal error. Cannot attach glyphs to cursors.");
-        }
+    #[test]
+    fn view_is_scrolled_by_pixels() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("1\n2\n3\n4\n5\n6\n7\n8\n9\n10");
+        let line_height = text.data.default_line_height();
+        text.set_view_height(Some(line_height * 3.5));
+        assert_eq!(text.content_height.value(), line_height * 10.0);
+        text.scroll_to(line_height * 2.5);
+        assert_eq!(text.data.buffer.first_view_line(), Line(2));
+        assert_eq!(text.scroll_position.value(), line_height * 2.0);
+        text.scroll_by(line_height * 0.6);
+        assert_eq!(text.data.buffer.first_view_line(), Line(2));
+        text.scroll_by(line_height * 0.6);
+        assert_eq!(text.data.buffer.first_view_line(), Line(3));
+        // The view cannot be scrolled past the last line.
+        text.scroll_to(line_height * 100.0);
+        assert_eq!(text.data.buffer.first_view_line(), Line(7));
+
+        text.set_cursor_follow(true);
+        text.set_cursor(Location(Line(1), Column(0)));
+        assert_eq!(text.data.buffer.first_view_line(), Line(1));
+        text.set_cursor(Location(Line(5), Column(0)));
+        assert_eq!(text.data.buffer.first_view_line(), Line(3));
     }
 
-    /// Detach all glyphs from cursors and place them back in lines.
-    #[profile(Debug)]
-    pub fn detach_glyphs_from_cursors(&self) {
-        let selection_map = self.selection_map.borrow();
-        for (&line, cursor_map) in &selection_map.location_map {
-            for cursor_id in cursor_map.values() {
-                let selection = selection_map.id_map.get(cursor_id).unwrap();
-                for glyph in &*selection.set_attached_glyphs.value() {
-                    if let Some(glyph) = glyph.upgrade() {
-                        self.lines.borrow_mut()[line].add_child(&glyph);
-                        let pos_x = selection.position_target.value().x;
-                        glyph.update_xy(|pos| Vector2(pos.x + pos_x, 0.0));
-                        glyph.attached_to_cursor.set(false);
-                    }
-                }
-                selection.set_attached_glyphs(Rc::new(vec![]));
-            }
-        }
+    #[test]
+    fn view_is_scrolled_horizontally() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("short\n0123456789012345678901234567890123456789");
+        let line_width = *text.data.lines.borrow()[ViewLine(1)].divs.last();
+        let view_width = line_width / 4.0;
+        text.set_view_width(Some(view_width));
+        text.horizontal_scroll_to(view_width);
+        assert_eq!(text.horizontal_scroll_position.value(), view_width);
+        let is_clipped = |column: usize| {
+            text.data.lines.borrow()[ViewLine(1)].glyphs[Column(column)].is_clipped()
+        };
+        assert!(is_clipped(0));
+        assert!(!is_clipped(15));
+        assert!(is_clipped(39));
+        // The view cannot be scrolled past the end of the longest line.
+        text.horizontal_scroll_by(line_width);
+        assert_eq!(text.horizontal_scroll_position.value(), line_width - view_width);
+
+        text.set_cursor_follow(true);
+        text.set_cursor(Location(Line(1), Column(0)));
+        assert_eq!(text.horizontal_scroll_position.value(), 0.0);
+        // The view is not scrolled if the lines are wrapped.
+        text.set_wrap_mode(wrap::WrapMode::Viewport);
+        text.horizontal_scroll_to(view_width);
+        assert_eq!(text.horizontal_scroll_position.value(), 0.0);
     }
-}
 
+    #[test]
+    fn gutter_fits_line_numbers() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("foo\nbar\nbaz");
+        text.set_gutter_width(5.0);
+        assert_eq!(text.gutter_width.value(), 5.0);
+        text.set_line_numbers_visible(true);
+        let width = text.gutter_width.value();
+        assert!(width > gutter::MARKER_AREA_WIDTH);
+        assert!(text.data.gutter.is_over_line_numbers(-width + 1.0));
+        assert!(!text.data.gutter.is_over_line_numbers(-1.0));
+        text.set_gutter_width(width * 2.0);
+        assert_eq!(text.gutter_width.value(), width * 2.0);
+        text.set_line_numbers_visible(false);
+        text.set_gutter_width(5.0);
+        assert_eq!(text.gutter_width.value(), 5.0);
+
+        let (start, end) = text.data.line_selection(Line(1));
+        text.select(start, end);
+        let selection = *text.selections.value().newest().unwrap();
+        assert_eq!(selection.start, Location(Line(1), Column(0)));
+        assert_eq!(selection.end, Location(Line(2), Column(0)));
+        let (start, end) = text.data.line_selection(Line(2));
+        text.select(start, end);
+        let selection = *text.selections.value().newest().unwrap();
+        assert_eq!(selection.end, Location(Line(2), Column(3)));
+    }
 
+    #[test]
+    fn consecutive_clicks_are_counted() {
+        let mut multi_click = MultiClick::default();
+        let position = Vector2(10.0, 10.0);
+        assert_eq!(multi_click.register(1000.0, position), 1);
+        assert_eq!(multi_click.register(1100.0, position), 2);
+        assert_eq!(multi_click.register(1200.0, position + Vector2(1.0, 1.0)), 3);
+        assert_eq!(multi_click.register(1300.0, position), 1);
+        assert_eq!(multi_click.register(1400.0, position + Vector2(20.0, 0.0)), 1);
+        let late = 1400.0 + MULTI_CLICK_INTERVAL_MS + 1.0;
+        assert_eq!(multi_click.register(late, position + Vector2(20.0, 0.0)), 1);
+    }
 
-// ===========================
-// === Property Management ===
-// ===========================
+    #[test]
+    fn multi_click_selection_is_extended_by_units() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("foo bar baz\nqux");
+        let location = |line, column| Location(Line(line), Column(column));
+        let m = &text.data;
+        assert_eq!(m.start_multi_click(1, location(0, 5)), None);
+        assert_eq!(m.multi_click_selection_to(location(0, 9)), None);
+
+        let word = m.start_multi_click(2, location(0, 5));
+        assert_eq!(word, Some((location(0, 4), location(0, 7))));
+        assert_eq!(
+            m.multi_click_selection_to(location(0, 9)),
+            Some((location(0, 4), location(0, 11)))
+        );
+        assert_eq!(
+            m.multi_click_selection_to(location(0, 1)),
+            Some((location(0, 7), location(0, 0)))
+        );
 
-impl TextModel {
-    /// Check whether the property change will invalidate the cache, and thus, will require line
-    /// re-shaping and re-drawing.
-    fn property_change_invalidates_cache(property: impl Into<formatting::PropertyTag>) -> bool {
-        let tag = property.into();
-        match tag {
-            formatting::PropertyTag::FontSize => true,
-            formatting::PropertyTag::Color => false,
-            formatting::PropertyTag::Weight => true,
-            formatting::PropertyTag::Width => true,
-            formatting::PropertyTag::Style => true,
-            formatting::PropertyTag::SdfWeight => false,
-        }
+        let line = m.start_multi_click(3, location(0, 5));
+        assert_eq!(line, Some((location(0, 0), location(0, 11))));
+        assert_eq!(
+            m.multi_click_selection_to(location(1, 1)),
+            Some((location(0, 0), location(1, 3)))
+        );
+        m.finish_multi_click();
+        assert_eq!(m.multi_click_selection_to(location(1, 1)), None);
     }
 
-    /// Set the property to selected glyphs. Redraw lines if needed.
-    fn set_property(&self, ranges: &Vec<buffer::Range<Byte>>, property: formatting::Property) {
-        if Self::property_change_invalidates_cache(property) {
-            self.clear_cache_and_redraw_sorted_line_ranges(ranges.iter().copied())
-        } else {
-            self.set_glyphs_property_without_line_redraw(ranges, property)
-        }
+    #[test]
+    fn font_size_change_recomputes_view_line_count() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("1\n2\n3\n4\n5\n6\n7\n8\n9\n10");
+        text.set_font_size(10.0);
+        let line_height = text.data.default_line_height();
+        text.set_view_height(Some(line_height * 4.5));
+        assert_eq!(text.data.buffer.view_line_limit(), Some(4));
+        text.set_font_size(20.0);
+        assert_eq!(text.font_size.value(), 20.0);
+        assert_eq!(text.data.buffer.view_line_limit(), Some(2));
+        text.set_view_height(None);
+        assert_eq!(text.data.buffer.view_line_limit(), None);
     }
 
-    /// Modify the property of selected glyphs. Redraw lines if needed.
-    fn mod_property(&self, ranges: &Vec<buffer::Range<Byte>>, property: formatting::PropertyDiff) {
-        if Self::property_change_invalidates_cache(property) {
-            self.clear_cache_and_redraw_sorted_line_ranges(ranges.iter().copied())
-        } else {
-            self.mod_glyphs_property_without_line_redraw(ranges, property)
-        }
+    #[test]
+    fn font_size_change_relayouts_lines() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("ab\ncd");
+        text.set_font_size(10.0);
+        text.set_font_size(20.0);
+        let expected = app.new_view::<Text>();
+        expected.set_font_size(20.0);
+        expected.set_content("ab\ncd");
+        let layout = |text: &Text| {
+            let lines = text.data.lines.borrow();
+            let widths = lines.iter().map(|line| line.div_by_column(Column(2))).collect_vec();
+            let baselines = lines.iter().map(|line| line.baseline()).collect_vec();
+            (widths, baselines)
+        };
+        assert_eq!(layout(&text), layout(&expected));
     }
 
-    /// Set the property to selected glyphs. No redraw will be performed.
-    fn set_glyphs_property_without_line_redraw(
-        &self,
-        ranges: &Vec<buffer::Range<Byte>>,
-        property: formatting::Property,
-    ) {
-        let property = self.buffer.resolve_property(property);
-        let color_change = property.tag() == formatting::PropertyTag::Color;
-        self.modify_glyphs_in_ranges_without_line_redraw(ranges, color_change, |g| {
-            g.set_property(property)
-        });
+    #[test]
+    fn theme_style_is_overridden_by_explicit_values() {
+        let app = Application::new("root");
+        let style_sheet = &app.display.default_scene.style_sheet;
+        style_sheet.set(theme::CARET_WIDTH, 3.0);
+        style_sheet.set(theme::LINE_HEIGHT, 1.5);
+        let text = app.new_view::<Text>();
+        assert_eq!(text.caret_width.value(), 3.0);
+        assert_eq!(text.caret_color.value(), None);
+        assert_eq!(text.line_height.value(), 1.5);
+        let line_height = text.data.default_line_height();
+        text.set_line_height(1.0);
+        assert_eq!(text.data.default_line_height() * 1.5, line_height);
+        style_sheet.set(theme::LINE_HEIGHT, 2.0);
+        assert_eq!(text.line_height.value(), 1.0);
+        style_sheet.set(theme::CARET_WIDTH, 4.0);
+        assert_eq!(text.caret_width.value(), 4.0);
+        text.set_caret_width(1.0);
+        style_sheet.set(theme::CARET_WIDTH, 5.0);
+        assert_eq!(text.caret_width.value(), 1.0);
     }
 
-    /// Modify the property of selected glyphs. No redraw will be performed.
-    fn mod_glyphs_property_without_line_redraw(
-        &self,
-        ranges: &Vec<buffer::Range<Byte>>,
-        property: formatting::PropertyDiff,
-    ) {
-        let color_change = property.tag() == formatting::PropertyTag::Color;
-        self.modify_glyphs_in_ranges_without_line_redraw(ranges, color_change, |g| {
-            g.mod_property(property)
-        });
+    #[test]
+    fn identical_labels_share_shaping_results() {
+        let app = Application::new("root");
+        let text1 = app.new_view::<Text>();
+        let text2 = app.new_view::<Text>();
+        text1.set_content("Number");
+        text2.set_content("Number");
+        let cache = app.display.default_scene.extension::<ShapeCache>();
+        cache.clear();
+        let misses = cache.miss_count();
+        let hits = cache.hit_count();
+        let glyph_count1 = text1.data.shape_line(Line(0)).glyph_count();
+        assert_eq!(cache.miss_count(), misses + 1);
+        let glyph_count2 = text2.data.shape_line(Line(0)).glyph_count();
+        assert_eq!(cache.miss_count(), misses + 1);
+        assert_eq!(cache.hit_count(), hits + 1);
+        assert_eq!(glyph_count1, glyph_count2);
+        text2.set_content("Text");
+        text2.data.shape_line(Line(0));
+        assert_eq!(cache.miss_count(), misses + 2);
+    }
+
+    #[test]
+    fn font_features_reshape_lines() {
+        let app = Application::new("root");
+        let text1 = app.new_view::<Text>();
+        let text2 = app.new_view::<Text>();
+        text1.set_content("a -> b");
+        text2.set_content("a -> b");
+        let cache = app.display.default_scene.extension::<ShapeCache>();
+        let misses = cache.miss_count();
+        let ligatures = font_features::FontFeatures::ligatures();
+        text2.set_font_features(ligatures.clone());
+        assert_eq!(text2.font_features.value(), ligatures);
+        // The text areas with different features do not share the shaping results.
+        assert_eq!(cache.miss_count(), misses + 1);
+        text1.set_font_features(ligatures);
+        assert_eq!(cache.miss_count(), misses + 1);
+    }
+
+    #[test]
+    fn proportional_font_is_rendered_with_colored_ranges() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_font("DejaVuSans");
+        text.set_content("iiii WWWW");
+        let red = color::Rgba::red();
+        let blue = color::Rgba::blue();
+        text.set_property(buffer::Range::new(Byte(0), Byte(4)), red.into());
+        text.set_property(buffer::Range::new(Byte(5), Byte(9)), blue.into());
+        let divs = text.data.lines.borrow()[ViewLine(0)].divs.clone();
+        assert_eq!(divs.len(), 10);
+        // The glyphs are placed by their advances, so the narrow ones take less space.
+        assert!(divs[4] - divs[0] < divs[9] - divs[5]);
+
+        text.set_cursor(Location(Line(0), Column(7)));
+        let cursor_x = {
+            let selections = text.data.selection_map.borrow();
+            let cursor = selections.id_map.values().next().unwrap();
+            cursor.position_target.value().x
+        };
+        let position_of =
+            |column| text.data.lines.borrow()[ViewLine(0)].position_of_column(Column(column)).x;
+        assert_eq!(cursor_x, position_of(7));
+        assert_eq!(position_of(7) - position_of(5), divs[7] - divs[5]);
+
+        let glyph_color = |column| {
+            color::Rgba::from(text.data.lines.borrow()[ViewLine(0)].glyphs[Column(column)].color())
+        };
+        let is_close = |a: f32, b: f32| (a - b).abs() < 0.01;
+        let has_color = |column, expected: color::Rgba| {
+            let color = glyph_color(column);
+            is_close(color.red, expected.red)
+                && is_close(color.green, expected.green)
+                && is_close(color.blue, expected.blue)
+        };
+        assert!((0..4).all(|column| has_color(column, red)));
+        assert!(!has_color(4, red) && !has_color(4, blue));
+        assert!((5..9).all(|column| has_color(column, blue)));
     }
 
-    /// Modify the selected glyphs. No redraw will be performed.
-    fn modify_glyphs_in_ranges_without_line_redraw(
-        &self,
-        ranges: &Vec<buffer::Range<Byte>>,
-        color_change: bool,
-        f: impl Fn(&Glyph),
-    ) {
-        for &range in ranges {
-            self.modify_glyphs_in_range_without_line_redraw(range, color_change, &f);
-        }
+    #[test]
+    fn decorations_are_drawn_along_glyph_runs() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("let typo = 1");
+        let typo = buffer::Range::new(Byte(4), Byte(8));
+        let property = |p: formatting::Property| text.set_property(typo, p);
+        property(formatting::Underline(true).into());
+        property(formatting::UnderlineStyle::Wavy.into());
+        let decorations = || text.data.lines.borrow()[ViewLine(0)].decorations();
+        let glyph_x = |column| text.data.lines.borrow()[ViewLine(0)].glyphs[Column(column)].x();
+        let underlines = decorations();
+        assert_eq!(underlines.len(), 1);
+        assert_eq!(underlines[0].style, formatting::UnderlineStyle::Wavy);
+        assert_eq!(underlines[0].start.x, glyph_x(4));
+        assert_eq!(underlines[0].end_x, glyph_x(8));
+        assert!(underlines[0].start.y < 0.0);
+        let red = color::Lcha::from(color::Rgba::red());
+        property(formatting::UnderlineColor::new(red).into());
+        property(formatting::Strikethrough(true).into());
+        let decorations = decorations();
+        assert_eq!(decorations.len(), 2);
+        assert_eq!(decorations[0].color, red);
+        assert_eq!(decorations[1].style, formatting::UnderlineStyle::Solid);
+        assert!(decorations[1].start.y > 0.0);
+        text.set_property(typo, formatting::Property::Underline(None));
+        assert_eq!(text.data.lines.borrow()[ViewLine(0)].decorations().len(), 1);
     }
 
-    /// Modify the selected glyphs. No redraw will be performed.
-    fn modify_glyphs_in_range_without_line_redraw(
-        &self,
-        range: buffer::Range<Byte>,
-        color_change: bool,
-        f: impl Fn(&Glyph),
-    ) {
-        let range = buffer::Range::<ViewLocation<Byte>>::from_in_context_snapped(self, range);
-        let lines = self.lines.borrow();
-        if range.start.line == range.end.line {
-            let line = &lines[range.start.line];
-            for glyph in line {
-                if glyph.line_byte_offset.get() >= range.end.offset {
-                    break;
-                }
-                if glyph.line_byte_offset.get() >= range.start.offset {
-                    f(glyph)
-                }
-            }
-            if color_change {
-                line.update_truncation_color();
-            }
-        } else {
-            let first_line = range.start.line;
-            let second_line = first_line + ViewLine(1);
-            let last_line = range.end.line;
-            for glyph in &lines[first_line] {
-                if glyph.line_byte_offset.get() >= range.start.offset {
-                    f(glyph)
-                }
-            }
-            for line in &lines[second_line..last_line] {
-                for glyph in line {
-                    f(glyph)
-                }
-            }
-            for glyph in &lines[last_line] {
-                if glyph.line_byte_offset.get() < range.end.offset {
-                    f(glyph)
-                }
-            }
-        }
+    #[test]
+    fn default_format_sets_all_properties() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        let color = color::Lcha::new(0.5, 0.2, 0.1, 1.0);
+        let format =
+            formatting::Format::new().font_size(20.0).color(color).weight(formatting::Weight::Bold);
+        text.set_default_format(format);
+        let formatting = &text.data.buffer.formatting;
+        assert_eq!(formatting.font_size().default, formatting::Size(20.0));
+        assert_eq!(formatting.color().default, color);
+        assert_eq!(formatting.weight().default, formatting::Weight::Bold);
+        assert_eq!(formatting.width().default, default());
+        assert_eq!(text.font_size.value(), 20.0);
     }
 
-    fn set_selection_color(&self, color: color::Lch) {
-        for selection in self.selection_map.borrow().id_map.values() {
-            selection.set_color(color);
-        }
+    #[test]
+    fn styled_content_is_formatted_like_separate_properties() {
+        let app = Application::new("root");
+        let red = color::Rgba::red();
+        let styled = buffer::StyledText::new().text("big").size(30.0).text(" red\nb").color(red);
+        let text = app.new_view::<Text>();
+        text.set_styled_content(styled);
+        let expected = app.new_view::<Text>();
+        expected.set_content("big red\nb");
+        expected.set_property(buffer::Range::new(Byte(0), Byte(3)), formatting::Size(30.0).into());
+        expected.set_property(buffer::Range::new(Byte(3), Byte(9)), red.into());
+        assert_eq!(text.content.value().to_string(), expected.content.value().to_string());
+        let layout = |text: &Text| {
+            let lines = text.data.lines.borrow();
+            let columns = (0..=7).map(|column| lines[ViewLine(0)].div_by_column(Column(column)));
+            let baselines = lines.iter().map(|line| line.baseline()).collect_vec();
+            (columns.collect_vec(), baselines)
+        };
+        assert_eq!(layout(&text), layout(&expected));
     }
-}
 
+    #[test]
+    fn appended_lines_are_trimmed_and_followed() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("1\n2");
+        text.set_font_size(10.0);
+        text.set_view_height(Some(text.data.default_line_height() * 2.5));
+        text.set_max_line_count(Some(4));
+        text.set_pinned_to_bottom(true);
+        text.append("\n3\n4\n5");
+        assert_eq!(text.content.value().to_string(), "2\n3\n4\n5");
+        assert_eq!(text.data.buffer.first_view_line(), Line(2));
+        assert!(text.pinned_to_bottom.value());
+
+        text.set_first_view_line(Line(0));
+        assert!(!text.pinned_to_bottom.value());
+        text.append("\n6");
+        assert_eq!(text.content.value().to_string(), "3\n4\n5\n6");
+        assert_eq!(text.data.buffer.first_view_line(), Line(0));
+    }
 
+    #[test]
+    fn lines_are_positioned_from_the_top_after_trimming() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("big\na");
+        text.set_property(buffer::Range::new(Byte(0), Byte(3)), formatting::Size(30.0).into());
+        text.set_max_line_count(Some(3));
+        text.append("\nb\nc");
+        assert_eq!(text.content.value().to_string(), "a\nb\nc");
+        let expected = app.new_view::<Text>();
+        expected.set_content("a\nb\nc");
+        let baselines =
+            |text: &Text| text.data.lines.borrow().iter().map(|line| line.baseline()).collect_vec();
+        assert_eq!(baselines(&text), baselines(&expected));
+    }
 
-// ===================================
-// === Default Property Management ===
-// ===================================
+    #[test]
+    fn selected_lines_are_transformed() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("c\nb\na\nb");
+        text.select_all();
+        text.sort_lines();
+        assert_eq!(text.content.value().to_string(), "a\nb\nb\nc");
+        text.remove_duplicate_lines();
+        assert_eq!(text.content.value().to_string(), "a\nb\nc");
+        text.set_cursor(Location(Line(1), Column(0)));
+        text.join_lines();
+        assert_eq!(text.content.value().to_string(), "a\nb c");
+        text.undo();
+        assert_eq!(text.content.value().to_string(), "a\nb\nc");
+    }
 
-impl TextModel {
-    /// Change a default value of a property.
-    fn set_property_default(&self, property: Option<formatting::ResolvedProperty>) {
-        if let Some(property) = property {
-            if Self::property_change_invalidates_cache(property) {
-                self.set_property_default_with_line_redraw(property)
-            } else {
-                self.set_property_default_without_line_redraw(property)
-            }
-        }
+    #[test]
+    fn selected_lines_are_indented() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("foo\n\nbar\nbaz");
+        let content = || text.content.value().to_string();
+        let selection = || *text.selections.value().newest().unwrap();
+        let start = LocationLike::from(Location(Line(0), Column(1)));
+        let end = LocationLike::from(Location(Line(2), Column(2)));
+        text.select(start, end);
+        text.indent_selection();
+        assert_eq!(content(), "    foo\n\n    bar\nbaz");
+        assert_eq!(selection().start, Location(Line(0), Column(5)));
+        assert_eq!(selection().end, Location(Line(2), Column(6)));
+
+        text.set_indent_unit(buffer::IndentUnit::Tab);
+        text.indent_selection();
+        assert_eq!(content(), "\t    foo\n\n\t    bar\nbaz");
+        text.outdent_selection();
+        text.outdent_selection();
+        assert_eq!(content(), "foo\n\nbar\nbaz");
+        assert_eq!(selection().start, Location(Line(0), Column(1)));
+        assert_eq!(selection().end, Location(Line(2), Column(2)));
+        // Outdenting the lines without indentation does not modify the text.
+        text.outdent_selection();
+        text.undo();
+        assert_eq!(content(), "    foo\n\n    bar\nbaz");
     }
 
-    /// Change a default value of a property that requires line redraw, like changing the default
-    /// glyph weight or size.
-    fn set_property_default_with_line_redraw(&self, property: formatting::ResolvedProperty) {
-        let range = self.buffer.full_range();
-        let formatting = self.buffer.sub_style(range);
-        let span_ranges = formatting.span_ranges_of_default_values(property.tag());
-        self.clear_cache_and_redraw_sorted_line_ranges(span_ranges);
+    #[test]
+    fn selected_lines_are_moved() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("a\nb\nc\nd");
+        let content = || text.content.value().to_string();
+        let selection = || *text.selections.value().newest().unwrap();
+        let start = LocationLike::from(Location(Line(1), Column(0)));
+        let end = LocationLike::from(Location(Line(3), Column(0)));
+        text.select(start, end);
+        text.move_lines_up();
+        assert_eq!(content(), "b\nc\na\nd");
+        assert_eq!(selection().start, Location(Line(0), Column(0)));
+        assert_eq!(selection().end, Location(Line(2), Column(0)));
+        // The first line can not be moved up.
+        text.move_lines_up();
+        assert_eq!(content(), "b\nc\na\nd");
+
+        text.set_cursor(Location(Line(2), Column(1)));
+        text.add_cursor(Location(Line(0), Column(1)));
+        text.move_lines_down();
+        assert_eq!(content(), "c\nb\nd\na");
+        let cursors = text.selections.value().iter().map(|s| s.end).collect_vec();
+        assert!(cursors.contains(&Location(Line(1), Column(1))));
+        assert!(cursors.contains(&Location(Line(3), Column(1))));
+        text.undo();
+        assert_eq!(content(), "b\nc\na\nd");
     }
 
-    /// Change a default value of a property  that does not require line redraw, like changing the
-    /// default glyph color.
-    fn set_property_default_without_line_redraw(&self, property: formatting::ResolvedProperty) {
-        let range = self.buffer.full_range();
-        let formatting = self.buffer.sub_style(range);
-        let span_ranges = formatting.span_ranges_of_default_values(property.tag());
-        for span_range in span_ranges {
-            let range = buffer::Range::<Location>::from_in_context_snapped(self, span_range);
-            let mut lines = self.lines.borrow_mut();
-            if range.single_line() {
-                let view_line = ViewLine::from_in_context_snapped(self, range.start.line);
-                let line = &mut lines[view_line];
-                if let Some(last_index) = line.glyphs.last_valid_index() {
-                    let start = std::cmp::min(last_index, range.start.offset);
-                    let end = std::cmp::min(last_index + Column(1), range.end.offset);
-                    for glyph in &mut line.glyphs[start..end] {
-                        glyph.set_property(property);
-                    }
-                }
-            } else {
-                let view_line = ViewLine::from_in_context_snapped(self, range.start.line);
-                let first_line = &mut lines[view_line];
-                if let Some(last_index) = first_line.glyphs.last_valid_index() {
-                    let start = std::cmp::min(last_index, range.start.offset);
-                    for glyph in &mut first_line.glyphs[start..] {
-                        glyph.set_property(property);
-                    }
-                }
+    #[test]
+    fn selections_and_lines_are_duplicated() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("foo bar\nbaz");
+        let content = || text.content.value().to_string();
+        let selection = || *text.selections.value().newest().unwrap();
+        let start = LocationLike::from(Location(Line(0), Column(4)));
+        let end = LocationLike::from(Location(Line(0), Column(7)));
+        text.select(start, end);
+        text.duplicate_selection();
+        assert_eq!(content(), "foo barbar\nbaz");
+        assert_eq!(selection().start, Location(Line(0), Column(7)));
+        assert_eq!(selection().end, Location(Line(0), Column(10)));
+
+        text.set_cursor(Location(Line(1), Column(1)));
+        text.duplicate_selection();
+        assert_eq!(content(), "foo barbar\nbaz\nbaz");
+        assert_eq!(selection().end, Location(Line(2), Column(1)));
+        text.undo();
+        assert_eq!(content(), "foo barbar\nbaz");
+    }
 
-                let view_line = ViewLine::from_in_context_snapped(self, range.end.line);
-                let last_line = &mut lines[view_line];
-                if let Some(last_index) = last_line.glyphs.last_valid_index() {
-                    let end = std::cmp::min(last_index + Column(1), range.end.offset);
-                    for glyph in &mut last_line.glyphs[..end] {
-                        glyph.set_property(property);
-                    }
-                }
-                for line_index in range.start.line.value + 1..range.end.line.value {
-                    let view_line = ViewLine::from_in_context_snapped(self, Line(line_index));
-                    let line = &mut lines[view_line];
-                    for glyph in &mut line.glyphs[..] {
-                        glyph.set_property(property);
-                    }
-                }
-            }
-        }
+    #[test]
+    fn dragged_text_is_dropped() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("foo bar baz");
+        let content = || text.content.value().to_string();
+        let selection = || *text.selections.value().newest().unwrap();
+        let location = |column: usize| Location(Line(0), Column(column));
+        let bar = buffer::Range::new(location(4), location(7));
+        let drop_text =
+            |target: usize, copy: bool| text.data.buffer.frp.drop_text(bar, location(target), copy);
+        drop_text(11, false);
+        assert_eq!(content(), "foo  bazbar");
+        assert_eq!(selection().start, location(8));
+        assert_eq!(selection().end, location(11));
+        text.undo();
+        assert_eq!(content(), "foo bar baz");
+        drop_text(0, true);
+        assert_eq!(content(), "barfoo bar baz");
+        assert_eq!(selection().end, location(3));
+        text.undo();
+        // Dropping the text inside of itself does nothing.
+        drop_text(5, false);
+        drop_text(7, false);
+        assert_eq!(content(), "foo bar baz");
     }
 
-    #[profile(Debug)]
-    fn set_font(&self, font_name: &str) -> glyph::System {
-        let glyph_system = font::glyph::System::new(&self.scene, font_name);
-        self.glyph_system.replace(glyph_system.clone());
-        // Remove old Glyph structures, as they still refer to the old Glyph System.
-        self.take_lines();
-        self.redraw();
-        glyph_system
+    #[test]
+    fn ime_composition_is_committed_as_single_change() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        let ime = app.display.default_scene.extension::<Ime>();
+        text.set_content("ab");
+        text.set_cursor(Location(Line(0), Column(1)));
+        text.focus();
+        let content = || text.content.value().to_string();
+        let composition_set = || {
+            let sets = text.highlight_sets.value();
+            let set = sets.iter().find(|set| set.name == IME_COMPOSITION_HIGHLIGHT_SET)?;
+            Some((set.ranges.clone(), set.style.underline))
+        };
+        let range = |start, end| buffer::Range::new(Byte(start), Byte(end));
+        ime.update_composition("k");
+        assert_eq!(content(), "akb");
+        assert_eq!(composition_set(), Some((vec![range(1, 2)], true)));
+        ime.update_composition("漢");
+        assert_eq!(content(), "a漢b");
+        assert_eq!(composition_set(), Some((vec![range(1, 4)], true)));
+        ime.end_composition("漢字");
+        assert_eq!(content(), "a漢字b");
+        assert_eq!(composition_set(), None);
+        text.undo();
+        assert_eq!(content(), "ab");
+        // Losing focus cancels the composition.
+        ime.update_composition("´");
+        assert_eq!(content(), "a´b");
+        text.blur();
+        assert_eq!(content(), "ab");
+        assert_eq!(composition_set(), None);
     }
-}
 
+    #[test]
+    fn pasted_chunks_replace_subsequent_selections() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("a b c");
+        let content = || text.content.value().to_string();
+        text.set_cursor(Location(Line(0), Column(1)));
+        text.add_cursor(Location(Line(0), Column(3)));
+        text.add_cursor(Location(Line(0), Column(5)));
+        text.paste_chunks(Rc::new(vec!["1".into(), "2\n3".into()]));
+        assert_eq!(content(), "a1 b2\n3 c");
+        text.undo();
+        text.set_single_line_mode(true);
+        text.set_cursor(Location(Line(0), Column(1)));
+        text.paste_string("1\n2\x1E3");
+        assert_eq!(content(), "a1 b c");
+    }
 
+    #[test]
+    fn line_comments_are_toggled() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("    foo\n\n  bar\nbaz");
+        let content = || text.content.value().to_string();
+        let start = LocationLike::from(Location(Line(0), Column(5)));
+        let end = LocationLike::from(Location(Line(2), Column(3)));
+        text.select(start, end);
+        text.toggle_line_comment("# ");
+        assert_eq!(content(), "  #   foo\n\n  # bar\nbaz");
+        let selection = *text.selections.value().newest().unwrap();
+        assert_eq!(selection.start, Location(Line(0), Column(7)));
+        assert_eq!(selection.end, Location(Line(2), Column(5)));
+        text.toggle_line_comment("# ");
+        assert_eq!(content(), "    foo\n\n  bar\nbaz");
+
+        // The lines are uncommented only if all of them are commented.
+        text.set_content("#foo\nbar");
+        text.select_all();
+        text.toggle_line_comment("# ");
+        assert_eq!(content(), "# #foo\n# bar");
+        text.toggle_line_comment("# ");
+        assert_eq!(content(), "#foo\nbar");
+        // The trailing whitespace of the prefix is optional.
+        text.set_cursor(Location(Line(0), Column(0)));
+        text.toggle_line_comment("# ");
+        assert_eq!(content(), "foo\nbar");
+    }
 
-// ========================
-// === Line Positioning ===
-// ========================
+    #[test]
+    fn whitespace_issues_are_reported() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("a \n \tb\nc");
+        text.set_whitespace_diagnostics(true);
+        let issues =
+            || text.whitespace_issues.value().iter().map(|t| (t.kind, t.range)).collect_vec();
+        let range = |start, end| buffer::Range::new(Byte(start), Byte(end));
+        assert_eq!(issues(), vec![
+            (whitespace::IssueKind::TrailingWhitespace, range(1, 2)),
+            (whitespace::IssueKind::MixedIndentation, range(3, 5)),
+        ]);
+        text.set_cursor(Location(Line(2), Column(1)));
+        text.insert("  ");
+        assert_eq!(issues().len(), 3);
+        text.set_whitespace_diagnostics(false);
+        assert_eq!(issues(), vec![]);
+    }
 
-impl TextModel {
-    // Update the lines y-axis position starting with the provided line index. Results the first
-    // well positioned line or the next line after the last visible line.
-    fn position_lines_starting_with(&self, mut line_index: ViewLine) -> ViewLine {
-        let last_line_index = self.lines.last_line_index();
-        let lines = self.lines.borrow();
-        while line_index <= last_line_index {
-            let line = &lines[line_index];
-            let current_pos_y = line.baseline();
-            let ascender = -line.metrics().ascender;
-            let new_baseline = if line_index == ViewLine(0) {
-                ascender
-            } else {
-                let prev_line_index = ViewLine(line_index.value - 1);
-                let prev_line = &lines[prev_line_index];
-                let offset = prev_line.metrics().descender + ascender - line.metrics().gap;
-                prev_line.baseline() + offset
-            };
-            let new_baseline = new_baseline.round();
-            if current_pos_y == new_baseline {
-                break;
-            }
-            line.set_baseline(new_baseline);
-            line_index += ViewLine(1);
-        }
-        line_index
+    #[test]
+    fn whitespace_issues_follow_undo_and_redo() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("a");
+        text.set_whitespace_diagnostics(true);
+        text.set_cursor(Location(Line(0), Column(1)));
+        text.insert("  ");
+        let issues = || text.whitespace_issues.value().len();
+        assert_eq!(issues(), 1);
+        text.undo();
+        assert_eq!(issues(), 0);
+        text.redo();
+        assert_eq!(issues(), 1);
     }
 
-    /// Position all lines in the provided line range. The range has to be sorted.
-    #[profile(Debug)]
-    fn position_sorted_line_ranges(
-        &self,
-        sorted_line_ranges: impl Iterator<Item = RangeInclusive<ViewLine>>,
-    ) {
-        let mut first_ok_line_index = None;
-        let mut line_index_to_position;
-        for range in sorted_line_ranges {
-            line_index_to_position = match first_ok_line_index {
-                None => *range.start(),
-                Some(p) => std::cmp::max(p + ViewLine(1), *range.start()),
-            };
-            // We are positioning one more line, because if a line is removed, the last redraw line
-            // index can be placed in the previous line, that was already well positioned. The next
-            // line has to be updated.
-            let range_end = *range.end() + ViewLine(1);
-            if line_index_to_position <= range_end {
-                loop {
-                    let ok_line_index = self.position_lines_starting_with(line_index_to_position);
-                    first_ok_line_index = Some(ok_line_index);
-                    if ok_line_index >= range_end {
-                        break;
-                    }
-                    line_index_to_position = ok_line_index + ViewLine(1);
-                }
-            }
+    #[test]
+    fn typed_text_is_substituted() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.insert_typed("a--");
+        assert_eq!(text.content.value().to_string(), "a--");
+        text.set_substitutions(Some(buffer::Substitutions::prose()));
+        for typed in ["\"", "b", "\"", "-", "-"] {
+            text.insert_typed(typed);
         }
+        assert_eq!(text.content.value().to_string(), "a--“b”—");
+        text.undo();
+        assert_eq!(text.content.value().to_string(), "a--“b”--");
+        text.undo();
+        assert_eq!(text.content.value().to_string(), "a--“b”-");
+        text.set_substitutions(None);
+        text.insert_typed("-");
+        assert_eq!(text.content.value().to_string(), "a--“b”--");
     }
-}
-
 
+    #[test]
+    fn typed_characters_are_undone_together() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        let content = || text.content.value().to_string();
+        let type_chars = |chars: &str| chars.chars().for_each(|c| text.insert_typed(c.to_string()));
+        type_chars("abc");
+        text.cursor_move_left();
+        type_chars("x");
+        ensogl_core::system::web::simulate_sleep(2.0 * UNDO_COALESCING_WINDOW_MS as f64);
+        type_chars("y");
+        assert_eq!(content(), "abxyc");
+        text.undo();
+        assert_eq!(content(), "abxc");
+        text.undo();
+        assert_eq!(content(), "abc");
+        text.undo();
+        assert_eq!(content(), "");
+
+        text.set_undo_coalescing_window(None);
+        type_chars("ab");
+        text.undo();
+        assert_eq!(content(), "a");
+
+        text.begin_undo_transaction();
+        text.insert("1");
+        text.begin_undo_transaction();
+        text.delete_left();
+        text.end_undo_transaction();
+        text.insert("2");
+        text.end_undo_transaction();
+        assert_eq!(content(), "a2");
+        text.undo();
+        assert_eq!(content(), "a");
+    }
 
-// =======================
-// === Size Management ===
-// =======================
-
-impl TextModel {
-    fn compute_width_if_dirty(&self) -> Option<f32> {
-        self.width_dirty.get().then(|| {
-            self.width_dirty.set(false);
-            let mut max_width = 0.0;
-            for line in &*self.lines.borrow() {
-                if let Some(truncation) = &*line.truncation.borrow() {
-                    let width = truncation.max_x();
-                    if width > max_width {
-                        max_width = width;
-                    }
-                } else {
-                    let last_glyph = line.glyphs.iter().rev().find(|g| !g.attached_to_cursor.get());
-                    let width = last_glyph.map(|g| g.x() + g.x_advance.get()).unwrap_or_default();
-                    if width > max_width {
-                        max_width = width;
-                    }
-                }
-            }
-            let selection_map = self.selection_map.borrow();
-            for selection in selection_map.id_map.values() {
-                let width = selection.right_side_of_last_attached_glyph.value();
-                if width > max_width {
-                    max_width = width;
-                }
-            }
-            max_width
-        })
+    #[test]
+    fn word_operations_respect_word_policy() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("kebab-case snake_case");
+        let selection = || *text.selections.value().newest().unwrap();
+        let cursor = || selection().end;
+        text.set_cursor(Location(Line(0), Column(0)));
+        text.cursor_move_right_word();
+        assert_eq!(cursor(), Location(Line(0), Column(5)));
+
+        text.set_word_policy(buffer::WordPolicy::default().with_word_chars("-"));
+        text.set_cursor(Location(Line(0), Column(0)));
+        text.cursor_move_right_word();
+        assert_eq!(cursor(), Location(Line(0), Column(10)));
+        text.set_cursor(Location(Line(0), Column(2)));
+        text.select_word_at_cursor();
+        assert_eq!(selection().start, Location(Line(0), Column(0)));
+        assert_eq!(selection().end, Location(Line(0), Column(10)));
+
+        text.set_content("a.b→c");
+        text.cursor_move_to_text_end();
+        text.delete_word_left();
+        assert_eq!(text.content.value().to_string(), "a.");
+        text.set_word_policy(buffer::WordPolicy::identifier());
+        text.insert("→c");
+        text.delete_word_left();
+        assert_eq!(text.content.value().to_string(), "a.→");
     }
 
-    fn compute_height_if_dirty(&self) -> Option<f32> {
-        self.height_dirty.get().then(|| {
-            self.height_dirty.set(false);
-            let mut max_height = -self.lines.borrow().last().descent.value();
-            let selection_map = self.selection_map.borrow();
+    #[test]
+    fn editing_state_is_restored() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        let content = (0..20).map(|i| format!("line {i}")).join("\n");
+        text.set_content(content.as_str());
+        text.set_view_height(Some(100.0));
+        text.set_editable(false);
+        text.set_cursor(Location(Line(15), Column(2)));
+        text.add_cursor(Location(Line(3), Column(4)));
+        text.set_first_view_line(Line(10));
+        let json = text.editing_state().to_json();
+
+        let restored = app.new_view::<Text>();
+        restored.set_content(content.as_str());
+        restored.restore_editing_state(Rc::new(EditingState::from_json(&json).unwrap()));
+        let state = restored.editing_state();
+        let cursors = |state: &EditingState| state.selections.iter().map(|s| s.end).collect_vec();
+        assert_eq!(cursors(&state), vec![
+            Location(Line(3), Column(4)),
+            Location(Line(15), Column(2))
+        ]);
+        assert_eq!(state.first_view_line, Line(10));
+        assert_eq!(state.view, text.editing_state().view);
+        assert!(!restored.editable.value());
+
+        let short = app.new_view::<Text>();
+        short.set_content("short");
+        short.restore_editing_state(Rc::new(EditingState::from_json(&json).unwrap()));
+        let state = short.editing_state();
+        assert_eq!(cursors(&state), vec![Location(Line(0), Column(5))]);
+        assert_eq!(state.first_view_line, Line(0));
+        short.add_cursor(Location(Line(0), Column(0)));
+        assert_eq!(short.selections.value().len(), 2);
+    }
 
-            for (view_line, map) in &selection_map.location_map {
-                for selection_id in map.values() {
-                    let selection = selection_map.id_map.get(selection_id).unwrap();
-                    let baseline = selection.position.value().y;
-                    let descender = self.lines.borrow()[*view_line].metrics.value().descender;
-                    let height = -baseline - descender;
-                    if height > max_height {
-                        max_height = height;
-                    }
-                }
-            }
-            max_height
-        })
+    #[test]
+    fn max_line_width_follows_edits() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("ab\nabcd\nabc");
+        assert_eq!(text.max_line_width.value(), 4);
+        text.set_cursor(Location(Line(2), Column(3)));
+        text.insert("defgh");
+        assert_eq!(text.max_line_width.value(), 8);
+        text.undo();
+        assert_eq!(text.max_line_width.value(), 4);
+        text.set_cursor(Location(Line(1), Column(4)));
+        text.add_cursor(Location(Line(0), Column(1)));
+        text.insert("\n");
+        assert_eq!(text.content.value().to_string(), "a\nb\nabcd\n\nabc");
+        assert_eq!(text.max_line_width.value(), 4);
     }
-}
 
+    #[test]
+    fn char_and_word_counts_follow_edits() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("foo bar\r\nbaz");
+        assert_eq!((text.char_count.value(), text.word_count.value()), (12, 3));
+        text.set_cursor(Location(Line(0), Column(3)));
+        text.insert("\nx");
+        assert_eq!(text.content.value().to_string(), "foo\nx bar\r\nbaz");
+        assert_eq!((text.char_count.value(), text.word_count.value()), (14, 4));
+        text.set_cursor(Location(Line(1), Column(0)));
+        text.delete_left();
+        assert_eq!(text.content.value().to_string(), "foox bar\r\nbaz");
+        assert_eq!((text.char_count.value(), text.word_count.value()), (13, 3));
+        text.undo();
+        assert_eq!((text.char_count.value(), text.word_count.value()), (14, 4));
+    }
 
+    #[test]
+    fn text_area_is_moved_to_overlay_layer() {
+        let app = Application::new("root");
+        let scene = &app.display.default_scene;
+        let text = app.new_view::<Text>();
+        scene.add_child(&text);
+        scene.layers.label.add(&text);
+        text.set_content("popup");
+        text.set_cursor(Location(Line(0), Column(2)));
+        let update = || scene.display_object().update(scene);
+        let overlay = &scene.layers.above_nodes;
+
+        text.set_overlay_layer(Some(overlay.downgrade()));
+        update();
+        assert_eq!(text.display_layer().as_ref(), Some(overlay));
+        assert_eq!(text.content.value().to_string(), "popup");
+        text.set_overlay_layer(None);
+        update();
+        assert_eq!(text.display_layer().as_ref(), Some(&scene.layers.label));
+        assert_eq!(text.selections.value().newest().unwrap().end, Location(Line(0), Column(2)));
+    }
 
-// ==================
-// === Operations ===
-// ==================
+    #[test]
+    fn shaping_is_prefetched_only_after_the_text_area_is_shown() {
+        let app = Application::new("root");
+        let scene = &app.display.default_scene;
+        let scheduler = idle::idle_scheduler();
+        scheduler.clear();
+        let text = app.new_view::<Text>();
+        text.set_content("a\nb\nc");
+        let prefetch_queued = || scheduler.queued_count_of(idle::Priority::Low) > 0;
+        assert!(!prefetch_queued());
+
+        scene.add_child(&text);
+        scene.layers.label.add(&text);
+        scene.display_object().update(scene);
+        frp::microtasks::flush_microtasks();
+        assert!(prefetch_queued());
+    }
 
-impl TextModel {
-    fn copy(&self, text_chunks: &[String]) {
-        let encoded = match text_chunks {
-            [] => "".to_string(),
-            [s] => s.clone(),
-            lst => lst.join(CLIPBOARD_RECORD_SEPARATOR),
+    #[test]
+    fn text_block_is_aligned_vertically() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("a\nb");
+        let view_height = 200.0;
+        text.set_view_height(Some(view_height));
+        let text_block_bounds = || {
+            let lines = text.data.lines.borrow();
+            let first_line = &lines[ViewLine(0)];
+            let last_line = &lines[text.data.lines.last_line_index()];
+            let top = first_line.baseline() + first_line.metrics().ascender;
+            let bottom = last_line.baseline() + last_line.metrics().descender;
+            (top, bottom)
         };
-        clipboard::write_text(encoded);
+        let assert_near = |a: f32, b: f32| assert!((a - b).abs() <= 1.0, "{a} != {b}");
+        let (top, bottom) = text_block_bounds();
+        assert_near(top, 0.0);
+        let content_height = top - bottom;
+        text.set_vertical_alignment(VerticalAlignment::Bottom);
+        assert_near(text_block_bounds().1, -view_height);
+        text.set_vertical_alignment(VerticalAlignment::Middle);
+        assert_near(text_block_bounds().0, -(view_height - content_height) / 2.0);
+        text.set_vertical_alignment(VerticalAlignment::Top);
+        let grid_step = 7.0;
+        text.set_baseline_grid(Some(grid_step));
+        for line in &*text.data.lines.borrow() {
+            assert_eq!(line.baseline() % grid_step, 0.0);
+        }
     }
 
-    /// Paste new text in the place of current selections / cursors. In case of pasting multiple
-    /// chunks (e.g. after copying multiple selections), the chunks will be pasted into subsequent
-    /// selections. In case there are more chunks than selections, end chunks will be dropped. In
-    /// case there is more selections than chunks, end selections will be replaced with empty
-    /// strings. I `self.single_line` is set to true then each chunk will be truncated to its first
-    /// line.
-    fn paste_string(&self, s: &str) {
-        let mut chunks = self.decode_paste(s);
-        if self.frp.output.single_line_mode.value() {
-            for f in &mut chunks {
-                Self::drop_all_but_first_line(f);
-            }
+    #[test]
+    fn long_lines_are_wrapped_into_rows() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("foo bar baz\nqux");
+        let second_baseline = || text.data.lines.borrow()[ViewLine(1)].baseline();
+        let unwrapped_baseline = second_baseline();
+        let wrap_width = text.data.lines.borrow()[ViewLine(0)].div_by_column(Column(5));
+        text.set_wrap_mode(wrap::WrapMode::Width(wrap_width));
+        assert!(text.data.lines.borrow()[ViewLine(0)].rows.is_wrapped());
+        assert!(second_baseline() < unwrapped_baseline);
+
+        text.set_cursor(Location(Line(0), Column(1)));
+        text.cursor_move_down();
+        let cursor = || text.selections.value().newest().unwrap().end;
+        assert_eq!(cursor().line, Line(0));
+        assert!(cursor().offset >= Column(4));
+        text.cursor_move_left_of_line();
+        assert_eq!(cursor(), Location(Line(0), Column(4)));
+        text.cursor_move_up();
+        assert_eq!(cursor(), Location(Line(0), Column(0)));
+
+        text.set_wrap_mode(wrap::WrapMode::None);
+        assert!(!text.data.lines.borrow()[ViewLine(0)].rows.is_wrapped());
+        assert_eq!(second_baseline(), unwrapped_baseline);
+    }
+
+    #[test]
+    fn wrapped_words_are_hyphenated() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("foo hyphenation");
+        let wrap_width = text.data.lines.borrow()[ViewLine(0)].div_by_column(Column(9));
+        let dictionary = hyphenation::Dictionary::new("en-US").word("hy-phen-a-tion");
+        text.add_hyphenation_dictionary(dictionary);
+        text.set_wrap_mode(wrap::WrapMode::Width(wrap_width));
+        {
+            let line = &text.data.lines.borrow()[ViewLine(0)];
+            assert_eq!(line.rows.start(1), Column(4));
+            assert!(line.rows.hyphenated_rows().is_empty());
         }
-        self.buffer.frp.paste(chunks);
+
+        text.set_hyphenation_language(Some("en-US".into()));
+        {
+            let line = &text.data.lines.borrow()[ViewLine(0)];
+            assert_eq!(line.rows.start(1), Column(6));
+            assert_eq!(line.rows.hyphenated_rows(), &[0]);
+            assert_eq!(line.hyphens().len(), 1);
+            assert_eq!(line.hyphens()[0].x(), line.row_end_x(0));
+            assert_eq!(line.hyphens()[0].color(), line.glyphs[Column(5)].color());
+        }
+
+        text.set_hyphenation_language(None);
+        let line = &text.data.lines.borrow()[ViewLine(0)];
+        assert_eq!(line.rows.start(1), Column(4));
+        assert!(line.hyphens().is_empty());
     }
 
-    fn decode_paste(&self, encoded: &str) -> Vec<String> {
-        encoded.split(CLIPBOARD_RECORD_SEPARATOR).map(|s| s.into()).collect()
+    #[test]
+    fn annotations_follow_edits() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("let x = 1");
+        let range = |start, end| buffer::Range::new(Byte(start), Byte(end));
+        let annotation = annotation::Annotation::new(range(4, 5), "warning");
+        text.import_annotations(Rc::new(annotation::Document::new(vec![annotation])));
+        text.set_cursor(Location(Line(0), Column(0)));
+        text.insert("// ");
+        let ranges =
+            || text.annotations.value().annotations.iter().map(|t| t.range()).collect_vec();
+        assert_eq!(ranges(), vec![range(7, 8)]);
+        assert_eq!(text.export_annotations().annotations[0].range(), range(7, 8));
+        text.set_content("x");
+        assert_eq!(ranges(), vec![range(1, 1)]);
     }
 
-    fn drop_all_but_first_line(s: &mut String) {
-        *s = s.lines().next().unwrap_or("").to_string();
+    #[test]
+    fn annotations_are_cropped_after_redo() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("let x = 1 + 2");
+        let start = LocationLike::from(Location(Line(0), Column(9)));
+        let end = LocationLike::from(Location(Line(0), Column(13)));
+        text.select(start, end);
+        text.delete_left();
+        text.undo();
+        let range = |start, end| buffer::Range::new(Byte(start), Byte(end));
+        let annotation = annotation::Annotation::new(range(10, 13), "warning");
+        text.import_annotations(Rc::new(annotation::Document::new(vec![annotation])));
+        text.redo();
+        assert_eq!(text.content.value().to_string(), "let x = 1");
+        let ranges =
+            || text.annotations.value().annotations.iter().map(|t| t.range()).collect_vec();
+        assert_eq!(ranges(), vec![range(9, 9)]);
     }
 
-    fn process_key_event(
-        event: &ensogl_core::event::Event<KeyDown>,
-        single_line_mode: &bool,
-    ) -> Option<ImString> {
-        let check_modifiers = (!event.ctrl() && !event.meta()).then_some(());
-        let text =
-            check_modifiers.and_then(|_| Self::key_to_string(event.key(), *single_line_mode));
-        if text.is_some() {
-            event.stop_propagation();
-        }
-        text
+    #[test]
+    fn highlight_sets_are_managed_independently() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("let x = x + 1");
+        let range = |start, end| buffer::Range::new(Byte(start), Byte(end));
+        let style = |z_order| highlight::Style::new(color::Rgba::red(), z_order);
+        let occurrences = vec![range(4, 5), range(8, 9)];
+        text.set_highlight_set(highlight::Set::new("occurrences", occurrences, style(1)));
+        text.set_highlight_set(highlight::Set::new("search", vec![range(0, 3)], style(0)));
+        let sets = || text.highlight_sets.value().iter().map(|t| t.name.clone()).collect_vec();
+        assert_eq!(sets(), vec!["search", "occurrences"]);
+
+        text.set_highlight_set(highlight::Set::new("search", vec![range(8, 9)], style(2)));
+        assert_eq!(sets(), vec!["occurrences", "search"]);
+        text.set_cursor(Location(Line(0), Column(0)));
+        text.insert("// ");
+        let ranges = text.highlight_sets.value().iter().map(|t| t.ranges.clone()).collect_vec();
+        assert_eq!(ranges, vec![vec![range(7, 8), range(11, 12)], vec![range(11, 12)]]);
+
+        text.clear_highlight_set(ImString::new("occurrences"));
+        assert_eq!(sets(), vec!["search"]);
+        text.clear_highlight_sets();
+        assert!(sets().is_empty());
     }
 
-    fn key_to_string(key: &Key, single_line_mode: bool) -> Option<ImString> {
-        match key {
-            Key::Character(s) => Some(s.into()),
-            Key::Enter if !single_line_mode => Some("\n".into()),
-            Key::Space => Some(" ".into()),
-            _ => None,
-        }
+    #[test]
+    fn highlight_sets_are_cropped_after_redo() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("let x = 1 + 2");
+        let start = LocationLike::from(Location(Line(0), Column(9)));
+        let end = LocationLike::from(Location(Line(0), Column(13)));
+        text.select(start, end);
+        text.delete_left();
+        text.undo();
+        let range = |start, end| buffer::Range::new(Byte(start), Byte(end));
+        let style = highlight::Style::new(color::Rgba::red(), 0);
+        text.set_highlight_set(highlight::Set::new("search", vec![range(10, 13)], style));
+        text.redo();
+        let ranges = text.highlight_sets.value().iter().map(|t| t.ranges.clone()).collect_vec();
+        assert_eq!(ranges, vec![vec![range(9, 9)]]);
     }
-}
 
+    #[test]
+    fn picked_glyphs_are_resolved_to_locations() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("ab\ncd");
+        text.set_glyph_picking(true);
+        let lines = text.data.lines.borrow();
+        let glyph = &lines[ViewLine(1)].glyphs[Column(1)];
+        assert!(lines.iter().all(|line| line.glyphs.iter().all(|g| g.is_pickable())));
+        let target = glyph.view.display_object().clone_ref();
+        assert_eq!(text.data.glyph_location(&target), Some(Location(Line(1), Column(1))));
+        assert_eq!(text.data.glyph_location(text.display_object()), None);
+    }
 
+    #[test]
+    fn search_matches_are_highlighted() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("foo bar Foo");
+        let range = |start, end| buffer::Range::new(Byte(start), Byte(end));
+        text.find_all(buffer::Query::text("foo"), None);
+        assert_eq!(*text.search_matches.value(), vec![range(0, 3)]);
+        let options = buffer::SearchOptions { case_insensitive: true, regex: false };
+        let query = buffer::Query::new("foo", options).unwrap();
+        let bold = formatting::Property::from(formatting::Weight::Bold);
+        text.find_all(query, Some(bold));
+        assert_eq!(*text.search_matches.value(), vec![range(0, 3), range(8, 11)]);
+        let formatting = text.data.buffer.formatting.get();
+        let not_bold = formatting.span_ranges_of_default_values(formatting::PropertyTag::Weight);
+        assert!(not_bold.iter().all(|t| t.start >= Byte(3) && t.end <= Byte(8)));
+    }
 
-// ==============
-// === Traits ===
-// ==============
+    #[test]
+    fn search_matches_are_replaced() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("foo bar foo foo");
+        let content = || text.content.value().to_string();
+        text.find_all(buffer::Query::text("foo"), None);
+        text.set_cursor(Location(Line(0), Column(0)));
+        // The cursor is not a match, so the first match is only selected.
+        text.replace_next("baz");
+        assert_eq!(text.replacements_made.value(), 0);
+        assert_eq!(content(), "foo bar foo foo");
+        text.replace_next("baz");
+        assert_eq!(text.replacements_made.value(), 1);
+        assert_eq!(content(), "baz bar foo foo");
+        let range = |start, end| buffer::Range::new(Byte(start), Byte(end));
+        assert_eq!(text.current_match.value(), Some(range(8, 11)));
+
+        text.replace_all("qux");
+        assert_eq!(text.replacements_made.value(), 2);
+        assert_eq!(content(), "baz bar qux qux");
+        assert!(text.search_matches.value().is_empty());
+        text.undo();
+        assert_eq!(content(), "baz bar foo foo");
+    }
 
-impl<S, T> FromInContextSnapped<&TextModel, S> for T
-where T: for<'t> FromInContextSnapped<&'t buffer::Buffer, S>
-{
-    fn from_in_context_snapped(context: &TextModel, arg: S) -> Self {
-        T::from_in_context_snapped(&context.buffer, arg)
+    #[test]
+    fn cursor_jumps_between_search_matches() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("foo bar foo\nfoo");
+        let range = |start, end| buffer::Range::new(Byte(start), Byte(end));
+        text.find_all(buffer::Query::text("foo"), None);
+        let search_set = || {
+            let sets = text.highlight_sets.value();
+            sets.iter().find(|set| set.name == SEARCH_HIGHLIGHT_SET).map(|set| set.ranges.clone())
+        };
+        assert_eq!(search_set(), Some(vec![range(0, 3), range(8, 11), range(12, 15)]));
+
+        text.set_cursor(Location(Line(0), Column(1)));
+        text.find_next();
+        assert_eq!(text.current_match.value(), Some(range(8, 11)));
+        let selection = *text.selections.value().newest().unwrap();
+        assert_eq!(selection.start, Location(Line(0), Column(8)));
+        assert_eq!(selection.end, Location(Line(0), Column(11)));
+        text.find_next();
+        assert_eq!(text.current_match.value(), Some(range(12, 15)));
+        text.find_next();
+        assert_eq!(text.current_match.value(), Some(range(0, 3)));
+        text.find_prev();
+        assert_eq!(text.current_match.value(), Some(range(12, 15)));
+
+        text.clear_search();
+        assert!(text.search_matches.value().is_empty());
+        assert_eq!(text.current_match.value(), None);
+        assert_eq!(search_set(), None);
     }
-}
 
-impl<S, T> FromInContextSnapped<&Text, S> for T
-where T: for<'t> FromInContextSnapped<&'t TextModel, S>
-{
-    fn from_in_context_snapped(context: &Text, arg: S) -> Self {
-        T::from_in_context_snapped(&context.data, arg)
+    #[test]
+    fn matching_brackets_are_highlighted() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("f(a[0])\nb");
+        let range = |start, end| buffer::Range::new(Byte(start), Byte(end));
+        let brackets_set = || {
+            let sets = text.highlight_sets.value();
+            sets.iter().find(|set| set.name == BRACKET_HIGHLIGHT_SET).map(|set| set.ranges.clone())
+        };
+        text.set_cursor(Location(Line(0), Column(1)));
+        let pair = buffer::BracketPair { open: range(1, 2), close: range(6, 7) };
+        assert_eq!(text.matching_brackets.value(), Some(pair));
+        assert_eq!(brackets_set(), Some(vec![range(1, 2), range(6, 7)]));
+
+        text.jump_to_matching_bracket();
+        assert_eq!(text.cursor_location.value(), Some(Location(Line(0), Column(6))));
+        text.jump_to_matching_bracket();
+        assert_eq!(text.cursor_location.value(), Some(Location(Line(0), Column(1))));
+
+        text.set_cursor(Location(Line(1), Column(0)));
+        assert_eq!(text.matching_brackets.value(), None);
+        assert_eq!(brackets_set(), None);
+        text.jump_to_matching_bracket();
+        assert_eq!(text.cursor_location.value(), Some(Location(Line(1), Column(0))));
     }
-}
 
-impl FrpNetworkProvider for Text {
-    fn network(&self) -> &frp::Network {
-        self.frp.network()
+    #[test]
+    fn tokens_are_styled_with_palette() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("let x = 1");
+        let range = |start, end| buffer::Range::new(Byte(start), Byte(end));
+        let bold = formatting::Format::new().weight(formatting::Weight::Bold);
+        let italic = formatting::Format::new().style(formatting::Style::Italic);
+        let palette = token_style::Palette::new([(token_style::kind::KEYWORD, bold)]);
+        text.set_token_palette(palette.clone_ref());
+        let keyword = token_style::Token::new(range(0, 3), token_style::kind::KEYWORD);
+        let number = token_style::Token::new(range(8, 9), token_style::kind::NUMBER);
+        text.set_tokens(Rc::new(vec![keyword, number]));
+        let keyword_format = text.token_palette.value().get(token_style::kind::KEYWORD);
+        assert_eq!(keyword_format.and_then(|f| f.weight), Some(formatting::Weight::Bold));
+        let formatting = text.data.buffer.formatting.get();
+        let not_bold = formatting.span_ranges_of_default_values(formatting::PropertyTag::Weight);
+        assert!(not_bold.iter().all(|t| t.start >= Byte(3)));
+
+        // Changing the palette resets the properties applied with the previous one.
+        text.set_token_palette(palette.with(token_style::kind::KEYWORD, italic));
+        let formatting = text.data.buffer.formatting.get();
+        let not_bold = formatting.span_ranges_of_default_values(formatting::PropertyTag::Weight);
+        assert!(not_bold.iter().any(|t| t.start == Byte(0) && t.end >= Byte(9)));
+        let upright = formatting.span_ranges_of_default_values(formatting::PropertyTag::Style);
+        assert!(upright.iter().all(|t| t.start >= Byte(3)));
     }
-}
 
+    #[test]
+    fn tokens_are_cropped_after_redo() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("let x = 1 + 2");
+        let start = LocationLike::from(Location(Line(0), Column(9)));
+        let end = LocationLike::from(Location(Line(0), Column(13)));
+        text.select(start, end);
+        text.delete_left();
+        text.undo();
+        let range = |start, end| buffer::Range::new(Byte(start), Byte(end));
+        let number = token_style::Token::new(range(12, 13), token_style::kind::NUMBER);
+        text.set_tokens(Rc::new(vec![number]));
+        text.redo();
+        let ranges = || text.data.tokens.borrow().tokens().iter().map(|t| t.range).collect_vec();
+        assert_eq!(ranges(), vec![range(9, 9)]);
+    }
 
+    #[test]
+    fn dom_mirror_follows_text() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("first\nsecond");
+        let mirrored_line_count = || text.data.dom_mirror.borrow().as_ref().map(|m| m.line_count());
+        assert_eq!(mirrored_line_count(), None);
+        text.set_dom_mirror(dom_mirror::Mode::Overlay);
+        assert_eq!(mirrored_line_count(), Some(2));
+        text.set_cursor(Location(Line(0), Column(5)));
+        text.insert("\nthird\n");
+        assert_eq!(mirrored_line_count(), Some(4));
+        text.undo();
+        assert_eq!(mirrored_line_count(), Some(text.line_count.value()));
+        text.set_dom_mirror(dom_mirror::Mode::Disabled);
+        assert_eq!(mirrored_line_count(), None);
+    }
 
-// ================
-// === App View ===
-// ================
+    #[test]
+    fn text_is_split_into_pages() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("a<b\nc\nd\ne\nf");
+        let line_height = text.data.measured_line_height(Line(0));
+        let setup = pagination::PageSetup::new(100.0, line_height * 2.5);
+        let pages = text.paginate(&setup);
+        let lines = pages.iter().map(|page| page.lines.clone()).collect_vec();
+        assert_eq!(lines, vec![Line(0)..Line(2), Line(2)..Line(4), Line(4)..Line(5)]);
+        let html = text.export_pages_html(&setup);
+        assert_eq!(html.matches("class=\"page\"").count(), 3);
+        assert!(html.contains("a&lt;b"));
+        text.show_page(pages[1].clone());
+        assert_eq!(text.data.buffer.first_view_line(), Line(2));
+        assert_eq!(text.data.buffer.last_view_line(), Line(3));
+    }
 
-impl application::View for Text {
-    fn label() -> &'static str {
-        "TextArea"
+    #[test]
+    fn glyph_advance_is_split_between_covered_clusters() {
+        let line = Rope::from("afie\u{301}");
+        let mut divs = NonEmptyVec::singleton(0.0);
+        assert_eq!(push_glyph_divs(&mut divs, &line, Byte(0)..Byte(1), 4.0), 1);
+        // The "fi" ligature.
+        assert_eq!(push_glyph_divs(&mut divs, &line, Byte(1)..Byte(3), 6.0), 2);
+        // A single cluster with a combining mark.
+        assert_eq!(push_glyph_divs(&mut divs, &line, Byte(3)..Byte(6), 5.0), 1);
+        assert_eq!(divs.as_slice(), &[0.0, 4.0, 7.0, 10.0, 15.0]);
     }
 
-    fn new(app: &Application) -> Self {
-        Text::new(app)
+    #[test]
+    fn tabs_reach_next_tab_stop() {
+        assert_eq!(tab_advance(0.0, 8.0), Some(8.0));
+        assert_eq!(tab_advance(3.0, 8.0), Some(5.0));
+        assert_eq!(tab_advance(8.0, 8.0), Some(8.0));
+        assert_eq!(tab_advance(3.0, 0.0), None);
+        assert_eq!(spaces_to_tab_stop("", 4), 4);
+        assert_eq!(spaces_to_tab_stop("ab", 4), 2);
+        assert_eq!(spaces_to_tab_stop("abcd", 4), 4);
+        assert_eq!(spaces_to_tab_stop("a\tb", 4), 3);
+        assert_eq!(spaces_to_tab_stop("ab", 0), 1);
     }
 
-    fn global_shortcuts() -> Vec<shortcut::Shortcut> {
-        use shortcut::ActionType::*;
-        // These shortcuts emit `focus` event when triggered.
-        let focus_capturing_shortcuts = [
-            (PressAndRepeat, "left", "cursor_move_left", ""),
-            (PressAndRepeat, "right", "cursor_move_right", ""),
-            (PressAndRepeat, "up", "cursor_move_up", "!single_line_mode"),
-            (PressAndRepeat, "down", "cursor_move_down", "!single_line_mode"),
-            (PressAndRepeat, "cmd left", "cursor_move_left_word", ""),
-            (PressAndRepeat, "cmd right", "cursor_move_right_word", ""),
-            (Press, "alt left", "cursor_move_left_of_line", ""),
-            (Press, "alt right", "cursor_move_right_of_line", ""),
-            (Press, "home", "cursor_move_left_of_line", ""),
-            (Press, "end", "cursor_move_right_of_line", ""),
-            (Press, "alt shift left", "cursor_select_left_of_line", ""),
-            (Press, "alt shift right", "cursor_select_right_of_line", ""),
-            (Press, "shift home", "cursor_select_left_of_line", ""),
-            (Press, "shift end", "cursor_select_right_of_line", ""),
-            (Press, "cmd up", "cursor_move_to_text_start", ""),
-            (Press, "cmd down", "cursor_move_to_text_end", ""),
-            (Press, "ctrl home", "cursor_move_to_text_start", ""),
-            (Press, "ctrl end", "cursor_move_to_text_end", ""),
-            (Press, "cmd shift up", "cursor_select_to_text_start", ""),
-            (Press, "cmd shift down", "cursor_select_to_text_end", ""),
-            (Press, "ctrl shift home", "cursor_select_to_text_start", ""),
-            (Press, "ctrl shift end", "cursor_select_to_text_end", ""),
-            (PressAndRepeat, "shift left", "cursor_select_left", ""),
-            (PressAndRepeat, "shift right", "cursor_select_right", ""),
-            (PressAndRepeat, "cmd shift left", "cursor_select_left_word", ""),
-            (PressAndRepeat, "cmd shift right", "cursor_select_right_word", ""),
-            (PressAndRepeat, "shift up", "cursor_select_up", "!single_line_mode"),
-            (PressAndRepeat, "shift down", "cursor_select_down", "!single_line_mode"),
-            (Press, "shift left-mouse-button", "set_newest_selection_end_to_mouse_position", ""),
-            (DoublePress, "left-mouse-button", "select_word_at_cursor", ""),
-            (Press, "left-mouse-button", "set_cursor_at_mouse_position", ""),
-            (Press, "left-mouse-button", "start_newest_selection_end_follow_mouse", ""),
-            (Press, "cmd left-mouse-button", "add_cursor_at_mouse_position", ""),
-            (Press, "cmd left-mouse-button", "start_newest_selection_end_follow_mouse", ""),
-            (Press, "cmd a", "select_all", ""),
+    #[test]
+    fn tab_key_inserts_tab_or_spaces() {
+        let app = Application::new("root");
+        let text = app.new_view::<Text>();
+        text.set_content("ab\nc");
+        let content = || text.content.value().to_string();
+        text.set_cursor(Location(Line(0), Column(2)));
+        text.insert_tab();
+        assert_eq!(content(), "ab\t\nc");
+        text.set_insert_spaces_for_tab(true);
+        text.insert_tab();
+        assert_eq!(content(), "ab\t    \nc");
+        text.set_tab_size(3);
+        text.set_cursor(Location(Line(1), Column(1)));
+        text.insert_tab();
+        assert_eq!(content(), "ab\t    \nc  ");
+
+        // The selection covering several lines is indented.
+        text.select_all();
+        text.insert_tab();
+        assert_eq!(content(), "    ab\t    \n    c  ");
+    }
+
+    #[test]
+    fn touching_selections_are_merged() {
+        let selection = |line: usize, start: usize, end: usize, id: usize| {
+            let start = Location(Line(line), Column(start));
+            let end = Location(Line(line), Column(end));
+            buffer::Selection::new(start, end, selection::Id { value: id })
+        };
+        let selections = [
+            selection(0, 0, 2, 0),
+            selection(0, 2, 4, 1),
+            selection(0, 4, 4, 2),
+            selection(0, 6, 8, 3),
+            selection(1, 0, 2, 4),
         ];
-        let non_focus_capturing_shortcuts = [
-            (Press, "cmd c", "copy", ""),
-            (Press, "cmd x", "cut", ""),
-            (Press, "cmd v", "paste", ""),
-            (Press, "cmd z", "undo", ""),
-            (Press, "escape", "keep_oldest_cursor_only", ""),
-            (Release, "left-mouse-button", "stop_newest_selection_end_follow_mouse", ""),
-            (Release, "cmd left-mouse-button", "stop_newest_selection_end_follow_mouse", ""),
-            (PressAndRepeat, "backspace", "delete_left", ""),
-            (PressAndRepeat, "delete", "delete_right", ""),
-            (PressAndRepeat, "cmd backspace", "delete_word_left", ""),
-            (PressAndRepeat, "cmd delete", "delete_word_right", ""),
+        let merged = merge_adjacent_selections(&selections);
+        let expected = [
+            selection(0, 0, 4, 0),
+            selection(0, 4, 4, 2),
+            selection(0, 6, 8, 3),
+            selection(1, 0, 2, 4),
         ];
-        non_focus_capturing_shortcuts
-            .iter()
-            .copied()
-            .chain(focus_capturing_shortcuts.iter().copied())
-            .chain(focus_capturing_shortcuts.iter().map(|(a, r, _, c)| (*a, *r, "focus", *c)))
-            .map(|(action, rule, command, condition)| {
-                let only_hovered = action != Release && rule.contains("left-mouse-button");
-                let base_condition = if only_hovered { "focused & hovered" } else { "focused" };
-                let condition = if condition.is_empty() {
-                    Cow::from(base_condition)
-                } else {
-                    Cow::from(format!("{base_condition} & {condition}"))
-                };
-                Self::self_shortcut_when(action, rule, command, condition.as_ref())
-            })
-            .collect()
+        assert_eq!(merged, expected);
     }
-}
-
-
 
-// =============
-// === Tests ===
-// =============
+    fn clamp(text: &str) -> Option<ClampedText> {
+        let rope = Rope::from(text);
+        ClampedText::new(&rope, text, Byte(0)..Byte(text.len()))
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn moderate_combining_marks_are_not_clamped() {
+        // The zalgo string from the text area example.
+        assert!(clamp("Z̮̞̠͙͔ͅḀ̗̞͈̻̗Ḷ͙͎̯̹̞͓G̻O̭̗̮").is_none());
+        assert!(clamp("test").is_none());
+        // A family emoji ZWJ sequence is longer than the limit in bytes, but not in code points.
+        let family = concat!(
+            "\u{1F468}\u{1F3FB}\u{200D}\u{1F469}\u{1F3FB}\u{200D}",
+            "\u{1F467}\u{1F3FB}\u{200D}\u{1F466}\u{1F3FB}"
+        );
+        assert!(family.len() > MAX_SHAPED_GRAPHEME_CLUSTER_LEN);
+        assert!(clamp(family).is_none());
+    }
 
-    /// Assert that there is no inherent memory leak in the [text::Text].
     #[test]
-    fn assert_no_leak() {
-        let app = Application::new("root");
-        let text = app.new_view::<Text>();
-        let text_frp = text.frp.downgrade();
-        let text_data = Rc::downgrade(&text.data);
-        drop(text);
-        assert_eq!(text_frp.strong_count(), 0, "There are FRP references left.");
-        assert_eq!(text_data.strong_count(), 0, "There are  data references left.");
+    fn extreme_combining_marks_are_clamped() {
+        let marks = "\u{301}".repeat(1000);
+        let text = format!("a{marks}b{marks}c");
+        let clamped = clamp(&text).unwrap();
+        let mark_len = '\u{301}'.len_utf8();
+        let kept_cluster_len = 1 + (MAX_SHAPED_GRAPHEME_CLUSTER_LEN - 1) * mark_len;
+        assert_eq!(clamped.text.len(), 2 * kept_cluster_len + 1);
+        assert_eq!(clamped.text.chars().count(), 2 * MAX_SHAPED_GRAPHEME_CLUSTER_LEN + 1);
+        let original_cluster_len = 1 + 1000 * mark_len;
+        assert_eq!(clamped.original_offset(0), 0);
+        assert_eq!(clamped.original_offset(kept_cluster_len as u32), original_cluster_len as u32);
+        assert_eq!(
+            clamped.original_offset(2 * kept_cluster_len as u32),
+            2 * original_cluster_len as u32
+        );
     }
 }
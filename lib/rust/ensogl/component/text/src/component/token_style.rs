@@ -0,0 +1,219 @@
+//! Token styles for syntax highlighting. Syntax highlighters assign token kinds, like `keyword` or
+//! `comment`, to text ranges instead of concrete colors, and the [`Palette`] maps the kinds to
+//! formats. The palette of the well-known kinds is resolved through the theme, so switching the
+//! color scheme re-colors all open text areas without highlighting the text again.
+
+use crate::prelude::*;
+use enso_text::unit::*;
+
+use crate::buffer;
+use crate::buffer::formatting;
+use crate::buffer::formatting::Format;
+use crate::component::annotation;
+
+use ensogl_core::data::color;
+
+
+
+// =============
+// === Kinds ===
+// =============
+
+/// The well-known token kinds. Their formats are resolved through the theme, see [`theme_path`].
+/// Highlighters may use other kinds as well, styled by the explicitly provided [`Palette`] only.
+#[allow(missing_docs)]
+pub mod kind {
+    pub const KEYWORD: &str = "keyword";
+    pub const IDENTIFIER: &str = "identifier";
+    pub const TYPE: &str = "type";
+    pub const FUNCTION: &str = "function";
+    pub const OPERATOR: &str = "operator";
+    pub const NUMBER: &str = "number";
+    pub const STRING: &str = "string";
+    pub const COMMENT: &str = "comment";
+    pub const PUNCTUATION: &str = "punctuation";
+    pub const INVALID: &str = "invalid";
+
+    /// All the well-known kinds.
+    pub const ALL: &[&str] = &[
+        KEYWORD,
+        IDENTIFIER,
+        TYPE,
+        FUNCTION,
+        OPERATOR,
+        NUMBER,
+        STRING,
+        COMMENT,
+        PUNCTUATION,
+        INVALID,
+    ];
+}
+
+/// The theme path of the style property of the token kind. The supported properties are `color`,
+/// and `bold` and `italic` enabled by non-zero numbers, for example
+/// `component.text_area.syntax.keyword.bold`.
+pub fn theme_path(kind: &str, property: &str) -> String {
+    format!("component.text_area.syntax.{kind}.{property}")
+}
+
+/// The format of the token kind defined by the theme values, see [`theme_path`].
+pub fn format_from_theme(color: Option<color::Rgba>, bold: bool, italic: bool) -> Format {
+    let mut format = Format::new();
+    format.color = color.map(|color| color.into());
+    format.weight = bold.then_some(formatting::Weight::Bold);
+    format.style = italic.then_some(formatting::Style::Italic);
+    format
+}
+
+
+
+// =============
+// === Token ===
+// =============
+
+/// A text range of the given token kind.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub struct Token {
+    pub range: buffer::Range<Byte>,
+    pub kind:  ImString,
+}
+
+impl Token {
+    /// Constructor.
+    pub fn new(range: buffer::Range<Byte>, kind: impl Into<ImString>) -> Self {
+        let kind = kind.into();
+        Self { range, kind }
+    }
+}
+
+
+
+// ===============
+// === Palette ===
+// ===============
+
+/// A shared immutable map from token kinds to formats. It can be cheaply shared between all text
+/// areas of an editor. Modifying the palette creates a new one.
+#[derive(Clone, CloneRef, Debug, Default)]
+pub struct Palette {
+    formats: Rc<HashMap<ImString, Format>>,
+}
+
+impl Palette {
+    /// Constructor.
+    pub fn new(formats: impl IntoIterator<Item = (impl Into<ImString>, Format)>) -> Self {
+        let formats = formats.into_iter().map(|(kind, format)| (kind.into(), format)).collect();
+        Self { formats: Rc::new(formats) }
+    }
+
+    /// The format of the token kind.
+    pub fn get(&self, kind: &str) -> Option<Format> {
+        self.formats.get(kind).copied()
+    }
+
+    /// A new palette with the format of the token kind set.
+    pub fn with(&self, kind: impl Into<ImString>, format: Format) -> Self {
+        let mut formats = (*self.formats).clone();
+        formats.insert(kind.into(), format);
+        Self { formats: Rc::new(formats) }
+    }
+
+    /// A new palette with the formats of the `other` palette replacing the formats of this one.
+    pub fn overridden_by(&self, other: &Palette) -> Self {
+        let mut formats = (*self.formats).clone();
+        formats.extend(other.formats.iter().map(|(kind, format)| (kind.clone(), *format)));
+        Self { formats: Rc::new(formats) }
+    }
+
+    /// The number of the token kinds with formats.
+    pub fn len(&self) -> usize {
+        self.formats.len()
+    }
+
+    /// Check whether there are no formats.
+    pub fn is_empty(&self) -> bool {
+        self.formats.is_empty()
+    }
+}
+
+
+
+// ==============
+// === Styled ===
+// ==============
+
+/// A property to set on the text ranges.
+pub type RangesProperty = (Rc<Vec<buffer::Range<Byte>>>, Option<formatting::Property>);
+
+/// The tokens of a text area together with the properties applied to their ranges. The applied
+/// properties are reset to the default values when the tokens or their formats change.
+#[derive(Clone, Debug, Default)]
+pub struct Styled {
+    tokens:  Vec<Token>,
+    applied: Vec<(buffer::Range<Byte>, formatting::PropertyTag)>,
+}
+
+impl Styled {
+    /// Replace the tokens. The properties should be updated with [`Self::restyle`] afterwards.
+    pub fn set_tokens(&mut self, tokens: Vec<Token>) {
+        self.tokens = tokens;
+    }
+
+    /// The current tokens.
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    /// Move the ranges to keep them in place after the text change.
+    pub fn apply_change(&mut self, change: &buffer::Change) {
+        for token in &mut self.tokens {
+            token.range = annotation::follow_change(token.range, change);
+        }
+        for (range, _) in &mut self.applied {
+            *range = annotation::follow_change(*range, change);
+        }
+    }
+
+    /// Update all ranges with the provided function, for example to crop them to the text.
+    pub fn map_ranges(&mut self, f: impl Fn(buffer::Range<Byte>) -> buffer::Range<Byte>) {
+        for token in &mut self.tokens {
+            token.range = f(token.range);
+        }
+        for (range, _) in &mut self.applied {
+            *range = f(*range);
+        }
+    }
+
+    /// The properties resetting the previously applied ones, followed by the properties of the
+    /// tokens formatted with the palette. Tokens of kinds missing in the palette are not styled.
+    pub fn restyle(&mut self, palette: &Palette) -> Vec<RangesProperty> {
+        let mut resets: Vec<(formatting::PropertyTag, Vec<buffer::Range<Byte>>)> = vec![];
+        for (range, tag) in mem::take(&mut self.applied) {
+            match resets.iter_mut().find(|(t, _)| *t == tag) {
+                Some((_, ranges)) => ranges.push(range),
+                None => resets.push((tag, vec![range])),
+            }
+        }
+        let properties =
+            resets.into_iter().map(|(tag, ranges)| (Rc::new(ranges), Some(tag.default_property())));
+        let mut properties = properties.collect_vec();
+        let mut ranges_by_kind: Vec<(&ImString, Vec<buffer::Range<Byte>>)> = vec![];
+        for token in &self.tokens {
+            match ranges_by_kind.iter_mut().find(|(kind, _)| **kind == token.kind) {
+                Some((_, ranges)) => ranges.push(token.range),
+                None => ranges_by_kind.push((&token.kind, vec![token.range])),
+            }
+        }
+        for (kind, ranges) in ranges_by_kind {
+            let Some(format) = palette.get(kind) else { continue };
+            for property in format.properties() {
+                for range in &ranges {
+                    self.applied.push((*range, property.tag()));
+                }
+                properties.push((Rc::new(ranges.clone()), Some(property.into())));
+            }
+        }
+        properties
+    }
+}
@@ -0,0 +1,251 @@
+//! Diagnostics of whitespace issues: trailing whitespace and indentation mixing tabs and spaces.
+//! The issues are computed per line and updated incrementally after buffer edits, so only the
+//! changed lines are inspected again.
+
+use crate::prelude::*;
+use enso_text::unit::*;
+
+use crate::buffer;
+
+use ensogl_core::data::color;
+use ensogl_core::display;
+use ensogl_core::display::shape::Rectangle;
+use std::collections::BTreeSet;
+
+
+
+// =================
+// === IssueKind ===
+// =================
+
+/// A kind of whitespace issue.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[allow(missing_docs)]
+pub enum IssueKind {
+    #[default]
+    TrailingWhitespace,
+    MixedIndentation,
+}
+
+impl IssueKind {
+    /// The background color of the text range having the issue.
+    pub fn color(self) -> color::Rgba {
+        match self {
+            Self::TrailingWhitespace => color::Rgba::new(0.86, 0.26, 0.26, 0.35),
+            Self::MixedIndentation => color::Rgba::new(0.92, 0.6, 0.2, 0.35),
+        }
+    }
+}
+
+
+
+// =============
+// === Issue ===
+// =============
+
+/// A whitespace issue in the given text range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub struct Issue {
+    pub kind:  IssueKind,
+    pub range: buffer::Range<Byte>,
+}
+
+/// Find the whitespace issues of a single line. The line should not contain the line ending. The
+/// returned ranges are relative to the line start.
+pub fn line_issues(line: &str) -> Vec<(IssueKind, std::ops::Range<Byte>)> {
+    let is_blank = |c: char| c == ' ' || c == '\t';
+    let mut issues = vec![];
+    let indentation = &line[..line.len() - line.trim_start_matches(is_blank).len()];
+    if indentation.contains(' ') && indentation.contains('\t') {
+        issues.push((IssueKind::MixedIndentation, Byte(0)..Byte(indentation.len())));
+    }
+    let content_len = line.trim_end_matches(is_blank).len();
+    if content_len < line.len() {
+        issues.push((IssueKind::TrailingWhitespace, Byte(content_len)..Byte(line.len())));
+    }
+    issues
+}
+
+
+
+// ==============
+// === Issues ===
+// ==============
+
+/// Per-line state of whitespace issues.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Issues {
+    map: BTreeMap<Line, Vec<(IssueKind, std::ops::Range<Byte>)>>,
+}
+
+impl Issues {
+    /// Iterate over all lines having any issue, together with their issues. The ranges are
+    /// relative to the line start.
+    pub fn iter(&self) -> impl Iterator<Item = (Line, &[(IssueKind, std::ops::Range<Byte>)])> {
+        self.map.iter().map(|(line, issues)| (*line, issues.as_slice()))
+    }
+
+    /// Check whether there are no issues.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Remove all issues.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Inspect the line again. The `line_text` should not contain the line ending. If it is
+    /// [`None`], the line does not exist.
+    pub fn update_line(&mut self, line: Line, line_text: Option<&str>) {
+        let issues = line_text.map(line_issues).unwrap_or_default();
+        if issues.is_empty() {
+            self.map.remove(&line);
+        } else {
+            self.map.insert(line, issues);
+        }
+    }
+
+    /// Update the issues after the buffer changes. Issues of lines below the changes are shifted
+    /// by the number of inserted or removed lines, and the changed lines are inspected again with
+    /// the `line_text` function.
+    pub fn apply_changes<'a>(
+        &mut self,
+        changes: impl IntoIterator<Item = &'a buffer::Change>,
+        line_text: impl Fn(Line) -> Option<String>,
+    ) {
+        let mut dirty_lines = BTreeSet::new();
+        for change in changes {
+            let start = *change.change_range.start();
+            let end = *change.change_range.end();
+            let line_diff = change.line_diff;
+            let moved = self.map.split_off(&(end + Line(1)));
+            self.map.retain(|line, _| *line < start);
+            self.map.extend(moved.into_iter().map(|(line, issues)| (line + line_diff, issues)));
+            dirty_lines = dirty_lines
+                .into_iter()
+                .filter(|line| *line < start || *line > end)
+                .map(|line| if line > end { line + line_diff } else { line })
+                .collect();
+            let new_end = std::cmp::max(start, end + line_diff);
+            dirty_lines.extend((start.value..=new_end.value).map(Line));
+        }
+        for line in dirty_lines {
+            self.update_line(line, line_text(line).as_deref());
+        }
+    }
+}
+
+
+
+// ============
+// === View ===
+// ============
+
+/// The view displaying the whitespace issues as colored backgrounds of the text ranges.
+#[derive(Clone, CloneRef, Debug, display::Object)]
+pub struct View {
+    display_object: display::object::Instance,
+    views:          Rc<RefCell<Vec<Rectangle>>>,
+    /// The per-line issues.
+    pub issues:     Rc<RefCell<Issues>>,
+}
+
+impl View {
+    /// Constructor.
+    pub fn new() -> Self {
+        let display_object = display::object::Instance::new_named("WhitespaceIssues");
+        let views = default();
+        let issues = default();
+        Self { display_object, views, issues }
+    }
+
+    /// Redraw the issue backgrounds. The `line_range_rect` function should return the bottom-left
+    /// corner and the size of the given in-line byte range, if the line is visible.
+    pub fn redraw(
+        &self,
+        line_range_rect: impl Fn(Line, std::ops::Range<Byte>) -> Option<(Vector2, Vector2)>,
+    ) {
+        let mut views = self.views.borrow_mut();
+        for view in views.drain(..) {
+            view.unset_parent();
+        }
+        for (line, issues) in self.issues.borrow().iter() {
+            for (kind, range) in issues {
+                if let Some((position, size)) = line_range_rect(line, range.clone()) {
+                    let view = Rectangle().build(|r| {
+                        r.set_pointer_events(false);
+                        r.set_color(kind.color());
+                    });
+                    view.set_xy(position);
+                    view.set_size(size);
+                    self.display_object.add_child(&view);
+                    views.push(view);
+                }
+            }
+        }
+    }
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(change_range: RangeInclusive<Line>, line_diff: i32) -> buffer::Change {
+        let line_diff = LineDiff(line_diff);
+        buffer::Change { change_range, line_diff, ..default() }
+    }
+
+    #[test]
+    fn issues_of_a_line() {
+        use IssueKind::*;
+        assert_eq!(line_issues("foo"), vec![]);
+        assert_eq!(line_issues("\tfoo  \t"), vec![(TrailingWhitespace, Byte(4)..Byte(7))]);
+        assert_eq!(line_issues(" \tfoo"), vec![(MixedIndentation, Byte(0)..Byte(2))]);
+        assert_eq!(line_issues("\t "), vec![
+            (MixedIndentation, Byte(0)..Byte(2)),
+            (TrailingWhitespace, Byte(0)..Byte(2)),
+        ]);
+    }
+
+    #[test]
+    fn only_changed_lines_are_inspected() {
+        let lines = RefCell::new(vec!["a ", "b", "c ", "d "]);
+        let line_text = |line: Line| lines.borrow().get(line.value).map(|t| t.to_string());
+        let mut issues = Issues::default();
+        issues.apply_changes(&[change(Line(0)..=Line(3), 0)], line_text);
+        let issue_lines = |issues: &Issues| issues.iter().map(|(line, _)| line).collect_vec();
+        assert_eq!(issue_lines(&issues), vec![Line(0), Line(2), Line(3)]);
+
+        // Inserting a new line after the first one.
+        lines.borrow_mut().insert(1, "x");
+        let inspected = RefCell::new(vec![]);
+        let line_text = |line: Line| {
+            inspected.borrow_mut().push(line);
+            lines.borrow().get(line.value).map(|t| t.to_string())
+        };
+        issues.apply_changes(&[change(Line(0)..=Line(0), 1)], line_text);
+        assert_eq!(inspected.take(), vec![Line(0), Line(1)]);
+        assert_eq!(issue_lines(&issues), vec![Line(0), Line(3), Line(4)]);
+
+        // Removing the two last lines.
+        lines.borrow_mut().truncate(3);
+        issues.apply_changes(&[change(Line(2)..=Line(4), -2)], |line| {
+            lines.borrow().get(line.value).map(|t| t.to_string())
+        });
+        assert_eq!(issue_lines(&issues), vec![Line(0)]);
+    }
+}
@@ -0,0 +1,282 @@
+//! Soft wrapping of long lines. A wrapped line is displayed in several visual rows. The rows are
+//! broken after whitespace if possible, or between any columns otherwise. If the hyphenation is
+//! enabled, the words are broken at their syllable boundaries instead, see [`Rows::hyphenated`].
+//! The buffer is not affected, the rows exist only in the line views.
+
+use crate::prelude::*;
+use enso_text::unit::*;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+
+
+// ================
+// === WrapMode ===
+// ================
+
+/// The soft wrapping mode of the text area.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum WrapMode {
+    /// The lines are not wrapped.
+    #[default]
+    None,
+    /// The lines are wrapped at the given width.
+    Width(f32),
+    /// The lines are wrapped at the view width. The lines are not wrapped if the view width is not
+    /// set.
+    Viewport,
+}
+
+impl WrapMode {
+    /// The width at which the lines should be wrapped, if any.
+    pub fn width(self, view_width: Option<f32>) -> Option<f32> {
+        let width = match self {
+            Self::None => None,
+            Self::Width(width) => Some(width),
+            Self::Viewport => view_width,
+        };
+        width.filter(|width| *width > 0.0)
+    }
+}
+
+
+
+// ============
+// === Rows ===
+// ============
+
+/// The visual rows of a single line. The x-axis positions are expressed in the coordinates of the
+/// unwrapped line, in which the rows are placed one after another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rows {
+    /// The first column of every row. The first row always starts at the column zero.
+    starts:       NonEmptyVec<Column>,
+    /// The x-axis position of the beginning of every row.
+    offsets:      NonEmptyVec<f32>,
+    /// The rows ending with a word broken by the hyphenation, in ascending order.
+    hyphens:      Vec<usize>,
+    column_count: usize,
+}
+
+impl Default for Rows {
+    fn default() -> Self {
+        let starts = NonEmptyVec::singleton(Column(0));
+        let offsets = NonEmptyVec::singleton(0.0);
+        let hyphens = default();
+        let column_count = 0;
+        Self { starts, offsets, hyphens, column_count }
+    }
+}
+
+impl Rows {
+    /// A single row containing all the columns. The `divs` are the division points between the
+    /// columns, see [`crate::component::line::View::divs`].
+    pub fn unwrapped(divs: &[f32]) -> Self {
+        let column_count = divs.len().saturating_sub(1);
+        Self { column_count, ..default() }
+    }
+
+    /// Break the line into rows not wider than the given width. The `divs` are the division points
+    /// between the columns. The rows are broken after the whitespace columns, and the whitespace
+    /// at the end of a row may exceed the width. A row without whitespace is broken at the last
+    /// column fitting the width. Every row contains at least one column.
+    pub fn new(divs: &[f32], width: f32, is_whitespace: impl Fn(Column) -> bool) -> Self {
+        Self::hyphenated(divs, width, is_whitespace, |_| 0.0, |_, _| None)
+    }
+
+    /// Break the line into rows like [`Self::new`], but the word not fitting the row is broken
+    /// with a hyphen if possible. The `break_word` function is given the columns of the word and
+    /// a function checking whether the row broken before the given column, followed by a hyphen
+    /// of the `hyphen_width` of that column, fits the width. It returns the column at which the
+    /// word should be broken, or [`None`] if the word can not be broken.
+    pub fn hyphenated(
+        divs: &[f32],
+        width: f32,
+        is_whitespace: impl Fn(Column) -> bool,
+        hyphen_width: impl Fn(Column) -> f32,
+        break_word: impl Fn(Range<Column>, &dyn Fn(Column) -> bool) -> Option<Column>,
+    ) -> Self {
+        let mut rows = Self::unwrapped(divs);
+        let mut row_start = Column(0);
+        let mut last_break = None;
+        for column in (0..rows.column_count).map(Column) {
+            let whitespace = is_whitespace(column);
+            let overflows = divs[column.value + 1] - divs[row_start.value] > width;
+            if overflows && !whitespace && column > row_start {
+                let word = word_around(column, rows.column_count, &is_whitespace);
+                let fits = |break_column: Column| {
+                    let row_width = divs[break_column.value] - divs[row_start.value];
+                    let in_row = break_column > row_start && break_column <= column;
+                    in_row
+                        && break_column > word.start
+                        && row_width + hyphen_width(break_column) <= width
+                };
+                let hyphen_break = break_word(word.clone(), &fits).filter(|t| fits(*t));
+                if hyphen_break.is_some() {
+                    rows.hyphens.push(rows.count() - 1);
+                }
+                let whitespace_break = last_break.filter(|start| *start > row_start);
+                row_start = hyphen_break.or(whitespace_break).unwrap_or(column);
+                last_break = None;
+                rows.starts.push(row_start);
+                rows.offsets.push(divs[row_start.value]);
+            }
+            if whitespace {
+                last_break = Some(column + Column(1));
+            }
+        }
+        rows
+    }
+
+    /// The number of rows.
+    pub fn count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Whether the line is displayed in more than one row.
+    pub fn is_wrapped(&self) -> bool {
+        self.count() > 1
+    }
+
+    /// The first column of the row.
+    pub fn start(&self, row: usize) -> Column {
+        self.starts.get(row).copied().unwrap_or(Column(self.column_count))
+    }
+
+    /// The column after the last column of the row, which is the first column of the next row.
+    pub fn end(&self, row: usize) -> Column {
+        self.starts.get(row + 1).copied().unwrap_or(Column(self.column_count))
+    }
+
+    /// The last column of the row. It is the row start if the row is empty.
+    pub fn last_column(&self, row: usize) -> Column {
+        let start = self.start(row);
+        let end = self.end(row);
+        if end > start {
+            Column(end.value - 1)
+        } else {
+            start
+        }
+    }
+
+    /// The x-axis position of the beginning of the row in the unwrapped line.
+    pub fn x_offset(&self, row: usize) -> f32 {
+        self.offsets.get(row).copied().unwrap_or_else(|| *self.offsets.last())
+    }
+
+    /// The row containing the column. The column starting a row belongs to it, so the cursor
+    /// placed between two rows is displayed at the beginning of the second one.
+    pub fn row_of_column(&self, column: Column) -> usize {
+        self.starts.as_slice().partition_point(|start| *start <= column).saturating_sub(1)
+    }
+
+    /// The row containing the x-axis position in the unwrapped line.
+    pub fn row_of_x(&self, x: f32) -> usize {
+        self.offsets.as_slice().partition_point(|offset| *offset <= x).saturating_sub(1)
+    }
+
+    /// Whether the row ends with a word broken by the hyphenation.
+    pub fn ends_with_hyphen(&self, row: usize) -> bool {
+        self.hyphens.binary_search(&row).is_ok()
+    }
+
+    /// The rows ending with a word broken by the hyphenation.
+    pub fn hyphenated_rows(&self) -> &[usize] {
+        &self.hyphens
+    }
+}
+
+/// The columns of the word containing the non-whitespace column.
+fn word_around(
+    column: Column,
+    column_count: usize,
+    is_whitespace: impl Fn(Column) -> bool,
+) -> Range<Column> {
+    let start = (0..column.value).rev().find(|t| is_whitespace(Column(*t))).map_or(0, |t| t + 1);
+    let end = (column.value..column_count).find(|t| is_whitespace(Column(*t)));
+    Column(start)..Column(end.unwrap_or(column_count))
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The rows of a monospace line, every character being one unit wide.
+    fn row_starts(text: &str, width: f32) -> Vec<usize> {
+        let divs = (0..=text.chars().count()).map(|t| t as f32).collect_vec();
+        let chars = text.chars().collect_vec();
+        let rows = Rows::new(&divs, width, |column| chars[column.value].is_whitespace());
+        (0..rows.count()).map(|row| rows.start(row).value).collect()
+    }
+
+    #[test]
+    fn lines_are_broken_after_whitespace() {
+        assert_eq!(row_starts("foo bar baz", 20.0), vec![0]);
+        assert_eq!(row_starts("foo bar baz", 7.0), vec![0, 8]);
+        assert_eq!(row_starts("foo bar baz", 5.0), vec![0, 4, 8]);
+        // The whitespace at the end of a row may exceed the width.
+        assert_eq!(row_starts("foo   bar", 4.0), vec![0, 6]);
+        // The words longer than the width are broken between any columns.
+        assert_eq!(row_starts("foobarbaz qux", 4.0), vec![0, 4, 8, 10]);
+        assert_eq!(row_starts("foo", 0.5), vec![0, 1, 2]);
+        assert_eq!(row_starts("", 5.0), vec![0]);
+    }
+
+    #[test]
+    fn words_are_hyphenated_at_break_points() {
+        let text = "foo hyphenation".chars().collect_vec();
+        let divs = (0..=text.len()).map(|t| t as f32).collect_vec();
+        let is_whitespace = |column: Column| text[column.value].is_whitespace();
+        // The syllable boundaries of "hyphenation", relative to the word start.
+        let break_points = [2, 6, 7];
+        let break_word = |word: Range<Column>, fits: &dyn Fn(Column) -> bool| {
+            let columns = break_points.iter().rev().map(|t| word.start + Column(*t));
+            columns.filter(|t| *t < word.end).find(|t| fits(*t))
+        };
+        let rows = Rows::hyphenated(&divs, 8.0, is_whitespace, |_| 1.0, break_word);
+        let starts = (0..rows.count()).map(|row| rows.start(row).value).collect_vec();
+        assert_eq!(starts, vec![0, 6, 11]);
+        assert_eq!(rows.hyphenated_rows(), &[0, 1]);
+        assert!(rows.ends_with_hyphen(1));
+        assert!(!rows.ends_with_hyphen(2));
+        // Without break points fitting the width, the line is broken after the whitespace.
+        let rows = Rows::hyphenated(&divs, 8.0, is_whitespace, |_| 1.0, |_, _| None);
+        let starts = (0..rows.count()).map(|row| rows.start(row).value).collect_vec();
+        assert_eq!(starts, vec![0, 4, 12]);
+        assert!(rows.hyphenated_rows().is_empty());
+    }
+
+    #[test]
+    fn columns_and_positions_are_mapped_to_rows() {
+        let divs = (0..=11).map(|t| t as f32).collect_vec();
+        let text = "foo bar baz".chars().collect_vec();
+        let rows = Rows::new(&divs, 5.0, |column| text[column.value] == ' ');
+        assert!(rows.is_wrapped());
+        assert_eq!(rows.row_of_column(Column(0)), 0);
+        assert_eq!(rows.row_of_column(Column(3)), 0);
+        assert_eq!(rows.row_of_column(Column(4)), 1);
+        assert_eq!(rows.row_of_column(Column(11)), 2);
+        assert_eq!(rows.end(0), Column(4));
+        assert_eq!(rows.end(2), Column(11));
+        assert_eq!(rows.last_column(0), Column(3));
+        assert_eq!(rows.x_offset(1), 4.0);
+        assert_eq!(rows.row_of_x(3.5), 0);
+        assert_eq!(rows.row_of_x(8.0), 2);
+        assert!(!Rows::unwrapped(&divs).is_wrapped());
+    }
+
+    #[test]
+    fn wrap_width_depends_on_mode() {
+        assert_eq!(WrapMode::None.width(Some(100.0)), None);
+        assert_eq!(WrapMode::Width(50.0).width(Some(100.0)), Some(50.0));
+        assert_eq!(WrapMode::Viewport.width(Some(100.0)), Some(100.0));
+        assert_eq!(WrapMode::Viewport.width(None), None);
+    }
+}
@@ -3,6 +3,9 @@
 
 use crate::prelude::*;
 
+use ensogl_core::animation::idle;
+use ensogl_core::animation::quality;
+use ensogl_core::display::render::memory;
 use ensogl_core::display::scene;
 use ensogl_core::display::world::Context;
 use ensogl_core::system::gpu;
@@ -46,6 +49,14 @@ pub const DEFAULT_FONT: &str = "mplus1p";
 /// The name of the default font family for code.
 pub const DEFAULT_CODE_FONT: &str = "enso";
 
+/// Glyphs of the default fonts prepared in the idle time after the fonts are loaded. See
+/// [`Registry::schedule_prewarm`] to learn more.
+const PREWARMED_GLYPHS: &str = concat!(
+    " !\"#$%&'()*+,-./0123456789:;<=>?@",
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZ[\\]^_`",
+    "abcdefghijklmnopqrstuvwxyz{|}~",
+);
+
 
 
 // =====================
@@ -203,6 +214,31 @@ impl VariationAxes {
 
 
 
+impl From<NonVariableFaceHeader> for VariationAxes {
+    /// The axes values corresponding to the face header, used to render the width, weight, and
+    /// style of the text with variable fonts.
+    fn from(header: NonVariableFaceHeader) -> Self {
+        let mut axes = Self::default();
+        axes.set_weight(header.weight);
+        axes.set_style(header.style);
+        axes.set_width(header.width);
+        axes
+    }
+}
+
+impl VariationAxes {
+    /// The axes values in the format used by the shaper.
+    pub fn to_rustybuzz(&self) -> Vec<rustybuzz::Variation> {
+        let to_variation = |axis: &VariationAxis| rustybuzz::Variation {
+            tag:   axis.tag,
+            value: axis.value.into_inner(),
+        };
+        self.vec.iter().map(to_variation).collect()
+    }
+}
+
+
+
 // ============
 // === Face ===
 // ============
@@ -531,6 +567,15 @@ impl Font {
         }
     }
 
+    /// The variation axes rendering the text of the given face header. In case of non-variable
+    /// fonts, the face header selects the face, so no axes are set.
+    pub fn variable_variations(&self, header: NonVariableFaceHeader) -> VariationAxes {
+        match self {
+            Font::NonVariable(_) => default(),
+            Font::Variable(_) => header.into(),
+        }
+    }
+
     /// Get render info for the provided glyph, generating one if not found.
     pub fn glyph_info(
         &self,
@@ -544,6 +589,29 @@ impl Font {
         }
     }
 
+    /// Get render info for the provided glyph, suitable for rendering it at the given size in
+    /// screen pixels. The glyph MSDF is generated, or regenerated at a higher resolution tier, if
+    /// the cached one is not sufficient. Under the reduced quality, the tiny glyphs (not requiring
+    /// the [`msdf::Tier::Full`]) request a lower tier, which is cheaper to generate, see
+    /// [`FontWithGpuData`] docs.
+    pub fn glyph_info_for_size(
+        &self,
+        non_variable_font_variations: NonVariableFaceHeader,
+        variable_font_variations: &VariationAxes,
+        glyph_id: GlyphId,
+        size_px: f32,
+    ) -> Option<GlyphRenderInfo> {
+        let tier = msdf::Tier::for_size(size_px);
+        let is_tiny = tier < msdf::Tier::Full;
+        let tier = if is_tiny && quality::is_reduced() { tier.lower() } else { tier };
+        match self {
+            Font::NonVariable(font) =>
+                font.glyph_info_of_tier(&non_variable_font_variations, glyph_id, tier),
+            Font::Variable(font) =>
+                font.glyph_info_of_tier(variable_font_variations, glyph_id, tier),
+        }
+    }
+
     /// Get render info for the provided glyph, generating one if not found.
     pub fn glyph_info_of_known_face(
         &self,
@@ -691,23 +759,42 @@ impl<F: Family> FontTemplate<F> {
         Self { rc: Rc::new(data) }
     }
 
-    /// Get render info for one character, generating one if not found.
+    /// Get render info for one character, generating one if not found. The glyphs are generated
+    /// at the lowest resolution tier, as the layout does not depend on it, and the glyphs are
+    /// regenerated at a higher tier when rendered (see [`Self::glyph_info_of_tier`]).
     pub fn glyph_info(
         &self,
         variations: &F::Variations,
         glyph_id: GlyphId,
     ) -> Option<GlyphRenderInfo> {
-        let opt_render_info =
-            self.cache.borrow().get(variations).and_then(|t| t.glyphs.get(&glyph_id)).copied();
-        if opt_render_info.is_some() {
-            opt_render_info
-        } else {
-            self.family.with_borrowed_face(variations, |face| {
-                self.non_cached_glyph_info_of_known_face(variations, glyph_id, face)
-            })
+        self.glyph_info_of_tier(variations, glyph_id, msdf::Tier::MIN)
+    }
+
+    /// Get render info for one character, with the MSDF of at least the given resolution tier. The
+    /// MSDF is generated if not found or if the cached one has a lower tier. The replaced MSDF
+    /// stays in the atlas, so the glyphs already rendered with it are not affected.
+    pub fn glyph_info_of_tier(
+        &self,
+        variations: &F::Variations,
+        glyph_id: GlyphId,
+        tier: msdf::Tier,
+    ) -> Option<GlyphRenderInfo> {
+        match self.cached_glyph_info(variations, glyph_id) {
+            Some(render_info) if render_info.tier >= tier => Some(render_info),
+            _ => self.family.with_borrowed_face(variations, |face| {
+                self.non_cached_glyph_info_of_known_face(variations, glyph_id, face, tier)
+            }),
         }
     }
 
+    fn cached_glyph_info(
+        &self,
+        variations: &F::Variations,
+        glyph_id: GlyphId,
+    ) -> Option<GlyphRenderInfo> {
+        self.cache.borrow().get(variations).and_then(|t| t.glyphs.get(&glyph_id)).copied()
+    }
+
     /// Get render info for one character, generating one if not found.
     pub fn glyph_info_of_known_face(
         &self,
@@ -715,12 +802,11 @@ impl<F: Family> FontTemplate<F> {
         glyph_id: GlyphId,
         face: &Face,
     ) -> GlyphRenderInfo {
-        let opt_render_info =
-            self.cache.borrow().get(variations).and_then(|t| t.glyphs.get(&glyph_id)).copied();
+        let opt_render_info = self.cached_glyph_info(variations, glyph_id);
         if let Some(render_info) = opt_render_info {
             render_info
         } else {
-            self.non_cached_glyph_info_of_known_face(variations, glyph_id, face)
+            self.non_cached_glyph_info_of_known_face(variations, glyph_id, face, msdf::Tier::MIN)
         }
     }
 
@@ -729,6 +815,7 @@ impl<F: Family> FontTemplate<F> {
         variations: &F::Variations,
         glyph_id: GlyphId,
         face: &Face,
+        tier: msdf::Tier,
     ) -> GlyphRenderInfo {
         log_miss(GlyphCacheMiss {
             face:       self.name.normalized.clone(),
@@ -736,7 +823,7 @@ impl<F: Family> FontTemplate<F> {
             glyph_id:   glyph_id.0,
         });
         self.family.update_msdfgen_variations(variations);
-        let render_info = GlyphRenderInfo::load(&face.msdf, glyph_id, &self.atlas);
+        let render_info = GlyphRenderInfo::load(&face.msdf, glyph_id, &self.atlas, tier);
         if !self.cache.borrow().contains_key(variations) {
             self.cache.borrow_mut().insert(variations.clone(), default());
         }
@@ -855,16 +942,36 @@ impl FontTemplate<NonVariableFamily> {
         let shaped = rustybuzz::shape(&buzz_face, &[], buffer);
         for info in shaped.glyph_infos() {
             let id = GlyphId(info.glyph_id as u16);
-            // Load it into the cache.
-            let _ = self.glyph_info(variations, id);
+            self.prepare_glyph_by_id(variations, id);
         }
         Ok(())
     }
 
-    /// Load the glyph with the given ID into the cache.
+    /// Load the glyph with the given ID into the cache. The glyph is generated at the full
+    /// resolution, so it does not need to be regenerated regardless of the size it is displayed at.
     pub fn prepare_glyph_by_id(&self, variations: &NonVariableFaceHeader, id: GlyphId) {
         // Load it into the cache.
-        let _ = self.glyph_info(variations, id);
+        let _ = self.glyph_info_of_tier(variations, id, msdf::Tier::Full);
+    }
+
+    /// Load the glyphs for the given text into the cache in the idle time. Every character is
+    /// prepared by a separate idle task, so that the frame budget is respected. Glyphs which are
+    /// already cached at the full resolution are skipped by the tasks.
+    pub fn schedule_prewarm(&self, variations: NonVariableFaceHeader, glyphs: &str) {
+        if !self.family.faces.borrow().contains_key(&variations) {
+            warn!("Cannot prewarm font '{}', no face found for {variations:?}.", self.name);
+            return;
+        }
+        for glyph in glyphs.chars() {
+            let font = self.clone_ref();
+            idle::schedule_idle_with_priority(idle::Priority::Low, move || {
+                let mut encoded = [0; 4];
+                let glyph = glyph.encode_utf8(&mut encoded);
+                if let Err(err) = font.prepare_glyphs_for_text(&variations, glyph) {
+                    warn!("Failed to prewarm font '{}': {err}", font.name);
+                }
+            });
+        }
     }
 }
 
@@ -938,6 +1045,14 @@ profiler::metadata_logger!("GlyphCacheMiss", log_miss(GlyphCacheMiss));
 // =======================
 
 /// A font with associated GPU-stored data.
+///
+/// It also keeps track of the maximum scale the glyphs are rendered with, that is, the ratio
+/// between the glyph size in screen pixels and its font size. The glyphs request MSDFs of the
+/// resolution tier sufficient for their font size multiplied by this scale. When the scale grows,
+/// for example, when the camera is zoomed in, all glyphs of the font are refreshed, so their MSDFs
+/// are regenerated at a higher tier if needed. The glyphs are also refreshed when the full quality
+/// is restored, as the tiny glyphs get lower tiers under the reduced quality (see the
+/// [`quality`] module).
 #[allow(missing_docs)]
 #[derive(Clone, CloneRef, Debug, Deref)]
 pub struct FontWithGpuData {
@@ -947,16 +1062,77 @@ pub struct FontWithGpuData {
     pub opacity_increase: gpu::Uniform<f32>,
     pub opacity_exponent: gpu::Uniform<f32>,
     context:              Rc<RefCell<Option<Context>>>,
+    atlas_version:        Rc<Cell<usize>>,
+    render_scale:         Rc<Cell<f32>>,
+    quality:              Rc<Cell<quality::Quality>>,
+    glyphs:               Rc<RefCell<Vec<glyph::WeakGlyph>>>,
 }
 
 impl FontWithGpuData {
-    fn new(font: Font, hinting: Hinting) -> Self {
+    fn new(font: Font, hinting: Hinting, render_scale: f32) -> Self {
         let Hinting { opacity_increase, opacity_exponent } = hinting;
         let opacity_increase = gpu::Uniform::new(opacity_increase);
         let opacity_exponent = gpu::Uniform::new(opacity_exponent);
         let atlas = gpu::Uniform::new(default());
         let context = default();
-        Self { font, atlas, opacity_exponent, opacity_increase, context }
+        let atlas_version = default();
+        let render_scale = Rc::new(Cell::new(render_scale));
+        let quality = Rc::new(Cell::new(quality::quality()));
+        let glyphs = default();
+        Self {
+            font,
+            atlas,
+            opacity_exponent,
+            opacity_increase,
+            context,
+            atlas_version,
+            render_scale,
+            quality,
+            glyphs,
+        }
+    }
+
+    /// The maximum ratio between the size of the glyphs in screen pixels and their font size.
+    pub fn render_scale(&self) -> f32 {
+        self.render_scale.get()
+    }
+
+    /// Register a glyph rendered with this font, so it is refreshed when the render scale grows.
+    /// The dropped glyphs are forgotten when the list would need to grow.
+    fn register_glyph(&self, glyph: &glyph::Glyph) {
+        let mut glyphs = self.glyphs.borrow_mut();
+        if glyphs.len() == glyphs.capacity() {
+            glyphs.retain(|glyph| glyph.upgrade().is_some());
+        }
+        glyphs.push(glyph.downgrade());
+    }
+
+    /// Update the render scale. If it grows, all registered glyphs are refreshed, which
+    /// regenerates the MSDFs of the glyphs rendered bigger than their resolution tier allows.
+    fn update_render_scale(&self, render_scale: f32) {
+        if render_scale > self.render_scale.get() {
+            self.render_scale.set(render_scale);
+            self.refresh_glyphs();
+        }
+    }
+
+    /// Update the quality mode. If the full quality is restored, all registered glyphs are
+    /// refreshed, which regenerates the MSDFs of the tiny glyphs at their regular tiers.
+    fn update_quality(&self, quality: quality::Quality) {
+        let was_reduced = self.quality.replace(quality).is_reduced();
+        if was_reduced && !quality.is_reduced() {
+            self.refresh_glyphs();
+        }
+    }
+
+    fn refresh_glyphs(&self) {
+        let glyphs = mem::take(&mut *self.glyphs.borrow_mut());
+        let glyphs = glyphs.into_iter().filter_map(|glyph| glyph.upgrade()).collect_vec();
+        for glyph in &glyphs {
+            glyph.refresh_render_info();
+        }
+        let mut registered = self.glyphs.borrow_mut();
+        registered.extend(glyphs.iter().map(|glyph| glyph.downgrade()));
     }
 
     fn set_context_and_update(&self, context: Option<&Context>) {
@@ -964,17 +1140,30 @@ impl FontWithGpuData {
         self.update_atlas();
     }
 
+    /// Check whether the font is not used by any glyph system, so only the [`Registry`] refers to
+    /// it.
+    fn is_unused(&self) -> bool {
+        Rc::strong_count(&self.context) == 1
+    }
+
     /// Upload the current atlas to the GPU if it is dirty (contains more glyphs than the currently-
-    /// uploaded version); drop the `gpu::Texture` if context has been lost.
+    /// uploaded version); drop the `gpu::Texture` if context has been lost, or if the font is not
+    /// used in the low memory mode. The atlas is uploaded again when the font is used.
     #[profile(Debug)]
     fn update_atlas(&self) {
-        if let Some(context) = self.context.borrow().as_ref() {
+        let evicted = memory::is_low() && self.is_unused();
+        if evicted {
+            self.atlas.set(None);
+        } else if let Some(context) = self.context.borrow().as_ref() {
             let num_glyphs = self.font.msdf_texture().glyphs();
             let gpu_tex_glyphs = self
                 .atlas
                 .with_item(|texture| texture.as_ref().map_or_default(|texture| texture.layers()));
-            let texture_changed = gpu_tex_glyphs as u32 != num_glyphs;
+            let version = self.font.msdf_texture().version();
+            let texture_changed =
+                gpu_tex_glyphs as u32 != num_glyphs || self.atlas_version.get() != version;
             if texture_changed {
+                self.atlas_version.set(version);
                 let glyph_size = self.font.msdf_texture().size();
                 let texture = gpu::Texture::new(
                     context,
@@ -1043,11 +1232,12 @@ impl Registry {
             .into_iter()
             .map(|(name, font)| {
                 let hinting = Hinting::for_font(&name, scene_shape);
-                let font = FontWithGpuData::new(font, hinting);
+                let font = FontWithGpuData::new(font, hinting, scene_shape.pixel_ratio);
                 font.set_context_and_update(context);
                 (name, font)
             })
             .collect();
+        Self::schedule_prewarm(&fonts);
         let fonts = Rc::new(fonts);
         let fonts_ = Rc::clone(&fonts);
         let set_context_handle = scene.on_set_context(move |context| {
@@ -1057,14 +1247,39 @@ impl Registry {
         });
         let network = frp::Network::new("font::Registry");
         let on_before_rendering = ensogl_core::animation::on_before_rendering();
+        let shape = scene.shape().clone_ref();
+        let camera = scene.camera();
         frp::extend! { network
-            eval_ on_before_rendering([fonts] Self::update(&fonts));
+            eval_ on_before_rendering([fonts] {
+                let render_scale = shape.value().pixel_ratio * camera.zoom().max(1.0);
+                Self::update(&fonts, render_scale)
+            });
         }
         Self { network, fonts, set_context_handle }
     }
 
-    fn update(fonts: impl AsRef<HashMap<Name, FontWithGpuData>>) {
+    /// Schedule preparing the most common glyphs of the default fonts in the idle time, so they
+    /// do not need to be generated when the text is displayed for the first time. The glyphs are
+    /// not prepared in the low memory mode, so the atlases contain only the displayed glyphs.
+    fn schedule_prewarm(fonts: &HashMap<Name, FontWithGpuData>) {
+        if memory::is_low() {
+            return;
+        }
+        for name in [DEFAULT_FONT, DEFAULT_CODE_FONT] {
+            if let Some(Font::NonVariable(font)) = fonts.get(&Name::from(name)).map(|t| &t.font) {
+                let variations = font.family.closest_non_variable_variations(default());
+                if let Some(variations) = variations {
+                    font.schedule_prewarm(variations.variations, PREWARMED_GLYPHS);
+                }
+            }
+        }
+    }
+
+    fn update(fonts: impl AsRef<HashMap<Name, FontWithGpuData>>, render_scale: f32) {
+        let quality = quality::quality();
         for font in fonts.as_ref().values() {
+            font.update_render_scale(render_scale);
+            font.update_quality(quality);
             font.update_atlas()
         }
     }
@@ -1231,4 +1446,19 @@ mod tests {
         })
         .unwrap();
     }
+
+    #[test]
+    fn variation_axes_follow_face_header() {
+        let header = NonVariableFaceHeader::new(Width::Condensed, Weight::Bold, Style::Italic);
+        let axes = VariationAxes::from(header);
+        let value = |tag: &[u8; 4]| {
+            let tag = Tag::from_bytes(tag);
+            axes.vec.iter().find(|axis| axis.tag == tag).map(|axis| axis.value.into_inner())
+        };
+        assert_eq!(value(b"wght"), Some(700.0));
+        assert_eq!(value(b"wdth"), Some(62.5));
+        assert_eq!(value(b"ital"), Some(1.0));
+        assert_eq!(value(b"slnt"), Some(0.0));
+        assert_eq!(axes.to_rustybuzz().len(), 4);
+    }
 }
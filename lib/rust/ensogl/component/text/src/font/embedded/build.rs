@@ -137,6 +137,34 @@ pub async fn load_enso_font(out_dir: impl AsRef<Path>, code_gen: &mut CodeGenera
 
 
 
+// ========================
+// === DejaVu Sans Font ===
+// ========================
+
+/// The name of the proportional DejaVu Sans font family. It is used in the tests of proportional
+/// font rendering.
+const DEJAVU_SANS_FAMILY: &str = "DejaVuSans";
+
+/// Download the DejaVu Sans font family to the `out_dir` and register its definition and font
+/// files in the generated code.
+pub async fn load_dejavu_sans_font(
+    out_dir: impl AsRef<Path>,
+    code_gen: &mut CodeGenerator,
+) -> Result {
+    let font_family = enso_build::ide::web::dejavu_font::sans_font();
+    let cache = ide_ci::cache::Cache::new_default().await?;
+    let octocrab = ide_ci::github::setup_octocrab().await?;
+    let package = enso_build::ide::web::dejavu_font::download(&cache, &octocrab).await?;
+    enso_build::ide::web::dejavu_font::extract_fonts(&font_family, package, &out_dir).await?;
+    code_gen.add_non_variable_font_definition(DEJAVU_SANS_FAMILY, &font_family);
+    for file in font_family.files() {
+        code_gen.add_font_data(file);
+    }
+    Ok(())
+}
+
+
+
 // ====================
 // === Google Fonts ===
 // ====================
@@ -235,6 +263,8 @@ async fn main() -> Result {
 
     load_enso_font(&out_dir, &mut code_gen).await?;
 
+    load_dejavu_sans_font(&out_dir, &mut code_gen).await?;
+
     let body = code_gen.body();
     let out_path = out_dir.join(GENERATED_SOURCE_FILE_NAME);
     ide_ci::fs::tokio::write(&out_path, body).await?;
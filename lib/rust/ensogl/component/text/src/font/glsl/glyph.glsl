@@ -1,23 +1,50 @@
+// The shape system math helpers (like `PI` and `encode`) are prepended to this code.
+
 const bool DEBUG = false;
 
+/// The opacity of fully dimmed glyphs, relative to their color opacity.
+const highp float DIMMED_OPACITY = 0.4;
+
 highp float median(highp vec3 v) {
     return max(min(v.x, v.y), min(max(v.x, v.y), v.z));
 }
 
-highp float get_fatting() {
+highp float get_font_size_px() {
     highp vec2 local_to_px_ratio = 1.0 / fwidth(input_local.xy);
-    highp float font_size_px = input_font_size * (local_to_px_ratio.x + local_to_px_ratio.y) * 0.5;
-    highp float fatting = input_sdf_weight;
-    return font_size_px * fatting;
+    return input_font_size * (local_to_px_ratio.x + local_to_px_ratio.y) * 0.5;
 }
 
-highp float get_alpha(vec2 uv) {
+/// The emphasis animation value, from 0.0 (not emphasized) to 1.0 (the peak of a pulse).
+highp float get_emphasis() {
+    if (input_emphasis_period <= 0.0) {
+        return 0.0;
+    }
+    highp float pulses = (input_time - input_emphasis_start_time) / input_emphasis_period;
+    bool finished = input_emphasis_pulse_count > 0.0 && pulses >= input_emphasis_pulse_count;
+    if (pulses < 0.0 || finished) {
+        return 0.0;
+    }
+    return 0.5 - 0.5 * cos(pulses * 2.0 * PI);
+}
+
+highp float get_fatting() {
+    highp float fatting = input_sdf_weight + input_emphasis_sdf_weight * get_emphasis();
+    return get_font_size_px() * fatting;
+}
+
+/// The signed distance to the glyph edge in pixels. Positive values are inside of the glyph.
+highp float get_sig_dist_px(vec2 uv) {
     highp vec2  msdf_unit_px = input_msdf_range / (fwidth(uv) * vec2(input_msdf_size));
     highp float avg_msdf_unit_px = (msdf_unit_px.x + msdf_unit_px.y) * 0.5;
 
-    highp vec3  msdf_sample = texture(input_atlas,vec3(uv, input_atlas_index)).rgb;
+    // The lower resolution tier MSDFs occupy only a part of the atlas layer.
+    highp vec2  atlas_uv = input_atlas_offset + uv * input_atlas_scale;
+    highp vec3  msdf_sample = texture(input_atlas,vec3(atlas_uv, input_atlas_index)).rgb;
     highp float sig_dist = median(msdf_sample) - 0.5;
-    highp float sig_dist_px = sig_dist * avg_msdf_unit_px + get_fatting();
+    return sig_dist * avg_msdf_unit_px + get_fatting();
+}
+
+highp float get_opacity(highp float sig_dist_px) {
     highp float opacity = 0.5 + sig_dist_px;
     opacity += input_opacity_increase;
     opacity = clamp(opacity, 0.0, 1.0);
@@ -25,11 +52,56 @@ highp float get_alpha(vec2 uv) {
     return opacity;
 }
 
+/// Desaturate and fade out the color according to the glyph dimming.
+highp vec4 dim_color(highp vec4 color) {
+    highp float luminance = dot(color.rgb, vec3(0.2126, 0.7152, 0.0722));
+    color.rgb = mix(color.rgb, vec3(luminance), input_dim);
+    color.a *= mix(1.0, DIMMED_OPACITY, input_dim);
+    return color;
+}
+
+highp float get_alpha(vec2 uv) {
+    return get_opacity(get_sig_dist_px(uv));
+}
+
+/// The ID of the glyph instance written to the pointer target texture in the picking pass.
+highp vec4 get_id(highp float alpha) {
+    if (input_pickable == 0.0 || alpha <= 0.0) {
+        return vec4(0.0);
+    }
+    return encode(input_global_instance_id, 1.0);
+}
+
 highp vec4 color_from_msdf() {
-    highp vec4 color = input_color;
-    color.a *= get_alpha(input_uv);
+    highp float sig_dist_px = get_sig_dist_px(input_uv);
+    highp vec4 color = dim_color(mix(input_color, input_emphasis_color, get_emphasis()));
+    color.a *= get_opacity(sig_dist_px);
     color.rgb *= color.a; // premultiply
 
+    // The outline is the glyph shape grown by the outline width, drawn below the glyph.
+    if (input_outline_width > 0.0) {
+        highp float outline_width_px = get_font_size_px() * input_outline_width;
+        highp vec4 outline = dim_color(input_outline_color);
+        outline.a *= get_opacity(sig_dist_px + outline_width_px);
+        outline.rgb *= outline.a; // premultiply
+        color = color + outline * (1.0 - color.a);
+    }
+
+    // The shadow is the glyph shape moved by the shadow offset, drawn below the glyph and its
+    // outline. It is clipped to the glyph sprite.
+    if (input_shadow_blur > 0.0 || input_shadow_offset != vec2(0.0)) {
+        highp vec2 shadow_uv = input_uv - input_shadow_offset * input_font_size / input_size;
+        highp float blur_px = max(get_font_size_px() * input_shadow_blur, 1.0);
+        highp vec4 shadow = dim_color(input_shadow_color);
+        shadow.a *= clamp(0.5 + get_sig_dist_px(shadow_uv) / blur_px, 0.0, 1.0);
+        bool outside = any(lessThan(shadow_uv, vec2(0.0))) || any(greaterThan(shadow_uv, vec2(1.0)));
+        if (outside) {
+            shadow.a = 0.0;
+        }
+        shadow.rgb *= shadow.a; // premultiply
+        color = color + shadow * (1.0 - color.a);
+    }
+
     if(DEBUG) {
         vec4 bg_box = vec4(input_uv * input_size / 10.0, 0.0, 1.0);
         color = (color * 0.7 + bg_box * 0.3);
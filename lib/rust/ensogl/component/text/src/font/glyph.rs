@@ -6,10 +6,19 @@ use crate::prelude::*;
 use crate::buffer::formatting::PropertyDiffApply;
 use crate::font;
 use crate::font::VariationAxes;
+use crate::Emphasis;
+use crate::FontFamily;
+use crate::Outline;
 use crate::PropertyDiff;
 use crate::ResolvedProperty;
+use crate::Script;
 use crate::SdfWeight;
+use crate::Shadow;
 use crate::Size;
+use crate::Strikethrough;
+use crate::Underline;
+use crate::UnderlineColor;
+use crate::UnderlineStyle;
 
 use enso_text::Byte;
 use ensogl_core::data::color;
@@ -17,6 +26,7 @@ use ensogl_core::data::color::Rgba;
 use ensogl_core::display;
 use ensogl_core::display::layout::alignment;
 use ensogl_core::display::scene::Scene;
+use ensogl_core::display::shape::primitive::shader::builder::glsl_math;
 use ensogl_core::display::symbol::geometry::SpriteSystem;
 use ensogl_core::display::symbol::material::Material;
 use ensogl_core::display::symbol::shader::builder::CodeTemplate;
@@ -40,7 +50,7 @@ use owned_ttf_parser::GlyphId;
 pub struct SystemData {}
 
 const FUNCTIONS: &str = include_str!("glsl/glyph.glsl");
-const MAIN: &str = "output_color = color_from_msdf(); output_id=vec4(0.0,0.0,0.0,0.0);";
+const MAIN: &str = "output_color = color_from_msdf(); output_id = get_id(output_color.a);";
 
 impl SystemData {
     /// Defines a default material of this system.
@@ -49,12 +59,27 @@ impl SystemData {
         material.add_input_def::<texture::FloatSamplerArray>("atlas");
         material.add_input_def::<Vector2<u32>>("msdf_size");
         material.add_input_def::<u32>("atlas_index");
+        material.add_input_def::<Vector2<f32>>("atlas_offset");
+        material.add_input_def::<f32>("atlas_scale");
         material.add_input("pixel_ratio", 1.0);
         material.add_input("z_zoom_1", 1.0);
         material.add_input("msdf_range", GlyphRenderInfo::MSDF_PARAMS.range as f32);
         material.add_input("font_size", 10.0);
         material.add_input("color", Vector4::new(0.0, 0.0, 0.0, 1.0));
         material.add_input("sdf_weight", 0.0);
+        material.add_input("outline_color", Vector4::new(0.0, 0.0, 0.0, 0.0));
+        material.add_input("outline_width", 0.0);
+        material.add_input("shadow_color", Vector4::new(0.0, 0.0, 0.0, 0.0));
+        material.add_input("shadow_offset", Vector2::new(0.0, 0.0));
+        material.add_input("shadow_blur", 0.0);
+        material.add_input("emphasis_color", Vector4::new(0.0, 0.0, 0.0, 0.0));
+        material.add_input("emphasis_sdf_weight", 0.0);
+        material.add_input("emphasis_period", 0.0);
+        material.add_input("emphasis_pulse_count", 0.0);
+        material.add_input("emphasis_start_time", 0.0);
+        material.add_input("time", 0.0);
+        material.add_input("dim", 0.0);
+        material.add_input("pickable", 0.0);
         // === Adjusting look and feel of different fonts on different operating systems ===
         material.add_input("opacity_increase", 0.0);
         material.add_input("opacity_exponent", 1.0);
@@ -64,7 +89,8 @@ impl SystemData {
         //     which will be enabled only if pass of given attachment type was enabled.
         material.add_output("id", Vector4::<f32>::new(0.0, 0.0, 0.0, 0.0));
 
-        let code = CodeTemplate::new(FUNCTIONS, MAIN, "");
+        let functions = format!("{}\n\n{FUNCTIONS}", glsl_math());
+        let code = CodeTemplate::new(functions, MAIN, "");
         material.set_code(code);
         material
     }
@@ -106,7 +132,21 @@ pub mod glyph_shape {
             font_size: f32,
             color: Vector4<f32>,
             sdf_weight: f32,
-            atlas_index: u32
+            outline_color: Vector4<f32>,
+            outline_width: f32,
+            shadow_color: Vector4<f32>,
+            shadow_offset: Vector2<f32>,
+            shadow_blur: f32,
+            emphasis_color: Vector4<f32>,
+            emphasis_sdf_weight: f32,
+            emphasis_period: f32,
+            emphasis_pulse_count: f32,
+            emphasis_start_time: f32,
+            dim: f32,
+            pickable: f32,
+            atlas_index: u32,
+            atlas_offset: Vector2<f32>,
+            atlas_scale: f32
         ) {
             // The shape does not matter. The [`SystemData`] defines custom GLSL code.
             Plane().into()
@@ -171,6 +211,12 @@ pub struct GlyphData {
     display_object:         display::object::Instance,
     properties:             Cell<font::family::NonVariableFaceHeader>,
     variations:             RefCell<VariationAxes>,
+    font_family:            Cell<FontFamily>,
+    script:                 Cell<Script>,
+    underline:              Cell<Underline>,
+    underline_color:        Cell<UnderlineColor>,
+    underline_style:        Cell<UnderlineStyle>,
+    strikethrough:          Cell<Strikethrough>,
 }
 
 
@@ -193,10 +239,8 @@ macro_rules! define_prop_setters_and_getters {
 
         /// Set `NonVariableFaceHeader` of the glyph.
         pub fn set_properties(&self, props: font::family::NonVariableFaceHeader) {
-            self.properties.set(props.clone());
-            $(
-                self.variations.borrow_mut().[<set_ $prop:snake:lower>](props.[<$prop:snake:lower>]);
-            )*
+            self.properties.set(props);
+            *self.variations.borrow_mut() = props.into();
             self.refresh();
         }
 
@@ -297,6 +341,29 @@ impl Glyph {
         self.view.color.set(Rgba::from(color).into());
     }
 
+    /// Font family getter.
+    pub fn font_family(&self) -> FontFamily {
+        self.font_family.get()
+    }
+
+    /// Font family setter. Please note that it does not change the font used to render the glyph,
+    /// as it is defined by the glyph system which created the glyph. It should be set by the glyph
+    /// creator, so glyphs of other families are not reused.
+    pub fn set_font_family(&self, font_family: FontFamily) {
+        self.font_family.set(font_family);
+    }
+
+    /// Script getter.
+    pub fn script(&self) -> Script {
+        self.script.get()
+    }
+
+    /// Script setter. Please note that the script affects the glyph layout (its size and baseline
+    /// offset), which is computed by the text area, so it requires redrawing the line.
+    pub fn set_script(&self, script: Script) {
+        self.script.set(script);
+    }
+
     /// SDF-based glyph thickness getter.
     pub fn sdf_weight(&self) -> SdfWeight {
         SdfWeight(self.view.sdf_weight.get())
@@ -308,25 +375,144 @@ impl Glyph {
         self.view.sdf_weight.set(value.into().value);
     }
 
+    /// Outline getter.
+    pub fn outline(&self) -> Outline {
+        let color = Rgba::from(self.view.outline_color.get()).into();
+        let width = self.view.outline_width.get();
+        Outline { color, width }
+    }
+
+    /// Outline setter.
+    pub fn set_outline(&self, outline: Outline) {
+        self.view.outline_color.set(Rgba::from(outline.color).into());
+        self.view.outline_width.set(outline.width);
+    }
+
+    /// Shadow getter.
+    pub fn shadow(&self) -> Shadow {
+        let color = Rgba::from(self.view.shadow_color.get()).into();
+        let offset = self.view.shadow_offset.get();
+        let blur = self.view.shadow_blur.get();
+        Shadow { color, offset, blur }
+    }
+
+    /// Shadow setter.
+    pub fn set_shadow(&self, shadow: Shadow) {
+        self.view.shadow_color.set(Rgba::from(shadow.color).into());
+        self.view.shadow_offset.set(shadow.offset);
+        self.view.shadow_blur.set(shadow.blur);
+    }
+
+    /// Emphasis getter.
+    pub fn emphasis(&self) -> Emphasis {
+        let color = Rgba::from(self.view.emphasis_color.get()).into();
+        let sdf_weight = self.view.emphasis_sdf_weight.get();
+        let period = self.view.emphasis_period.get();
+        let pulse_count = self.view.emphasis_pulse_count.get() as u32;
+        let start_time = self.view.emphasis_start_time.get();
+        Emphasis { color, sdf_weight, period, pulse_count, start_time }
+    }
+
+    /// Emphasis setter.
+    pub fn set_emphasis(&self, emphasis: Emphasis) {
+        self.view.emphasis_color.set(Rgba::from(emphasis.color).into());
+        self.view.emphasis_sdf_weight.set(emphasis.sdf_weight);
+        self.view.emphasis_period.set(emphasis.period);
+        self.view.emphasis_pulse_count.set(emphasis.pulse_count as f32);
+        self.view.emphasis_start_time.set(emphasis.start_time);
+    }
+
+    /// Underline getter.
+    pub fn underline(&self) -> Underline {
+        self.underline.get()
+    }
+
+    /// Underline setter. Please note that the underline and the strikethrough are not a part of
+    /// the glyph shape. They are drawn by the line along the runs of decorated glyphs, so the line
+    /// decorations need to be updated after changing them, see
+    /// [`crate::component::line::View::update_decorations`].
+    pub fn set_underline(&self, underline: Underline) {
+        self.underline.set(underline);
+    }
+
+    /// Underline color getter.
+    pub fn underline_color(&self) -> UnderlineColor {
+        self.underline_color.get()
+    }
+
+    /// Underline color setter.
+    pub fn set_underline_color(&self, underline_color: UnderlineColor) {
+        self.underline_color.set(underline_color);
+    }
+
+    /// Underline style getter.
+    pub fn underline_style(&self) -> UnderlineStyle {
+        self.underline_style.get()
+    }
+
+    /// Underline style setter.
+    pub fn set_underline_style(&self, underline_style: UnderlineStyle) {
+        self.underline_style.set(underline_style);
+    }
+
+    /// Strikethrough getter.
+    pub fn strikethrough(&self) -> Strikethrough {
+        self.strikethrough.get()
+    }
+
+    /// Strikethrough setter. Just like the underline, it is drawn by the line, see
+    /// [`Self::set_underline`].
+    pub fn set_strikethrough(&self, strikethrough: Strikethrough) {
+        self.strikethrough.set(strikethrough);
+    }
+
+    /// Dimming getter.
+    pub fn dim(&self) -> f32 {
+        self.view.dim.get()
+    }
+
+    /// Dimming setter. The value from 0.0 to 1.0 desaturates the glyph and reduces its opacity,
+    /// which is used to display the content of disabled text areas.
+    pub fn set_dim(&self, dim: f32) {
+        self.view.dim.set(dim);
+    }
+
+    /// Check whether the glyph is rendered in the scene picking pass.
+    pub fn is_pickable(&self) -> bool {
+        self.view.pickable.get() != 0.0
+    }
+
+    /// Render the glyph in the scene picking pass, so its view becomes the target of the pointer
+    /// events over the glyph shape. Not pickable glyphs are transparent for the pointer events.
+    pub fn set_pickable(&self, pickable: bool) {
+        self.view.pickable.set(if pickable { 1.0 } else { 0.0 });
+    }
+
+    /// Check whether the glyph is hidden by clipping, see [`Self::set_clipped`].
+    pub fn is_clipped(&self) -> bool {
+        !self.view.has_parent()
+    }
+
+    /// Hide the glyph outside the visible part of the text area. The glyph keeps its position and
+    /// style, so it can be displayed again after scrolling.
+    pub fn set_clipped(&self, clipped: bool) {
+        if clipped {
+            self.view.unset_parent();
+        } else if !self.view.has_parent() {
+            self.display_object.add_child(&self.view);
+        }
+    }
+
     /// Size getter.
     pub fn font_size(&self) -> Size {
         Size(self.view.font_size.get())
     }
 
-    /// Size setter.
+    /// Size setter. The glyph MSDF is regenerated at a higher resolution tier if the new size
+    /// requires it.
     pub fn set_font_size(&self, size: Size) {
-        let size = size.value;
-        self.view.font_size.set(size);
-        let opt_glyph_info = self.view.data.borrow().font.glyph_info(
-            self.properties.get(),
-            &self.variations.borrow(),
-            self.glyph_id.get(),
-        );
-        if let Some(glyph_info) = opt_glyph_info {
-            self.view.set_size(glyph_info.scale.scale(size));
-        } else {
-            error!("Cannot find glyph render info for glyph id: {:?}.", self.glyph_id.get());
-        }
+        self.view.font_size.set(size.value);
+        self.refresh_render_info();
     }
 }
 
@@ -337,12 +523,25 @@ impl Glyph {
     /// Change the displayed character.
     pub fn set_glyph_id(&self, glyph_id: GlyphId) {
         self.glyph_id.set(glyph_id);
+        self.refresh_render_info();
+    }
+
+    /// Update the glyph MSDF location and the glyph size. The MSDF resolution tier is chosen for
+    /// the glyph size in screen pixels, see [`FontWithGpuData`] docs to learn more.
+    pub(crate) fn refresh_render_info(&self) {
+        let glyph_id = self.glyph_id.get();
+        let font_size = self.font_size().value;
         let variations = self.variations.borrow();
+        let data = self.view.data.borrow();
+        let size_px = font_size * data.font.render_scale();
+        let properties = self.properties.get();
         let opt_glyph_info =
-            self.view.data.borrow().font.glyph_info(self.properties.get(), &variations, glyph_id);
+            data.font.glyph_info_for_size(properties, &variations, glyph_id, size_px);
         if let Some(glyph_info) = opt_glyph_info {
             self.view.atlas_index.set(glyph_info.msdf_texture_glyph_id);
-            self.view.set_size(glyph_info.scale.scale(self.font_size().value));
+            self.view.atlas_offset.set(glyph_info.atlas_offset);
+            self.view.atlas_scale.set(glyph_info.atlas_scale);
+            self.view.set_size(glyph_info.scale.scale(font_size));
         } else {
             // This should not happen. Fonts contain special glyph for missing characters.
             warn!("Cannot find glyph render info for glyph id: {:?}.", glyph_id);
@@ -358,7 +557,7 @@ impl Glyph {
     /// changed at once. However, such a situation is not common as many property modifiers, such as
     /// color do not call this method.
     fn refresh(&self) {
-        self.set_glyph_id(self.glyph_id.get());
+        self.refresh_render_info();
     }
 }
 
@@ -423,11 +622,18 @@ impl System {
         let variations = default();
         let x_advance = default();
         let attached_to_cursor = default();
+        let font_family = default();
+        let script = default();
+        let underline = default();
+        let underline_color = default();
+        let underline_style = default();
+        let strikethrough = default();
         let view = glyph_shape::View::new_with_data(ShapeData { font });
         view.color.set(Vector4::new(0.0, 0.0, 0.0, 0.0));
         view.atlas_index.set(0);
+        view.atlas_scale.set(1.0);
         display_object.add_child(&view);
-        Glyph {
+        let glyph = Glyph {
             data: Rc::new(GlyphData {
                 view,
                 display_object,
@@ -437,7 +643,15 @@ impl System {
                 variations,
                 x_advance,
                 attached_to_cursor,
+                font_family,
+                script,
+                underline,
+                underline_color,
+                underline_style,
+                strikethrough,
             }),
-        }
+        };
+        self.font.register_glyph(&glyph);
+        glyph
     }
 }
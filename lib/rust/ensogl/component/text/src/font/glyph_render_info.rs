@@ -34,10 +34,22 @@ use msdf_sys::MsdfParameters;
 /// [freetype documentation](https://www.freetype.org/freetype2/docs/glyphs/glyphs-3.html#section-1)
 #[derive(Copy, Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct GlyphRenderInfo {
-    /// An index of glyph in a msdf texture (counted from the top of column). For details, see
-    /// msdf::Texture documentation.
+    /// An index of the msdf texture layer containing the glyph (counted from the top of column).
+    /// For details, see msdf::Texture documentation.
     pub msdf_texture_glyph_id: u32,
 
+    /// The resolution tier of the glyph MSDF.
+    #[serde(default)]
+    pub tier: msdf::Tier,
+
+    /// The offset of the glyph MSDF cell in the texture layer coordinates.
+    #[serde(default)]
+    pub atlas_offset: Vector2<f32>,
+
+    /// The size of the glyph MSDF cell relative to the texture layer size.
+    #[serde(default = "full_layer_scale")]
+    pub atlas_scale: f32,
+
     /// A required offset of the _base square_. See structure documentation for details.
     pub offset: Vector2<f32>,
 
@@ -73,21 +85,48 @@ impl GlyphRenderInfo {
         overlap_support: true,
     };
 
-    /// Load new [`GlyphRenderInfo`] from msdf_sys font handle. This also extends the atlas with
-    /// MSDF generated for this character.
+    /// Parameters used for MSDF generation of the given tier. The lower tiers use proportionally
+    /// smaller cells, so the glyph is framed the same way, and the range expressed in texels covers
+    /// the same distance.
+    pub fn msdf_params(tier: msdf::Tier) -> MsdfParameters {
+        let cells_per_side = tier.cells_per_side() as f64;
+        MsdfParameters {
+            width: tier.cell_size(),
+            height: tier.cell_size(),
+            range: Self::MSDF_PARAMS.range / cells_per_side,
+            max_scale: Self::MSDF_PARAMS.max_scale / cells_per_side,
+            ..Self::MSDF_PARAMS
+        }
+    }
+
+    /// Load new [`GlyphRenderInfo`] from msdf_sys font handle. This also places the MSDF generated
+    /// for this character in the atlas, in a cell of the given tier.
     #[profile(Debug)]
-    pub fn load(handle: &msdf_sys::OwnedFace, glyph_id: GlyphId, atlas: &msdf::Texture) -> Self {
-        let params = Self::MSDF_PARAMS;
+    pub fn load(
+        handle: &msdf_sys::OwnedFace,
+        glyph_id: GlyphId,
+        atlas: &msdf::Texture,
+        tier: msdf::Tier,
+    ) -> Self {
+        let params = Self::msdf_params(tier);
         let msdf = Msdf::generate_by_index(handle, glyph_id.0 as usize, &params);
-        let inversed_scale = Vector2::new(1.0 / msdf.scale.x, 1.0 / msdf.scale.y);
+        // The base square covers the whole cell, which is smaller than the full resolution one.
+        let cells_per_side = tier.cells_per_side() as f64;
+        let inversed_scale = msdf.scale.map(|scale| 1.0 / (scale * cells_per_side));
         let translation = msdf::convert_msdf_translation(&msdf);
-        let glyph_id = atlas.glyphs();
-        atlas.extend_with_raw_data(msdf.data.iter());
+        let slot = atlas.insert_with_raw_data(tier, msdf.data.iter());
         GlyphRenderInfo {
-            msdf_texture_glyph_id: glyph_id,
-            offset:                -translation,
-            scale:                 Vector2(inversed_scale.x as f32, inversed_scale.y as f32),
-            advance:               msdf::x_distance_from_msdf_value(msdf.advance),
+            msdf_texture_glyph_id: slot.layer,
+            tier,
+            atlas_offset: slot.offset,
+            atlas_scale: slot.scale,
+            offset: -translation,
+            scale: Vector2(inversed_scale.x as f32, inversed_scale.y as f32),
+            advance: msdf::x_distance_from_msdf_value(msdf.advance),
         }
     }
 }
+
+fn full_layer_scale() -> f32 {
+    1.0
+}
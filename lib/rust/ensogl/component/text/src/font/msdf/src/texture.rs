@@ -6,6 +6,86 @@ use crate::Msdf;
 
 
 
+// ============
+// === Tier ===
+// ============
+
+/// The resolution tier of a glyph MSDF. Glyphs rendered only at small sizes do not need the full
+/// resolution, so their MSDFs are generated in smaller cells, packed several per texture layer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(missing_docs)]
+pub enum Tier {
+    Quarter,
+    Half,
+    #[default]
+    Full,
+}
+
+impl Tier {
+    /// All tiers, from the lowest to the highest resolution.
+    pub const ALL: [Tier; 3] = [Tier::Quarter, Tier::Half, Tier::Full];
+
+    /// The lowest resolution tier.
+    pub const MIN: Tier = Tier::Quarter;
+
+    /// The lowest tier sufficient for rendering a glyph of the given size in screen pixels. A tier
+    /// is sufficient as long as its cell is not smaller than the rendered glyph.
+    pub fn for_size(size_px: f32) -> Self {
+        Self::ALL.into_iter().find(|tier| size_px <= tier.cell_size() as f32).unwrap_or(Tier::Full)
+    }
+
+    /// The next lower resolution tier. The lowest tier is returned unchanged.
+    pub fn lower(self) -> Self {
+        match self {
+            Tier::Full => Tier::Half,
+            Tier::Half | Tier::Quarter => Tier::Quarter,
+        }
+    }
+
+    /// Number of cells along a single side of the texture layer.
+    pub fn cells_per_side(self) -> usize {
+        match self {
+            Tier::Quarter => 4,
+            Tier::Half => 2,
+            Tier::Full => 1,
+        }
+    }
+
+    /// Number of cells in a single texture layer.
+    pub fn cells_per_layer(self) -> usize {
+        self.cells_per_side() * self.cells_per_side()
+    }
+
+    /// Width and height of a single cell in texels.
+    pub fn cell_size(self) -> usize {
+        Texture::WIDTH / self.cells_per_side()
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+
+
+// ============
+// === Slot ===
+// ============
+
+/// The location of a glyph MSDF in the [`Texture`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Slot {
+    /// The index of the texture layer.
+    pub layer:  u32,
+    /// The offset of the cell in the layer texture coordinates.
+    pub offset: Vector2<f32>,
+    /// The size of the cell relative to the layer size.
+    pub scale:  f32,
+}
+
+
+
 // ===============
 // === Texture ===
 // ===============
@@ -14,11 +94,16 @@ use crate::Msdf;
 ///
 /// This structure keeps texture data in 8-bit-per-channel RGB format, which is ready to be passed
 /// to WebGL `texImage2D`. The texture contains MSDFs for all loaded glyph, organized in vertical
-/// column.
+/// column of layers. Each layer contains either a single [`Tier::Full`] cell, or several cells of a
+/// lower tier.
 #[derive(Clone, CloneRef, Debug, Default)]
 pub struct Texture {
     /// A plain data of this texture.
-    data: Rc<RefCell<Vec<u8>>>,
+    data:        Rc<RefCell<Vec<u8>>>,
+    /// For every tier, the last layer with its cells and the number of the cells already taken.
+    open_layers: Rc<RefCell<[Option<(u32, usize)>; 3]>>,
+    /// Incremented on every modification, as lower tier cells do not change the number of layers.
+    version:     Rc<Cell<usize>>,
 }
 
 impl Texture {
@@ -54,6 +139,11 @@ impl Texture {
         Vector2(width, height)
     }
 
+    /// The version of the texture data, changed on every modification.
+    pub fn version(&self) -> usize {
+        self.version.get()
+    }
+
     /// Do operation on borrowed texture data. Panics, if inside `operation` the texture data will
     /// be borrowed again (e.g. by calling `with_borrowed_data`.
     pub fn with_borrowed_data<F, R>(&self, operation: F) -> R
@@ -68,6 +158,48 @@ impl Texture {
         let f32_iterator = iter.into_iter();
         let converted_iterator = f32_iterator.map(Self::f32_to_cell);
         self.data.borrow_mut().extend(converted_iterator);
+        self.version.modify_(|v| *v += 1);
+    }
+
+    /// Place MSDF data in f32 format, generated for the given tier, in a free cell of the texture.
+    /// A new layer is appended if there is no free cell of the tier.
+    #[profile(Debug)]
+    pub fn insert_with_raw_data<T: IntoIterator<Item = f32>>(&self, tier: Tier, iter: T) -> Slot {
+        let (layer, index) = self.take_cell(tier);
+        let cells_per_side = tier.cells_per_side();
+        let (column, row) = (index % cells_per_side, index / cells_per_side);
+        let cell_row_size = tier.cell_size() * Self::CHANNELS_COUNT;
+        let cell_start = layer as usize * Self::ONE_GLYPH_SIZE
+            + row * tier.cell_size() * Self::ROW_SIZE
+            + column * cell_row_size;
+        let values = iter.into_iter().map(Self::f32_to_cell).collect_vec();
+        let mut data = self.data.borrow_mut();
+        for (cell_row, values) in values.chunks(cell_row_size).take(tier.cell_size()).enumerate() {
+            let start = cell_start + cell_row * Self::ROW_SIZE;
+            data[start..start + values.len()].copy_from_slice(values);
+        }
+        self.version.modify_(|v| *v += 1);
+        let scale = 1.0 / cells_per_side as f32;
+        let offset = Vector2(column as f32 * scale, row as f32 * scale);
+        Slot { layer, offset, scale }
+    }
+
+    /// Take a free cell of the given tier, returning its layer and its index in the layer.
+    fn take_cell(&self, tier: Tier) -> (u32, usize) {
+        let mut open_layers = self.open_layers.borrow_mut();
+        let open_layer = &mut open_layers[tier.index()];
+        let (layer, index) = match *open_layer {
+            Some((layer, taken)) if taken < tier.cells_per_layer() => (layer, taken),
+            _ => {
+                let layer = self.glyphs();
+                let mut data = self.data.borrow_mut();
+                let size = data.len() + Self::ONE_GLYPH_SIZE;
+                data.resize(size, 0);
+                (layer, 0)
+            }
+        };
+        *open_layer = Some((layer, index + 1));
+        (layer, index)
     }
 
     fn f32_to_cell(value: f32) -> u8 {
@@ -97,6 +229,8 @@ impl Texture {
             );
         }
         *self.data.borrow_mut() = image.data;
+        *self.open_layers.borrow_mut() = default();
+        self.version.modify_(|v| *v += 1);
     }
 }
 
@@ -156,6 +290,46 @@ mod test {
         assert_eq!([0, 0, 63, 127, 191, 255, 255], texture.data.borrow().as_slice());
     }
 
+    #[test]
+    fn choosing_tier_for_size() {
+        assert_eq!(Tier::for_size(6.0), Tier::Quarter);
+        assert_eq!(Tier::for_size(8.0), Tier::Quarter);
+        assert_eq!(Tier::for_size(12.0), Tier::Half);
+        assert_eq!(Tier::for_size(24.0), Tier::Full);
+        assert_eq!(Tier::for_size(f32::INFINITY), Tier::Full);
+        assert!(Tier::Quarter < Tier::Half && Tier::Half < Tier::Full);
+        assert_eq!(Tier::Full.lower(), Tier::Half);
+        assert_eq!(Tier::Half.lower(), Tier::Quarter);
+        assert_eq!(Tier::Quarter.lower(), Tier::Quarter);
+    }
+
+    #[test]
+    fn packing_lower_tier_cells() {
+        let texture = Texture::default();
+        let cell = |tier: Tier| vec![1.0; tier.cell_size() * tier.cell_size() * 3];
+        let first = texture.insert_with_raw_data(Tier::Half, cell(Tier::Half));
+        assert_eq!(first, Slot { layer: 0, offset: Vector2(0.0, 0.0), scale: 0.5 });
+        let full = texture.insert_with_raw_data(Tier::Full, cell(Tier::Full));
+        assert_eq!(full, Slot { layer: 1, offset: Vector2(0.0, 0.0), scale: 1.0 });
+        let second = texture.insert_with_raw_data(Tier::Half, cell(Tier::Half));
+        assert_eq!(second, Slot { layer: 0, offset: Vector2(0.5, 0.0), scale: 0.5 });
+        let third = texture.insert_with_raw_data(Tier::Half, cell(Tier::Half));
+        assert_eq!(third.offset, Vector2(0.0, 0.5));
+        texture.insert_with_raw_data(Tier::Half, cell(Tier::Half));
+        let fifth = texture.insert_with_raw_data(Tier::Half, cell(Tier::Half));
+        assert_eq!(fifth.layer, 2);
+        assert_eq!(texture.glyphs(), 3);
+
+        let data = texture.data.borrow();
+        let layer = &data[..Texture::ONE_GLYPH_SIZE];
+        let half_row = Texture::ROW_SIZE / 2;
+        assert!(layer[..Texture::ROW_SIZE].iter().all(|&v| v == 255));
+        assert!(layer[Texture::ONE_GLYPH_SIZE - Texture::ROW_SIZE..].iter().all(|&v| v == 255));
+        let partial_layer = &data[2 * Texture::ONE_GLYPH_SIZE..];
+        assert!(partial_layer[..half_row].iter().all(|&v| v == 255));
+        assert!(partial_layer[half_row..Texture::ROW_SIZE].iter().all(|&v| v == 0));
+    }
+
     #[test]
     fn x_dimension_converting() {
         assert_eq!(1.0 / 8.0, x_distance_from_msdf_value(4.0));
@@ -30,6 +30,7 @@ use ensogl_core::display;
 use ensogl_core::display::shape::system::Shape;
 use ensogl_core::display::shape::system::ShapeWithDefaultableData;
 use ensogl_core::display::style;
+use ensogl_core::gui::accessibility;
 use ensogl_core::gui::component::ShapeView;
 use ensogl_hardcoded_theme::component::toggle_button as theme;
 
@@ -78,6 +79,8 @@ ensogl_core::define_endpoints_2! {
         set_state        (bool),
         /// Read only mode forbids changing the state of the button by clicking.
         set_read_only    (bool),
+        /// Set the label read by the assistive technology, like screen readers.
+        set_accessible_label (ImString),
     }
     Output {
         /// Current state of the button, as visible in the scene,
@@ -103,13 +106,16 @@ ensogl_core::define_endpoints_2! {
 #[clone_ref(bound = "S: CloneRef")]
 struct Model<S: Shape> {
     #[display_object]
-    icon:       ShapeView<S>,
-    hover_area: Rectangle,
+    icon:          ShapeView<S>,
+    hover_area:    Rectangle,
+    accessibility: accessibility::Node,
 }
 
 impl<Shape: ColorableShape + 'static> Model<Shape> {
-    fn new() -> Self {
+    fn new(app: &Application) -> Self {
         let icon = ShapeView::<Shape>::new();
+        let scene = &app.display.default_scene;
+        let accessibility = accessibility::Node::new(scene, accessibility::Role::Button);
         let hover_area = Rectangle::new();
         hover_area
             .set_color(INVISIBLE_HOVER_COLOR)
@@ -117,7 +123,7 @@ impl<Shape: ColorableShape + 'static> Model<Shape> {
             .set_alignment_center()
             .allow_grow();
         icon.add_child(&hover_area);
-        Self { icon, hover_area }
+        Self { icon, hover_area, accessibility }
     }
 }
 
@@ -254,7 +260,7 @@ impl<Shape: ColorableShape + 'static> ToggleButton<Shape> {
     /// Constructor.
     pub fn new(app: &Application, tooltip_style: tooltip::Style) -> Self {
         let frp = Frp::new();
-        let model = Rc::new(Model::<Shape>::new());
+        let model = Rc::new(Model::<Shape>::new(app));
         Self { frp, model }.init_frp(app, tooltip_style)
     }
 
@@ -287,11 +293,15 @@ impl<Shape: ColorableShape + 'static> ToggleButton<Shape> {
 
             // === State ===
 
-            clicked <- mouse_down.constant(()).gate_not(&input.set_read_only);
+            pressed <- any_(&mouse_down, &model.accessibility.activated);
+            clicked <- pressed.gate_not(&input.set_read_only);
             toggle <- any_(input.toggle, clicked);
             output.state <+ output.state.not().sample(&toggle);
             output.state <+ input.set_state;
             output.last_user_state <+ output.state.sample(&clicked);
+            model.accessibility.set_pressed <+ output.state.map(|state| Some(*state));
+            model.accessibility.set_disabled <+ input.set_read_only;
+            model.accessibility.set_label <+ input.set_accessible_label;
 
 
             // === Mouse Interactions ===
@@ -7,7 +7,9 @@
 // ==============
 
 pub mod easing;
+pub mod idle;
 pub mod physics;
+pub mod quality;
 
 
 
@@ -4,6 +4,7 @@
 use crate::prelude::*;
 
 use crate::animation;
+use crate::animation::quality;
 use crate::data::function::Fn1;
 use crate::types::unit2::Duration;
 
@@ -240,6 +241,15 @@ where
             }
         }
     }
+
+    /// Jump to the target value, finishing the animation.
+    fn skip(&self) {
+        let value = self.target_value.get();
+        self.callback.call(value);
+        self.value.set(value);
+        self.active.set(false);
+        self.on_end.call(EndStatus::Normal);
+    }
 }
 
 impl<T: Value, F, OnStep, OnEnd> Animator<T, F, OnStep, OnEnd>
@@ -434,7 +444,12 @@ where
     let animation_loop = easing.animation_loop.downgrade();
     move |time: animation::FixedFrameRateStep<animation::TimeInfo>| {
         if data.active.get() {
-            data.step(time.map(|t| t.since_animation_loop_started))
+            // Under the reduced quality, animations jump to their targets to save the frame time.
+            if quality::is_reduced() {
+                data.skip()
+            } else {
+                data.step(time.map(|t| t.since_animation_loop_started))
+            }
         } else if let Some(animation_loop) = animation_loop.upgrade() {
             animation_loop.set(None);
         }
@@ -0,0 +1,240 @@
+//! A cooperative scheduler of low-priority tasks. The tasks are run at the end of every animation
+//! frame, in the time left between the end of rendering and the deadline of the next frame (the
+//! frame slack). It is meant to be used for work which improves the application responsiveness in
+//! the future, like shaping of not-yet-visible text lines, prewarming of the font atlas, or
+//! compacting of internal data structures.
+//!
+//! Tasks are not preempted. Every task should be short (well below a millisecond). Long jobs should
+//! be split into smaller steps, each scheduling the next one.
+
+use crate::prelude::*;
+use crate::system::web::traits::*;
+
+use crate::animation::TimeInfo;
+use crate::system::web;
+
+use std::collections::VecDeque;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The frame duration the scheduler tries to fit in. Idle tasks are run only until this time
+/// elapses since the frame start.
+const TARGET_FRAME_TIME_MS: f64 = 1000.0 / 60.0;
+
+/// Time reserved at the end of every frame for the browser work, like compositing and handling of
+/// I/O events. See the docs of [`crate::animation::LoopRegistry`] to learn more.
+const FRAME_END_MARGIN_MS: f64 = 2.0;
+
+
+
+// ================
+// === Priority ===
+// ================
+
+/// Priority of an idle task. Tasks of higher priority are always run before tasks of lower
+/// priority. Tasks of the same priority are run in the order they were scheduled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[allow(missing_docs)]
+pub enum Priority {
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl Priority {
+    /// All priorities, sorted from the highest to the lowest one.
+    pub const ALL: [Priority; 3] = [Priority::High, Priority::Normal, Priority::Low];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+
+
+// ================
+// === IdleTask ===
+// ================
+
+/// A task that can be scheduled to be run in the idle time.
+pub trait IdleTask = FnOnce() + 'static;
+
+
+
+// =====================
+// === IdleScheduler ===
+// =====================
+
+/// Cooperative scheduler of idle tasks. See the module docs to learn more.
+#[derive(Clone, CloneRef, Debug, Default)]
+pub struct IdleScheduler {
+    data: Rc<RefCell<IdleSchedulerData>>,
+}
+
+#[derive(Derivative, Default)]
+#[derivative(Debug)]
+struct IdleSchedulerData {
+    #[derivative(Debug = "ignore")]
+    queues:       [VecDeque<Box<dyn FnOnce()>>; 3],
+    last_run_len: usize,
+}
+
+impl IdleScheduler {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Schedule the task with the given priority.
+    pub fn schedule(&self, priority: Priority, task: impl IdleTask) {
+        self.data.borrow_mut().queues[priority.index()].push_back(Box::new(task));
+    }
+
+    /// The number of tasks waiting to be run.
+    pub fn queued_count(&self) -> usize {
+        self.data.borrow().queues.iter().map(|queue| queue.len()).sum()
+    }
+
+    /// The number of tasks of the given priority waiting to be run.
+    pub fn queued_count_of(&self, priority: Priority) -> usize {
+        self.data.borrow().queues[priority.index()].len()
+    }
+
+    /// The number of tasks run during the last [`Self::run_while`] call.
+    pub fn last_run_count(&self) -> usize {
+        self.data.borrow().last_run_len
+    }
+
+    /// Drop all queued tasks without running them.
+    pub fn clear(&self) {
+        let queues = mem::take(&mut self.data.borrow_mut().queues);
+        // Tasks are dropped after releasing the borrow, as they may own structures which schedule
+        // new tasks on drop.
+        drop(queues);
+    }
+
+    /// Run the queued tasks as long as the `has_time` function returns [`true`]. At least one task
+    /// is run (if any is queued), so the queue is guaranteed to progress even if all frames are
+    /// overloaded. Tasks are allowed to schedule new tasks. Returns the number of tasks run.
+    pub fn run_while(&self, has_time: impl Fn() -> bool) -> usize {
+        let mut count = 0;
+        loop {
+            if count > 0 && !has_time() {
+                break;
+            }
+            let next_task = self.next_task();
+            match next_task {
+                Some(task) => {
+                    task();
+                    count += 1;
+                }
+                None => break,
+            }
+        }
+        self.data.borrow_mut().last_run_len = count;
+        count
+    }
+
+    fn next_task(&self) -> Option<Box<dyn FnOnce()>> {
+        self.data.borrow_mut().queues.iter_mut().find_map(|queue| queue.pop_front())
+    }
+
+    /// Run the queued tasks in the time left until the end of the frame that started at the
+    /// provided time. See [`Self::run_while`] to learn more.
+    pub fn run_in_frame_slack(&self, time: TimeInfo) -> usize {
+        let frame_start = time.frame_start().unchecked_raw() as f64;
+        let deadline = frame_start + TARGET_FRAME_TIME_MS - FRAME_END_MARGIN_MS;
+        let performance = web::window.performance_or_panic();
+        self.run_while(|| performance.now() < deadline)
+    }
+}
+
+
+
+// ========================
+// === Global Scheduler ===
+// ========================
+
+thread_local! {
+    static IDLE_SCHEDULER: IdleScheduler = IdleScheduler::new();
+}
+
+/// The global idle scheduler, run by the [`crate::display::world::World`] after every frame.
+pub fn idle_scheduler() -> IdleScheduler {
+    IDLE_SCHEDULER.with(|scheduler| scheduler.clone_ref())
+}
+
+/// Schedule the task to be run in the idle time with the [`Priority::Normal`] priority.
+pub fn schedule_idle(task: impl IdleTask) {
+    schedule_idle_with_priority(Priority::Normal, task)
+}
+
+/// Schedule the task to be run in the idle time with the given priority.
+pub fn schedule_idle_with_priority(priority: Priority, task: impl IdleTask) {
+    IDLE_SCHEDULER.with(|scheduler| scheduler.schedule(priority, task))
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recording_task(log: &Rc<RefCell<Vec<usize>>>, id: usize) -> impl IdleTask {
+        let log = log.clone_ref();
+        move || log.borrow_mut().push(id)
+    }
+
+    #[test]
+    fn tasks_are_run_by_priority() {
+        let scheduler = IdleScheduler::new();
+        let log = Rc::new(RefCell::new(vec![]));
+        scheduler.schedule(Priority::Low, recording_task(&log, 0));
+        scheduler.schedule(Priority::Normal, recording_task(&log, 1));
+        scheduler.schedule(Priority::High, recording_task(&log, 2));
+        scheduler.schedule(Priority::Normal, recording_task(&log, 3));
+        assert_eq!(scheduler.queued_count(), 4);
+        assert_eq!(scheduler.queued_count_of(Priority::Normal), 2);
+        assert_eq!(scheduler.run_while(|| true), 4);
+        assert_eq!(*log.borrow(), vec![2, 1, 3, 0]);
+        assert_eq!(scheduler.queued_count(), 0);
+    }
+
+    #[test]
+    fn budget_is_respected() {
+        let scheduler = IdleScheduler::new();
+        let log = Rc::new(RefCell::new(vec![]));
+        for id in 0..5 {
+            scheduler.schedule(Priority::Normal, recording_task(&log, id));
+        }
+        // At least one task is always run.
+        assert_eq!(scheduler.run_while(|| false), 1);
+        let budget = Cell::new(2);
+        let has_time = || budget.replace(budget.get().saturating_sub(1)) > 0;
+        assert_eq!(scheduler.run_while(has_time), 3);
+        assert_eq!(scheduler.last_run_count(), 3);
+        assert_eq!(*log.borrow(), vec![0, 1, 2, 3]);
+        assert_eq!(scheduler.queued_count(), 1);
+    }
+
+    #[test]
+    fn tasks_can_schedule_tasks() {
+        let scheduler = IdleScheduler::new();
+        let log = Rc::new(RefCell::new(vec![]));
+        let scheduler_ref = scheduler.clone_ref();
+        let nested_task = recording_task(&log, 1);
+        let task = move || scheduler_ref.schedule(Priority::High, nested_task);
+        scheduler.schedule(Priority::Low, task);
+        assert_eq!(scheduler.run_while(|| true), 2);
+        assert_eq!(*log.borrow(), vec![1]);
+    }
+}
@@ -5,6 +5,7 @@
 use crate::prelude::*;
 
 use crate::animation;
+use crate::animation::quality;
 use crate::data::function::Fn0;
 use crate::data::function::Fn1;
 use crate::types::unit2::Duration;
@@ -609,7 +610,12 @@ where
     /// Proceed with the next simulation step for the given time delta.
     pub fn step(&self, delta_seconds: Duration) -> bool {
         if self.simulation.active() {
-            self.simulation.step(delta_seconds);
+            // Under the reduced quality, animations jump to their targets to save the frame time.
+            if quality::is_reduced() {
+                self.simulation.skip();
+            } else {
+                self.simulation.step(delta_seconds);
+            }
             self.on_step.call(self.simulation.value());
         };
         let is_active = self.simulation.active();
@@ -0,0 +1,218 @@
+//! Adaptive quality governor. It detects sustained frame overruns and switches the application to
+//! the [`Quality::Reduced`] mode, in which non-essential work is degraded until the frame budget
+//! recovers:
+//! - Animations, both physics-based and easing ones, jump to their targets instead of being
+//!   simulated frame by frame.
+//! - Components skip speculative work, like the text area shaping prefetch. Such components should
+//!   check [`is_reduced`] before scheduling the work.
+//! - Fonts generate the MSDFs of tiny glyphs at a lower resolution tier, and regenerate them at
+//!   their regular tiers when the full quality is restored.
+//!
+//! The world reports the frame times to the global governor and emits its `quality` FRP output
+//! when the mode changes, so applications can display a "reduced quality" indicator.
+
+use crate::prelude::*;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// Frames longer than this time (in milliseconds) are considered overruns.
+const FRAME_BUDGET_MS: f64 = 1000.0 / 30.0;
+
+/// Frames shorter than this time (in milliseconds) are considered recovered. It is lower than the
+/// [`FRAME_BUDGET_MS`] to prevent flickering between the modes.
+const RECOVERED_FRAME_TIME_MS: f64 = 1000.0 / 50.0;
+
+/// The number of consecutive frame overruns after which the quality is reduced.
+const OVERRUN_FRAME_COUNT: usize = 30;
+
+/// The number of consecutive recovered frames after which the full quality is restored.
+const RECOVERED_FRAME_COUNT: usize = 120;
+
+
+
+// ===============
+// === Quality ===
+// ===============
+
+/// The rendering quality mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum Quality {
+    #[default]
+    Full,
+    Reduced,
+}
+
+impl Quality {
+    /// Check whether the quality is reduced.
+    pub fn is_reduced(self) -> bool {
+        self == Quality::Reduced
+    }
+}
+
+
+
+// ================
+// === Governor ===
+// ================
+
+/// Adaptive quality governor. See the module docs to learn more.
+#[derive(Clone, CloneRef, Debug)]
+pub struct Governor {
+    data: Rc<GovernorData>,
+}
+
+#[derive(Debug)]
+struct GovernorData {
+    enabled:         Cell<bool>,
+    quality:         Cell<Quality>,
+    overrun_count:   Cell<usize>,
+    recovered_count: Cell<usize>,
+}
+
+impl Default for Governor {
+    fn default() -> Self {
+        let enabled = Cell::new(true);
+        let quality = default();
+        let overrun_count = default();
+        let recovered_count = default();
+        let data = Rc::new(GovernorData { enabled, quality, overrun_count, recovered_count });
+        Self { data }
+    }
+}
+
+impl Governor {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// The current quality mode.
+    pub fn quality(&self) -> Quality {
+        self.data.quality.get()
+    }
+
+    /// Enable or disable the governor. A disabled governor always reports the full quality.
+    /// Returns the new quality if it changed.
+    pub fn set_enabled(&self, enabled: bool) -> Option<Quality> {
+        self.data.enabled.set(enabled);
+        self.data.overrun_count.set(0);
+        self.data.recovered_count.set(0);
+        if enabled {
+            None
+        } else {
+            self.set_quality(Quality::Full)
+        }
+    }
+
+    /// Report the duration of the last frame in milliseconds. Returns the new quality if it
+    /// changed.
+    pub fn on_frame(&self, frame_time: f64) -> Option<Quality> {
+        if !self.data.enabled.get() {
+            return None;
+        }
+        let is_overrun = frame_time > FRAME_BUDGET_MS;
+        let is_recovered = frame_time < RECOVERED_FRAME_TIME_MS;
+        let overrun_count = if is_overrun { self.data.overrun_count.get() + 1 } else { 0 };
+        let recovered_count = if is_recovered { self.data.recovered_count.get() + 1 } else { 0 };
+        self.data.overrun_count.set(overrun_count);
+        self.data.recovered_count.set(recovered_count);
+        match self.quality() {
+            Quality::Full if overrun_count >= OVERRUN_FRAME_COUNT =>
+                self.set_quality(Quality::Reduced),
+            Quality::Reduced if recovered_count >= RECOVERED_FRAME_COUNT =>
+                self.set_quality(Quality::Full),
+            _ => None,
+        }
+    }
+
+    fn set_quality(&self, quality: Quality) -> Option<Quality> {
+        let changed = self.data.quality.replace(quality) != quality;
+        self.data.overrun_count.set(0);
+        self.data.recovered_count.set(0);
+        changed.then_some(quality)
+    }
+}
+
+
+
+// =======================
+// === Global Governor ===
+// =======================
+
+thread_local! {
+    static GOVERNOR: Governor = Governor::new();
+}
+
+/// The global quality governor, fed with frame times by the [`crate::display::world::World`].
+pub fn governor() -> Governor {
+    GOVERNOR.with(|governor| governor.clone_ref())
+}
+
+/// The current quality mode of the global governor.
+pub fn quality() -> Quality {
+    GOVERNOR.with(|governor| governor.quality())
+}
+
+/// Check whether the global governor reduced the quality. Components should skip non-essential
+/// work in such a case.
+pub fn is_reduced() -> bool {
+    quality().is_reduced()
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quality_is_reduced_after_sustained_overruns() {
+        let governor = Governor::new();
+        let slow_frame = FRAME_BUDGET_MS * 2.0;
+        for _ in 1..OVERRUN_FRAME_COUNT {
+            assert_eq!(governor.on_frame(slow_frame), None);
+        }
+        // A single fast frame resets the overrun counter.
+        assert_eq!(governor.on_frame(RECOVERED_FRAME_TIME_MS / 2.0), None);
+        for _ in 1..OVERRUN_FRAME_COUNT {
+            assert_eq!(governor.on_frame(slow_frame), None);
+        }
+        assert_eq!(governor.on_frame(slow_frame), Some(Quality::Reduced));
+        assert!(governor.quality().is_reduced());
+    }
+
+    #[test]
+    fn quality_is_restored_after_recovery() {
+        let governor = Governor::new();
+        for _ in 0..OVERRUN_FRAME_COUNT {
+            governor.on_frame(FRAME_BUDGET_MS * 2.0);
+        }
+        assert_eq!(governor.quality(), Quality::Reduced);
+        for _ in 1..RECOVERED_FRAME_COUNT {
+            assert_eq!(governor.on_frame(RECOVERED_FRAME_TIME_MS / 2.0), None);
+        }
+        assert_eq!(governor.on_frame(RECOVERED_FRAME_TIME_MS / 2.0), Some(Quality::Full));
+    }
+
+    #[test]
+    fn disabled_governor_keeps_full_quality() {
+        let governor = Governor::new();
+        for _ in 0..OVERRUN_FRAME_COUNT {
+            governor.on_frame(FRAME_BUDGET_MS * 2.0);
+        }
+        assert_eq!(governor.set_enabled(false), Some(Quality::Full));
+        for _ in 0..OVERRUN_FRAME_COUNT {
+            assert_eq!(governor.on_frame(FRAME_BUDGET_MS * 2.0), None);
+        }
+        assert_eq!(governor.quality(), Quality::Full);
+    }
+}
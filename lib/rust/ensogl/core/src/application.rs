@@ -5,6 +5,8 @@ use enso_web::traits::*;
 
 use crate::application::command::FrpNetworkProvider;
 use crate::display;
+use crate::display::render::memory;
+use crate::display::render::memory::MemoryMode;
 use crate::display::scene::DomPath;
 use crate::display::world::World;
 use crate::gui::cursor::Cursor;
@@ -17,8 +19,12 @@ use crate::system::web;
 
 pub mod command;
 pub mod frp;
+pub mod retention;
+pub mod scripting;
+pub mod settings;
 pub mod shortcut;
 pub mod tooltip;
+pub mod undo;
 pub mod view;
 
 pub use view::View;
@@ -68,13 +74,23 @@ pub struct Application {
 #[derive(Debug, display::Object)]
 #[allow(missing_docs)]
 pub struct ApplicationData {
-    pub cursor:    Cursor,
+    pub cursor:        Cursor,
     #[display_object]
-    pub display:   World,
-    pub commands:  command::Registry,
-    pub shortcuts: shortcut::Registry,
-    pub views:     view::Registry,
-    pub frp:       Frp,
+    pub display:       World,
+    pub commands:      command::Registry,
+    pub shortcuts:     shortcut::Registry,
+    pub views:         view::Registry,
+    /// The keyboard shortcuts overview, toggled with the `cmd /` shortcut.
+    pub shortcut_help: shortcut::help::ShortcutHelp,
+    /// The undo history shared by all components of the application.
+    pub undo:          undo::Manager,
+    /// The user settings persisted between application runs.
+    pub settings:      settings::Store,
+    /// The bridge exposing views to JavaScript, available as `window.ensoglScripting`.
+    pub scripting:     scripting::Bridge,
+    /// The values owned by the application, see [`Application::keep_alive`].
+    pub retention:     retention::Registry,
+    pub frp:           Frp,
 }
 
 impl Application {
@@ -87,15 +103,44 @@ impl Application {
         let shortcuts =
             shortcut::Registry::new(&scene.mouse.frp_deprecated, &scene, &scene, &commands);
         let views = view::Registry::create(&commands, &shortcuts);
+        let shortcut_help = shortcut::help::ShortcutHelp::new(&shortcuts);
+        views.register::<shortcut::help::ShortcutHelp>();
+        commands.register_instance(&shortcut_help);
+        display.add_child(&shortcut_help);
+        let undo = undo::Manager::new();
+        views.register::<undo::Manager>();
+        commands.register_instance(&undo);
+        let settings = settings::Store::new();
+        let scripting = scripting::Bridge::new();
+        let retention = retention::Registry::new();
         let cursor = Cursor::new(&display.default_scene);
         display.add_child(&cursor);
         let frp = Frp::new();
 
-        let data = ApplicationData { cursor, display, commands, shortcuts, views, frp };
+        let data = ApplicationData {
+            cursor,
+            display,
+            commands,
+            shortcuts,
+            views,
+            shortcut_help,
+            undo,
+            settings,
+            scripting,
+            retention,
+            frp,
+        };
 
         Self { inner: Rc::new(data) }.init()
     }
 
+    /// Constructor of an application rendered in the given GPU memory mode. See the
+    /// [`memory`] module docs to learn more.
+    pub fn new_with_memory_mode(dom: impl DomPath, mode: MemoryMode) -> Self {
+        memory::set_mode(mode);
+        Self::new(dom)
+    }
+
     fn init(self) -> Self {
         let frp = &self.frp;
         let network = self.frp.network();
@@ -109,6 +154,7 @@ impl Application {
         }
         // We hide the system cursor to replace it with the EnsoGL-provided one.
         self.frp.hide_system_cursor();
+        scripting::expose_to_window(&self);
         self
     }
 
@@ -123,6 +169,36 @@ impl Application {
     pub fn new_view<T: View>(&self) -> T {
         self.views.new_view(self)
     }
+
+    /// Keep the value alive until the returned handle or the application is disposed. Use it
+    /// instead of `mem::forget` for views, networks, and other values which should live as long as
+    /// the application. See the [`retention`] module docs to learn more.
+    pub fn keep_alive(&self, value: impl Any) -> retention::Handle {
+        self.retention.keep_alive(value)
+    }
+
+    /// Add the display object to the scene, which owns it until it is removed from the scene, or
+    /// until the returned handle or the application is disposed.
+    pub fn add_owned_child<T: display::Object + 'static>(&self, child: T) -> retention::Handle {
+        self.display.add_child(&child);
+        self.retention.keep_alive_while_displayed(child)
+    }
+
+    /// Keep the application alive until it is disposed, even if all other references are dropped.
+    /// It replaces the `mem::forget` of the application in the entry points.
+    pub fn keep_alive_until_disposed(&self) {
+        self.retention.keep_alive(self.clone_ref());
+    }
+
+    /// Drop all values owned by the application and the views exposed to JavaScript, tearing down
+    /// their FRP networks, DOM event listeners, and GPU resources. The application itself is
+    /// dropped after the last reference to it is dropped.
+    pub fn dispose(&self) {
+        for name in self.scripting.view_names() {
+            self.scripting.dispose(&name);
+        }
+        self.retention.dispose_all();
+    }
 }
 
 
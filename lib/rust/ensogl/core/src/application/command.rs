@@ -8,6 +8,8 @@ use crate::application::shortcut::Shortcut;
 use crate::application::Application;
 use crate::frp;
 
+use std::any::TypeId;
+
 
 
 // ============
@@ -100,6 +102,123 @@ impl Command {
 
 
 
+// ================
+// === Endpoint ===
+// ================
+
+/// The direction of an FRP endpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum EndpointKind {
+    Input,
+    Output,
+}
+
+/// Runtime metadata of an FRP endpoint, generated by the `define_endpoints!` and
+/// `define_endpoints_2!` macros. It allows discovering and driving the API of a component without
+/// knowing its type, for example in the component gallery or in scripted tests.
+#[derive(Clone, Derivative)]
+#[derivative(Debug)]
+pub struct Endpoint {
+    /// The name of the endpoint, the same as the name of its field.
+    pub name:      &'static str,
+    #[allow(missing_docs)]
+    pub kind:      EndpointKind,
+    /// The name of the type of the endpoint values, as reported by [`std::any::type_name`].
+    pub type_name: &'static str,
+    /// The lines of the endpoint documentation.
+    pub docs:      &'static [&'static str],
+    type_id:       TypeId,
+    /// The [`frp::Any`] node of an input endpoint, or the [`frp::Sampler`] of an output one.
+    #[derivative(Debug = "ignore")]
+    node:          Rc<dyn Any>,
+    /// Debug representation of the current value of an output endpoint.
+    #[derivative(Debug = "ignore")]
+    debug_value:   Option<Rc<dyn Fn() -> String>>,
+    /// A constructor of a stream emitting when an output endpoint emits a value.
+    #[derivative(Debug = "ignore")]
+    changes:       Option<Rc<dyn Fn(&frp::Network) -> frp::Stream>>,
+}
+
+impl Endpoint {
+    /// Constructor of an input endpoint metadata.
+    pub fn input<T: frp::Data>(
+        name: &'static str,
+        docs: &'static [&'static str],
+        node: &frp::Any<T>,
+    ) -> Self {
+        Self::new::<T>(name, EndpointKind::Input, docs, Rc::new(node.clone_ref()))
+    }
+
+    /// Constructor of an output endpoint metadata.
+    pub fn output<T: frp::Data>(
+        name: &'static str,
+        docs: &'static [&'static str],
+        node: &frp::Sampler<T>,
+    ) -> Self {
+        let sampler = node.clone_ref();
+        let debug_value = Rc::new(move || format!("{:?}", sampler.value()));
+        let sampler = node.clone_ref();
+        let changes = Rc::new(move |network: &frp::Network| {
+            frp::extend! { network
+                changed <- sampler.constant(());
+            }
+            changed
+        });
+        let mut endpoint =
+            Self::new::<T>(name, EndpointKind::Output, docs, Rc::new(node.clone_ref()));
+        endpoint.debug_value = Some(debug_value);
+        endpoint.changes = Some(changes);
+        endpoint
+    }
+
+    fn new<T: frp::Data>(
+        name: &'static str,
+        kind: EndpointKind,
+        docs: &'static [&'static str],
+        node: Rc<dyn Any>,
+    ) -> Self {
+        let type_name = std::any::type_name::<T>();
+        let type_id = TypeId::of::<T>();
+        let debug_value = None;
+        let changes = None;
+        Self { name, kind, type_name, docs, type_id, node, debug_value, changes }
+    }
+
+    /// The documentation of the endpoint, with the doc lines trimmed and joined.
+    pub fn doc(&self) -> String {
+        self.docs.iter().map(|line| line.trim()).join(" ")
+    }
+
+    /// Check whether the endpoint values are of the given type.
+    pub fn is<T: 'static>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+
+    /// The node of the input endpoint, if the endpoint is an input of the given type.
+    pub fn as_input<T: frp::Data>(&self) -> Option<&frp::Any<T>> {
+        self.node.downcast_ref()
+    }
+
+    /// The sampler of the output endpoint, if the endpoint is an output of the given type.
+    pub fn as_output<T: frp::Data>(&self) -> Option<&frp::Sampler<T>> {
+        self.node.downcast_ref()
+    }
+
+    /// Debug representation of the current value, if the endpoint is an output.
+    pub fn debug_value(&self) -> Option<String> {
+        self.debug_value.as_ref().map(|debug_value| debug_value())
+    }
+
+    /// A stream in the provided network emitting every time the output endpoint emits a value.
+    /// Returns [`None`] if the endpoint is an input.
+    pub fn changes(&self, network: &frp::Network) -> Option<frp::Stream> {
+        self.changes.as_ref().map(|changes| changes(network))
+    }
+}
+
+
+
 // ==================
 // === CommandApi ===
 // ==================
@@ -114,6 +233,10 @@ pub trait CommandApi: Sized {
     fn status_api(&self) -> Rc<RefCell<HashMap<String, frp::Sampler<bool>>>> {
         default()
     }
+    /// Runtime metadata of all FRP endpoints. See [`Endpoint`] to learn more.
+    fn endpoints(&self) -> Vec<Endpoint> {
+        default()
+    }
 }
 
 
@@ -373,6 +373,21 @@ macro_rules! define_endpoints {
                 let _params     = default();
                 Self {source,input,status_map,command_map,$($out_field),*,_params}
             }
+
+            /// Runtime metadata of all endpoints. See [`Endpoint`] to learn more.
+            ///
+            /// [`Endpoint`]: $crate::application::command::Endpoint
+            pub fn endpoints(&self) -> Vec<$crate::application::command::Endpoint> {
+                use $crate::application::command::Endpoint;
+                vec![
+                    $(Endpoint::input(
+                        stringify!($in_field), &[$($($in_doc)*),*], &self.input.$in_field
+                    ),)*
+                    $(Endpoint::output(
+                        stringify!($out_field), &[$($($out_doc)*),*], &self.$out_field
+                    ),)*
+                ]
+            }
         }
 
         /// Frp output setters.
@@ -407,6 +422,10 @@ macro_rules! define_endpoints {
             fn status_api(&self) -> Rc<RefCell<HashMap<String,$crate::frp::Sampler<bool>>>> {
                 self.status_map.clone()
             }
+
+            fn endpoints(&self) -> Vec<$crate::application::command::Endpoint> {
+                self.output.endpoints()
+            }
         }
 
         impl $(<$($param $(:$($constraints)*)?),*>)?  $crate::application::command::FrpNetworkProvider for Frp $(<$($param),*>)?  {
@@ -433,6 +452,21 @@ macro_rules! build_command_map {
     ($($ts:tt)*) => {};
 }
 
+/// Internal helper of `define_endpoints_2` macro. Collects the doc lines from the given field
+/// attributes into a static slice, skipping other attributes.
+#[macro_export]
+macro_rules! endpoint_docs {
+    ([$($docs:expr),*]) => {
+        &[$($docs),*]
+    };
+    ([$($docs:expr),*] [doc = $doc:expr] $($attrs:tt)*) => {
+        $crate::endpoint_docs!([$($docs,)* $doc] $($attrs)*)
+    };
+    ([$($docs:expr),*] $attr:tt $($attrs:tt)*) => {
+        $crate::endpoint_docs!([$($docs),*] $($attrs)*)
+    };
+}
+
 /// Defines a method which is an alias to FRP emit method. Used internally by the `define_endpoints`
 /// macro.
 #[macro_export]
@@ -1050,6 +1084,25 @@ macro_rules! define_endpoints_2_normalized_public {
             ) -> Self {
                 Self {input, output, combined}
             }
+
+            /// Runtime metadata of all endpoints. See [`Endpoint`] to learn more.
+            ///
+            /// [`Endpoint`]: $crate::application::command::Endpoint
+            pub fn endpoints(&self) -> Vec<$crate::application::command::Endpoint> {
+                use $crate::application::command::Endpoint;
+                vec![
+                    $(Endpoint::input(
+                        stringify!($in_field),
+                        $crate::endpoint_docs!([] $($in_field_attr)*),
+                        &self.input.$in_field,
+                    ),)*
+                    $(Endpoint::output(
+                        stringify!($out_field),
+                        $crate::endpoint_docs!([] $($out_field_attr)*),
+                        &self.output.$out_field,
+                    ),)*
+                ]
+            }
         }
 
         impl $($ctx)* Deref for Public $($param)* {
@@ -1077,6 +1130,10 @@ macro_rules! define_endpoints_2_normalized_public {
             fn status_api(&self) -> Rc<RefCell<HashMap<String,$crate::frp::Sampler<bool>>>> {
                 self.output.status_map.clone()
             }
+
+            fn endpoints(&self) -> Vec<$crate::application::command::Endpoint> {
+                Public::endpoints(self)
+            }
         }
 
         pub mod public {
@@ -1374,6 +1431,10 @@ macro_rules! define_endpoints_2_normalized_glue {
                 fn status_api(&self) -> Rc<RefCell<HashMap<String,$crate::frp::Sampler<bool>>>> {
                     self.public.status_api()
                 }
+
+                fn endpoints(&self) -> Vec<$crate::application::command::Endpoint> {
+                    self.public.endpoints()
+                }
             }
 
             /// Weak version of FRP.
@@ -1503,6 +1564,50 @@ mod tests {
         }
     }
 
+    mod documented_api {
+        use super::*;
+        use crate::application::command::EndpointKind;
+
+        define_endpoints_2! {
+            Input {
+                /// Set the label.
+                /// It is displayed above the component.
+                set_label(ImString),
+                clear(),
+            }
+            Output {
+                /// The current label.
+                label(ImString),
+            }
+        }
+
+        #[test]
+        fn endpoint_metadata_describes_and_drives_the_api() {
+            let frp = Frp::new();
+            let network = frp.network();
+            frp::extend! { network
+                frp.private.output.label <+ frp.private.input.set_label;
+            }
+            let endpoints = frp.endpoints();
+            let names = endpoints.iter().map(|endpoint| endpoint.name).collect_vec();
+            let focus = ["deprecated_focus", "deprecated_defocus", "deprecated_set_focus"];
+            assert_eq!(names[..3], focus);
+            assert_eq!(names[3..], ["set_label", "clear", "focused", "label"]);
+
+            let find = |name| endpoints.iter().find(|endpoint| endpoint.name == name).unwrap();
+            let set_label = find("set_label");
+            assert_eq!(set_label.kind, EndpointKind::Input);
+            assert_eq!(set_label.doc(), "Set the label. It is displayed above the component.");
+            assert!(set_label.is::<ImString>());
+            assert!(set_label.as_input::<bool>().is_none());
+            assert_eq!(find("clear").doc(), "");
+            set_label.as_input::<ImString>().unwrap().emit(ImString::from("Hello"));
+            let label = find("label");
+            assert_eq!(label.kind, EndpointKind::Output);
+            assert_eq!(label.as_output::<ImString>().unwrap().value(), "Hello");
+        }
+    }
+
     // Check compilation of macro output.
     define_endpoints_2! { <Value:(Default+Debug+Clone+'static)>
         Input{
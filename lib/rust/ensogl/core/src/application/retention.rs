@@ -0,0 +1,213 @@
+//! Ownership of values created dynamically at runtime. A value kept alive with
+//! [`Application::keep_alive`](crate::application::Application::keep_alive) is owned by the
+//! application until its [`Handle`] is disposed or the application is disposed. Unlike calling
+//! `mem::forget`, this does not leak the value: disposing it drops it, tearing down its FRP
+//! networks, DOM event listeners, and GPU resources.
+//!
+//! Display objects can be owned by the scene with
+//! [`Application::add_owned_child`](crate::application::Application::add_owned_child). They are
+//! disposed automatically after being removed from the scene.
+
+use crate::prelude::*;
+
+use crate::animation::idle;
+use crate::display;
+use crate::frp;
+
+
+
+// ================
+// === Retained ===
+// ================
+
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct Retained {
+    #[derivative(Debug = "ignore")]
+    value:    Box<dyn Any>,
+    /// The network disposing the value when it is removed from the scene, if any.
+    _network: Option<frp::Network>,
+}
+
+
+
+// ================
+// === Registry ===
+// ================
+
+#[derive(Debug, Default)]
+struct RegistryData {
+    values:  RefCell<BTreeMap<usize, Retained>>,
+    next_id: Cell<usize>,
+}
+
+impl RegistryData {
+    fn new_id(&self) -> usize {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        id
+    }
+
+    fn insert(&self, id: usize, retained: Retained) {
+        self.values.borrow_mut().insert(id, retained);
+    }
+
+    fn remove(&self, id: usize) -> Option<Retained> {
+        self.values.borrow_mut().remove(&id)
+    }
+}
+
+/// The values owned by the application. See the module docs to learn more.
+#[derive(Clone, CloneRef, Debug, Default)]
+pub struct Registry {
+    data: Rc<RegistryData>,
+}
+
+impl Registry {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Keep the value alive until the returned handle or the registry is disposed.
+    pub fn keep_alive(&self, value: impl Any) -> Handle {
+        let id = self.data.new_id();
+        let value = Box::new(value);
+        self.data.insert(id, Retained { value, _network: None });
+        Handle { id, registry: Rc::downgrade(&self.data) }
+    }
+
+    /// Keep the display object alive until it is hidden, for example removed from the scene, or
+    /// until the returned handle or the registry is disposed. The object should be already
+    /// displayed.
+    ///
+    /// The object is dropped in the idle time after being hidden, as the object can not be dropped
+    /// while it emits the event.
+    pub fn keep_alive_while_displayed<T: display::Object + 'static>(&self, object: T) -> Handle {
+        let id = self.data.new_id();
+        let network = frp::Network::new("retention::Registry");
+        let on_hide = &object.display_object().on_hide;
+        let registry = Rc::downgrade(&self.data);
+        frp::extend! { network
+            eval_ on_hide ([registry] {
+                let retained = registry.upgrade().and_then(|registry| registry.remove(id));
+                if let Some(retained) = retained {
+                    idle::schedule_idle_with_priority(idle::Priority::High, move || drop(retained));
+                }
+            });
+        }
+        let value = Box::new(object);
+        self.data.insert(id, Retained { value, _network: Some(network) });
+        Handle { id, registry: Rc::downgrade(&self.data) }
+    }
+
+    /// The number of values kept alive.
+    pub fn len(&self) -> usize {
+        self.data.values.borrow().len()
+    }
+
+    /// Check whether no values are kept alive.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drop all values, in the reverse order of retaining them.
+    pub fn dispose_all(&self) {
+        let values = mem::take(&mut *self.data.values.borrow_mut());
+        // The values are dropped outside of the borrow, as dropping them may dispose other values.
+        for (_, retained) in values.into_iter().rev() {
+            drop(retained);
+        }
+    }
+}
+
+
+
+// ==============
+// === Handle ===
+// ==============
+
+/// A handle of a value kept alive by the [`Registry`]. Dropping the handle does not drop the
+/// value, use [`Handle::dispose`] instead.
+#[derive(Clone, Debug)]
+pub struct Handle {
+    id:       usize,
+    registry: Weak<RegistryData>,
+}
+
+impl Handle {
+    /// Drop the value. Returns [`false`] if it was already disposed.
+    pub fn dispose(&self) -> bool {
+        let retained = self.registry.upgrade().and_then(|registry| registry.remove(self.id));
+        retained.is_some()
+    }
+
+    /// Check whether the value is still kept alive.
+    pub fn is_alive(&self) -> bool {
+        let registry = self.registry.upgrade();
+        registry.map_or(false, |registry| registry.values.borrow().contains_key(&self.id))
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use enso_frp::microtasks;
+
+    /// A value recording whether it was dropped.
+    struct Tracked(Rc<Cell<bool>>);
+
+    impl Drop for Tracked {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    #[test]
+    fn values_live_until_disposed() {
+        let registry = Registry::new();
+        let dropped = (0..3).map(|_| Rc::new(Cell::new(false))).collect_vec();
+        let handles = dropped.iter().map(|d| registry.keep_alive(Tracked(d.clone_ref())));
+        let handles = handles.collect_vec();
+        assert_eq!(registry.len(), 3);
+        assert!(dropped.iter().all(|d| !d.get()));
+
+        assert!(handles[1].dispose());
+        assert!(dropped[1].get());
+        assert!(!handles[1].is_alive());
+        assert!(!handles[1].dispose());
+
+        registry.dispose_all();
+        assert!(dropped.iter().all(|d| d.get()));
+        assert!(registry.is_empty());
+        assert!(!handles[0].is_alive());
+    }
+
+    #[test]
+    fn displayed_objects_are_released_after_being_hidden() {
+        let world = display::world::World::new();
+        let scene = &world.default_scene;
+        let update = |root: &display::object::Instance| {
+            root.update(scene);
+            microtasks::flush_microtasks();
+        };
+        let registry = Registry::new();
+        let root = display::object::Instance::new();
+        root.show();
+        let object = display::object::Instance::new();
+        root.add_child(&object);
+        update(&root);
+        let handle = registry.keep_alive_while_displayed(object.clone_ref());
+        assert!(handle.is_alive());
+        object.unset_parent();
+        update(&root);
+        assert!(!handle.is_alive());
+    }
+}
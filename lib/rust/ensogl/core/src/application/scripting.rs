@@ -0,0 +1,500 @@
+//! A scripting bridge exposing views and their FRP endpoints to JavaScript. It allows host pages
+//! and end-to-end test frameworks to automate components without rebuilding the Rust code.
+//!
+//! The bridge is available as the `window.ensoglScripting` object with the following functions:
+//! - `labels()`: the labels of all registered view definitions.
+//! - `views()`: the names of all views exposed to scripts.
+//! - `create(label)`: create a new instance of a registered view, add it to the scene, and return
+//!   its name.
+//! - `dispose(view)`: remove the view from the bridge and the scene.
+//! - `endpoints(view)`: the list of the view endpoints with their kinds, types, and docs.
+//! - `set(view, endpoint, value)`: emit the value to the input endpoint.
+//! - `get(view, endpoint)`: the current value of the output endpoint.
+//! - `subscribe(view, endpoint, callback)`: call the callback with every value emitted by the
+//!   output endpoint. Returns the subscription identifier.
+//! - `unsubscribe(id)`: cancel the subscription.
+//!
+//! Values are passed as JSON. Inputs of the [`SUPPORTED_TYPES`] can be set from scripts. Outputs of
+//! these types are reported as JSON values, while outputs of other types are reported as strings
+//! containing their debug representation. Views created by the application can be exposed to
+//! scripts with [`Bridge::expose`].
+
+use crate::prelude::*;
+
+use crate::application::command::Endpoint;
+use crate::application::command::EndpointKind;
+use crate::application::view::AnyView;
+use crate::application::Application;
+use crate::display::object::ObjectOps;
+use crate::frp;
+
+use serde::Serialize;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The names of the types of values which can be passed to and from scripts.
+pub const SUPPORTED_TYPES: &[&str] =
+    &["()", "bool", "f32", "f64", "i32", "usize", "String", "ImString"];
+
+/// Run the macro for all [`SUPPORTED_TYPES`].
+macro_rules! with_supported_types {
+    ($macro:ident) => {
+        $macro!((), bool, f32, f64, i32, usize, String, ImString)
+    };
+}
+
+
+
+// =============
+// === Error ===
+// =============
+
+/// An error of a script request.
+#[derive(Clone, Debug, Fail)]
+#[allow(missing_docs)]
+pub enum Error {
+    UnknownLabel(String),
+    UnknownView(String),
+    UnknownEndpoint { view: String, endpoint: String },
+    NotAnInput(String),
+    NotAnOutput(String),
+    UnsupportedType { endpoint: String, type_name: String },
+    InvalidValue { endpoint: String, message: String },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownLabel(label) => write!(f, "No view is registered with label '{label}'."),
+            Self::UnknownView(view) => write!(f, "No view named '{view}' is exposed to scripts."),
+            Self::UnknownEndpoint { view, endpoint } =>
+                write!(f, "The view '{view}' has no endpoint named '{endpoint}'."),
+            Self::NotAnInput(endpoint) => write!(f, "The endpoint '{endpoint}' is not an input."),
+            Self::NotAnOutput(endpoint) => write!(f, "The endpoint '{endpoint}' is not an output."),
+            Self::UnsupportedType { endpoint, type_name } => write!(
+                f,
+                "Values of the endpoint '{endpoint}' of type '{type_name}' cannot be set from \
+                scripts. The supported types are: {}.",
+                SUPPORTED_TYPES.join(", ")
+            ),
+            Self::InvalidValue { endpoint, message } =>
+                write!(f, "Invalid value for the endpoint '{endpoint}': {message}"),
+        }
+    }
+}
+
+
+
+// ===========================
+// === EndpointDescription ===
+// ===========================
+
+/// The description of an endpoint sent to scripts.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[allow(missing_docs)]
+pub struct EndpointDescription {
+    pub name:      String,
+    pub kind:      String,
+    pub type_name: String,
+    pub doc:       String,
+}
+
+impl From<&Endpoint> for EndpointDescription {
+    fn from(endpoint: &Endpoint) -> Self {
+        let name = endpoint.name.into();
+        let kind = match endpoint.kind {
+            EndpointKind::Input => "input".into(),
+            EndpointKind::Output => "output".into(),
+        };
+        let type_name = endpoint.type_name.into();
+        let doc = endpoint.doc();
+        Self { name, kind, type_name, doc }
+    }
+}
+
+
+
+// ==============
+// === Values ===
+// ==============
+
+/// Parse the JSON value and emit it to the input endpoint. An empty string is treated as `null`,
+/// so commands can be emitted without providing a value.
+fn emit_json(endpoint: &Endpoint, value: &str) -> Result<(), Error> {
+    let value = if value.trim().is_empty() { "null" } else { value };
+    let invalid_value = |error: serde_json::Error| {
+        let endpoint = endpoint.name.into();
+        let message = error.to_string();
+        Error::InvalidValue { endpoint, message }
+    };
+    macro_rules! try_emit {
+        ($($type:ty),*) => {$(
+            if let Some(node) = endpoint.as_input::<$type>() {
+                node.emit(serde_json::from_str::<$type>(value).map_err(invalid_value)?);
+                return Ok(());
+            }
+        )*};
+    }
+    with_supported_types!(try_emit);
+    let endpoint_name = endpoint.name.into();
+    let type_name = endpoint.type_name.into();
+    Err(Error::UnsupportedType { endpoint: endpoint_name, type_name })
+}
+
+/// The current value of the output endpoint as JSON. Values of unsupported types are converted to
+/// strings containing their debug representation.
+fn output_json(endpoint: &Endpoint) -> String {
+    macro_rules! try_serialize {
+        ($($type:ty),*) => {$(
+            if let Some(sampler) = endpoint.as_output::<$type>() {
+                if let Ok(json) = serde_json::to_string(&sampler.value()) {
+                    return json;
+                }
+            }
+        )*};
+    }
+    with_supported_types!(try_serialize);
+    let debug_value = endpoint.debug_value().unwrap_or_default();
+    serde_json::to_string(&debug_value).unwrap_or_default()
+}
+
+
+
+// ==============
+// === Bridge ===
+// ==============
+
+/// A subscription to an output endpoint. The callback is called as long as the network lives.
+#[derive(Debug)]
+struct Subscription {
+    view:     String,
+    _network: frp::Network,
+}
+
+#[derive(Debug, Default)]
+struct Model {
+    views:                RefCell<BTreeMap<String, AnyView>>,
+    subscriptions:        RefCell<HashMap<usize, Subscription>>,
+    next_view_id:         Cell<usize>,
+    next_subscription_id: Cell<usize>,
+}
+
+/// The scripting bridge. See the module docs to learn more.
+#[derive(Clone, CloneRef, Debug, Default)]
+pub struct Bridge {
+    model: Rc<Model>,
+}
+
+impl Bridge {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Make the view accessible to scripts under the given name, replacing the view previously
+    /// exposed under this name.
+    pub fn expose(&self, name: impl Into<String>, view: AnyView) {
+        let name = name.into();
+        self.drop_subscriptions_of(&name);
+        self.model.views.borrow_mut().insert(name, view);
+    }
+
+    /// Remove the view from the bridge and the scene. Returns [`false`] if there is no such view.
+    pub fn dispose(&self, name: &str) -> bool {
+        self.drop_subscriptions_of(name);
+        let view = self.model.views.borrow_mut().remove(name);
+        if let Some(view) = &view {
+            view.unset_parent();
+        }
+        view.is_some()
+    }
+
+    /// The names of all views exposed to scripts.
+    pub fn view_names(&self) -> Vec<String> {
+        self.model.views.borrow().keys().cloned().collect()
+    }
+
+    /// Create a new instance of the registered view, add it to the scene, and expose it under a
+    /// generated name, which is returned.
+    pub fn create(&self, app: &Application, label: &str) -> Result<String, Error> {
+        let view = app.views.new_view_by_label(label, app);
+        let view = view.ok_or_else(|| Error::UnknownLabel(label.into()))?;
+        app.display.add_child(&view);
+        let id = self.model.next_view_id.get();
+        self.model.next_view_id.set(id + 1);
+        let name = format!("{label}#{id}");
+        self.expose(name.clone(), view);
+        Ok(name)
+    }
+
+    /// The descriptions of all endpoints of the view.
+    pub fn endpoints(&self, view: &str) -> Result<Vec<EndpointDescription>, Error> {
+        let views = self.model.views.borrow();
+        let view = views.get(view).ok_or_else(|| Error::UnknownView(view.into()))?;
+        Ok(view.endpoints().iter().map(EndpointDescription::from).collect())
+    }
+
+    /// Emit the value, encoded as JSON, to the input endpoint of the view.
+    pub fn set(&self, view: &str, endpoint: &str, value: &str) -> Result<(), Error> {
+        // The endpoint is cloned, so the emitted value can trigger further script requests.
+        let endpoint = self.endpoint(view, endpoint)?;
+        if endpoint.kind != EndpointKind::Input {
+            return Err(Error::NotAnInput(endpoint.name.into()));
+        }
+        emit_json(&endpoint, value)
+    }
+
+    /// The current value of the output endpoint of the view, encoded as JSON.
+    pub fn get(&self, view: &str, endpoint: &str) -> Result<String, Error> {
+        let endpoint = self.output(view, endpoint)?;
+        Ok(output_json(&endpoint))
+    }
+
+    /// Call the callback with every value emitted by the output endpoint of the view, encoded as
+    /// JSON. Returns the identifier of the subscription, which can be passed to
+    /// [`Bridge::unsubscribe`].
+    pub fn subscribe(
+        &self,
+        view: &str,
+        endpoint: &str,
+        callback: impl Fn(String) + 'static,
+    ) -> Result<usize, Error> {
+        let endpoint = self.output(view, endpoint)?;
+        let network = frp::Network::new("scripting::Subscription");
+        if let Some(changes) = endpoint.changes(&network) {
+            frp::extend! { network
+                eval_ changes (callback(output_json(&endpoint)));
+            }
+        }
+        let id = self.model.next_subscription_id.get();
+        self.model.next_subscription_id.set(id + 1);
+        let subscription = Subscription { view: view.into(), _network: network };
+        self.model.subscriptions.borrow_mut().insert(id, subscription);
+        Ok(id)
+    }
+
+    /// Cancel the subscription. Returns [`false`] if there is no such subscription.
+    pub fn unsubscribe(&self, id: usize) -> bool {
+        self.model.subscriptions.borrow_mut().remove(&id).is_some()
+    }
+
+    fn endpoint(&self, view: &str, endpoint: &str) -> Result<Endpoint, Error> {
+        let views = self.model.views.borrow();
+        let any_view = views.get(view).ok_or_else(|| Error::UnknownView(view.into()))?;
+        let found = any_view.endpoints().iter().find(|e| e.name == endpoint).cloned();
+        found.ok_or_else(|| Error::UnknownEndpoint {
+            view:     view.into(),
+            endpoint: endpoint.into(),
+        })
+    }
+
+    fn output(&self, view: &str, endpoint: &str) -> Result<Endpoint, Error> {
+        let endpoint = self.endpoint(view, endpoint)?;
+        match endpoint.kind {
+            EndpointKind::Output => Ok(endpoint),
+            EndpointKind::Input => Err(Error::NotAnOutput(endpoint.name.into())),
+        }
+    }
+
+    fn drop_subscriptions_of(&self, view: &str) {
+        let subscriptions = self.model.subscriptions.borrow();
+        let ids = subscriptions.iter().filter(|(_, subscription)| subscription.view == view);
+        let ids = ids.map(|(id, _)| *id).collect_vec();
+        drop(subscriptions);
+        // The networks are dropped after the borrow ends, as dropping them may trigger other
+        // requests.
+        let removed = ids.iter().filter_map(|id| self.model.subscriptions.borrow_mut().remove(id));
+        drop(removed.collect_vec());
+    }
+}
+
+
+
+// ===========================
+// === JavaScript Bindings ===
+// ===========================
+
+#[cfg(target_arch = "wasm32")]
+mod js {
+    use super::*;
+    use wasm_bindgen::prelude::*;
+
+    #[wasm_bindgen(inline_js = "
+        export function create_scripting_api(
+            labels, views, create, dispose, endpoints, set, get, subscribe, unsubscribe
+        ) {
+            return {
+                labels: () => JSON.parse(labels()),
+                views: () => JSON.parse(views()),
+                create,
+                dispose,
+                endpoints: (view) => JSON.parse(endpoints(view)),
+                set: (view, endpoint, value) =>
+                    set(view, endpoint, JSON.stringify(value === undefined ? null : value)),
+                get: (view, endpoint) => JSON.parse(get(view, endpoint)),
+                subscribe: (view, endpoint, callback) =>
+                    subscribe(view, endpoint, (value) => callback(JSON.parse(value))),
+                unsubscribe,
+            }
+        }
+    ")]
+    extern "C" {
+        #[allow(unsafe_code)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn create_scripting_api(
+            labels: &Labels,
+            views: &Views,
+            create: &Create,
+            dispose: &Dispose,
+            endpoints: &Endpoints,
+            set: &Set,
+            get: &Get,
+            subscribe: &Subscribe,
+            unsubscribe: &Unsubscribe,
+        ) -> JsValue;
+    }
+
+    pub type Labels = Closure<dyn Fn() -> String>;
+    pub type Views = Closure<dyn Fn() -> String>;
+    pub type Create = Closure<dyn Fn(String) -> Result<String, JsValue>>;
+    pub type Dispose = Closure<dyn Fn(String) -> bool>;
+    pub type Endpoints = Closure<dyn Fn(String) -> Result<String, JsValue>>;
+    pub type Set = Closure<dyn Fn(String, String, String) -> Result<(), JsValue>>;
+    pub type Get = Closure<dyn Fn(String, String) -> Result<String, JsValue>>;
+    pub type Subscribe =
+        Closure<dyn Fn(String, String, js_sys::Function) -> Result<usize, JsValue>>;
+    pub type Unsubscribe = Closure<dyn Fn(usize) -> bool>;
+}
+
+#[cfg(target_arch = "wasm32")]
+fn to_js_error(error: Error) -> wasm_bindgen::JsValue {
+    js_sys::Error::new(&error.to_string()).into()
+}
+
+/// Expose the `window.ensoglScripting` object, which can be used to drive the views of the
+/// application from JavaScript. See the module docs to learn more.
+#[cfg(target_arch = "wasm32")]
+pub fn expose_to_window(app: &Application) {
+    use crate::system::web;
+    use wasm_bindgen::prelude::Closure;
+    use wasm_bindgen::JsValue;
+
+    // The closures live for the rest of the page life, so they must not keep the application alive.
+    let weak_app = Rc::downgrade(&app.inner);
+    let bridge = &app.scripting;
+    let registry = &app.views;
+
+    let labels: js::Labels =
+        Closure::new(f_!([registry] serde_json::to_string(&registry.labels()).unwrap_or_default()));
+    let views: js::Views =
+        Closure::new(f_!([bridge] serde_json::to_string(&bridge.view_names()).unwrap_or_default()));
+    let created_by = bridge.clone_ref();
+    let create: js::Create = Closure::new(move |label: String| {
+        let inner = weak_app.upgrade().ok_or_else(|| JsValue::from("The app was dropped."))?;
+        created_by.create(&Application { inner }, &label).map_err(to_js_error)
+    });
+    let dispose: js::Dispose = Closure::new(f!([bridge](view: String) bridge.dispose(&view)));
+    let endpoints: js::Endpoints = Closure::new(f!([bridge](view: String) {
+        let endpoints = bridge.endpoints(&view).map_err(to_js_error)?;
+        Ok(serde_json::to_string(&endpoints).unwrap_or_default())
+    }));
+    let set: js::Set = Closure::new(f!([bridge](view: String, endpoint: String, value: String) {
+        bridge.set(&view, &endpoint, &value).map_err(to_js_error)
+    }));
+    let get: js::Get = Closure::new(f!([bridge](view: String, endpoint: String) {
+        bridge.get(&view, &endpoint).map_err(to_js_error)
+    }));
+    let subscribe: js::Subscribe =
+        Closure::new(f!([bridge](view: String, endpoint: String, callback: js_sys::Function) {
+            let callback = move |value: String| {
+                if let Err(error) = callback.call1(&JsValue::NULL, &value.into()) {
+                    error!("Script subscription callback failed: {error:?}.");
+                }
+            };
+            bridge.subscribe(&view, &endpoint, callback).map_err(to_js_error)
+        }));
+    let unsubscribe: js::Unsubscribe = Closure::new(f!([bridge](id) bridge.unsubscribe(id)));
+
+    let api = js::create_scripting_api(
+        &labels,
+        &views,
+        &create,
+        &dispose,
+        &endpoints,
+        &set,
+        &get,
+        &subscribe,
+        &unsubscribe,
+    );
+    js_sys::Reflect::set(&web::window, &"ensoglScripting".into(), &api).ok();
+
+    // The closures are owned by the `window.ensoglScripting` object for the rest of the page life.
+    mem::forget(labels);
+    mem::forget(views);
+    mem::forget(create);
+    mem::forget(dispose);
+    mem::forget(endpoints);
+    mem::forget(set);
+    mem::forget(get);
+    mem::forget(subscribe);
+    mem::forget(unsubscribe);
+}
+
+/// Stub of `expose_to_window` for non-web targets.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn expose_to_window(app: &Application) {
+    let _ = app;
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::application::undo;
+    use crate::application::View;
+
+    #[test]
+    fn driving_views_through_the_bridge() {
+        let app = Application::new("root");
+        let bridge = &app.scripting;
+        let label = <undo::Manager as View>::label();
+        assert!(matches!(bridge.create(&app, "Unknown"), Err(Error::UnknownLabel(_))));
+        let view = bridge.create(&app, label).unwrap();
+        assert_eq!(bridge.view_names(), vec![view.clone()]);
+
+        let endpoints = bridge.endpoints(&view).unwrap();
+        let undone = endpoints.iter().find(|endpoint| endpoint.name == "undone").unwrap();
+        assert_eq!(undone.kind, "output");
+        assert_eq!(undone.doc, "The name of the reverted entry.");
+
+        let values: Rc<RefCell<Vec<String>>> = default();
+        let callback = f!([values](value: String) values.borrow_mut().push(value));
+        let subscription = bridge.subscribe(&view, "can_undo", callback).unwrap();
+        app.undo.record("edit", || {}, || {});
+        assert_eq!(bridge.get(&view, "can_undo").unwrap(), "true");
+        bridge.set(&view, "undo", "").unwrap();
+        assert_eq!(bridge.get(&view, "undone").unwrap(), "\"edit\"");
+        assert_eq!(values.borrow().last().map(|value| value.as_str()), Some("false"));
+        assert!(values.borrow().iter().any(|value| value == "true"));
+        assert!(bridge.unsubscribe(subscription));
+        assert!(!bridge.unsubscribe(subscription));
+
+        assert!(matches!(bridge.set(&view, "can_undo", "true"), Err(Error::NotAnInput(_))));
+        assert!(matches!(bridge.get(&view, "undo"), Err(Error::NotAnOutput(_))));
+        assert!(matches!(bridge.set(&view, "undo", "[1]"), Err(Error::InvalidValue { .. })));
+        assert!(matches!(bridge.get(&view, "unknown"), Err(Error::UnknownEndpoint { .. })));
+        assert!(bridge.dispose(&view));
+        assert!(matches!(bridge.endpoints(&view), Err(Error::UnknownView(_))));
+    }
+}
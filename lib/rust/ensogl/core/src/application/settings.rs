@@ -0,0 +1,186 @@
+//! An application-wide store of user settings, like panel sizes or layouts, which should survive
+//! reloading the application. Values are serialized to JSON and kept under string keys in a
+//! [`Backend`]. In the browser, the [`LocalStorage`] backend is used by default. Elsewhere, the
+//! settings are kept in memory only.
+
+use crate::prelude::*;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+
+
+// ===============
+// === Backend ===
+// ===============
+
+/// A storage of serialized settings.
+pub trait Backend: Debug {
+    /// Read the value stored under the key.
+    fn load(&self, key: &str) -> Option<String>;
+    /// Store the value under the key, replacing the previous one.
+    fn save(&self, key: &str, value: &str);
+    /// Remove the value stored under the key.
+    fn remove(&self, key: &str);
+}
+
+
+// === Memory ===
+
+/// A [`Backend`] keeping the settings in memory.
+#[derive(Debug, Default)]
+pub struct Memory {
+    values: RefCell<HashMap<String, String>>,
+}
+
+impl Backend for Memory {
+    fn load(&self, key: &str) -> Option<String> {
+        self.values.borrow().get(key).cloned()
+    }
+
+    fn save(&self, key: &str, value: &str) {
+        self.values.borrow_mut().insert(key.to_owned(), value.to_owned());
+    }
+
+    fn remove(&self, key: &str) {
+        self.values.borrow_mut().remove(key);
+    }
+}
+
+
+// === LocalStorage ===
+
+/// A [`Backend`] keeping the settings in the browser local storage. All keys are prefixed to avoid
+/// conflicts with other users of the storage.
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug)]
+pub struct LocalStorage {
+    storage: web_sys::Storage,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    const KEY_PREFIX: &'static str = "ensogl.settings.";
+
+    /// Constructor. Returns [`None`] if the local storage is not available, for example due to
+    /// browser privacy settings.
+    pub fn new() -> Option<Self> {
+        let storage = web_sys::window()?.local_storage().ok()??;
+        Some(Self { storage })
+    }
+
+    fn key(key: &str) -> String {
+        format!("{}{key}", Self::KEY_PREFIX)
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Backend for LocalStorage {
+    fn load(&self, key: &str) -> Option<String> {
+        self.storage.get_item(&Self::key(key)).ok()?
+    }
+
+    fn save(&self, key: &str, value: &str) {
+        if self.storage.set_item(&Self::key(key), value).is_err() {
+            warn!("Cannot save the setting {key:?} in the local storage.");
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = self.storage.remove_item(&Self::key(key));
+    }
+}
+
+
+
+// =============
+// === Store ===
+// =============
+
+/// The store of user settings. See the [module documentation](self) for details.
+#[derive(Clone, CloneRef, Debug)]
+pub struct Store {
+    backend: Rc<RefCell<Rc<dyn Backend>>>,
+}
+
+impl Default for Store {
+    fn default() -> Self {
+        #[cfg(target_arch = "wasm32")]
+        if let Some(local_storage) = LocalStorage::new() {
+            return Self::new_with_backend(local_storage);
+        }
+        Self::new_with_backend(Memory::default())
+    }
+}
+
+impl Store {
+    /// Constructor. Uses the default backend for the current platform.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Constructor.
+    pub fn new_with_backend(backend: impl Backend + 'static) -> Self {
+        let backend: Rc<dyn Backend> = Rc::new(backend);
+        Self { backend: Rc::new(RefCell::new(backend)) }
+    }
+
+    /// Replace the backend. The settings stored in the previous backend are not moved.
+    pub fn set_backend(&self, backend: impl Backend + 'static) {
+        *self.backend.borrow_mut() = Rc::new(backend);
+    }
+
+    fn backend(&self) -> Rc<dyn Backend> {
+        self.backend.borrow().clone()
+    }
+
+    /// Read the setting. Returns [`None`] if it was not stored, or cannot be deserialized to the
+    /// requested type.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let serialized = self.backend().load(key)?;
+        match serde_json::from_str(&serialized) {
+            Ok(value) => Some(value),
+            Err(err) => {
+                warn!("Ignoring the malformed setting {key:?}: {err}");
+                None
+            }
+        }
+    }
+
+    /// Store the setting, replacing the previous value.
+    pub fn set<T: Serialize>(&self, key: &str, value: &T) {
+        match serde_json::to_string(value) {
+            Ok(serialized) => self.backend().save(key, &serialized),
+            Err(err) => error!("Cannot serialize the setting {key:?}: {err}"),
+        }
+    }
+
+    /// Remove the setting.
+    pub fn remove(&self, key: &str) {
+        self.backend().remove(key)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip() {
+        let store = Store::new_with_backend(Memory::default());
+        assert_eq!(store.get::<f32>("panel.width"), None);
+        store.set("panel.width", &240.0_f32);
+        store.set("panel.collapsed", &true);
+        assert_eq!(store.get::<f32>("panel.width"), Some(240.0));
+        assert_eq!(store.get::<bool>("panel.collapsed"), Some(true));
+        assert_eq!(store.get::<Vec<u32>>("panel.width"), None);
+        store.remove("panel.width");
+        assert_eq!(store.get::<f32>("panel.width"), None);
+    }
+}
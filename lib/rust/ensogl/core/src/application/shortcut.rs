@@ -5,6 +5,9 @@ use enso_shortcuts::traits::*;
 
 use crate::frp;
 use crate::frp::io::mouse::Mouse_DEPRECATED;
+use std::ops::BitAnd;
+use std::ops::BitOr;
+use std::ops::Not;
 
 use super::command;
 
@@ -15,6 +18,8 @@ use enso_shortcuts as shortcuts;
 // === Export ===
 // ==============
 
+pub mod help;
+
 pub use shortcuts::ActionType;
 
 
@@ -94,36 +99,87 @@ impl Condition {
         Self::Or(Box::new(a), Box::new(b))
     }
 
-    /// Split the input on the provided `separator`, process each chunk with `f`, and fold results
-    /// using the `cons`.
-    fn split_parse(
-        input: &str,
-        separator: char,
-        cons: impl Fn(Self, Self) -> Self,
-        f: impl Fn(&str) -> Self,
-    ) -> Self {
-        input.split(separator).map(|t| t.trim()).map(f).reduce(cons).unwrap_or(Self::Never)
-    }
-
     /// Parses the provided input expression. The currently recognizable symbols are (sorted by
-    /// precedence - high to low): negations(!), conjunctions (&), alternatives (|), and variables.
-    /// For example, it parses the following expression: "a & b | !c". Parentheses are not supported
-    /// yet.
-    #[allow(clippy::redundant_closure)] // Rust TC does not agree.
+    /// precedence - high to low): parentheses, negations (!), conjunctions (&), alternatives (|),
+    /// and variables. The `always` and `never` variables denote the [`Condition::Always`] and
+    /// [`Condition::Never`] conditions. For example, it parses the following expression:
+    /// "a & (b | !c)". An empty expression is parsed as [`Condition::Always`].
     fn parse(s: impl AsRef<str>) -> Self {
         let s = s.as_ref().trim();
         if s.is_empty() {
             Self::Always
         } else {
-            Self::split_parse(s, '|', Self::or, |s| {
-                Self::split_parse(s, '&', Self::and, |s| {
-                    if let Some(expr) = s.strip_prefix('!') {
-                        Self::not(Self::when(expr.trim()))
-                    } else {
-                        Self::when(s)
-                    }
-                })
-            })
+            ConditionParser { rest: s }.alternative()
+        }
+    }
+
+    /// The precedence of the top-level operator of the condition. Operators with higher
+    /// precedence bind tighter.
+    fn precedence(&self) -> usize {
+        match self {
+            Self::Or(..) => 0,
+            Self::And(..) => 1,
+            _ => 2,
+        }
+    }
+}
+
+
+// === ConditionParser ===
+
+/// A recursive descent parser of [`Condition`] expressions. See [`Condition::parse`].
+#[derive(Debug)]
+struct ConditionParser<'a> {
+    rest: &'a str,
+}
+
+impl<'a> ConditionParser<'a> {
+    /// Consume the symbol if the unparsed input starts with it.
+    fn consume(&mut self, symbol: char) -> bool {
+        self.rest = self.rest.trim_start();
+        let rest = self.rest.strip_prefix(symbol);
+        rest.map(|rest| self.rest = rest).is_some()
+    }
+
+    fn alternative(&mut self) -> Condition {
+        let mut condition = self.conjunction();
+        while self.consume('|') {
+            condition = Condition::or(condition, self.conjunction());
+        }
+        condition
+    }
+
+    fn conjunction(&mut self) -> Condition {
+        let mut condition = self.negation();
+        while self.consume('&') {
+            condition = Condition::and(condition, self.negation());
+        }
+        condition
+    }
+
+    fn negation(&mut self) -> Condition {
+        if self.consume('!') {
+            Condition::not(self.negation())
+        } else {
+            self.atom()
+        }
+    }
+
+    fn atom(&mut self) -> Condition {
+        if self.consume('(') {
+            let condition = self.alternative();
+            self.consume(')');
+            condition
+        } else {
+            let is_symbol = |c: char| matches!(c, '|' | '&' | '!' | '(' | ')');
+            let end = self.rest.find(is_symbol).unwrap_or(self.rest.len());
+            let (name, rest) = self.rest.split_at(end);
+            self.rest = rest;
+            match name.trim() {
+                "always" => Condition::Always,
+                "never" => Condition::Never,
+                name => Condition::when(name),
+            }
         }
     }
 }
@@ -134,6 +190,175 @@ impl From<&str> for Condition {
     }
 }
 
+impl Condition {
+    /// Names of all context flags used in the condition, in the order of appearance.
+    pub fn flag_names(&self) -> Vec<&str> {
+        match self {
+            Self::Always | Self::Never => default(),
+            Self::When(name) => vec![name.as_str()],
+            Self::Not(a) => a.flag_names(),
+            Self::Or(a, b) | Self::And(a, b) => {
+                let mut names = a.flag_names();
+                names.extend(b.flag_names());
+                names
+            }
+        }
+    }
+}
+
+impl Not for Condition {
+    type Output = Condition;
+    fn not(self) -> Condition {
+        Condition::not(self)
+    }
+}
+
+impl<T: Into<Condition>> BitAnd<T> for Condition {
+    type Output = Condition;
+    fn bitand(self, rhs: T) -> Condition {
+        Condition::and(self, rhs.into())
+    }
+}
+
+impl<T: Into<Condition>> BitOr<T> for Condition {
+    type Output = Condition;
+    fn bitor(self, rhs: T) -> Condition {
+        Condition::or(self, rhs.into())
+    }
+}
+
+/// Displays the condition in the format accepted by the parser, so that parsing the displayed
+/// condition gives the same condition. The operands are parenthesized where the precedence of the
+/// operators requires it. The operators are left-associative, so the right operand of an operator
+/// is parenthesized if it has the same precedence.
+impl Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operand = |f: &mut fmt::Formatter, operand: &Condition, min_precedence: usize| {
+            if operand.precedence() < min_precedence {
+                write!(f, "({operand})")
+            } else {
+                write!(f, "{operand}")
+            }
+        };
+        let precedence = self.precedence();
+        match self {
+            Self::Always => write!(f, "always"),
+            Self::Never => write!(f, "never"),
+            Self::When(name) => write!(f, "{name}"),
+            Self::Not(a) => {
+                write!(f, "!")?;
+                operand(f, a, precedence)
+            }
+            Self::Or(a, b) | Self::And(a, b) => {
+                let symbol = if matches!(self, Self::Or(..)) { "|" } else { "&" };
+                operand(f, a, precedence)?;
+                write!(f, " {symbol} ")?;
+                operand(f, b, precedence + 1)
+            }
+        }
+    }
+}
+
+
+
+// ===================
+// === ContextFlag ===
+// ===================
+
+/// A typed name of a boolean status of a component, which can be used in shortcut conditions. The
+/// name has to match one of the `bool` outputs of the component FRP API. The flags are defined
+/// with the [`define_context_flags`] macro, so the flag names are checked at compile time.
+///
+/// Flags can be combined into [`Condition`]s with the `!`, `&`, and `|` operators.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct ContextFlag {
+    name: &'static str,
+}
+
+impl ContextFlag {
+    /// Constructor. Consider using the [`define_context_flags`] macro instead.
+    pub const fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+
+    /// The name of the flag, as used in string conditions.
+    pub const fn name(self) -> &'static str {
+        self.name
+    }
+}
+
+impl Display for ContextFlag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl From<ContextFlag> for Condition {
+    fn from(flag: ContextFlag) -> Self {
+        Condition::when(flag.name)
+    }
+}
+
+impl Not for ContextFlag {
+    type Output = Condition;
+    fn not(self) -> Condition {
+        !Condition::from(self)
+    }
+}
+
+impl<T: Into<Condition>> BitAnd<T> for ContextFlag {
+    type Output = Condition;
+    fn bitand(self, rhs: T) -> Condition {
+        Condition::from(self) & rhs.into()
+    }
+}
+
+impl<T: Into<Condition>> BitOr<T> for ContextFlag {
+    type Output = Condition;
+    fn bitor(self, rhs: T) -> Condition {
+        Condition::from(self) | rhs.into()
+    }
+}
+
+/// Define a module of [`ContextFlag`]s. Each flag is a constant named after the flag. The module
+/// also contains the `ALL` constant, listing all the defined flags for runtime introspection. For
+/// example, the following code:
+///
+/// ```text
+/// define_context_flags! {
+///     /// Graph editor flags.
+///     pub mod flag {
+///         /// A node is being edited.
+///         node_editing,
+///         read_only,
+///     }
+/// }
+/// ```
+///
+/// allows writing conditions like `!flag::node_editing & !flag::read_only`.
+#[macro_export]
+macro_rules! define_context_flags {
+    (
+        $(#[$meta:meta])*
+        $vis:vis mod $module:ident {
+            $($(#[$flag_meta:meta])* $flag:ident),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[allow(non_upper_case_globals)]
+        $vis mod $module {
+            $(
+                $(#[$flag_meta])*
+                pub const $flag: $crate::application::shortcut::ContextFlag =
+                    $crate::application::shortcut::ContextFlag::new(stringify!($flag));
+            )*
+
+            /// All the flags defined in this module.
+            pub const ALL: &[$crate::application::shortcut::ContextFlag] = &[$($flag),*];
+        }
+    };
+}
+
 
 
 // ==============
@@ -206,6 +431,26 @@ impl Shortcut {
         let rule = rule.into();
         Self { action, rule }
     }
+
+    /// The rule triggering the shortcut.
+    pub fn rule(&self) -> &Rule {
+        &self.rule
+    }
+
+    /// The label of the target component, like "TextEditor".
+    pub fn target(&self) -> &str {
+        &self.action.target
+    }
+
+    /// The name of the command evaluated in the target component.
+    pub fn command(&self) -> &str {
+        &self.action.command.name
+    }
+
+    /// The condition which needs to be true in order for the command to be executed.
+    pub fn condition(&self) -> &Condition {
+        &self.action.condition
+    }
 }
 
 
@@ -239,6 +484,9 @@ pub struct RegistryModel {
     mouse:              Mouse_DEPRECATED,
     command_registry:   command::Registry,
     shortcuts_registry: shortcuts::HashSetRegistry<Shortcut>,
+    /// All shortcuts added to this registry, in the order of addition. Used to display the
+    /// shortcuts overview, see [`help::ShortcutHelp`].
+    definitions:        Rc<RefCell<Vec<Shortcut>>>,
     currently_handled:  frp::Source<Option<ImString>>,
     /// If present, this is the receiver of commands.
     target:             Option<frp::NetworkId>,
@@ -315,7 +563,13 @@ impl RegistryModel {
         let mouse = mouse.clone_ref();
         let command_registry = command_registry.clone_ref();
         let shortcuts_registry = default();
-        Self { mouse, command_registry, shortcuts_registry, currently_handled, target }
+        let definitions = default();
+        Self { mouse, command_registry, shortcuts_registry, definitions, currently_handled, target }
+    }
+
+    /// All shortcuts added to this registry, in the order of addition.
+    pub fn definitions(&self) -> Vec<Shortcut> {
+        self.definitions.borrow().clone()
     }
 
     fn process_rules(&self, stop_propagation: impl FnOnce<()>, rules: &[Shortcut]) {
@@ -385,5 +639,46 @@ impl Add<Shortcut> for &RegistryModel {
     type Output = ();
     fn add(self, shortcut: Shortcut) {
         self.shortcuts_registry.add(shortcut.rule.tp, &shortcut.rule.pattern, shortcut.clone());
+        self.definitions.borrow_mut().push(shortcut);
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn displayed_condition_is_parsed_back() {
+        let a = || Condition::when("a");
+        let b = || Condition::when("b");
+        let c = || Condition::when("c");
+        let conditions = [
+            Condition::Always,
+            Condition::Never,
+            a(),
+            !a(),
+            !!a(),
+            a() & b() | c(),
+            a() & (b() | c()),
+            (a() | b()) & c(),
+            a() | (b() | c()),
+            a() & (b() & c()),
+            !(a() | b()) & !(a() & b()),
+            Condition::Always & !Condition::Never | a(),
+        ];
+        for condition in conditions {
+            let displayed = condition.to_string();
+            assert_eq!(Condition::parse(&displayed), condition, "{displayed}");
+        }
+        assert_eq!((a() & (b() | c())).to_string(), "a & (b | c)");
+        assert_eq!((a() & b() | c()).to_string(), "a & b | c");
+        assert_eq!(Condition::parse(""), Condition::Always);
+        assert_eq!(Condition::parse(" a & !b | c "), a() & !b() | c());
     }
 }
@@ -0,0 +1,346 @@
+//! A searchable overlay listing all keyboard shortcuts registered in the application, grouped by
+//! the component they target. It is toggled with the `cmd /` shortcut. While it is visible, typed
+//! characters filter the displayed shortcuts.
+
+use crate::prelude::*;
+use enso_web::traits::*;
+
+use crate::application::command::FrpNetworkProvider;
+use crate::application::shortcut;
+use crate::application::shortcut::Shortcut;
+use crate::application::Application;
+use crate::control::io::keyboard::KeyDown;
+use crate::display;
+use crate::frp;
+use crate::frp::io::keyboard::Key;
+use crate::system::web;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+const FONTS: &str = "\"SF Mono\", Menlo, Monaco, Consolas, \"Liberation Mono\", monospace";
+const BACKGROUND_COLOR: &str = "#FFFFFFF0";
+const TEXT_COLOR: &str = "#000000D0";
+const DIMMED_TEXT_COLOR: &str = "#00000080";
+const WIDTH: f32 = 640.0;
+const MAX_HEIGHT_PERCENT: f32 = 80.0;
+const SEARCH_PLACEHOLDER: &str = "Type to search…";
+
+
+
+// =============
+// === Entry ===
+// =============
+
+/// A single shortcut displayed in the overlay.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[allow(missing_docs)]
+pub struct Entry {
+    pub pattern:   String,
+    pub command:   String,
+    pub action:    String,
+    pub condition: String,
+}
+
+impl Entry {
+    /// Constructor.
+    pub fn new(shortcut: &Shortcut) -> Self {
+        let pattern = shortcut.rule().pattern.clone();
+        let command = shortcut.command().to_string();
+        let action = match shortcut.rule().tp {
+            shortcut::ActionType::Press => default(),
+            other => format!("{other:?}"),
+        };
+        let condition = match shortcut.condition() {
+            shortcut::Condition::Always => default(),
+            condition => condition.to_string(),
+        };
+        Self { pattern, command, action, condition }
+    }
+
+    /// Check whether the entry of the given component matches the search query. The query is
+    /// matched case-insensitively against the component name, the pattern, and the command.
+    pub fn matches(&self, component: &str, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        let fields = [component, &self.pattern, &self.command];
+        query.is_empty() || fields.iter().any(|t| t.to_lowercase().contains(&query))
+    }
+}
+
+/// Group the shortcuts by the component they target, keeping only the ones matching the query.
+/// Entries of a component are sorted by the pattern, and duplicates are removed.
+pub fn group_entries(shortcuts: &[Shortcut], query: &str) -> BTreeMap<String, Vec<Entry>> {
+    let mut groups: BTreeMap<String, Vec<Entry>> = default();
+    for shortcut in shortcuts {
+        let entry = Entry::new(shortcut);
+        if entry.matches(shortcut.target(), query) {
+            groups.entry(shortcut.target().to_string()).or_default().push(entry);
+        }
+    }
+    for entries in groups.values_mut() {
+        entries.sort();
+        entries.dedup();
+    }
+    groups
+}
+
+
+
+// ===========
+// === Frp ===
+// ===========
+
+crate::define_endpoints_2! {
+    Input {
+        show(),
+        hide(),
+        toggle(),
+        set_query(String),
+    }
+    Output {
+        visible(bool),
+        query(String),
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+/// Internal representation of [`ShortcutHelp`].
+#[derive(Clone, CloneRef, Debug, Deref, display::Object)]
+pub struct Model {
+    rc: Rc<ModelData>,
+}
+
+/// Internal representation of [`ShortcutHelp`].
+#[derive(Debug, display::Object)]
+pub struct ModelData {
+    display_object: display::object::Instance,
+    shortcuts:      shortcut::Registry,
+    root:           web::HtmlDivElement,
+    search:         web::HtmlDivElement,
+    list:           web::HtmlDivElement,
+}
+
+impl Model {
+    fn new(shortcuts: &shortcut::Registry) -> Self {
+        let display_object = display::object::Instance::new_named("ShortcutHelp");
+        let shortcuts = shortcuts.clone_ref();
+        let root = web::document.create_div_or_panic();
+        root.set_style_or_warn("display", "none");
+        root.set_style_or_warn("position", "absolute");
+        root.set_style_or_warn("z-index", "100");
+        root.set_style_or_warn("left", "50%");
+        root.set_style_or_warn("top", "50%");
+        root.set_style_or_warn("transform", "translate(-50%, -50%)");
+        root.set_style_or_warn("width", format!("{WIDTH}px"));
+        root.set_style_or_warn("max-height", format!("{MAX_HEIGHT_PERCENT}%"));
+        root.set_style_or_warn("overflow", "auto");
+        root.set_style_or_warn("padding", "12px 16px");
+        root.set_style_or_warn("border-radius", "8px");
+        root.set_style_or_warn("background", BACKGROUND_COLOR);
+        root.set_style_or_warn("color", TEXT_COLOR);
+        root.set_style_or_warn("font-family", FONTS);
+        root.set_style_or_warn("font-size", "12px");
+        root.set_style_or_warn("pointer-events", "all");
+        let search = web::document.create_div_or_panic();
+        search.set_style_or_warn("padding-bottom", "8px");
+        search.set_style_or_warn("font-size", "14px");
+        root.append_or_warn(&search);
+        let list = web::document.create_div_or_panic();
+        root.append_or_warn(&list);
+        web::document.body_or_panic().append_or_warn(&root);
+        let data = ModelData { display_object, shortcuts, root, search, list };
+        Self { rc: Rc::new(data) }
+    }
+
+    fn set_visible(&self, visible: bool) {
+        let display = if visible { "block" } else { "none" };
+        self.root.set_style_or_warn("display", display);
+        if visible {
+            self.display_object.focus();
+        } else {
+            self.display_object.blur();
+        }
+    }
+
+    /// Rebuild the displayed list from the current content of the shortcut registry.
+    fn refresh(&self, query: &str) {
+        if query.is_empty() {
+            self.search.set_inner_text(SEARCH_PLACEHOLDER);
+            self.search.set_style_or_warn("color", DIMMED_TEXT_COLOR);
+        } else {
+            self.search.set_inner_text(query);
+            self.search.set_style_or_warn("color", TEXT_COLOR);
+        }
+        self.list.set_inner_html("");
+        for (component, entries) in group_entries(&self.shortcuts.definitions(), query) {
+            let header = web::document.create_div_or_panic();
+            header.set_inner_text(&component);
+            header.set_style_or_warn("font-weight", "bold");
+            header.set_style_or_warn("padding", "8px 0px 4px 0px");
+            self.list.append_or_warn(&header);
+            for entry in entries {
+                self.list.append_or_warn(&Self::row(&entry));
+            }
+        }
+    }
+
+    fn row(entry: &Entry) -> web::HtmlDivElement {
+        let row = web::document.create_div_or_panic();
+        row.set_style_or_warn("display", "flex");
+        let command = if entry.action.is_empty() {
+            entry.command.clone()
+        } else {
+            format!("{} ({})", entry.command, entry.action)
+        };
+        let cells = [(&entry.pattern, "30%"), (&command, "35%"), (&entry.condition, "35%")];
+        for (text, width) in cells {
+            let cell = web::document.create_div_or_panic();
+            cell.set_inner_text(text);
+            cell.set_style_or_warn("width", width);
+            row.append_or_warn(&cell);
+        }
+        row
+    }
+}
+
+impl Drop for ModelData {
+    fn drop(&mut self) {
+        self.root.remove_from_parent_or_warn();
+    }
+}
+
+
+
+// ====================
+// === ShortcutHelp ===
+// ====================
+
+/// A searchable overlay listing all keyboard shortcuts registered in the application. See the
+/// module docs to learn more.
+#[derive(Clone, CloneRef, Debug, Deref, display::Object)]
+#[allow(missing_docs)]
+pub struct ShortcutHelp {
+    #[deref]
+    pub frp:   Frp,
+    #[display_object]
+    pub model: Model,
+}
+
+impl ShortcutHelp {
+    /// Constructor. The overlay lists the shortcuts of the provided registry.
+    pub fn new(shortcuts: &shortcut::Registry) -> Self {
+        let frp = Frp::new();
+        let model = Model::new(shortcuts);
+        Self { frp, model }.init()
+    }
+
+    fn init(self) -> Self {
+        let network = self.frp.network();
+        let input = &self.frp.private.input;
+        let out = &self.frp.private.output;
+        let model = &self.model;
+        let key_down = model.display_object.on_event::<KeyDown>();
+
+        frp::extend! { network
+            show <- any_(&input.show, &input.toggle.gate_not(&out.visible));
+            hide <- any_(&input.hide, &input.toggle.gate(&out.visible));
+            out.visible <+ bool(&hide, &show);
+            visible <- out.visible.on_change();
+            eval visible ((t) model.set_visible(*t));
+
+            query_on_key <= key_down.map2(&out.query, |event, query| {
+                let query = match event.key() {
+                    Key::Character(c) => Some(format!("{query}{c}")),
+                    Key::Space => Some(format!("{query} ")),
+                    Key::Backspace => {
+                        let mut query = query.clone();
+                        query.pop();
+                        Some(query)
+                    }
+                    _ => None,
+                };
+                if query.is_some() {
+                    event.stop_propagation();
+                }
+                query
+            });
+            query_on_show <- show.constant(String::new());
+            out.query <+ any(&input.set_query, &query_on_key, &query_on_show);
+            refresh <- out.query.gate(&out.visible);
+            eval refresh ((query) model.refresh(query));
+        }
+        self
+    }
+}
+
+impl FrpNetworkProvider for ShortcutHelp {
+    fn network(&self) -> &frp::Network {
+        self.frp.network()
+    }
+}
+
+impl crate::application::View for ShortcutHelp {
+    fn label() -> &'static str {
+        "ShortcutHelp"
+    }
+
+    fn new(app: &Application) -> Self {
+        ShortcutHelp::new(&app.shortcuts)
+    }
+
+    fn global_shortcuts() -> Vec<Shortcut> {
+        use shortcut::ActionType::*;
+        vec![
+            Self::self_shortcut(Press, "cmd /", "toggle"),
+            Self::self_shortcut_when(Press, "escape", "hide", "visible"),
+        ]
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use shortcut::ActionType::*;
+
+    fn shortcut(target: &str, pattern: &str, command: &str, condition: &str) -> Shortcut {
+        Shortcut::new_when(shortcut::Rule::new(Press, pattern), target, command, condition)
+    }
+
+    #[test]
+    fn entries_are_grouped_and_filtered() {
+        let shortcuts = [
+            shortcut("TextEditor", "cmd z", "undo", "focused"),
+            shortcut("TextEditor", "cmd c", "copy", "focused & !read_only"),
+            shortcut("TextEditor", "cmd c", "copy", "focused & !read_only"),
+            shortcut("GraphEditor", "cmd g", "collapse_selected_nodes", ""),
+        ];
+        let groups = group_entries(&shortcuts, "");
+        assert_eq!(groups.keys().collect_vec(), ["GraphEditor", "TextEditor"]);
+        let text_editor = &groups["TextEditor"];
+        assert_eq!(text_editor.iter().map(|e| e.pattern.as_str()).collect_vec(), [
+            "cmd c", "cmd z"
+        ]);
+        assert_eq!(text_editor[0].condition, "focused & !read_only");
+        assert_eq!(groups["GraphEditor"][0].condition, "");
+
+        let groups = group_entries(&shortcuts, " UNDO ");
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups["TextEditor"].len(), 1);
+        assert_eq!(group_entries(&shortcuts, "graph").keys().collect_vec(), ["GraphEditor"]);
+    }
+}
@@ -0,0 +1,357 @@
+//! An application-wide undo and redo history. Components record their undoable operations in the
+//! [`Manager`] instead of handling the `cmd z` shortcut on their own, so that a single undo stream
+//! reverts the most recent user action, regardless of which component performed it.
+
+use crate::prelude::*;
+
+use crate::application::command::FrpNetworkProvider;
+use crate::application::shortcut;
+use crate::application::shortcut::Shortcut;
+use crate::application::Application;
+use crate::display;
+use crate::frp;
+
+
+
+// =================
+// === Operation ===
+// =================
+
+/// A single undoable operation, defined by functions reverting and reapplying it. The functions
+/// should not keep the component they modify alive, as they live as long as the history.
+#[derive(Clone, CloneRef, Derivative)]
+#[derivative(Debug)]
+struct Operation {
+    #[derivative(Debug = "ignore")]
+    undo: Rc<dyn Fn()>,
+    #[derivative(Debug = "ignore")]
+    redo: Rc<dyn Fn()>,
+}
+
+
+
+// =============
+// === Entry ===
+// =============
+
+/// A single step of the history, consisting of one or more operations recorded together. The
+/// operations are reverted in the reverse order of recording.
+#[derive(Clone, Debug, Default)]
+struct Entry {
+    name:       ImString,
+    operations: Vec<Operation>,
+}
+
+impl Entry {
+    fn undo(&self) {
+        self.operations.iter().rev().for_each(|op| (op.undo)());
+    }
+
+    fn redo(&self) {
+        self.operations.iter().for_each(|op| (op.redo)());
+    }
+}
+
+
+
+// ===========
+// === Frp ===
+// ===========
+
+crate::define_endpoints_2! {
+    Input {
+        /// Revert the most recent entry of the history.
+        undo(),
+        /// Reapply the most recently reverted entry.
+        redo(),
+        /// Forget the whole history.
+        clear(),
+    }
+    Output {
+        can_undo(bool),
+        can_redo(bool),
+        /// The name of the reverted entry.
+        undone(ImString),
+        /// The name of the reapplied entry.
+        redone(ImString),
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+#[derive(Debug, Default, display::Object)]
+struct Model {
+    display_object:    display::object::Instance,
+    undo_stack:        RefCell<Vec<Entry>>,
+    redo_stack:        RefCell<Vec<Entry>>,
+    /// The entry being built by the currently open transactions, with their nesting depth.
+    transaction:       RefCell<Option<Entry>>,
+    transaction_depth: Cell<usize>,
+    /// Set while the history is being replayed. Operations recorded by the undo and redo
+    /// functions are ignored.
+    replaying:         Cell<bool>,
+}
+
+impl Model {
+    fn record(&self, name: ImString, operation: Operation) -> bool {
+        if self.replaying.get() {
+            false
+        } else if let Some(transaction) = &mut *self.transaction.borrow_mut() {
+            transaction.operations.push(operation);
+            false
+        } else {
+            let operations = vec![operation];
+            self.push(Entry { name, operations });
+            true
+        }
+    }
+
+    fn push(&self, entry: Entry) {
+        if !entry.operations.is_empty() {
+            self.undo_stack.borrow_mut().push(entry);
+            self.redo_stack.borrow_mut().clear();
+        }
+    }
+
+    fn open_transaction(&self, name: ImString) {
+        let depth = self.transaction_depth.get();
+        if depth == 0 {
+            *self.transaction.borrow_mut() = Some(Entry { name, operations: default() });
+        }
+        self.transaction_depth.set(depth + 1);
+    }
+
+    fn close_transaction(&self) {
+        let depth = self.transaction_depth.get().saturating_sub(1);
+        self.transaction_depth.set(depth);
+        if depth == 0 {
+            let entry = self.transaction.borrow_mut().take();
+            if let Some(entry) = entry {
+                self.push(entry);
+            }
+        }
+    }
+
+    fn replay(
+        &self,
+        from: &RefCell<Vec<Entry>>,
+        to: &RefCell<Vec<Entry>>,
+        undo: bool,
+    ) -> Option<ImString> {
+        let entry = from.borrow_mut().pop()?;
+        self.replaying.set(true);
+        if undo {
+            entry.undo();
+        } else {
+            entry.redo();
+        }
+        self.replaying.set(false);
+        let name = entry.name.clone();
+        to.borrow_mut().push(entry);
+        Some(name)
+    }
+
+    fn undo(&self) -> Option<ImString> {
+        self.replay(&self.undo_stack, &self.redo_stack, true)
+    }
+
+    fn redo(&self) -> Option<ImString> {
+        self.replay(&self.redo_stack, &self.undo_stack, false)
+    }
+
+    fn clear(&self) {
+        self.undo_stack.borrow_mut().clear();
+        self.redo_stack.borrow_mut().clear();
+    }
+
+    fn can_undo(&self) -> bool {
+        !self.undo_stack.borrow().is_empty()
+    }
+
+    fn can_redo(&self) -> bool {
+        !self.redo_stack.borrow().is_empty()
+    }
+}
+
+
+
+// ===============
+// === Manager ===
+// ===============
+
+/// The application-wide undo and redo history. See the module docs to learn more.
+///
+/// The `cmd z`, `cmd y`, and `cmd shift z` shortcuts are active only when there is something to
+/// undo or redo, so components not using the manager keep handling them on their own.
+#[derive(Clone, CloneRef, Debug, Deref, display::Object)]
+pub struct Manager {
+    #[deref]
+    frp:   Frp,
+    #[display_object]
+    model: Rc<Model>,
+}
+
+impl Manager {
+    /// Constructor.
+    pub fn new() -> Self {
+        let frp = Frp::new();
+        let model = Rc::new(Model::default());
+        Self { frp, model }.init()
+    }
+
+    fn init(self) -> Self {
+        let network = self.frp.network();
+        let input = &self.frp.private.input;
+        let out = &self.frp.private.output;
+        let model = &self.model;
+        frp::extend! { network
+            out.undone <+ input.undo.filter_map(f_!(model.undo()));
+            out.redone <+ input.redo.filter_map(f_!(model.redo()));
+            eval_ input.clear (model.clear());
+            history_changed <- any_(&input.undo, &input.redo, &input.clear);
+            out.can_undo <+ history_changed.map(f_!(model.can_undo()));
+            out.can_redo <+ history_changed.map(f_!(model.can_redo()));
+        }
+        self
+    }
+
+    fn update_status(&self) {
+        self.frp.private.output.can_undo.emit(self.model.can_undo());
+        self.frp.private.output.can_redo.emit(self.model.can_redo());
+    }
+
+    /// Record an operation which was just performed. Recording clears the redo history. If a
+    /// [transaction](Self::transaction) is open, the operation becomes a part of it. Operations
+    /// recorded while the history is being replayed are ignored, so the `undo` and `redo`
+    /// functions may trigger the same code paths which recorded the operation in the first place.
+    ///
+    /// The functions should not keep strong references to the component they modify, as they are
+    /// kept as long as the operation stays in the history.
+    pub fn record(
+        &self,
+        name: impl Into<ImString>,
+        undo: impl Fn() + 'static,
+        redo: impl Fn() + 'static,
+    ) {
+        let operation = Operation { undo: Rc::new(undo), redo: Rc::new(redo) };
+        if self.model.record(name.into(), operation) {
+            self.update_status();
+        }
+    }
+
+    /// Open a transaction. All operations recorded until the returned guard is dropped are undone
+    /// and redone together, as a single entry named after the outermost open transaction.
+    pub fn transaction(&self, name: impl Into<ImString>) -> Transaction {
+        self.model.open_transaction(name.into());
+        Transaction { manager: self.clone_ref() }
+    }
+
+    /// Check whether the history is being replayed at the moment.
+    pub fn is_replaying(&self) -> bool {
+        self.model.replaying.get()
+    }
+}
+
+impl Default for Manager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrpNetworkProvider for Manager {
+    fn network(&self) -> &frp::Network {
+        self.frp.network()
+    }
+}
+
+impl crate::application::View for Manager {
+    fn label() -> &'static str {
+        "UndoManager"
+    }
+
+    fn new(app: &Application) -> Self {
+        app.undo.clone_ref()
+    }
+
+    fn global_shortcuts() -> Vec<Shortcut> {
+        use shortcut::ActionType::*;
+        vec![
+            Self::self_shortcut_when(Press, "cmd z", "undo", "can_undo"),
+            Self::self_shortcut_when(Press, "cmd y", "redo", "can_redo"),
+            Self::self_shortcut_when(Press, "cmd shift z", "redo", "can_redo"),
+        ]
+    }
+}
+
+
+
+// ===================
+// === Transaction ===
+// ===================
+
+/// A guard of an open transaction. See [`Manager::transaction`] to learn more.
+#[derive(Debug)]
+pub struct Transaction {
+    manager: Manager,
+}
+
+impl Drop for Transaction {
+    fn drop(&mut self) {
+        self.manager.model.close_transaction();
+        self.manager.update_status();
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorder(manager: &Manager, log: &Rc<RefCell<Vec<String>>>, name: &'static str) {
+        let undo_log = log.clone_ref();
+        let redo_log = log.clone_ref();
+        let undo = move || undo_log.borrow_mut().push(format!("undo {name}"));
+        let redo = move || redo_log.borrow_mut().push(format!("redo {name}"));
+        manager.record(name, undo, redo);
+    }
+
+    #[test]
+    fn operations_are_undone_across_components() {
+        let manager = Manager::new();
+        let log = Rc::<RefCell<Vec<String>>>::default();
+        assert!(!manager.can_undo.value());
+        recorder(&manager, &log, "text");
+        {
+            let _transaction = manager.transaction("graph");
+            recorder(&manager, &log, "node");
+            recorder(&manager, &log, "edge");
+        }
+        assert!(manager.can_undo.value());
+
+        manager.undo();
+        assert_eq!(manager.undone.value(), "graph");
+        assert_eq!(*log.borrow(), ["undo edge", "undo node"]);
+        assert!(manager.can_redo.value());
+
+        manager.undo();
+        manager.undo();
+        assert!(!manager.can_undo.value());
+        assert_eq!(log.borrow().len(), 3);
+
+        manager.redo();
+        assert_eq!(manager.redone.value(), "text");
+        assert_eq!(log.borrow().last().map(|t| t.as_str()), Some("redo text"));
+
+        recorder(&manager, &log, "other");
+        assert!(!manager.can_redo.value());
+    }
+}
@@ -5,6 +5,8 @@ use crate::prelude::*;
 use super::command;
 use super::shortcut;
 use super::Application;
+use crate::display;
+use crate::frp;
 
 
 // ==============
@@ -15,18 +17,101 @@ pub use command::View;
 
 
 
+// ===============
+// === AnyView ===
+// ===============
+
+/// A type-erased instance of a view, created by [`Registry::new_view_by_label`]. It keeps the view
+/// alive and gives access to its display object and the FRP endpoints exposed by the
+/// [`command::CommandApi`], including their [runtime metadata](command::Endpoint).
+#[derive(Clone, CloneRef, Derivative, display::Object)]
+#[derivative(Debug)]
+pub struct AnyView {
+    label:          &'static str,
+    display_object: display::object::Instance,
+    network:        frp::WeakNetwork,
+    command_map:    Rc<RefCell<HashMap<String, command::Command>>>,
+    status_map:     Rc<RefCell<HashMap<String, frp::Sampler<bool>>>>,
+    endpoints:      Rc<Vec<command::Endpoint>>,
+    #[derivative(Debug = "ignore")]
+    _view:          Rc<dyn Any>,
+}
+
+impl AnyView {
+    /// Constructor.
+    pub fn new<V: View>(view: V) -> Self {
+        let label = V::label();
+        let display_object = display::Object::display_object(&view).clone_ref();
+        let network = V::network(&view).downgrade();
+        let command_map = view.deref().command_api();
+        let status_map = view.deref().status_api();
+        let endpoints = Rc::new(view.deref().endpoints());
+        let _view = Rc::new(view);
+        Self { label, display_object, network, command_map, status_map, endpoints, _view }
+    }
+
+    /// The label of the view definition.
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    /// The names of all commands of the view, sorted alphabetically.
+    pub fn command_names(&self) -> Vec<String> {
+        self.command_map.borrow().keys().cloned().sorted().collect()
+    }
+
+    /// The names of all status endpoints of the view, sorted alphabetically.
+    pub fn status_names(&self) -> Vec<String> {
+        self.status_map.borrow().keys().cloned().sorted().collect()
+    }
+
+    /// Emit the command of the given name. Returns [`false`] if there is no such command or it is
+    /// disabled.
+    pub fn run_command(&self, name: &str) -> bool {
+        match self.command_map.borrow().get(name) {
+            Some(command) if command.enabled => {
+                command.frp.emit(());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The status endpoint of the given name.
+    pub fn status(&self, name: &str) -> Option<frp::Sampler<bool>> {
+        self.status_map.borrow().get(name).cloned()
+    }
+
+    /// Runtime metadata of all FRP endpoints of the view.
+    pub fn endpoints(&self) -> &[command::Endpoint] {
+        &self.endpoints
+    }
+
+    /// The network of the view. Can be used to attach additional FRP logic to the view.
+    pub fn network(&self) -> Option<frp::Network> {
+        self.network.upgrade()
+    }
+}
+
+
+
 // ================
 // === Registry ===
 // ================
 
+type Constructor = Rc<dyn Fn(&Application) -> AnyView>;
+
 /// View registry. Please note that all view definitions should be registered here as soon as
 /// possible in order to enable their default shortcuts and spread the information about their API.
-#[derive(Debug, Clone, CloneRef)]
+#[derive(Clone, CloneRef, Derivative)]
+#[derivative(Debug)]
 #[allow(missing_docs)]
 pub struct Registry {
     pub command_registry:  command::Registry,
     pub shortcut_registry: shortcut::Registry,
     pub definitions:       Rc<RefCell<HashSet<String>>>,
+    #[derivative(Debug = "ignore")]
+    constructors:          Rc<RefCell<BTreeMap<String, Constructor>>>,
 }
 
 impl Registry {
@@ -38,7 +123,8 @@ impl Registry {
         let command_registry = command_registry.clone_ref();
         let shortcut_registry = shortcut_registry.clone_ref();
         let definitions = default();
-        Self { command_registry, shortcut_registry, definitions }
+        let constructors = default();
+        Self { command_registry, shortcut_registry, definitions, constructors }
     }
 
     /// View registration.
@@ -53,9 +139,23 @@ impl Registry {
             self.shortcut_registry.add(shortcut)
         }
         self.definitions.borrow_mut().insert(label);
+        let constructor: Constructor = Rc::new(|app| AnyView::new(app.new_view::<V>()));
+        self.constructors.borrow_mut().insert(V::label().into(), constructor);
         self.command_registry.register::<V>();
     }
 
+    /// The labels of all registered views, sorted alphabetically.
+    pub fn labels(&self) -> Vec<String> {
+        self.constructors.borrow().keys().cloned().collect()
+    }
+
+    /// Create a new instance of the registered view with the given label. Returns [`None`] if no
+    /// view with such label was registered.
+    pub fn new_view_by_label(&self, label: &str, app: &Application) -> Option<AnyView> {
+        let constructor = self.constructors.borrow().get(label).cloned();
+        constructor.map(|constructor| constructor(app))
+    }
+
     /// New view constructor.
     pub fn new_view<V: View>(&self, app: &Application) -> V {
         let label = V::label();
@@ -83,3 +183,43 @@ impl Registry {
         view
     }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::undo;
+
+    #[test]
+    fn creating_registered_views_by_label() {
+        let app = Application::new("root");
+        let label = <undo::Manager as View>::label();
+        assert!(app.views.labels().iter().any(|registered| registered == label));
+        assert!(app.views.new_view_by_label("Unknown", &app).is_none());
+        let view = app.views.new_view_by_label(label, &app).unwrap();
+        assert_eq!(view.label(), label);
+        let commands = view.command_names();
+        assert_eq!(commands, vec![
+            "clear",
+            "deprecated_defocus",
+            "deprecated_focus",
+            "redo",
+            "undo"
+        ]);
+        assert_eq!(view.status_names(), vec!["can_redo", "can_undo", "focused"]);
+        let undone = view.endpoints().iter().find(|endpoint| endpoint.name == "undone").unwrap();
+        assert_eq!(undone.doc(), "The name of the reverted entry.");
+        let can_undo = view.status("can_undo").unwrap();
+        app.undo.record("edit", || {}, || {});
+        assert!(can_undo.value());
+        assert!(view.run_command("undo"));
+        assert!(!can_undo.value());
+        assert_eq!(undone.debug_value().as_deref(), Some("\"edit\""));
+        assert!(!view.run_command("unknown"));
+    }
+}
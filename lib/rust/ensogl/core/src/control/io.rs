@@ -5,5 +5,8 @@
 // === Export ===
 // ==============
 
+pub mod ime;
 pub mod keyboard;
 pub mod mouse;
+pub mod pointer_lock;
+pub mod virtual_keyboard;
@@ -0,0 +1,260 @@
+//! Support for the input method editors (IMEs), used to type the CJK characters or the letters
+//! composed with dead keys.
+//!
+//! The canvas can not receive text input on its own, so when a text area reports its caret with
+//! [`Frp::set_caret`], an invisible DOM text area is focused, which receives the composition
+//! events. The in-progress composition string is available as [`Frp::composition`], so the text
+//! area can display it at the caret, and the final string as [`Frp::committed`]. The DOM text area
+//! follows the caret, so the native IME candidate window is displayed next to it.
+//!
+//! The IME support is enabled by default on devices without a touch screen only, as on touch
+//! devices the composition is handled by the
+//! [on-screen keyboard support](crate::control::io::virtual_keyboard).
+
+use crate::prelude::*;
+
+use crate::animation;
+use crate::display;
+use crate::display::scene::Extension;
+use crate::display::Scene;
+use crate::frp;
+use crate::system::web;
+use crate::system::web::ime::Bridge;
+use crate::system::web::Closure;
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+crate::define_endpoints_2! {
+    Input {
+        /// Enable or disable the IME support.
+        set_enabled (bool),
+        /// Set the caret of the focused text area, at which the composition is displayed. Setting
+        /// a caret starts receiving the composition events, and setting [`None`] stops it.
+        set_caret (Option<display::object::Instance>),
+        /// Stop receiving the composition events if the given caret is the current one. Used by
+        /// the text areas losing focus, as another one may have already set its caret.
+        unset_caret (display::object::Instance),
+        /// Set the in-progress composition string. It is reported by the browser.
+        update_composition (ImString),
+        /// End the composition, committing the given string. It is reported by the browser.
+        end_composition (ImString),
+    }
+    Output {
+        enabled (bool),
+        caret (Option<display::object::Instance>),
+        /// The in-progress composition string, which should be displayed at the caret, or [`None`]
+        /// if there is no composition in progress.
+        composition (Option<ImString>),
+        /// The final composition string, which should be inserted into the focused text area.
+        committed (ImString),
+    }
+}
+
+
+
+// ================
+// === Position ===
+// ================
+
+/// The position of a point in CSS pixels relative to the top-left corner of the screen. The point
+/// position is given in the clip space.
+pub fn css_position(clip_space: Vector4<f32>, screen_width: f32, screen_height: f32) -> Vector2 {
+    let x = (clip_space.x / clip_space.w + 1.0) / 2.0 * screen_width;
+    let y = (1.0 - clip_space.y / clip_space.w) / 2.0 * screen_height;
+    Vector2(x, y)
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+#[derive(Debug)]
+struct Model {
+    bridge:             RefCell<Option<Bridge>>,
+    position:           Cell<Option<Vector2>>,
+    update_composition: frp::Any<ImString>,
+    end_composition:    frp::Any<ImString>,
+}
+
+impl Model {
+    fn new(update_composition: &frp::Any<ImString>, end_composition: &frp::Any<ImString>) -> Self {
+        let bridge = default();
+        let position = default();
+        let update_composition = update_composition.clone_ref();
+        let end_composition = end_composition.clone_ref();
+        Self { bridge, position, update_composition, end_composition }
+    }
+
+    fn set_enabled(&self, enabled: bool) {
+        let mut bridge = self.bridge.borrow_mut();
+        if !enabled {
+            *bridge = None;
+        } else if bridge.is_none() {
+            let update_composition = self.update_composition.clone_ref();
+            let end_composition = self.end_composition.clone_ref();
+            let on_update =
+                Closure::new(move |text: String| update_composition.emit(ImString::new(text)));
+            let on_end =
+                Closure::new(move |text: String| end_composition.emit(ImString::new(text)));
+            *bridge = Some(Bridge::new(on_update, on_end));
+            self.position.set(None);
+        }
+    }
+
+    fn set_focused(&self, focused: bool) {
+        if let Some(bridge) = &*self.bridge.borrow() {
+            if focused {
+                bridge.focus()
+            } else {
+                bridge.blur()
+            }
+        }
+    }
+
+    /// Move the DOM text area to the caret, so the IME candidate window is displayed next to it.
+    fn follow_caret(&self, caret: &display::object::Instance) {
+        let Some(camera) = caret.display_layer().map(|layer| layer.camera()) else { return };
+        let screen = camera.screen();
+        let world_space = caret.transformation_matrix() * Vector4(0.0, 0.0, 0.0, 1.0);
+        let clip_space = camera.view_projection_matrix() * world_space;
+        let position = css_position(clip_space, screen.width, screen.height);
+        if self.position.get() != Some(position) {
+            self.position.set(Some(position));
+            if let Some(bridge) = &*self.bridge.borrow() {
+                bridge.set_position(position.x, position.y);
+            }
+        }
+    }
+}
+
+
+
+// ===========
+// === Ime ===
+// ===========
+
+/// The IME support. See the module docs to learn more. It is a [scene extension](Extension),
+/// available with [`Scene::extension`].
+#[derive(Clone, CloneRef, Debug, Deref)]
+pub struct Ime {
+    #[deref]
+    frp:   Frp,
+    model: Rc<Model>,
+}
+
+impl Ime {
+    fn new() -> Self {
+        let frp = Frp::new();
+        let input = &frp.private.input;
+        let model = Rc::new(Model::new(&input.update_composition, &input.end_composition));
+        Self { frp, model }.init()
+    }
+
+    fn init(self) -> Self {
+        let network = self.frp.network();
+        let input = &self.frp.private.input;
+        let out = &self.frp.private.output;
+        let model = &self.model;
+        let after_animations = animation::on_after_animations();
+
+        frp::extend! { network
+            out.enabled <+ input.set_enabled.on_change();
+            eval out.enabled ((enabled) model.set_enabled(*enabled));
+
+            unset_caret <- input.unset_caret.map2(&out.caret,
+                |unset, current| current.as_ref().filter(|c| *c == unset).map(|_| None)
+            );
+            unset_caret <- unset_caret.filter_map(|c| c.clone());
+            caret <- any(&input.set_caret, &unset_caret);
+            caret <- all_with(&caret, &out.enabled, |c, enabled| c.clone().filter(|_| *enabled));
+            out.caret <+ caret;
+            has_caret <- out.caret.map(|caret| caret.is_some()).on_change();
+            eval has_caret ((focused) model.set_focused(*focused));
+
+
+            // === Composition ===
+
+            update <- input.update_composition.gate(&has_caret).map(|t| Some(t.clone()));
+            end <- input.end_composition.gate(&has_caret);
+            caret_unset <- has_caret.on_false();
+            cancel <- any_(&end, &caret_unset);
+            composition <- any(&update, &cancel.constant(None));
+            out.composition <+ composition.on_change();
+            out.committed <+ end.filter(|t| !t.is_empty());
+
+
+            // === Candidate Window Position ===
+
+            follow_caret <- out.caret.sample(&after_animations);
+            eval follow_caret ((caret) if let Some(caret) = caret {
+                model.follow_caret(caret)
+            });
+        }
+        self.frp.set_caret(None);
+        self.frp.set_enabled(!web::virtual_keyboard::has_touch_screen());
+        self
+    }
+}
+
+impl Extension for Ime {
+    fn init(_scene: &Scene) -> Self {
+        Self::new()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn composition_is_committed_at_end() {
+        let ime = Ime::new();
+        let caret = display::object::Instance::new();
+        ime.set_enabled(true);
+        ime.update_composition("か");
+        assert_eq!(ime.composition.value(), None);
+        ime.set_caret(Some(caret.clone()));
+        ime.update_composition("か");
+        assert_eq!(ime.composition.value(), Some("か".into()));
+        ime.update_composition("漢");
+        assert_eq!(ime.composition.value(), Some("漢".into()));
+        ime.end_composition("漢字");
+        assert_eq!(ime.composition.value(), None);
+        assert_eq!(ime.committed.value(), ImString::new("漢字"));
+    }
+
+    #[test]
+    fn composition_is_cancelled_when_caret_is_unset() {
+        let ime = Ime::new();
+        let caret = display::object::Instance::new();
+        ime.set_enabled(true);
+        ime.set_caret(Some(caret.clone()));
+        ime.update_composition("´");
+        ime.unset_caret(display::object::Instance::new());
+        assert_eq!(ime.caret.value(), Some(caret.clone()));
+        assert_eq!(ime.composition.value(), Some("´".into()));
+        ime.unset_caret(caret);
+        assert_eq!(ime.caret.value(), None);
+        assert_eq!(ime.composition.value(), None);
+        assert_eq!(ime.committed.value(), ImString::default());
+    }
+
+    #[test]
+    fn positions_are_converted_to_css_pixels() {
+        assert_eq!(css_position(Vector4(0.0, 0.0, 0.0, 1.0), 800.0, 600.0), Vector2(400.0, 300.0));
+        assert_eq!(css_position(Vector4(-1.0, 1.0, 0.0, 1.0), 800.0, 600.0), Vector2(0.0, 0.0));
+        assert_eq!(css_position(Vector4(2.0, -2.0, 0.0, 2.0), 800.0, 600.0), Vector2(800.0, 600.0));
+    }
+}
@@ -186,11 +186,22 @@ where
         self.data.borrow().ctrl_key
     }
 
+    /// Check whether the `shift` key was pressed when the event was triggered.
+    pub fn shift_key(&self) -> bool {
+        self.data.borrow().shift_key
+    }
+
     /// Prevent the default action of the event.
     pub fn prevent_default(&self) {
         self.js_event.as_ref().map(|t| t.as_ref().prevent_default());
     }
 
+    /// Check whether the default action of the event was prevented, for example by a display
+    /// object handling the event. Simulated events are never prevented.
+    pub fn is_default_prevented(&self) -> bool {
+        self.js_event.as_ref().map_or(false, |t| t.as_ref().default_prevented())
+    }
+
     /// Convert the event to a different type. No checks will be performed during this action.
     pub fn unchecked_convert_to<NewEventType: IsEvent>(
         self,
@@ -262,15 +273,17 @@ pub trait ToEventData {
 #[derive(Copy, Clone, Debug, Default)]
 pub struct MouseEventData {
     /// Mouse client position. See [`Event<EventType,JsEvent>::client()`].
-    pub client:   Vector2,
+    pub client:    Vector2,
     /// Mouse screen position. See [`Event<EventType,JsEvent>::screen()`].
-    pub screen:   Vector2,
+    pub screen:    Vector2,
     /// Mouse movement. See [`Event<EventType,JsEvent>::movement()`].
-    pub movement: Vector2,
+    pub movement:  Vector2,
     /// See [`Event<EventType,JsEvent>::button()`].
-    pub button:   mouse::Button,
+    pub button:    mouse::Button,
     /// See [`Event<EventType,JsEvent>::ctrl_key()`].
-    pub ctrl_key: bool,
+    pub ctrl_key:  bool,
+    /// See [`Event<EventType,JsEvent>::shift_key()`].
+    pub shift_key: bool,
 }
 
 impl MouseEventData {
@@ -301,11 +314,12 @@ impl ToEventData for web::MouseEvent {
     type Data = MouseEventData;
     fn to_data(&self, shape: Shape) -> Self::Data {
         MouseEventData {
-            client:   Vector2(self.client_x() as f32, shape.height - self.client_y() as f32),
-            screen:   Vector2(self.screen_x() as f32, shape.height - self.screen_y() as f32),
-            movement: Vector2(self.movement_x() as f32, -self.movement_y() as f32),
-            button:   mouse::Button::from_code(self.button().into()),
-            ctrl_key: self.ctrl_key(),
+            client:    Vector2(self.client_x() as f32, shape.height - self.client_y() as f32),
+            screen:    Vector2(self.screen_x() as f32, shape.height - self.screen_y() as f32),
+            movement:  Vector2(self.movement_x() as f32, -self.movement_y() as f32),
+            button:    mouse::Button::from_code(self.button().into()),
+            ctrl_key:  self.ctrl_key(),
+            shift_key: self.shift_key(),
         }
     }
 }
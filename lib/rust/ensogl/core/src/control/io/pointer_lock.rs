@@ -0,0 +1,183 @@
+//! Pointer lock support for drag-to-adjust controls, like sliders or numeric scrubbing.
+//!
+//! While the pointer is [locked](Frp::lock), it is hidden and does not hit the screen edges, so the
+//! drag can go on indefinitely. The relative movement is reported by [`Frp::movement`], and the
+//! unbounded [`Frp::position`] accumulates it, starting from the position at which the pointer was
+//! locked. Controls reading the position from [`Frp::position`] instead of the scene mouse work the
+//! same way, no matter whether the pointer is locked or not.
+//!
+//! The browser grants the lock only when it is requested while handling a user gesture, like a
+//! mouse press. It releases the lock when the Escape key is pressed, which is reflected by
+//! [`Frp::locked`].
+
+use crate::prelude::*;
+
+use crate::control::callback;
+use crate::control::io::mouse;
+use crate::display::scene::Extension;
+use crate::display::Scene;
+use crate::frp;
+use crate::frp::io::keyboard::Key;
+use crate::system::web;
+use crate::system::web::pointer_lock::PointerLockObserver;
+use crate::system::web::Closure;
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+crate::define_endpoints_2! {
+    Input {
+        /// Lock the pointer. It has to be called while handling a mouse press, as the browser
+        /// ignores the requests made in other contexts.
+        lock(),
+        /// Release the pointer lock.
+        unlock(),
+    }
+    Output {
+        /// Whether the pointer is locked.
+        locked (bool),
+        /// The relative movement of the locked pointer, in screen pixels, with the y axis pointing
+        /// up.
+        movement (Vector2),
+        /// The unbounded pointer position in the screen space. It follows the scene mouse position
+        /// while the pointer is not locked.
+        position (Vector2),
+    }
+}
+
+
+
+// ===============
+// === Helpers ===
+// ===============
+
+fn is_escape(key: &Key) -> bool {
+    matches!(key, Key::Other(name) if name == "Escape")
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+#[derive(Debug)]
+struct Model {
+    canvas:       web::HtmlCanvasElement,
+    lock_changed: frp::Source<bool>,
+    moved:        frp::Source<Vector2>,
+    _observer:    PointerLockObserver,
+    _move_handle: callback::Handle,
+}
+
+impl Model {
+    fn new(scene: &Scene, lock_changed: &frp::Source<bool>, moved: &frp::Source<Vector2>) -> Self {
+        let canvas = scene.dom.layers.canvas.clone();
+        let on_lock_changed = f!((locked: bool) lock_changed.emit(locked));
+        let _observer = PointerLockObserver::new(Closure::new(on_lock_changed));
+        let on_move = f!((event: &mouse::Move) moved.emit(event.movement()));
+        let lock_changed = lock_changed.clone_ref();
+        let moved = moved.clone_ref();
+        let _move_handle = scene.mouse.mouse_manager.on_move.add(on_move);
+        Self { canvas, lock_changed, moved, _observer, _move_handle }
+    }
+}
+
+
+
+// ===================
+// === PointerLock ===
+// ===================
+
+/// The pointer lock of the scene canvas. See the module docs to learn more. It is a
+/// [scene extension](Extension), available with [`Scene::extension`].
+#[derive(Clone, CloneRef, Debug, Deref)]
+pub struct PointerLock {
+    #[deref]
+    frp:   Frp,
+    model: Rc<Model>,
+}
+
+impl PointerLock {
+    fn new(scene: &Scene) -> Self {
+        let frp = Frp::new();
+        let network = frp.network();
+        frp::extend! { network
+            lock_changed <- source::<bool>();
+            moved <- source::<Vector2>();
+        }
+        let model = Rc::new(Model::new(scene, &lock_changed, &moved));
+        Self { frp, model }.init(scene)
+    }
+
+    fn init(self, scene: &Scene) -> Self {
+        let network = self.frp.network();
+        let input = &self.frp.private.input;
+        let out = &self.frp.private.output;
+        let model = &self.model;
+        let mouse = &scene.mouse.frp_deprecated;
+        let keyboard = &scene.global_keyboard.frp;
+
+        frp::extend! { network
+            eval_ input.lock (web::pointer_lock::request(model.canvas.as_ref()));
+            escape_pressed <- keyboard.down.filter(|key| is_escape(key));
+            unlock <- any_(&input.unlock, &escape_pressed);
+            eval_ unlock (web::pointer_lock::exit());
+            out.locked <+ model.lock_changed.on_change();
+
+            movement <- model.moved.gate(&out.locked);
+            out.movement <+ movement;
+            unlocked_position <- mouse.position.gate_not(&out.locked);
+            locked_position <- movement.map2(&out.position, |movement, pos| pos + movement);
+            out.position <+ any(&unlocked_position, &locked_position);
+        }
+        self
+    }
+}
+
+impl Extension for PointerLock {
+    fn init(scene: &Scene) -> Self {
+        Self::new(scene)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::world::World;
+
+    #[test]
+    fn locked_pointer_position_is_unbounded() {
+        let world = World::new();
+        let scene = &world.default_scene;
+        let pointer_lock = scene.extension::<PointerLock>();
+        scene.mouse.frp_deprecated.position.emit(Vector2(10.0, 20.0));
+        assert_eq!(pointer_lock.position.value(), Vector2(10.0, 20.0));
+        pointer_lock.model.moved.emit(Vector2(5.0, 5.0));
+        assert_eq!(pointer_lock.position.value(), Vector2(10.0, 20.0));
+
+        pointer_lock.model.lock_changed.emit(true);
+        assert!(pointer_lock.locked.value());
+        for _ in 0..100 {
+            pointer_lock.model.moved.emit(Vector2(-50.0, 1.0));
+        }
+        assert_eq!(pointer_lock.movement.value(), Vector2(-50.0, 1.0));
+        assert_eq!(pointer_lock.position.value(), Vector2(-4990.0, 120.0));
+        scene.mouse.frp_deprecated.position.emit(Vector2(0.0, 0.0));
+        assert_eq!(pointer_lock.position.value(), Vector2(-4990.0, 120.0));
+
+        pointer_lock.model.lock_changed.emit(false);
+        assert!(!pointer_lock.locked.value());
+        scene.mouse.frp_deprecated.position.emit(Vector2(30.0, 40.0));
+        assert_eq!(pointer_lock.position.value(), Vector2(30.0, 40.0));
+    }
+}
@@ -0,0 +1,410 @@
+//! Support for the on-screen keyboards of touch devices.
+//!
+//! The canvas can not receive text input on its own, so when a text area reports its caret with
+//! [`Frp::set_caret`], an invisible DOM text area is focused, which opens the on-screen keyboard.
+//! While the keyboard is open, the camera of the caret's layer is shifted, so the caret is not
+//! obscured by it. The shift is reverted once the caret is [unset](Frp::unset_caret) or the
+//! keyboard is closed. The height of the obscured area is available as
+//! [`Frp::obscured_height`], so layouts can be adjusted to it as well.
+//!
+//! Optionally, a bar of symbols awkward to type on mobile keyboards is displayed just above the
+//! keyboard. The pressed symbols are reported by [`Frp::text_input`], together with the text typed
+//! without key presses, for example, with gestures.
+//!
+//! The keyboard support is enabled by default on devices with a touch screen only.
+
+use crate::prelude::*;
+use crate::system::web::traits::*;
+
+use crate::animation;
+use crate::display;
+use crate::display::camera::Camera2d;
+use crate::display::scene::Extension;
+use crate::display::Scene;
+use crate::frp;
+use crate::system::web;
+use crate::system::web::virtual_keyboard::Bridge;
+use crate::system::web::Closure;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The minimal distance between the caret origin and the top of the on-screen keyboard. It should
+/// fit the caret descent.
+pub const CARET_MARGIN: f32 = 32.0;
+/// The height of the symbol bar.
+pub const SYMBOL_BAR_HEIGHT: f32 = 36.0;
+/// The symbols displayed in the symbol bar by default.
+pub const DEFAULT_SYMBOLS: &[&str] = &[
+    "(", ")", "[", "]", "{", "}", "<", ">", "=", "+", "-", "*", "/", "\\", "|", "_", "\"", "'",
+    "`", ":", ";", "#", "@", "~",
+];
+const FONTS: &str = "\"SF Mono\", Menlo, Monaco, Consolas, \"Liberation Mono\", monospace";
+const BAR_COLOR: &str = "#F2F2F2";
+const BUTTON_COLOR: &str = "#FFFFFF";
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+crate::define_endpoints_2! {
+    Input {
+        /// Enable or disable the on-screen keyboard support.
+        set_enabled (bool),
+        /// Enable or disable the symbol bar.
+        set_symbol_bar_enabled (bool),
+        /// Set the symbols displayed in the symbol bar.
+        set_symbols (Vec<ImString>),
+        /// Set the caret of the focused text area, which should be kept visible above the
+        /// keyboard. Setting a caret opens the keyboard, and setting [`None`] closes it.
+        set_caret (Option<display::object::Instance>),
+        /// Close the keyboard if the given caret is the current one. Used by the text areas losing
+        /// focus, as another one may have already set its caret.
+        unset_caret (display::object::Instance),
+        /// Set the height of the bottom part of the window covered by the on-screen keyboard. It
+        /// is reported by the browser.
+        set_obscured_height (f32),
+    }
+    Output {
+        enabled (bool),
+        caret (Option<display::object::Instance>),
+        keyboard_visible (bool),
+        symbol_bar_visible (bool),
+        /// The height of the bottom part of the window covered by the keyboard and the symbol bar.
+        obscured_height (f32),
+        /// The text typed without key presses or with the symbol bar, which should be inserted
+        /// into the focused text area.
+        text_input (ImString),
+    }
+}
+
+
+
+// =================
+// === SymbolBar ===
+// =================
+
+/// The on-screen bar of symbol buttons, displayed above the keyboard.
+#[derive(Debug)]
+struct SymbolBar {
+    root:       web::HtmlDivElement,
+    text_input: frp::Any<ImString>,
+    handles:    RefCell<Vec<web::EventListenerHandle>>,
+}
+
+impl SymbolBar {
+    fn new(text_input: &frp::Any<ImString>) -> Self {
+        let root = web::document.create_div_or_panic();
+        root.set_style_or_warn("display", "none");
+        root.set_style_or_warn("position", "fixed");
+        root.set_style_or_warn("z-index", "100");
+        root.set_style_or_warn("left", "0");
+        root.set_style_or_warn("right", "0");
+        root.set_style_or_warn("height", format!("{SYMBOL_BAR_HEIGHT}px"));
+        root.set_style_or_warn("gap", "4px");
+        root.set_style_or_warn("padding", "4px");
+        root.set_style_or_warn("box-sizing", "border-box");
+        root.set_style_or_warn("overflow-x", "auto");
+        root.set_style_or_warn("background", BAR_COLOR);
+        root.set_style_or_warn("font-family", FONTS);
+        root.set_style_or_warn("font-size", "16px");
+        web::document.body_or_panic().append_or_warn(&root);
+        let text_input = text_input.clone_ref();
+        let handles = default();
+        Self { root, text_input, handles }
+    }
+
+    fn set_symbols(&self, symbols: &[ImString]) {
+        self.root.set_inner_html("");
+        let mut handles = self.handles.borrow_mut();
+        handles.clear();
+        for symbol in symbols {
+            let button = web::document.create_div_or_panic();
+            button.set_inner_text(symbol);
+            button.set_style_or_warn("flex", "0 0 auto");
+            button.set_style_or_warn("min-width", "28px");
+            button.set_style_or_warn("line-height", "28px");
+            button.set_style_or_warn("text-align", "center");
+            button.set_style_or_warn("border-radius", "4px");
+            button.set_style_or_warn("background", BUTTON_COLOR);
+            button.set_style_or_warn("user-select", "none");
+            let text_input = self.text_input.clone_ref();
+            let symbol = symbol.clone_ref();
+            type Handler = web::JsEventHandler<web_sys::Event>;
+            // Preventing the default action keeps the focus, and thus, the keyboard open.
+            let on_press: Handler = Closure::new(move |e: web_sys::Event| {
+                e.prevent_default();
+                text_input.emit(symbol.clone_ref());
+            });
+            handles.push(web::add_event_listener(&button, "pointerdown", on_press));
+            self.root.append_or_warn(&button);
+        }
+    }
+
+    fn set_visible(&self, visible: bool) {
+        let display = if visible { "flex" } else { "none" };
+        self.root.set_style_or_warn("display", display);
+    }
+
+    fn set_bottom(&self, bottom: f32) {
+        self.root.set_style_or_warn("bottom", format!("{bottom}px"));
+    }
+}
+
+impl Drop for SymbolBar {
+    fn drop(&mut self) {
+        self.root.remove_from_parent_or_warn();
+    }
+}
+
+
+
+// =============
+// === Shift ===
+// =============
+
+/// The vertical shift of the scene, in screen pixels, needed to display a point above the area
+/// obscured by the keyboard. The point position is given in the screen space, which has the origin
+/// in the center of the screen.
+pub fn required_shift(point_y: f32, screen_height: f32, obscured_height: f32) -> f32 {
+    let keyboard_top = -screen_height / 2.0 + obscured_height;
+    (keyboard_top + CARET_MARGIN - point_y).max(0.0)
+}
+
+/// The camera shifted to keep the caret visible, and the applied shift in the world units.
+#[derive(Debug)]
+struct Shift {
+    camera: Camera2d,
+    shift:  f32,
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+#[derive(Debug)]
+struct Model {
+    bridge:              RefCell<Option<Bridge>>,
+    symbol_bar:          SymbolBar,
+    shift:               RefCell<Option<Shift>>,
+    set_obscured_height: frp::Any<f32>,
+    text_input:          frp::Any<ImString>,
+}
+
+impl Model {
+    fn new(set_obscured_height: &frp::Any<f32>, text_input: &frp::Any<ImString>) -> Self {
+        let bridge = default();
+        let symbol_bar = SymbolBar::new(text_input);
+        let shift = default();
+        let set_obscured_height = set_obscured_height.clone_ref();
+        let text_input = text_input.clone_ref();
+        Self { bridge, symbol_bar, shift, set_obscured_height, text_input }
+    }
+
+    fn set_enabled(&self, enabled: bool) {
+        let mut bridge = self.bridge.borrow_mut();
+        if !enabled {
+            *bridge = None;
+        } else if bridge.is_none() {
+            let set_obscured_height = self.set_obscured_height.clone_ref();
+            let text_input = self.text_input.clone_ref();
+            let on_obscured_height =
+                Closure::new(move |height: f32| set_obscured_height.emit(height));
+            let on_text = Closure::new(move |text: String| text_input.emit(ImString::new(text)));
+            *bridge = Some(Bridge::new(on_obscured_height, on_text));
+        }
+    }
+
+    fn set_keyboard_open(&self, open: bool) {
+        if let Some(bridge) = &*self.bridge.borrow() {
+            if open {
+                bridge.show()
+            } else {
+                bridge.hide()
+            }
+        }
+    }
+
+    /// Shift the camera of the caret's layer, so the caret is displayed above the obscured area.
+    fn keep_visible(&self, caret: &display::object::Instance, obscured_height: f32) {
+        let Some(camera) = caret.display_layer().map(|layer| layer.camera()) else {
+            self.reset_shift();
+            return;
+        };
+        let is_same_camera = self
+            .shift
+            .borrow()
+            .as_ref()
+            .map_or(false, |s| s.camera.display_object() == camera.display_object());
+        if !is_same_camera {
+            self.reset_shift();
+        }
+        let applied_shift = self.shift.borrow().as_ref().map_or(0.0, |s| s.shift);
+        let zoom = camera.zoom();
+        let screen = camera.screen();
+        let world_space = caret.transformation_matrix() * Vector4(0.0, 0.0, 0.0, 1.0);
+        let clip_space = camera.view_projection_matrix() * world_space;
+        let screen_y = clip_space.y / clip_space.w * screen.height / 2.0;
+        let unshifted_y = screen_y - applied_shift * zoom;
+        let shift = required_shift(unshifted_y, screen.height, obscured_height) / zoom;
+        if shift != applied_shift {
+            camera.mod_position(|p| p.y -= shift - applied_shift);
+            *self.shift.borrow_mut() = Some(Shift { camera, shift });
+        }
+    }
+
+    /// Revert the camera shift.
+    fn reset_shift(&self) {
+        if let Some(Shift { camera, shift }) = self.shift.take() {
+            camera.mod_position(|p| p.y += shift);
+        }
+    }
+}
+
+
+
+// =======================
+// === VirtualKeyboard ===
+// =======================
+
+/// The on-screen keyboard support. See the module docs to learn more. It is a
+/// [scene extension](Extension), available with [`Scene::extension`].
+#[derive(Clone, CloneRef, Debug, Deref)]
+pub struct VirtualKeyboard {
+    #[deref]
+    frp:   Frp,
+    model: Rc<Model>,
+}
+
+impl VirtualKeyboard {
+    fn new() -> Self {
+        let frp = Frp::new();
+        let input = &frp.private.input;
+        let out = &frp.private.output;
+        let model = Rc::new(Model::new(&input.set_obscured_height, &out.text_input));
+        Self { frp, model }.init()
+    }
+
+    fn init(self) -> Self {
+        let network = self.frp.network();
+        let input = &self.frp.private.input;
+        let out = &self.frp.private.output;
+        let model = &self.model;
+        let after_animations = animation::on_after_animations();
+
+        frp::extend! { network
+            out.enabled <+ input.set_enabled.on_change();
+            eval out.enabled ((enabled) model.set_enabled(*enabled));
+
+            unset_caret <- input.unset_caret.map2(&out.caret,
+                |unset, current| current.as_ref().filter(|c| *c == unset).map(|_| None)
+            );
+            unset_caret <- unset_caret.filter_map(|c| c.clone());
+            caret <- any(&input.set_caret, &unset_caret);
+            caret <- all_with(&caret, &out.enabled, |c, enabled| c.clone().filter(|_| *enabled));
+            out.caret <+ caret;
+            has_caret <- out.caret.map(|caret| caret.is_some()).on_change();
+            eval has_caret ((open) model.set_keyboard_open(*open));
+
+            keyboard_height <- all_with(&input.set_obscured_height, &out.enabled,
+                |height, enabled| if *enabled { *height } else { 0.0 }
+            );
+            out.keyboard_visible <+ keyboard_height.map(|height| *height > 0.0).on_change();
+            symbol_bar_visible <- all_with3(
+                &input.set_symbol_bar_enabled, &out.keyboard_visible, &has_caret,
+                |enabled, keyboard, caret| *enabled && *keyboard && *caret
+            );
+            out.symbol_bar_visible <+ symbol_bar_visible.on_change();
+            eval out.symbol_bar_visible ((visible) model.symbol_bar.set_visible(*visible));
+            eval keyboard_height ((height) model.symbol_bar.set_bottom(*height));
+            eval input.set_symbols ((symbols) model.symbol_bar.set_symbols(symbols));
+            obscured_height <- all_with(&keyboard_height, &out.symbol_bar_visible,
+                |height, bar| if *bar { height + SYMBOL_BAR_HEIGHT } else { *height }
+            );
+            out.obscured_height <+ obscured_height.on_change();
+
+
+            // === Caret Visibility ===
+
+            caret_and_height <- all(&out.caret, &out.obscured_height);
+            keep_visible <- caret_and_height.sample(&after_animations).gate(&out.keyboard_visible);
+            eval keep_visible (((caret, height)) if let Some(caret) = caret {
+                model.keep_visible(caret, *height)
+            });
+            keyboard_hidden <- out.keyboard_visible.on_false();
+            caret_unset <- has_caret.on_false();
+            reset_shift <- any_(&keyboard_hidden, &caret_unset);
+            eval_ reset_shift (model.reset_shift());
+        }
+        self.frp.set_obscured_height(0.0);
+        self.frp.set_symbol_bar_enabled(false);
+        self.frp.set_symbols(DEFAULT_SYMBOLS.iter().copied().map(ImString::new).collect_vec());
+        self.frp.set_caret(None);
+        self.frp.set_enabled(web::virtual_keyboard::has_touch_screen());
+        self
+    }
+}
+
+impl Extension for VirtualKeyboard {
+    fn init(_scene: &Scene) -> Self {
+        Self::new()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shift_keeps_points_above_keyboard() {
+        // The screen spans from -300 to 300, and the keyboard covers it up to -100.
+        let keyboard_top = -100.0;
+        assert_eq!(required_shift(0.0, 600.0, 200.0), 0.0);
+        assert_eq!(required_shift(keyboard_top + CARET_MARGIN, 600.0, 200.0), 0.0);
+        assert_eq!(required_shift(-150.0, 600.0, 200.0), 50.0 + CARET_MARGIN);
+        assert_eq!(required_shift(-150.0, 600.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn caret_is_kept_only_when_enabled() {
+        let keyboard = VirtualKeyboard::new();
+        let caret = display::object::Instance::new();
+        keyboard.set_enabled(true);
+        keyboard.set_caret(Some(caret.clone()));
+        assert_eq!(keyboard.caret.value(), Some(caret.clone()));
+        keyboard.unset_caret(display::object::Instance::new());
+        assert_eq!(keyboard.caret.value(), Some(caret.clone()));
+        keyboard.unset_caret(caret.clone());
+        assert_eq!(keyboard.caret.value(), None);
+        keyboard.set_caret(Some(caret.clone()));
+        keyboard.set_enabled(false);
+        assert_eq!(keyboard.caret.value(), None);
+    }
+
+    #[test]
+    fn symbol_bar_adds_to_obscured_height() {
+        let keyboard = VirtualKeyboard::new();
+        keyboard.set_enabled(true);
+        keyboard.set_symbol_bar_enabled(true);
+        keyboard.set_obscured_height(200.0);
+        assert!(keyboard.keyboard_visible.value());
+        assert!(!keyboard.symbol_bar_visible.value());
+        assert_eq!(keyboard.obscured_height.value(), 200.0);
+        keyboard.set_caret(Some(display::object::Instance::new()));
+        assert!(keyboard.symbol_bar_visible.value());
+        assert_eq!(keyboard.obscured_height.value(), 200.0 + SYMBOL_BAR_HEIGHT);
+    }
+}
@@ -5,8 +5,11 @@
 // === Export ===
 // ==============
 
+pub mod input_latency;
 pub mod monitor;
+pub mod probe;
 pub mod stats;
+pub mod time_travel;
 
 pub use monitor::*;
 pub use stats::*;
@@ -0,0 +1,142 @@
+//! Measurement of the input latency, the time between the DOM keyboard event and the end of the
+//! frame in which the visual effect of this event (like a newly typed glyph) was rendered.
+//!
+//! The measurement is cooperative. The scene registers the timestamp of every DOM keyboard event
+//! with [`register_input`]. Components which display the effects of the input (for example, the
+//! text area after inserting typed characters) call [`mark_visual_change`] while handling the
+//! event. The world calls [`on_frame_rendered`] after rendering every frame, which computes the
+//! latency of the marked input, writes it to the profiling log, and reports it to the stats (see
+//! [`crate::debug::monitor::sampler::INPUT_LATENCY`]). Inputs not marked before the end of the
+//! next frame (for example, key presses handled by shortcuts not changing anything visually) are
+//! discarded.
+
+use crate::prelude::*;
+
+
+
+// ====================
+// === InputLatency ===
+// ====================
+
+/// Input latency measurement, written to the profiling log.
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+pub struct InputLatency {
+    /// The DOM timestamp of the input event, in milliseconds.
+    pub input_time: f64,
+    /// The time between the input event and the end of the frame rendering, in milliseconds.
+    pub latency:    f64,
+}
+
+profiler::metadata_logger!("InputLatency", log_input_latency(InputLatency));
+
+
+
+// ===============
+// === Tracker ===
+// ===============
+
+/// Input latency tracker. See the module docs to learn more.
+#[derive(Debug, Default)]
+pub struct Tracker {
+    /// The oldest input registered since the last rendered frame.
+    pending_input:   Cell<Option<f64>>,
+    /// The oldest input whose effects are waiting to be rendered.
+    awaiting_render: Cell<Option<f64>>,
+}
+
+impl Tracker {
+    /// Constructor.
+    pub fn new() -> Self {
+        default()
+    }
+
+    /// Register an input event with the given DOM timestamp. If several events are registered in
+    /// a single frame, the oldest one is measured.
+    pub fn register_input(&self, time: f64) {
+        if self.pending_input.get().is_none() {
+            self.pending_input.set(Some(time));
+        }
+    }
+
+    /// Mark that the pending input caused a visual change, which will be visible after rendering
+    /// the next frame.
+    pub fn mark_visual_change(&self) {
+        if let Some(input_time) = self.pending_input.take() {
+            if self.awaiting_render.get().is_none() {
+                self.awaiting_render.set(Some(input_time));
+            }
+        }
+    }
+
+    /// Finish the measurement after rendering a frame at the given time. Returns the latency of the
+    /// input whose effects were rendered in this frame, if any.
+    pub fn on_frame_rendered(&self, now: f64) -> Option<InputLatency> {
+        self.pending_input.set(None);
+        let input_time = self.awaiting_render.take()?;
+        let latency = now - input_time;
+        Some(InputLatency { input_time, latency })
+    }
+}
+
+
+
+// ======================
+// === Global Tracker ===
+// ======================
+
+thread_local! {
+    static TRACKER: Tracker = Tracker::new();
+}
+
+/// Register an input event with the given DOM timestamp in the global tracker.
+pub fn register_input(time: f64) {
+    TRACKER.with(|tracker| tracker.register_input(time))
+}
+
+/// Mark that the pending input caused a visual change. Should be called by components while
+/// handling the input event.
+pub fn mark_visual_change() {
+    TRACKER.with(|tracker| tracker.mark_visual_change())
+}
+
+/// Finish the measurement after rendering a frame at the given time. The measured latency is
+/// written to the profiling log.
+pub fn on_frame_rendered(now: f64) -> Option<InputLatency> {
+    let measurement = TRACKER.with(|tracker| tracker.on_frame_rendered(now));
+    if let Some(measurement) = measurement {
+        log_input_latency(measurement);
+    }
+    measurement
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latency_of_the_oldest_marked_input_is_measured() {
+        let tracker = Tracker::new();
+        tracker.register_input(10.0);
+        tracker.register_input(12.0);
+        tracker.mark_visual_change();
+        let measurement = tracker.on_frame_rendered(30.0).unwrap();
+        assert_eq!(measurement.input_time, 10.0);
+        assert_eq!(measurement.latency, 20.0);
+        assert!(tracker.on_frame_rendered(40.0).is_none());
+    }
+
+    #[test]
+    fn unmarked_inputs_are_discarded() {
+        let tracker = Tracker::new();
+        tracker.register_input(10.0);
+        assert!(tracker.on_frame_rendered(20.0).is_none());
+        tracker.mark_visual_change();
+        assert!(tracker.on_frame_rendered(30.0).is_none());
+    }
+}
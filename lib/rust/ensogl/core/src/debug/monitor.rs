@@ -474,6 +474,11 @@ impl Default for Monitor {
         renderer.add(sampler::SHADER_COMPILE_COUNT);
         renderer.add(sampler::SPRITE_SYSTEM_COUNT);
         renderer.add(sampler::SPRITE_COUNT);
+        renderer.add(sampler::DISPLAY_OBJECT_UPDATE_COUNT);
+        renderer.add(sampler::DISPLAY_OBJECT_TRANSFORM_COUNT);
+        renderer.add(sampler::INPUT_LATENCY);
+        renderer.add(sampler::IDLE_TASK_COUNT);
+        renderer.add(sampler::IDLE_TASK_RUN_COUNT);
         let initialized = default();
         Self { renderer: Rc::new(RefCell::new(renderer)), frp, initialized }
     }
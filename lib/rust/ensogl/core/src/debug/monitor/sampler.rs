@@ -298,3 +298,49 @@ pub const SHADER_COMPILE_COUNT: Sampler = Sampler {
     err_threshold: 100.0,
     ..DEFAULT_SAMPLER
 };
+
+#[allow(missing_docs)]
+pub const DISPLAY_OBJECT_UPDATE_COUNT: Sampler = Sampler {
+    label: "Display object updates",
+    expr: |s| Some(s.display_object_update_count as f64),
+    warn_threshold: 1000.0,
+    err_threshold: 10_000.0,
+    ..DEFAULT_SAMPLER
+};
+
+#[allow(missing_docs)]
+pub const DISPLAY_OBJECT_TRANSFORM_COUNT: Sampler = Sampler {
+    label: "Display object transforms",
+    expr: |s| Some(s.display_object_transform_count as f64),
+    warn_threshold: 1000.0,
+    err_threshold: 10_000.0,
+    ..DEFAULT_SAMPLER
+};
+
+#[allow(missing_docs)]
+pub const INPUT_LATENCY: Sampler = Sampler {
+    label: "Input latency (ms)",
+    expr: |s| s.input_latency,
+    warn_threshold: 1000.0 / 30.0,
+    err_threshold: 100.0,
+    precision: 2,
+    ..DEFAULT_SAMPLER
+};
+
+#[allow(missing_docs)]
+pub const IDLE_TASK_COUNT: Sampler = Sampler {
+    label: "Idle task queue length",
+    expr: |s| Some(s.idle_task_count as f64),
+    warn_threshold: 1000.0,
+    err_threshold: 10_000.0,
+    ..DEFAULT_SAMPLER
+};
+
+#[allow(missing_docs)]
+pub const IDLE_TASK_RUN_COUNT: Sampler = Sampler {
+    label: "Idle tasks run",
+    expr: |s| Some(s.idle_task_run_count as f64),
+    warn_threshold: 1000.0,
+    err_threshold: 10_000.0,
+    ..DEFAULT_SAMPLER
+};
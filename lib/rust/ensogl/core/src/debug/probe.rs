@@ -0,0 +1,321 @@
+//! Named probes attached to FRP nodes. Every probe remembers the latest value emitted by its node
+//! and the number of emitted values. The probes are listed in a collapsible debug panel, allowing
+//! to diagnose why a component's outputs are not updating without recompiling it with additional
+//! logs.
+//!
+//! Probes are attached with the [`probe!`](crate::probe) macro, for example
+//! `probe!(network, dropdown.selected_entries)`. A probe lives as long as the network it was
+//! attached to. The panel is toggled with the `ctrl alt p` debug shortcut.
+
+use crate::prelude::*;
+use crate::system::web::traits::*;
+
+use crate::animation;
+use crate::frp;
+use crate::system::web;
+use crate::system::web::Closure;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// Values with longer debug representation are truncated in the panel.
+pub const MAX_VALUE_LENGTH: usize = 200;
+const FONTS: &str = "\"SF Mono\", Menlo, Monaco, Consolas, \"Liberation Mono\", monospace";
+const BACKGROUND_COLOR: &str = "#FFFFFFE0";
+const NAME_COLOR: &str = "#008CFF";
+const COUNT_COLOR: &str = "#00000080";
+
+
+
+// =============
+// === Entry ===
+// =============
+
+/// The state of a single probe.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Entry {
+    /// The name of the probe, usually the path of the probed node.
+    pub name:         String,
+    /// The debug representation of the latest value, or [`None`] if no value was emitted yet.
+    pub value:        Option<String>,
+    /// The number of values emitted since the probe was attached.
+    pub update_count: usize,
+}
+
+impl Entry {
+    fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self { name, ..default() }
+    }
+}
+
+
+
+// ================
+// === Registry ===
+// ================
+
+/// All probes alive in the application, in the attachment order.
+#[derive(Debug, Default)]
+pub struct Registry {
+    entries: RefCell<BTreeMap<usize, Entry>>,
+    next_id: Cell<usize>,
+    /// Whether the entries changed since the last [`Registry::take_changed`] call.
+    changed: Cell<bool>,
+}
+
+impl Registry {
+    /// Register a new probe and return its identifier.
+    pub fn register(&self, name: impl Into<String>) -> usize {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        self.entries.borrow_mut().insert(id, Entry::new(name));
+        self.changed.set(true);
+        id
+    }
+
+    /// Record the new value of the probe.
+    pub fn record(&self, id: usize, value: &impl Debug) {
+        if let Some(entry) = self.entries.borrow_mut().get_mut(&id) {
+            let mut value = format!("{value:?}");
+            if let Some((index, _)) = value.char_indices().nth(MAX_VALUE_LENGTH) {
+                value.truncate(index);
+                value.push('…');
+            }
+            entry.value = Some(value);
+            entry.update_count += 1;
+            self.changed.set(true);
+        }
+    }
+
+    /// Remove the probe.
+    pub fn unregister(&self, id: usize) {
+        self.entries.borrow_mut().remove(&id);
+        self.changed.set(true);
+    }
+
+    /// The state of all probes.
+    pub fn entries(&self) -> Vec<Entry> {
+        self.entries.borrow().values().cloned().collect()
+    }
+
+    /// Check whether the entries changed since the last call.
+    pub fn take_changed(&self) -> bool {
+        self.changed.replace(false)
+    }
+}
+
+thread_local! {
+    static REGISTRY: Registry = Registry::default();
+}
+
+/// The state of all probes alive in the application.
+pub fn entries() -> Vec<Entry> {
+    REGISTRY.with(|registry| registry.entries())
+}
+
+
+
+// =============
+// === Probe ===
+// =============
+
+/// A probe registered in the global registry. It is unregistered when dropped, together with the
+/// network it was attached to.
+#[derive(Debug)]
+struct Probe {
+    id: usize,
+}
+
+impl Probe {
+    fn new(name: impl Into<String>) -> Self {
+        let id = REGISTRY.with(|registry| registry.register(name));
+        Self { id }
+    }
+
+    fn record(&self, value: &impl Debug) {
+        REGISTRY.with(|registry| registry.record(self.id, value))
+    }
+}
+
+impl Drop for Probe {
+    fn drop(&mut self) {
+        // The registry may be already destroyed if the probe is dropped during the thread exit.
+        let _ = REGISTRY.try_with(|registry| registry.unregister(self.id));
+    }
+}
+
+/// Attach a named probe to the node. The probe records every value emitted by the node as long as
+/// the network lives. See the [`probe!`](crate::probe) macro for a more convenient version.
+pub fn attach<T: frp::EventOutput>(network: &frp::Network, name: impl Into<String>, node: &T) {
+    let probe = Probe::new(name);
+    frp::extend! { network
+        eval *node ((value) probe.record(value));
+    }
+}
+
+/// Attach a probe to an FRP node, named after the node expression. An explicit name can be
+/// provided as the third argument. See the [`crate::debug::probe`] module docs to learn more.
+///
+/// ```text
+/// probe!(network, dropdown.selected_entries);
+/// probe!(network, dropdown.selected_entries, "Selected entries");
+/// ```
+#[macro_export]
+macro_rules! probe {
+    ($network:expr, $node:expr) => {
+        $crate::debug::probe::attach(&$network, stringify!($node), &$node)
+    };
+    ($network:expr, $node:expr, $name:expr) => {
+        $crate::debug::probe::attach(&$network, $name, &$node)
+    };
+}
+
+
+
+// =============
+// === Panel ===
+// =============
+
+/// The on-screen panel listing all probes. Clicking its header collapses the list.
+#[derive(Debug)]
+struct Panel {
+    root:      web::HtmlDivElement,
+    _on_click: web::EventListenerHandle,
+    _network:  frp::Network,
+}
+
+impl Panel {
+    fn new() -> Self {
+        let root = web::document.create_div_or_panic();
+        root.set_style_or_warn("position", "absolute");
+        root.set_style_or_warn("z-index", "100");
+        root.set_style_or_warn("right", "16px");
+        root.set_style_or_warn("top", "16px");
+        root.set_style_or_warn("max-width", "480px");
+        root.set_style_or_warn("max-height", "60%");
+        root.set_style_or_warn("overflow", "auto");
+        root.set_style_or_warn("padding", "6px 10px");
+        root.set_style_or_warn("border-radius", "6px");
+        root.set_style_or_warn("background", BACKGROUND_COLOR);
+        root.set_style_or_warn("font-family", FONTS);
+        root.set_style_or_warn("font-size", "11px");
+        let header = web::document.create_div_or_panic();
+        header.set_style_or_warn("cursor", "pointer");
+        header.set_style_or_warn("user-select", "none");
+        root.append_or_warn(&header);
+        let list = web::document.create_div_or_panic();
+        list.set_style_or_warn("margin-top", "4px");
+        root.append_or_warn(&list);
+        web::document.body_or_panic().append_or_warn(&root);
+
+        let collapsed = Rc::new(Cell::new(false));
+        let collapsible_list = list.clone();
+        let on_click: web::JsEventHandler<web::Event> = Closure::new(move |_| {
+            collapsed.set(!collapsed.get());
+            let display = if collapsed.get() { "none" } else { "block" };
+            collapsible_list.set_style_or_warn("display", display);
+        });
+        let _on_click = web::add_event_listener(&header, "click", on_click);
+
+        let network = frp::Network::new("debug::probe::Panel");
+        let on_frame = animation::on_before_animations();
+        let (redrawn_header, redrawn_list) = (header.clone(), list.clone());
+        frp::extend! { network
+            changed <- on_frame.filter(|_| REGISTRY.with(|registry| registry.take_changed()));
+            eval_ changed (Self::redraw(&redrawn_header, &redrawn_list));
+        }
+        Self::redraw(&header, &list);
+        Self { root, _on_click, _network: network }
+    }
+
+    fn redraw(header: &web::HtmlDivElement, list: &web::HtmlDivElement) {
+        let entries = entries();
+        header.set_inner_text(&format!("FRP probes ({})", entries.len()));
+        list.set_inner_html("");
+        for entry in entries {
+            let row = web::document.create_div_or_panic();
+            row.set_style_or_warn("white-space", "pre-wrap");
+            row.set_style_or_warn("word-break", "break-all");
+            let name = Self::inline_div(&entry.name);
+            name.set_style_or_warn("color", NAME_COLOR);
+            let count = Self::inline_div(&format!(" [{}] ", entry.update_count));
+            count.set_style_or_warn("color", COUNT_COLOR);
+            let value = Self::inline_div(entry.value.as_deref().unwrap_or("<no value>"));
+            row.append_or_warn(&name);
+            row.append_or_warn(&count);
+            row.append_or_warn(&value);
+            list.append_or_warn(&row);
+        }
+    }
+
+    fn inline_div(text: &str) -> web::HtmlDivElement {
+        let div = web::document.create_div_or_panic();
+        div.set_style_or_warn("display", "inline");
+        div.set_inner_text(text);
+        div
+    }
+}
+
+impl Drop for Panel {
+    fn drop(&mut self) {
+        self.root.remove_from_parent_or_warn();
+    }
+}
+
+thread_local! {
+    static PANEL: RefCell<Option<Panel>> = default();
+}
+
+/// Show or hide the panel listing all probes.
+pub fn toggle_panel() {
+    PANEL.with(|panel| {
+        let mut panel = panel.borrow_mut();
+        if panel.take().is_none() {
+            *panel = Some(Panel::new());
+        }
+    })
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probe_records_latest_value_while_network_lives() {
+        let network = frp::Network::new("test");
+        frp::extend! { network
+            source <- source::<usize>();
+        }
+        crate::probe!(network, source);
+        let probed = || entries().into_iter().find(|entry| entry.name == "source");
+        assert_eq!(probed().unwrap().value, None);
+        source.emit(3);
+        source.emit(5);
+        let entry = probed().unwrap();
+        assert_eq!(entry.value.as_deref(), Some("5"));
+        assert_eq!(entry.update_count, 2);
+        drop(network);
+        assert!(probed().is_none());
+    }
+
+    #[test]
+    fn long_values_are_truncated() {
+        let registry = Registry::default();
+        let id = registry.register("long");
+        registry.record(id, &"x".repeat(2 * MAX_VALUE_LENGTH));
+        let value = registry.entries()[0].value.clone().unwrap();
+        assert_eq!(value.chars().count(), MAX_VALUE_LENGTH + 1);
+        assert!(registry.take_changed());
+        assert!(!registry.take_changed());
+    }
+}
@@ -118,6 +118,9 @@ impl StatsInternal {
         self.stats_data.shader_compile_count = 0;
         self.stats_data.data_upload_count = 0;
         self.stats_data.data_upload_size = 0;
+        self.stats_data.idle_task_run_count = 0;
+        self.stats_data.display_object_update_count = 0;
+        self.stats_data.display_object_transform_count = 0;
         self.stats_data.cpu_and_idle_time = None;
         self.stats_data.gpu_time = None;
     }
@@ -195,25 +198,32 @@ macro_rules! gen_stats {
 }
 
 gen_stats! {
-    fps                  : f64,
-    frame_time           : f64,
+    fps                            : f64,
+    frame_time                     : f64,
     // To learn more why we are not computing CPU-time only, please refer to the docs of
     // [`crate::core::animation::loops::LoopRegistry`].
-    cpu_and_idle_time    : Option<f64>,
-    gpu_time             : Option<f64>,
-    idle_time            : f64,
-    wasm_memory_usage    : u32,
-    gpu_memory_usage     : u32,
-    draw_calls           : Vec<&'static str>,
-    buffer_count         : usize,
-    data_upload_count    : usize,
-    data_upload_size     : u32,
-    sprite_system_count  : usize,
-    sprite_count         : usize,
-    symbol_count         : usize,
-    mesh_count           : usize,
-    shader_count         : usize,
-    shader_compile_count : usize,
+    cpu_and_idle_time              : Option<f64>,
+    gpu_time                       : Option<f64>,
+    idle_time                      : f64,
+    wasm_memory_usage              : u32,
+    gpu_memory_usage               : u32,
+    draw_calls                     : Vec<&'static str>,
+    buffer_count                   : usize,
+    data_upload_count              : usize,
+    data_upload_size               : u32,
+    sprite_system_count            : usize,
+    sprite_count                   : usize,
+    symbol_count                   : usize,
+    mesh_count                     : usize,
+    shader_count                   : usize,
+    shader_compile_count           : usize,
+    idle_task_count                : usize,
+    idle_task_run_count            : usize,
+    display_object_update_count    : usize,
+    display_object_transform_count : usize,
+    // The latency of the last input whose effects were rendered. It is not reset every frame, see
+    // the [`crate::debug::input_latency`] module docs to learn more.
+    input_latency                  : Option<f64>,
 }
 
 impl StatsData {
@@ -0,0 +1,374 @@
+//! Time-travel debugging. When recording is enabled, lightweight snapshots of the application state
+//! are captured periodically. A timeline displayed at the bottom of the screen allows stepping
+//! backwards and forwards through them, restoring the captured state.
+//!
+//! The core captures only the camera position. Other components, like text areas, register their
+//! own capture functions with [`TimeTravel::on_capture`]. Capturing has to be cheap, as it happens
+//! every [`CAPTURE_INTERVAL_MS`]. For example, text areas only keep a reference to their persistent
+//! rope.
+//!
+//! Recording is toggled with the `ctrl alt t` debug shortcut. The `ctrl alt [` and `ctrl alt ]`
+//! shortcuts step backwards and forwards.
+
+use crate::prelude::*;
+use crate::system::web::traits::*;
+
+use crate::animation;
+use crate::control::callback;
+use crate::control::callback::traits::*;
+use crate::display::scene::Extension;
+use crate::display::Scene;
+use crate::frp;
+use crate::system::web;
+
+use enso_data_structures::size_capped_vec_deque::SizeCappedVecDeque;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The time between two consecutive snapshots.
+pub const CAPTURE_INTERVAL_MS: f32 = 1000.0;
+/// The maximum number of kept snapshots. The oldest ones are dropped first.
+pub const MAX_SNAPSHOTS: usize = 300;
+const FONTS: &str = "\"SF Mono\", Menlo, Monaco, Consolas, \"Liberation Mono\", monospace";
+const BACKGROUND_COLOR: &str = "#FFFFFFE0";
+const MARK_COLOR: &str = "#00000030";
+const CURRENT_MARK_COLOR: &str = "#008CFF";
+
+
+
+// ================
+// === Snapshot ===
+// ================
+
+/// A snapshot of the application state, consisting of functions restoring the state of the
+/// individual components.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct Snapshot {
+    /// The time of capturing, in milliseconds since the animation loop start.
+    pub time: f32,
+    #[derivative(Debug = "ignore")]
+    restores: RefCell<Vec<Box<dyn Fn()>>>,
+}
+
+impl Snapshot {
+    fn new(time: f32) -> Self {
+        Self { time, restores: default() }
+    }
+
+    /// Add a function restoring the captured state of a component. It should not keep the
+    /// component alive, as it lives as long as the snapshot.
+    pub fn add(&self, restore: impl Fn() + 'static) {
+        self.restores.borrow_mut().push(Box::new(restore));
+    }
+
+    fn restore(&self) {
+        self.restores.borrow().iter().for_each(|restore| restore());
+    }
+}
+
+
+
+// ================
+// === Timeline ===
+// ================
+
+/// The on-screen timeline, displaying a mark for every snapshot.
+#[derive(Debug)]
+struct Timeline {
+    root:  web::HtmlDivElement,
+    marks: web::HtmlDivElement,
+    label: web::HtmlDivElement,
+}
+
+impl Timeline {
+    fn new() -> Self {
+        let root = web::document.create_div_or_panic();
+        root.set_style_or_warn("display", "none");
+        root.set_style_or_warn("position", "absolute");
+        root.set_style_or_warn("z-index", "100");
+        root.set_style_or_warn("left", "16px");
+        root.set_style_or_warn("right", "16px");
+        root.set_style_or_warn("bottom", "16px");
+        root.set_style_or_warn("padding", "6px 10px");
+        root.set_style_or_warn("border-radius", "6px");
+        root.set_style_or_warn("background", BACKGROUND_COLOR);
+        root.set_style_or_warn("font-family", FONTS);
+        root.set_style_or_warn("font-size", "11px");
+        root.set_style_or_warn("pointer-events", "none");
+        let label = web::document.create_div_or_panic();
+        root.append_or_warn(&label);
+        let marks = web::document.create_div_or_panic();
+        marks.set_style_or_warn("display", "flex");
+        marks.set_style_or_warn("gap", "1px");
+        marks.set_style_or_warn("height", "8px");
+        marks.set_style_or_warn("margin-top", "4px");
+        root.append_or_warn(&marks);
+        web::document.body_or_panic().append_or_warn(&root);
+        Self { root, marks, label }
+    }
+
+    fn set_visible(&self, visible: bool) {
+        let display = if visible { "block" } else { "none" };
+        self.root.set_style_or_warn("display", display);
+    }
+
+    fn redraw(&self, count: usize, current: Option<usize>) {
+        let position = match current {
+            Some(index) => format!("{}/{count}", index + 1),
+            None => "live".into(),
+        };
+        self.label.set_inner_text(&format!("Time travel: {position} ({count} snapshots)"));
+        self.marks.set_inner_html("");
+        for index in 0..count {
+            let mark = web::document.create_div_or_panic();
+            let color = if Some(index) == current { CURRENT_MARK_COLOR } else { MARK_COLOR };
+            mark.set_style_or_warn("flex", "1");
+            mark.set_style_or_warn("max-width", "6px");
+            mark.set_style_or_warn("background", color);
+            self.marks.append_or_warn(&mark);
+        }
+    }
+}
+
+impl Drop for Timeline {
+    fn drop(&mut self) {
+        self.root.remove_from_parent_or_warn();
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+#[derive(Debug)]
+struct Model {
+    capturers:         callback::registry::Ref1<Snapshot>,
+    snapshots:         RefCell<SizeCappedVecDeque<Rc<Snapshot>>>,
+    /// The index of the restored snapshot, or [`None`] if the application is live.
+    current:           Cell<Option<usize>>,
+    recording:         Cell<bool>,
+    frame_time:        Cell<f32>,
+    last_capture_time: Cell<f32>,
+    timeline:          Timeline,
+}
+
+impl Model {
+    fn new() -> Self {
+        let capturers = default();
+        let snapshots = RefCell::new(SizeCappedVecDeque::new(MAX_SNAPSHOTS));
+        let current = default();
+        let recording = default();
+        let frame_time = default();
+        let last_capture_time = Cell::new(f32::NEG_INFINITY);
+        let timeline = Timeline::new();
+        Self { capturers, snapshots, current, recording, frame_time, last_capture_time, timeline }
+    }
+
+    fn capture(&self, time: f32) {
+        let snapshot = Snapshot::new(time);
+        self.capturers.run_all(&snapshot);
+        self.snapshots.borrow_mut().push_back(Rc::new(snapshot));
+        self.last_capture_time.set(time);
+        self.redraw_timeline();
+    }
+
+    fn on_frame(&self, time: f32) {
+        self.frame_time.set(time);
+        let is_live = self.current.get().is_none();
+        let capture_due = time - self.last_capture_time.get() >= CAPTURE_INTERVAL_MS;
+        if self.recording.get() && is_live && capture_due {
+            self.capture(time);
+        }
+    }
+
+    fn set_recording(&self, recording: bool) {
+        self.recording.set(recording);
+        self.timeline.set_visible(recording);
+        if !recording {
+            self.current.set(None);
+            *self.snapshots.borrow_mut() = SizeCappedVecDeque::new(MAX_SNAPSHOTS);
+            self.last_capture_time.set(f32::NEG_INFINITY);
+        }
+        self.redraw_timeline();
+    }
+
+    fn step_back(&self) {
+        if !self.recording.get() {
+            return;
+        }
+        let target = match self.current.get() {
+            Some(index) => index.checked_sub(1),
+            None => {
+                // Capture the live state first, so it is possible to step forward back to it.
+                self.capture(self.frame_time.get());
+                self.snapshots.borrow().len().checked_sub(2)
+            }
+        };
+        if let Some(index) = target {
+            self.restore(index);
+        }
+    }
+
+    fn step_forward(&self) {
+        if let Some(index) = self.current.get() {
+            let last = self.snapshots.borrow().len().saturating_sub(1);
+            let next = index + 1;
+            self.restore(next.min(last));
+            if next >= last {
+                self.current.set(None);
+                self.redraw_timeline();
+            }
+        }
+    }
+
+    fn restore(&self, index: usize) {
+        let snapshot = self.snapshots.borrow().get(index).cloned();
+        if let Some(snapshot) = snapshot {
+            self.current.set(Some(index));
+            snapshot.restore();
+            self.redraw_timeline();
+        }
+    }
+
+    fn redraw_timeline(&self) {
+        if self.recording.get() {
+            self.timeline.redraw(self.snapshots.borrow().len(), self.current.get());
+        }
+    }
+}
+
+
+
+// ==================
+// === TimeTravel ===
+// ==================
+
+/// Periodic snapshots of the application state with a timeline allowing to restore them. See the
+/// module docs to learn more. It is a [scene extension](Extension), available with
+/// [`Scene::extension`].
+#[derive(Clone, CloneRef, Debug)]
+pub struct TimeTravel {
+    model:   Rc<Model>,
+    network: frp::Network,
+}
+
+impl TimeTravel {
+    fn new(scene: &Scene) -> Self {
+        let model = Rc::new(Model::new());
+        let network = frp::Network::new("TimeTravel");
+        let on_frame = animation::on_before_animations();
+        frp::extend! { network
+            eval on_frame ([model] (time)
+                model.on_frame(time.since_animation_loop_started.unchecked_raw())
+            );
+        }
+        let time_travel = Self { model, network };
+        time_travel.init_camera_capture(scene);
+        time_travel
+    }
+
+    fn init_camera_capture(&self, scene: &Scene) {
+        let camera = scene.camera();
+        self.on_capture(move |snapshot| {
+            let position = camera.position();
+            let camera = camera.clone_ref();
+            snapshot.add(move || camera.set_position(position));
+        })
+        .forget();
+    }
+
+    /// Register a function capturing the state of a component. It is called for every snapshot
+    /// and should [add](Snapshot::add) a function restoring the captured state to it. Dropping
+    /// the returned handle unregisters the function.
+    pub fn on_capture(&self, f: impl FnMut(&Snapshot) + 'static) -> callback::Handle {
+        self.model.capturers.add(f)
+    }
+
+    /// Check whether the snapshots are being captured.
+    pub fn is_recording(&self) -> bool {
+        self.model.recording.get()
+    }
+
+    /// Start or stop capturing the snapshots. Stopping drops all captured snapshots.
+    pub fn set_recording(&self, recording: bool) {
+        self.model.set_recording(recording)
+    }
+
+    /// Toggle capturing the snapshots.
+    pub fn toggle_recording(&self) {
+        self.set_recording(!self.is_recording())
+    }
+
+    /// Restore the previous snapshot. The periodic capturing is paused until the application is
+    /// back to the live state.
+    pub fn step_back(&self) {
+        self.model.step_back()
+    }
+
+    /// Restore the next snapshot. Stepping forward from the newest snapshot resumes the periodic
+    /// capturing.
+    pub fn step_forward(&self) {
+        self.model.step_forward()
+    }
+
+    /// The number of captured snapshots.
+    pub fn snapshot_count(&self) -> usize {
+        self.model.snapshots.borrow().len()
+    }
+}
+
+impl Extension for TimeTravel {
+    fn init(scene: &Scene) -> Self {
+        Self::new(scene)
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::world::World;
+
+    #[test]
+    fn stepping_through_snapshots() {
+        let world = World::new();
+        let time_travel = world.default_scene.extension::<TimeTravel>();
+        let value = Rc::new(Cell::new(0));
+        let captured_value = value.clone_ref();
+        let _handle = time_travel.on_capture(move |snapshot| {
+            let captured = captured_value.get();
+            let value = captured_value.clone_ref();
+            snapshot.add(move || value.set(captured));
+        });
+        time_travel.set_recording(true);
+        for i in 0..3 {
+            value.set(i);
+            time_travel.model.capture(i as f32);
+        }
+        value.set(3);
+        time_travel.step_back();
+        assert_eq!(value.get(), 2);
+        time_travel.step_back();
+        assert_eq!(value.get(), 1);
+        time_travel.step_forward();
+        assert_eq!(value.get(), 2);
+        time_travel.step_forward();
+        assert_eq!(value.get(), 3);
+        assert_eq!(time_travel.model.current.get(), None);
+        assert_eq!(time_travel.snapshot_count(), 4);
+        time_travel.set_recording(false);
+        assert_eq!(time_travel.snapshot_count(), 0);
+    }
+}
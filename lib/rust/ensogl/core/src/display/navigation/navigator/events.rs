@@ -237,6 +237,11 @@ impl NavigatorEvents {
     fn initialize_wheel_zoom(&mut self) {
         let data = Rc::downgrade(&self.data);
         let listener = self.mouse_manager.on_wheel.add(move |event: &mouse::Wheel| {
+            // The event was handled by a display object under the pointer. See
+            // [`crate::display::object::event::Event::prevent_default`].
+            if event.is_default_prevented() {
+                return;
+            }
             if let Some(data) = data.upgrade() {
                 if event.ctrl_key() {
                     // Prevent zoom event to be handed to the browser. This avoids browser scaling
@@ -266,6 +271,11 @@ impl NavigatorEvents {
     fn initialize_mouse_start_event(&mut self) {
         let data = Rc::downgrade(&self.data);
         let listener = self.mouse_manager.on_down.add(move |event: &mouse::Down| {
+            // The event was handled by a display object under the pointer. See
+            // [`crate::display::object::event::Event::prevent_default`].
+            if event.is_default_prevented() {
+                return;
+            }
             if let Some(data) = data.upgrade() {
                 match event.button() {
                     mouse::MiddleButton => data.set_movement_type(Some(MovementType::Pan)),
@@ -2,6 +2,9 @@
 //! emitted, it is propagated in two stages: capturing and bubbling. Each stage is
 //! configurable and some events propagation can be cancelled. To learn more about the mechanics,
 //! see: https://javascript.info/bubbling-and-capturing.
+//!
+//! Independently of the propagation, a handler can prevent the default action of the event, which
+//! is performed by the code emitting the event, see [`crate::display::Object::dispatch_event`].
 
 use crate::prelude::*;
 
@@ -64,13 +67,14 @@ pub enum Phase {
 #[allow(missing_docs)]
 #[derive(Clone, CloneRef, Debug)]
 pub struct SomeEvent {
-    pub data:       frp::AnyData,
-    state:          Rc<Cell<State>>,
-    current_target: Rc<RefCell<Option<WeakInstance>>>,
+    pub data:          frp::AnyData,
+    state:             Rc<Cell<State>>,
+    current_target:    Rc<RefCell<Option<WeakInstance>>>,
     /// Indicates whether the event participates in the capturing phase.
-    pub captures:   Rc<Cell<bool>>,
+    pub captures:      Rc<Cell<bool>>,
     /// Indicates whether the event participates in the bubbling phase.
-    pub bubbles:    Rc<Cell<bool>>,
+    pub bubbles:       Rc<Cell<bool>>,
+    default_prevented: Rc<Cell<bool>>,
 }
 
 impl SomeEvent {
@@ -84,7 +88,9 @@ impl SomeEvent {
         let current_target = event.current_target.clone_ref();
         let captures = event.captures.clone_ref();
         let bubbles = event.bubbles.clone_ref();
-        Self { data: frp::AnyData::new(event), state, current_target, captures, bubbles }
+        let default_prevented = event.default_prevented.clone_ref();
+        let data = frp::AnyData::new(event);
+        Self { data, state, current_target, captures, bubbles, default_prevented }
     }
 
     /// The [`State]` of the event.
@@ -97,6 +103,11 @@ impl SomeEvent {
         matches!(self.state(), State::RunningCancelled(_) | State::StoppedCancelled(_))
     }
 
+    /// Check whether the default action of the event was prevented by any of the handlers.
+    pub fn is_default_prevented(&self) -> bool {
+        self.default_prevented.get()
+    }
+
     /// Enables or disables bubbling for this event.
     pub fn set_bubbling(&self, value: bool) {
         self.bubbles.set(value);
@@ -186,12 +197,13 @@ impl<T: Debug> Debug for Event<T> {
 #[derivative(Default(bound = "T: Default"))]
 pub struct EventData<T> {
     #[deref]
-    pub payload:    T,
-    target:         Option<WeakInstance>,
-    current_target: Rc<RefCell<Option<WeakInstance>>>,
-    state:          Rc<Cell<State>>,
-    captures:       Rc<Cell<bool>>,
-    bubbles:        Rc<Cell<bool>>,
+    pub payload:       T,
+    target:            Option<WeakInstance>,
+    current_target:    Rc<RefCell<Option<WeakInstance>>>,
+    state:             Rc<Cell<State>>,
+    captures:          Rc<Cell<bool>>,
+    bubbles:           Rc<Cell<bool>>,
+    default_prevented: Rc<Cell<bool>>,
 }
 
 impl<T: Debug> Debug for EventData<T> {
@@ -199,6 +211,7 @@ impl<T: Debug> Debug for EventData<T> {
         f.debug_struct("Event")
             .field("payload", &self.payload)
             .field("state", &self.state.get())
+            .field("default_prevented", &self.default_prevented.get())
             .finish()
     }
 }
@@ -209,8 +222,34 @@ impl<T: 'static> Event<T> {
         let current_target = Rc::new(RefCell::new(target.clone()));
         let captures = Rc::new(Cell::new(true));
         let bubbles = Rc::new(Cell::new(true));
-        let data = Rc::new(EventData { payload, target, current_target, state, captures, bubbles });
-        Self { data }
+        let default_prevented = default();
+        let data = EventData {
+            payload,
+            target,
+            current_target,
+            state,
+            captures,
+            bubbles,
+            default_prevented,
+        };
+        Self { data: Rc::new(data) }
+    }
+
+    /// Prevents the default action of the event, like panning the scene on mouse wheel, while the
+    /// event is still propagated. The default action is performed by the code which emitted the
+    /// event, if the event was not prevented. For mouse events, the default action of the browser
+    /// is prevented as well.
+    ///
+    /// See: https://developer.mozilla.org/en-US/docs/Web/API/Event/preventDefault.
+    pub fn prevent_default(&self) {
+        self.default_prevented.set(true);
+    }
+
+    /// Check whether [`Self::prevent_default`] was called for this event.
+    ///
+    /// See: https://developer.mozilla.org/en-US/docs/Web/API/Event/defaultPrevented.
+    pub fn is_default_prevented(&self) -> bool {
+        self.default_prevented.get()
     }
 
     /// Prevents further propagation of the current event in the capturing and bubbling phases. It
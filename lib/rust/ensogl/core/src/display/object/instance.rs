@@ -1159,6 +1159,7 @@ use crate::display::scene::layer::AnySymbolPartition;
 use crate::display::scene::layer::Layer;
 use crate::display::scene::layer::WeakLayer;
 use crate::display::scene::Scene;
+use crate::display::scene::WeakPointerTargetRegistry;
 
 use enso_types::Dim;
 use nalgebra::Matrix4;
@@ -1738,7 +1739,20 @@ pub struct HierarchyFrp {
     pub on_transformed:     frp::Stream<()>,
     /// Fires during the scene refresh if this object was resized due to auto-layout rules.
     pub on_resized:         frp::Sampler<Vector2>,
+    /// Whether the display object is effectively visible, that is, whether it is attached to a
+    /// visible parent, assigned to a scene layer and, if its viewport culling is enabled,
+    /// overlapping the viewport of its layer's camera. Unlike [`Self::on_show`], it does not
+    /// report objects attached to the visible hierarchy which are not rendered in any layer.
+    /// Viewport culling is enabled by [`InstanceDef::init_when_shown`].
+    pub is_shown:           frp::Sampler<bool>,
+    /// Fires when the display object becomes effectively visible. See [`Self::is_shown`].
+    pub shown:              frp::Stream<()>,
+    /// Fires when the display object stops being effectively visible, for example when its parent
+    /// was hidden, when it was detached from its scene layer or when it was moved out of the
+    /// viewport. See [`Self::is_shown`].
+    pub hidden:             frp::Stream<()>,
     on_show_source:         frp::Source<(Option<Scene>, Option<WeakLayer>)>,
+    culled_source:          frp::Source<bool>,
     on_hide_source:         frp::Source<Option<Scene>>,
     on_transformed_source:  frp::Source<()>,
     on_resized_source:      frp::Source<Vector2>,
@@ -1758,11 +1772,21 @@ impl HierarchyFrp {
             on_layer_change_source <- source();
             on_transformed_source <- source();
             on_resized_source <- source();
+            culled_source <- source();
             on_show <- on_show_source.batch().iter();
             on_hide <- on_hide_source.batch().iter();
             on_layer_change <- on_layer_change_source.batch().iter();
             on_transformed <- on_transformed_source.batch().iter();
             on_resized <- on_resized_source.batch().iter().sampler();
+            visible <- bool(&on_hide, &on_show);
+            has_layer <- on_layer_change.map(|(_, _, layer, _)| layer.is_some());
+            culled <- culled_source.batch().iter();
+            // The visibility, the layer and the culling are reported in separate batches, so the
+            // state is debounced to not report the object as shown in between them.
+            shown_state <- all_with3(&visible, &has_layer, &culled, |v, l, c| *v && *l && !*c);
+            is_shown <- shown_state.debounce().on_change().sampler();
+            shown <- is_shown.on_true();
+            hidden <- is_shown.on_false();
         }
         Self {
             on_show_source,
@@ -1770,11 +1794,15 @@ impl HierarchyFrp {
             on_layer_change_source,
             on_transformed_source,
             on_resized_source,
+            culled_source,
             on_show,
             on_hide,
             on_layer_change,
             on_transformed,
             on_resized,
+            is_shown,
+            shown,
+            hidden,
         }
     }
 }
@@ -1786,7 +1814,8 @@ impl HierarchyFrp {
 // =======================
 
 /// The part of display object model related to its hierarchy.
-#[derive(Debug, Deref)]
+#[derive(Derivative, Deref)]
+#[derivative(Debug)]
 pub struct HierarchyModel {
     #[deref]
     frp:              HierarchyFrp,
@@ -1801,6 +1830,20 @@ pub struct HierarchyModel {
     /// Layer where the object is displayed. It may be set to by user or inherited from the parent.
     layer:            RefCell<Option<LayerAssignment>>,
     dirty:            dirty::Flags,
+    /// Incremented every time the global transformation or the layer of this object changes.
+    revision:         Cell<usize>,
+    /// Incremented every time the revision of this object or any of its descendants changes.
+    subtree_revision: Cell<usize>,
+    /// The z-index of this object, relative to the z-index of its parent.
+    z_index:          Cell<i32>,
+    /// The z-index of this object within its layer. It is the sum of z-indexes of this object and
+    /// all of its ancestors.
+    global_z_index:   Cell<i32>,
+    /// Whether the object is reported as hidden when it does not overlap the viewport.
+    viewport_culling: Cell<bool>,
+    /// Functions to run the next time the object is shown. See [`InstanceDef::init_when_shown`].
+    #[derivative(Debug = "ignore")]
+    pending_init:     RefCell<Vec<Box<dyn FnOnce()>>>,
 }
 
 impl HierarchyModel {
@@ -1814,6 +1857,12 @@ impl HierarchyModel {
         let assigned_layer = default();
         let layer = default();
         let dirty = dirty::Flags::new(&parent_bind);
+        let revision = default();
+        let subtree_revision = default();
+        let z_index = default();
+        let global_z_index = default();
+        let viewport_culling = default();
+        let pending_init = default();
         Self {
             frp,
             visible,
@@ -1824,12 +1873,34 @@ impl HierarchyModel {
             assigned_layer,
             layer,
             dirty,
+            revision,
+            subtree_revision,
+            z_index,
+            global_z_index,
+            viewport_culling,
+            pending_init,
         }
     }
 }
 
 
 
+// ======================
+// === UpdateCounters ===
+// ======================
+
+/// Counters of the work performed during a display object hierarchy update. They are reported to
+/// the scene statistics and can be inspected in the performance monitor.
+#[derive(Debug, Default)]
+struct UpdateCounters {
+    /// The number of objects visited during the update.
+    updated:     Cell<usize>,
+    /// The number of objects whose global transformation matrix changed.
+    transformed: Cell<usize>,
+}
+
+
+
 // =======================
 // === Hierarchy Logic ===
 // =======================
@@ -1843,6 +1914,12 @@ impl Model {
         self.layer.borrow().as_ref().and_then(|t| t.layer.upgrade())
     }
 
+    /// Get the layer explicitly assigned to this object, if any. Unlike [`Self::display_layer`],
+    /// it does not include the layer inherited from the parent.
+    fn assigned_display_layer(&self) -> Option<Layer> {
+        self.assigned_layer.borrow().as_ref().and_then(|t| t.layer.upgrade())
+    }
+
     /// Add this object to the provided scene layer. Do not use this method explicitly. Use layers'
     /// methods instead.
     pub(crate) fn add_to_display_layer(&self, layer: &Layer) {
@@ -2002,7 +2079,50 @@ impl Model {
         self.refresh_layout();
         let parent_origin =
             self.parent().map_or(Matrix4::identity(), |parent| parent.transformation_matrix());
-        self.update_with_origin(scene, parent_origin, false, false, None);
+        let counters = UpdateCounters::default();
+        self.update_with_origin(scene, parent_origin, false, false, None, &counters);
+        let stats = &scene.stats;
+        stats.mod_display_object_update_count(|t| t + counters.updated.get());
+        stats.mod_display_object_transform_count(|t| t + counters.transformed.get());
+    }
+
+    /// The revision of this object. It is incremented every time the global transformation or the
+    /// layer of this object changes during the display object hierarchy update.
+    pub fn revision(&self) -> usize {
+        self.revision.get()
+    }
+
+    /// The revision of the subtree starting at this object. It is incremented every time the
+    /// revision of this object or of any of its descendants changes. It can be used to skip
+    /// processing of subtrees which did not change since they were last seen.
+    pub fn subtree_revision(&self) -> usize {
+        self.subtree_revision.get()
+    }
+
+    /// The z-index of this object, relative to the z-index of its parent.
+    pub fn z_index(&self) -> i32 {
+        self.z_index.get()
+    }
+
+    /// The z-index of this object within its layer, as computed during the last display object
+    /// hierarchy update. It is the sum of z-indexes of this object and all of its ancestors.
+    pub fn global_z_index(&self) -> i32 {
+        self.global_z_index.get()
+    }
+
+    /// Set the z-index of this object, relative to the z-index of its parent. Within a layer (and
+    /// within a symbol partition), shapes of objects with a higher z-index are drawn above, and
+    /// receive mouse events before, the shapes of objects with a lower z-index. The z-index does
+    /// not affect the order of objects placed in different layers. Shapes of objects with the same
+    /// z-index are ordered as described in the docs of [`Layer`].
+    ///
+    /// The z-index is clamped to the [`layer::Z_INDEX_RANGE`] range. Changing the z-index
+    /// re-instantiates the shapes of this object and of its descendants, so it should not be
+    /// changed every frame.
+    pub fn set_z_index(&self, z_index: i32) {
+        if self.z_index.replace(z_index) != z_index {
+            self.dirty.new_layer.set();
+        }
     }
 
     /// Update the display object tree transformations based on the parent object origin. See docs
@@ -2016,6 +2136,12 @@ impl Model {
     /// the sprite instance to a new one on layer change. Please note that updating the display
     /// object hierarchy during its refresh is a very complex operation and an extra care should be
     /// taken when modifying this logic.
+    ///
+    /// # Incremental Updates
+    /// Only the branches containing modified objects are traversed. Moreover, if the recomputed
+    /// transformation matrix of an object did not change (e.g. the position was set to its current
+    /// value), its children are not updated, unless they are dirty themselves. Returns [`true`] if
+    /// the revision of any object in the subtree changed.
     fn update_with_origin(
         &self,
         scene: &Scene,
@@ -2023,7 +2149,11 @@ impl Model {
         parent_origin_changed: bool,
         parent_layers_changed: bool,
         parent_layer: Option<&LayerAssignment>,
-    ) {
+        counters: &UpdateCounters,
+    ) -> bool {
+        counters.updated.modify(|t| *t += 1);
+        let mut subtree_changed = false;
+
         // === Scene Layers Update ===
 
         let has_new_parent = self.dirty.new_parent.check();
@@ -2031,7 +2161,12 @@ impl Model {
         let assigned_layer = assigned_layer_ref.as_ref();
         let assigned_layers_changed = self.dirty.new_layer.take().check();
         let has_assigned_layer = assigned_layer.is_some();
-        let layer_changed = if assigned_layers_changed {
+        // The parent was already updated, so its global z-index is up to date.
+        let parent_z_index = self.parent().map_or(0, |parent| parent.global_z_index());
+        let global_z_index = parent_z_index + self.z_index.get();
+        let z_index_changed = self.global_z_index.replace(global_z_index) != global_z_index;
+        let layer_changed = if assigned_layers_changed || z_index_changed {
+            // Changing the z-index requires the shapes to be re-instantiated in the same layer.
             // We might as well check here if assigned layers were not removed and accidentally the
             // inherited layers are not the same as previously assigned ones, but this is so rare
             // situation that we are not checking it to optimize the performance of this case.
@@ -2075,9 +2210,17 @@ impl Model {
         let new_parent_origin = is_origin_dirty.as_some(parent_origin);
         let parent_origin_label = if new_parent_origin.is_some() { "new" } else { "old" };
         debug_span!("Update with {} parent origin.", parent_origin_label).in_scope(|| {
-            let origin_changed = self.transformation.borrow_mut().update(new_parent_origin);
+            let matrix_changed = self.transformation.borrow_mut().update(new_parent_origin);
+            if matrix_changed {
+                counters.transformed.modify(|t| *t += 1);
+            }
+            // An object attached to a new parent is always reported as transformed, as its
+            // transformation was never reported in the context of the new parent.
+            let origin_changed = matrix_changed || has_new_parent;
             let new_origin = self.transformation.borrow().matrix;
             if origin_changed || layer_changed {
+                self.revision.modify(|t| *t += 1);
+                subtree_changed = true;
                 self.dirty.modified_children.unset_all();
                 if origin_changed {
                     trace!("Self origin changed.");
@@ -2096,7 +2239,8 @@ impl Model {
                                     true,
                                     layer_changed,
                                     new_layer,
-                                )
+                                    counters,
+                                );
                             });
                         });
                     })
@@ -2114,12 +2258,13 @@ impl Model {
                         self.dirty.modified_children.take().iter().for_each(|ix| {
                             self.children.borrow().get(ix).and_then(|t| t.upgrade()).for_each(
                                 |child| {
-                                    child.update_with_origin(
+                                    subtree_changed |= child.update_with_origin(
                                         scene,
                                         new_origin,
                                         false,
                                         layer_changed,
                                         new_layer,
+                                        counters,
                                     )
                                 },
                             )
@@ -2129,11 +2274,16 @@ impl Model {
             }
         });
         self.dirty.transformation.unset();
+        self.update_viewport_culling(scene);
         if self.dirty.computed_size.check() {
             self.on_resized_source.emit(self.layout.computed_size.get());
         }
         self.dirty.computed_size.unset();
         self.dirty.new_parent.unset();
+        if subtree_changed {
+            self.subtree_revision.modify(|t| *t += 1);
+        }
+        subtree_changed
     }
 
     /// Hide all removed children and show this display object if it was attached to a new parent.
@@ -2166,6 +2316,86 @@ impl Model {
     }
 }
 
+
+
+// === Lazy Initialization ===
+
+impl InstanceDef {
+    /// Run the provided function the first time the object is [shown](HierarchyFrp::shown), or
+    /// immediately if it is shown already. The function is dropped after it runs, so it may keep
+    /// strong references to this object without leaking it.
+    ///
+    /// Enables viewport culling of this object: from now on, it is reported as hidden when its
+    /// bounds, given by its computed size, do not overlap the viewport of its layer's camera.
+    /// Objects without a computed size are never culled, as their children can be placed anywhere.
+    pub fn init_when_shown(&self, f: impl FnOnce() + 'static) {
+        if self.is_shown.value() {
+            f();
+        } else {
+            self.pending_init.borrow_mut().push(Box::new(f));
+            self.enable_viewport_culling();
+        }
+    }
+
+    fn enable_viewport_culling(&self) {
+        if self.viewport_culling.replace(true) {
+            return;
+        }
+        // The network is extended once per object, and only for objects using lazy
+        // initialization, so that the cost is not paid by all display objects.
+        let network = &self.network;
+        let weak = self.downgrade();
+        frp::extend! { network
+            scene_on_show <- self.on_show._0();
+            scene_on_layer_change <- self.on_layer_change._0();
+            scene <- any(scene_on_show, scene_on_layer_change);
+            eval scene ([weak] (scene) {
+                let Some(scene) = scene else { return };
+                let Some(instance) = weak.upgrade() else { return };
+                scene.add_viewport_observer(&instance);
+            });
+            eval_ self.shown ([weak] {
+                let Some(instance) = weak.upgrade() else { return };
+                let pending_init = mem::take(&mut *instance.pending_init.borrow_mut());
+                pending_init.into_iter().for_each(|f| f());
+            });
+        }
+    }
+}
+
+impl Model {
+    /// Check whether the object overlaps the viewport of its layer's camera, and report it as
+    /// culled otherwise. Does nothing if the viewport culling of this object is disabled.
+    pub(crate) fn update_viewport_culling(&self, scene: &Scene) {
+        if self.viewport_culling.get() {
+            self.culled_source.emit(!self.overlaps_viewport(scene));
+        }
+    }
+
+    fn overlaps_viewport(&self, scene: &Scene) -> bool {
+        let size = self.layout.computed_size.get();
+        if size.x == 0.0 && size.y == 0.0 {
+            return true;
+        }
+        let camera = self.display_layer().map_or_else(|| scene.camera(), |layer| layer.camera());
+        let matrix = camera.view_projection_matrix() * self.transformation_matrix();
+        let corners = [Vector2(0.0, 0.0), Vector2(size.x, 0.0), Vector2(0.0, size.y), size];
+        let mut min = Vector2(f32::INFINITY, f32::INFINITY);
+        let mut max = Vector2(f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for corner in corners {
+            let clip_space = matrix * Vector4(corner.x, corner.y, 0.0, 1.0);
+            let ndc = clip_space.xy() / clip_space.w;
+            // A degenerated camera, e.g. before the first layout of the scene, can not cull.
+            if clip_space.w <= 0.0 || !ndc.x.is_finite() || !ndc.y.is_finite() {
+                return true;
+            }
+            min = min.inf(&ndc);
+            max = max.sup(&ndc);
+        }
+        min.x <= 1.0 && max.x >= -1.0 && min.y <= 1.0 && max.y >= -1.0
+    }
+}
+
 impl InstanceDef {
     /// Checks if the provided object is child of the current one.
     pub fn has_child<T: Object>(&self, child: &T) -> bool {
@@ -2509,6 +2739,10 @@ pub struct EventModel {
     capturing_fan:      frp::Fan,
     bubbling_fan:       frp::Fan,
     focused_descendant: RefCell<Option<WeakInstance>>,
+    hit_area:           Cell<HitArea>,
+    /// The pointer target registry of the scene the object was last shown in. The expanded hit
+    /// area is registered there, see [`InstanceDef::set_hit_area`].
+    pointer_targets:    RefCell<Option<WeakPointerTargetRegistry>>,
 }
 
 impl EventModel {
@@ -2516,10 +2750,78 @@ impl EventModel {
         let capturing_fan = frp::Fan::new(network);
         let bubbling_fan = frp::Fan::new(network);
         let focused_descendant = default();
+        let hit_area = default();
+        let pointer_targets = default();
         frp::extend! { network
             source <- source();
         }
-        Self { source, capturing_fan, bubbling_fan, focused_descendant }
+        Self { source, capturing_fan, bubbling_fan, focused_descendant, hit_area, pointer_targets }
+    }
+}
+
+
+// === Hit Area ===
+
+/// The area in which a display object receives pointer events. See [`InstanceDef::set_hit_area`]
+/// to learn more.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum HitArea {
+    /// Pointer events are received exactly where the shapes of the object are drawn.
+    #[default]
+    Shape,
+    /// Pointer events are received where the shapes of the object are drawn, and additionally in
+    /// the bounding box of the object (as defined by its computed size) expanded by the given
+    /// number of pixels in every direction.
+    Expanded(f32),
+    /// Shapes of the object and of its descendants do not receive pointer events. The events pass
+    /// through them to the background, or to an object with an expanded hit area below.
+    None,
+}
+
+impl HitArea {
+    /// The expansion of the bounding box, if the hit area is [`HitArea::Expanded`].
+    pub fn expansion(self) -> Option<f32> {
+        match self {
+            HitArea::Expanded(expansion) => Some(expansion),
+            _ => None,
+        }
+    }
+}
+
+impl Model {
+    /// The area in which this object receives pointer events.
+    pub fn hit_area(&self) -> HitArea {
+        self.event.hit_area.get()
+    }
+}
+
+impl InstanceDef {
+    /// Set the area in which this object receives pointer events. See [`HitArea`] to learn about
+    /// the available options.
+    ///
+    /// The expanded hit areas are checked only if the pointer is not over any shape, so they never
+    /// cover shapes of other objects. Small targets, like ports or resize handles, can be made
+    /// easier to hit without drawing invisible shapes around them. Decorative objects can be made
+    /// click-through with [`HitArea::None`].
+    ///
+    /// The expanded hit area is registered in the scene the object is shown in, so it can be set
+    /// before the object is attached to the scene.
+    pub fn set_hit_area(&self, hit_area: HitArea) {
+        let old_hit_area = self.event.hit_area.replace(hit_area);
+        if old_hit_area.expansion().is_none() {
+            self.register_hit_area();
+        }
+    }
+
+    /// Register the expanded hit area in the pointer target registry of the scene the object is
+    /// shown in. Does nothing if the hit area is not expanded or the object was not shown yet.
+    fn register_hit_area(&self) {
+        if self.hit_area().expansion().is_some() {
+            let registry = self.event.pointer_targets.borrow().as_ref().and_then(|t| t.upgrade());
+            if let Some(registry) = registry {
+                registry.insert_hit_area(&self.clone_ref().into());
+            }
+        }
     }
 }
 
@@ -2550,6 +2852,16 @@ impl InstanceDef {
                 event.finish_propagation();
             });
         }
+        let weak = self.downgrade();
+        frp::extend! { network
+            eval self.on_show ([] ((scene, _)) {
+                let Some(scene) = scene else { return };
+                let Some(instance) = weak.upgrade() else { return };
+                let registry = scene.mouse.pointer_target_registry.downgrade();
+                *instance.event.pointer_targets.borrow_mut() = Some(registry);
+                instance.register_hit_area();
+            });
+        }
         self
     }
 
@@ -2710,6 +3022,13 @@ impl InstanceDef {
         self.event.source.emit(event);
     }
 
+    fn dispatch_event<T>(&self, payload: T) -> bool
+    where T: 'static {
+        let event = self.new_event(payload);
+        self.event.source.emit(&event);
+        !event.is_default_prevented()
+    }
+
     pub(crate) fn resume_event(&self, event: event::SomeEvent) {
         self.event.source.emit(event);
     }
@@ -4390,6 +4709,45 @@ pub trait ObjectOps: Object + AutoLayoutOps + LayoutOps {
         self.display_object().def.global_position()
     }
 
+    /// The revision of this object. See [`Model::revision`] to learn more.
+    fn revision(&self) -> usize {
+        self.display_object().def.revision()
+    }
+
+    /// The revision of the subtree starting at this object. See [`Model::subtree_revision`] to
+    /// learn more.
+    fn subtree_revision(&self) -> usize {
+        self.display_object().def.subtree_revision()
+    }
+
+    /// The z-index of this object, relative to the z-index of its parent. See
+    /// [`Model::set_z_index`] to learn more.
+    fn z_index(&self) -> i32 {
+        self.display_object().def.z_index()
+    }
+
+    /// The z-index of this object within its layer. See [`Model::global_z_index`] to learn more.
+    fn global_z_index(&self) -> i32 {
+        self.display_object().def.global_z_index()
+    }
+
+    /// Set the z-index of this object, relative to the z-index of its parent. See
+    /// [`Model::set_z_index`] to learn more.
+    fn set_z_index(&self, z_index: i32) {
+        self.display_object().def.set_z_index(z_index)
+    }
+
+    /// The area in which this object receives pointer events. See [`HitArea`] to learn more.
+    fn hit_area(&self) -> HitArea {
+        self.display_object().def.hit_area()
+    }
+
+    /// Set the area in which this object receives pointer events. See
+    /// [`InstanceDef::set_hit_area`] to learn more.
+    fn set_hit_area(&self, hit_area: HitArea) {
+        self.display_object().def.set_hit_area(hit_area)
+    }
+
 
     // === Information ===
 
@@ -4407,6 +4765,12 @@ pub trait ObjectOps: Object + AutoLayoutOps + LayoutOps {
         self.display_object().def.display_layer()
     }
 
+    /// Get the layer explicitly assigned to this object, if any. Unlike [`Self::display_layer`],
+    /// it does not include the layer inherited from the parent.
+    fn assigned_display_layer(&self) -> Option<Layer> {
+        self.display_object().def.assigned_display_layer()
+    }
+
     /// Add another display object as a child to this display object. Children will inherit all
     /// transformations of their parents.
     fn add_child<T: Object + ?Sized>(&self, child: &T) {
@@ -4454,6 +4818,12 @@ pub trait ObjectOps: Object + AutoLayoutOps + LayoutOps {
         self.display_object().def.is_visible()
     }
 
+    /// Defer an expensive initialization, like text shaping or loading textures, until the object
+    /// is displayed for the first time. See [`InstanceDef::init_when_shown`] to learn more.
+    fn init_when_shown(&self, f: impl FnOnce() + 'static) {
+        self.display_object().def.init_when_shown(f)
+    }
+
 
     // === EventModel ===
 
@@ -4470,6 +4840,14 @@ pub trait ObjectOps: Object + AutoLayoutOps + LayoutOps {
         self.display_object().def.emit_event_without_bubbling(event)
     }
 
+    /// Emit a new event and check whether its default action should be performed. Returns `false`
+    /// if any of the handlers called [`event::Event::prevent_default`], similarly to the
+    /// JavaScript `dispatchEvent` function.
+    fn dispatch_event<T>(&self, event: T) -> bool
+    where T: 'static {
+        self.display_object().def.dispatch_event(event)
+    }
+
     /// Get event stream for bubbling events. See docs of [`event::Event`] to learn more.
     fn on_event<T>(&self) -> frp::Stream<event::Event<T>>
     where T: frp::Data {
@@ -4567,6 +4945,8 @@ impl<T: Object + ?Sized> GenericLayoutApi for T {}
 #[cfg(test)]
 mod hierarchy_tests {
     use super::*;
+    use crate::display::camera::Camera2d;
+    use crate::display::scene::PointerTargetId;
     use crate::display::world::World;
     use enso_frp::microtasks;
     use std::f32::consts::PI;
@@ -4900,6 +5280,100 @@ mod hierarchy_tests {
         assert_eq!(node3.global_position(), Vector3::new(7.0, 6.0, 0.0));
     }
 
+    #[test]
+    fn incremental_update_test() {
+        let world = World::new();
+        let scene = &world.default_scene;
+        let node1 = Instance::new();
+        let node2 = Instance::new();
+        let node3 = Instance::new();
+        node1.add_child(&node2);
+        node1.add_child(&node3);
+        update(&node1, scene);
+        let revisions = || [node1.revision(), node2.revision(), node3.revision()];
+        let subtree_revisions =
+            || [node1.subtree_revision(), node2.subtree_revision(), node3.subtree_revision()];
+        let initial_revisions = revisions();
+        let initial_subtree_revisions = subtree_revisions();
+
+        // Setting the current value does not change the matrices.
+        node1.set_position(Vector3::new(0.0, 0.0, 0.0));
+        update(&node1, scene);
+        assert_eq!(revisions(), initial_revisions);
+        assert_eq!(subtree_revisions(), initial_subtree_revisions);
+
+        // Only the modified branch is updated.
+        node2.set_position(Vector3::new(1.0, 0.0, 0.0));
+        update(&node1, scene);
+        let [rev1, rev2, rev3] = initial_revisions;
+        let [sub1, sub2, sub3] = initial_subtree_revisions;
+        assert_eq!(revisions(), [rev1, rev2 + 1, rev3]);
+        assert_eq!(subtree_revisions(), [sub1 + 1, sub2 + 1, sub3]);
+
+        // Moving the parent updates all children.
+        node1.set_position(Vector3::new(0.0, 1.0, 0.0));
+        update(&node1, scene);
+        assert_eq!(revisions(), [rev1 + 1, rev2 + 2, rev3 + 1]);
+        assert_eq!(subtree_revisions(), [sub1 + 2, sub2 + 2, sub3 + 1]);
+    }
+
+    #[test]
+    fn z_index_test() {
+        let world = World::new();
+        let scene = &world.default_scene;
+        let node1 = Instance::new();
+        let node2 = Instance::new();
+        let node3 = Instance::new();
+        node1.add_child(&node2);
+        node2.add_child(&node3);
+        node1.set_z_index(2);
+        node3.set_z_index(-1);
+        update(&node1, scene);
+        assert_eq!([node1.z_index(), node2.z_index(), node3.z_index()], [2, 0, -1]);
+        let global_z_indexes =
+            || [node1.global_z_index(), node2.global_z_index(), node3.global_z_index()];
+        assert_eq!(global_z_indexes(), [2, 2, 1]);
+
+        node2.set_z_index(3);
+        update(&node1, scene);
+        assert_eq!(global_z_indexes(), [2, 5, 4]);
+
+        // Re-attaching an object to a new parent updates the z-index of its subtree.
+        node1.add_child(&node3);
+        update(&node1, scene);
+        assert_eq!(global_z_indexes(), [2, 5, 1]);
+    }
+
+    #[test]
+    fn hit_area_test() {
+        let world = World::new();
+        let scene = &world.default_scene;
+        let registry = &scene.mouse.pointer_target_registry;
+        let root = Instance::new();
+        root.show();
+        let node1 = Instance::new();
+        let node2 = Instance::new();
+        assert_eq!(node1.hit_area(), HitArea::Shape);
+
+        // The hit areas are registered in the scene once the objects are shown.
+        node1.set_hit_area(HitArea::Expanded(4.0));
+        root.add_child(&node1);
+        root.add_child(&node2);
+        assert!(registry.hit_areas().is_empty());
+        update(&root, scene);
+        assert_eq!(registry.hit_areas(), vec![node1.clone_ref()]);
+        node2.set_hit_area(HitArea::Expanded(8.0));
+        assert_eq!(registry.hit_areas().len(), 2);
+        let target = PointerTargetId::HitArea { object: node1.id() };
+        assert_eq!(registry.get(target).map(|(_, object)| object), Some(node1.clone_ref()));
+
+        // Objects are unregistered when their hit area is no longer expanded, or when dropped.
+        node1.set_hit_area(HitArea::None);
+        assert_eq!(registry.hit_areas(), vec![node2.clone_ref()]);
+        drop(node2);
+        assert!(registry.hit_areas().is_empty());
+    }
+
     #[test]
     fn parent_test() {
         let node1 = Instance::new();
@@ -5204,6 +5678,77 @@ mod hierarchy_tests {
         assert_eq!(node3.display_layer().as_ref(), Some(&layer1));
     }
 
+    #[test]
+    fn shown_and_hidden_test() {
+        let world = World::new();
+        let scene = &world.default_scene;
+
+        let layer = Layer::new("0");
+        let node1 = Instance::new();
+        let node2 = Instance::new();
+        let initialized = Rc::new(Cell::new(0));
+        node2.init_when_shown(f!(initialized.set(initialized.get() + 1)));
+        node1.show();
+        node1.add_child(&node2);
+        update(&node1, scene);
+        assert!(node2.is_visible());
+        assert!(!node2.is_shown.value());
+        assert_eq!(initialized.get(), 0);
+
+        node1.add_to_display_layer(&layer);
+        update(&node1, scene);
+        assert!(node2.is_shown.value());
+        assert_eq!(initialized.get(), 1);
+
+        node2.unset_parent();
+        update(&node1, scene);
+        assert!(!node2.is_shown.value());
+
+        node1.add_child(&node2);
+        update(&node1, scene);
+        assert!(node2.is_shown.value());
+        assert_eq!(initialized.get(), 1);
+
+        let node3 = Instance::new();
+        node2.add_child(&node3);
+        update(&node1, scene);
+        node3.init_when_shown(f!(initialized.set(initialized.get() + 1)));
+        assert_eq!(initialized.get(), 2);
+    }
+
+    #[test]
+    fn viewport_culling_test() {
+        let world = World::new();
+        let scene = &world.default_scene;
+        let camera = Camera2d::new();
+        camera.set_screen(100.0, 100.0);
+        camera.update(scene);
+        let layer = Layer::new_with_camera("0", &camera);
+
+        let root = Instance::new();
+        let node = Instance::new();
+        node.set_size((10.0, 10.0));
+        node.set_xy((500.0, 0.0));
+        let initialized = Rc::new(Cell::new(false));
+        node.init_when_shown(f!(initialized.set(true)));
+        root.show();
+        root.add_to_display_layer(&layer);
+        root.add_child(&node);
+        update(&root, scene);
+        assert!(node.is_visible());
+        assert!(!node.is_shown.value());
+        assert!(!initialized.get());
+
+        node.set_xy((-5.0, -5.0));
+        update(&root, scene);
+        assert!(node.is_shown.value());
+        assert!(initialized.get());
+
+        node.set_xy((0.0, -500.0));
+        update(&root, scene);
+        assert!(!node.is_shown.value());
+    }
+
     #[test]
     fn focus_consistency_test() {
         //         obj_root
@@ -5419,6 +5964,24 @@ mod hierarchy_tests {
             "bubbling_2"
         ]);
         drop(network);
+
+
+        // === Preventing the default action ===
+
+        let network = frp::Network::new("network");
+        let out: Rc<RefCell<Vec<&'static str>>> = default();
+        frp::extend! { network
+            eval capturing_2 ([out] (e) {
+                e.prevent_default();
+                out.borrow_mut().push("capturing_2")
+            });
+            eval_ bubbling_1 (out.borrow_mut().push("bubbling_1"));
+        }
+
+        assert!(!obj_3.dispatch_event::<f32>(0.0));
+        assert_eq!(&*out.borrow(), &["capturing_2", "bubbling_1"]);
+        assert!(obj_2.dispatch_event::<u32>(0));
+        drop(network);
     }
 }
 
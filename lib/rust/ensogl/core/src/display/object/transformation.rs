@@ -182,19 +182,25 @@ impl CachedTransformation {
         default()
     }
 
-    /// Update the transformation matrix if it was out of date. Returns [`true`] if the update was
-    /// needed.
+    /// Update the transformation matrix if it was out of date. Returns [`true`] if the matrix
+    /// changed. Please note that recomputing the matrix does not have to change it, for example,
+    /// when the position was set to its current value, or the new origin is the same as the old
+    /// one.
     pub fn update(&mut self, new_origin: Option<Matrix4<f32>>) -> bool {
-        let changed = self.dirty || new_origin.is_some();
-        if changed {
+        let needs_update = self.dirty || new_origin.is_some();
+        if needs_update {
             if self.dirty {
                 self.transform_matrix = self.transform.matrix();
                 self.dirty = false;
             }
             new_origin.into_iter().for_each(|t| self.origin = t);
-            self.matrix = self.origin * self.transform_matrix;
+            let matrix = self.origin * self.transform_matrix;
+            let changed = matrix != self.matrix;
+            self.matrix = matrix;
+            changed
+        } else {
+            false
         }
-        changed
     }
 }
 
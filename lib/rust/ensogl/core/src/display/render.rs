@@ -6,6 +6,7 @@
 // ==============
 
 pub mod composer;
+pub mod memory;
 pub mod pass;
 pub mod passes;
 pub mod pipeline;
@@ -0,0 +1,113 @@
+//! The GPU memory mode. The [`MemoryMode::Low`] mode trades the rendering quality for a smaller GPU
+//! memory footprint, allowing the application to run on integrated GPUs and older devices, which
+//! otherwise lose the WebGL context when running out of memory:
+//! - The screen is rendered with the device pixel ratio of 1.0, even on high-dpi monitors, which
+//!   shrinks every screen-sized buffer.
+//! - The intermediate layer and mask color buffers use 4 bits per channel instead of 8.
+//! - Components drop GPU resources not used at the moment, like the atlases of fonts no text is
+//!   displayed with, and skip preparing data speculatively. Such components should check
+//!   [`is_low`].
+//!
+//! The mode has to be set before the world is created, usually with
+//! [`Application::new_with_memory_mode`](crate::application::Application::new_with_memory_mode).
+
+use crate::prelude::*;
+use crate::system::gpu::*;
+
+
+
+// ==================
+// === MemoryMode ===
+// ==================
+
+/// The GPU memory mode. See the module docs to learn more.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[allow(missing_docs)]
+pub enum MemoryMode {
+    #[default]
+    Full,
+    Low,
+}
+
+impl MemoryMode {
+    /// Check whether the memory footprint is reduced.
+    pub fn is_low(self) -> bool {
+        self == MemoryMode::Low
+    }
+
+    /// The internal format and the item type of the intermediate color buffers.
+    pub fn intermediate_color_format(self) -> (texture::AnyInternalFormat, texture::AnyItemType) {
+        match self {
+            MemoryMode::Full => (texture::Rgba8.into(), texture::item_type::u8.into()),
+            MemoryMode::Low => (texture::Rgba4.into(), texture::item_type::u16_4_4_4_4.into()),
+        }
+    }
+
+    /// The number of bytes of a single pixel of the intermediate color buffers.
+    pub fn intermediate_color_bytes_per_pixel(self) -> usize {
+        match self {
+            MemoryMode::Full => 4,
+            MemoryMode::Low => 2,
+        }
+    }
+
+    /// The device pixel ratio the screen is rendered with, or [`None`] if the ratio provided by
+    /// the browser is used.
+    pub fn device_pixel_ratio(self) -> Option<f32> {
+        self.is_low().then_some(1.0)
+    }
+}
+
+
+
+// ===================
+// === Global Mode ===
+// ===================
+
+thread_local! {
+    static MODE: Cell<MemoryMode> = default();
+}
+
+/// The current memory mode.
+pub fn mode() -> MemoryMode {
+    MODE.with(|mode| mode.get())
+}
+
+/// Set the memory mode. It affects only the worlds and GPU resources created afterwards.
+pub fn set_mode(memory_mode: MemoryMode) {
+    MODE.with(|mode| mode.set(memory_mode))
+}
+
+/// Check whether the memory footprint is reduced. Components should not keep unused GPU resources
+/// nor prepare data speculatively in such a case.
+pub fn is_low() -> bool {
+    mode().is_low()
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn low_mode_halves_intermediate_buffers() {
+        let full = MemoryMode::Full;
+        let low = MemoryMode::Low;
+        assert_eq!(
+            full.intermediate_color_bytes_per_pixel(),
+            2 * low.intermediate_color_bytes_per_pixel()
+        );
+        assert_eq!(low.intermediate_color_format().0, texture::AnyInternalFormat::Rgba4);
+        assert_eq!(full.device_pixel_ratio(), None);
+        assert_eq!(low.device_pixel_ratio(), Some(1.0));
+        assert!(!is_low());
+        set_mode(MemoryMode::Low);
+        assert!(is_low());
+        set_mode(MemoryMode::Full);
+    }
+}
@@ -3,6 +3,7 @@
 use crate::prelude::*;
 use crate::system::gpu::*;
 
+use crate::display::render::memory;
 use crate::display::render::pass;
 use crate::display::scene;
 use crate::display::scene::layer;
@@ -238,16 +239,17 @@ impl Framebuffers {
             mag_filter: texture::MagFilter::NEAREST,
             ..default()
         };
-        let framebuffer = |color, id| {
-            let out_color = pass::OutputDefinition::new_rgba(color);
+        let framebuffer = |out_color: pass::OutputDefinition, id| {
             let out_id = pass::OutputDefinition::new(id, rgba, tex_type, id_params);
             let tex_color = instance.new_screen_texture(&out_color);
             let tex_id = instance.new_screen_texture(&out_id);
             instance.new_framebuffer(&[&tex_color, &tex_id])
         };
-        let composed = framebuffer("color", "id")?;
-        let mask = framebuffer("mask_color", "mask_id")?;
-        let layer = framebuffer("layer_color", "layer_id")?;
+        let (format, item_type) = memory::mode().intermediate_color_format();
+        let intermediate = |color| pass::OutputDefinition::new(color, format, item_type, default());
+        let composed = framebuffer(pass::OutputDefinition::new_rgba("color"), "id")?;
+        let mask = framebuffer(intermediate("mask_color"), "mask_id")?;
+        let layer = framebuffer(intermediate("layer_color"), "layer_id")?;
         Ok(Self { composed, mask, layer })
     }
 }
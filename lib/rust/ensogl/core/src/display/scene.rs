@@ -13,6 +13,7 @@ use crate::control::io::keyboard::dom::KeyboardManager;
 use crate::control::io::mouse;
 use crate::control::io::mouse::MouseManager;
 use crate::data::dirty;
+use crate::debug::input_latency;
 use crate::debug::stats::Stats;
 use crate::display;
 use crate::display::camera::Camera2d;
@@ -69,12 +70,16 @@ shared! { PointerTargetRegistry
 #[derive(Debug)]
 pub struct ShapeRegistryData {
     mouse_target_map : HashMap<PointerTargetId, (PointerTarget_DEPRECATED, display::object::Instance)>,
+    /// Display objects with expanded hit areas. They are kept as weak references, as they are not
+    /// unregistered explicitly when dropped.
+    hit_area_map : HashMap<display::object::Id, (PointerTarget_DEPRECATED, display::object::WeakInstance)>,
 }
 
 impl {
     fn new(background_pointer_target: &PointerTarget_DEPRECATED, background: &display::object::Instance) -> Self {
         let mouse_target_map = default();
-        Self {mouse_target_map} . init(background_pointer_target, background)
+        let hit_area_map = default();
+        Self {mouse_target_map,hit_area_map} . init(background_pointer_target, background)
     }
 
     pub fn insert
@@ -88,7 +93,34 @@ impl {
     }
 
     pub fn get(&self, target:PointerTargetId) -> Option<(PointerTarget_DEPRECATED, display::object::Instance)> {
-        self.mouse_target_map.get(&target).cloned()
+        match target {
+            PointerTargetId::HitArea { object } => self.hit_area_map.get(&object).and_then(
+                |(target, weak)| weak.upgrade().map(|object| (target.clone_ref(), object))),
+            _ => self.mouse_target_map.get(&target).cloned(),
+        }
+    }
+
+    /// Register a display object with an expanded hit area. See [`display::object::HitArea`].
+    pub fn insert_hit_area(&mut self, display_object:&display::object::Instance) {
+        let id = display_object.id();
+        let weak = display_object.downgrade();
+        self.hit_area_map.entry(id).or_insert_with(|| (PointerTarget_DEPRECATED::new(), weak));
+    }
+
+    /// All alive display objects with expanded hit areas. Objects which were dropped or whose hit
+    /// area is no longer expanded are unregistered.
+    pub fn hit_areas(&mut self) -> Vec<display::object::Instance> {
+        let mut objects = Vec::with_capacity(self.hit_area_map.len());
+        self.hit_area_map.retain(|_, (_, weak)| {
+            match weak.upgrade().filter(|t| t.hit_area().expansion().is_some()) {
+                Some(object) => {
+                    objects.push(object);
+                    true
+                }
+                None => false,
+            }
+        });
+        objects
     }
 }}
 
@@ -224,7 +256,9 @@ impl Mouse {
                     last_pressed_elem.borrow_mut().insert(button, current_target);
                     pointer_target_registry.with_mouse_target(current_target, |t, d| {
                         t.emit_mouse_down(button);
-                        d.emit_event(event.clone());
+                        if !d.dispatch_event(event.clone()) {
+                            event.prevent_default();
+                        }
                     });
                 }
             }),
@@ -243,7 +277,9 @@ impl Mouse {
                         });
                     }
                     pointer_target_registry.with_mouse_target(current_target, |t, d| {
-                        d.emit_event(event.clone());
+                        if !d.dispatch_event(event.clone()) {
+                            event.prevent_default();
+                        }
                         t.emit_mouse_up(button);
                     });
                     frp_deprecated.up.emit(button);
@@ -255,7 +291,9 @@ impl Mouse {
                 if display_mode.get().allow_mouse_events() {
                     frp_deprecated.wheel.emit(());
                     pointer_target_registry.with_mouse_target(target.get(), |_, d| {
-                        d.emit_event(event.clone());
+                        if !d.dispatch_event(event.clone()) {
+                            event.prevent_default();
+                        }
                     });
                 }
             }),
@@ -288,15 +326,56 @@ impl Mouse {
     }
 
     /// Discover what object the mouse pointer is on.
-    fn handle_over_and_out_events(&self) {
+    fn handle_over_and_out_events(&self, scene: &SceneData) {
         let opt_new_target = PointerTargetId::decode_from_rgba(self.pointer_target_encoded.get());
         let new_target = opt_new_target.unwrap_or_else(|err| {
             error!("{err}");
             default()
         });
+        let new_target = self.apply_hit_area_overrides(scene, new_target);
         self.switch_target(new_target);
     }
 
+    /// Adjust the target read from the GPU according to the hit areas of display objects (see
+    /// [`display::object::HitArea`]). If the target, or any of its ancestors, passes the pointer
+    /// events through, the target is replaced with the background. If the target is the
+    /// background, and the pointer is in an expanded hit area of a visible display object, that
+    /// object becomes the target. In case of overlapping expanded hit areas, the object with the
+    /// highest z-index wins.
+    fn apply_hit_area_overrides(
+        &self,
+        scene: &SceneData,
+        target: PointerTargetId,
+    ) -> PointerTargetId {
+        let registry = &self.pointer_target_registry;
+        let passes_through = |object: &display::object::Instance| {
+            object.rev_parent_chain().iter().any(|t| t.hit_area() == display::object::HitArea::None)
+        };
+        let target = match registry.get(target) {
+            Some((_, object)) if target.is_symbol() && passes_through(&object) =>
+                PointerTargetId::Background,
+            _ => target,
+        };
+        if !target.is_background() {
+            return target;
+        }
+        let screen_position = self.last_position.get() - self.scene_frp.shape.value().center();
+        let hit_areas = registry.hit_areas();
+        let hovered = hit_areas.into_iter().filter(|object| {
+            let Some(expansion) = object.hit_area().expansion() else { return false };
+            if !object.is_visible() || passes_through(object) {
+                return false;
+            }
+            let position = scene.screen_to_object_space(object, screen_position);
+            let size = object.computed_size();
+            let min = Vector2(-expansion, -expansion);
+            let max = size + Vector2(expansion, expansion);
+            position.x >= min.x && position.y >= min.y && position.x <= max.x && position.y <= max.y
+        });
+        let hovered = hovered.max_by_key(|object| object.global_z_index());
+        hovered.map_or(target, |object| PointerTargetId::HitArea { object: object.id() })
+    }
+
     /// Set mouse target and emit hover events if necessary.
     fn switch_target(&self, new_target: PointerTargetId) {
         let current_target = self.target.get();
@@ -409,6 +488,7 @@ impl Keyboard {
                 if frp_keyboard::is_browser_shortcut(event) {
                     event.prevent_default();
                 }
+                input_latency::register_input(event.time_stamp());
                 input.down.emit(frp_keyboard::KeyWithCode::from(event));
             }
         ));
@@ -1014,6 +1094,9 @@ pub struct SceneData {
     disable_context_menu: EventListenerHandle,
     #[derivative(Debug = "ignore")]
     on_set_context: RefCell<Vec<Weak<dyn Fn(Option<&Context>)>>>,
+    /// Display objects culled when they do not overlap the viewport. They are checked again every
+    /// time a camera changes.
+    viewport_observers: RefCell<HashSet<display::object::WeakInstance>>,
 }
 
 impl SceneData {
@@ -1067,6 +1150,7 @@ impl SceneData {
         let shader_compiler = default();
         let initial_shader_compilation = default();
         let on_set_context = default();
+        let viewport_observers = default();
         Self {
             display_object,
             display_mode,
@@ -1090,6 +1174,7 @@ impl SceneData {
             extensions,
             disable_context_menu,
             on_set_context,
+            viewport_observers,
         }
         .init()
     }
@@ -1231,17 +1316,9 @@ impl SceneData {
         object: &impl display::Object,
         screen_pos: Vector2,
     ) -> Vector2 {
-        let origin_world_space = Vector4(0.0, 0.0, 0.0, 1.0);
-        let layer = object.display_layer();
-        let camera = layer.map_or(self.camera(), |l| l.camera());
-        let origin_clip_space = camera.view_projection_matrix() * origin_world_space;
+        let camera = self.object_camera(object);
         if let Some(inv_object_matrix) = object.transformation_matrix().try_inverse() {
-            let shape = camera.screen();
-            let clip_space_z = origin_clip_space.z;
-            let clip_space_x = origin_clip_space.w * 2.0 * screen_pos.x / shape.width;
-            let clip_space_y = origin_clip_space.w * 2.0 * screen_pos.y / shape.height;
-            let clip_space = Vector4(clip_space_x, clip_space_y, clip_space_z, origin_clip_space.w);
-            let world_space = camera.inversed_view_projection_matrix() * clip_space;
+            let world_space = Self::screen_to_camera_world_space(&camera, screen_pos);
             (inv_object_matrix * world_space).xy()
         } else {
             warn!(
@@ -1252,6 +1329,96 @@ impl SceneData {
         }
     }
 
+    /// Transforms screen position to the world coordinate system of the provided layer, taking its
+    /// camera into account.
+    pub fn screen_to_layer_space(&self, layer: &Layer, screen_pos: Vector2) -> Vector2 {
+        Self::screen_to_camera_world_space(&layer.camera(), screen_pos).xy()
+    }
+
+    /// Transforms a position in the object (display object) coordinate system to the screen
+    /// coordinate system. It is the inverse of [`Self::screen_to_object_space`].
+    pub fn object_to_screen_space(
+        &self,
+        object: &impl display::Object,
+        object_pos: Vector2,
+    ) -> Vector2 {
+        let camera = self.object_camera(object);
+        let object_space = Vector4(object_pos.x, object_pos.y, 0.0, 1.0);
+        let world_space = object.transformation_matrix() * object_space;
+        let clip_space = camera.view_projection_matrix() * world_space;
+        let shape = camera.screen();
+        let screen_x = clip_space.x / clip_space.w * shape.width / 2.0;
+        let screen_y = clip_space.y / clip_space.w * shape.height / 2.0;
+        Vector2(screen_x, screen_y)
+    }
+
+    /// Transforms a position in the coordinate system of the `source` object to the coordinate
+    /// system of the `target` object. The objects may be placed on different layers, with
+    /// different cameras.
+    pub fn object_to_object_space(
+        &self,
+        source: &impl display::Object,
+        target: &impl display::Object,
+        source_pos: Vector2,
+    ) -> Vector2 {
+        let screen_pos = self.object_to_screen_space(source, source_pos);
+        self.screen_to_object_space(target, screen_pos)
+    }
+
+    /// Transforms a position in the DOM client coordinate system (with the origin in the top left
+    /// corner of the scene canvas and the y-axis pointing down, as used by DOM events) to the
+    /// screen coordinate system (with the origin in the center of the scene and the y-axis
+    /// pointing up).
+    pub fn client_to_screen_space(&self, client_pos: Vector2) -> Vector2 {
+        let shape = self.shape().value();
+        Vector2(client_pos.x - shape.width / 2.0, shape.height / 2.0 - client_pos.y)
+    }
+
+    /// Check the viewport culling of the object every time a camera changes. See
+    /// [`display::object::InstanceDef::init_when_shown`].
+    pub(crate) fn add_viewport_observer(&self, object: &display::object::Instance) {
+        self.viewport_observers.borrow_mut().insert(object.downgrade());
+    }
+
+    fn update_viewport_observers(&self) {
+        let mut observers = self.viewport_observers.borrow_mut();
+        observers.retain(|observer| observer.exists());
+        let objects: Vec<_> = observers.iter().filter_map(|observer| observer.upgrade()).collect();
+        drop(observers);
+        for object in objects {
+            object.update_viewport_culling(self);
+        }
+    }
+
+    /// The camera used to render the object. It is the camera of the object's layer, or the main
+    /// camera if the object is not displayed in any layer.
+    fn object_camera(&self, object: &impl display::Object) -> Camera2d {
+        let layer = object.display_layer();
+        layer.map_or(self.camera(), |l| l.camera())
+    }
+
+    /// Transforms screen position to the world coordinate system of the given camera. The depth of
+    /// the result is chosen so that it lies on the `z = 0` plane of the world. A degenerated screen
+    /// (e.g. of zero width before the first layout) is handled gracefully.
+    fn screen_to_camera_world_space(camera: &Camera2d, screen_pos: Vector2) -> Vector4<f32> {
+        let origin_world_space = Vector4(0.0, 0.0, 0.0, 1.0);
+        let origin_clip_space = camera.view_projection_matrix() * origin_world_space;
+        let shape = camera.screen();
+        let to_clip_space = |pos: f32, size: f32| {
+            let valid_size = size.is_finite() && size != 0.0;
+            if valid_size {
+                origin_clip_space.w * 2.0 * pos / size
+            } else {
+                0.0
+            }
+        };
+        let clip_space_z = origin_clip_space.z;
+        let clip_space_x = to_clip_space(screen_pos.x, shape.width);
+        let clip_space_y = to_clip_space(screen_pos.y, shape.height);
+        let clip_space = Vector4(clip_space_x, clip_space_y, clip_space_z, origin_clip_space.w);
+        camera.inversed_view_projection_matrix() * clip_space
+    }
+
     /// Register the given function to be called when the GL context is changed. The callback will
     /// be unregistered when the returned handle is dropped.
     #[must_use]
@@ -1394,6 +1561,54 @@ impl Scene {
     }
 }
 
+
+
+// === Pointer Position ===
+
+impl Scene {
+    /// A sampler of the mouse pointer position in the coordinate system of the provided object.
+    /// It is updated when the pointer moves, when the camera changes, and when the object is
+    /// transformed, so it stays correct when the pointer is still but the object moves under it.
+    /// The FRP nodes are created in the provided network, which should be owned by the caller.
+    pub fn pointer_position_in_object_space(
+        &self,
+        network: &frp::Network,
+        object: &impl display::Object,
+    ) -> frp::Sampler<Vector2> {
+        let scene = self.clone_ref();
+        let object = object.display_object().clone_ref();
+        let mouse_position = &self.mouse.frp_deprecated.position;
+        frp::extend! { network
+            refresh <- any_(&self.frp.camera_changed, &object.on_transformed);
+            refreshed_position <- mouse_position.sample(&refresh);
+            screen_position <- any(mouse_position, &refreshed_position);
+            position <- screen_position.map(move |p| scene.screen_to_object_space(&object, *p));
+            position <- position.sampler();
+        }
+        position
+    }
+
+    /// A sampler of the mouse pointer position in the world coordinate system of the provided
+    /// layer. It is updated when the pointer moves and when the camera changes. The FRP nodes are
+    /// created in the provided network, which should be owned by the caller.
+    pub fn pointer_position_in_layer_space(
+        &self,
+        network: &frp::Network,
+        layer: &Layer,
+    ) -> frp::Sampler<Vector2> {
+        let scene = self.clone_ref();
+        let layer = layer.clone_ref();
+        let mouse_position = &self.mouse.frp_deprecated.position;
+        frp::extend! { network
+            refreshed_position <- mouse_position.sample(&self.frp.camera_changed);
+            screen_position <- any(mouse_position, &refreshed_position);
+            position <- screen_position.map(move |p| scene.screen_to_layer_space(&layer, *p));
+            position <- position.sampler();
+        }
+        position
+    }
+}
+
 impl system::gpu::context::Display for Scene {
     fn device_context_handler(&self) -> &system::gpu::context::DeviceContextHandler {
         self.no_mut_access.device_context_handler()
@@ -1468,8 +1683,12 @@ impl Scene {
             self.frp.frame_time_source.emit(time.since_animation_loop_started.unchecked_raw());
             // Please note that `update_camera` is called first as it may trigger FRP events
             // which may change display objects layout.
-            scene_was_dirty |= self.update_camera(self);
+            let camera_changed = self.update_camera(self);
+            scene_was_dirty |= camera_changed;
             self.display_object.update(self);
+            if camera_changed {
+                self.update_viewport_observers();
+            }
             UpdateStatus { scene_was_dirty, pointer_position_changed: false }
         })
     }
@@ -1491,7 +1710,7 @@ impl Scene {
                 context.profiler.measure_data_upload(|| {
                     scene_was_dirty |= self.update_symbols();
                 });
-                self.mouse.handle_over_and_out_events();
+                self.mouse.handle_over_and_out_events(self);
                 scene_was_dirty |= self.shader_compiler.run(context, time);
             }
             pointer_position_changed |= self.pointer_position_changed.get();
@@ -17,6 +17,7 @@ use crate::display::symbol;
 use crate::display::symbol::RenderGroup;
 use crate::display::symbol::SymbolId;
 use crate::display::Context;
+use crate::system::gpu::data::BufferPartitionId;
 
 use enso_data_structures::dependency_graph::DependencyGraph;
 use enso_shapely::shared;
@@ -114,7 +115,8 @@ bitflags::bitflags! {
 /// symbol A will be drawn first, below symbol B!
 ///
 /// # Symbol Instance Ordering
-/// Within a layer, instances of a symbol are ordered first by partition, and then partially by
+/// Within a layer, instances of a symbol are ordered first by partition, then by the z-index of
+/// their display objects (see [`display::object::Instance::set_z_index`]), and then partially by
 /// creation-order.
 ///
 /// Partitions created earlier are drawn below partitions created later. Within a partition, if two
@@ -286,17 +288,20 @@ impl Layer {
         object.display_object().remove_from_display_layer(self);
     }
 
-    /// Instantiate the provided [`ShapeProxy`].
+    /// Instantiate the provided [`ShapeProxy`] in the given symbol partition, with the given
+    /// z-index. See [`display::object::Instance::set_z_index`] to learn more about z-indexes.
     pub fn instantiate<S>(
         &self,
         data: &S::ShapeData,
         symbol_partition: SymbolPartitionId,
+        z_index: i32,
     ) -> (ShapeInstance<S>, LayerShapeBinding)
     where
         S: Shape,
     {
+        let buffer_partition = symbol_partition.buffer_partition(z_index);
         let (shape_system_info, symbol_id, shape_instance, global_instance_id) =
-            self.shape_system_registry.instantiate(data, symbol_partition);
+            self.shape_system_registry.instantiate(data, buffer_partition);
         self.add_shape(shape_system_info, symbol_id);
         (shape_instance, LayerShapeBinding::new(self, global_instance_id))
     }
@@ -1152,6 +1157,22 @@ pub struct SymbolPartitionId {
     index: usize,
 }
 
+/// The range of z-indexes distinguished within a symbol partition. Z-indexes outside of this range
+/// are clamped to it.
+pub const Z_INDEX_RANGE: RangeInclusive<i32> = -16..=16;
+
+impl SymbolPartitionId {
+    /// The buffer partition containing the instances of this symbol partition with the given
+    /// z-index. Every symbol partition is divided into one buffer partition per z-index in the
+    /// [`Z_INDEX_RANGE`] range. Empty buffer partitions do not use any space in the buffers.
+    fn buffer_partition(self, z_index: i32) -> BufferPartitionId {
+        let (min, max) = (*Z_INDEX_RANGE.start(), *Z_INDEX_RANGE.end());
+        let levels = (max - min + 1) as usize;
+        let level = (z_index.clamp(min, max) - min) as usize;
+        BufferPartitionId { index: self.index * levels + level }
+    }
+}
+
 impl<S: Shape> LayerSymbolPartition<S> {
     /// Add the display object to this symbol partition and remove it from a layer it was assigned
     /// to, if any.
@@ -1379,6 +1400,66 @@ impl Default for Masked {
 
 
 
+// =================
+// === Promotion ===
+// =================
+
+/// Moves a display object to another layer at runtime and returns it to its previous layer
+/// afterwards. It is used by popup-style components, like a dropdown list promoted to an overlay
+/// layer while open, so they are not drawn between the surrounding content.
+///
+/// The object keeps its place in the display object hierarchy, so its FRP state is preserved. Its
+/// sublayers, like masked layers of scroll areas, follow it to the new layer, and it is picked and
+/// transformed with the camera of the layer it is displayed in.
+#[derive(Clone, CloneRef, Debug)]
+pub struct Promotion {
+    object:   display::object::Instance,
+    /// The layer explicitly assigned to the object before it was promoted, or [`None`] if it
+    /// inherited the layer of its parent. Is [`None`] if the object is not promoted.
+    previous: Rc<RefCell<Option<Option<WeakLayer>>>>,
+}
+
+impl Promotion {
+    /// Constructor.
+    pub fn new(object: &impl display::Object) -> Self {
+        let object = object.display_object().clone_ref();
+        let previous = default();
+        Self { object, previous }
+    }
+
+    /// Move the object to the layer. If it is already promoted, its previous layer is still the
+    /// one it had before the first promotion.
+    pub fn promote(&self, layer: &Layer) {
+        let mut previous = self.previous.borrow_mut();
+        if previous.is_none() {
+            *previous = Some(self.object.assigned_display_layer().map(|l| l.downgrade()));
+        }
+        layer.add(&self.object);
+    }
+
+    /// Return the object to its layer from before the promotion. Does nothing if it is not
+    /// promoted.
+    pub fn demote(&self) {
+        let previous = self.previous.borrow_mut().take();
+        if let Some(previous) = previous {
+            match previous.and_then(|layer| layer.upgrade()) {
+                Some(layer) => layer.add(&self.object),
+                None =>
+                    if let Some(layer) = self.object.assigned_display_layer() {
+                        layer.remove(&self.object);
+                    },
+            }
+        }
+    }
+
+    /// Check whether the object is moved to another layer.
+    pub fn is_promoted(&self) -> bool {
+        self.previous.borrow().is_some()
+    }
+}
+
+
+
 // ===============
 // === LayerId ===
 // ===============
@@ -1486,14 +1567,12 @@ pub struct ShapeSystemRegistryData {
 impl {
     /// Instantiate the provided [`ShapeProxy`].
     pub fn instantiate<S>
-    (&mut self, data: &S::ShapeData, symbol_partition: SymbolPartitionId) -> (ShapeSystemInfo, SymbolId, ShapeInstance<S>, symbol::GlobalInstanceId)
+    (&mut self, data: &S::ShapeData, buffer_partition: BufferPartitionId) -> (ShapeSystemInfo, SymbolId, ShapeInstance<S>, symbol::GlobalInstanceId)
     where S : Shape {
         self.with_get_or_register_mut::<S,_,_>(data, |entry| {
             let system = entry.shape_system;
             let system_id = ShapeSystem::<S>::id();
-            let SymbolPartitionId { index } = symbol_partition;
-            let partition_id = crate::system::gpu::data::BufferPartitionId { index };
-            let (shape_instance, global_instance_id) = system.instantiate(partition_id);
+            let (shape_instance, global_instance_id) = system.instantiate(buffer_partition);
             let symbol_id = system.sprite_system().symbol.id;
             let above = S::always_above().to_vec();
             let below = S::always_below().to_vec();
@@ -1787,6 +1866,10 @@ impl PartialSemigroup<&ScissorBox> for ScissorBox {
 /// shader output), which corresponds to equation [`BlendEquation::Add`], source
 /// [`BlendEquation::One`] and destination [`BlendEquation::OneMinusSrcAlpha`].
 ///
+/// The most common blend modes are available as constants: [`BlendMode::NORMAL`],
+/// [`BlendMode::ADDITIVE`], [`BlendMode::MULTIPLY`], and [`BlendMode::SCREEN`]. For example, an
+/// additive layer can be used to render glow or heatmap overlays, as overlapping shapes accumulate
+/// their colors instead of covering each other.
 ///
 /// To learn more about blending in general, see a [Learn OpenGL tutorial about blending][tutorial].
 ///
@@ -1794,7 +1877,7 @@ impl PartialSemigroup<&ScissorBox> for ScissorBox {
 /// [equation]: https://developer.mozilla.org/en-US/docs/Web/API/WebGLRenderingContext/blendEquationSeparate
 /// [color]: https://developer.mozilla.org/en-US/docs/Web/API/WebGLRenderingContext/blendColor
 /// [tutorial]: https://learnopengl.com/Advanced-OpenGL/Blending
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct BlendMode {
     /// Blend equation used for RGB components.
     pub equation_color: BlendEquation,
@@ -1823,7 +1906,7 @@ pub struct BlendMode {
 /// destination pixel values. [`BlendEquation`] selects what function `f` is used.
 ///
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/API/WebGLRenderingContext/blendEquationSeparate
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 #[repr(u32)]
 pub enum BlendEquation {
     #[default]
@@ -1844,7 +1927,7 @@ pub enum BlendEquation {
 /// information.
 ///
 /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/API/WebGLRendering*Context/blendFuncSepare
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u32)]
 pub enum BlendFactor {
     /// Always `0.0`. Will effectively remove given color component from the blend equation.
@@ -1878,6 +1961,37 @@ impl BlendMode {
     pub const PREMULTIPLIED_ALPHA_OVER: BlendMode =
         BlendMode::simple(BlendEquation::Add, BlendFactor::One, BlendFactor::OneMinusSrcAlpha);
 
+    /// Alias for [`Self::PREMULTIPLIED_ALPHA_OVER`], the default blend mode of every layer. Shapes
+    /// are drawn over the colors below them, according to their alpha.
+    pub const NORMAL: BlendMode = Self::PREMULTIPLIED_ALPHA_OVER;
+
+    /// The colors of shapes are added to the colors below them. Overlapping shapes get brighter,
+    /// which is useful for glow effects and heatmaps. The alpha channel is blended as in
+    /// [`Self::NORMAL`] mode.
+    pub const ADDITIVE: BlendMode = BlendMode {
+        src_color: BlendFactor::One,
+        dst_color: BlendFactor::One,
+        ..Self::PREMULTIPLIED_ALPHA_OVER
+    };
+
+    /// The colors of shapes are multiplied by the colors below them, which always results in
+    /// darker colors. Transparent parts of shapes leave the colors below unchanged. The alpha
+    /// channel is blended as in [`Self::NORMAL`] mode.
+    pub const MULTIPLY: BlendMode = BlendMode {
+        src_color: BlendFactor::DstColor,
+        dst_color: BlendFactor::OneMinusSrcAlpha,
+        ..Self::PREMULTIPLIED_ALPHA_OVER
+    };
+
+    /// The inverted colors of shapes are multiplied by the inverted colors below them, and the
+    /// result is inverted, which always results in brighter colors. The alpha channel is blended as
+    /// in [`Self::NORMAL`] mode.
+    pub const SCREEN: BlendMode = BlendMode {
+        src_color: BlendFactor::One,
+        dst_color: BlendFactor::OneMinusSrcColor,
+        ..Self::PREMULTIPLIED_ALPHA_OVER
+    };
+
     /// Pick maximum value for each color component.
     pub const MAX: BlendMode =
         BlendMode::simple(BlendEquation::Max, BlendFactor::One, BlendFactor::One);
@@ -1923,3 +2037,53 @@ impl Default for BlendMode {
         Self::PREMULTIPLIED_ALPHA_OVER
     }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::display::world::World;
+
+    #[test]
+    fn promoted_objects_return_to_their_previous_layer() {
+        let world = World::new();
+        let scene = &world.default_scene;
+        let base = Layer::new("Base");
+        let overlay = Layer::new("Overlay");
+        let root = display::object::Instance::new();
+        let inheriting = display::object::Instance::new();
+        let assigned = display::object::Instance::new();
+        let assigned_child = display::object::Instance::new();
+        root.add_child(&inheriting);
+        root.add_child(&assigned);
+        assigned.add_child(&assigned_child);
+        base.add(&root);
+        let other = Layer::new("Other");
+        other.add(&assigned);
+        root.update(scene);
+
+        let inheriting_promotion = Promotion::new(&inheriting);
+        let assigned_promotion = Promotion::new(&assigned);
+        inheriting_promotion.promote(&overlay);
+        assigned_promotion.promote(&overlay);
+        assert!(assigned_promotion.is_promoted());
+        root.update(scene);
+        assert_eq!(inheriting.display_layer().as_ref(), Some(&overlay));
+        assert_eq!(assigned_child.display_layer().as_ref(), Some(&overlay));
+
+        inheriting_promotion.demote();
+        assigned_promotion.demote();
+        assert!(!assigned_promotion.is_promoted());
+        root.update(scene);
+        assert_eq!(inheriting.display_layer().as_ref(), Some(&base));
+        assert_eq!(inheriting.assigned_display_layer(), None);
+        assert_eq!(assigned.display_layer().as_ref(), Some(&other));
+        assert_eq!(assigned_child.display_layer().as_ref(), Some(&other));
+    }
+}
@@ -3,6 +3,7 @@
 use crate::prelude::*;
 
 use crate::control::io::mouse;
+use crate::display;
 use crate::display::shape::primitive::glsl;
 use crate::display::symbol;
 
@@ -147,12 +148,14 @@ impl Default for PointerTarget_DEPRECATED {
 // === PointerTargetId ===
 // =======================
 
-/// Pointer target ID, a unique ID for an object pointed by the mouse.
+/// Pointer target ID, a unique ID for an object pointed by the mouse. The [`Self::HitArea`] variant
+/// points to an expanded hit area of a display object (see [`display::object::HitArea`]).
 #[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
 #[allow(missing_docs)]
 pub enum PointerTargetId {
     Background,
     Symbol { id: symbol::GlobalInstanceId },
+    HitArea { object: display::object::Id },
 }
 
 impl PointerTargetId {
@@ -191,7 +194,7 @@ impl PointerTargetId {
         self == Self::Background
     }
 
-    /// Check whether this id points to a symbol.
+    /// Check whether this id points to a symbol or to an expanded hit area of a display object.
     pub fn is_symbol(self) -> bool {
         !self.is_background()
     }
@@ -98,6 +98,16 @@ fn glsl_constants() -> String {
     format!("{codes}\n\nconst float CACHED_SHAPE_MAX_DISTANCE = {CACHED_TEXTURE_MAX_DISTANCE:?};")
 }
 
+/// The GLSL math helpers, including the `encode` function writing the instance IDs to the pointer
+/// target texture. Allows shaders not generated by the [`Builder`], like the text glyph shader, to
+/// use the same ID encoding as the shapes.
+pub fn glsl_math() -> String {
+    let redirections = overload::builtin_redirections();
+    let codes = glsl_codes();
+    let math = overload::allow_overloading(MATH);
+    [redirections.as_str(), codes.as_str(), math.as_str()].join("\n\n")
+}
+
 /// The GLSL common code and shared constants (including debug codes).
 pub fn glsl_prelude_and_constants() -> String {
     let constants = glsl_constants();
@@ -184,8 +184,8 @@ impl RawSprite {
     /// Check if given pointer-event-target means this object.
     pub fn is_this_target(&self, target: display::scene::PointerTargetId) -> bool {
         match target {
-            display::scene::PointerTargetId::Background => false,
             display::scene::PointerTargetId::Symbol { id } => self.global_instance_id == id,
+            _ => false,
         }
     }
 
@@ -5,6 +5,7 @@ use crate::control::callback::traits::*;
 use crate::data::dirty::traits::*;
 use crate::display::render::*;
 use crate::prelude::*;
+use crate::system::web::traits::*;
 use wasm_bindgen::prelude::*;
 
 use crate::animation;
@@ -12,6 +13,7 @@ use crate::application::command::FrpNetworkProvider;
 use crate::control::callback;
 use crate::data::dirty;
 use crate::debug;
+use crate::debug::input_latency;
 use crate::debug::stats::Stats;
 use crate::display;
 use crate::display::garbage;
@@ -301,6 +303,9 @@ impl<'t> From<&'t World> for &'t Scene {
 crate::define_endpoints_2! {
     Output {
         after_rendering(),
+        /// The rendering quality, reduced automatically under sustained frame overruns. See the
+        /// [`animation::quality`] module docs to learn more.
+        quality(animation::quality::Quality),
     }
 }
 
@@ -349,7 +354,8 @@ impl WorldDataWithLoop {
             eval on_frame_start ([data] (t) {
                 data.stats.calculate_prev_frame_stats(*t);
                 let gpu_perf_results = data.default_scene.on_frame_start();
-                data.update_stats(*t, gpu_perf_results)
+                data.update_stats(*t, gpu_perf_results);
+                data.update_quality();
             });
             layout_update <- on_before_layout.map(f!((t) data.run_next_frame_layout(*t)));
             _eval <- on_before_rendering.map2(&layout_update,
@@ -486,9 +492,17 @@ impl WorldData {
     fn init(self) -> Self {
         self.init_composer();
         self.init_debug_hotkeys();
+        self.init_memory_mode();
         self
     }
 
+    /// Render the screen with the device pixel ratio required by the memory mode. See the
+    /// [`memory`] module docs to learn more.
+    fn init_memory_mode(&self) {
+        let pixel_ratio = memory::mode().device_pixel_ratio();
+        self.default_scene.dom.root.override_device_pixel_ratio(pixel_ratio);
+    }
+
     fn init_debug_hotkeys(&self) {
         let stats_monitor = self.stats_monitor.clone_ref();
         let display_mode = self.display_mode.clone_ref();
@@ -532,6 +546,14 @@ impl WorldData {
                     } else {
                         error!("Could not lose context: Context lost.");
                     }
+                } else if key == "KeyP" {
+                    debug::probe::toggle_panel();
+                } else if key == "KeyT" {
+                    scene().extension::<debug::time_travel::TimeTravel>().toggle_recording();
+                } else if key == "BracketLeft" {
+                    scene().extension::<debug::time_travel::TimeTravel>().step_back();
+                } else if key == "BracketRight" {
+                    scene().extension::<debug::time_travel::TimeTravel>().step_forward();
                 } else if key.starts_with(digit_prefix) {
                     let code_value = key.trim_start_matches(digit_prefix).parse().unwrap_or(0);
                     if let Some(mode) = glsl::codes::DisplayModes::from_value(code_value) {
@@ -669,14 +691,47 @@ impl WorldData {
     /// See [`Scene::update_rendering`] for information about actions performed in this step.
     ///
     /// Apart from the scene late update, this function also performs garbage collection and actual
-    /// rendering of the scene using updated GPU buffers.
+    /// rendering of the scene using updated GPU buffers. The time left till the end of the frame
+    /// is used to run the idle tasks (see [`animation::idle`]).
     #[profile(Objective)]
     pub fn run_next_frame_rendering(&self, time: animation::TimeInfo, early_status: UpdateStatus) {
         let update_status = self.default_scene.update_rendering(time, early_status);
         self.garbage_collector.mouse_events_handled();
         self.default_scene.render(update_status);
+        self.measure_input_latency();
         self.on.after_frame.run_all(time);
         self.after_rendering.emit(());
+        self.run_idle_tasks(time);
+    }
+
+    fn measure_input_latency(&self) {
+        let now = web::window.performance_or_panic().now();
+        if let Some(measurement) = input_latency::on_frame_rendered(now) {
+            self.stats.set_input_latency(Some(measurement.latency));
+        }
+    }
+
+    fn update_quality(&self) {
+        let frame_time = self.stats.frame_time();
+        if let Some(quality) = animation::quality::governor().on_frame(frame_time) {
+            self.quality.emit(quality);
+        }
+    }
+
+    /// Enable or disable the adaptive quality governor. See the [`animation::quality`] module docs
+    /// to learn more.
+    pub fn set_adaptive_quality_enabled(&self, enabled: bool) {
+        if let Some(quality) = animation::quality::governor().set_enabled(enabled) {
+            self.quality.emit(quality);
+        }
+    }
+
+    #[profile(Debug)]
+    fn run_idle_tasks(&self, time: animation::TimeInfo) {
+        let scheduler = animation::idle::idle_scheduler();
+        let run_count = scheduler.run_in_frame_slack(time);
+        self.stats.set_idle_task_run_count(run_count);
+        self.stats.set_idle_task_count(scheduler.queued_count());
     }
 
     /// Pass object for garbage collection.
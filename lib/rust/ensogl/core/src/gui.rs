@@ -5,8 +5,10 @@
 // === Export ===
 // ==============
 
+pub mod accessibility;
 pub mod component;
 pub mod cursor;
+pub mod error_boundary;
 pub mod style;
 
 pub use component::Widget;
@@ -0,0 +1,349 @@
+//! An accessibility tree, mirroring the interactive components into hidden DOM nodes annotated with
+//! the [ARIA](https://developer.mozilla.org/en-US/docs/Web/Accessibility/ARIA) roles and states.
+//!
+//! Everything in the scene is drawn on a canvas, which is opaque for assistive technology, like
+//! screen readers. Components create a [`Node`] with their [`Role`], and keep its state (the label,
+//! whether it is disabled, pressed, selected, or expanded) up to date. The nodes are not visible,
+//! but they can be focused with the keyboard and read by screen readers.
+//!
+//! The focus is forwarded both ways. When a component gains focus, it should [focus](Frp::focus)
+//! its node, so the assistive technology follows it. When the node is focused by the assistive
+//! technology, the component should focus itself in reaction to [`Frp::focused`]. Activating a
+//! node, by clicking it or pressing Enter or Space on it, is reported by [`Frp::activated`].
+
+use crate::prelude::*;
+use crate::system::web::traits::*;
+
+use crate::display::scene::Extension;
+use crate::display::Scene;
+use crate::frp;
+use crate::system::web;
+use crate::system::web::Closure;
+
+
+
+// ============
+// === Role ===
+// ============
+
+/// The role of a node, defining how it is presented by the assistive technology.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum Role {
+    #[default]
+    Group,
+    Button,
+    ListBox,
+    Option,
+    TabList,
+    Tab,
+    TextBox,
+}
+
+impl Role {
+    /// The ARIA role name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Group => "group",
+            Self::Button => "button",
+            Self::ListBox => "listbox",
+            Self::Option => "option",
+            Self::TabList => "tablist",
+            Self::Tab => "tab",
+            Self::TextBox => "textbox",
+        }
+    }
+
+    /// Check whether the node can be focused. The containers are not focusable, their children are.
+    pub fn is_focusable(self) -> bool {
+        !matches!(self, Self::Group | Self::ListBox | Self::TabList)
+    }
+
+    /// Check whether the node is activated by pressing Enter or Space while focused.
+    pub fn is_activatable(self) -> bool {
+        matches!(self, Self::Button | Self::Option | Self::Tab)
+    }
+}
+
+
+
+// =============
+// === State ===
+// =============
+
+/// The state of a node, reflected in its ARIA attributes. The [`None`] values mean the state does
+/// not apply to the node, for example, a button which can not be toggled is not `pressed`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub struct State {
+    pub label:    ImString,
+    pub disabled: bool,
+    pub pressed:  Option<bool>,
+    pub selected: Option<bool>,
+    pub expanded: Option<bool>,
+}
+
+impl State {
+    /// The ARIA attributes of the node. The attributes with [`None`] values should be removed from
+    /// the DOM element.
+    pub fn attributes(&self) -> [(&'static str, Option<String>); 5] {
+        let flag = |value: Option<bool>| value.map(|value| value.to_string());
+        let label = (!self.label.is_empty()).then(|| self.label.to_string());
+        [
+            ("aria-label", label),
+            ("aria-disabled", self.disabled.then(|| "true".into())),
+            ("aria-pressed", flag(self.pressed)),
+            ("aria-selected", flag(self.selected)),
+            ("aria-expanded", flag(self.expanded)),
+        ]
+    }
+}
+
+
+
+// ===========
+// === FRP ===
+// ===========
+
+crate::define_endpoints_2! {
+    Input {
+        set_label (ImString),
+        set_disabled (bool),
+        set_pressed (Option<bool>),
+        set_selected (Option<bool>),
+        set_expanded (Option<bool>),
+        /// Move the DOM focus to the node, so the assistive technology follows the component.
+        focus (),
+    }
+    Output {
+        /// The node was activated by the assistive technology.
+        activated (),
+        /// Whether the node has the DOM focus.
+        focused (bool),
+    }
+}
+
+
+
+// =================
+// === NodeModel ===
+// =================
+
+#[derive(Debug)]
+struct NodeModel {
+    role:     Role,
+    element:  web::HtmlDivElement,
+    state:    RefCell<State>,
+    _handles: Vec<web::EventListenerHandle>,
+}
+
+impl NodeModel {
+    fn new(role: Role, parent: &web::HtmlElement, frp: &Frp) -> Self {
+        let element = web::document.create_div_or_panic();
+        element.set_attribute_or_warn("role", role.name());
+        if role.is_focusable() {
+            element.set_tab_index(0);
+        }
+        parent.append_or_warn(&element);
+        let out = &frp.private.output;
+        let activated = out.activated.clone_ref();
+        let on_click: web::JsEventHandler<web::Event> = Closure::new(move |_| activated.emit(()));
+        let activated = out.activated.clone_ref();
+        let on_key_down: web::JsEventHandler<web::KeyboardEvent> =
+            Closure::new(move |event: web::KeyboardEvent| {
+                let key = event.key();
+                if role.is_activatable() && (key == "Enter" || key == " ") {
+                    event.prevent_default();
+                    event.stop_propagation();
+                    activated.emit(());
+                }
+            });
+        let focused = out.focused.clone_ref();
+        let on_focus: web::JsEventHandler<web::Event> = Closure::new(move |_| focused.emit(true));
+        let focused = out.focused.clone_ref();
+        let on_blur: web::JsEventHandler<web::Event> = Closure::new(move |_| focused.emit(false));
+        let _handles = vec![
+            web::add_event_listener(&element, "click", on_click),
+            web::add_event_listener(&element, "keydown", on_key_down),
+            web::add_event_listener(&element, "focus", on_focus),
+            web::add_event_listener(&element, "blur", on_blur),
+        ];
+        let state = default();
+        Self { role, element, state, _handles }
+    }
+
+    fn update_state(&self, f: impl FnOnce(&mut State)) {
+        let mut state = self.state.borrow_mut();
+        let old_attributes = state.attributes();
+        f(&mut state);
+        let attributes = state.attributes();
+        for ((name, value), (_, old_value)) in attributes.iter().zip(old_attributes) {
+            if *value != old_value {
+                match value {
+                    Some(value) => self.element.set_attribute_or_warn(name, value),
+                    None => self.element.remove_attribute_or_warn(name),
+                }
+            }
+        }
+    }
+
+    fn focus(&self) {
+        if self.element.focus().is_err() {
+            warn!("Failed to focus the accessibility node {:?}.", self.role);
+        }
+    }
+}
+
+impl Drop for NodeModel {
+    fn drop(&mut self) {
+        self.element.remove_from_parent_or_warn();
+    }
+}
+
+
+
+// ============
+// === Node ===
+// ============
+
+/// A node of the accessibility tree. Dropping the node removes it from the tree. See the module
+/// docs to learn more.
+#[derive(Clone, CloneRef, Debug, Deref)]
+pub struct Node {
+    #[deref]
+    frp:   Frp,
+    model: Rc<NodeModel>,
+}
+
+impl Node {
+    /// Constructor of a top-level node.
+    pub fn new(scene: &Scene, role: Role) -> Self {
+        let accessibility = scene.extension::<Accessibility>();
+        Self::new_in(&accessibility.root.element, role)
+    }
+
+    fn new_in(parent: &web::HtmlElement, role: Role) -> Self {
+        let frp = Frp::new();
+        let model = Rc::new(NodeModel::new(role, parent, &frp));
+        Self { frp, model }.init()
+    }
+
+    fn init(self) -> Self {
+        let network = self.frp.network();
+        let input = &self.frp.private.input;
+        let model = &self.model;
+        frp::extend! { network
+            eval input.set_label ((label) model.update_state(|s| s.label = label.clone_ref()));
+            eval input.set_disabled ((disabled) model.update_state(|s| s.disabled = *disabled));
+            eval input.set_pressed ((pressed) model.update_state(|s| s.pressed = *pressed));
+            eval input.set_selected ((selected) model.update_state(|s| s.selected = *selected));
+            eval input.set_expanded ((expanded) model.update_state(|s| s.expanded = *expanded));
+            eval_ input.focus (model.focus());
+        }
+        self
+    }
+
+    /// Create a child node. The children are presented in the creation order.
+    pub fn add_child(&self, role: Role) -> Node {
+        Self::new_in(&self.model.element, role)
+    }
+
+    /// The role of the node.
+    pub fn role(&self) -> Role {
+        self.model.role
+    }
+
+    /// The current state of the node.
+    pub fn state(&self) -> State {
+        self.model.state.borrow().clone()
+    }
+}
+
+
+
+// =====================
+// === Accessibility ===
+// =====================
+
+/// The root of the accessibility tree, a DOM element hidden visually, but available to the
+/// assistive technology.
+#[derive(Debug)]
+struct Root {
+    element: web::HtmlDivElement,
+}
+
+impl Root {
+    fn new() -> Self {
+        let element = web::document.create_div_or_panic();
+        element.set_attribute_or_warn("aria-label", "Application");
+        element.set_style_or_warn("position", "absolute");
+        element.set_style_or_warn("width", "1px");
+        element.set_style_or_warn("height", "1px");
+        element.set_style_or_warn("margin", "-1px");
+        element.set_style_or_warn("overflow", "hidden");
+        element.set_style_or_warn("clip-path", "inset(50%)");
+        element.set_style_or_warn("white-space", "nowrap");
+        web::document.body_or_panic().append_or_warn(&element);
+        Self { element }
+    }
+}
+
+impl Drop for Root {
+    fn drop(&mut self) {
+        self.element.remove_from_parent_or_warn();
+    }
+}
+
+/// The accessibility tree of the scene. See the module docs to learn more. It is a
+/// [scene extension](Extension), available with [`Scene::extension`]. The nodes are usually
+/// created with [`Node::new`].
+#[derive(Clone, CloneRef, Debug)]
+pub struct Accessibility {
+    root: Rc<Root>,
+}
+
+impl Extension for Accessibility {
+    fn init(_scene: &Scene) -> Self {
+        Self { root: Rc::new(Root::new()) }
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::world::World;
+
+    #[test]
+    fn state_is_reflected_in_attributes() {
+        let world = World::new();
+        let node = Node::new(&world.default_scene, Role::Tab);
+        let attributes = |node: &Node| {
+            let attributes = node.state().attributes();
+            attributes.into_iter().filter_map(|(k, v)| Some((k, v?))).collect_vec()
+        };
+        assert!(attributes(&node).is_empty());
+        node.set_label("Settings");
+        node.set_selected(Some(false));
+        assert_eq!(attributes(&node), [
+            ("aria-label", "Settings".to_string()),
+            ("aria-selected", "false".to_string())
+        ]);
+        node.set_selected(Some(true));
+        node.set_disabled(true);
+        node.set_label("");
+        assert_eq!(attributes(&node), [
+            ("aria-disabled", "true".to_string()),
+            ("aria-selected", "true".to_string())
+        ]);
+        let child = node.add_child(Role::Button);
+        assert_eq!(child.role(), Role::Button);
+        assert!(Role::Tab.is_activatable());
+        assert!(!Role::TabList.is_focusable());
+    }
+}
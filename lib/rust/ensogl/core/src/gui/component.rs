@@ -4,6 +4,7 @@ use crate::prelude::*;
 
 use crate::application::command::Command;
 use crate::application::command::CommandApi;
+use crate::application::command::Endpoint;
 use crate::application::Application;
 use crate::display;
 use crate::display::scene;
@@ -179,7 +180,8 @@ impl<S: Shape> Drop for ShapeViewModel<S> {
 impl<S: Shape> ShapeViewModel<S> {
     /// Constructor.
     fn new_with_data(data: S::ShapeData) -> Self {
-        let (shape, _) = world::with_context(|t| t.layers.DETACHED.instantiate(&data, default()));
+        let (shape, _) =
+            world::with_context(|t| t.layers.DETACHED.instantiate(&data, default(), default()));
         let events_deprecated = PointerTarget_DEPRECATED::new();
         let pointer_targets = default();
         let data = RefCell::new(data);
@@ -203,7 +205,8 @@ impl<S: Shape> ShapeViewModel<S> {
         if let Some(new_layer) = new_layer.and_then(|layer| layer.upgrade()) {
             self.add_to_scene_layer(scene, &new_layer, new_symbol_partition)
         } else {
-            let (shape, _) = scene.layers.DETACHED.instantiate(&*self.data.borrow(), default());
+            let (shape, _) =
+                scene.layers.DETACHED.instantiate(&*self.data.borrow(), default(), default());
             self.shape.swap(&shape);
         }
     }
@@ -230,7 +233,8 @@ impl<S: Shape> ShapeViewModel<S> {
         let symbol_partition = symbol_partition
             .and_then(|assignment| assignment.partition_id(shape_system))
             .unwrap_or_default();
-        let (shape, instance) = layer.instantiate(&*self.data.borrow(), symbol_partition);
+        let z_index = self.display_object.global_z_index();
+        let (shape, instance) = layer.instantiate(&*self.data.borrow(), symbol_partition, z_index);
         scene.mouse.pointer_target_registry.insert(
             instance.global_instance_id,
             self.events_deprecated.clone_ref(),
@@ -372,4 +376,7 @@ impl<Model: 'static, Frp: 'static + crate::application::frp::API> CommandApi
     fn status_api(&self) -> Rc<RefCell<HashMap<String, frp::Sampler<bool>>>> {
         self.data.frp.public().status_api()
     }
+    fn endpoints(&self) -> Vec<Endpoint> {
+        self.data.frp.public().endpoints()
+    }
 }
@@ -0,0 +1,334 @@
+//! A component-level error boundary. It owns a view and runs the interactions with it in a guarded
+//! section. If the view panics, the panic is caught, the view is dropped and replaced by an error
+//! placeholder, and a structured [`Report`] is emitted. This way, a single faulty component, like a
+//! visualization, can not take down the whole scene. Clicking the placeholder, or emitting the
+//! [`retry`](Frp::retry) input, constructs the view again.
+//!
+//! Panics are caught with [`std::panic::catch_unwind`], so the boundary works only on targets
+//! compiled with unwinding panics. On targets compiled with `panic = "abort"`, including the
+//! default WASM target, a panic still terminates the application. The panic location is recorded
+//! by a panic hook installed on the first guarded call, which forwards to the previous hook.
+
+use crate::prelude::*;
+
+use crate::application::view::AnyView;
+use crate::application::Application;
+use crate::application::View;
+use crate::control::io::mouse;
+use crate::data::color;
+use crate::display;
+use crate::display::object::ObjectOps;
+use crate::display::shape::Rectangle;
+
+use serde::Serialize;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The width of the error placeholder, used if the failed view had no size.
+pub const PLACEHOLDER_DEFAULT_WIDTH: f32 = 120.0;
+/// The height of the error placeholder, used if the failed view had no size.
+pub const PLACEHOLDER_DEFAULT_HEIGHT: f32 = 40.0;
+const PLACEHOLDER_COLOR: color::Rgba = color::Rgba::new(0.9, 0.2, 0.2, 0.15);
+const PLACEHOLDER_BORDER_COLOR: color::Rgba = color::Rgba::new(0.9, 0.2, 0.2, 1.0);
+const PLACEHOLDER_BORDER_WIDTH: f32 = 2.0;
+const PLACEHOLDER_CORNER_RADIUS: f32 = 6.0;
+
+
+
+// ===========
+// === Frp ===
+// ===========
+
+crate::define_endpoints_2! {
+    Input {
+        /// Construct the view again. If the view is not failed, it is replaced by a new instance.
+        retry(),
+    }
+    Output {
+        /// Whether the view panicked and is replaced by the error placeholder.
+        failed(bool),
+        /// The report of the latest panic.
+        report(Report),
+    }
+}
+
+
+
+// ==============
+// === Report ===
+// ==============
+
+/// A structured report of a panic caught by an error boundary.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Report {
+    /// The label of the failed view.
+    pub component:     String,
+    /// The panic message, if it was a string.
+    pub message:       String,
+    /// The source location of the panic, if it was recorded.
+    pub location:      Option<String>,
+    /// The number of failures of the view since the boundary was created.
+    pub failure_count: usize,
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let location = self.location.as_deref().unwrap_or("unknown location");
+        write!(f, "The component '{}' panicked at {location}: {}", self.component, self.message)
+    }
+}
+
+thread_local! {
+    static LAST_PANIC_LOCATION: RefCell<Option<String>> = default();
+}
+
+fn install_panic_location_hook() {
+    static INSTALLED: std::sync::Once = std::sync::Once::new();
+    INSTALLED.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let location = info.location().map(|location| location.to_string());
+            let _ = LAST_PANIC_LOCATION.try_with(|last| *last.borrow_mut() = location);
+            previous_hook(info);
+        }));
+    });
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "<non-string panic payload>".into()
+    }
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+type Constructor = Box<dyn Fn(&Application) -> AnyView>;
+
+#[derive(Derivative, display::Object)]
+#[derivative(Debug)]
+struct Model {
+    display_object: display::object::Instance,
+    app:            Application,
+    label:          String,
+    #[derivative(Debug = "ignore")]
+    constructor:    Constructor,
+    view:           RefCell<Option<AnyView>>,
+    placeholder:    Rectangle,
+    /// The size of the view at the moment of failure, used as the placeholder size.
+    last_size:      Cell<Vector2>,
+    failure_count:  Cell<usize>,
+}
+
+impl Model {
+    fn new(app: &Application, label: impl Into<String>, constructor: Constructor) -> Self {
+        let display_object = display::object::Instance::new_named("ErrorBoundary");
+        let app = app.clone_ref();
+        let label = label.into();
+        let view = default();
+        let placeholder = Rectangle().build(|r| {
+            r.set_color(PLACEHOLDER_COLOR)
+                .set_border_color(PLACEHOLDER_BORDER_COLOR)
+                .set_border_and_inset(PLACEHOLDER_BORDER_WIDTH)
+                .set_corner_radius(PLACEHOLDER_CORNER_RADIUS);
+        });
+        let last_size = default();
+        let failure_count = default();
+        Self {
+            display_object,
+            app,
+            label,
+            constructor,
+            view,
+            placeholder,
+            last_size,
+            failure_count,
+        }
+    }
+
+    /// Run the function, converting a panic to a report.
+    fn catch<R>(&self, f: impl FnOnce() -> R) -> Result<R, Report> {
+        install_panic_location_hook();
+        LAST_PANIC_LOCATION.with(|last| last.borrow_mut().take());
+        panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+            let failure_count = self.failure_count.get() + 1;
+            self.failure_count.set(failure_count);
+            let component = self.label.clone();
+            let message = panic_message(payload.as_ref());
+            let location = LAST_PANIC_LOCATION.with(|last| last.borrow_mut().take());
+            Report { component, message, location, failure_count }
+        })
+    }
+
+    /// Construct the view, replacing the current view or the placeholder. Returns the report if
+    /// the construction panicked.
+    fn mount(&self) -> Option<Report> {
+        self.unmount();
+        match self.catch(|| (self.constructor)(&self.app)) {
+            Ok(view) => {
+                self.placeholder.unset_parent();
+                self.display_object.add_child(&view);
+                *self.view.borrow_mut() = Some(view);
+                None
+            }
+            Err(report) => Some(report),
+        }
+    }
+
+    fn unmount(&self) {
+        let view = self.view.borrow_mut().take();
+        if let Some(view) = view {
+            self.last_size.set(view.computed_size());
+            view.unset_parent();
+            // The view is in an unknown state, so dropping it may panic as well.
+            let _ = self.catch(|| drop(view));
+        }
+    }
+
+    fn show_placeholder(&self, report: &Report) {
+        error!("{report}");
+        self.unmount();
+        let last_size = self.last_size.get();
+        let has_size = last_size.x > 0.0 && last_size.y > 0.0;
+        let default_size = Vector2(PLACEHOLDER_DEFAULT_WIDTH, PLACEHOLDER_DEFAULT_HEIGHT);
+        let size = if has_size { last_size } else { default_size };
+        self.placeholder.set_size(size);
+        self.display_object.add_child(&self.placeholder);
+    }
+}
+
+
+
+// =====================
+// === ErrorBoundary ===
+// =====================
+
+/// An error boundary around a view. See the module docs to learn more.
+#[derive(Clone, CloneRef, Debug, Deref, display::Object)]
+pub struct ErrorBoundary {
+    #[deref]
+    frp:   Frp,
+    #[display_object]
+    model: Rc<Model>,
+}
+
+impl ErrorBoundary {
+    /// Constructor of a boundary around a new instance of the view.
+    pub fn new<V: View>(app: &Application) -> Self {
+        let constructor = Box::new(|app: &Application| AnyView::new(app.new_view::<V>()));
+        Self::new_with_constructor(app, V::label(), constructor)
+    }
+
+    /// Constructor of a boundary around a new instance of the view registered in the
+    /// [view registry](crate::application::view::Registry) with the given label. Returns [`None`]
+    /// if there is no such view.
+    pub fn new_by_label(app: &Application, label: &str) -> Option<Self> {
+        let registered = app.views.labels().iter().any(|registered| registered == label);
+        let owned_label = label.to_owned();
+        let constructor = Box::new(move |app: &Application| {
+            app.views.new_view_by_label(&owned_label, app).expect("The view was registered.")
+        });
+        registered.then(|| Self::new_with_constructor(app, label, constructor))
+    }
+
+    fn new_with_constructor(
+        app: &Application,
+        label: impl Into<String>,
+        constructor: Constructor,
+    ) -> Self {
+        let frp = Frp::new();
+        let model = Rc::new(Model::new(app, label, constructor));
+        let network = frp.network();
+        let out = &frp.private.output;
+        let placeholder_clicked = model.placeholder.on_event::<mouse::Down>();
+        frp::extend! { network
+            clicked <- placeholder_clicked.constant(());
+            retry <- any(&frp.retry, &clicked);
+            mount_failure <- retry.map(f_!(model.mount()));
+            failure <- mount_failure.unwrap();
+            eval failure ((report) model.show_placeholder(report));
+            out.failed <+ mount_failure.map(|failure| failure.is_some());
+            out.report <+ failure;
+        }
+        // The initial construction shares the logic with retrying.
+        frp.retry();
+        Self { frp, model }
+    }
+
+    /// Run the function with the view in a guarded section. If it panics, the view is replaced by
+    /// the error placeholder. Returns [`None`] if the function panicked or the view is failed.
+    ///
+    /// All interactions which may trigger the view's FRP handlers, like emitting its inputs, should
+    /// be performed in a guarded section.
+    pub fn guard<R>(&self, f: impl FnOnce(&AnyView) -> R) -> Option<R> {
+        let view = self.model.view.borrow().clone()?;
+        let result = self.model.catch(|| f(&view));
+        drop(view);
+        match result {
+            Ok(value) => Some(value),
+            Err(report) => {
+                self.model.show_placeholder(&report);
+                self.frp.private.output.failed.emit(true);
+                self.frp.private.output.report.emit(report);
+                None
+            }
+        }
+    }
+
+    /// The guarded view, or [`None`] if it failed. Interactions with the view should be performed
+    /// through [`ErrorBoundary::guard`].
+    pub fn view(&self) -> Option<AnyView> {
+        self.model.view.borrow().clone()
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::application::undo;
+
+    #[test]
+    fn panics_replace_the_view_with_the_placeholder_until_retried() {
+        let app = Application::new("root");
+        let label = <undo::Manager as View>::label();
+        let boundary = ErrorBoundary::new::<undo::Manager>(&app);
+        assert!(!boundary.failed.value());
+        assert_eq!(boundary.guard(|view| view.label()), Some(label));
+
+        assert_eq!(boundary.guard(|_| -> usize { panic!("Broken handler.") }), None);
+        assert!(boundary.failed.value());
+        assert!(boundary.view().is_none());
+        let report = boundary.report.value();
+        assert_eq!(report.component, label);
+        assert_eq!(report.message, "Broken handler.");
+        assert!(report.location.unwrap().contains("error_boundary.rs"));
+        assert_eq!(report.failure_count, 1);
+        assert_eq!(boundary.guard(|view| view.label()), None);
+
+        boundary.retry();
+        assert!(!boundary.failed.value());
+        assert_eq!(boundary.guard(|view| view.label()), Some(label));
+        assert!(ErrorBoundary::new_by_label(&app, "Unknown").is_none());
+    }
+}
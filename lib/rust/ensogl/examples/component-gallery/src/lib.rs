@@ -0,0 +1,288 @@
+//! A component gallery, listing every view registered in the application as a separate scenario.
+//! Every view is displayed together with knobs generated from its FRP endpoints metadata: a button
+//! for every command, editable fields for inputs of simple types, and live values of all outputs.
+//! Hovering an endpoint displays its type and documentation. The gallery serves as living
+//! documentation and a manual test bench for components.
+
+#![recursion_limit = "1024"]
+// === Standard Linter Configuration ===
+#![deny(non_ascii_idents)]
+#![warn(unsafe_code)]
+#![allow(clippy::bool_to_int_with_if)]
+#![allow(clippy::let_and_return)]
+// === Non-Standard Linter Configuration ===
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+use ensogl_core::prelude::*;
+use ensogl_core::system::web::traits::*;
+
+use ensogl_core::animation;
+use ensogl_core::application::command::Endpoint;
+use ensogl_core::application::command::EndpointKind;
+use ensogl_core::application::view::AnyView;
+use ensogl_core::application::Application;
+use ensogl_core::display;
+use ensogl_core::display::navigation::navigator::Navigator;
+use ensogl_core::display::object::ObjectOps;
+use ensogl_core::frp;
+use ensogl_core::system::web;
+use ensogl_core::system::web::Closure;
+use ensogl_drop_down::Dropdown;
+use ensogl_example_harness::Harness;
+use ensogl_example_harness::Scenario;
+use ensogl_text_msdf::run_once_initialized;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+const FONTS: &str = "\"SF Mono\", Menlo, Monaco, Consolas, \"Liberation Mono\", monospace";
+const BACKGROUND_COLOR: &str = "#FFFFFFE0";
+const BUTTON_COLOR: &str = "#00000010";
+const STATUS_ON_COLOR: &str = "#008CFF";
+const STATUS_OFF_COLOR: &str = "#00000080";
+const SECTION_COLOR: &str = "#00000080";
+/// Output values are formatted only from time to time, as it may be expensive for large values.
+const OUTPUT_REFRESH_INTERVAL_MS: f32 = 250.0;
+
+
+
+// ===================
+// === Entry Point ===
+// ===================
+
+/// An entry point.
+#[entry_point]
+#[allow(dead_code)]
+pub fn main() {
+    run_once_initialized(|| {
+        let app = Application::new("root");
+        init(&app);
+        app.keep_alive_until_disposed();
+    });
+}
+
+
+
+// ========================
+// === Init Application ===
+// ========================
+
+fn init(app: &Application) {
+    let scene = &app.display.default_scene;
+    let navigator = Navigator::new(scene, &scene.camera());
+
+    app.views.register::<ensogl_text::Text>();
+    app.views.register::<ensogl_slider::Slider>();
+    app.views.register::<Dropdown<String>>();
+    app.views.register::<ensogl_log_viewer::LogViewer>();
+
+    let harness = Harness::new(app);
+    for label in app.views.labels() {
+        harness.add(Scenario::new(label.clone(), move |app, root| setup_view(app, root, &label)));
+    }
+
+    app.keep_alive((harness, navigator));
+}
+
+fn setup_view(
+    app: &Application,
+    root: &display::object::Instance,
+    label: &str,
+) -> Option<(AnyView, Knobs)> {
+    let view = app.views.new_view_by_label(label, app)?;
+    root.add_child(&view);
+    let knobs = Knobs::new(&view);
+    Some((view, knobs))
+}
+
+
+
+// =============
+// === Knobs ===
+// =============
+
+/// A panel with controls of a view, generated from its FRP endpoints metadata.
+#[derive(Debug)]
+struct Knobs {
+    root:     web::HtmlDivElement,
+    _handles: Vec<web::EventListenerHandle>,
+    _network: frp::Network,
+}
+
+impl Knobs {
+    fn new(view: &AnyView) -> Self {
+        let root = web::document.create_div_or_panic();
+        root.set_style_or_warn("position", "absolute");
+        root.set_style_or_warn("z-index", "100");
+        root.set_style_or_warn("right", "16px");
+        root.set_style_or_warn("bottom", "16px");
+        root.set_style_or_warn("max-width", "360px");
+        root.set_style_or_warn("max-height", "60%");
+        root.set_style_or_warn("overflow", "auto");
+        root.set_style_or_warn("padding", "6px 10px");
+        root.set_style_or_warn("border-radius", "6px");
+        root.set_style_or_warn("background", BACKGROUND_COLOR);
+        root.set_style_or_warn("font-family", FONTS);
+        root.set_style_or_warn("font-size", "11px");
+        let title = web::document.create_div_or_panic();
+        title.set_style_or_warn("font-weight", "bold");
+        title.set_inner_text(view.label());
+        root.append_or_warn(&title);
+        web::document.body_or_panic().append_or_warn(&root);
+
+        let network = frp::Network::new("Knobs");
+        let mut handles = vec![];
+        Self::add_section(&root, "Commands");
+        for name in view.command_names() {
+            handles.push(Self::add_command_button(&root, view, name));
+        }
+        Self::add_section(&root, "Inputs");
+        for endpoint in view.endpoints().iter().filter(|e| e.kind == EndpointKind::Input) {
+            handles.extend(Self::add_input_knob(&root, endpoint));
+        }
+        Self::add_section(&root, "Outputs");
+        let outputs = view.endpoints().iter().filter(|e| e.kind == EndpointKind::Output);
+        let indicators = outputs.map(|endpoint| {
+            let indicator = Self::add_row(&root, endpoint);
+            (endpoint.clone(), indicator)
+        });
+        let indicators = indicators.collect_vec();
+        let last_refresh_time = Cell::new(f32::NEG_INFINITY);
+        let on_frame = animation::on_before_animations();
+        frp::extend! { network
+            eval on_frame ((time) {
+                let time = time.since_animation_loop_started.unchecked_raw();
+                if time - last_refresh_time.get() >= OUTPUT_REFRESH_INTERVAL_MS {
+                    last_refresh_time.set(time);
+                    for (endpoint, indicator) in &indicators {
+                        Self::refresh_output(endpoint, indicator);
+                    }
+                }
+            });
+        }
+        Self { root, _handles: handles, _network: network }
+    }
+
+    fn add_section(root: &web::HtmlDivElement, name: &str) {
+        let section = web::document.create_div_or_panic();
+        section.set_style_or_warn("margin-top", "8px");
+        section.set_style_or_warn("color", SECTION_COLOR);
+        section.set_inner_text(name);
+        root.append_or_warn(&section);
+    }
+
+    /// Add a row displaying the endpoint, with the endpoint docs displayed on hover.
+    fn add_row(root: &web::HtmlDivElement, endpoint: &Endpoint) -> web::HtmlDivElement {
+        let row = web::document.create_div_or_panic();
+        row.set_style_or_warn("margin-top", "4px");
+        row.set_attribute_or_warn("title", format!("{}\n{}", endpoint.type_name, endpoint.doc()));
+        row.set_inner_text(endpoint.name);
+        root.append_or_warn(&row);
+        row
+    }
+
+    fn add_command_button(
+        root: &web::HtmlDivElement,
+        view: &AnyView,
+        name: String,
+    ) -> web::EventListenerHandle {
+        let button = web::document.create_div_or_panic();
+        button.set_style_or_warn("cursor", "pointer");
+        button.set_style_or_warn("margin-top", "4px");
+        button.set_style_or_warn("padding", "2px 6px");
+        button.set_style_or_warn("border-radius", "4px");
+        button.set_style_or_warn("background", BUTTON_COLOR);
+        button.set_inner_text(&name);
+        root.append_or_warn(&button);
+        let view = view.clone_ref();
+        let on_click: web::JsEventHandler<web::Event> = Closure::new(move |_| {
+            if !view.run_command(&name) {
+                warn!("The command '{name}' is disabled.");
+            }
+        });
+        web::add_event_listener(&button, "click", on_click)
+    }
+
+    /// Add a knob for the input endpoint. Boolean inputs are toggled by clicking, numeric and text
+    /// inputs are edited in place and emitted when the edited field loses focus. Inputs of other
+    /// types are listed without a knob.
+    fn add_input_knob(
+        root: &web::HtmlDivElement,
+        endpoint: &Endpoint,
+    ) -> Option<web::EventListenerHandle> {
+        if endpoint.is::<()>() {
+            return None;
+        }
+        let row = Self::add_row(root, endpoint);
+        if let Some(node) = endpoint.as_input::<bool>() {
+            let node = node.clone_ref();
+            let value = Rc::new(Cell::new(false));
+            let name = endpoint.name;
+            let toggle = row.clone();
+            toggle.set_style_or_warn("cursor", "pointer");
+            let on_click: web::JsEventHandler<web::Event> = Closure::new(move |_| {
+                value.set(!value.get());
+                node.emit(value.get());
+                toggle.set_inner_text(&format!("{name}: {}", value.get()));
+            });
+            Some(web::add_event_listener(&row, "click", on_click))
+        } else if let Some(node) = endpoint.as_input::<f32>() {
+            let node = node.clone_ref();
+            Some(Self::add_editable_field(&row, move |text| match text.trim().parse::<f32>() {
+                Ok(value) => node.emit(value),
+                Err(_) => warn!("Cannot parse '{text}' as a number."),
+            }))
+        } else if let Some(node) = endpoint.as_input::<ImString>() {
+            let node = node.clone_ref();
+            Some(Self::add_editable_field(&row, move |text| node.emit(ImString::from(text))))
+        } else if let Some(node) = endpoint.as_input::<String>() {
+            let node = node.clone_ref();
+            Some(Self::add_editable_field(&row, move |text| node.emit(text.to_owned())))
+        } else {
+            None
+        }
+    }
+
+    fn add_editable_field(
+        row: &web::HtmlDivElement,
+        on_edit: impl Fn(&str) + 'static,
+    ) -> web::EventListenerHandle {
+        let field = web::document.create_div_or_panic();
+        field.set_attribute_or_warn("contenteditable", "true");
+        field.set_style_or_warn("padding", "2px 6px");
+        field.set_style_or_warn("border-radius", "4px");
+        field.set_style_or_warn("background", BUTTON_COLOR);
+        row.append_or_warn(&field);
+        let edited = field.clone();
+        let on_blur: web::JsEventHandler<web::Event> =
+            Closure::new(move |_| on_edit(&edited.inner_text()));
+        web::add_event_listener(&field, "blur", on_blur)
+    }
+
+    fn refresh_output(endpoint: &Endpoint, indicator: &web::HtmlDivElement) {
+        if let Some(value) = endpoint.debug_value() {
+            let is_on = endpoint.as_output::<bool>().map_or(false, |sampler| sampler.value());
+            let color = if is_on { STATUS_ON_COLOR } else { STATUS_OFF_COLOR };
+            let text = format!("{}: {value}", endpoint.name);
+            if indicator.inner_text() != text {
+                indicator.set_style_or_warn("color", color);
+                indicator.set_inner_text(&text);
+            }
+        }
+    }
+}
+
+impl Drop for Knobs {
+    fn drop(&mut self) {
+        self.root.remove_from_parent_or_warn();
+    }
+}
@@ -1,4 +1,5 @@
-//! A debug scene which shows the Dynamic drop-down component.
+//! A debug scene which shows the Dynamic drop-down component. The configurations are switched
+//! with the scenario selector in the top left corner.
 
 #![recursion_limit = "1024"]
 // === Standard Linter Configuration ===
@@ -10,10 +11,13 @@
 use ensogl_core::prelude::*;
 
 use ensogl_core::application::Application;
+use ensogl_core::display;
 use ensogl_core::display::navigation::navigator::Navigator;
 use ensogl_core::display::object::ObjectOps;
 use ensogl_drop_down::Dropdown;
 use ensogl_drop_down::DropdownValue;
+use ensogl_example_harness::Harness;
+use ensogl_example_harness::Scenario;
 use ensogl_text_msdf::run_once_initialized;
 
 
@@ -29,7 +33,7 @@ pub fn main() {
     run_once_initialized(|| {
         let app = Application::new("root");
         init(&app);
-        mem::forget(app);
+        app.keep_alive_until_disposed();
     });
 }
 
@@ -46,6 +50,20 @@ fn init(app: &Application) {
     navigator.disable_wheel_panning();
 
     app.views.register::<Dropdown<EntryData>>();
+    let harness = Harness::new(app);
+    harness.add(Scenario::new("Connected drop-downs", setup_connected_dropdowns));
+    harness.add(Scenario::new("Automatic width", setup_auto_width_dropdowns));
+
+    app.keep_alive((harness, navigator));
+}
+
+
+
+// =================
+// === Scenarios ===
+// =================
+
+fn setup_connected_dropdowns(app: &Application, root: &display::object::Instance) -> impl Any {
     let main_dropdown = setup_main_dropdown(app);
 
     let multi_config_dropdown = setup_static_dropdown(app, Vector2(-200.0, 0.0), None, vec![
@@ -84,14 +102,25 @@ fn init(app: &Application) {
         secondary_dropdown.set_all_entries <+ selected_vec;
     }
 
-    world.add_child(&main_dropdown);
-    world.add_child(&multi_config_dropdown);
-    world.add_child(&open_dropdown);
-    world.add_child(&secondary_dropdown);
-    world.add_child(&dropdown_static1);
-    world.add_child(&dropdown_static2);
+    root.add_child(&main_dropdown);
+    root.add_child(&multi_config_dropdown);
+    root.add_child(&open_dropdown);
+    root.add_child(&secondary_dropdown);
+    root.add_child(&dropdown_static1);
+    root.add_child(&dropdown_static2);
 
+    (
+        main_dropdown,
+        multi_config_dropdown,
+        open_dropdown,
+        secondary_dropdown,
+        dropdown_static1,
+        dropdown_static2,
+        network,
+    )
+}
 
+fn setup_auto_width_dropdowns(app: &Application, root: &display::object::Instance) -> impl Any {
     let auto_max_size = Some(Vector2(1000.0, 250.0));
     let auto_width_demo1 = setup_static_dropdown(app, Vector2(-200.0, 300.0), auto_max_size, vec![
         "A", "B", "C", "D", "E", "F",
@@ -112,25 +141,12 @@ fn init(app: &Application) {
         "on its own, up to a set maximum width.",
     ]);
 
-    world.add_child(&auto_width_demo1);
-    world.add_child(&auto_width_demo2);
-    world.add_child(&auto_width_demo3);
-    world.add_child(&auto_width_demo4);
+    root.add_child(&auto_width_demo1);
+    root.add_child(&auto_width_demo2);
+    root.add_child(&auto_width_demo3);
+    root.add_child(&auto_width_demo4);
 
-    std::mem::forget((
-        main_dropdown,
-        multi_config_dropdown,
-        open_dropdown,
-        secondary_dropdown,
-        dropdown_static1,
-        dropdown_static2,
-        auto_width_demo1,
-        auto_width_demo2,
-        auto_width_demo3,
-        auto_width_demo4,
-        navigator,
-        network,
-    ));
+    (auto_width_demo1, auto_width_demo2, auto_width_demo3, auto_width_demo4)
 }
 
 fn setup_main_dropdown(app: &Application) -> Dropdown<EntryData> {
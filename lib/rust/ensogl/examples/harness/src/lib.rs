@@ -0,0 +1,266 @@
+//! A harness for examples consisting of multiple named scenarios. The scenarios can be switched at
+//! runtime with a drop-down displayed in the top left corner of the screen, so many configurations
+//! of a component can be exercised without separate builds.
+//!
+//! Every scenario has a setup function, creating its state, and an optional teardown function.
+//! Switching the scenario runs the teardown of the active one and drops its state, then runs the
+//! setup of the selected one. Display objects created by the setup should be added to the provided
+//! scenario root, which is removed from the scene during the switch.
+
+#![recursion_limit = "1024"]
+// === Standard Linter Configuration ===
+#![deny(non_ascii_idents)]
+#![warn(unsafe_code)]
+#![allow(clippy::bool_to_int_with_if)]
+#![allow(clippy::let_and_return)]
+// === Non-Standard Linter Configuration ===
+#![warn(missing_copy_implementations)]
+#![warn(missing_debug_implementations)]
+#![warn(missing_docs)]
+#![warn(trivial_casts)]
+#![warn(trivial_numeric_casts)]
+#![warn(unused_import_braces)]
+#![warn(unused_qualifications)]
+
+use ensogl_core::prelude::*;
+
+use ensogl_core::application::Application;
+use ensogl_core::display;
+use ensogl_core::display::object::ObjectOps;
+use ensogl_core::frp;
+use ensogl_drop_down::Dropdown;
+use ensogl_drop_down::DropdownValue;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The distance between the scenario selector and the top left corner of the screen.
+pub const SELECTOR_MARGIN: f32 = 16.0;
+
+
+
+// ================
+// === Scenario ===
+// ================
+
+type Setup = Box<dyn Fn(&Application, &display::object::Instance) -> Box<dyn Any>>;
+type Teardown = Box<dyn Fn(&Application)>;
+
+/// A named configuration of an example. See the crate docs to learn more.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct Scenario {
+    name:     ImString,
+    #[derivative(Debug = "ignore")]
+    setup:    Setup,
+    #[derivative(Debug = "ignore")]
+    teardown: Option<Teardown>,
+}
+
+impl Scenario {
+    /// Constructor. The state returned by `setup` is kept alive as long as the scenario is active.
+    pub fn new<State: 'static>(
+        name: impl Into<ImString>,
+        setup: impl Fn(&Application, &display::object::Instance) -> State + 'static,
+    ) -> Self {
+        let name = name.into();
+        let setup = Box::new(move |app: &Application, root: &display::object::Instance| {
+            Box::new(setup(app, root)) as Box<dyn Any>
+        });
+        Self { name, setup, teardown: None }
+    }
+
+    /// Set the function called when the scenario is deactivated, before its state is dropped. It
+    /// should revert changes made by the setup outside of the scenario root, like moving the
+    /// camera.
+    pub fn with_teardown(mut self, teardown: impl Fn(&Application) + 'static) -> Self {
+        self.teardown = Some(Box::new(teardown));
+        self
+    }
+
+    /// The name of the scenario.
+    pub fn name(&self) -> &ImString {
+        &self.name
+    }
+}
+
+
+
+// ====================
+// === ScenarioName ===
+// ====================
+
+/// A scenario name displayed in the selector.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+struct ScenarioName(ImString);
+
+impl DropdownValue for ScenarioName {
+    fn label(&self) -> ImString {
+        self.0.clone()
+    }
+}
+
+
+
+// ==============
+// === Active ===
+// ==============
+
+/// The state of the active scenario.
+#[derive(Derivative)]
+#[derivative(Debug)]
+struct Active {
+    index:  usize,
+    root:   display::object::Instance,
+    #[derivative(Debug = "ignore")]
+    _state: Box<dyn Any>,
+}
+
+
+
+// =============
+// === Model ===
+// =============
+
+#[derive(Debug)]
+struct Model {
+    app:       Application,
+    scenarios: RefCell<Vec<Scenario>>,
+    active:    RefCell<Option<Active>>,
+}
+
+impl Model {
+    fn new(app: &Application) -> Self {
+        let app = app.clone_ref();
+        let scenarios = default();
+        let active = default();
+        Self { app, scenarios, active }
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.scenarios.borrow().iter().position(|scenario| scenario.name.as_str() == name)
+    }
+
+    fn activate(&self, index: usize) {
+        self.deactivate();
+        let scenarios = self.scenarios.borrow();
+        if let Some(scenario) = scenarios.get(index) {
+            info!("Setting up the scenario '{}'.", scenario.name);
+            let root = display::object::Instance::new_named("Scenario");
+            self.app.display.add_child(&root);
+            let _state = (scenario.setup)(&self.app, &root);
+            *self.active.borrow_mut() = Some(Active { index, root, _state });
+        }
+    }
+
+    fn deactivate(&self) {
+        let active = self.active.borrow_mut().take();
+        if let Some(active) = active {
+            let scenarios = self.scenarios.borrow();
+            if let Some(scenario) = scenarios.get(active.index) {
+                info!("Tearing down the scenario '{}'.", scenario.name);
+                if let Some(teardown) = &scenario.teardown {
+                    teardown(&self.app);
+                }
+            }
+            active.root.unset_parent();
+        }
+    }
+
+    fn active_name(&self) -> Option<ImString> {
+        let index = self.active.borrow().as_ref()?.index;
+        self.scenarios.borrow().get(index).map(|scenario| scenario.name.clone())
+    }
+
+    fn names(&self) -> Vec<ScenarioName> {
+        self.scenarios.borrow().iter().map(|scenario| ScenarioName(scenario.name.clone())).collect()
+    }
+}
+
+
+
+// ===============
+// === Harness ===
+// ===============
+
+/// Example harness, switching between registered scenarios. See the crate docs to learn more.
+#[derive(Clone, CloneRef, Debug)]
+pub struct Harness {
+    model:    Rc<Model>,
+    selector: Dropdown<ScenarioName>,
+    network:  frp::Network,
+}
+
+impl Harness {
+    /// Constructor. The scenario selector is displayed on the `panel` layer, so it does not move
+    /// with the camera.
+    pub fn new(app: &Application) -> Self {
+        let model = Rc::new(Model::new(app));
+        app.views.register::<Dropdown<ScenarioName>>();
+        let selector = app.new_view::<Dropdown<ScenarioName>>();
+        let scene = &app.display.default_scene;
+        scene.add_child(&selector);
+        scene.layers.panel.add(&selector);
+
+        let network = frp::Network::new("Harness");
+        frp::extend! { network
+            eval scene.frp.shape ([selector] (shape) {
+                let x = -shape.width / 2.0 + SELECTOR_MARGIN;
+                let y = shape.height / 2.0 - SELECTOR_MARGIN;
+                selector.set_xy(Vector2(x, y));
+            });
+            selected <- selector.single_selected_entry.unwrap().on_change();
+            chosen <- selected.map(f!([model] (name) model.index_of(&name.0))).unwrap();
+            eval chosen ([model] (index) model.activate(*index));
+        }
+        Self { model, selector, network }
+    }
+
+    /// Register a new scenario. The first registered scenario is activated immediately.
+    pub fn add(&self, scenario: Scenario) {
+        let is_first = self.model.scenarios.borrow().is_empty();
+        let name = ScenarioName(scenario.name.clone());
+        self.model.scenarios.borrow_mut().push(scenario);
+        self.selector.set_all_entries(self.model.names());
+        if is_first {
+            self.selector.set_selected_entries(HashSet::from([name]));
+        }
+    }
+
+    /// Switch to the scenario with the given name. Returns [`false`] if there is no such scenario.
+    pub fn select(&self, name: &str) -> bool {
+        let index = self.model.index_of(name);
+        if let Some(index) = index {
+            let name = ScenarioName(self.model.scenarios.borrow()[index].name.clone());
+            self.selector.set_selected_entries(HashSet::from([name]));
+        }
+        index.is_some()
+    }
+
+    /// Tear down the active scenario and set it up again, for example to check that the teardown
+    /// cleans up everything.
+    pub fn reload(&self) {
+        let index = self.model.active.borrow().as_ref().map(|active| active.index);
+        if let Some(index) = index {
+            self.model.activate(index);
+        }
+    }
+
+    /// The name of the active scenario.
+    pub fn active_scenario(&self) -> Option<ImString> {
+        self.model.active_name()
+    }
+
+    /// The names of all registered scenarios, in the registration order.
+    pub fn scenario_names(&self) -> Vec<ImString> {
+        self.model.names().into_iter().map(|name| name.0).collect()
+    }
+
+    /// The network of the harness. Scenario-independent connections can be added to it.
+    pub fn network(&self) -> &frp::Network {
+        &self.network
+    }
+}
@@ -34,6 +34,7 @@ pub use ensogl_example_animation as animation;
 pub use ensogl_example_built_in_shapes as built_in_shapes;
 pub use ensogl_example_cached_shape as cached_shape;
 pub use ensogl_example_complex_shape_system as complex_shape_system;
+pub use ensogl_example_component_gallery as component_gallery;
 pub use ensogl_example_dom_symbols as dom_symbols;
 pub use ensogl_example_drop_down as drop_down;
 pub use ensogl_example_drop_manager as drop_manager;
@@ -147,7 +147,7 @@ impl Borders {
                 borders.right.set_x(*w);
             });
         }
-        mem::forget(frp);
+        app.keep_alive(frp);
     }
 }
 
@@ -208,7 +208,7 @@ fn init(app: Application) {
     div.set_inner_text(content);
     web::document.body().unwrap().append_child(&div).unwrap();
 
-    init_debug_hotkeys(&app.display.default_scene, &area, &div);
+    let hotkeys_handle = init_debug_hotkeys(&app.display.default_scene, &area, &div);
 
     let scene = scene.clone_ref();
     let handler = app.display.on.before_frame.add(move |_time| {
@@ -217,12 +217,15 @@ fn init(app: Application) {
         div.set_style_or_warn("top", format!("{}px", shape.height / 2.0 - 0.5));
     });
 
-    mem::forget(handler);
-    mem::forget(navigator);
-    mem::forget(app);
+    app.keep_alive((handler, navigator, hotkeys_handle));
+    app.keep_alive_until_disposed();
 }
 
-fn init_debug_hotkeys(scene: &Scene, area: &Rc<RefCell<Option<Text>>>, div: &web::HtmlDivElement) {
+fn init_debug_hotkeys(
+    scene: &Scene,
+    area: &Rc<RefCell<Option<Text>>>,
+    div: &web::HtmlDivElement,
+) -> web::EventListenerHandle {
     let html_version = Rc::new(Cell::new(false));
     let scene = scene.clone_ref();
     let area = area.clone_ref();
@@ -319,6 +322,5 @@ fn init_debug_hotkeys(scene: &Scene, area: &Rc<RefCell<Option<Text>>>, div: &web
             }
         }
     });
-    let handle = web::add_event_listener_with_bool(&web::window, "keydown", closure, true);
-    mem::forget(handle);
+    web::add_event_listener_with_bool(&web::window, "keydown", closure, true)
 }
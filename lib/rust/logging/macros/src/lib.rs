@@ -124,6 +124,7 @@ fn logging_api(
     let global_logger_path = ident_to_path(global_logger_ident.clone());
     let levels = levels(level_names, &config, global_logger_path);
     let api: Api = [
+        level_enum(&levels),
         span_trait(),
         logger_trait(&levels),
         span_api(&levels),
@@ -141,6 +142,7 @@ fn logging_api(
 struct Level {
     // Intrinsic properties of a level:
     name:                  String,
+    variant:               syn::Ident,
     enabled:               bool,
     uncollapsed:           bool,
     // Identifiers for API cross-references:
@@ -153,7 +155,8 @@ impl Level {
         let trait_methods = trait_methods(&name);
         let global_logger_methods =
             trait_methods.clone().map(|x| qualified(global_logger_path.clone(), x));
-        Level { global_logger_methods, trait_methods, enabled, uncollapsed, name }
+        let variant = ident(name.to_pascal_case());
+        Level { global_logger_methods, trait_methods, enabled, uncollapsed, name, variant }
     }
 }
 
@@ -235,6 +238,42 @@ impl Export {
 
 
 
+// ==================
+// === Level enum ===
+// ==================
+
+fn level_enum(levels: &[Level]) -> Api {
+    let enum_name = ident("Level");
+    let variants: Vec<_> = levels.iter().map(|level| &level.variant).collect();
+    let names = levels.iter().map(|level| &level.name);
+    let implementation = quote! {
+        /// The severity of a log event. The levels are ordered from the most severe one.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[allow(missing_docs)]
+        pub enum #enum_name {
+            #(#variants,)*
+        }
+        impl #enum_name {
+            /// All levels, from the most severe one.
+            pub const ALL: &'static [#enum_name] = &[#(#enum_name::#variants),*];
+            /// The name of the level, the same as the name of its event macro.
+            pub fn name(self) -> &'static str {
+                match self {
+                    #(Self::#variants => #names,)*
+                }
+            }
+            /// The level of the given name.
+            pub fn from_name(name: &str) -> Option<Self> {
+                Self::ALL.iter().copied().find(|level| level.name() == name)
+            }
+        }
+    };
+    let exports = vec![Export { ident: enum_name, prelude: false }];
+    Api { implementation, exports }
+}
+
+
+
 // =====================
 // === LogSpan trait ===
 // =====================
@@ -348,27 +387,34 @@ fn trait_methods(level: &str) -> LoggerMethods<syn::Ident> {
 // =================
 
 fn event_api(levels: impl IntoIterator<Item = &Level>) -> Api {
-    levels
-        .into_iter()
-        .map(|level| event_api_for_level(&level.name, &level.global_logger_methods, level.enabled))
-        .collect()
+    levels.into_iter().map(event_api_for_level).collect()
 }
 
-fn event_api_for_level(level: &str, methods: &LoggerMethods<syn::Path>, enabled: bool) -> Api {
-    let event_macro = ident(level);
-    let emit_fn = &methods.emit_fn;
-    let level_tag = level.to_screaming_snake_case();
-    let body = if enabled {
+/// The events of levels enabled at compile time are filtered by the runtime module levels (see the
+/// `filter` module), emitted to the global logger, and recorded in the ring buffer (see the
+/// `buffer` module).
+fn event_api_for_level(level: &Level) -> Api {
+    let event_macro = ident(&level.name);
+    let variant = &level.variant;
+    let emit_fn = &level.global_logger_methods.emit_fn;
+    let level_tag = level.name.to_screaming_snake_case();
+    let body = if level.enabled {
         quote! {
-            use $crate::internal::Logger;
-            $crate::internal::#emit_fn(
-                &format!(
-                    "[{}] {}:{} {}",
-                    #level_tag,
-                    file!(),
-                    line!(),
-                    format_args!($($args)*)
-                ));
+            let module = module_path!();
+            if $crate::filter::is_enabled($crate::Level::#variant, module) {
+                use $crate::internal::Logger;
+                let message = format!($($args)*);
+                $crate::internal::#emit_fn(
+                    &format!("[{}] {}:{} {}", #level_tag, file!(), line!(), message)
+                );
+                $crate::buffer::push($crate::buffer::Record {
+                    level: $crate::Level::#variant,
+                    module,
+                    file: file!(),
+                    line: line!(),
+                    message,
+                });
+            }
         }
     } else {
         quote! {
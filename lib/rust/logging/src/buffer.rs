@@ -0,0 +1,190 @@
+//! A bounded in-memory buffer of the latest log records. It allows inspecting the logs inside the
+//! application, for example in a log viewer panel, without opening the browser console.
+
+use crate::Level;
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::fmt;
+
+
+
+// =================
+// === Constants ===
+// =================
+
+/// The default number of records kept in the buffer.
+pub const DEFAULT_CAPACITY: usize = 1000;
+
+
+
+// ==============
+// === Record ===
+// ==============
+
+/// A single log event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    /// The level of the event.
+    pub level:   Level,
+    /// The path of the module which emitted the event.
+    pub module:  &'static str,
+    /// The source file which emitted the event.
+    pub file:    &'static str,
+    /// The line of the source file.
+    pub line:    u32,
+    /// The formatted message.
+    pub message: String,
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = self.level.name().to_uppercase();
+        write!(f, "[{level}] {}: {}", self.module, self.message)
+    }
+}
+
+
+
+// ==================
+// === RingBuffer ===
+// ==================
+
+/// The buffer of the latest records. When the capacity is exceeded, the oldest records are dropped.
+/// Every record has an index, counting all records pushed to the buffer.
+#[derive(Clone, Debug)]
+pub struct RingBuffer {
+    records:      VecDeque<Record>,
+    capacity:     usize,
+    pushed_count: usize,
+}
+
+impl Default for RingBuffer {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl RingBuffer {
+    /// Constructor.
+    pub fn new(capacity: usize) -> Self {
+        let records = VecDeque::new();
+        let pushed_count = 0;
+        Self { records, capacity, pushed_count }
+    }
+
+    /// Add the record, dropping the oldest one if the capacity is exceeded.
+    pub fn push(&mut self, record: Record) {
+        self.records.push_back(record);
+        self.pushed_count += 1;
+        self.truncate();
+    }
+
+    /// Change the capacity, dropping the oldest records if needed.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.truncate();
+    }
+
+    fn truncate(&mut self) {
+        let excess = self.records.len().saturating_sub(self.capacity);
+        self.records.drain(..excess);
+    }
+
+    /// The number of records pushed since the buffer was created. It is the index of the next
+    /// record.
+    pub fn pushed_count(&self) -> usize {
+        self.pushed_count
+    }
+
+    /// The records with indices greater or equal to the given one, still present in the buffer.
+    pub fn records_since(&self, index: usize) -> Vec<Record> {
+        let first_index = self.pushed_count - self.records.len();
+        let skipped = index.saturating_sub(first_index);
+        self.records.iter().skip(skipped).cloned().collect()
+    }
+
+    /// All records in the buffer, from the oldest one.
+    pub fn records(&self) -> Vec<Record> {
+        self.records.iter().cloned().collect()
+    }
+
+    /// Remove all records. The indices of new records continue from the previous ones.
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+}
+
+
+
+// =====================
+// === Global Buffer ===
+// =====================
+
+thread_local! {
+    static BUFFER: RefCell<RingBuffer> = RefCell::new(RingBuffer::default());
+}
+
+/// Add the record to the global buffer. Used by the event macros.
+pub fn push(record: Record) {
+    // The buffer may be already destroyed if an event is logged during the thread exit.
+    let _ = BUFFER.try_with(|buffer| buffer.borrow_mut().push(record));
+}
+
+/// Change the capacity of the global buffer.
+pub fn set_capacity(capacity: usize) {
+    BUFFER.with(|buffer| buffer.borrow_mut().set_capacity(capacity))
+}
+
+/// The number of records pushed to the global buffer. See [`RingBuffer::pushed_count`].
+pub fn pushed_count() -> usize {
+    BUFFER.with(|buffer| buffer.borrow().pushed_count())
+}
+
+/// The records of the global buffer since the given index. See [`RingBuffer::records_since`].
+pub fn records_since(index: usize) -> Vec<Record> {
+    BUFFER.with(|buffer| buffer.borrow().records_since(index))
+}
+
+/// All records of the global buffer.
+pub fn records() -> Vec<Record> {
+    BUFFER.with(|buffer| buffer.borrow().records())
+}
+
+/// Remove all records from the global buffer.
+pub fn clear() {
+    BUFFER.with(|buffer| buffer.borrow_mut().clear())
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(message: &str) -> Record {
+        let message = message.into();
+        Record { level: Level::Warn, module: module_path!(), file: file!(), line: 0, message }
+    }
+
+    #[test]
+    fn oldest_records_are_dropped() {
+        let mut buffer = RingBuffer::new(2);
+        buffer.push(record("a"));
+        buffer.push(record("b"));
+        buffer.push(record("c"));
+        let messages = |records: Vec<Record>| -> Vec<String> {
+            records.into_iter().map(|record| record.message).collect()
+        };
+        assert_eq!(messages(buffer.records()), ["b", "c"]);
+        assert_eq!(buffer.pushed_count(), 3);
+        assert_eq!(messages(buffer.records_since(0)), ["b", "c"]);
+        assert_eq!(messages(buffer.records_since(2)), ["c"]);
+        assert!(buffer.records_since(3).is_empty());
+        assert_eq!(record("d").to_string(), "[WARN] enso_logging::buffer::tests: d");
+    }
+}
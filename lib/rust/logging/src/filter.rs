@@ -0,0 +1,125 @@
+//! Runtime control of the log levels. The levels enabled at compile time (see the
+//! `ENSO_MAX_LOG_LEVEL` environment variable) can be further limited at runtime, both globally and
+//! per module.
+//!
+//! The module levels apply to the module and all its submodules, identified by their paths, like
+//! `ensogl_text::component`. If several configured modules contain the logging module, the level of
+//! the most nested one is used.
+
+use crate::Level;
+
+use std::cell::RefCell;
+
+
+
+// ==============
+// === Filter ===
+// ==============
+
+/// The maximum enabled log levels. [`None`] disables all levels.
+#[derive(Clone, Debug)]
+pub struct Filter {
+    max_level:         Option<Level>,
+    module_max_levels: Vec<(String, Option<Level>)>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        let max_level = Level::ALL.last().copied();
+        let module_max_levels = Vec::new();
+        Self { max_level, module_max_levels }
+    }
+}
+
+impl Filter {
+    /// Set the maximum level of modules without a configured level.
+    pub fn set_max_level(&mut self, level: Option<Level>) {
+        self.max_level = level;
+    }
+
+    /// Set the maximum level of the module and its submodules.
+    pub fn set_module_max_level(&mut self, module: impl Into<String>, level: Option<Level>) {
+        let module = module.into();
+        self.reset_module_max_level(&module);
+        self.module_max_levels.push((module, level));
+    }
+
+    /// Remove the configured level of the module. The module will use the level of the enclosing
+    /// configured module or the global one.
+    pub fn reset_module_max_level(&mut self, module: &str) {
+        self.module_max_levels.retain(|(configured, _)| configured != module);
+    }
+
+    /// The maximum level of the module.
+    pub fn max_level_of(&self, module: &str) -> Option<Level> {
+        let contains = |configured: &str| {
+            let nested = module.strip_prefix(configured);
+            nested.map_or(false, |nested| nested.is_empty() || nested.starts_with("::"))
+        };
+        let matching = self.module_max_levels.iter().filter(|(configured, _)| contains(configured));
+        let most_nested = matching.max_by_key(|(configured, _)| configured.len());
+        most_nested.map_or(self.max_level, |(_, level)| *level)
+    }
+
+    /// Check whether the events of the level are enabled in the module.
+    pub fn is_enabled(&self, level: Level, module: &str) -> bool {
+        self.max_level_of(module).map_or(false, |max_level| level <= max_level)
+    }
+}
+
+
+
+// =====================
+// === Global Filter ===
+// =====================
+
+thread_local! {
+    static FILTER: RefCell<Filter> = RefCell::new(Filter::default());
+}
+
+/// Set the maximum level of modules without a configured level.
+pub fn set_max_level(level: Option<Level>) {
+    FILTER.with(|filter| filter.borrow_mut().set_max_level(level))
+}
+
+/// Set the maximum level of the module and its submodules.
+pub fn set_module_max_level(module: impl Into<String>, level: Option<Level>) {
+    FILTER.with(|filter| filter.borrow_mut().set_module_max_level(module, level))
+}
+
+/// Remove the configured level of the module.
+pub fn reset_module_max_level(module: &str) {
+    FILTER.with(|filter| filter.borrow_mut().reset_module_max_level(module))
+}
+
+/// Check whether the events of the level are enabled in the module. Used by the event macros.
+pub fn is_enabled(level: Level, module: &str) -> bool {
+    // The filter may be already destroyed if an event is logged during the thread exit.
+    FILTER.try_with(|filter| filter.borrow().is_enabled(level, module)).unwrap_or(true)
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_nested_module_level_is_used() {
+        let mut filter = Filter::default();
+        assert!(filter.is_enabled(Level::Trace, "app::view"));
+        filter.set_max_level(Some(Level::Warn));
+        filter.set_module_max_level("app", Some(Level::Info));
+        filter.set_module_max_level("app::view", None);
+        assert!(!filter.is_enabled(Level::Info, "lib"));
+        assert!(filter.is_enabled(Level::Info, "app::model"));
+        assert!(!filter.is_enabled(Level::Error, "app::view::node"));
+        assert!(!filter.is_enabled(Level::Info, "application"));
+        filter.reset_module_max_level("app::view");
+        assert!(filter.is_enabled(Level::Info, "app::view::node"));
+    }
+}
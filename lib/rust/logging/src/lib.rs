@@ -1,4 +1,8 @@
 //! High-performance logging library.
+//!
+//! The log events are emitted with the level macros, like [`warn!`]. Levels can be disabled both at
+//! compile time and at runtime, globally or per module (see [`filter`]). The latest events are kept
+//! in a ring buffer (see [`buffer`]), so they can be inspected inside the application.
 
 // === Features ===
 #![feature(local_key_cell_methods)]
@@ -17,5 +21,13 @@
 #![warn(unused_import_braces)]
 
 
+// ==============
+// === Export ===
+// ==============
+
+pub mod buffer;
+pub mod filter;
+
+
 
 enso_logging_macros::define_log_levels![Error, Warn, Info, Debug, Trace];
@@ -43,6 +43,15 @@ impl Sub<Byte> for Byte {
     }
 }
 
+impl Byte {
+    /// Add the byte difference, saturating at zero. Unlike the [`Add`] implementation, it does not
+    /// report an error on underflow.
+    pub fn saturating_add_diff(self, diff: ByteDiff) -> Self {
+        let value = self.value as i64 + diff.value as i64;
+        Byte(value.max(0) as usize)
+    }
+}
+
 impl Sub<ByteDiff> for Byte {
     type Output = Byte;
     fn sub(self, rhs: ByteDiff) -> Self::Output {
@@ -311,6 +320,24 @@ macro_rules! define_line_unit {
             pub fn inc(self) -> Self {
                 self + $name(1)
             }
+
+            /// Add the value, saturating at the numeric bounds.
+            pub fn saturating_add(self, rhs: usize) -> Self {
+                $name(self.value.saturating_add(rhs))
+            }
+
+            /// Subtract the value, saturating at zero. Unlike the [`Sub`] implementation, it does
+            /// not report an error on underflow.
+            pub fn saturating_sub(self, rhs: usize) -> Self {
+                $name(self.value.saturating_sub(rhs))
+            }
+
+            /// Add the line difference, saturating at zero. Unlike the [`Add`] implementation, it
+            /// does not report an error on underflow.
+            pub fn saturating_add_diff(self, diff: LineDiff) -> Self {
+                let value = self.value as i64 + diff.value as i64;
+                $name(value.max(0) as usize)
+            }
         }
 
         impl iter::Step for $name {
@@ -101,6 +101,7 @@
 // ==============
 
 pub mod index;
+pub mod line_widths;
 pub mod range;
 pub mod rope;
 pub mod spans;
@@ -108,6 +109,7 @@ pub mod text;
 pub mod unit;
 
 pub use index::*;
+pub use line_widths::LineWidthIndex;
 pub use range::Range;
 pub use range::RangeBounds;
 pub use rope::metric;
@@ -0,0 +1,280 @@
+//! An index of the widths of all lines of a text, updated incrementally after text changes. It
+//! answers the widest line query in logarithmic time, so the horizontal scroll extent of a text
+//! area can be maintained without scanning the whole text after every edit. It also counts the
+//! characters and the words of every line, so the statistics of the whole text are maintained
+//! incrementally as well.
+
+use crate::index::*;
+use crate::prelude::*;
+use crate::unit::*;
+
+use crate::Rope;
+
+
+
+// =================
+// === LineWidth ===
+// =================
+
+/// The width of the line in characters (code points), excluding the line ending. Returns 0 for
+/// lines out of the text.
+/// ```
+/// # use enso_text::*;
+/// # use enso_text::line_widths::line_width;
+/// let rope = Rope::from("ab\r\nabc\n");
+/// assert_eq!(line_width(&rope, Line(0)), 2);
+/// assert_eq!(line_width(&rope, Line(1)), 3);
+/// assert_eq!(line_width(&rope, Line(2)), 0);
+/// ```
+pub fn line_width(text: &Rope, line: Line) -> usize {
+    text.line_range(line).map_or(0, |range| text.sub(range).code_point_count())
+}
+
+
+
+// =================
+// === LineStats ===
+// =================
+
+/// The measurements of a single line of a text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct LineStats {
+    /// See [`line_width`].
+    width: usize,
+    /// The number of characters (code points), including the line ending.
+    chars: usize,
+    /// The number of words, see [`Rope::word_count`]. Words never span multiple lines, as the
+    /// line endings are whitespace.
+    words: usize,
+}
+
+impl LineStats {
+    /// Measure the line. Returns zeros for lines out of the text.
+    fn new(text: &Rope, line: Line) -> Self {
+        let Ok(range) = text.line_range(line) else { return default() };
+        let content = text.sub(range.clone());
+        let next_line_start = text.line_range(Line(line.value + 1)).map(|next| next.start);
+        let end_with_line_ending = next_line_start.unwrap_or(range.end);
+        let width = content.code_point_count();
+        let chars = text.sub(range.start..end_with_line_ending).code_point_count();
+        let words = content.word_count();
+        Self { width, chars, words }
+    }
+}
+
+
+
+// ======================
+// === LineWidthIndex ===
+// ======================
+
+/// The widths of all lines of a text, see [`line_width`]. The index is a list of the line widths
+/// with a multiset of the widths, so the widest line is found in logarithmic time, and updating
+/// it after a change requires measuring the changed lines only.
+#[derive(Clone, Debug)]
+pub struct LineWidthIndex {
+    lines:      Vec<LineStats>,
+    /// The number of lines of every width.
+    counts:     BTreeMap<usize, usize>,
+    char_count: usize,
+    word_count: usize,
+}
+
+impl LineWidthIndex {
+    /// Constructor, measuring all lines of the text.
+    pub fn new(text: &Rope) -> Self {
+        let mut index =
+            Self { lines: default(), counts: default(), char_count: 0, word_count: 0 };
+        for line in 0..=text.last_line_index().value {
+            let stats = LineStats::new(text, Line(line));
+            index.lines.push(stats);
+            index.add(stats);
+        }
+        index
+    }
+
+    /// The number of indexed lines.
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// The width of the line, or [`None`] if the line is out of the text.
+    pub fn line_width(&self, line: Line) -> Option<usize> {
+        self.lines.get(line.value).map(|stats| stats.width)
+    }
+
+    /// The number of characters (code points) of the whole text, the same as
+    /// [`Rope::code_point_count`].
+    /// ```
+    /// # use enso_text::*;
+    /// # use enso_text::line_widths::LineWidthIndex;
+    /// assert_eq!(LineWidthIndex::new(&Rope::from("ab\r\nabcd\n")).char_count(), 9);
+    /// ```
+    pub fn char_count(&self) -> usize {
+        self.char_count
+    }
+
+    /// The number of words of the whole text, the same as [`Rope::word_count`].
+    /// ```
+    /// # use enso_text::*;
+    /// # use enso_text::line_widths::LineWidthIndex;
+    /// assert_eq!(LineWidthIndex::new(&Rope::from("foo bar\n\tbaz")).word_count(), 3);
+    /// ```
+    pub fn word_count(&self) -> usize {
+        self.word_count
+    }
+
+    /// The width of the widest line.
+    /// ```
+    /// # use enso_text::*;
+    /// # use enso_text::line_widths::LineWidthIndex;
+    /// assert_eq!(LineWidthIndex::new(&Rope::from("ab\nabcd\n")).max_line_width(), 4);
+    /// ```
+    pub fn max_line_width(&self) -> usize {
+        self.counts.last_key_value().map_or(0, |(width, _)| *width)
+    }
+
+    /// Update the index after the text changes. Every change is described by the range of the
+    /// replaced lines and the difference of the number of lines it introduced, with the range
+    /// expressed in the text after applying the previous changes. The `text` is the text after
+    /// applying all the changes, used to measure the changed lines.
+    ///
+    /// If the changes do not match the text, the whole text is measured again.
+    pub fn apply_changes(
+        &mut self,
+        text: &Rope,
+        changes: impl IntoIterator<Item = (RangeInclusive<Line>, LineDiff)>,
+    ) {
+        // The lines inserted by the changes, expressed in the text after all changes. They are
+        // measured at the end, as the text after the intermediate changes is not known.
+        let mut dirty: Vec<RangeInclusive<usize>> = vec![];
+        for (lines, line_diff) in changes {
+            let start = lines.start().value;
+            let end = lines.end().value;
+            let new_line_count = end as i64 - start as i64 + 1 + line_diff.value as i64;
+            if start > end || end >= self.lines.len() || new_line_count < 1 {
+                *self = Self::new(text);
+                return;
+            }
+            let new_end = start + new_line_count as usize - 1;
+            let shift = |line: usize| (line as i64 + line_diff.value as i64) as usize;
+            let mut merged = start..=new_end;
+            dirty.retain_mut(|range| {
+                if *range.end() < start {
+                    true
+                } else if *range.start() > end {
+                    *range = shift(*range.start())..=shift(*range.end());
+                    true
+                } else {
+                    let merged_start = (*range.start()).min(*merged.start());
+                    let merged_end = shift((*range.end()).max(end));
+                    merged = merged_start..=merged_end.max(*merged.end());
+                    false
+                }
+            });
+            dirty.push(merged);
+            let new_lines = iter::repeat(LineStats::default()).take(new_line_count as usize);
+            for stats in self.lines.splice(start..=end, new_lines).collect_vec() {
+                self.remove(stats);
+            }
+            *self.counts.entry(0).or_default() += new_line_count as usize;
+        }
+        if self.lines.len() != text.last_line_index().value + 1 {
+            *self = Self::new(text);
+            return;
+        }
+        for line in dirty.into_iter().flatten() {
+            self.set_line_stats(line, LineStats::new(text, Line(line)));
+        }
+    }
+
+    fn set_line_stats(&mut self, line: usize, stats: LineStats) {
+        let old_stats = mem::replace(&mut self.lines[line], stats);
+        self.remove(old_stats);
+        self.add(stats);
+    }
+
+    fn add(&mut self, stats: LineStats) {
+        *self.counts.entry(stats.width).or_default() += 1;
+        self.char_count += stats.chars;
+        self.word_count += stats.words;
+    }
+
+    fn remove(&mut self, stats: LineStats) {
+        if let Some(count) = self.counts.get_mut(&stats.width) {
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&stats.width);
+            }
+        }
+        self.char_count -= stats.chars;
+        self.word_count -= stats.words;
+    }
+}
+
+impl Default for LineWidthIndex {
+    fn default() -> Self {
+        Self::new(&default())
+    }
+}
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replace the byte range, returning the change description expected by the index.
+    fn replace(
+        rope: &mut Rope,
+        range: Range<usize>,
+        text: &str,
+    ) -> (RangeInclusive<Line>, LineDiff) {
+        let start = rope.line_snapped(Byte(range.start));
+        let end = rope.line_snapped(Byte(range.end));
+        rope.replace(Byte(range.start)..Byte(range.end), text);
+        let inserted_lines = Rope::from(text).last_line_index().value as i32;
+        let line_diff = LineDiff(start.value as i32 + inserted_lines - end.value as i32);
+        (start..=end, line_diff)
+    }
+
+    fn assert_matches_text(index: &LineWidthIndex, rope: &Rope) {
+        let mut widths = vec![];
+        rope.for_each_line_length(|width| widths.push(width));
+        assert_eq!(index.lines.iter().map(|stats| stats.width).collect_vec(), widths);
+        assert_eq!(index.max_line_width(), rope.longest_line().1);
+        assert_eq!(index.char_count(), rope.code_point_count());
+        assert_eq!(index.word_count(), rope.word_count());
+    }
+
+    #[test]
+    fn index_follows_changes() {
+        let mut rope = Rope::from("first\nsecond line\n\nfourth");
+        let mut index = LineWidthIndex::new(&rope);
+        assert_matches_text(&index, &rope);
+
+        let change = replace(&mut rope, 2..2, "a longer\nline break");
+        index.apply_changes(&rope, [change]);
+        assert_matches_text(&index, &rope);
+
+        let change = replace(&mut rope, 0..20, "x");
+        index.apply_changes(&rope, [change]);
+        assert_matches_text(&index, &rope);
+
+        // A batch of changes, every one expressed in the text after the previous ones.
+        let first = replace(&mut rope, 0..0, "1\n2\n3\n");
+        let second = replace(&mut rope, 8..10, "");
+        let third = replace(&mut rope, 2..3, "a very long line\n");
+        let last = rope.last_byte_index().value;
+        let fourth = replace(&mut rope, last..last, "\nthe end");
+        index.apply_changes(&rope, [first, second, third, fourth]);
+        assert_matches_text(&index, &rope);
+
+        index.apply_changes(&rope, [(Line(100)..=Line(101), LineDiff(0))]);
+        assert_matches_text(&index, &rope);
+    }
+}
@@ -6,6 +6,8 @@ use crate::prelude::*;
 use crate::unit::*;
 
 use crate::rope;
+use std::cmp::max;
+use std::cmp::min;
 
 
 
@@ -107,6 +109,48 @@ impl<T> Range<T> {
     }
 }
 
+impl<T: Copy + Ord> Range<T> {
+    /// Return the range with `start` and `end` swapped if `start` was bigger than `end`.
+    pub fn normalized(&self) -> Self {
+        Self { start: min(self.start, self.end), end: max(self.start, self.end) }
+    }
+
+    /// Check whether `start` is not bigger than `end`.
+    pub fn is_normalized(&self) -> bool {
+        self.start <= self.end
+    }
+
+    /// Return the smallest range containing this range and the provided value.
+    pub fn extend_to_include(&self, value: T) -> Self {
+        Self { start: min(self.start, value), end: max(self.end, value) }
+    }
+
+    /// The common part of both ranges. Ranges which are only touching (the end of one of them is
+    /// the start of the other) have an empty intersection. Returns [`None`] if ranges are
+    /// disjoint.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let start = max(self.start, other.start);
+        let end = min(self.end, other.end);
+        (start <= end).then_some(Self { start, end })
+    }
+
+    /// The smallest range containing both ranges. Please note that it contains the gap between
+    /// the ranges if they are disjoint.
+    pub fn union(&self, other: &Self) -> Self {
+        Self { start: min(self.start, other.start), end: max(self.end, other.end) }
+    }
+
+    /// Check whether the ranges have at least one common value. Empty ranges overlap nothing.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        max(self.start, other.start) < min(self.end, other.end)
+    }
+
+    /// Clamp the value to the range bounds, inclusive on both sides.
+    pub fn clamp(&self, value: T) -> T {
+        max(self.start, min(self.end, value))
+    }
+}
+
 impl<Offset, Line: PartialEq> Range<Location<Offset, Line>> {
     /// Checks whether the range describes a single line.
     pub fn single_line(&self) -> bool {
@@ -289,3 +333,77 @@ impl RangeBounds for RangeFull {
         Range::new(0.byte(), upper_bound)
     }
 }
+
+
+
+// =============
+// === Tests ===
+// =============
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// All ranges with bounds in `0..=max_bound`, including not normalized ones.
+    fn all_ranges(max_bound: usize) -> Vec<Range<Byte>> {
+        let bounds = (0..=max_bound).map(Byte);
+        bounds
+            .clone()
+            .cartesian_product(bounds)
+            .map(|(start, end)| Range::new(start, end))
+            .collect()
+    }
+
+    fn values(range: &Range<Byte>) -> Vec<Byte> {
+        (range.start.value..range.end.value).map(Byte).collect()
+    }
+
+    #[test]
+    fn intersection_and_union_properties() {
+        let ranges = all_ranges(5).into_iter().filter(|r| r.is_normalized()).collect_vec();
+        for (a, b) in ranges.iter().cartesian_product(&ranges) {
+            let union = a.union(b);
+            assert!(union.contains_range(a) && union.contains_range(b));
+            assert_eq!(union, b.union(a));
+            assert_eq!(a.intersection(b), b.intersection(a));
+            let common = values(a).into_iter().filter(|v| b.contains(v)).collect_vec();
+            match a.intersection(b) {
+                Some(intersection) => {
+                    assert!(a.contains_range(&intersection) && b.contains_range(&intersection));
+                    assert_eq!(values(&intersection), common);
+                }
+                None => assert!(common.is_empty()),
+            }
+            assert_eq!(a.overlaps(b), !common.is_empty());
+        }
+    }
+
+    #[test]
+    fn extending_and_clamping_properties() {
+        for range in all_ranges(5) {
+            let normalized = range.normalized();
+            assert!(normalized.is_normalized());
+            assert_eq!(normalized, normalized.normalized());
+            for value in (0..=6).map(Byte) {
+                let extended = normalized.extend_to_include(value);
+                assert!(extended.contains_range(&normalized));
+                assert!(extended.start <= value && value <= extended.end);
+                let clamped = normalized.clamp(value);
+                assert!(normalized.start <= clamped && clamped <= normalized.end);
+                if normalized.start <= value && value <= normalized.end {
+                    assert_eq!(clamped, value);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn saturating_line_and_byte_arithmetic() {
+        assert_eq!(Line(2).saturating_sub(3), Line(0));
+        assert_eq!(Line(2).saturating_add(usize::MAX), Line(usize::MAX));
+        assert_eq!(Line(2).saturating_add_diff(LineDiff(-3)), Line(0));
+        assert_eq!(Line(2).saturating_add_diff(LineDiff(3)), Line(5));
+        assert_eq!(Byte(2).saturating_add_diff(ByteDiff(-3)), Byte(0));
+        assert_eq!(Byte(2).saturating_add_diff(ByteDiff(3)), Byte(5));
+    }
+}
@@ -62,6 +62,35 @@ impl<T: Clone + Debug> Spans<T> {
         Self { raw: self.raw.subseq(range.into_rope_interval()) }
     }
 
+    /// The number of spans in the tree. See [`Self::compact`] to learn more.
+    pub fn span_count(&self) -> usize {
+        self.raw.iter().count()
+    }
+
+    /// Merge adjacent spans holding equal values. Every edit splits the affected spans, so after
+    /// many edits the tree contains a lot of fragments, which slows down all further operations.
+    pub fn compact(&mut self)
+    where T: PartialEq {
+        let mut builder = rope::spans::Builder::new(self.raw.len());
+        let mut current: Option<(usize, usize, T)> = None;
+        for (interval, value) in self.raw.iter() {
+            if let Some((_, end, current_value)) = &mut current {
+                if *end == interval.start && *current_value == *value {
+                    *end = interval.end;
+                    continue;
+                }
+            }
+            let next = (interval.start, interval.end, value.clone());
+            if let Some((start, end, value)) = current.replace(next) {
+                builder.add_span(start..end, value);
+            }
+        }
+        if let Some((start, end, value)) = current {
+            builder.add_span(start..end, value);
+        }
+        self.raw = builder.build();
+    }
+
     // FIXME: convert to iterator
     /// Convert the span tree to vector of non-overlapping ranges and their values.
     pub fn to_vector(&self) -> Vec<RangedValue<Byte, T>> {
@@ -313,6 +342,24 @@ impl<I, A> RangedValue<I, A> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_compact_spans() {
+        let mut spans = Spans::<Option<i32>>::default();
+        spans.replace_resize((Byte(0)..Byte(0)).into(), Byte(6), None);
+        for byte in 0..6 {
+            let value = if byte < 4 { Some(1) } else { Some(2) };
+            spans.replace_resize((Byte(byte)..Byte(byte + 1)).into(), Byte(1), value);
+        }
+        assert_eq!(spans.span_count(), 6);
+        spans.compact();
+        assert_eq!(spans.span_count(), 2);
+        assert_eq!(spans.len(), Byte(6));
+        assert_eq!(spans.to_vector(), vec![
+            RangedValue::new(Byte(0)..Byte(4), Some(1)),
+            RangedValue::new(Byte(4)..Byte(6), Some(2)),
+        ]);
+    }
+
     type V1 = Vec<RangedValue<i32, i32>>;
     type V2 = Vec<RangedValue<i32, char>>;
 
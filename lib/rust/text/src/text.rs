@@ -590,6 +590,128 @@ impl Rope {
     pub fn line_len_up_to_offset(&self, tgt_offset: Byte) -> Result<Byte, BoundsError> {
         self.offset_to_location(tgt_offset).map(|location| location.offset)
     }
+
+    /// The number of code points in this text.
+    /// ```
+    /// # use enso_text::*;
+    /// assert_eq!(Rope::from("a🧑🏾ட்").code_point_count(), 5);
+    /// ```
+    pub fn code_point_count(&self) -> usize {
+        self.code_point_count_up_to(self.last_byte_index())
+    }
+
+    /// The number of code points before the given byte offset, which must be a code point
+    /// boundary. The rope is traversed chunk by chunk, without materializing it as a single
+    /// string.
+    fn code_point_count_up_to(&self, offset: Byte) -> usize {
+        self.rope.iter_chunks(0..offset.value).map(|chunk| chunk.chars().count()).sum()
+    }
+
+    /// The index of the code point starting at the given byte offset. In case the offset points
+    /// inside of a code point, the [`LocationError::NotClusterBoundary`] error with the index of
+    /// the next code point is returned.
+    /// ```
+    /// # use enso_text::*;
+    /// let rope = Rope::from("a🧑🏾ட்");
+    /// assert_eq!(rope.code_point_index_of_byte_offset(Byte(0)).ok(), Some(CodePointIndex(0)));
+    /// assert_eq!(rope.code_point_index_of_byte_offset(Byte(1)).ok(), Some(CodePointIndex(1)));
+    /// assert_eq!(rope.code_point_index_of_byte_offset(Byte(5)).ok(), Some(CodePointIndex(2)));
+    /// assert_eq!(rope.code_point_index_of_byte_offset(Byte(15)).ok(), Some(CodePointIndex(5)));
+    /// assert!(rope.code_point_index_of_byte_offset(Byte(2)).is_err());
+    /// assert!(rope.code_point_index_of_byte_offset(Byte(16)).is_err());
+    /// ```
+    pub fn code_point_index_of_byte_offset(
+        &self,
+        offset: Byte,
+    ) -> Result<CodePointIndex, LocationError<CodePointIndex>> {
+        self.validate_byte_offset(offset)?;
+        let mut boundary = offset;
+        while !self.rope.is_codepoint_boundary(boundary.value) {
+            boundary -= ByteDiff(1);
+        }
+        let index = CodePointIndex(self.code_point_count_up_to(boundary));
+        if boundary == offset {
+            Ok(index)
+        } else {
+            Err(LocationError::NotClusterBoundary(index + CodePointIndex(1)))
+        }
+    }
+
+    /// The index of the code point starting at the given byte offset. Snapped to the closest valid
+    /// value. In case the offset points inside of a code point, it will be snapped to its right
+    /// side.
+    pub fn code_point_index_of_byte_offset_snapped(&self, offset: Byte) -> CodePointIndex {
+        use self::BoundsError::*;
+        use LocationError::*;
+        match self.code_point_index_of_byte_offset(offset) {
+            Ok(index) => index,
+            Err(BoundsError(TooSmall)) => CodePointIndex(0),
+            Err(BoundsError(TooBig)) => CodePointIndex(self.code_point_count()),
+            Err(LineTooShort(index)) => index,
+            Err(NotClusterBoundary(index)) => index,
+        }
+    }
+
+    /// The byte offset of the code point with the given index. The index equal to the number of
+    /// code points in the text points to the text end.
+    /// ```
+    /// # use enso_text::*;
+    /// let rope = Rope::from("a🧑🏾ட்");
+    /// assert_eq!(rope.byte_offset_of_code_point_index(CodePointIndex(0)).ok(), Some(Byte(0)));
+    /// assert_eq!(rope.byte_offset_of_code_point_index(CodePointIndex(2)).ok(), Some(Byte(5)));
+    /// assert_eq!(rope.byte_offset_of_code_point_index(CodePointIndex(5)).ok(), Some(Byte(15)));
+    /// assert!(rope.byte_offset_of_code_point_index(CodePointIndex(6)).is_err());
+    /// ```
+    pub fn byte_offset_of_code_point_index(
+        &self,
+        index: CodePointIndex,
+    ) -> Result<Byte, BoundsError> {
+        let mut remaining = index.value;
+        let mut chunk_start = 0;
+        for chunk in self.rope.iter_chunks(..) {
+            if let Some((offset, _)) = chunk.char_indices().nth(remaining) {
+                return Ok(Byte(chunk_start + offset));
+            }
+            remaining -= chunk.chars().count();
+            chunk_start += chunk.len();
+        }
+        (remaining == 0).then_some(Byte(chunk_start)).ok_or(BoundsError::TooBig)
+    }
+
+    /// The byte offset of the code point with the given index. Snapped to the text end in case the
+    /// index was too big.
+    pub fn byte_offset_of_code_point_index_snapped(&self, index: CodePointIndex) -> Byte {
+        self.byte_offset_of_code_point_index(index).unwrap_or_else(|_| self.last_byte_index())
+    }
+
+    /// Snap the code point index to the grapheme cluster boundary. In case the index points inside
+    /// of a grapheme cluster, it will be snapped to its right side.
+    /// ```
+    /// # use enso_text::*;
+    /// let rope = Rope::from("a🧑🏾ட்");
+    /// let snapped = |index| rope.snap_code_point_index_to_grapheme(CodePointIndex(index)).value;
+    /// assert_eq!((0..=6).map(snapped).collect::<Vec<_>>(), vec![0, 1, 3, 3, 5, 5, 5]);
+    /// ```
+    pub fn snap_code_point_index_to_grapheme(&self, index: CodePointIndex) -> CodePointIndex {
+        let offset = self.byte_offset_of_code_point_index_snapped(index);
+        let snapped_offset = match self.next_grapheme_offset(offset) {
+            Some(next) if self.prev_grapheme_offset(next) != Some(offset) => next,
+            _ => offset,
+        };
+        self.code_point_index_of_byte_offset_snapped(snapped_offset)
+    }
+
+    /// The location of the given code point index. Snapped to the closest valid value.
+    pub fn location_of_code_point_index_snapped(&self, index: CodePointIndex) -> Location {
+        let offset = self.byte_offset_of_code_point_index_snapped(index);
+        Location::<Column, Line>::from_in_context_snapped(self, offset)
+    }
+
+    /// The code point index of the given location. Snapped to the closest valid value.
+    pub fn code_point_index_of_location_snapped(&self, location: Location) -> CodePointIndex {
+        let offset = Byte::from_in_context_snapped(self, location);
+        self.code_point_index_of_byte_offset_snapped(offset)
+    }
 }
 
 
@@ -861,6 +983,101 @@ impl Rope {
     }
 }
 
+
+// === Statistics ===
+
+impl Rope {
+    /// The number of words, being sequences of non-whitespace characters.
+    /// ```
+    /// # use enso_text::*;
+    /// assert_eq!(Rope::from("  foo bar\n\tbaz ").word_count(), 3);
+    /// assert_eq!(Rope::from("").word_count(), 0);
+    /// ```
+    pub fn word_count(&self) -> usize {
+        let mut count = 0;
+        let mut in_word = false;
+        for chunk in self.rope.iter_chunks(..) {
+            for char in chunk.chars() {
+                let is_word_char = !char.is_whitespace();
+                if is_word_char && !in_word {
+                    count += 1;
+                }
+                in_word = is_word_char;
+            }
+        }
+        count
+    }
+
+    /// Call the function with the length of every line, in characters, excluding the line
+    /// endings. Just like [`Rope::last_line_index`], the text ending with a newline has an empty
+    /// last line.
+    pub fn for_each_line_length(&self, mut f: impl FnMut(usize)) {
+        let mut length = 0;
+        let mut pending_carriage_return = false;
+        for chunk in self.rope.iter_chunks(..) {
+            for char in chunk.chars() {
+                if char == '\n' {
+                    f(length);
+                    length = 0;
+                    pending_carriage_return = false;
+                    continue;
+                }
+                if pending_carriage_return {
+                    length += 1;
+                }
+                pending_carriage_return = char == '\r';
+                if !pending_carriage_return {
+                    length += 1;
+                }
+            }
+        }
+        if pending_carriage_return {
+            length += 1;
+        }
+        f(length);
+    }
+
+    /// The index and the length in characters of the longest line. If there are many such lines,
+    /// the first one is returned.
+    /// ```
+    /// # use enso_text::*;
+    /// assert_eq!(Rope::from("ab\r\nabc\nxyz\n").longest_line(), (Line(1), 3));
+    /// assert_eq!(Rope::from("").longest_line(), (Line(0), 0));
+    /// ```
+    pub fn longest_line(&self) -> (Line, usize) {
+        let mut index = 0;
+        let mut longest = (Line(0), 0);
+        self.for_each_line_length(|length| {
+            if length > longest.1 {
+                longest = (Line(index), length);
+            }
+            index += 1;
+        });
+        longest
+    }
+
+    /// The histogram of line lengths. The element `i` of the result is the number of lines with
+    /// length (in characters) in range `i * bucket_width .. (i + 1) * bucket_width`.
+    /// ```
+    /// # use enso_text::*;
+    /// let rope = Rope::from("a\nabcde\nabc\n");
+    /// assert_eq!(rope.line_length_histogram(2), vec![2, 1, 1]);
+    /// ```
+    pub fn line_length_histogram(&self, bucket_width: usize) -> Vec<usize> {
+        let bucket_width = bucket_width.max(1);
+        let mut histogram = vec![];
+        self.for_each_line_length(|length| {
+            let bucket = length / bucket_width;
+            if histogram.len() <= bucket {
+                histogram.resize(bucket + 1, 0);
+            }
+            histogram[bucket] += 1;
+        });
+        histogram
+    }
+}
+
+
 // === Display ===
 
 impl Display for Rope {
@@ -1026,6 +1243,30 @@ impl RopeCell {
         self.cell.borrow().grapheme_count()
     }
 
+    pub fn code_point_count(&self) -> usize {
+        self.cell.borrow().code_point_count()
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.cell.borrow().word_count()
+    }
+
+    pub fn longest_line(&self) -> (Line, usize) {
+        self.cell.borrow().longest_line()
+    }
+
+    pub fn line_length_histogram(&self, bucket_width: usize) -> Vec<usize> {
+        self.cell.borrow().line_length_histogram(bucket_width)
+    }
+
+    pub fn code_point_index_of_byte_offset_snapped(&self, offset: Byte) -> CodePointIndex {
+        self.cell.borrow().code_point_index_of_byte_offset_snapped(offset)
+    }
+
+    pub fn byte_offset_of_code_point_index_snapped(&self, index: CodePointIndex) -> Byte {
+        self.cell.borrow().byte_offset_of_code_point_index_snapped(index)
+    }
+
     pub fn len(&self) -> Bytes {
         self.cell.borrow().len()
     }
@@ -1267,6 +1508,12 @@ impl FromInContextSnapped<&Rope, Location<Byte, Line>> for Byte {
     }
 }
 
+impl FromInContextSnapped<&Rope, CodePointIndex> for Byte {
+    fn from_in_context_snapped(rope: &Rope, index: CodePointIndex) -> Self {
+        rope.byte_offset_of_code_point_index_snapped(index)
+    }
+}
+
 impl FromInContextSnapped<&Rope, Location<Column, Line>> for Byte {
     fn from_in_context_snapped(context: &Rope, location: Location) -> Self {
         let location = Location::<Byte, Line>::from_in_context_snapped(context, location);
@@ -1302,6 +1549,28 @@ impl FromInContextSnapped<&Rope, Byte> for Location<Column, Line> {
 }
 
 
+impl FromInContextSnapped<&Rope, CodePointIndex> for Location<Column, Line> {
+    fn from_in_context_snapped(rope: &Rope, index: CodePointIndex) -> Self {
+        rope.location_of_code_point_index_snapped(index)
+    }
+}
+
+
+// === Conversions to CodePointIndex ===
+
+impl FromInContextSnapped<&Rope, Byte> for CodePointIndex {
+    fn from_in_context_snapped(rope: &Rope, offset: Byte) -> Self {
+        rope.code_point_index_of_byte_offset_snapped(offset)
+    }
+}
+
+impl FromInContextSnapped<&Rope, Location<Column, Line>> for CodePointIndex {
+    fn from_in_context_snapped(rope: &Rope, location: Location<Column, Line>) -> Self {
+        rope.code_point_index_of_location_snapped(location)
+    }
+}
+
+
 // === Conversions to Location<Byte, Line> ===
 
 impl FromInContextSnapped<&Rope, Location<Column, Line>> for Location<Byte, Line> {
@@ -1371,4 +1640,24 @@ mod tests {
         let expected = Location { line: Line(1), offset: Utf16CodeUnit(15) };
         assert_eq!(rope.utf16_code_unit_location_of_location(from), expected);
     }
+
+    #[test]
+    fn code_point_index_conversions_round_trip() {
+        let rope = Rope::from("a🧑🏾\r\nட்b\n");
+        let code_point_count = rope.code_point_count();
+        assert_eq!(code_point_count, 9);
+        for index in (0..=code_point_count).map(CodePointIndex) {
+            let offset = Byte::from_in_context_snapped(&rope, index);
+            assert_eq!(CodePointIndex::from_in_context_snapped(&rope, offset), index);
+        }
+        let location = |line, column| Location { line: Line(line), offset: Column(column) };
+        let index_of =
+            |line, column| rope.code_point_index_of_location_snapped(location(line, column));
+        assert_eq!(index_of(0, 2), CodePointIndex(3));
+        assert_eq!(index_of(1, 0), CodePointIndex(5));
+        assert_eq!(index_of(1, 1), CodePointIndex(7));
+        assert_eq!(index_of(2, 0), CodePointIndex(9));
+        assert_eq!(rope.location_of_code_point_index_snapped(CodePointIndex(7)), location(1, 1));
+        assert_eq!(rope.snap_code_point_index_to_grapheme(CodePointIndex(6)), CodePointIndex(7));
+    }
 }
@@ -17,6 +17,7 @@ use std::iter::Sum;
 pub mod traits {
     pub use super::byte_diff::Into as TRAIT_byte_diff_into;
     pub use super::bytes::Into as TRAIT_bytes_into;
+    pub use super::code_point_index::Into as TRAIT_code_point_index_into;
 }
 pub use traits::*;
 
@@ -172,6 +173,7 @@ impl LineDiff {
 
 unit! {
 /// A column index.
+#[derive(serde::Serialize, serde::Deserialize)]
 Column::column(usize)
 }
 
@@ -192,6 +194,17 @@ impl AddAssign<usize> for Column {
 
 
 
+// ======================
+// === CodePointIndex ===
+// ======================
+
+unit! {
+/// An index of a Unicode code point, counted from the beginning of the text.
+CodePointIndex::code_point_index(usize)
+}
+
+
+
 // ======================
 // === UTF16CodeUnit ===
 // ======================
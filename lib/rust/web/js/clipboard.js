@@ -72,14 +72,24 @@ export function writeText(text) {
     }
 }
 
+/// Whether custom MIME types can be both written to and read from the clipboard. If not, the
+/// `text/plain` flavour is the only one which survives copying and pasting.
+export function isCustomSupported() {
+    return (
+        !!navigator.clipboard &&
+        typeof ClipboardItem !== 'undefined' &&
+        typeof navigator.clipboard.read === 'function'
+    )
+}
+
 /// Write custom `data` payload to the clipboard. Data will be saved as a `Blob` with `mimeType`.
 /// If `textData` is not empty, an additional clipboard item will be written with the `text/plain` type.
 ///
-/// Unlike `writeText`, there are no special fallbacks in case of errors or the clipboard being unavailable.
-/// If writing did not succeeed, the function will simply log an error to the console.
+/// If the Clipboard API is not available or writing did not succeed, only the `textData` is written,
+/// the same way as in `writeText`.
 export function writeCustom(mimeType, data, textData) {
-    if (!navigator.clipboard) {
-        console.error('Clipboard API not available.')
+    if (!navigator.clipboard || typeof ClipboardItem === 'undefined') {
+        fallbackWriteText(textData)
     } else {
         const blob = new Blob([data], { type: mimeType })
         const payload = { [blob.type]: blob }
@@ -89,7 +99,39 @@ export function writeCustom(mimeType, data, textData) {
         navigator.clipboard.write([new ClipboardItem(payload)]).then(
             () => {},
             err => {
-                console.error('Could not write to clipboard.', err)
+                console.warn('Could not write custom data to clipboard, writing text only.', err)
+                writeText(textData)
+            }
+        )
+    }
+}
+
+/// Write many flavours of the same content to the clipboard at once. The `mimeTypes` and `data` are
+/// arrays of the same length, the `data` containing `Uint8Array`s.
+///
+/// If the Clipboard API is not available or writing did not succeed, only the `fallbackText` is
+/// written, the same way as in `writeText`. If `fallbackText` is empty, the `text/plain` flavour is
+/// written instead.
+export function writeFlavours(mimeTypes, data, fallbackText) {
+    const payload = {}
+    let textData = ''
+    for (let i = 0; i < mimeTypes.length; i++) {
+        payload[mimeTypes[i]] = new Blob([data[i]], { type: mimeTypes[i] })
+        if (mimeTypes[i] === 'text/plain') {
+            textData = new TextDecoder().decode(data[i])
+        }
+    }
+    if (typeof fallbackText === 'string' && fallbackText !== '') {
+        textData = fallbackText
+    }
+    if (!navigator.clipboard || typeof ClipboardItem === 'undefined') {
+        fallbackWriteText(textData)
+    } else {
+        navigator.clipboard.write([new ClipboardItem(payload)]).then(
+            () => {},
+            err => {
+                console.warn('Could not write all flavours to clipboard, writing text only.', err)
+                writeText(textData)
             }
         )
     }
@@ -127,11 +169,11 @@ export function readText(callback) {
 /// Read a custom payload of `expectedMimeType` from the clipboard, passing it to `whenExpected` callback.
 /// If there is no value of `expectedMimeType` in the payload, use `plainTextFallback` callback instead.
 ///
-/// Unlike `readText`, there are no special fallbacks in case of errors or the clipboard being unavailable.
-/// If reading did not succeeed, the function will simply log an error to the console.
+/// If the Clipboard API is not available or reading did not succeed, the text is read the same way as
+/// in `readText` and passed to `plainTextFallback`.
 export function readCustom(expectedMimeType, whenExpected, plainTextFallback) {
     if (!navigator.clipboard) {
-        console.error('Clipboard API not available.')
+        plainTextFallback(lastTextPaste)
     } else {
         readCustomImpl(expectedMimeType, whenExpected, plainTextFallback)
     }
@@ -159,7 +201,8 @@ async function readCustomImpl(expectedMimeType, whenExpected, plainTextFallback)
             }
         }
     } catch (error) {
-        console.error('Error while reading clipboard.', error)
+        console.warn('Could not read custom data from clipboard, reading text only.', error)
+        readText(plainTextFallback)
     }
 }
 
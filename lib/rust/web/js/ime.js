@@ -0,0 +1,78 @@
+// ===========
+// === IME ===
+// ===========
+
+let bridges = new Map()
+let nextBridgeId = 0
+
+// Create an invisible text area receiving the input method composition, as the canvas itself can
+// not receive text input. The in-progress composition is reported to `onUpdate`, and the final
+// string to `onEnd`. The key presses being a part of the composition are not propagated to the
+// window, so they do not trigger the application shortcuts.
+export function ime_observe(onUpdate, onEnd) {
+    let textArea = document.createElement('textarea')
+    textArea.setAttribute('autocomplete', 'off')
+    textArea.setAttribute('autocorrect', 'off')
+    textArea.setAttribute('autocapitalize', 'off')
+    textArea.setAttribute('spellcheck', 'false')
+    textArea.style.position = 'fixed'
+    textArea.style.left = '0'
+    textArea.style.top = '0'
+    textArea.style.width = '1px'
+    textArea.style.height = '1px'
+    textArea.style.opacity = '0'
+    textArea.style.pointerEvents = 'none'
+    document.body.appendChild(textArea)
+
+    let composing = false
+    textArea.addEventListener('compositionstart', e => {
+        composing = true
+        onUpdate(e.data || '')
+    })
+    textArea.addEventListener('compositionupdate', e => {
+        onUpdate(e.data || '')
+    })
+    textArea.addEventListener('compositionend', e => {
+        composing = false
+        onEnd(e.data || '')
+        textArea.value = ''
+    })
+    textArea.addEventListener('input', () => {
+        if (!composing) {
+            textArea.value = ''
+        }
+    })
+    let stopComposingKey = e => {
+        if (e.isComposing || e.keyCode === 229) {
+            e.stopPropagation()
+        }
+    }
+    textArea.addEventListener('keydown', stopComposingKey)
+    textArea.addEventListener('keyup', stopComposingKey)
+
+    let id = nextBridgeId
+    nextBridgeId += 1
+    bridges.set(id, { textArea })
+    return id
+}
+
+export function ime_unobserve(id) {
+    bridges.get(id).textArea.remove()
+    bridges.delete(id)
+}
+
+export function ime_focus(id) {
+    bridges.get(id).textArea.focus({ preventScroll: true })
+}
+
+export function ime_blur(id) {
+    bridges.get(id).textArea.blur()
+}
+
+// Move the text area to the given position in CSS pixels. The browser displays the IME candidate
+// window next to the focused text area, so it should be placed at the caret.
+export function ime_set_position(id, x, y) {
+    let textArea = bridges.get(id).textArea
+    textArea.style.left = `${x}px`
+    textArea.style.top = `${y}px`
+}
@@ -0,0 +1,38 @@
+// ===================
+// === PointerLock ===
+// ===================
+
+let listeners = new Map()
+let nextListenerId = 0
+
+export function pointer_lock_observe(f) {
+    let listener = () => f(document.pointerLockElement != null)
+    document.addEventListener('pointerlockchange', listener)
+    document.addEventListener('pointerlockerror', listener)
+    let id = nextListenerId
+    nextListenerId += 1
+    listeners.set(id, listener)
+    return id
+}
+
+export function pointer_lock_unobserve(id) {
+    let listener = listeners.get(id)
+    document.removeEventListener('pointerlockchange', listener)
+    document.removeEventListener('pointerlockerror', listener)
+    listeners.delete(id)
+}
+
+export function pointer_lock_request(target) {
+    // Older browsers do not return a promise, while newer ones reject it if the request was not
+    // triggered by a user gesture. The failure is reported with the `pointerlockerror` event anyway.
+    let result = target.requestPointerLock()
+    if (result instanceof Promise) {
+        result.catch(() => {})
+    }
+}
+
+export function pointer_lock_exit() {
+    if (document.pointerLockElement != null) {
+        document.exitPointerLock()
+    }
+}
@@ -0,0 +1,90 @@
+// =======================
+// === VirtualKeyboard ===
+// =======================
+
+let bridges = new Map()
+let nextBridgeId = 0
+
+// Create an invisible text area. Focusing it opens the on-screen keyboard on touch devices, as the
+// canvas itself can not receive text input. The keyboard events it receives bubble up to the
+// window, where they are handled like physical key presses. Only the text not delivered by a
+// printable key press is reported to `onText`, as some on-screen keyboards (and all IMEs) report
+// the typed text with the `input` event only.
+export function virtual_keyboard_observe(onObscuredHeight, onText) {
+    let textArea = document.createElement('textarea')
+    textArea.setAttribute('autocomplete', 'off')
+    textArea.setAttribute('autocorrect', 'off')
+    textArea.setAttribute('autocapitalize', 'off')
+    textArea.setAttribute('spellcheck', 'false')
+    textArea.style.position = 'fixed'
+    textArea.style.left = '0'
+    textArea.style.bottom = '0'
+    textArea.style.width = '1px'
+    textArea.style.height = '1px'
+    textArea.style.opacity = '0'
+    textArea.style.pointerEvents = 'none'
+    document.body.appendChild(textArea)
+
+    let deliveredByKeyDown = false
+    textArea.addEventListener('keydown', e => {
+        deliveredByKeyDown = e.key.length === 1
+    })
+    textArea.addEventListener('input', e => {
+        if (!deliveredByKeyDown && !e.isComposing && e.data) {
+            onText(e.data)
+        }
+        deliveredByKeyDown = false
+        textArea.value = ''
+    })
+    textArea.addEventListener('compositionend', e => {
+        if (e.data) {
+            onText(e.data)
+        }
+        textArea.value = ''
+    })
+
+    let viewport = window.visualViewport
+    let onViewportChange = () => onObscuredHeight(obscuredHeight())
+    if (viewport != null) {
+        viewport.addEventListener('resize', onViewportChange)
+        viewport.addEventListener('scroll', onViewportChange)
+    }
+
+    let id = nextBridgeId
+    nextBridgeId += 1
+    bridges.set(id, { textArea, onViewportChange })
+    return id
+}
+
+export function virtual_keyboard_unobserve(id) {
+    let bridge = bridges.get(id)
+    let viewport = window.visualViewport
+    if (viewport != null) {
+        viewport.removeEventListener('resize', bridge.onViewportChange)
+        viewport.removeEventListener('scroll', bridge.onViewportChange)
+    }
+    bridge.textArea.remove()
+    bridges.delete(id)
+}
+
+export function virtual_keyboard_show(id) {
+    bridges.get(id).textArea.focus({ preventScroll: true })
+}
+
+export function virtual_keyboard_hide(id) {
+    bridges.get(id).textArea.blur()
+}
+
+export function has_touch_screen() {
+    return navigator.maxTouchPoints > 0
+}
+
+// The height of the bottom part of the layout viewport covered by the on-screen keyboard, as the
+// visual viewport shrinks when the keyboard opens.
+function obscuredHeight() {
+    let viewport = window.visualViewport
+    if (viewport == null) {
+        return 0
+    }
+    return Math.max(0, window.innerHeight - viewport.height - viewport.offsetTop)
+}
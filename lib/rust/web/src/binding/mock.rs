@@ -589,8 +589,10 @@ impl EventListenerOptions {
 mock_data! { Event => Object
     fn new(type_: &str) -> Result<Event, JsValue>;
     fn prevent_default(&self);
+    fn default_prevented(&self) -> bool;
     fn stop_propagation(&self);
     fn current_target(&self) -> Option<EventTarget>;
+    fn time_stamp(&self) -> f64;
 }
 
 
@@ -610,6 +612,7 @@ mock_data! { MouseEvent => Event
     fn button(&self) -> i16;
     fn alt_key(&self) -> bool;
     fn ctrl_key(&self) -> bool;
+    fn shift_key(&self) -> bool;
     fn client_x(&self) -> i32;
     fn client_y(&self) -> i32;
     fn offset_x(&self) -> i32;
@@ -655,6 +658,7 @@ mock_data! { Element => Node
     fn set_class_name(&self, value: &str);
     fn set_id(&self, value: &str);
     fn set_attribute(&self, name: &str, value: &str) -> Result<(), JsValue>;
+    fn remove_attribute(&self, name: &str) -> Result<(), JsValue>;
     fn set_scroll_top(&self, value: i32);
     fn prepend_with_node_0(&self) -> Result<(), JsValue>;
     fn prepend_with_node_1(&self, n1: &Node) -> Result<(), JsValue>;
@@ -670,6 +674,8 @@ mock_data! { HtmlElement => Element
     fn get_elements_by_class_name(&self, class_names: &str) -> HtmlCollection;
     fn style(&self) -> CssStyleDeclaration;
     fn offset_top(&self) -> i32;
+    fn focus(&self) -> Result<(), JsValue>;
+    fn set_tab_index(&self, index: i32);
 }
 impl From<HtmlElement> for EventTarget {
     fn from(_: HtmlElement) -> Self {
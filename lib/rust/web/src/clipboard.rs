@@ -19,9 +19,11 @@
 
 use crate::prelude::*;
 
+use js_sys::Array;
 use js_sys::Uint8Array;
 use wasm_bindgen::prelude::wasm_bindgen;
 use wasm_bindgen::prelude::Closure;
+use wasm_bindgen::JsValue;
 
 
 
@@ -51,9 +53,15 @@ extern "C" {
     #[allow(unsafe_code)]
     fn readText(closure: &ReadTextClosure);
 
+    #[allow(unsafe_code)]
+    fn isCustomSupported() -> bool;
+
     #[allow(unsafe_code)]
     fn writeCustom(mime_type: String, data: Uint8Array, text_data: String);
 
+    #[allow(unsafe_code)]
+    fn writeFlavours(mime_types: Array, data: Array, fallback_text: String);
+
     #[allow(unsafe_code)]
     fn readCustom(
         expected_mime_type: String,
@@ -62,26 +70,48 @@ extern "C" {
     );
 }
 
+/// Check whether the data of custom MIME types can be both written to and read from the
+/// clipboard. If not, for example in Firefox, only the `text/plain` data written with [`write`]
+/// can be read back, so it should contain all the information needed when pasting.
+pub fn is_custom_supported() -> bool {
+    isCustomSupported()
+}
+
 /// Write the provided data to the clipboard, using the provided MIME type.
 /// If `text_data` is present, it will be added to the clipboard with a `text/plain` MIME type.
 ///
 /// See the module documentation for mode details.
 ///
-/// - Unlike `write_text`, there is no special fallback mechanism in case of failures or unavailable
-///   clipboard. The function will simply report an error to the console.
+/// - In case of failures or unavailable clipboard, only the `text_data` is written, using the same
+///   fallback mechanism as `write_text`.
 pub fn write(data: BinaryData<'_>, mime_type: MimeType, text_data: Option<String>) {
     let data = Uint8Array::from(data);
     writeCustom(mime_type, data, text_data.unwrap_or_default());
 }
 
+/// Write many flavours of the same content to the clipboard at once, each with its own MIME type.
+/// For example, the copied text can be written both as `text/plain` and `text/html`, so the
+/// application it is pasted into can choose the richest format it supports.
+///
+/// See the module documentation for more details.
+///
+/// - In case of failures or unavailable clipboard, only the `fallback_text` is written, using the
+///   same fallback mechanism as `write_text`. If `fallback_text` is not present, the `text/plain`
+///   flavour is written instead.
+pub fn write_flavours(flavours: &[(MimeType, BinaryData<'_>)], fallback_text: Option<String>) {
+    let mime_types = flavours.iter().map(|(mime_type, _)| JsValue::from_str(mime_type));
+    let data = flavours.iter().map(|(_, data)| JsValue::from(Uint8Array::from(*data)));
+    writeFlavours(mime_types.collect(), data.collect(), fallback_text.unwrap_or_default());
+}
+
 /// Read the arbitrary binary data from the console. It is expected to have `expected_mime_type`.
 /// If the value of such type is not present in the clipboard content, the `plain/text` MIME type
 /// is requested and the result is passed to the `plain_text_fallback` callback.
 ///
 /// See the module documentation for more details.
 ///
-/// - Unlike `read_text`, there is no special fallback mechanism in case of failures or unavailable
-///   clipboard. The function will simply report an error to the console.
+/// - In case of failures or unavailable clipboard, the text is read using the same fallback
+///   mechanism as `read_text` and passed to the `plain_text_fallback` callback.
 pub fn read(
     expected_mime_type: MimeType,
     when_expected: impl Fn(Vec<u8>) + 'static,
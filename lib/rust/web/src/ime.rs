@@ -0,0 +1,104 @@
+//! Binding to the input method editors (IMEs), used to type the characters not available on the
+//! keyboard directly, like the CJK characters or the letters composed with dead keys.
+
+use crate::prelude::*;
+
+use crate::Closure;
+
+
+
+// =============
+// === Types ===
+// =============
+
+/// Listener of the in-progress composition string.
+pub type CompositionListener = Closure<dyn FnMut(String)>;
+
+
+
+// ===================
+// === JS Bindings ===
+// ===================
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(module = "/js/ime.js")]
+extern "C" {
+    #[allow(unsafe_code)]
+    fn ime_observe(on_update: &CompositionListener, on_end: &CompositionListener) -> usize;
+
+    #[allow(unsafe_code)]
+    fn ime_unobserve(id: usize);
+
+    #[allow(unsafe_code)]
+    fn ime_focus(id: usize);
+
+    #[allow(unsafe_code)]
+    fn ime_blur(id: usize);
+
+    #[allow(unsafe_code)]
+    fn ime_set_position(id: usize, x: f32, y: f32);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn ime_observe(_on_update: &CompositionListener, _on_end: &CompositionListener) -> usize {
+    0
+}
+#[cfg(not(target_arch = "wasm32"))]
+fn ime_unobserve(_id: usize) {}
+#[cfg(not(target_arch = "wasm32"))]
+fn ime_focus(_id: usize) {}
+#[cfg(not(target_arch = "wasm32"))]
+fn ime_blur(_id: usize) {}
+#[cfg(not(target_arch = "wasm32"))]
+fn ime_set_position(_id: usize, _x: f32, _y: f32) {}
+
+
+
+// ==============
+// === Bridge ===
+// ==============
+
+/// A bridge to the input method editor. As the canvas can not receive text input, the bridge
+/// creates an invisible text area, which receives the composition events while
+/// [focused](Self::focus). The composition string is reported to the `on_update` listener after
+/// every change, and the final string to the `on_end` listener once the composition is committed.
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub struct Bridge {
+    pub on_update: CompositionListener,
+    pub on_end:    CompositionListener,
+    pub bridge_id: usize,
+}
+
+impl Bridge {
+    /// Constructor.
+    pub fn new(on_update: CompositionListener, on_end: CompositionListener) -> Self {
+        let bridge_id = ime_observe(&on_update, &on_end);
+        Self { on_update, on_end, bridge_id }
+    }
+
+    /// Start receiving the composition events.
+    pub fn focus(&self) {
+        ime_focus(self.bridge_id)
+    }
+
+    /// Stop receiving the composition events.
+    pub fn blur(&self) {
+        ime_blur(self.bridge_id)
+    }
+
+    /// Set the position of the IME candidate window, in CSS pixels relative to the top-left corner
+    /// of the window.
+    pub fn set_position(&self, x: f32, y: f32) {
+        ime_set_position(self.bridge_id, x, y)
+    }
+}
+
+impl Drop for Bridge {
+    fn drop(&mut self) {
+        ime_unobserve(self.bridge_id);
+    }
+}
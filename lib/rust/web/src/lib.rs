@@ -39,9 +39,12 @@ pub mod binding;
 pub mod clipboard;
 pub mod closure;
 pub mod event;
+pub mod ime;
 pub mod platform;
+pub mod pointer_lock;
 pub mod resize_observer;
 pub mod stream;
+pub mod virtual_keyboard;
 
 pub use std::time::Duration;
 pub use std::time::Instant;
@@ -634,6 +637,7 @@ ops! { NodeOps for Node
 ops! { ElementOps for Element
     trait {
         fn set_attribute_or_warn<T: AsRef<str>, U: AsRef<str>>(&self, name: T, value: U);
+        fn remove_attribute_or_warn<T: AsRef<str>>(&self, name: T);
     }
 
     impl {
@@ -646,6 +650,13 @@ ops! { ElementOps for Element
                 warn!("{warn_msg}")
             }
         }
+
+        fn remove_attribute_or_warn<T: AsRef<str>>(&self, name: T) {
+            let name = name.as_ref();
+            if self.remove_attribute(name).is_err() {
+                warn!("Failed to remove attribute \"{name}\" from \"{self:?}\"")
+            }
+        }
     }
 }
 
@@ -0,0 +1,93 @@
+//! Binding to the https://developer.mozilla.org/en-US/docs/Web/API/Pointer_Lock_API.
+
+use crate::prelude::*;
+
+use crate::Closure;
+use crate::JsValue;
+
+
+
+// =============
+// === Types ===
+// =============
+
+/// Listener closure for the [`PointerLockObserver`]. It receives the information whether the
+/// pointer is locked.
+pub type Listener = Closure<dyn FnMut(bool)>;
+
+
+
+// ===================
+// === JS Bindings ===
+// ===================
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(module = "/js/pointer_lock.js")]
+extern "C" {
+    #[allow(unsafe_code)]
+    fn pointer_lock_observe(closure: &Listener) -> usize;
+
+    #[allow(unsafe_code)]
+    fn pointer_lock_unobserve(id: usize);
+
+    #[allow(unsafe_code)]
+    fn pointer_lock_request(target: &JsValue);
+
+    #[allow(unsafe_code)]
+    fn pointer_lock_exit();
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn pointer_lock_observe(_closure: &Listener) -> usize {
+    0
+}
+#[cfg(not(target_arch = "wasm32"))]
+fn pointer_lock_unobserve(_id: usize) {}
+#[cfg(not(target_arch = "wasm32"))]
+fn pointer_lock_request(_target: &JsValue) {}
+#[cfg(not(target_arch = "wasm32"))]
+fn pointer_lock_exit() {}
+
+/// Request locking the pointer to the target element. The pointer is hidden, and the mouse move
+/// events report the relative movement only. The request succeeds only when made while handling
+/// a user gesture, like a mouse press. The browser releases the lock when the Escape key is
+/// pressed.
+pub fn request(target: &JsValue) {
+    pointer_lock_request(target)
+}
+
+/// Release the pointer lock, if any.
+pub fn exit() {
+    pointer_lock_exit()
+}
+
+
+
+// ===========================
+// === PointerLockObserver ===
+// ===========================
+
+/// Observer of the pointer lock state changes, including the failed lock requests.
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub struct PointerLockObserver {
+    pub listener:    Listener,
+    pub observer_id: usize,
+}
+
+impl PointerLockObserver {
+    /// Constructor.
+    pub fn new(listener: Listener) -> Self {
+        let observer_id = pointer_lock_observe(&listener);
+        Self { listener, observer_id }
+    }
+}
+
+impl Drop for PointerLockObserver {
+    fn drop(&mut self) {
+        pointer_lock_unobserve(self.observer_id);
+    }
+}
@@ -0,0 +1,115 @@
+//! Binding to the on-screen keyboard of touch devices. The area covered by the keyboard is
+//! computed with the https://developer.mozilla.org/en-US/docs/Web/API/VisualViewport API.
+
+use crate::prelude::*;
+
+use crate::Closure;
+
+
+
+// =============
+// === Types ===
+// =============
+
+/// Listener of the height of the bottom part of the window covered by the on-screen keyboard.
+pub type ObscuredHeightListener = Closure<dyn FnMut(f32)>;
+
+/// Listener of the text typed with the on-screen keyboard which was not delivered as a key press.
+pub type TextListener = Closure<dyn FnMut(String)>;
+
+
+
+// ===================
+// === JS Bindings ===
+// ===================
+
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen(module = "/js/virtual_keyboard.js")]
+extern "C" {
+    #[allow(unsafe_code)]
+    fn virtual_keyboard_observe(
+        on_obscured_height: &ObscuredHeightListener,
+        on_text: &TextListener,
+    ) -> usize;
+
+    #[allow(unsafe_code)]
+    fn virtual_keyboard_unobserve(id: usize);
+
+    #[allow(unsafe_code)]
+    fn virtual_keyboard_show(id: usize);
+
+    #[allow(unsafe_code)]
+    fn virtual_keyboard_hide(id: usize);
+
+    /// Check whether the device has a touch screen, and thus, most likely uses an on-screen
+    /// keyboard.
+    #[allow(unsafe_code)]
+    pub fn has_touch_screen() -> bool;
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn virtual_keyboard_observe(
+    _on_obscured_height: &ObscuredHeightListener,
+    _on_text: &TextListener,
+) -> usize {
+    0
+}
+#[cfg(not(target_arch = "wasm32"))]
+fn virtual_keyboard_unobserve(_id: usize) {}
+#[cfg(not(target_arch = "wasm32"))]
+fn virtual_keyboard_show(_id: usize) {}
+#[cfg(not(target_arch = "wasm32"))]
+fn virtual_keyboard_hide(_id: usize) {}
+/// Check whether the device has a touch screen, and thus, most likely uses an on-screen keyboard.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn has_touch_screen() -> bool {
+    false
+}
+
+
+
+// ==============
+// === Bridge ===
+// ==============
+
+/// A bridge to the on-screen keyboard. As the canvas can not receive text input, the bridge
+/// creates an invisible text area, which opens the keyboard when [shown](Self::show). The key
+/// presses are delivered to the window as usual. The text typed in other ways, like with gestures
+/// or an IME, is reported to the text listener.
+///
+/// The obscured height listener is notified whenever the visual viewport changes, for example,
+/// when the keyboard is opened or closed.
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub struct Bridge {
+    pub on_obscured_height: ObscuredHeightListener,
+    pub on_text:            TextListener,
+    pub bridge_id:          usize,
+}
+
+impl Bridge {
+    /// Constructor.
+    pub fn new(on_obscured_height: ObscuredHeightListener, on_text: TextListener) -> Self {
+        let bridge_id = virtual_keyboard_observe(&on_obscured_height, &on_text);
+        Self { on_obscured_height, on_text, bridge_id }
+    }
+
+    /// Open the on-screen keyboard.
+    pub fn show(&self) {
+        virtual_keyboard_show(self.bridge_id)
+    }
+
+    /// Close the on-screen keyboard.
+    pub fn hide(&self) {
+        virtual_keyboard_hide(self.bridge_id)
+    }
+}
+
+impl Drop for Bridge {
+    fn drop(&mut self) {
+        virtual_keyboard_unobserve(self.bridge_id);
+    }
+}